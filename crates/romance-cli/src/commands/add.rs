@@ -1,88 +1,94 @@
 use anyhow::Result;
 use romance_core::addon;
+use romance_core::project::Project;
 use std::path::Path;
 
 pub fn run_validation() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::validation::ValidationAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::validation::ValidationAddon, &mut project)
 }
 
 pub fn run_soft_delete() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::soft_delete::SoftDeleteAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::soft_delete::SoftDeleteAddon, &mut project)
 }
 
 pub fn run_audit_log() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::audit_log::AuditLogAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::audit_log::AuditLogAddon, &mut project)
 }
 
 pub fn run_storage() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::storage::StorageAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::storage::StorageAddon, &mut project)
 }
 
 pub fn run_search() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::search::SearchAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::search::SearchAddon, &mut project)
 }
 
 pub fn run_oauth(provider: &str) -> Result<()> {
-    let project_root = Path::new(".");
+    let mut project = Project::open(".")?;
     addon::run_addon(
         &addon::oauth::OauthAddon {
             provider: provider.to_string(),
         },
-        project_root,
+        &mut project,
     )
 }
 
 pub fn run_security() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::security::SecurityAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::security::SecurityAddon, &mut project)
 }
 
 pub fn run_observability() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::observability::ObservabilityAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::observability::ObservabilityAddon, &mut project)
 }
 
 pub fn run_dashboard() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::dashboard::DashboardAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::dashboard::DashboardAddon, &mut project)
 }
 
 pub fn run_i18n() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::i18n::I18nAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::i18n::I18nAddon, &mut project)
 }
 
 pub fn run_email() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::email::EmailAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::email::EmailAddon, &mut project)
 }
 
 pub fn run_cache() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::cache::CacheAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::cache::CacheAddon, &mut project)
 }
 
 pub fn run_tasks() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::tasks::TasksAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::tasks::TasksAddon, &mut project)
 }
 
 pub fn run_websocket() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::websocket::WebsocketAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::websocket::WebsocketAddon, &mut project)
 }
 
 pub fn run_api_keys() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::api_keys::ApiKeysAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::api_keys::ApiKeysAddon, &mut project)
 }
 
 pub fn run_multitenancy() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_addon(&addon::multitenancy::MultitenancyAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_addon(&addon::multitenancy::MultitenancyAddon, &mut project)
+}
+
+pub fn run_path(path: &Path) -> Result<()> {
+    let mut project = Project::open(".")?;
+    addon::declarative::install_from_path(path, &mut project)
 }
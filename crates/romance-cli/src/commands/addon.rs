@@ -1,97 +1,46 @@
 use anyhow::Result;
 use colored::Colorize;
 use romance_core::addon::Addon;
+use romance_core::error::Failure;
+use romance_core::project::Project;
+use romance_core::say;
 use std::path::Path;
 
 /// List all available addons with descriptions.
 pub fn run_list() -> Result<()> {
-    println!("{}", "Available addons:".bold());
-    println!();
-    println!(
-        "  {:<16} {}",
-        "validation".cyan(),
-        "Backend validation (validator) + frontend (Zod)"
-    );
-    println!(
-        "  {:<16} {}",
-        "soft-delete".cyan(),
-        "Soft delete with deleted_at timestamp"
-    );
-    println!(
-        "  {:<16} {}",
-        "audit-log".cyan(),
-        "Track create/update/delete operations"
-    );
-    println!(
-        "  {:<16} {}",
-        "storage".cyan(),
-        "File and image uploads (local/S3)"
-    );
-    println!(
-        "  {:<16} {}",
-        "search".cyan(),
-        "Full-text search (PostgreSQL tsvector)"
-    );
-    println!(
-        "  {:<16} {}",
-        "security".cyan(),
-        "Rate limiting, CORS, security headers"
-    );
-    println!(
-        "  {:<16} {}",
-        "observability".cyan(),
-        "Structured logging and tracing"
-    );
-    println!(
-        "  {:<16} {}",
-        "cache".cyan(),
-        "Redis caching layer"
-    );
-    println!(
-        "  {:<16} {}",
-        "email".cyan(),
-        "Email sending (SMTP/templates)"
-    );
-    println!(
-        "  {:<16} {}",
-        "oauth".cyan(),
-        "Social authentication (Google/GitHub/Discord)"
-    );
-    println!(
-        "  {:<16} {}",
-        "tasks".cyan(),
-        "Background task processing"
-    );
-    println!(
-        "  {:<16} {}",
-        "websocket".cyan(),
-        "WebSocket support"
-    );
-    println!(
-        "  {:<16} {}",
-        "api-keys".cyan(),
-        "API key authentication"
-    );
-    println!(
-        "  {:<16} {}",
-        "i18n".cyan(),
-        "Internationalization"
-    );
-    println!(
-        "  {:<16} {}",
-        "dashboard".cyan(),
-        "Developer dashboard"
-    );
-    println!(
-        "  {:<16} {}",
-        "multitenancy".cyan(),
-        "Row-level multitenancy (tenant_id on entities)"
-    );
-    println!();
-    println!(
+    let addons: &[(&str, &str)] = &[
+        ("validation", "Backend validation (validator) + frontend (Zod)"),
+        ("soft-delete", "Soft delete with deleted_at timestamp"),
+        ("audit-log", "Track create/update/delete operations"),
+        ("storage", "File and image uploads (local/S3)"),
+        ("search", "Full-text search (PostgreSQL tsvector)"),
+        ("security", "Rate limiting, CORS, security headers"),
+        ("observability", "Structured logging and tracing"),
+        ("cache", "Redis caching layer"),
+        ("email", "Email sending (SMTP/templates)"),
+        ("oauth", "Social authentication (Google/GitHub/Discord)"),
+        ("tasks", "Background task processing"),
+        ("websocket", "WebSocket support"),
+        ("api-keys", "API key authentication"),
+        ("i18n", "Internationalization"),
+        ("dashboard", "Developer dashboard"),
+        ("multitenancy", "Row-level multitenancy (tenant_id on entities)"),
+    ];
+
+    say!("{}", "Available addons:".bold());
+    say!();
+    for (name, description) in addons {
+        say!("  {:<16} {}", name.cyan(), description);
+    }
+    say!();
+    say!(
         "Install with: {}",
         "romance add <addon-name>".green()
     );
+    say!(
+        "Install a local addon with: {}",
+        "romance add --path <dir>".green()
+    );
     Ok(())
 }
 
@@ -101,13 +50,15 @@ pub fn run_status() -> Result<()> {
 
     // Verify we are in a Romance project
     if !project_root.join("romance.toml").exists() {
-        anyhow::bail!(
-            "No romance.toml found. Please run this command from a Romance project root."
-        );
+        return Err(Failure::NotAProject.error(
+            "No romance.toml found. Please run this command from a Romance project root.",
+        ));
     }
 
-    println!("{}", "Addon status:".bold());
-    println!();
+    let project = Project::open(project_root)?;
+
+    say!("{}", "Addon status:".bold());
+    say!();
 
     let addons: Vec<(&str, Box<dyn Addon>)> = vec![
         ("validation", Box::new(romance_core::addon::validation::ValidationAddon)),
@@ -128,13 +79,13 @@ pub fn run_status() -> Result<()> {
     ];
 
     for (label, addon) in &addons {
-        let installed = addon.is_already_installed(project_root);
+        let installed = addon.is_already_installed(project.fs(), project_root);
         let status = if installed {
             "installed".green().to_string()
         } else {
             "not installed".dimmed().to_string()
         };
-        println!("  {:<16} {}", label, status);
+        say!("  {:<16} {}", label, status);
     }
 
     // OAuth is special -- check with a default provider since is_already_installed
@@ -142,14 +93,23 @@ pub fn run_status() -> Result<()> {
     let oauth_addon = romance_core::addon::oauth::OauthAddon {
         provider: "google".to_string(),
     };
-    let oauth_installed = oauth_addon.is_already_installed(project_root);
+    let oauth_installed = oauth_addon.is_already_installed(project.fs(), project_root);
     let oauth_status = if oauth_installed {
         "installed".green().to_string()
     } else {
         "not installed".dimmed().to_string()
     };
-    println!("  {:<16} {}", "oauth", oauth_status);
+    say!("  {:<16} {}", "oauth", oauth_status);
+
+    let local = romance_core::addon::declarative::list_installed(&project)?;
+    if !local.is_empty() {
+        say!();
+        say!("{}", "Local addons:".bold());
+        for name in local {
+            say!("  {:<16} {}", name, "installed".green());
+        }
+    }
 
-    println!();
+    say!();
     Ok(())
 }
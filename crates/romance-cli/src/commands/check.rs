@@ -1,46 +1,32 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use colored::Colorize;
+use romance_core::error::Failure;
+use romance_core::say;
+use romance_core::utils::ui;
 use std::process::Command;
 
 pub fn run() -> Result<()> {
-    println!("{}", "Running checks...".bold());
+    say!("{}", "Running checks...".bold());
 
-    // cargo check
-    print!("  cargo check... ");
-    let status = Command::new("cargo")
-        .args(["check"])
-        .current_dir("backend")
-        .status()?;
-    if !status.success() {
-        println!("{}", "FAILED".red());
-        bail!("cargo check failed");
-    }
-    println!("{}", "OK".green());
+    check("cargo check", "cargo", &["check"], "backend")?;
+    check("cargo test", "cargo", &["test"], "backend")?;
+    check("tsc --noEmit", "npx", &["tsc", "--noEmit"], "frontend")?;
 
-    // cargo test
-    print!("  cargo test... ");
-    let status = Command::new("cargo")
-        .args(["test"])
-        .current_dir("backend")
-        .status()?;
-    if !status.success() {
-        println!("{}", "FAILED".red());
-        bail!("cargo test failed");
-    }
-    println!("{}", "OK".green());
+    ui::success("All checks passed!");
+    Ok(())
+}
 
-    // tsc --noEmit
-    print!("  tsc --noEmit... ");
-    let status = Command::new("npx")
-        .args(["tsc", "--noEmit"])
-        .current_dir("frontend")
+/// Run one check, reporting it as passed or failed.
+fn check(name: &str, program: &str, args: &[&str], dir: &str) -> Result<()> {
+    let status = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .stdout(ui::child_stdout())
         .status()?;
     if !status.success() {
-        println!("{}", "FAILED".red());
-        bail!("tsc --noEmit failed");
+        ui::check_fail(name);
+        return Err(Failure::Command.error(format!("{} failed", name)));
     }
-    println!("{}", "OK".green());
-
-    println!("{}", "All checks passed!".green().bold());
+    ui::check_pass(name);
     Ok(())
 }
@@ -1,75 +1,319 @@
 use anyhow::Result;
 use colored::Colorize;
+use dialoguer::Input;
+use romance_core::database::{self, ScratchDatabase, Target};
+use romance_core::error::Failure;
+use romance_core::generator::migration;
+use romance_core::generator::plan::GenerationTracker;
+use romance_core::import;
+use romance_core::lint;
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::schema::{self, drift};
+use romance_core::utils::ui;
+use std::path::Path;
 use std::process::Command;
 
 pub fn migrate() -> Result<()> {
-    println!("{}", "Running migrations...".bold());
+    say!("{}", "Running migrations...".bold());
     let status = Command::new("cargo")
         .args(["run", "-p", "migration", "--", "up"])
         .current_dir("backend")
+        .stdout(ui::child_stdout())
         .status()?;
     if !status.success() {
-        anyhow::bail!("Migration failed");
+        return Err(Failure::Command.error("Migration failed"));
     }
-    println!("{}", "Migrations applied successfully!".green());
+    say!("{}", "Migrations applied successfully!".green());
     Ok(())
 }
 
 pub fn rollback() -> Result<()> {
-    println!("{}", "Rolling back last migration...".bold());
+    say!("{}", "Rolling back last migration...".bold());
     let status = Command::new("cargo")
         .args(["run", "-p", "migration", "--", "down"])
         .current_dir("backend")
+        .stdout(ui::child_stdout())
         .status()?;
     if !status.success() {
-        anyhow::bail!("Rollback failed");
+        return Err(Failure::Command.error("Rollback failed"));
     }
-    println!("{}", "Rollback completed!".green());
+    say!("{}", "Rollback completed!".green());
     Ok(())
 }
 
 pub fn status() -> Result<()> {
-    println!("{}", "Migration status:".bold());
+    say!("{}", "Migration status:".bold());
     let status = Command::new("cargo")
         .args(["run", "-p", "migration", "--", "status"])
         .current_dir("backend")
+        .stdout(ui::child_stdout())
         .status()?;
     if !status.success() {
-        anyhow::bail!("Failed to get migration status");
+        return Err(Failure::Command.error("Failed to get migration status"));
     }
     Ok(())
 }
 
 pub fn seed() -> Result<()> {
-    println!("{}", "Running seed data...".bold());
+    run_seed(None)
+}
 
-    let project_root = std::path::Path::new(".");
+/// Run the seed data against `url`, or the DATABASE_URL of backend/.env.
+fn run_seed(url: Option<&str>) -> Result<()> {
+    say!("{}", "Running seed data...".bold());
 
     // Generate seed file if it doesn't exist
-    romance_core::seed::generate_seed_file(project_root)?;
+    let project = romance_core::project::Project::open(".")?;
+    romance_core::seed::generate_seed_file(&project)?;
 
-    let status = Command::new("cargo")
-        .args(["run", "--bin", "seed"])
-        .current_dir("backend")
-        .status();
+    let backend_command = |args: &[&str]| {
+        let mut command = Command::new("cargo");
+        command.args(args).current_dir("backend").stdout(ui::child_stdout());
+        if let Some(url) = url {
+            command.env("DATABASE_URL", url);
+        }
+        command
+    };
+    let status = backend_command(&["run", "--bin", "seed"]).status();
 
     match status {
         Ok(s) if s.success() => {
-            println!("{}", "Seed data applied successfully!".green());
+            say!("{}", "Seed data applied successfully!".green());
             Ok(())
         }
         _ => {
             // Fallback: try running as a test since seed might not be a binary
-            println!("  Trying seed via cargo test...");
-            let status = Command::new("cargo")
-                .args(["test", "seed", "--", "--ignored"])
-                .current_dir("backend")
-                .status()?;
+            say!("  Trying seed via cargo test...");
+            let status = backend_command(&["test", "seed", "--", "--ignored"]).status()?;
             if !status.success() {
-                anyhow::bail!("Seed failed. Ensure backend/src/seed.rs is configured.");
+                return Err(Failure::Command.error("Seed failed. Ensure backend/src/seed.rs is configured."));
             }
-            println!("{}", "Seed data applied successfully!".green());
+            say!("{}", "Seed data applied successfully!".green());
             Ok(())
         }
     }
 }
+
+/// Compare the models with the schema the migrations create: replayed on a
+/// scratch database, or read from `url`.
+pub fn drift(fix: bool, url: Option<&str>) -> Result<()> {
+    let project = Project::open(".")?;
+    let models = schema::scan(&project)?;
+    let db = match url {
+        Some(url) => {
+            say!("{}", "Reading database schema...".bold());
+            import::postgres::introspect(url)?
+        }
+        None => {
+            say!("{}", "Replaying migrations on a scratch database...".bold());
+            let scratch = ScratchDatabase::create(&project, "romance_drift")?;
+            database::migrate(&project.join("backend"), &scratch.url)?;
+            import::postgres::introspect(&scratch.url)?
+        }
+    };
+
+    let differences = drift::compare(&models, &db);
+    if differences.is_empty() {
+        ui::success("The database matches the models");
+        return Ok(());
+    }
+    for difference in &differences {
+        if ui::is_json() {
+            ui::event("drift", serde_json::to_value(difference)?);
+        } else {
+            say!("  {} {}", "!".red(), difference);
+        }
+    }
+    if !fix {
+        return Err(Failure::Precondition.error(format!(
+            "{} difference(s) between the models and the database (romance db drift --fix writes a migration fixing them)",
+            differences.len()
+        )));
+    }
+
+    let mut tracker = GenerationTracker::new();
+    let (migration, skipped) = match drift::write_migration(&project, &models, &differences, &mut tracker) {
+        Ok(written) => written,
+        Err(e) => {
+            tracker.rollback(project.fs());
+            return Err(e);
+        }
+    };
+    for reason in &skipped {
+        ui::warn(&format!("Not fixed: {}", reason));
+    }
+    if migration.is_some() {
+        say!("Review the migration, then apply it with: romance db migrate");
+    }
+    Ok(())
+}
+
+/// Check the migrations the database hasn't applied yet (all of them with
+/// `all`) for destructive or locking operations.
+pub fn lint(all: bool, url: Option<&str>) -> Result<()> {
+    let project = Project::open(".")?;
+    let mut migrations = migration::modules(&project);
+    if !all {
+        let url = url.map(str::to_string).or_else(|| database::url(&project)).ok_or_else(|| {
+            Failure::Precondition
+                .error("No DATABASE_URL in backend/.env to read the applied migrations from (pass --url, or --all to check every migration)")
+        })?;
+        let applied = database::applied_migrations(&url)?;
+        migrations.retain(|module| !applied.contains(module));
+    }
+    if migrations.is_empty() {
+        ui::success("No pending migrations");
+        return Ok(());
+    }
+
+    let mut findings = Vec::new();
+    for module in &migrations {
+        findings.extend(lint::lint(&project, module)?);
+    }
+    if findings.is_empty() {
+        ui::success(&format!("{} migration(s) checked, nothing unsafe found", migrations.len()));
+        return Ok(());
+    }
+    for finding in &findings {
+        if ui::is_json() {
+            ui::event("lint", serde_json::to_value(finding)?);
+        } else {
+            let marker = if finding.destructive { "!".red() } else { "!".yellow() };
+            say!("  {} {}", marker, finding);
+            say!("    {}", finding.suggestion.dimmed());
+        }
+    }
+    Err(Failure::Precondition.error(format!(
+        "{} unsafe operation(s) in {} migration(s) (silence a reviewed one with `// romance-lint: allow(<rule>)`)",
+        findings.len(),
+        migrations.len()
+    )))
+}
+
+/// Squash the migrations before `before` into a baseline migration, and
+/// record the baseline as applied in the database that applied them.
+pub fn squash(before: &str, url: Option<&str>) -> Result<()> {
+    if before.len() != 14 || !before.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Failure::InvalidInput.error(format!(
+            "Invalid timestamp '{}': use the YYYYMMDDHHMMSS of a migration file name",
+            before
+        )));
+    }
+    let project = Project::open(".")?;
+    let all = migration::modules(&project);
+    let squashed: Vec<String> = all
+        .iter()
+        .filter(|m| m[1..].split_once('_').is_some_and(|(stamp, _)| stamp < before))
+        .cloned()
+        .collect();
+    if squashed.len() < 2 {
+        return Err(Failure::Precondition.error(format!(
+            "Nothing to squash: {} migration(s) before {}",
+            squashed.len(),
+            before
+        )));
+    }
+
+    // Only migrations every database has applied can be replaced; this one
+    // at least
+    let url = url.map(str::to_string).or_else(|| database::url(&project)).ok_or_else(|| {
+        Failure::Precondition.error("No DATABASE_URL in backend/.env to check the applied migrations in (pass --url)")
+    })?;
+    migration::check_applied(&squashed, &database::applied_migrations(&url)?)?;
+
+    say!("{}", format!("Replaying {} migrations on a scratch database...", squashed.len()).bold());
+    let schema = {
+        let scratch = ScratchDatabase::create(&project, "romance_squash")?;
+        database::migrate_first(&project.join("backend"), &scratch.url, squashed.len())?;
+        database::dump_schema(&scratch.url)?
+    };
+
+    let mut tracker = GenerationTracker::new();
+    let baseline = match migration::squash(&project, &squashed, &schema, &mut tracker) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            tracker.rollback(project.fs());
+            return Err(e);
+        }
+    };
+    database::psql(&url, &baseline.record_sql, "record the baseline as applied")?;
+    ui::success(&format!("Squashed {} migrations into {}", squashed.len(), baseline.module));
+    if ui::is_json() {
+        ui::event(
+            "squashed",
+            serde_json::json!({ "baseline": baseline.module, "squashed": squashed, "record_sql": baseline.record_sql }),
+        );
+    } else {
+        say!("Other databases that applied them need the baseline recorded before they next migrate:");
+        for line in baseline.record_sql.lines() {
+            say!("  {}", line.dimmed());
+        }
+    }
+    Ok(())
+}
+
+/// The database of `backend.database_url`, announced with its environment.
+fn target() -> Result<Target> {
+    let project = Project::open(".")?;
+    let target = Target::resolve(&project)?;
+    say!("Database {} ({})", target.name.bold(), target.environment);
+    Ok(target)
+}
+
+/// Let `action` go ahead on a production database only once the user has
+/// typed its name, or with `--allow-production`.
+fn guard_production(target: &Target, action: &str, allowed: bool) -> Result<()> {
+    if !target.needs_confirmation(action, allowed, ui::is_interactive())? {
+        return Ok(());
+    }
+    ui::warn(&format!("The active environment is production: this will {} {}", action, target.name));
+    let typed: String = Input::new()
+        .with_prompt(format!("Type the database name to {} it", action))
+        .interact_text()?;
+    target.confirm(&typed)
+}
+
+pub fn create() -> Result<()> {
+    let target = target()?;
+    target.create()?;
+    ui::success(&format!("Created database {}", target.name));
+    Ok(())
+}
+
+pub fn drop(allow_production: bool) -> Result<()> {
+    let target = target()?;
+    guard_production(&target, "drop", allow_production)?;
+    target.drop()?;
+    ui::success(&format!("Dropped database {}", target.name));
+    Ok(())
+}
+
+/// Drop and re-create the database, migrate it and load the seed data.
+pub fn reset(allow_production: bool) -> Result<()> {
+    let target = target()?;
+    guard_production(&target, "reset", allow_production)?;
+    target.drop()?;
+    target.create()?;
+    say!("{}", "Running migrations...".bold());
+    database::migrate(Path::new("backend"), &target.url)?;
+    run_seed(Some(&target.url))?;
+    ui::success(&format!("Reset database {}", target.name));
+    Ok(())
+}
+
+pub fn dump(file: &Path) -> Result<()> {
+    let target = target()?;
+    target.dump(file)?;
+    ui::success(&format!("Dumped {} to {}", target.name, file.display()));
+    Ok(())
+}
+
+/// Replace the database with the dump in `file`.
+pub fn restore(file: &Path, allow_production: bool) -> Result<()> {
+    let target = target()?;
+    guard_production(&target, "replace", allow_production)?;
+    target.restore(file)?;
+    ui::success(&format!("Restored {} from {}", target.name, file.display()));
+    Ok(())
+}
@@ -1,206 +1,143 @@
 use anyhow::Result;
 use colored::Colorize;
-use heck::{ToLowerCamelCase, ToSnakeCase};
-use std::fs;
+use romance_core::generator::destroy::{self, DestroyReport};
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::utils::ui;
 use std::path::Path;
 
-/// Remove all lines in a file that contain the given pattern.
-fn remove_line_containing(file: &Path, pattern: &str) -> Result<bool> {
-    let content = fs::read_to_string(file)?;
-    let new_content: String = content
-        .lines()
-        .filter(|line| !line.contains(pattern))
-        .collect::<Vec<_>>()
-        .join("\n");
-    if content != new_content {
-        // Preserve trailing newline
-        fs::write(file, new_content + "\n")?;
-        Ok(true)
-    } else {
-        Ok(false)
-    }
-}
+pub fn run_entity(name: &str, keep_table: bool) -> Result<()> {
+    say!(
+        "{}",
+        format!("Destroying entity '{}'...", name).bold()
+    );
+    say!();
 
-/// Remove a file and print a message. Returns true if the file existed.
-fn remove_file(path: &Path) -> bool {
-    if path.exists() {
-        if let Err(e) = fs::remove_file(path) {
-            eprintln!(
-                "  {} Failed to remove {}: {}",
-                "!".yellow(),
-                path.display(),
-                e
-            );
-            return false;
-        }
-        println!("  {} Removed {}", "x".red(), path.display());
-        true
-    } else {
-        println!("  {} Already absent: {}", "-".dimmed(), path.display());
-        false
+    let report = destroy::entity(&Project::open(".")?, name, keep_table)?;
+    print_report(&report);
+    if keep_table {
+        print_kept("Table kept (--keep-table); no drop migration was generated.");
     }
+    finish(&report)
 }
 
-/// Remove a directory recursively and print a message. Returns true if the directory existed.
-fn remove_dir(path: &Path) -> bool {
-    if path.exists() {
-        if let Err(e) = fs::remove_dir_all(path) {
-            eprintln!(
-                "  {} Failed to remove {}: {}",
-                "!".yellow(),
-                path.display(),
-                e
-            );
-            return false;
-        }
-        println!("  {} Removed {}/", "x".red(), path.display());
-        true
-    } else {
-        println!("  {} Already absent: {}/", "-".dimmed(), path.display());
-        false
+pub fn run_field(entity: &str, field: &str, keep_column: bool) -> Result<()> {
+    say!(
+        "{}",
+        format!("Destroying field '{}.{}'...", entity, field).bold()
+    );
+    say!();
+
+    let report = destroy::field(&Project::open(".")?, entity, field, keep_column)?;
+    print_report(&report);
+    if keep_column {
+        print_kept("Column kept (--keep-column); no migration was generated.");
     }
+    finish(&report)
 }
 
-pub fn run_entity(name: &str) -> Result<()> {
-    let snake = name.to_snake_case();
-    let camel = name.to_lower_camel_case();
-
-    println!(
+pub fn run_relation(a: &str, b: &str, keep_table: bool) -> Result<()> {
+    say!(
         "{}",
-        format!("Destroying entity '{}'...", name).bold()
+        format!("Destroying relation '{}' <-> '{}'...", a, b).bold()
     );
-    println!();
-
-    let mut removed = 0u32;
-    let mut total = 0u32;
+    say!();
 
-    // ── Backend files ────────────────────────────────────────────────
-    println!("{}", "Backend files:".bold());
+    let report = destroy::relation(&Project::open(".")?, a, b, keep_table)?;
+    print_report(&report);
+    if keep_table {
+        print_kept("Junction table kept (--keep-table); no drop migration was generated.");
+    }
+    finish(&report)
+}
 
-    let backend_files = [
-        format!("backend/src/entities/{}.rs", snake),
-        format!("backend/src/handlers/{}.rs", snake),
-        format!("backend/src/routes/{}.rs", snake),
-    ];
+pub fn run_auth(keep_table: bool) -> Result<()> {
+    say!("{}", "Destroying authentication...".bold());
+    say!();
 
-    for file in &backend_files {
-        total += 1;
-        if remove_file(Path::new(file)) {
-            removed += 1;
-        }
+    let report = destroy::auth(&mut Project::open(".")?, keep_table)?;
+    print_report(&report);
+    if keep_table {
+        print_kept("Users table kept (--keep-table); no drop migration was generated.");
     }
+    finish(&report)
+}
 
-    println!();
+pub fn run_admin() -> Result<()> {
+    say!("{}", "Destroying admin panel...".bold());
+    say!();
 
-    // ── Frontend files ───────────────────────────────────────────────
-    println!("{}", "Frontend files:".bold());
+    let report = destroy::admin(&Project::open(".")?)?;
+    print_report(&report);
+    finish(&report)
+}
+
+fn print_kept(message: &str) {
+    say!();
+    say!("{}", message.dimmed());
+}
 
-    let feature_dir = format!("frontend/src/features/{}", camel);
-    total += 1;
-    if remove_dir(Path::new(&feature_dir)) {
-        removed += 1;
+fn print_report(report: &DestroyReport) {
+    if ui::is_json() {
+        report.removed.iter().for_each(|path| ui::removed(path));
+        for (path, what) in &report.cleaned {
+            ui::updated_with(path, what);
+        }
+        report.created.iter().for_each(|path| ui::created(path));
+        report.leftovers.iter().for_each(|leftover| ui::warn(leftover));
+        return;
     }
 
-    println!();
-
-    // ── Clean markers ────────────────────────────────────────────────
-    println!("{}", "Cleaning markers:".bold());
-
-    // Remove `pub mod {snake};` from entities/mod.rs, handlers/mod.rs, routes/mod.rs
-    let mod_pattern = format!("pub mod {};", snake);
-    let mod_files = [
-        "backend/src/entities/mod.rs",
-        "backend/src/handlers/mod.rs",
-        "backend/src/routes/mod.rs",
-    ];
-
-    for file in &mod_files {
-        let path = Path::new(file);
-        if path.exists() {
-            match remove_line_containing(path, &mod_pattern) {
-                Ok(true) => {
-                    println!("  {} Cleaned {} from {}", "~".cyan(), mod_pattern, file);
-                }
-                Ok(false) => {
-                    println!("  {} No match in {}", "-".dimmed(), file);
-                }
-                Err(e) => {
-                    eprintln!("  {} Error cleaning {}: {}", "!".yellow(), file, e);
-                }
-            }
+    if !report.removed.is_empty() {
+        say!("{}", "Removed:".bold());
+        for path in &report.removed {
+            say!("  {} {}", "x".red(), path);
         }
+        say!();
     }
 
-    // Remove `.merge({snake}::router())` from routes/mod.rs
-    let route_pattern = format!("{}::router()", snake);
-    let routes_mod = Path::new("backend/src/routes/mod.rs");
-    if routes_mod.exists() {
-        match remove_line_containing(routes_mod, &route_pattern) {
-            Ok(true) => {
-                println!(
-                    "  {} Cleaned route merge for {} from routes/mod.rs",
-                    "~".cyan(),
-                    snake
-                );
-            }
-            Ok(false) => {
-                println!(
-                    "  {} No route merge found in routes/mod.rs",
-                    "-".dimmed()
-                );
-            }
-            Err(e) => {
-                eprintln!("  {} Error cleaning routes/mod.rs: {}", "!".yellow(), e);
-            }
+    if !report.cleaned.is_empty() {
+        say!("{}", "Cleaned:".bold());
+        for (path, what) in &report.cleaned {
+            say!("  {} {} ({})", "~".cyan(), path, what);
         }
+        say!();
     }
 
-    // ── Clean frontend App.tsx ────────────────────────────────────────
-    let app_tsx = Path::new("frontend/src/App.tsx");
-    if app_tsx.exists() {
-        // Remove import lines containing the entity feature path
-        let import_pattern = format!("features/{}", camel);
-        match remove_line_containing(app_tsx, &import_pattern) {
-            Ok(true) => {
-                println!(
-                    "  {} Cleaned imports for {} from App.tsx",
-                    "~".cyan(),
-                    camel
-                );
-            }
-            Ok(false) => {
-                println!("  {} No imports found in App.tsx", "-".dimmed());
-            }
-            Err(e) => {
-                eprintln!("  {} Error cleaning App.tsx imports: {}", "!".yellow(), e);
-            }
+    if !report.created.is_empty() {
+        say!("{}", "Created:".bold());
+        for path in &report.created {
+            say!("  {} {}", "+".green(), path);
         }
+        say!();
+    }
 
-        // Remove Route elements for this entity (match on the snake_case path pattern)
-        let route_path_pattern = format!("\"/{}", snake);
-        match remove_line_containing(app_tsx, &route_path_pattern) {
-            Ok(true) => {
-                println!(
-                    "  {} Cleaned routes for {} from App.tsx",
-                    "~".cyan(),
-                    snake
-                );
-            }
-            Ok(false) => {
-                println!("  {} No routes found in App.tsx", "-".dimmed());
-            }
-            Err(e) => {
-                eprintln!("  {} Error cleaning App.tsx routes: {}", "!".yellow(), e);
-            }
+    if !report.leftovers.is_empty() {
+        say!("{}", "Could not clean:".yellow().bold());
+        for leftover in &report.leftovers {
+            say!("  {} {}", "!".yellow(), leftover);
         }
+        say!();
     }
+}
 
-    println!();
-    println!(
-        "{}",
-        format!("Done. Removed {}/{} targets.", removed, total)
-            .bold()
-    );
+fn finish(report: &DestroyReport) -> Result<()> {
+    if Path::new("backend/src").exists() {
+        romance_core::ai_context::regenerate(&Project::open(".")?)?;
+    }
 
+    if !report.created.is_empty() {
+        say!("Apply the migration with: {}", "romance db migrate".bold());
+    }
+    if report.leftovers.is_empty() {
+        say!("{}", "Done.".green().bold());
+    } else {
+        say!(
+            "{}",
+            format!("Done, with {} item(s) to review.", report.leftovers.len())
+                .yellow()
+                .bold()
+        );
+    }
     Ok(())
 }
@@ -1,17 +1,21 @@
 use anyhow::Result;
+use romance_core::say;
+use romance_core::utils::ui;
 use std::process::Command;
 
 pub fn run() -> Result<()> {
-    println!("Starting development servers...");
+    say!("Starting development servers...");
 
     let mut backend = Command::new("cargo")
         .args(["watch", "-x", "run"])
         .current_dir("backend")
+        .stdout(ui::child_stdout())
         .spawn()?;
 
     let mut frontend = Command::new("npm")
         .args(["run", "dev"])
         .current_dir("frontend")
+        .stdout(ui::child_stdout())
         .spawn()?;
 
     // Wait for either to exit
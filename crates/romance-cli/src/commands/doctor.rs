@@ -1,5 +1,6 @@
 use anyhow::Result;
 use colored::Colorize;
+use romance_core::say;
 use std::path::Path;
 use std::process::Command;
 
@@ -373,11 +374,11 @@ fn check_installed_addons() -> Vec<CheckResult> {
 }
 
 pub fn run() -> Result<()> {
-    println!("{}", "Romance Doctor".bold());
-    println!();
+    say!("{}", "Romance Doctor".bold());
+    say!();
 
     // === Project Structure ===
-    println!("{}", "Project Structure".bold().underline());
+    say!("{}", "Project Structure".bold().underline());
     let structure_checks = vec![
         check_romance_toml(),
         check_backend_structure(),
@@ -390,8 +391,8 @@ pub fn run() -> Result<()> {
     print_checks(&structure_checks);
 
     // === Tools ===
-    println!();
-    println!("{}", "Development Tools".bold().underline());
+    say!();
+    say!("{}", "Development Tools".bold().underline());
     let tool_checks = vec![
         check_tool("cargo", &["--version"], "Cargo", "https://rustup.rs"),
         check_tool("node", &["--version"], "Node.js", "https://nodejs.org"),
@@ -401,22 +402,22 @@ pub fn run() -> Result<()> {
     print_checks(&tool_checks);
 
     // === Configuration ===
-    println!();
-    println!("{}", "Configuration".bold().underline());
+    say!();
+    say!("{}", "Configuration".bold().underline());
     let config_checks = vec![check_database_url(), check_jwt_secret()];
     print_checks(&config_checks);
 
     // === External Services ===
     let service_checks = check_external_services();
     if !service_checks.is_empty() {
-        println!();
-        println!("{}", "External Services".bold().underline());
+        say!();
+        say!("{}", "External Services".bold().underline());
         print_checks(&service_checks);
     }
 
     // === Addons ===
-    println!();
-    println!("{}", "Addons".bold().underline());
+    say!();
+    say!("{}", "Addons".bold().underline());
     let addon_checks = check_installed_addons();
     print_checks(&addon_checks);
 
@@ -433,13 +434,13 @@ pub fn run() -> Result<()> {
     let passed = all_checks.iter().filter(|c| c.passed).count();
     let failed = total - passed;
 
-    println!();
+    say!();
     let summary = format!("{}/{} checks passed", passed, total);
     if failed == 0 {
-        println!("{}", summary.green().bold());
+        say!("{}", summary.green().bold());
     } else {
-        println!("{}", summary.yellow().bold());
-        println!(
+        say!("{}", summary.yellow().bold());
+        say!(
             "{}",
             format!("{} issue(s) found — see above for details", failed).yellow()
         );
@@ -451,9 +452,9 @@ pub fn run() -> Result<()> {
 fn print_checks(checks: &[CheckResult]) {
     for check in checks {
         if check.passed {
-            println!("  {} {}", "\u{2713}".green(), check.message);
+            say!("  {} {}", "\u{2713}".green(), check.message);
         } else {
-            println!("  {} {}", "\u{2717}".red(), check.message);
+            say!("  {} {}", "\u{2717}".red(), check.message);
         }
     }
 }
@@ -1,71 +1,226 @@
 use anyhow::Result;
 use colored::Colorize;
+use dialoguer::Confirm;
+use romance_core::error::Failure;
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::utils::ui;
+use std::path::Path;
 
-pub fn run_entity(name: &str, fields: &[String]) -> Result<()> {
-    let entity = if fields.is_empty() {
+pub fn run_entity(name: &str, module: Option<&str>, fields: &[String]) -> Result<()> {
+    let mut entity = if fields.is_empty() {
         let (prompted_fields, prompted_relations) = romance_core::entity::prompt_entity_fields(name)?;
         romance_core::entity::EntityDefinition {
             name: name.to_string(),
             fields: prompted_fields,
             relations: prompted_relations,
+            module: None,
+            table_name: None,
         }
     } else {
         romance_core::entity::parse_entity(name, fields)?
     };
+    entity.module = module.map(str::to_string);
 
     // Check prerequisites and print warnings before generation
-    let project_root = std::path::Path::new(".");
-    let warnings = romance_core::generator::check_entity_prerequisites(&entity, project_root);
+    let project = Project::open(".")?;
+    let warnings = romance_core::generator::check_entity_prerequisites(&entity, &project);
     for warning in &warnings {
-        eprintln!("  {} {}", "warn".yellow(), warning);
+        ui::warn(warning);
     }
 
     // Phase 1: Pre-validate all markers before writing any files
-    romance_core::generator::backend::validate(&entity)?;
-    romance_core::generator::migration::validate(&entity)?;
-    romance_core::generator::frontend::validate(&entity)?;
+    romance_core::generator::backend::validate(&project, &entity)?;
+    romance_core::generator::migration::validate(&project, &entity)?;
+    romance_core::generator::frontend::validate(&project, &entity)?;
 
     // Phase 2: Generate with rollback tracking
     let mut tracker = romance_core::generator::plan::GenerationTracker::new();
     let result = (|| -> Result<()> {
-        romance_core::generator::backend::generate(&entity, &mut tracker)?;
-        romance_core::generator::migration::generate(&entity, &mut tracker)?;
-        romance_core::generator::backend::generate_relations(&entity)?;
-        romance_core::generator::frontend::generate(&entity, &mut tracker)?;
+        romance_core::generator::backend::generate(&project, &entity, &mut tracker)?;
+        romance_core::generator::migration::generate(&project, &entity, &mut tracker)?;
+        romance_core::generator::backend::generate_relations(&project, &entity)?;
+        romance_core::generator::frontend::generate(&project, &entity, &mut tracker)?;
         Ok(())
     })();
 
     if let Err(e) = result {
-        eprintln!("  {} Generation failed: {}", "error".red(), e);
-        tracker.rollback();
+        ui::error(&format!("Generation failed: {}", e));
+        tracker.rollback(project.fs());
         return Err(e);
     }
 
     // Regenerate AI context with updated schema
-    romance_core::ai_context::regenerate(project_root)?;
+    romance_core::ai_context::regenerate(&project)?;
 
-    println!("Entity '{}' generated successfully!", name);
+    say!("Entity '{}' generated successfully!", name);
+    Ok(())
+}
+
+/// Infer `name` (and its child entities) from a sample JSON payload, show
+/// the inferred fields and generate them once confirmed.
+pub fn run_entity_from_json(name: &str, module: Option<&str>, file: &Path, nested_entities: bool) -> Result<()> {
+    let project = Project::open(".")?;
+    let source = std::fs::read_to_string(file)
+        .map_err(|e| Failure::Io.error(format!("Failed to read {}: {}", file.display(), e)))?;
+    let sample: serde_json::Value = serde_json::from_str(&source)
+        .map_err(|e| Failure::InvalidInput.error(format!("Invalid JSON in {}: {}", file.display(), e)))?;
+    let mut import = romance_core::import::sample::infer(&project, name, &sample, nested_entities)?;
+    for entity in &mut import.entities {
+        entity.module = module.map(str::to_string);
+    }
+    for warning in &import.warnings {
+        ui::warn(warning);
+    }
+
+    let module_arg = module.map(|m| format!(" --module {}", m)).unwrap_or_default();
+    for entity in &import.entities {
+        let args = romance_core::entity::format_entity_args(entity);
+        if ui::is_json() {
+            ui::event("inferred", serde_json::json!({ "entity": entity.name, "fields": args }));
+        } else {
+            say!("  romance generate entity {}{} {}", entity.name, module_arg, args.join(" "));
+        }
+    }
+    if ui::is_interactive()
+        && !Confirm::new()
+            .with_prompt("Generate with these fields?")
+            .default(true)
+            .interact()?
+    {
+        say!("Nothing generated. Copy the commands above to adjust the fields.");
+        return Ok(());
+    }
+
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    if let Err(e) = romance_core::import::generate(&project, &import, &mut tracker) {
+        ui::error(&format!("Generation failed: {}", e));
+        tracker.rollback(project.fs());
+        return Err(e);
+    }
+
+    romance_core::ai_context::regenerate(&project)?;
+
+    let names: Vec<&str> = import.entities.iter().map(|e| e.name.as_str()).collect();
+    say!("Generated from {}: {}", file.display(), names.join(", "));
+    Ok(())
+}
+
+pub fn run_module(
+    name: &str,
+    prefix: Option<String>,
+    table_prefix: Option<String>,
+    schema: Option<String>,
+) -> Result<()> {
+    let mut project = Project::open(".")?;
+    let config = romance_core::config::ModuleConfig {
+        prefix,
+        table_prefix,
+        schema,
+    };
+
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    if let Err(e) = romance_core::generator::module::generate(&mut project, name, config, &mut tracker) {
+        ui::error(&format!("Generation failed: {}", e));
+        tracker.rollback(project.fs());
+        return Err(e);
+    }
+
+    say!(
+        "Module '{}' generated. Add entities with: romance generate entity <Name> --module {}",
+        name, name
+    );
+    Ok(())
+}
+
+pub fn run_migration(name: &str, sql: bool) -> Result<()> {
+    let project = Project::open(".")?;
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    let module = match romance_core::generator::migration::generate_blank(&project, name, sql, &mut tracker) {
+        Ok(module) => module,
+        Err(e) => {
+            ui::error(&format!("Generation failed: {}", e));
+            tracker.rollback(project.fs());
+            return Err(e);
+        }
+    };
+
+    if sql {
+        say!("Write the SQL in backend/migration/sql/{}.up.sql and .down.sql,", module);
+    } else {
+        say!("Fill in backend/migration/src/{}.rs,", module);
+    }
+    say!("then apply it with: romance db migrate");
     Ok(())
 }
 
 pub fn run_types() -> Result<()> {
-    romance_core::generator::types::generate()
+    romance_core::generator::types::generate(&Project::open(".")?)
 }
 
 pub fn run_openapi() -> Result<()> {
-    romance_core::generator::openapi::generate()
+    romance_core::generator::openapi::generate(&Project::open(".")?)
 }
 
 pub fn run_auth() -> Result<()> {
-    romance_core::generator::auth::generate()?;
-    let project_root = std::path::Path::new(".");
-    romance_core::ai_context::regenerate(project_root)?;
+    let mut project = Project::open(".")?;
+    romance_core::generator::auth::generate(&mut project)?;
+    romance_core::ai_context::regenerate(&project)?;
     Ok(())
 }
 
 pub fn run_admin() -> Result<()> {
-    romance_core::generator::admin::generate()?;
-    let project_root = std::path::Path::new(".");
-    romance_core::ai_context::regenerate(project_root)?;
+    let project = Project::open(".")?;
+    romance_core::generator::admin::generate(&project)?;
+    romance_core::ai_context::regenerate(&project)?;
+    Ok(())
+}
+
+/// Run a user-defined recipe from `.romance/generators/<name>/`.
+pub fn run_recipe(args: &[String]) -> Result<()> {
+    let project = Project::open(".")?;
+    let Some((name, rest)) = args.split_first() else {
+        anyhow::bail!("Missing generator name");
+    };
+
+    let recipe = romance_core::generator::recipe::Recipe::load(&project, name)?;
+    if rest.iter().any(|a| a == "--help" || a == "-h") {
+        print_recipe_help(&recipe);
+        return Ok(());
+    }
+
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    if let Err(e) = romance_core::generator::recipe::generate(&project, &recipe, rest, &mut tracker) {
+        ui::error(&format!("Generation failed: {}", e));
+        tracker.rollback(project.fs());
+        return Err(e);
+    }
+
+    say!("Generator '{}' finished successfully!", name);
     Ok(())
 }
+
+fn print_recipe_help(recipe: &romance_core::generator::recipe::Recipe) {
+    if let Some(description) = &recipe.description {
+        say!("{}", description);
+        say!();
+    }
+    say!("{} {}", "Usage:".bold(), recipe.usage());
+    if !recipe.args.is_empty() {
+        say!();
+        say!("{}", "Arguments:".bold());
+        for arg in &recipe.args {
+            let default = arg
+                .default
+                .as_ref()
+                .map(|d| format!(" [default: {}]", d))
+                .unwrap_or_default();
+            say!(
+                "  {:<16} {}{}",
+                arg.name.cyan(),
+                arg.help.as_deref().unwrap_or(""),
+                default
+            );
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use colored::Colorize;
+use romance_core::error::Failure;
+use romance_core::history::{self, ChangeKind};
+use romance_core::project::Project;
+use romance_core::say;
+use std::path::Path;
+
+/// List journal entries, most recent first.
+pub fn run_list() -> Result<()> {
+    let project_root = Path::new(".");
+    if !project_root.join("romance.toml").exists() {
+        return Err(Failure::NotAProject.error(
+            "Not a Romance project (romance.toml not found)",
+        ));
+    }
+
+    let entries = history::list(project_root)?;
+    if entries.is_empty() {
+        say!("No history recorded yet.");
+        return Ok(());
+    }
+
+    say!("{}", "History:".bold());
+    say!();
+    for entry in entries.iter().rev() {
+        let when = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| entry.timestamp.clone());
+        let summary = format!(
+            "{} created, {} modified, {} deleted",
+            entry.count(ChangeKind::Created),
+            entry.count(ChangeKind::Modified),
+            entry.count(ChangeKind::Deleted)
+        );
+        let line = format!(
+            "  #{:<4} {}  {:<36} {}",
+            entry.id, when, entry.command, summary
+        );
+        if entry.undone_at.is_some() {
+            say!("{} {}", line.dimmed(), "(undone)".dimmed());
+        } else if !entry.succeeded {
+            say!("{} {}", line, "(failed)".red());
+        } else {
+            say!("{}", line);
+        }
+        for migration in &entry.migrations {
+            say!("         {} {}", "migration".cyan(), migration);
+        }
+    }
+    say!();
+    say!("Revert an operation with: {}", "romance undo [<id>]".green());
+    Ok(())
+}
+
+/// Revert a single journal entry (the latest one by default).
+pub fn run_undo(id: Option<u32>) -> Result<()> {
+    let project_root = Path::new(".");
+    if !project_root.join("romance.toml").exists() {
+        return Err(Failure::NotAProject.error(
+            "Not a Romance project (romance.toml not found)",
+        ));
+    }
+
+    let (entry, report) = history::undo(project_root, id)?;
+
+    say!(
+        "{}",
+        format!("Undoing #{}: romance {}", entry.id, entry.command).bold()
+    );
+    for path in &report.removed {
+        romance_core::utils::ui::removed(path);
+    }
+    for path in &report.restored {
+        if romance_core::utils::ui::is_json() {
+            romance_core::utils::ui::event("restored", serde_json::json!({ "path": path }));
+        } else {
+            say!("  {} {}", "restore".cyan(), path);
+        }
+    }
+
+    if !entry.migrations.is_empty() {
+        say!();
+        say!(
+            "{}",
+            "Removed migrations that may already have been applied:".yellow()
+        );
+        for migration in &entry.migrations {
+            say!("  {}", migration);
+        }
+        say!(
+            "If so, roll them back first with: {}",
+            "romance db rollback".bold()
+        );
+    }
+
+    if project_root.join("backend/src").exists() {
+        romance_core::ai_context::regenerate(&Project::open(project_root)?)?;
+    }
+
+    say!();
+    say!("{}", format!("Undid #{}.", entry.id).green().bold());
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::MultiSelect;
+use romance_core::error::Failure;
+use romance_core::import::{self, Database, Import};
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::utils::ui;
+use std::path::Path;
+
+pub fn run_sql(file: &Path, tables: &[String]) -> Result<()> {
+    let project = Project::open(".")?;
+    let sql = std::fs::read_to_string(file)
+        .map_err(|e| Failure::Io.error(format!("Failed to read {}: {}", file.display(), e)))?;
+    let mut db = import::sql::parse(&sql)?;
+    if !tables.is_empty() {
+        db.retain_tables(tables)?;
+    }
+    run(&project, db)
+}
+
+pub fn run_db(url: &str, tables: &[String]) -> Result<()> {
+    let project = Project::open(".")?;
+    say!("{}", "Reading database schema...".bold());
+    let mut db = import::postgres::introspect(url)?;
+    db.tables.retain(|t| t.name != "seaql_migrations");
+    if tables.is_empty() {
+        let picked = pick_tables(&project, &db)?;
+        db.retain_tables(&picked)?;
+    } else {
+        db.retain_tables(tables)?;
+    }
+    run(&project, db)
+}
+
+pub fn run_openapi(file: &Path, schemas: &[String]) -> Result<()> {
+    let project = Project::open(".")?;
+    let source = std::fs::read_to_string(file)
+        .map_err(|e| Failure::Io.error(format!("Failed to read {}: {}", file.display(), e)))?;
+    let doc = import::openapi::parse(&source)?;
+    let import = import::openapi::plan(&project, &doc, schemas)?;
+    generate(&project, import)
+}
+
+/// Let the user pick the tables to import, with the tables of existing
+/// entities unchecked. Without a prompt, those are left out.
+fn pick_tables(project: &Project, db: &Database) -> Result<Vec<String>> {
+    let names: Vec<String> = db.tables.iter().map(|t| t.qualified_name()).collect();
+    let new_tables = import::new_tables(project, db);
+    if !ui::is_interactive() || names.is_empty() {
+        let existing: Vec<&str> = names
+            .iter()
+            .filter(|n| !new_tables.contains(n))
+            .map(String::as_str)
+            .collect();
+        if !existing.is_empty() {
+            say!(
+                "Leaving out tables of existing entities: {} (name them with --tables to import them)",
+                existing.join(", ")
+            );
+        }
+        return Ok(new_tables);
+    }
+    let defaults: Vec<bool> = names.iter().map(|n| new_tables.contains(n)).collect();
+    let picked = MultiSelect::new()
+        .with_prompt("Tables to import (space to toggle, enter to confirm)")
+        .items(&names)
+        .defaults(&defaults)
+        .interact()?;
+    Ok(picked.into_iter().map(|i| names[i].clone()).collect())
+}
+
+/// Generate entities for the tables in `db`, in baseline mode.
+fn run(project: &Project, db: Database) -> Result<()> {
+    generate(project, import::plan(project, &db))
+}
+
+fn generate(project: &Project, import: Import) -> Result<()> {
+    for warning in &import.warnings {
+        ui::warn(warning);
+    }
+
+    say!(
+        "{}",
+        format!(
+            "Importing {} entit{} and {} junction table(s)...",
+            import.entities.len(),
+            if import.entities.len() == 1 { "y" } else { "ies" },
+            import.junctions.len()
+        )
+        .bold()
+    );
+
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    if let Err(e) = import::generate(project, &import, &mut tracker) {
+        ui::error(&format!("Import failed: {}", e));
+        tracker.rollback(project.fs());
+        return Err(e);
+    }
+
+    romance_core::ai_context::regenerate(project)?;
+
+    let names: Vec<&str> = import.entities.iter().map(|e| e.name.as_str()).collect();
+    say!("Imported: {}", names.join(", "));
+    if import.baseline {
+        say!(
+            "The tables already exist, so no create-table migrations were generated; \
+             a baseline migration records them."
+        );
+    } else {
+        say!("Create the tables with: {}", "romance db migrate".bold());
+    }
+    Ok(())
+}
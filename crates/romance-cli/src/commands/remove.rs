@@ -1,89 +1,98 @@
 use anyhow::Result;
 use romance_core::addon;
-use std::path::Path;
+use romance_core::project::Project;
 
 pub fn run_validation() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::validation::ValidationAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::validation::ValidationAddon, &mut project)
 }
 
 pub fn run_soft_delete() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::soft_delete::SoftDeleteAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::soft_delete::SoftDeleteAddon, &mut project)
 }
 
 pub fn run_audit_log() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::audit_log::AuditLogAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::audit_log::AuditLogAddon, &mut project)
 }
 
 pub fn run_storage() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::storage::StorageAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::storage::StorageAddon, &mut project)
 }
 
 pub fn run_search() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::search::SearchAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::search::SearchAddon, &mut project)
 }
 
 pub fn run_oauth() -> Result<()> {
-    let project_root = Path::new(".");
+    let mut project = Project::open(".")?;
     // Provider doesn't matter for uninstall — we just need an OauthAddon instance
     addon::run_uninstall(
         &addon::oauth::OauthAddon {
             provider: String::new(),
         },
-        project_root,
+        &mut project,
     )
 }
 
 pub fn run_security() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::security::SecurityAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::security::SecurityAddon, &mut project)
 }
 
 pub fn run_observability() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::observability::ObservabilityAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::observability::ObservabilityAddon, &mut project)
 }
 
 pub fn run_dashboard() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::dashboard::DashboardAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::dashboard::DashboardAddon, &mut project)
 }
 
 pub fn run_email() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::email::EmailAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::email::EmailAddon, &mut project)
 }
 
 pub fn run_i18n() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::i18n::I18nAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::i18n::I18nAddon, &mut project)
 }
 
 pub fn run_cache() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::cache::CacheAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::cache::CacheAddon, &mut project)
 }
 
 pub fn run_tasks() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::tasks::TasksAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::tasks::TasksAddon, &mut project)
 }
 
 pub fn run_websocket() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::websocket::WebsocketAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::websocket::WebsocketAddon, &mut project)
 }
 
 pub fn run_api_keys() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::api_keys::ApiKeysAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::api_keys::ApiKeysAddon, &mut project)
 }
 
 pub fn run_multitenancy() -> Result<()> {
-    let project_root = Path::new(".");
-    addon::run_uninstall(&addon::multitenancy::MultitenancyAddon, project_root)
+    let mut project = Project::open(".")?;
+    addon::run_uninstall(&addon::multitenancy::MultitenancyAddon, &mut project)
+}
+
+pub fn run_external(args: &[String]) -> Result<()> {
+    let mut project = Project::open(".")?;
+    let [name] = args else {
+        anyhow::bail!("Usage: romance remove <addon-name>");
+    };
+    let addon = addon::declarative::DeclarativeAddon::load_installed(&project, name)?;
+    addon::run_uninstall(&addon, &mut project)
 }
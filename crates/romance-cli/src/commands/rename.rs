@@ -0,0 +1,80 @@
+use anyhow::Result;
+use colored::Colorize;
+use romance_core::generator::rename::{self, RenameReport};
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::utils::ui;
+use std::path::Path;
+
+pub fn run_entity(from: &str, to: &str) -> Result<()> {
+    say!(
+        "{}",
+        format!("Renaming entity '{}' to '{}'...", from, to).bold()
+    );
+    say!();
+
+    let report = rename::entity(&Project::open(".")?, from, to)?;
+    print_report(&report);
+
+    if Path::new("backend/src").exists() {
+        romance_core::ai_context::regenerate(&Project::open(".")?)?;
+    }
+    if !report.created.is_empty() {
+        say!("Apply the migration with: {}", "romance db migrate".bold());
+    }
+    if report.leftovers.is_empty() {
+        say!("{}", "Done.".green().bold());
+    } else {
+        say!(
+            "{}",
+            format!("Done, with {} item(s) to review.", report.leftovers.len())
+                .yellow()
+                .bold()
+        );
+    }
+    Ok(())
+}
+
+fn print_report(report: &RenameReport) {
+    if ui::is_json() {
+        for (from, to) in &report.moved {
+            ui::event("renamed", serde_json::json!({ "from": from, "to": to }));
+        }
+        report.rewritten.iter().for_each(|path| ui::updated(path));
+        report.created.iter().for_each(|path| ui::created(path));
+        report.leftovers.iter().for_each(|leftover| ui::warn(leftover));
+        return;
+    }
+
+    if !report.moved.is_empty() {
+        say!("{}", "Moved:".bold());
+        for (from, to) in &report.moved {
+            say!("  {} {} -> {}", ">".cyan(), from, to);
+        }
+        say!();
+    }
+
+    if !report.rewritten.is_empty() {
+        say!("{}", "Updated:".bold());
+        for path in &report.rewritten {
+            say!("  {} {}", "~".cyan(), path);
+        }
+        say!();
+    }
+
+    if !report.created.is_empty() {
+        say!("{}", "Created:".bold());
+        for path in &report.created {
+            say!("  {} {}", "+".green(), path);
+        }
+        say!();
+    }
+
+    if !report.leftovers.is_empty() {
+        say!("{}", "Still referring to the old name:".yellow().bold());
+        for leftover in &report.leftovers {
+            say!("  {} {}", "!".yellow(), leftover);
+        }
+        say!();
+    }
+}
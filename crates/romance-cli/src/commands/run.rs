@@ -1,9 +1,12 @@
 use anyhow::Result;
 use colored::Colorize;
+use romance_core::error::Failure;
+use romance_core::say;
+use romance_core::utils::ui;
 use std::process::Command;
 
 pub fn run(command: &str, args: &[String]) -> Result<()> {
-    println!(
+    say!(
         "{}",
         format!("Running management command: {}", command).bold()
     );
@@ -21,22 +24,26 @@ pub fn run(command: &str, args: &[String]) -> Result<()> {
     let status = Command::new("cargo")
         .args(&cargo_args)
         .current_dir("backend")
+        .stdout(ui::child_stdout())
         .status();
 
     match status {
         Ok(s) if s.success() => {
-            println!("{}", format!("Command '{}' completed.", command).green());
+            say!("{}", format!("Command '{}' completed.", command).green());
             Ok(())
         }
         Ok(s) => {
-            anyhow::bail!(
+            Err(Failure::Command.error(format!(
                 "Command '{}' failed with exit code: {}",
                 command,
                 s.code().unwrap_or(-1)
-            );
+            )))
         }
         Err(e) => {
-            anyhow::bail!("Failed to execute command '{}': {}", command, e);
+            Err(Failure::Command.error(format!(
+                "Failed to execute command '{}': {}",
+                command, e
+            )))
         }
     }
 }
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use romance_core::error::Failure;
+use romance_core::project::Project;
+use romance_core::schema::{self, diagram};
+use romance_core::utils::ui;
+use std::path::Path;
+
+pub fn run_diagram(format: diagram::Format, output: Option<&Path>) -> Result<()> {
+    let project = Project::open(".")?;
+    let tables = schema::scan(&project)?;
+    if tables.is_empty() {
+        ui::warn("No models found in backend/src/entities");
+    }
+    let rendered = diagram::render(&tables, format);
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .map_err(|e| Failure::Io.error(format!("Failed to write {}: {}", path.display(), e)))?;
+            ui::created(&path.display().to_string());
+        }
+        None if ui::is_json() => {
+            ui::event(
+                "diagram",
+                serde_json::json!({ "format": format.name(), "content": rendered }),
+            );
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use colored::Colorize;
+use romance_core::error::Failure;
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::status::{self, ProjectStatus, TrackedFile};
+use romance_core::utils::ui;
+
+/// Report the project's drift; fails with [`Failure::Precondition`] when
+/// there is any, so CI can check for it.
+pub fn run(json: bool) -> Result<()> {
+    let report = status::scan(&Project::open(".")?)?;
+    if json {
+        ui::event("status", serde_json::to_value(&report)?);
+    } else {
+        print_report(&report);
+    }
+    if !report.is_clean() {
+        return Err(Failure::Precondition.error("The project has drifted from what romance generated"));
+    }
+    Ok(())
+}
+
+fn print_report(report: &ProjectStatus) {
+
+    say!("{}", "Project status".bold());
+    say!();
+
+    if !report.has_manifest {
+        say!(
+            "{} No .romance/manifest.json; file drift can't be reported. Run {} to create one.",
+            "warn".yellow(),
+            "romance update --init".bold()
+        );
+        say!();
+    }
+
+    print_files("Modified since generated", "M".yellow(), &report.modified);
+    print_files("Deleted", "D".red(), &report.deleted);
+    print_section(
+        "Untracked files with romance markers",
+        report.untracked.iter().map(|path| format!("  {} {}", "?".cyan(), path)),
+    );
+    print_section(
+        "Entities without a create-table migration",
+        report
+            .missing_migrations
+            .iter()
+            .map(|module| format!("  {} {}", "!".red(), module)),
+    );
+    print_section(
+        "Pending relations (waiting for the target entity)",
+        report.pending_relations.iter().map(|p| {
+            format!(
+                "  {} {} {} {}",
+                "~".yellow(),
+                p.source_entity,
+                p.relation_type,
+                p.target_entity
+            )
+        }),
+    );
+    print_section(
+        "Installed addons not recorded in the manifest",
+        report
+            .unrecorded_addons
+            .iter()
+            .map(|name| format!("  {} {}", "?".cyan(), name)),
+    );
+
+    if report.is_clean() {
+        say!("{}", "Everything matches what romance generated.".green());
+    } else if !report.unrecorded_addons.is_empty() || !report.untracked.is_empty() {
+        say!(
+            "Run {} to start tracking untracked generated files.",
+            "romance update".bold()
+        );
+    }
+}
+
+fn print_files(title: &str, tag: colored::ColoredString, files: &[TrackedFile]) {
+    print_section(
+        title,
+        files.iter().map(|file| match &file.entity {
+            Some(entity) => format!("  {} {} {}", tag, file.path, format!("(entity {})", entity).dimmed()),
+            None => format!("  {} {}", tag, file.path),
+        }),
+    );
+}
+
+fn print_section(title: &str, lines: impl Iterator<Item = String>) {
+    let lines: Vec<String> = lines.collect();
+    if lines.is_empty() {
+        return;
+    }
+    say!("{} ({}):", title.bold(), lines.len());
+    for line in lines {
+        say!("{}", line);
+    }
+    say!();
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use colored::Colorize;
+use romance_core::manifest::Manifest;
+use romance_core::project::Project;
+use romance_core::say;
+use romance_core::template;
+
+/// List embedded templates, marking the ones overridden by the project.
+pub fn run_list() -> Result<()> {
+    // Outside a project there is nothing to override
+    let overridden: Vec<String> = match Project::open(".") {
+        Ok(project) => template::override_dirs(&project)
+            .iter()
+            .flat_map(|dir| template::list_override_files(project.fs(), dir).unwrap_or_default())
+            .map(|(name, _)| name)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    say!("{}", "Templates:".bold());
+    say!();
+    for name in template::embedded_template_names() {
+        if overridden.contains(&name) {
+            say!("  {} {}", name, "(overridden)".cyan());
+        } else {
+            say!("  {}", name);
+        }
+    }
+    say!();
+    say!(
+        "Customize one with: {}",
+        "romance template eject <name>".green()
+    );
+    Ok(())
+}
+
+/// Copy an embedded template into `.romance/templates/` for editing.
+pub fn run_eject(name: &str, force: bool) -> Result<()> {
+    let project = Project::open(".")?;
+    let (name, dest) = template::eject(&project, name, force)?;
+    romance_core::utils::ui::created(&dest.display().to_string());
+    if !Manifest::exists(project.fs(), project.root()) {
+        say!(
+            "  {} No .romance/manifest.json; upstream changes to {} will not be reported by `romance update` (run `romance update --init`).",
+            "warn".yellow(),
+            name
+        );
+    }
+    say!();
+    say!(
+        "{}",
+        format!("Ejected '{}'. Edit it; it now overrides the default.", name)
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+/// Show the difference between the project's copy of a template and the default.
+pub fn run_diff(name: &str) -> Result<()> {
+    let project = Project::open(".")?;
+    let name = template::resolve_template_name(name)?;
+    let upstream = template::embedded_template(&name).unwrap_or_default();
+
+    let local = template::override_dirs(&project)
+        .iter()
+        .rev()
+        .map(|dir| dir.join(&name))
+        .find(|path| project.fs().exists(path));
+    let Some(local) = local else {
+        anyhow::bail!("Template '{}' is not overridden in this project", name);
+    };
+    let current = project.fs().read_to_string(&local)?;
+
+    if current == upstream {
+        say!("{} is identical to the default.", local.display());
+    } else {
+        say!(
+            "{}",
+            romance_core::updater::generate_diff(&current, &upstream, &name)
+        );
+    }
+    Ok(())
+}
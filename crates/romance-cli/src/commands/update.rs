@@ -1,181 +1,394 @@
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::Select;
-use romance_core::manifest::{FileCategory, Manifest};
+use romance_core::error::Failure;
+use romance_core::manifest::{save_base, FileCategory, Manifest};
+use romance_core::merge::{self, Side};
+use romance_core::project::Project;
+use romance_core::say;
 use romance_core::updater;
-use std::path::Path;
+use romance_core::utils::ui;
 
-pub fn run(init: bool) -> Result<()> {
+pub fn run(init: bool, resolve: bool) -> Result<()> {
     if init {
         return run_init();
     }
-
-    let project_dir = Path::new(".");
-
-    if !project_dir.join("romance.toml").exists() {
-        anyhow::bail!("Not a Romance project (romance.toml not found)");
+    if resolve {
+        return run_resolve();
     }
 
-    if !Manifest::exists(project_dir) {
-        println!("{}", "No .romance/manifest.json found.".yellow());
-        println!("This project was created before version tracking was available.");
-        println!(
+    let project = Project::open(".")?;
+    let project_dir = project.root();
+
+    if !Manifest::exists(project.fs(), project_dir) {
+        say!("{}", "No .romance/manifest.json found.".yellow());
+        say!("This project was created before version tracking was available.");
+        say!(
             "Run {} to create a baseline manifest.",
             "romance update --init".bold()
         );
         return Ok(());
     }
 
-    println!("{}", "Checking for template updates...".bold());
+    say!("{}", "Checking for template updates...".bold());
 
-    let plan = updater::plan_update(project_dir)?;
-    let mut manifest = Manifest::load(project_dir)?;
+    let mut manifest = Manifest::load(project.fs(), project_dir)?;
+    warn_changed_ejected_templates(&mut manifest);
+    let plan = updater::plan_update(&project)?;
+
+    for (group, reason) in &plan.skipped {
+        ui::skipped(&group.to_string(), reason);
+    }
+
+    // Files generated before entity, auth and addon files were tracked
+    if !plan.untracked.is_empty() {
+        for item in &plan.untracked {
+            updater::record_baseline(&project, &mut manifest, item)?;
+        }
+        say!(
+            "  {} generated file(s) are now tracked; template changes to them will be merged from now on",
+            plan.untracked.len().to_string().cyan()
+        );
+    }
 
-    let total_changes = plan.auto_update.len() + plan.conflicts.len() + plan.new_files.len();
+    let total_changes =
+        plan.auto_update.len() + plan.merged.len() + plan.conflicts.len() + plan.new_files.len();
     if total_changes == 0 {
-        println!("{}", "All scaffold files are up to date.".green());
+        say!("{}", "All generated files are up to date.".green());
         manifest.romance_version = env!("CARGO_PKG_VERSION").to_string();
         manifest.updated_at = chrono::Utc::now().to_rfc3339();
-        manifest.save(project_dir)?;
+        manifest.save(project.fs(), project_dir)?;
         return Ok(());
     }
 
-    println!(
+    print_group_plans(&plan);
+    say!(
         "  {} file(s) can be auto-updated",
         plan.auto_update.len().to_string().green()
     );
-    println!(
+    say!(
+        "  {} file(s) changed on both sides can be merged",
+        plan.merged.len().to_string().green()
+    );
+    say!(
         "  {} file(s) have conflicts",
         plan.conflicts.len().to_string().yellow()
     );
-    println!(
+    say!(
         "  {} file(s) are new in this version",
         plan.new_files.len().to_string().cyan()
     );
     if !plan.deleted.is_empty() {
-        println!(
+        say!(
             "  {} file(s) were deleted by user (skipping)",
             plan.deleted.len().to_string().red()
         );
     }
-    println!("  {} file(s) are unchanged", plan.unchanged.len());
-    println!();
+    say!("  {} file(s) are unchanged", plan.unchanged.len());
+    say!();
 
     // Auto-update unmodified files
     if !plan.auto_update.is_empty() {
-        println!("{}", "Auto-updating unmodified files:".bold());
+        say!("{}", "Auto-updating unmodified files:".bold());
         for item in &plan.auto_update {
-            updater::apply_update(project_dir, &mut manifest, item)?;
-            println!("  {} {}", "update".green(), item.output_path);
+            updater::apply_update(&project, &mut manifest, item)?;
+            ui::updated(&item.output_path);
+        }
+        say!();
+    }
+
+    // Merge files whose changes don't overlap with the template's
+    if !plan.merged.is_empty() {
+        say!("{}", "Merging your changes with the new templates:".bold());
+        for item in &plan.merged {
+            updater::apply_merge(&project, &mut manifest, item)?;
+            ui::updated_with(&item.output_path, "merged");
         }
-        println!();
+        say!();
     }
 
     // New files
     if !plan.new_files.is_empty() {
-        println!("{}", "Creating new files:".bold());
+        say!("{}", "Creating new files:".bold());
         for item in &plan.new_files {
             if item.current_content.is_some() {
-                println!(
-                    "  {} {} (already exists, skipping)",
-                    "skip".yellow(),
-                    item.output_path
-                );
+                ui::skipped(&item.output_path, "already exists");
             } else {
-                updater::apply_update(project_dir, &mut manifest, item)?;
-                println!("  {} {}", "create".green(), item.output_path);
+                updater::apply_update(&project, &mut manifest, item)?;
+                ui::created(&item.output_path);
             }
         }
-        println!();
+        say!();
     }
 
-    // Handle conflicts interactively
+    // Handle conflicts interactively; without prompts, keep the user's version
+    let mut with_markers = 0;
+    let mut kept = 0;
     if !plan.conflicts.is_empty() {
-        println!("{}", "Resolving conflicts:".bold());
+        say!("{}", "Resolving conflicts:".bold());
         for item in &plan.conflicts {
+            if !ui::is_interactive() {
+                kept += 1;
+                ui::skipped(&item.output_path, "conflict, kept your version");
+                continue;
+            }
+            let mut choices = vec![
+                "Overwrite (use new template)",
+                "Skip (keep your version)",
+                "Show diff",
+            ];
+            if let Some(merge) = &item.merge {
+                say!(
+                    "  {} has {} overlapping change(s)",
+                    item.output_path.yellow(),
+                    merge.conflicts
+                );
+                choices.insert(0, "Merge (write conflict markers to resolve later)");
+            }
+            let offset = choices.len() - 3;
             loop {
-                let choices = &[
-                    "Overwrite (use new template)",
-                    "Skip (keep your version)",
-                    "Show diff",
-                ];
                 let selection = Select::new()
                     .with_prompt(format!(
                         "  {} was modified by you AND template changed",
                         item.output_path.yellow()
                     ))
-                    .items(choices)
-                    .default(1)
+                    .items(&choices)
+                    .default(0)
                     .interact()?;
 
-                match selection {
-                    0 => {
-                        updater::apply_update(project_dir, &mut manifest, item)?;
-                        println!("    {} {}", "overwrite".green(), item.output_path);
+                match selection.checked_sub(offset) {
+                    None => {
+                        updater::apply_merge(&project, &mut manifest, item)?;
+                        with_markers += 1;
+                        ui::updated_with(&item.output_path, "conflict markers");
                         break;
                     }
-                    1 => {
-                        println!("    {} {}", "skip".yellow(), item.output_path);
+                    Some(0) => {
+                        updater::apply_update(&project, &mut manifest, item)?;
+                        ui::updated_with(&item.output_path, "overwritten");
                         break;
                     }
-                    2 => {
+                    Some(1) => {
+                        ui::skipped(&item.output_path, "kept your version");
+                        break;
+                    }
+                    Some(2) => {
                         let current = item.current_content.as_deref().unwrap_or("");
                         let diff =
                             updater::generate_diff(current, &item.new_content, &item.output_path);
-                        println!("{}", diff);
+                        say!("{}", diff);
                     }
                     _ => unreachable!(),
                 }
             }
         }
-        println!();
+        say!();
     }
 
     manifest.romance_version = env!("CARGO_PKG_VERSION").to_string();
     manifest.updated_at = chrono::Utc::now().to_rfc3339();
-    manifest.save(project_dir)?;
+    manifest.save(project.fs(), project_dir)?;
 
-    println!("{}", "Update complete!".green().bold());
+    say!("{}", "Update complete!".green().bold());
+    if with_markers > 0 {
+        say!(
+            "{} file(s) contain conflict markers. Edit them by hand or run {}.",
+            with_markers.to_string().yellow(),
+            "romance update --resolve".bold()
+        );
+    }
+    if kept > 0 {
+        return Err(Failure::Conflict.error(format!(
+            "{} file(s) changed on both sides were left as they are; run `romance update` interactively to resolve them",
+            kept
+        )));
+    }
     Ok(())
 }
 
-fn run_init() -> Result<()> {
-    let project_dir = Path::new(".");
+/// One line per scaffold, auth, addon or entity group that the update changes.
+fn print_group_plans(plan: &updater::UpdatePlan) {
+    say!("{}", "Update plan:".bold());
+    for (group, counts) in plan.by_group() {
+        if !counts.has_changes() {
+            continue;
+        }
+        let mut parts = Vec::new();
+        for (count, label) in [
+            (counts.auto_update, "update"),
+            (counts.merged, "merge"),
+            (counts.conflicts, "conflict"),
+            (counts.new_files, "new"),
+        ] {
+            if count > 0 {
+                parts.push(format!("{} {}", count, label));
+            }
+        }
+        say!(
+            "  {:<24} {} ({} unchanged)",
+            group.to_string(),
+            parts.join(", "),
+            counts.unchanged
+        );
+    }
+    say!();
+}
 
-    if !project_dir.join("romance.toml").exists() {
-        anyhow::bail!("Not a Romance project (romance.toml not found)");
+/// Walk through files left with conflict markers by a merge.
+fn run_resolve() -> Result<()> {
+    let project = Project::open(".")?;
+    let project_dir = project.root();
+    if !Manifest::exists(project.fs(), project_dir) {
+        return Err(Failure::Precondition
+            .error("No .romance/manifest.json found. Run `romance update --init` first."));
     }
 
-    if Manifest::exists(project_dir) {
-        println!(
+    let manifest = Manifest::load(project.fs(), project_dir)?;
+    let unresolved = updater::unresolved_conflicts(&project, &manifest);
+    if unresolved.is_empty() {
+        say!("{}", "No unresolved conflicts.".green());
+        return Ok(());
+    }
+
+    say!("{}", "Resolving conflicts:".bold());
+    let mut remaining = 0;
+    for (path, count) in &unresolved {
+        let choices = &[
+            "Keep yours (in every conflict)",
+            "Use template (in every conflict)",
+            "Skip (edit by hand)",
+            "Show conflicts",
+        ];
+        if !ui::is_interactive() {
+            remaining += 1;
+            ui::skipped(path, "unresolved conflicts");
+            continue;
+        }
+        loop {
+            let selection = Select::new()
+                .with_prompt(format!("  {} has {} conflict(s)", path.yellow(), count))
+                .items(choices)
+                .default(2)
+                .interact()?;
+
+            match selection {
+                0 | 1 => {
+                    let keep = if selection == 0 { Side::Yours } else { Side::Template };
+                    updater::resolve_conflicts(&project, path, keep)?;
+                    ui::updated_with(path, "resolved");
+                    break;
+                }
+                2 => {
+                    remaining += 1;
+                    ui::skipped(path, "edit by hand");
+                    break;
+                }
+                3 => print_conflicts(&project.fs().read_to_string(&project_dir.join(path))?),
+                _ => unreachable!(),
+            }
+        }
+    }
+    say!();
+
+    if remaining == 0 {
+        say!("{}", "All conflicts resolved.".green().bold());
+    } else {
+        say!(
+            "{} file(s) still contain conflict markers.",
+            remaining.to_string().yellow()
+        );
+        if !ui::is_interactive() {
+            return Err(Failure::Conflict.error(format!(
+                "{} file(s) still contain conflict markers",
+                remaining
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn print_conflicts(content: &str) {
+    let mut inside = false;
+    for (number, line) in content.lines().enumerate() {
+        let marker = line.trim_end();
+        if marker == merge::MARKER_YOURS {
+            inside = true;
+            say!("{}", format!("@@ line {} @@", number + 1).cyan());
+        }
+        if inside {
+            match marker {
+                merge::MARKER_YOURS | merge::MARKER_SEPARATOR | merge::MARKER_TEMPLATE => {
+                    say!("{}", line.dimmed())
+                }
+                _ => say!("{}", line),
+            }
+        }
+        if marker == merge::MARKER_TEMPLATE {
+            inside = false;
+        }
+    }
+}
+
+/// Warn about ejected templates whose upstream version changed, then mark the
+/// new upstream version as seen so each change is reported once.
+fn warn_changed_ejected_templates(manifest: &mut Manifest) {
+    let changed = romance_core::template::changed_upstream_templates(manifest);
+    if changed.is_empty() {
+        return;
+    }
+    say!(
+        "{}",
+        "Ejected templates changed upstream (your copies were not modified):".yellow()
+    );
+    for name in &changed {
+        ui::warn(&format!(".romance/templates/{}", name));
+        if let Some(content) = romance_core::template::embedded_template(name) {
+            if let Some(record) = manifest.ejected_templates.get_mut(name) {
+                record.upstream_hash = romance_core::manifest::content_hash(&content);
+            }
+        }
+    }
+    say!(
+        "Review with {} and merge the changes you want.",
+        "romance template diff <name>".bold()
+    );
+    say!();
+}
+
+fn run_init() -> Result<()> {
+    let project = Project::open(".")?;
+    let project_dir = project.root();
+
+    if Manifest::exists(project.fs(), project_dir) {
+        say!(
             "{}",
             "Manifest already exists. Use `romance update` instead.".yellow()
         );
         return Ok(());
     }
 
-    let config = romance_core::config::RomanceConfig::load(project_dir)?;
-    let mut manifest = Manifest::new(&config.project.name, env!("CARGO_PKG_VERSION"));
+    let mut manifest = Manifest::new(&project.config().project.name, env!("CARGO_PKG_VERSION"));
 
     let all_mappings = updater::get_scaffold_mappings();
     for (template_path, output_path) in &all_mappings {
         let full_path = project_dir.join(output_path);
-        if let Ok(content) = std::fs::read_to_string(&full_path) {
+        if let Ok(content) = project.fs().read_to_string(&full_path) {
             let category = if output_path.contains("mod.rs") || output_path.contains("lib.rs") {
                 FileCategory::Marker
             } else {
                 FileCategory::Scaffold
             };
             manifest.record_file(output_path, Some(template_path), category, &content, None);
+            save_base(project.fs(), project_dir, output_path, &content)?;
         }
     }
 
-    manifest.save(project_dir)?;
-    println!(
+    manifest.save(project.fs(), project_dir)?;
+    say!(
         "{}",
         "Manifest created at .romance/manifest.json".green().bold()
     );
-    println!("Future `romance update` runs will track changes from this baseline.");
+    say!("Future `romance update` runs will track changes from this baseline.");
 
     Ok(())
 }
@@ -1,10 +1,33 @@
 mod commands;
 
-use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use commands::Cli;
+use romance_core::error::Failure;
+use romance_core::utils::ui;
+use std::io::IsTerminal;
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    commands::run(cli)
+fn main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    ui::set_json(cli.json);
+    // Prompts would corrupt JSON output and hang without a terminal
+    ui::set_interactive(!cli.yes && !cli.json && std::io::stdin().is_terminal());
+
+    if let Err(err) = commands::run(cli, &matches) {
+        let failure = Failure::of(&err);
+        let code = failure.map_or(1, Failure::exit_code);
+        if ui::is_json() {
+            ui::event(
+                "error",
+                serde_json::json!({
+                    "message": format!("{:#}", err),
+                    "kind": failure,
+                    "exit_code": code,
+                }),
+            );
+        } else {
+            eprintln!("Error: {:?}", err);
+        }
+        std::process::exit(code);
+    }
 }
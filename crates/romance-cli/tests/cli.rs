@@ -0,0 +1,135 @@
+//! Runs the `romance` binary on scaffolded projects.
+
+use romance_core::fs::DiskFs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+fn romance(project_dir: &Path, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_romance"))
+        .current_dir(project_dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "romance {} failed:\n{}{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn read(project_dir: &Path, rel: &str) -> String {
+    std::fs::read_to_string(project_dir.join(rel)).unwrap_or_else(|e| panic!("{}: {}", rel, e))
+}
+
+#[test]
+fn generate_entity_accepts_options_after_the_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("app");
+    romance_core::scaffold::render_project(Arc::new(DiskFs), &project_dir, "app").unwrap();
+    romance(&project_dir, &["generate", "module", "billing"]);
+
+    // As documented for `romance generate module`
+    romance(
+        &project_dir,
+        &["generate", "entity", "Invoice", "number:string", "total:decimal", "--module", "billing"],
+    );
+    let model = read(&project_dir, "backend/src/entities/billing/invoice.rs");
+    assert!(model.contains("pub number: String"), "{}", model);
+    assert!(model.contains("pub total: Decimal"), "{}", model);
+
+    std::fs::write(project_dir.join("order.json"), r#"{"reference": "A-1", "paid": true}"#).unwrap();
+    romance(
+        &project_dir,
+        &["generate", "entity", "Order", "--module", "billing", "--from-json", "order.json", "--yes"],
+    );
+    let model = read(&project_dir, "backend/src/entities/billing/order.rs");
+    assert!(model.contains("pub reference: String"), "{}", model);
+    assert!(model.contains("pub paid: bool"), "{}", model);
+}
+
+#[test]
+fn production_database_commands_are_refused_without_a_terminal() {
+    let dir = tempfile::tempdir().unwrap();
+    // Nothing listens on port 1, so reaching the database would fail differently
+    std::fs::write(
+        dir.path().join("romance.toml"),
+        "[project]\nname = \"app\"\n\n[backend]\nport = 3000\ndatabase_url = \"postgres://localhost:1/app_prod\"\n\n[frontend]\nport = 5173\napi_base_url = \"http://localhost:3000\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("backup.dump"), "not a dump").unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_romance"))
+            .current_dir(dir.path())
+            .env("ROMANCE_ENV", "production")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    for args in [&["db", "drop"][..], &["db", "reset", "--yes"], &["db", "restore", "backup.dump"]] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(4), "romance {}", args.join(" "));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("the active environment is production"));
+    }
+
+    // The dump is checked before the database is touched
+    let output = run(&["db", "restore", "backup.dump", "--allow-production"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("doesn't hold a pg_dump custom-format dump"));
+}
+
+/// Runs `romance --json` and checks every line on stdout is one JSON event.
+fn json_events(project_dir: &Path, args: &[&str]) -> (Option<i32>, Vec<serde_json::Value>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_romance"))
+        .current_dir(project_dir)
+        .arg("--json")
+        .args(args)
+        .output()
+        .unwrap();
+    let events = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            let event: serde_json::Value =
+                serde_json::from_str(line).unwrap_or_else(|e| panic!("not a JSON event: {}: {}", line, e));
+            assert!(event["event"].is_string(), "no event kind: {}", line);
+            event
+        })
+        .collect();
+    (output.status.code(), events)
+}
+
+#[test]
+fn json_output_is_one_event_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("app");
+    romance_core::scaffold::render_project(Arc::new(DiskFs), &project_dir, "app").unwrap();
+
+    let (code, events) = json_events(&project_dir, &["status"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "status");
+
+    // The prerequisite warning is an event too
+    let (code, events) = json_events(&project_dir, &["generate", "entity", "Post", "author_id:uuid->Author"]);
+    assert_eq!(code, Some(0));
+    assert!(events.iter().any(|e| e["event"] == "warning" && e["message"].as_str().unwrap().contains("Author")));
+
+    // Output that would go to stdout is wrapped in an event
+    let (code, events) = json_events(&project_dir, &["schema", "diagram", "--format", "dot"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "diagram");
+    assert_eq!(events[0]["format"], "dot");
+    assert!(events[0]["content"].as_str().unwrap().starts_with("digraph"));
+
+    // Drift fails the command, for CI
+    std::fs::write(project_dir.join("backend/src/errors.rs"), "// edited\n").unwrap();
+    let (code, events) = json_events(&project_dir, &["status"]);
+    assert_eq!(code, Some(4));
+    assert_eq!(events[0]["event"], "status");
+    assert_eq!(events[0]["modified"][0]["path"], "backend/src/errors.rs");
+    assert_eq!(events[1]["event"], "error");
+}
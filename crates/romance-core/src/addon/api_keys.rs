@@ -1,45 +1,62 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct ApiKeysAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/api_keys/api_keys.rs.tera", "backend/src/api_keys.rs"),
+];
+
 impl Addon for ApiKeysAddon {
     fn name(&self) -> &str {
         "api-keys"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)?;
-        super::check_auth_exists(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)?;
+        super::check_auth_exists(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/api_keys.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/api_keys.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_api_keys(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_api_keys(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling API key authentication...".bold());
+        say!("{}", "Uninstalling API key authentication...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/api_keys.rs"))? {
-            println!("  {} backend/src/api_keys.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/api_keys.rs"))? {
+            utils::ui::removed("backend/src/api_keys.rs");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "api_keys")?;
+        super::remove_mod_from_main(fs, project_root, "api_keys")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "API key authentication uninstalled successfully."
                 .green()
@@ -54,70 +71,71 @@ impl Addon for ApiKeysAddon {
     }
 }
 
-fn install_api_keys(project_root: &Path) -> Result<()> {
+fn install_api_keys(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing API key authentication...".bold());
+    say!("{}", "Installing API key authentication...".bold());
 
-    let engine = TemplateEngine::new()?;
-    let timestamp = crate::generator::migration::next_timestamp();
+    let engine = TemplateEngine::for_project(project)?;
+    let timestamp = crate::generator::migration::next_timestamp(project);
 
     let mut ctx = Context::new();
     ctx.insert("timestamp", &timestamp);
 
     // Generate api_keys module
     let content = engine.render("addon/api_keys/api_keys.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/api_keys.rs"), &content)?;
-    println!("  {} backend/src/api_keys.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/api_keys.rs"), &content)?;
+    utils::ui::created("backend/src/api_keys.rs");
 
     // Generate migration
     let content = engine.render("addon/api_keys/migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_create_api_keys_table", timestamp);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration_module));
 
     // Register migration in lib.rs
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration_module),
     )?;
 
     // Add mod api_keys to main.rs
-    super::add_mod_to_main(project_root, "api_keys")?;
+    super::add_mod_to_main(fs, project_root, "api_keys")?;
 
     // Add sha2 dependency (for hashing API keys)
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[("sha2", r#""0.10""#)],
     )?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "API key authentication installed successfully!".green().bold()
     );
-    println!("  API keys are hashed with SHA-256 before storage.");
-    println!("  Use X-API-Key header for machine-to-machine auth.");
-    println!();
-    println!("Next steps:");
-    println!("  romance db migrate");
+    say!("  API keys are hashed with SHA-256 before storage.");
+    say!("  Use X-API-Key header for machine-to-machine auth.");
+    say!();
+    say!("Next steps:");
+    say!("  romance db migrate");
 
     Ok(())
 }
@@ -1,81 +1,100 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct AuditLogAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/audit_log/audit.rs.tera", "backend/src/audit.rs"),
+    ("addon/audit_log/model.rs.tera", "backend/src/entities/audit_entry.rs"),
+    ("addon/audit_log/handlers.rs.tera", "backend/src/handlers/audit_log.rs"),
+    ("addon/audit_log/AuditLog.tsx.tera", "frontend/src/features/admin/AuditLog.tsx"),
+];
+
 impl Addon for AuditLogAddon {
     fn name(&self) -> &str {
         "audit-log"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)?;
-        super::check_auth_exists(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)?;
+        super::check_auth_exists(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/audit.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/audit.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_audit_log(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_audit_log(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling audit log...".bold());
+        say!("{}", "Uninstalling audit log...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/audit.rs"))? {
-            println!("  {} backend/src/audit.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/audit.rs"))? {
+            utils::ui::removed("backend/src/audit.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/entities/audit_entry.rs"),
         )? {
-            println!(
-                "  {} backend/src/entities/audit_entry.rs",
-                "delete".red()
-            );
+            utils::ui::removed("backend/src/entities/audit_entry.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/handlers/audit_log.rs"),
         )? {
-            println!("  {} backend/src/handlers/audit_log.rs", "delete".red());
+            utils::ui::removed("backend/src/handlers/audit_log.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("frontend/src/features/admin/AuditLog.tsx"),
         )? {
-            println!(
-                "  {} frontend/src/features/admin/AuditLog.tsx",
-                "delete".red()
-            );
+            utils::ui::removed("frontend/src/features/admin/AuditLog.tsx");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "audit")?;
+        super::remove_mod_from_main(fs, project_root, "audit")?;
 
         // Remove from entities/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/entities/mod.rs"),
             "pub mod audit_entry;",
         )?;
 
         // Remove from handlers/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/handlers/mod.rs"),
             "pub mod audit_log;",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "audit_log")?;
+        super::remove_feature_flag(fs, project_root, "audit_log")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Audit log uninstalled successfully.".green().bold()
         );
@@ -88,79 +107,73 @@ impl Addon for AuditLogAddon {
     }
 }
 
-fn install_audit_log(project_root: &Path) -> Result<()> {
+fn install_audit_log(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing audit log...".bold());
+    say!("{}", "Installing audit log...".bold());
 
-    let engine = TemplateEngine::new()?;
-    let timestamp = crate::generator::migration::next_timestamp();
+    let engine = TemplateEngine::for_project(project)?;
+    let timestamp = crate::generator::migration::next_timestamp(project);
 
     let mut ctx = Context::new();
     ctx.insert("timestamp", &timestamp);
 
     // Generate audit module
     let content = engine.render("addon/audit_log/audit.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/audit.rs"), &content)?;
-    println!("  {} backend/src/audit.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/audit.rs"), &content)?;
+    utils::ui::created("backend/src/audit.rs");
 
     // Generate audit_entry entity model
     let content = engine.render("addon/audit_log/model.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/entities/audit_entry.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/entities/audit_entry.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/entities/audit_entry.rs");
 
     // Generate migration
     let content = engine.render("addon/audit_log/migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_create_audit_entries_table", timestamp);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration_module));
 
     // Generate audit log handler for admin
     let content = engine.render("addon/audit_log/handlers.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/audit_log.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/handlers/audit_log.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/handlers/audit_log.rs");
 
     // Generate frontend audit log viewer
     let content = engine.render("addon/audit_log/AuditLog.tsx.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("frontend/src/features/admin/AuditLog.tsx"),
         &content,
     )?;
-    println!(
-        "  {} frontend/src/features/admin/AuditLog.tsx",
-        "create".green()
-    );
+    utils::ui::created("frontend/src/features/admin/AuditLog.tsx");
 
     // Register modules
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/entities/mod.rs"),
         mods_marker,
         "pub mod audit_entry;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod audit_log;",
@@ -168,30 +181,32 @@ fn install_audit_log(project_root: &Path) -> Result<()> {
 
     // Register migration
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration_module),
     )?;
 
     // Add mod audit to main.rs
-    super::add_mod_to_main(project_root, "audit")?;
+    super::add_mod_to_main(fs, project_root, "audit")?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "audit_log", true)?;
+    super::update_feature_flag(fs, project_root, "audit_log", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Audit log installed successfully!".green().bold()
     );
-    println!("  All create/update/delete operations will be logged.");
-    println!("  View at /admin/audit-log");
+    say!("  All create/update/delete operations will be logged.");
+    say!("  View at /admin/audit-log");
 
     Ok(())
 }
@@ -1,47 +1,63 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct CacheAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/cache/cache.rs.tera", "backend/src/cache.rs"),
+];
+
 impl Addon for CacheAddon {
     fn name(&self) -> &str {
         "cache"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/cache.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/cache.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_cache(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_cache(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling caching layer...".bold());
+        say!("{}", "Uninstalling caching layer...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/cache.rs"))? {
-            println!("  {} backend/src/cache.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/cache.rs"))? {
+            utils::ui::removed("backend/src/cache.rs");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "cache")?;
+        super::remove_mod_from_main(fs, project_root, "cache")?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "cache")?;
+        super::remove_feature_flag(fs, project_root, "cache")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Caching layer uninstalled successfully.".green().bold()
         );
@@ -50,27 +66,29 @@ impl Addon for CacheAddon {
     }
 }
 
-fn install_cache(project_root: &Path) -> Result<()> {
+fn install_cache(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing caching layer...".bold());
+    say!("{}", "Installing caching layer...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate cache service module
     let content = engine.render("addon/cache/cache.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/cache.rs"), &content)?;
-    println!("  {} backend/src/cache.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/cache.rs"), &content)?;
+    utils::ui::created("backend/src/cache.rs");
 
     // Add mod cache to main.rs
-    super::add_mod_to_main(project_root, "cache")?;
+    super::add_mod_to_main(fs, project_root, "cache")?;
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[(
             "redis",
@@ -80,25 +98,27 @@ fn install_cache(project_root: &Path) -> Result<()> {
 
     // Add env vars
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "REDIS_URL=redis://127.0.0.1:6379",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "REDIS_URL=redis://127.0.0.1:6379",
     )?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "cache", true)?;
+    super::update_feature_flag(fs, project_root, "cache", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Caching layer installed successfully!".green().bold()
     );
-    println!("  Configure Redis connection in backend/.env");
-    println!("  Use CacheService::new()? to create an instance.");
-    println!("  Example: cache.set(\"key\", &value, 300).await?");
+    say!("  Configure Redis connection in backend/.env");
+    say!("  Use CacheService::new()? to create an instance.");
+    say!("  Example: cache.set(\"key\", &value, 300).await?");
 
     Ok(())
 }
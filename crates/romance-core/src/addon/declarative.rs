@@ -0,0 +1,702 @@
+//! Declarative addons defined by an `addon.toml` manifest.
+//!
+//! External addons are installed with `romance add --path ./my-addon` and
+//! uninstalled with `romance remove <name>`. On install the addon directory is
+//! copied to `.romance/addons/<name>/`, which both records the installation and
+//! keeps the definition around for uninstalling later.
+//!
+//! ```toml
+//! name = "feature-flags"
+//! description = "Feature flags backed by Unleash"
+//! dependencies = ["cache"]
+//! requires_auth = false
+//! feature = "feature_flags"
+//! env = ["UNLEASH_URL=http://localhost:4242"]
+//!
+//! [cargo]
+//! unleash-api-client = '"0.10"'
+//!
+//! [npm]
+//! "@unleash/proxy-client-react" = "^4.0.0"
+//!
+//! [[files]]
+//! template = "templates/flags.rs.tera"
+//! output = "backend/src/flags.rs"
+//!
+//! [[injections]]
+//! file = "backend/src/main.rs"
+//! marker = "MAIN_MODS"
+//! line = "mod flags;"
+//!
+//! [[migrations]]
+//! template = "templates/migration.rs.tera"
+//! name = "create_flags_table"
+//!
+//! [uninstall]
+//! remove_files = ["backend/src/flags_cache.rs"]
+//! remove_lines = [{ file = "backend/src/main.rs", contains = "flags::init" }]
+//! ```
+
+use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::generator::recipe::{full_marker, project_relative};
+use crate::inject;
+use crate::manifest::{FileCategory, RenderedFile};
+use crate::project::Project;
+use crate::say;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::{Context as _, Result};
+use colored::Colorize;
+use heck::ToSnakeCase;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tera::Context;
+
+/// Directory (relative to the project root) holding installed declarative addons.
+pub const INSTALLED_DIR: &str = ".romance/addons";
+
+#[derive(Debug, Deserialize)]
+pub struct AddonManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub requires_auth: bool,
+    /// Feature flag set under `[features]` in romance.toml.
+    #[serde(default)]
+    pub feature: Option<String>,
+    /// Lines appended to backend/.env and backend/.env.example.
+    #[serde(default)]
+    pub env: Vec<String>,
+    /// Cargo dependencies: crate name -> version spec (`'"1.0"'` or `'{ version = "1", ... }'`).
+    #[serde(default)]
+    pub cargo: BTreeMap<String, String>,
+    /// npm dependencies: package name -> version range.
+    #[serde(default)]
+    pub npm: BTreeMap<String, String>,
+    #[serde(default)]
+    pub files: Vec<AddonFile>,
+    #[serde(default)]
+    pub injections: Vec<AddonInjection>,
+    #[serde(default)]
+    pub migrations: Vec<AddonMigration>,
+    #[serde(default)]
+    pub uninstall: UninstallSteps,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddonFile {
+    /// Template path relative to the addon directory.
+    pub template: String,
+    /// Output path relative to the project root (rendered with Tera).
+    pub output: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddonInjection {
+    pub file: String,
+    /// Marker name (`MAIN_MODS`) or full marker text.
+    pub marker: String,
+    pub line: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddonMigration {
+    /// Template path relative to the addon directory.
+    pub template: String,
+    /// Module suffix; the migration is written as `m{timestamp}_{name}.rs`.
+    pub name: String,
+}
+
+/// Extra steps run by `romance remove`, on top of undoing files, injections,
+/// env vars and the feature flag.
+#[derive(Debug, Default, Deserialize)]
+pub struct UninstallSteps {
+    #[serde(default)]
+    pub remove_files: Vec<String>,
+    #[serde(default)]
+    pub remove_lines: Vec<RemoveLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveLine {
+    pub file: String,
+    pub contains: String,
+}
+
+/// An addon loaded from an `addon.toml` manifest.
+pub struct DeclarativeAddon {
+    pub manifest: AddonManifest,
+    /// Directory containing addon.toml and the addon's templates.
+    pub dir: PathBuf,
+}
+
+impl DeclarativeAddon {
+    /// Load an addon from a directory in `fs` containing `addon.toml`.
+    pub fn load(fs: &dyn ProjectFs, dir: &Path) -> Result<Self> {
+        let path = dir.join("addon.toml");
+        let content = fs
+            .read_to_string(&path)
+            .with_context(|| format!("No addon.toml found in {}", dir.display()))?;
+        let manifest: AddonManifest = toml::from_str(&content)
+            .with_context(|| format!("Invalid addon manifest {}", path.display()))?;
+        if manifest.name.is_empty()
+            || !manifest
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!(
+                "Invalid addon name '{}' in {} (use letters, digits, '-' and '_')",
+                manifest.name,
+                path.display()
+            );
+        }
+        if super::is_builtin(&manifest.name) {
+            anyhow::bail!(
+                "Addon name '{}' in {} is taken by a built-in addon",
+                manifest.name,
+                path.display()
+            );
+        }
+        Ok(Self {
+            manifest,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Load an addon previously installed into `.romance/addons/<name>/`.
+    pub fn load_installed(project: &Project, name: &str) -> Result<Self> {
+        let dir = project.join(INSTALLED_DIR).join(name);
+        if !project.fs().exists(&dir.join("addon.toml")) {
+            anyhow::bail!("Unknown addon '{}'", name);
+        }
+        Self::load(project.fs(), &dir)
+    }
+
+    fn context(&self, project: &Project) -> Context {
+        let mut ctx = Context::new();
+        let config = project.config();
+        ctx.insert("project_name", &config.project.name);
+        ctx.insert("project_name_snake", &config.project.name.to_snake_case());
+        ctx.insert("addon_name", &self.manifest.name);
+        ctx
+    }
+
+    /// Read a template of the addon, which must be inside its directory
+    /// (also once symlinks are resolved, when it is on disk).
+    fn read_template(&self, fs: &dyn ProjectFs, relative: &str) -> Result<String> {
+        let path = self.dir.join(relative);
+        let escapes = Path::new(relative).is_absolute()
+            || Path::new(relative).components().any(|c| matches!(c, Component::ParentDir))
+            || match (std::fs::canonicalize(&path), std::fs::canonicalize(&self.dir)) {
+                (Ok(path), Ok(dir)) => !path.starts_with(dir),
+                _ => false,
+            };
+        if escapes {
+            anyhow::bail!(
+                "Addon template '{}' must stay inside the addon directory {}",
+                relative,
+                self.dir.display()
+            );
+        }
+        fs.read_to_string(&path)
+            .with_context(|| format!("Addon template {} not found", path.display()))
+    }
+
+    /// The addon's files rendered with `ctx`, keyed by project-relative path.
+    fn render_files(
+        &self,
+        fs: &dyn ProjectFs,
+        engine: &mut TemplateEngine,
+        ctx: &Context,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let outputs = self.output_paths(engine, ctx)?;
+        let mut files = Vec::new();
+        for (file, output) in self.manifest.files.iter().zip(outputs) {
+            let name = format!("addons/{}/{}", self.manifest.name, file.template);
+            engine.add_template(&name, &self.read_template(fs, &file.template)?)?;
+            files.push((output, engine.render(&name, ctx)?));
+        }
+        Ok(files)
+    }
+
+    /// Rendered output paths of the addon's files.
+    fn output_paths(&self, engine: &mut TemplateEngine, ctx: &Context) -> Result<Vec<PathBuf>> {
+        self.manifest
+            .files
+            .iter()
+            .map(|f| project_relative(&engine.render_str(&f.output, ctx)?))
+            .collect()
+    }
+}
+
+/// Names of declarative addons installed in the project.
+pub fn list_installed(project: &Project) -> Result<Vec<String>> {
+    let fs = project.fs();
+    let dir = project.join(INSTALLED_DIR);
+    let mut names = Vec::new();
+    if fs.is_dir(&dir) {
+        for path in fs.read_dir(&dir)? {
+            if fs.exists(&path.join("addon.toml")) {
+                if let Some(name) = path.file_name() {
+                    names.push(name.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Install an addon from a local directory, first installing any of its
+/// dependencies that are sibling directories of it (e.g. in a shared addons repo).
+/// The directory is read through the project's filesystem.
+pub fn install_from_path(path: &Path, project: &mut Project) -> Result<()> {
+    let addon = DeclarativeAddon::load(project.fs(), path)?;
+    for dep in &addon.manifest.dependencies {
+        if super::is_builtin(dep) || list_installed(project)?.contains(dep) {
+            continue;
+        }
+        let sibling = path.parent().map(|p| p.join(dep));
+        match sibling {
+            Some(sibling) if project.fs().exists(&sibling.join("addon.toml")) => {
+                say!(
+                    "{}",
+                    format!("Installing dependency {} from {}...", dep, sibling.display()).dimmed()
+                );
+                install_from_path(&sibling, project)?;
+            }
+            _ => anyhow::bail!(
+                "Addon '{}' depends on '{}', which is not installed. Install it first with: romance add --path <dir>",
+                addon.manifest.name,
+                dep
+            ),
+        }
+    }
+    super::run_addon(&addon, project)
+}
+
+impl Addon for DeclarativeAddon {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)?;
+        if self.manifest.requires_auth {
+            super::check_auth_exists(fs, project_root)?;
+        }
+        Ok(())
+    }
+
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        let mut engine = TemplateEngine::for_project(project)?;
+        let ctx = self.context(project);
+        Ok(self
+            .render_files(project.fs(), &mut engine, &ctx)?
+            .into_iter()
+            .map(|(output, content)| RenderedFile {
+                output: output.to_string_lossy().into_owned(),
+                template: None,
+                category: FileCategory::Addon,
+                entity_name: None,
+                content,
+            })
+            .collect())
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(
+            &project_root
+                .join(INSTALLED_DIR)
+                .join(&self.manifest.name)
+                .join("addon.toml"),
+        )
+    }
+
+    fn install(&self, project: &Project) -> Result<()> {
+        let fs = project.fs();
+        let project_root = project.root();
+        let m = &self.manifest;
+        say!("{}", format!("Installing {}...", m.name).bold());
+
+        let mut engine = TemplateEngine::for_project(project)?;
+        let ctx = self.context(project);
+
+        // Render everything up front so a broken template writes nothing
+        let files = self.render_files(fs, &mut engine, &ctx)?;
+        let mut injections = Vec::new();
+        let mut checks = Vec::new();
+        for injection in &m.injections {
+            let target = project_relative(&engine.render_str(&injection.file, &ctx)?)?;
+            let marker = full_marker(&injection.marker);
+            if !files.iter().any(|(output, _)| *output == target) {
+                checks.push(crate::generator::plan::check(project_root.join(&target), &marker));
+            }
+            injections.push((target, marker, engine.render_str(&injection.line, &ctx)?));
+        }
+        if !m.migrations.is_empty() {
+            let lib = project_root.join("backend/migration/src/lib.rs");
+            checks.push(crate::generator::plan::check(&lib, crate::generator::context::markers::MIGRATION_MODS));
+            checks.push(crate::generator::plan::check(&lib, crate::generator::context::markers::MIGRATIONS));
+        }
+        crate::generator::plan::validate_markers(fs, &checks)?;
+
+        for (output, content) in &files {
+            utils::write_file(fs, &project_root.join(output), content)?;
+            utils::ui::created(&output.display().to_string());
+        }
+
+        for migration in &m.migrations {
+            let timestamp = crate::generator::migration::next_timestamp(project);
+            let module = format!("m{}_{}", timestamp, migration.name.to_snake_case());
+            let mut ctx = ctx.clone();
+            ctx.insert("timestamp", &timestamp);
+            ctx.insert("migration_module", &module);
+            let name = format!("addons/{}/{}", m.name, migration.template);
+            engine.add_template(&name, &self.read_template(fs, &migration.template)?)?;
+            let content = engine.render(&name, &ctx)?;
+            utils::write_file(
+                fs,
+                &project_root.join(format!("backend/migration/src/{}.rs", module)),
+                &content,
+            )?;
+            crate::generator::context::register_migration(fs, project_root, &module)?;
+            utils::ui::created(&format!("backend/migration/src/{}.rs", module));
+        }
+
+        for (target, marker, line) in &injections {
+            inject::insert(fs, &project_root.join(target), marker, line)?;
+            utils::ui::injected(&target.display().to_string(), line.trim());
+        }
+
+        for (name, spec) in &m.cargo {
+            super::add_cargo_dependency(fs, project_root, &format!("{} = {}", name, spec))?;
+        }
+        for (name, version) in &m.npm {
+            super::add_npm_dependency(fs, project_root, name, version)?;
+        }
+
+        for line in &m.env {
+            super::append_env_var(fs, &project_root.join("backend/.env"), line)?;
+            super::append_env_var(fs, &project_root.join("backend/.env.example"), line)?;
+        }
+
+        if let Some(feature) = &m.feature {
+            super::update_feature_flag(fs, project_root, feature, true)?;
+        }
+
+        // Keep the definition so the addon can be uninstalled later
+        copy_dir(fs, &self.dir, &project_root.join(INSTALLED_DIR).join(&m.name))?;
+
+        say!();
+        say!(
+            "{}",
+            format!("{} installed successfully!", m.name).green().bold()
+        );
+        if let Some(description) = &m.description {
+            say!("  {}", description);
+        }
+        if !m.npm.is_empty() {
+            say!("  Run `npm install` in frontend/ to install new packages.");
+        }
+
+        Ok(())
+    }
+
+    fn uninstall(&self, project: &Project) -> Result<()> {
+        let fs = project.fs();
+        let project_root = project.root();
+        let m = &self.manifest;
+        say!("{}", format!("Uninstalling {}...", m.name).bold());
+
+        let mut engine = TemplateEngine::for_project(project)?;
+        let ctx = self.context(project);
+
+        let mut files = self.output_paths(&mut engine, &ctx)?;
+        for extra in &m.uninstall.remove_files {
+            files.push(project_relative(&engine.render_str(extra, &ctx)?)?);
+        }
+        for file in &files {
+            if super::remove_file_if_exists(fs, &project_root.join(file))? {
+                utils::ui::removed(&file.display().to_string());
+            }
+        }
+
+        for injection in &m.injections {
+            let target = project_relative(&engine.render_str(&injection.file, &ctx)?)?;
+            let line = engine.render_str(&injection.line, &ctx)?;
+            crate::inject::remove(fs, &project_root.join(target), &full_marker(&injection.marker), &line)?;
+        }
+        for remove in &m.uninstall.remove_lines {
+            let target = project_relative(&engine.render_str(&remove.file, &ctx)?)?;
+            super::remove_line_from_file(fs, &project_root.join(target), &remove.contains)?;
+        }
+
+        for line in &m.env {
+            let key = line.split('=').next().unwrap_or(line);
+            super::remove_env_var(fs, &project_root.join("backend/.env"), key)?;
+            super::remove_env_var(fs, &project_root.join("backend/.env.example"), key)?;
+        }
+
+        if let Some(feature) = &m.feature {
+            super::remove_feature_flag(fs, project_root, feature)?;
+        }
+
+        fs.remove_dir_all(&project_root.join(INSTALLED_DIR).join(&m.name))?;
+
+        say!();
+        if !m.migrations.is_empty() {
+            say!(
+                "  {} Migrations were kept; roll them back with `romance db rollback` if needed.",
+                "note".yellow()
+            );
+        }
+        if !m.cargo.is_empty() || !m.npm.is_empty() {
+            say!(
+                "  {} Cargo/npm dependencies were kept; remove them manually if unused.",
+                "note".yellow()
+            );
+        }
+        say!(
+            "{}",
+            format!("{} uninstalled successfully.", m.name).green().bold()
+        );
+        Ok(())
+    }
+
+    fn dependencies(&self) -> Vec<&str> {
+        self.manifest.dependencies.iter().map(|s| s.as_str()).collect()
+    }
+}
+
+/// Copy a directory tree, skipping hidden entries such as `.git`. Only text
+/// files are copied; an addon's definition is its manifest and templates.
+fn copy_dir(fs: &dyn ProjectFs, from: &Path, to: &Path) -> Result<()> {
+    let same = match (from.canonicalize(), to.canonicalize()) {
+        (Ok(from), Ok(to)) => from == to,
+        _ => from == to,
+    };
+    if same {
+        return Ok(());
+    }
+    fs.create_dir_all(to)?;
+    for path in fs.read_dir(from)? {
+        let Some(name) = path.file_name() else { continue };
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if fs.is_dir(&path) {
+            copy_dir(fs, &path, &to.join(name))?;
+        } else if let Ok(content) = fs.read_to_string(&path) {
+            fs.write(&to.join(name), &content)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::DiskFs;
+    use tempfile::TempDir;
+
+    const ADDON: &str = r#"
+name = "flags"
+description = "Feature flags"
+feature = "flags"
+env = ["FLAGS_URL=http://localhost:4242"]
+
+[cargo]
+unleash-api-client = '"0.10"'
+
+[npm]
+"@unleash/proxy-client-react" = "^4.0.0"
+
+[[files]]
+template = "flags.rs.tera"
+output = "backend/src/flags.rs"
+
+[[injections]]
+file = "backend/src/main.rs"
+marker = "MAIN_MODS"
+line = "mod flags;"
+
+[[migrations]]
+template = "migration.rs.tera"
+name = "create_flags_table"
+"#;
+
+    fn setup() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("app");
+        let addon_dir = dir.path().join("addons/flags");
+        utils::write_file(&DiskFs, &addon_dir.join("addon.toml"), ADDON).unwrap();
+        utils::write_file(
+            &DiskFs,
+            &addon_dir.join("flags.rs.tera"),
+            "// flags for {{ project_name }}\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &addon_dir.join("migration.rs.tera"),
+            "// {{ migration_module }}\n",
+        )
+        .unwrap();
+
+        utils::write_file(
+            &DiskFs,
+            &root.join("romance.toml"),
+            "[project]\nname = \"app\"\n\n[backend]\nport = 3000\ndatabase_url = \"postgres://localhost/app\"\n\n[frontend]\nport = 5173\napi_base_url = \"http://localhost:3000\"\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("backend/src/main.rs"),
+            "mod errors;\n// === ROMANCE:MAIN_MODS ===\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("backend/Cargo.toml"),
+            "[dependencies]\n# === ROMANCE:DEPENDENCIES ===\n",
+        )
+        .unwrap();
+        utils::write_file(&DiskFs, &root.join("backend/.env"), "DATABASE_URL=x\nOLD_FLAGS_URL=y\n").unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("frontend/package.json"),
+            "{\n  \"dependencies\": {\n    \"react\": \"^19.0.0\"\n  }\n}\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("backend/migration/src/lib.rs"),
+            "// === ROMANCE:MIGRATION_MODS ===\n        vec![\n            // === ROMANCE:MIGRATIONS ===\n        ]\n",
+        )
+        .unwrap();
+        (dir, root)
+    }
+
+    fn read(root: &Path, rel: &str) -> String {
+        std::fs::read_to_string(root.join(rel)).unwrap()
+    }
+
+    #[test]
+    fn load_rejects_invalid_name() {
+        let dir = TempDir::new().unwrap();
+        utils::write_file(&DiskFs, &dir.path().join("addon.toml"), "name = \"../evil\"\n").unwrap();
+        assert!(DeclarativeAddon::load(&DiskFs, dir.path()).is_err());
+    }
+
+    #[test]
+    fn load_rejects_builtin_names() {
+        let dir = TempDir::new().unwrap();
+        for name in ["cache", "auth"] {
+            utils::write_file(&DiskFs, &dir.path().join("addon.toml"), &format!("name = \"{}\"\n", name)).unwrap();
+            let err = DeclarativeAddon::load(&DiskFs, dir.path()).err().unwrap().to_string();
+            assert!(err.contains("built-in"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn install_applies_all_steps() {
+        let (dir, root) = setup();
+        install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap()).unwrap();
+
+        assert_eq!(read(&root, "backend/src/flags.rs"), "// flags for app\n");
+        assert!(read(&root, "backend/src/main.rs").contains("mod flags;\n// === ROMANCE:MAIN_MODS ==="));
+        assert!(read(&root, "backend/Cargo.toml").contains("unleash-api-client = \"0.10\""));
+        assert!(read(&root, "frontend/package.json").contains("\"@unleash/proxy-client-react\": \"^4.0.0\""));
+        assert!(read(&root, "backend/.env").contains("FLAGS_URL=http://localhost:4242"));
+        assert!(read(&root, "romance.toml").contains("flags = true"));
+        let lib = read(&root, "backend/migration/src/lib.rs");
+        assert!(lib.contains("_create_flags_table::Migration"));
+        assert!(root.join(INSTALLED_DIR).join("flags/addon.toml").exists());
+        assert_eq!(list_installed(&Project::open(&root).unwrap()).unwrap(), vec!["flags"]);
+    }
+
+    #[test]
+    fn uninstall_reverts_install() {
+        let (dir, root) = setup();
+        install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap()).unwrap();
+
+        let addon = DeclarativeAddon::load_installed(&Project::open(&root).unwrap(), "flags").unwrap();
+        crate::addon::run_uninstall(&addon, &mut Project::open(&root).unwrap()).unwrap();
+
+        assert!(!root.join("backend/src/flags.rs").exists());
+        assert!(!read(&root, "backend/src/main.rs").contains("mod flags;"));
+        assert_eq!(read(&root, "backend/.env"), "DATABASE_URL=x\nOLD_FLAGS_URL=y\n");
+        assert!(!read(&root, "romance.toml").contains("flags = true"));
+        assert!(!root.join(INSTALLED_DIR).join("flags").exists());
+    }
+
+    #[test]
+    fn templates_must_stay_in_addon_dir() {
+        let (dir, root) = setup();
+        utils::write_file(&DiskFs, &dir.path().join("secret.txt"), "secret").unwrap();
+        let addon_toml = read(dir.path(), "addons/flags/addon.toml");
+        for template in ["../../secret.txt", &dir.path().join("secret.txt").display().to_string()] {
+            utils::write_file(
+                &DiskFs,
+                &dir.path().join("addons/flags/addon.toml"),
+                &addon_toml.replace("flags.rs.tera", template),
+            )
+            .unwrap();
+            let err = install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap())
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(err.contains("must stay inside the addon directory"), "{}", err);
+            assert!(!root.join("backend/src/flags.rs").exists());
+        }
+
+        // A symlink inside the addon pointing out of it is caught once resolved
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.txt"), dir.path().join("addons/flags/link.tera")).unwrap();
+            utils::write_file(
+                &DiskFs,
+                &dir.path().join("addons/flags/addon.toml"),
+                &addon_toml.replace("flags.rs.tera", "link.tera"),
+            )
+            .unwrap();
+            let err = install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap())
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(err.contains("must stay inside the addon directory"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn install_resolves_sibling_dependency() {
+        let (dir, root) = setup();
+        let base = dir.path().join("addons/base");
+        utils::write_file(&DiskFs, &base.join("addon.toml"), "name = \"base\"\nfeature = \"base\"\n").unwrap();
+        let addon_toml = read(dir.path(), "addons/flags/addon.toml")
+            .replace("feature = \"flags\"", "feature = \"flags\"\ndependencies = [\"base\"]");
+        std::fs::write(dir.path().join("addons/flags/addon.toml"), addon_toml).unwrap();
+
+        install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap()).unwrap();
+        assert_eq!(list_installed(&Project::open(&root).unwrap()).unwrap(), vec!["base", "flags"]);
+    }
+
+    #[test]
+    fn install_fails_on_unknown_dependency() {
+        let (dir, root) = setup();
+        let addon_toml = read(dir.path(), "addons/flags/addon.toml")
+            .replace("feature = \"flags\"", "feature = \"flags\"\ndependencies = [\"missing\"]");
+        std::fs::write(dir.path().join("addons/flags/addon.toml"), addon_toml).unwrap();
+
+        let err = install_from_path(&dir.path().join("addons/flags"), &mut Project::open(&root).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+        assert!(!root.join("backend/src/flags.rs").exists());
+    }
+}
@@ -1,61 +1,78 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct EmailAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/email/email.rs.tera", "backend/src/email.rs"),
+    ("addon/email/password_reset.rs.tera", "backend/src/handlers/password_reset.rs"),
+];
+
 impl Addon for EmailAddon {
     fn name(&self) -> &str {
         "email"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/email.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/email.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_email(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_email(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling email system...".bold());
+        say!("{}", "Uninstalling email system...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/email.rs"))? {
-            println!("  {} backend/src/email.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/email.rs"))? {
+            utils::ui::removed("backend/src/email.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/handlers/password_reset.rs"),
         )? {
-            println!(
-                "  {} backend/src/handlers/password_reset.rs",
-                "delete".red()
-            );
+            utils::ui::removed("backend/src/handlers/password_reset.rs");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "email")?;
+        super::remove_mod_from_main(fs, project_root, "email")?;
 
         // Remove from handlers/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/handlers/mod.rs"),
             "pub mod password_reset;",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "email")?;
+        super::remove_feature_flag(fs, project_root, "email")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Email system uninstalled successfully.".green().bold()
         );
@@ -64,39 +81,39 @@ impl Addon for EmailAddon {
     }
 }
 
-fn install_email(project_root: &Path) -> Result<()> {
+fn install_email(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing email system...".bold());
+    say!("{}", "Installing email system...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate email service module
     let content = engine.render("addon/email/email.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/email.rs"), &content)?;
-    println!("  {} backend/src/email.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/email.rs"), &content)?;
+    utils::ui::created("backend/src/email.rs");
 
     // Generate password reset handler
     let content = engine.render("addon/email/password_reset.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/password_reset.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/handlers/password_reset.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/handlers/password_reset.rs");
 
     // Add mod email to main.rs
-    super::add_mod_to_main(project_root, "email")?;
+    super::add_mod_to_main(fs, project_root, "email")?;
 
     // Register password_reset handler
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod password_reset;",
@@ -104,6 +121,7 @@ fn install_email(project_root: &Path) -> Result<()> {
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[(
             "lettre",
@@ -113,58 +131,68 @@ fn install_email(project_root: &Path) -> Result<()> {
 
     // Add env vars
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "SMTP_HOST=smtp.example.com",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "SMTP_PORT=587",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "SMTP_USER=your_smtp_user",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "SMTP_PASS=your_smtp_password",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "FROM_EMAIL=noreply@example.com",
     )?;
 
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "SMTP_HOST=smtp.example.com",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "SMTP_PORT=587",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "SMTP_USER=",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "SMTP_PASS=",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "FROM_EMAIL=noreply@example.com",
     )?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "email", true)?;
+    super::update_feature_flag(fs, project_root, "email", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Email system installed successfully!".green().bold()
     );
-    println!("  Configure SMTP settings in backend/.env");
-    println!("  Use EmailService::new() to create an instance.");
-    println!("  Password reset handler available at /api/auth/password-reset");
+    say!("  Configure SMTP settings in backend/.env");
+    say!("  Use EmailService::new() to create an instance.");
+    say!("  Password reset handler available at /api/auth/password-reset");
 
     Ok(())
 }
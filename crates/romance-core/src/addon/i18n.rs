@@ -1,63 +1,84 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct I18nAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/i18n/i18n.rs.tera", "backend/src/i18n.rs"),
+    ("addon/i18n/en.json.tera", "backend/locales/en.json"),
+    ("addon/i18n/ru.json.tera", "backend/locales/ru.json"),
+    ("addon/i18n/i18n_frontend.ts.tera", "frontend/src/lib/i18n.ts"),
+];
+
 impl Addon for I18nAddon {
     fn name(&self) -> &str {
         "i18n"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/i18n.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/i18n.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_i18n(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_i18n(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling i18n (internationalization)...".bold());
+        say!("{}", "Uninstalling i18n (internationalization)...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/i18n.rs"))? {
-            println!("  {} backend/src/i18n.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/i18n.rs"))? {
+            utils::ui::removed("backend/src/i18n.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("frontend/src/lib/i18n.ts"))? {
-            println!("  {} frontend/src/lib/i18n.ts", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("frontend/src/lib/i18n.ts"))? {
+            utils::ui::removed("frontend/src/lib/i18n.ts");
         }
 
         // Delete locales directory
         let locales_dir = project_root.join("backend/locales");
-        if locales_dir.exists() {
-            std::fs::remove_dir_all(&locales_dir)?;
-            println!("  {} backend/locales/", "delete".red());
+        if fs.exists(&locales_dir) {
+            fs.remove_dir_all(&locales_dir)?;
+            utils::ui::removed("backend/locales/");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "i18n")?;
+        super::remove_mod_from_main(fs, project_root, "i18n")?;
 
         // Remove locale_middleware from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "locale_middleware",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "i18n")?;
+        super::remove_feature_flag(fs, project_root, "i18n")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "i18n (internationalization) uninstalled successfully."
                 .green()
@@ -68,42 +89,44 @@ impl Addon for I18nAddon {
     }
 }
 
-fn install_i18n(project_root: &Path) -> Result<()> {
+fn install_i18n(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing i18n (internationalization)...".bold());
+    say!("{}", "Installing i18n (internationalization)...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate backend i18n module
     let content = engine.render("addon/i18n/i18n.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/i18n.rs"), &content)?;
-    println!("  {} backend/src/i18n.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/i18n.rs"), &content)?;
+    utils::ui::created("backend/src/i18n.rs");
 
     // Generate English locale file
     let content = engine.render("addon/i18n/en.json.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/locales/en.json"), &content)?;
-    println!("  {} backend/locales/en.json", "create".green());
+    utils::write_file(fs, &project_root.join("backend/locales/en.json"), &content)?;
+    utils::ui::created("backend/locales/en.json");
 
     // Generate Russian locale file
     let content = engine.render("addon/i18n/ru.json.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/locales/ru.json"), &content)?;
-    println!("  {} backend/locales/ru.json", "create".green());
+    utils::write_file(fs, &project_root.join("backend/locales/ru.json"), &content)?;
+    utils::ui::created("backend/locales/ru.json");
 
     // Generate frontend i18n module
     let content = engine.render("addon/i18n/i18n_frontend.ts.tera", &ctx)?;
-    utils::write_file(&project_root.join("frontend/src/lib/i18n.ts"), &content)?;
-    println!("  {} frontend/src/lib/i18n.ts", "create".green());
+    utils::write_file(fs, &project_root.join("frontend/src/lib/i18n.ts"), &content)?;
+    utils::ui::created("frontend/src/lib/i18n.ts");
 
     // Add mod i18n to main.rs
-    super::add_mod_to_main(project_root, "i18n")?;
+    super::add_mod_to_main(fs, project_root, "i18n")?;
 
     // Inject Accept-Language middleware via ROMANCE:MIDDLEWARE marker
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:MIDDLEWARE ===",
         "        .layer(axum::middleware::from_fn(crate::i18n::locale_middleware))",
@@ -111,25 +134,26 @@ fn install_i18n(project_root: &Path) -> Result<()> {
 
     // Add serde_json dependency if not present (should already be there)
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[("serde_json", r#""1""#)],
     )?;
 
     // Update romance.toml with i18n feature
-    super::update_feature_flag(project_root, "i18n", true)?;
+    super::update_feature_flag(fs, project_root, "i18n", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "i18n (internationalization) installed successfully!"
             .green()
             .bold()
     );
-    println!("  Locale files: backend/locales/en.json, backend/locales/ru.json");
-    println!("  Backend usage: i18n::t(\"en\", \"common.success\")");
-    println!("  Frontend usage: import {{ t }} from '@/lib/i18n'");
-    println!("  The Accept-Language middleware extracts locale from request headers.");
-    println!("  Access locale in handlers via: request.extensions().get::<i18n::Locale>()");
+    say!("  Locale files: backend/locales/en.json, backend/locales/ru.json");
+    say!("  Backend usage: i18n::t(\"en\", \"common.success\")");
+    say!("  Frontend usage: import {{ t }} from '@/lib/i18n'");
+    say!("  The Accept-Language middleware extracts locale from request headers.");
+    say!("  Access locale in handlers via: request.extensions().get::<i18n::Locale>()");
 
     Ok(())
 }
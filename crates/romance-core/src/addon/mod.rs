@@ -2,6 +2,7 @@ pub mod api_keys;
 pub mod audit_log;
 pub mod cache;
 pub mod dashboard;
+pub mod declarative;
 pub mod email;
 pub mod i18n;
 pub mod multitenancy;
@@ -15,19 +16,29 @@ pub mod tasks;
 pub mod validation;
 pub mod websocket;
 
+use crate::error::Failure;
+use crate::fs::ProjectFs;
+use crate::generator::context::markers;
+use crate::manifest::{FileCategory, RenderedFile};
+use crate::project::Project;
+use crate::say;
 use anyhow::Result;
 use std::path::Path;
 
 /// Trait that all addons implement to provide a consistent installation interface.
+///
+/// The checks only look at files under the project root in `fs`, so they can
+/// run before the project is opened; everything that writes or renders gets
+/// the opened [`Project`].
 pub trait Addon {
     fn name(&self) -> &str;
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()>;
-    fn is_already_installed(&self, project_root: &Path) -> bool;
-    fn install(&self, project_root: &Path) -> Result<()>;
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()>;
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool;
+    fn install(&self, project: &Project) -> Result<()>;
 
     /// Uninstall the addon. Default implementation returns an error.
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
-        let _ = project_root;
+    fn uninstall(&self, project: &Project) -> Result<()> {
+        let _ = project;
         anyhow::bail!("Uninstall not yet supported for '{}'", self.name())
     }
 
@@ -35,49 +46,113 @@ pub trait Addon {
     fn dependencies(&self) -> Vec<&str> {
         vec![]
     }
+
+    /// Files the addon renders from templates and owns outright, rendered
+    /// against the project as it is now. `romance update` re-renders these;
+    /// migrations and files shared with other addons are left out.
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        let _ = project;
+        Ok(vec![])
+    }
+}
+
+/// Names of the addons compiled into romance.
+pub const BUILTIN_ADDONS: &[&str] = &[
+    "validation",
+    "soft-delete",
+    "audit-log",
+    "storage",
+    "search",
+    "oauth",
+    "security",
+    "observability",
+    "dashboard",
+    "email",
+    "i18n",
+    "cache",
+    "tasks",
+    "websocket",
+    "api-keys",
+    "multitenancy",
+];
+
+/// Whether `name` refers to a compiled-in addon (or `auth`, which is a generator).
+pub fn is_builtin(name: &str) -> bool {
+    name == "auth" || BUILTIN_ADDONS.contains(&name)
+}
+
+/// The compiled-in addon called `name`.
+pub fn builtin(name: &str) -> Option<Box<dyn Addon>> {
+    let addon: Box<dyn Addon> = match name {
+        "validation" => Box::new(validation::ValidationAddon),
+        "soft-delete" => Box::new(soft_delete::SoftDeleteAddon),
+        "audit-log" => Box::new(audit_log::AuditLogAddon),
+        "storage" => Box::new(storage::StorageAddon),
+        "search" => Box::new(search::SearchAddon),
+        "oauth" => Box::new(oauth::OauthAddon {
+            provider: String::new(),
+        }),
+        "security" => Box::new(security::SecurityAddon),
+        "observability" => Box::new(observability::ObservabilityAddon),
+        "dashboard" => Box::new(dashboard::DashboardAddon),
+        "email" => Box::new(email::EmailAddon),
+        "i18n" => Box::new(i18n::I18nAddon),
+        "cache" => Box::new(cache::CacheAddon),
+        "tasks" => Box::new(tasks::TasksAddon),
+        "websocket" => Box::new(websocket::WebsocketAddon),
+        "api-keys" => Box::new(api_keys::ApiKeysAddon),
+        "multitenancy" => Box::new(multitenancy::MultitenancyAddon),
+        _ => return None,
+    };
+    Some(addon)
 }
 
 /// Resolve an addon name to its concrete instance and run it.
 /// Used for auto-installing dependencies.
-fn resolve_and_install_dependency(name: &str, project_root: &Path) -> Result<()> {
+fn resolve_and_install_dependency(name: &str, project: &mut Project) -> Result<()> {
     use colored::Colorize;
 
     match name {
         "auth" => {
             // Auth is not an addon, it's a generator. Just check it exists.
-            if !project_root.join("backend/src/auth.rs").exists() {
-                anyhow::bail!(
+            if !project.features().has_auth {
+                return Err(Failure::Precondition.error(format!(
                     "Addon requires auth. Run {} first.",
                     "romance generate auth".bold()
-                );
+                )));
             }
             Ok(())
         }
-        "validation" => run_addon(&validation::ValidationAddon, project_root),
-        "soft-delete" => run_addon(&soft_delete::SoftDeleteAddon, project_root),
-        "security" => run_addon(&security::SecurityAddon, project_root),
-        "observability" => run_addon(&observability::ObservabilityAddon, project_root),
-        "storage" => run_addon(&storage::StorageAddon, project_root),
-        "search" => run_addon(&search::SearchAddon, project_root),
-        "cache" => run_addon(&cache::CacheAddon, project_root),
-        "email" => run_addon(&email::EmailAddon, project_root),
-        "tasks" => run_addon(&tasks::TasksAddon, project_root),
-        "websocket" => run_addon(&websocket::WebsocketAddon, project_root),
-        "i18n" => run_addon(&i18n::I18nAddon, project_root),
-        "dashboard" => run_addon(&dashboard::DashboardAddon, project_root),
-        "audit-log" => run_addon(&audit_log::AuditLogAddon, project_root),
-        "api-keys" => run_addon(&api_keys::ApiKeysAddon, project_root),
-        "multitenancy" => run_addon(&multitenancy::MultitenancyAddon, project_root),
+        "validation" => run_addon(&validation::ValidationAddon, project),
+        "soft-delete" => run_addon(&soft_delete::SoftDeleteAddon, project),
+        "security" => run_addon(&security::SecurityAddon, project),
+        "observability" => run_addon(&observability::ObservabilityAddon, project),
+        "storage" => run_addon(&storage::StorageAddon, project),
+        "search" => run_addon(&search::SearchAddon, project),
+        "cache" => run_addon(&cache::CacheAddon, project),
+        "email" => run_addon(&email::EmailAddon, project),
+        "tasks" => run_addon(&tasks::TasksAddon, project),
+        "websocket" => run_addon(&websocket::WebsocketAddon, project),
+        "i18n" => run_addon(&i18n::I18nAddon, project),
+        "dashboard" => run_addon(&dashboard::DashboardAddon, project),
+        "audit-log" => run_addon(&audit_log::AuditLogAddon, project),
+        "api-keys" => run_addon(&api_keys::ApiKeysAddon, project),
+        "multitenancy" => run_addon(&multitenancy::MultitenancyAddon, project),
+        // Declarative addons must already be installed from their directory
+        _ if declarative::list_installed(project)?.iter().any(|n| n == name) => Ok(()),
         _ => anyhow::bail!("Unknown addon dependency: '{}'", name),
     }
 }
 
 /// Run an addon: check prerequisites, skip if already installed, then install.
-pub fn run_addon(addon: &dyn Addon, project_root: &Path) -> Result<()> {
-    addon.check_prerequisites(project_root)?;
-
-    if addon.is_already_installed(project_root) {
-        println!("'{}' is already installed, skipping.", addon.name());
+/// `project` is reloaded afterwards, as the addon may have turned on features.
+pub fn run_addon(addon: &dyn Addon, project: &mut Project) -> Result<()> {
+    addon
+        .check_prerequisites(project.fs(), project.root())
+        .map_err(|e| Failure::Precondition.tag(e))?;
+
+    if addon.is_already_installed(project.fs(), project.root()) {
+        say!("'{}' is already installed, skipping.", addon.name());
         return Ok(());
     }
 
@@ -86,31 +161,34 @@ pub fn run_addon(addon: &dyn Addon, project_root: &Path) -> Result<()> {
     if !deps.is_empty() {
         use colored::Colorize;
         for dep in &deps {
-            println!("{}", format!("Checking dependency: {}...", dep).dimmed());
-            resolve_and_install_dependency(dep, project_root)?;
+            say!("{}", format!("Checking dependency: {}...", dep).dimmed());
+            resolve_and_install_dependency(dep, project)?;
         }
-        println!();
+        say!();
     }
 
-    addon.install(project_root)?;
+    addon.install(project)?;
+    project.reload()?;
+    crate::manifest::track_generated(project, &addon.rendered_files(project)?)?;
 
     // Regenerate AI context
-    crate::ai_context::regenerate(project_root)?;
+    crate::ai_context::regenerate(project)?;
 
     Ok(())
 }
 
 /// Uninstall an addon: check if installed, then uninstall.
-pub fn run_uninstall(addon: &dyn Addon, project_root: &Path) -> Result<()> {
-    if !addon.is_already_installed(project_root) {
-        println!("'{}' is not installed, nothing to remove.", addon.name());
+pub fn run_uninstall(addon: &dyn Addon, project: &mut Project) -> Result<()> {
+    if !addon.is_already_installed(project.fs(), project.root()) {
+        say!("'{}' is not installed, nothing to remove.", addon.name());
         return Ok(());
     }
 
-    addon.uninstall(project_root)?;
+    addon.uninstall(project)?;
+    project.reload()?;
 
     // Regenerate AI context
-    crate::ai_context::regenerate(project_root).ok();
+    crate::ai_context::regenerate(project).ok();
 
     Ok(())
 }
@@ -119,42 +197,67 @@ pub fn run_uninstall(addon: &dyn Addon, project_root: &Path) -> Result<()> {
 // Shared helper functions for addon implementations
 // =========================================================================
 
+/// Render an addon's `(template, output)` pairs with `ctx`, for
+/// [`Addon::rendered_files`].
+pub fn render_files(
+    project: &Project,
+    files: &[(&str, &str)],
+    ctx: &tera::Context,
+) -> Result<Vec<RenderedFile>> {
+    let engine = crate::template::TemplateEngine::for_project(project)?;
+    files
+        .iter()
+        .map(|(template, output)| {
+            Ok(RenderedFile {
+                output: output.to_string(),
+                template: Some(template.to_string()),
+                category: FileCategory::Addon,
+                entity_name: None,
+                content: engine.render(template, ctx)?,
+            })
+        })
+        .collect()
+}
+
 /// Check that the project root contains a romance.toml file.
-pub fn check_romance_project(project_root: &Path) -> Result<()> {
-    if !project_root.join("romance.toml").exists() {
-        anyhow::bail!("Not a Romance project (romance.toml not found)");
+pub fn check_romance_project(fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+    if !fs.exists(&project_root.join("romance.toml")) {
+        return Err(Failure::NotAProject.error(
+            "Not a Romance project (romance.toml not found)",
+        ));
     }
     Ok(())
 }
 
 /// Check that auth has been generated (backend/src/auth.rs exists).
-pub fn check_auth_exists(project_root: &Path) -> Result<()> {
-    if !project_root.join("backend/src/auth.rs").exists() {
-        anyhow::bail!("Auth must be generated first. Run: romance generate auth");
+pub fn check_auth_exists(fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+    if !fs.exists(&project_root.join("backend/src/auth.rs")) {
+        return Err(Failure::Precondition
+            .error("Auth must be generated first. Run: romance generate auth"));
     }
     Ok(())
 }
 
 /// Add a `mod <mod_name>;` declaration to `backend/src/main.rs`.
 ///
-/// Uses `insert_at_marker()` with the `// === ROMANCE:MAIN_MODS ===` marker
+/// Uses `inject::insert()` with the `// === ROMANCE:MAIN_MODS ===` marker
 /// if present, otherwise falls back to `str::replace("mod errors;", ...)`.
-pub fn add_mod_to_main(project_root: &Path, mod_name: &str) -> Result<()> {
+pub fn add_mod_to_main(fs: &dyn ProjectFs, project_root: &Path, mod_name: &str) -> Result<()> {
     let main_path = project_root.join("backend/src/main.rs");
-    let main_content = std::fs::read_to_string(&main_path)?;
+    let main_content = fs.read_to_string(&main_path)?;
     let mod_line = format!("mod {};", mod_name);
 
     if main_content.contains(&mod_line) {
         return Ok(());
     }
 
-    let marker = "// === ROMANCE:MAIN_MODS ===";
+    let marker = markers::MAIN_MODS;
     if main_content.contains(marker) {
-        crate::utils::insert_at_marker(&main_path, marker, &mod_line)?;
+        crate::inject::insert(fs, &main_path, marker, &mod_line)?;
     } else {
         // Fallback for projects scaffolded before the marker existed
         let new_content = main_content.replace("mod errors;", &format!("mod errors;\n{}", mod_line));
-        std::fs::write(&main_path, new_content)?;
+        fs.write(&main_path, &new_content)?;
     }
 
     Ok(())
@@ -164,9 +267,9 @@ pub fn add_mod_to_main(project_root: &Path, mod_name: &str) -> Result<()> {
 ///
 /// Uses `insert_at_marker()` with the `# === ROMANCE:DEPENDENCIES ===` marker
 /// if present, otherwise falls back to appending at end of file.
-pub fn add_cargo_dependency(project_root: &Path, dep_line: &str) -> Result<()> {
+pub fn add_cargo_dependency(fs: &dyn ProjectFs, project_root: &Path, dep_line: &str) -> Result<()> {
     let cargo_path = project_root.join("backend/Cargo.toml");
-    let content = std::fs::read_to_string(&cargo_path)?;
+    let content = fs.read_to_string(&cargo_path)?;
 
     // Extract dependency name (everything before ' =')
     let dep_name = dep_line.split('=').next().unwrap_or("").trim();
@@ -176,50 +279,77 @@ pub fn add_cargo_dependency(project_root: &Path, dep_line: &str) -> Result<()> {
 
     let marker = "# === ROMANCE:DEPENDENCIES ===";
     if content.contains(marker) {
-        crate::utils::insert_at_marker(&cargo_path, marker, dep_line)?;
+        crate::utils::insert_at_marker(fs, &cargo_path, marker, dep_line)?;
     } else {
         // Fallback: append to end of file
         let new_content = format!("{}\n{}\n", content.trim_end(), dep_line);
-        std::fs::write(&cargo_path, new_content)?;
+        fs.write(&cargo_path, &new_content)?;
+    }
+
+    Ok(())
+}
+
+/// Add a package to `frontend/package.json` `dependencies`.
+///
+/// Edits the file textually so key order and formatting are preserved.
+pub fn add_npm_dependency(fs: &dyn ProjectFs, project_root: &Path, name: &str, version: &str) -> Result<()> {
+    let package_path = project_root.join("frontend/package.json");
+    let content = fs.read_to_string(&package_path)?;
+    if content.contains(&format!("\"{}\":", name)) {
+        return Ok(());
     }
 
+    let header = "\"dependencies\": {";
+    let Some(pos) = content.find(header) else {
+        anyhow::bail!("No \"dependencies\" section found in frontend/package.json");
+    };
+    let after = pos + header.len();
+    let entry = if content[after..].trim_start().starts_with('}') {
+        // Empty section: `"dependencies": {}`
+        format!("\n    \"{}\": \"{}\"\n  ", name, version)
+    } else {
+        format!("\n    \"{}\": \"{}\",", name, version)
+    };
+    let mut new_content = content.clone();
+    new_content.insert_str(after, &entry);
+    fs.write(&package_path, &new_content)?;
     Ok(())
 }
 
 /// Update `romance.toml` to set a feature flag under the `[features]` section.
 ///
-/// If the `[features]` section doesn't exist, it creates one.
-pub fn update_feature_flag(project_root: &Path, feature: &str, value: bool) -> Result<()> {
+/// If the `[features]` section doesn't exist, it creates one. The manifest
+/// keeps an unedited `romance.toml` unmodified for `romance status`.
+pub fn update_feature_flag(fs: &dyn ProjectFs, project_root: &Path, feature: &str, value: bool) -> Result<()> {
     let config_path = project_root.join("romance.toml");
-    let content = std::fs::read_to_string(&config_path)?;
+    let content = fs.read_to_string(&config_path)?;
     let line = format!("{} = {}", feature, value);
 
     if content.contains(&line) {
         return Ok(());
     }
 
-    if content.contains("[features]") {
-        if !content.contains(feature) {
-            let new_content = content.replace("[features]", &format!("[features]\n{}", line));
-            std::fs::write(&config_path, new_content)?;
+    let new_content = if content.contains("[features]") {
+        if content.contains(feature) {
+            return Ok(());
         }
+        content.replace("[features]", &format!("[features]\n{}", line))
     } else {
-        let new_content = format!("{}\n[features]\n{}\n", content.trim_end(), line);
-        std::fs::write(&config_path, new_content)?;
-    }
-
-    Ok(())
+        format!("{}\n[features]\n{}\n", content.trim_end(), line)
+    };
+    fs.write(&config_path, &new_content)?;
+    crate::manifest::refresh(fs, project_root, "romance.toml", &content, &new_content)
 }
 
 /// Append an environment variable line to a `.env` file if not already present.
-pub fn append_env_var(path: &Path, line: &str) -> Result<()> {
-    crate::generator::auth::append_env_var(path, line)
+pub fn append_env_var(fs: &dyn ProjectFs, path: &Path, line: &str) -> Result<()> {
+    crate::generator::auth::append_env_var(fs, path, line)
 }
 
 /// Remove a file if it exists. Returns true if file was removed.
-pub fn remove_file_if_exists(path: &Path) -> Result<bool> {
-    if path.exists() {
-        std::fs::remove_file(path)?;
+pub fn remove_file_if_exists(fs: &dyn ProjectFs, path: &Path) -> Result<bool> {
+    if fs.exists(path) {
+        fs.remove_file(path)?;
         Ok(true)
     } else {
         Ok(false)
@@ -227,14 +357,24 @@ pub fn remove_file_if_exists(path: &Path) -> Result<bool> {
 }
 
 /// Remove a line containing `needle` from a file.
-pub fn remove_line_from_file(path: &Path, needle: &str) -> Result<()> {
-    if !path.exists() {
+pub fn remove_line_from_file(fs: &dyn ProjectFs, path: &Path, needle: &str) -> Result<()> {
+    remove_lines(fs, path, |line| line.contains(needle))
+}
+
+/// Remove the `key=...` assignment from an env file, leaving other variables
+/// whose names merely end with `key` alone.
+pub fn remove_env_var(fs: &dyn ProjectFs, path: &Path, key: &str) -> Result<()> {
+    remove_lines(fs, path, |line| line.split('=').next() == Some(key))
+}
+
+fn remove_lines(fs: &dyn ProjectFs, path: &Path, matches: impl Fn(&str) -> bool) -> Result<()> {
+    if !fs.exists(path) {
         return Ok(());
     }
-    let content = std::fs::read_to_string(path)?;
+    let content = fs.read_to_string(path)?;
     let new_content: String = content
         .lines()
-        .filter(|line| !line.contains(needle))
+        .filter(|line| !matches(line))
         .collect::<Vec<_>>()
         .join("\n");
     // Preserve trailing newline
@@ -243,33 +383,34 @@ pub fn remove_line_from_file(path: &Path, needle: &str) -> Result<()> {
     } else {
         new_content
     };
-    std::fs::write(path, new_content)?;
+    fs.write(path, &new_content)?;
     Ok(())
 }
 
 /// Remove a `mod <name>;` declaration from `backend/src/main.rs`.
-pub fn remove_mod_from_main(project_root: &Path, mod_name: &str) -> Result<()> {
+pub fn remove_mod_from_main(fs: &dyn ProjectFs, project_root: &Path, mod_name: &str) -> Result<()> {
     let main_path = project_root.join("backend/src/main.rs");
-    remove_line_from_file(&main_path, &format!("mod {};", mod_name))
+    crate::inject::remove(fs, &main_path, markers::MAIN_MODS, &format!("mod {};", mod_name))?;
+    Ok(())
 }
 
 /// Remove a feature flag from `romance.toml`'s `[features]` section.
-pub fn remove_feature_flag(project_root: &Path, feature: &str) -> Result<()> {
+pub fn remove_feature_flag(fs: &dyn ProjectFs, project_root: &Path, feature: &str) -> Result<()> {
     let config_path = project_root.join("romance.toml");
     let line = format!("{} = true", feature);
-    remove_line_from_file(&config_path, &line)?;
+    remove_line_from_file(fs, &config_path, &line)?;
     // Also remove "feature = false" in case
     let line_false = format!("{} = false", feature);
-    remove_line_from_file(&config_path, &line_false)
+    remove_line_from_file(fs, &config_path, &line_false)
 }
 
 /// Remove a TOML section (e.g., `[security]`) and all its contents until the next section.
-pub fn remove_toml_section(project_root: &Path, section_name: &str) -> Result<()> {
+pub fn remove_toml_section(fs: &dyn ProjectFs, project_root: &Path, section_name: &str) -> Result<()> {
     let config_path = project_root.join("romance.toml");
-    if !config_path.exists() {
+    if !fs.exists(&config_path) {
         return Ok(());
     }
-    let content = std::fs::read_to_string(&config_path)?;
+    let content = fs.read_to_string(&config_path)?;
     let section_header = format!("[{}]", section_name);
     if !content.contains(&section_header) {
         return Ok(());
@@ -289,13 +430,14 @@ pub fn remove_toml_section(project_root: &Path, section_name: &str) -> Result<()
         }
     }
     let new_content = format!("{}\n", result_lines.join("\n").trim_end());
-    std::fs::write(&config_path, new_content)?;
+    fs.write(&config_path, &new_content)?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
 
     fn write_romance_toml(dir: &std::path::Path) {
         std::fs::write(
@@ -414,7 +556,7 @@ mod tests {
     #[test]
     fn security_prerequisites_fail_without_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
-        let result = security::SecurityAddon.check_prerequisites(dir.path());
+        let result = security::SecurityAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
     }
 
@@ -422,14 +564,14 @@ mod tests {
     fn security_prerequisites_pass_with_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = security::SecurityAddon.check_prerequisites(dir.path());
+        let result = security::SecurityAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
     #[test]
     fn validation_prerequisites_fail_without_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
-        let result = validation::ValidationAddon.check_prerequisites(dir.path());
+        let result = validation::ValidationAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
     }
 
@@ -437,14 +579,14 @@ mod tests {
     fn validation_prerequisites_pass_with_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = validation::ValidationAddon.check_prerequisites(dir.path());
+        let result = validation::ValidationAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
     #[test]
     fn soft_delete_prerequisites_fail_without_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
-        let result = soft_delete::SoftDeleteAddon.check_prerequisites(dir.path());
+        let result = soft_delete::SoftDeleteAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
     }
 
@@ -452,14 +594,14 @@ mod tests {
     fn soft_delete_prerequisites_pass_with_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = soft_delete::SoftDeleteAddon.check_prerequisites(dir.path());
+        let result = soft_delete::SoftDeleteAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
     #[test]
     fn api_keys_prerequisites_fail_without_romance_toml() {
         let dir = tempfile::tempdir().unwrap();
-        let result = api_keys::ApiKeysAddon.check_prerequisites(dir.path());
+        let result = api_keys::ApiKeysAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
     }
 
@@ -467,7 +609,7 @@ mod tests {
     fn api_keys_prerequisites_fail_without_auth() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = api_keys::ApiKeysAddon.check_prerequisites(dir.path());
+        let result = api_keys::ApiKeysAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("Auth must be generated first"));
@@ -479,7 +621,7 @@ mod tests {
         write_romance_toml(dir.path());
         std::fs::create_dir_all(dir.path().join("backend/src")).unwrap();
         std::fs::write(dir.path().join("backend/src/auth.rs"), "").unwrap();
-        let result = api_keys::ApiKeysAddon.check_prerequisites(dir.path());
+        let result = api_keys::ApiKeysAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
@@ -487,7 +629,7 @@ mod tests {
     fn audit_log_prerequisites_fail_without_auth() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = audit_log::AuditLogAddon.check_prerequisites(dir.path());
+        let result = audit_log::AuditLogAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("Auth must be generated first"));
@@ -499,7 +641,7 @@ mod tests {
         write_romance_toml(dir.path());
         std::fs::create_dir_all(dir.path().join("backend/src")).unwrap();
         std::fs::write(dir.path().join("backend/src/auth.rs"), "").unwrap();
-        let result = audit_log::AuditLogAddon.check_prerequisites(dir.path());
+        let result = audit_log::AuditLogAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
@@ -507,7 +649,7 @@ mod tests {
     fn multitenancy_prerequisites_fail_without_auth() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        let result = multitenancy::MultitenancyAddon.check_prerequisites(dir.path());
+        let result = multitenancy::MultitenancyAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("Auth must be generated first"));
@@ -519,7 +661,7 @@ mod tests {
         write_romance_toml(dir.path());
         std::fs::create_dir_all(dir.path().join("backend/src")).unwrap();
         std::fs::write(dir.path().join("backend/src/auth.rs"), "").unwrap();
-        let result = multitenancy::MultitenancyAddon.check_prerequisites(dir.path());
+        let result = multitenancy::MultitenancyAddon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
@@ -530,7 +672,7 @@ mod tests {
         let addon = oauth::OauthAddon {
             provider: "google".to_string(),
         };
-        let result = addon.check_prerequisites(dir.path());
+        let result = addon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("Auth must be generated first"));
@@ -545,7 +687,7 @@ mod tests {
         let addon = oauth::OauthAddon {
             provider: "github".to_string(),
         };
-        let result = addon.check_prerequisites(dir.path());
+        let result = addon.check_prerequisites(&DiskFs, dir.path());
         assert!(result.is_ok());
     }
 
@@ -556,7 +698,7 @@ mod tests {
     #[test]
     fn security_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!security::SecurityAddon.is_already_installed(dir.path()));
+        assert!(!security::SecurityAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -565,13 +707,13 @@ mod tests {
         let middleware_dir = dir.path().join("backend/src/middleware");
         std::fs::create_dir_all(&middleware_dir).unwrap();
         std::fs::write(middleware_dir.join("security_headers.rs"), "").unwrap();
-        assert!(security::SecurityAddon.is_already_installed(dir.path()));
+        assert!(security::SecurityAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn validation_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!validation::ValidationAddon.is_already_installed(dir.path()));
+        assert!(!validation::ValidationAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -580,13 +722,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("validation.rs"), "").unwrap();
-        assert!(validation::ValidationAddon.is_already_installed(dir.path()));
+        assert!(validation::ValidationAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn soft_delete_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!soft_delete::SoftDeleteAddon.is_already_installed(dir.path()));
+        assert!(!soft_delete::SoftDeleteAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -595,13 +737,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("soft_delete.rs"), "").unwrap();
-        assert!(soft_delete::SoftDeleteAddon.is_already_installed(dir.path()));
+        assert!(soft_delete::SoftDeleteAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn observability_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!observability::ObservabilityAddon.is_already_installed(dir.path()));
+        assert!(!observability::ObservabilityAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -610,13 +752,13 @@ mod tests {
         let middleware_dir = dir.path().join("backend/src/middleware");
         std::fs::create_dir_all(&middleware_dir).unwrap();
         std::fs::write(middleware_dir.join("request_id.rs"), "").unwrap();
-        assert!(observability::ObservabilityAddon.is_already_installed(dir.path()));
+        assert!(observability::ObservabilityAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn search_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!search::SearchAddon.is_already_installed(dir.path()));
+        assert!(!search::SearchAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -625,13 +767,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("search.rs"), "").unwrap();
-        assert!(search::SearchAddon.is_already_installed(dir.path()));
+        assert!(search::SearchAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn email_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!email::EmailAddon.is_already_installed(dir.path()));
+        assert!(!email::EmailAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -640,13 +782,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("email.rs"), "").unwrap();
-        assert!(email::EmailAddon.is_already_installed(dir.path()));
+        assert!(email::EmailAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn cache_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!cache::CacheAddon.is_already_installed(dir.path()));
+        assert!(!cache::CacheAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -655,13 +797,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("cache.rs"), "").unwrap();
-        assert!(cache::CacheAddon.is_already_installed(dir.path()));
+        assert!(cache::CacheAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn dashboard_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!dashboard::DashboardAddon.is_already_installed(dir.path()));
+        assert!(!dashboard::DashboardAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -670,13 +812,13 @@ mod tests {
         let dev_dir = dir.path().join("frontend/src/features/dev");
         std::fs::create_dir_all(&dev_dir).unwrap();
         std::fs::write(dev_dir.join("DevDashboard.tsx"), "").unwrap();
-        assert!(dashboard::DashboardAddon.is_already_installed(dir.path()));
+        assert!(dashboard::DashboardAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn storage_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!storage::StorageAddon.is_already_installed(dir.path()));
+        assert!(!storage::StorageAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -685,13 +827,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("storage.rs"), "").unwrap();
-        assert!(storage::StorageAddon.is_already_installed(dir.path()));
+        assert!(storage::StorageAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn websocket_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!websocket::WebsocketAddon.is_already_installed(dir.path()));
+        assert!(!websocket::WebsocketAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -700,13 +842,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("ws.rs"), "").unwrap();
-        assert!(websocket::WebsocketAddon.is_already_installed(dir.path()));
+        assert!(websocket::WebsocketAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn i18n_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!i18n::I18nAddon.is_already_installed(dir.path()));
+        assert!(!i18n::I18nAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -715,13 +857,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("i18n.rs"), "").unwrap();
-        assert!(i18n::I18nAddon.is_already_installed(dir.path()));
+        assert!(i18n::I18nAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn tasks_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!tasks::TasksAddon.is_already_installed(dir.path()));
+        assert!(!tasks::TasksAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -730,13 +872,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("tasks.rs"), "").unwrap();
-        assert!(tasks::TasksAddon.is_already_installed(dir.path()));
+        assert!(tasks::TasksAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn api_keys_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!api_keys::ApiKeysAddon.is_already_installed(dir.path()));
+        assert!(!api_keys::ApiKeysAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -745,13 +887,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("api_keys.rs"), "").unwrap();
-        assert!(api_keys::ApiKeysAddon.is_already_installed(dir.path()));
+        assert!(api_keys::ApiKeysAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn audit_log_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!audit_log::AuditLogAddon.is_already_installed(dir.path()));
+        assert!(!audit_log::AuditLogAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -760,13 +902,13 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("audit.rs"), "").unwrap();
-        assert!(audit_log::AuditLogAddon.is_already_installed(dir.path()));
+        assert!(audit_log::AuditLogAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
     fn multitenancy_not_installed_in_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(!multitenancy::MultitenancyAddon.is_already_installed(dir.path()));
+        assert!(!multitenancy::MultitenancyAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -775,7 +917,7 @@ mod tests {
         let backend_src = dir.path().join("backend/src");
         std::fs::create_dir_all(&backend_src).unwrap();
         std::fs::write(backend_src.join("tenant.rs"), "").unwrap();
-        assert!(multitenancy::MultitenancyAddon.is_already_installed(dir.path()));
+        assert!(multitenancy::MultitenancyAddon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -784,7 +926,7 @@ mod tests {
         let addon = oauth::OauthAddon {
             provider: "google".to_string(),
         };
-        assert!(!addon.is_already_installed(dir.path()));
+        assert!(!addon.is_already_installed(&DiskFs, dir.path()));
     }
 
     #[test]
@@ -796,7 +938,7 @@ mod tests {
         let addon = oauth::OauthAddon {
             provider: "google".to_string(),
         };
-        assert!(addon.is_already_installed(dir.path()));
+        assert!(addon.is_already_installed(&DiskFs, dir.path()));
     }
 
     // =========================================================================
@@ -808,7 +950,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("test.rs");
         std::fs::write(&path, "content").unwrap();
-        assert!(remove_file_if_exists(&path).unwrap());
+        assert!(remove_file_if_exists(&DiskFs, &path).unwrap());
         assert!(!path.exists());
     }
 
@@ -816,7 +958,7 @@ mod tests {
     fn remove_file_if_exists_returns_false_when_missing() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("nonexistent.rs");
-        assert!(!remove_file_if_exists(&path).unwrap());
+        assert!(!remove_file_if_exists(&DiskFs, &path).unwrap());
     }
 
     #[test]
@@ -824,7 +966,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("test.rs");
         std::fs::write(&path, "mod a;\nmod b;\nmod c;\n").unwrap();
-        remove_line_from_file(&path, "mod b;").unwrap();
+        remove_line_from_file(&DiskFs, &path, "mod b;").unwrap();
         let content = std::fs::read_to_string(&path).unwrap();
         assert!(!content.contains("mod b;"));
         assert!(content.contains("mod a;"));
@@ -836,12 +978,21 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("test.rs");
         std::fs::write(&path, "mod a;\nmod c;\n").unwrap();
-        remove_line_from_file(&path, "mod b;").unwrap();
+        remove_line_from_file(&DiskFs, &path, "mod b;").unwrap();
         let content = std::fs::read_to_string(&path).unwrap();
         assert!(content.contains("mod a;"));
         assert!(content.contains("mod c;"));
     }
 
+    #[test]
+    fn remove_env_var_matches_the_whole_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        std::fs::write(&path, "API_KEY=a\nOTHER_API_KEY=b\nAPI_KEY_ID=c\n").unwrap();
+        remove_env_var(&DiskFs, &path, "API_KEY").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "OTHER_API_KEY=b\nAPI_KEY_ID=c\n");
+    }
+
     #[test]
     fn remove_mod_from_main_works() {
         let dir = tempfile::tempdir().unwrap();
@@ -851,7 +1002,7 @@ mod tests {
             "mod errors;\nmod validation;\n// === ROMANCE:MAIN_MODS ===\nmod handlers;\n",
         )
         .unwrap();
-        remove_mod_from_main(dir.path(), "validation").unwrap();
+        remove_mod_from_main(&DiskFs, dir.path(), "validation").unwrap();
         let content =
             std::fs::read_to_string(dir.path().join("backend/src/main.rs")).unwrap();
         assert!(!content.contains("mod validation;"));
@@ -866,7 +1017,7 @@ mod tests {
             "[project]\nname = \"test\"\n[features]\nvalidation = true\ncache = true\n",
         )
         .unwrap();
-        remove_feature_flag(dir.path(), "validation").unwrap();
+        remove_feature_flag(&DiskFs, dir.path(), "validation").unwrap();
         let content = std::fs::read_to_string(dir.path().join("romance.toml")).unwrap();
         assert!(!content.contains("validation"));
         assert!(content.contains("cache = true"));
@@ -880,7 +1031,7 @@ mod tests {
             "[project]\nname = \"test\"\n\n[security]\nrate_limit = 60\ncors = true\n\n[features]\nauth = true\n",
         )
         .unwrap();
-        remove_toml_section(dir.path(), "security").unwrap();
+        remove_toml_section(&DiskFs, dir.path(), "security").unwrap();
         let content = std::fs::read_to_string(dir.path().join("romance.toml")).unwrap();
         assert!(!content.contains("[security]"));
         assert!(!content.contains("rate_limit"));
@@ -937,20 +1088,20 @@ mod tests {
     #[test]
     fn check_romance_project_fails_without_toml() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(check_romance_project(dir.path()).is_err());
+        assert!(check_romance_project(&DiskFs, dir.path()).is_err());
     }
 
     #[test]
     fn check_romance_project_passes_with_toml() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        assert!(check_romance_project(dir.path()).is_ok());
+        assert!(check_romance_project(&DiskFs, dir.path()).is_ok());
     }
 
     #[test]
     fn check_auth_exists_fails_without_auth() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(check_auth_exists(dir.path()).is_err());
+        assert!(check_auth_exists(&DiskFs, dir.path()).is_err());
     }
 
     #[test]
@@ -958,7 +1109,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         std::fs::create_dir_all(dir.path().join("backend/src")).unwrap();
         std::fs::write(dir.path().join("backend/src/auth.rs"), "").unwrap();
-        assert!(check_auth_exists(dir.path()).is_ok());
+        assert!(check_auth_exists(&DiskFs, dir.path()).is_ok());
     }
 
     #[test]
@@ -970,7 +1121,7 @@ mod tests {
             "mod errors;\n// === ROMANCE:MAIN_MODS ===\nmod handlers;\n",
         )
         .unwrap();
-        add_mod_to_main(dir.path(), "storage").unwrap();
+        add_mod_to_main(&DiskFs, dir.path(), "storage").unwrap();
         let content = std::fs::read_to_string(dir.path().join("backend/src/main.rs")).unwrap();
         assert!(content.contains("mod storage;"));
         assert!(content.contains("// === ROMANCE:MAIN_MODS ==="));
@@ -985,7 +1136,7 @@ mod tests {
             "mod errors;\nmod handlers;\n",
         )
         .unwrap();
-        add_mod_to_main(dir.path(), "storage").unwrap();
+        add_mod_to_main(&DiskFs, dir.path(), "storage").unwrap();
         let content = std::fs::read_to_string(dir.path().join("backend/src/main.rs")).unwrap();
         assert!(content.contains("mod storage;"));
     }
@@ -999,8 +1150,8 @@ mod tests {
             "mod errors;\n// === ROMANCE:MAIN_MODS ===\nmod handlers;\n",
         )
         .unwrap();
-        add_mod_to_main(dir.path(), "storage").unwrap();
-        add_mod_to_main(dir.path(), "storage").unwrap();
+        add_mod_to_main(&DiskFs, dir.path(), "storage").unwrap();
+        add_mod_to_main(&DiskFs, dir.path(), "storage").unwrap();
         let content = std::fs::read_to_string(dir.path().join("backend/src/main.rs")).unwrap();
         assert_eq!(content.matches("mod storage;").count(), 1);
     }
@@ -1009,7 +1160,7 @@ mod tests {
     fn update_feature_flag_creates_section() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        update_feature_flag(dir.path(), "cache", true).unwrap();
+        update_feature_flag(&DiskFs, dir.path(), "cache", true).unwrap();
         let content = std::fs::read_to_string(dir.path().join("romance.toml")).unwrap();
         assert!(content.contains("[features]"));
         assert!(content.contains("cache = true"));
@@ -1023,7 +1174,7 @@ mod tests {
             "[project]\nname = \"test\"\n[features]\nauth = true\n",
         )
         .unwrap();
-        update_feature_flag(dir.path(), "cache", true).unwrap();
+        update_feature_flag(&DiskFs, dir.path(), "cache", true).unwrap();
         let content = std::fs::read_to_string(dir.path().join("romance.toml")).unwrap();
         assert!(content.contains("cache = true"));
         assert!(content.contains("auth = true"));
@@ -1033,8 +1184,8 @@ mod tests {
     fn update_feature_flag_idempotent() {
         let dir = tempfile::tempdir().unwrap();
         write_romance_toml(dir.path());
-        update_feature_flag(dir.path(), "cache", true).unwrap();
-        update_feature_flag(dir.path(), "cache", true).unwrap();
+        update_feature_flag(&DiskFs, dir.path(), "cache", true).unwrap();
+        update_feature_flag(&DiskFs, dir.path(), "cache", true).unwrap();
         let content = std::fs::read_to_string(dir.path().join("romance.toml")).unwrap();
         assert_eq!(content.matches("cache = true").count(), 1);
     }
@@ -1,29 +1,47 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct MultitenancyAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/multitenancy/tenant.rs.tera", "backend/src/tenant.rs"),
+    ("addon/multitenancy/tenant_model.rs.tera", "backend/src/entities/tenant.rs"),
+    ("addon/multitenancy/tenant_handlers.rs.tera", "backend/src/handlers/tenant.rs"),
+    ("addon/multitenancy/tenant_routes.rs.tera", "backend/src/routes/tenant.rs"),
+];
+
 impl Addon for MultitenancyAddon {
     fn name(&self) -> &str {
         "multitenancy"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)?;
-        super::check_auth_exists(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)?;
+        super::check_auth_exists(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/tenant.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/tenant.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_multitenancy(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_multitenancy(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
-        uninstall_multitenancy(project_root)
+    fn uninstall(&self, project: &Project) -> Result<()> {
+        uninstall_multitenancy(project)
     }
 
     fn dependencies(&self) -> Vec<&str> {
@@ -31,96 +49,98 @@ impl Addon for MultitenancyAddon {
     }
 }
 
-fn install_multitenancy(project_root: &Path) -> Result<()> {
+fn install_multitenancy(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing multitenancy...".bold());
+    say!("{}", "Installing multitenancy...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // 1. Generate tenant extractor module
     let content = engine.render("addon/multitenancy/tenant.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/tenant.rs"), &content)?;
-    println!("  {} backend/src/tenant.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/tenant.rs"), &content)?;
+    utils::ui::created("backend/src/tenant.rs");
 
     // 2. Generate tenant entity model
     let content = engine.render("addon/multitenancy/tenant_model.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/entities/tenant.rs"),
         &content,
     )?;
-    println!("  {} backend/src/entities/tenant.rs", "create".green());
+    utils::ui::created("backend/src/entities/tenant.rs");
 
     // 3. Generate tenant handlers
     let content = engine.render("addon/multitenancy/tenant_handlers.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/tenant.rs"),
         &content,
     )?;
-    println!("  {} backend/src/handlers/tenant.rs", "create".green());
+    utils::ui::created("backend/src/handlers/tenant.rs");
 
     // 4. Generate tenant routes
     let content = engine.render("addon/multitenancy/tenant_routes.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/routes/tenant.rs"),
         &content,
     )?;
-    println!("  {} backend/src/routes/tenant.rs", "create".green());
+    utils::ui::created("backend/src/routes/tenant.rs");
 
     // 5. Generate tenants table migration
-    let ts1 = crate::generator::migration::next_timestamp();
+    let ts1 = crate::generator::migration::next_timestamp(project);
     let content = engine.render("addon/multitenancy/tenant_migration.rs.tera", &ctx)?;
     let migration1_module = format!("m{}_create_tenants_table", ts1);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration1_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration1_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration1_module));
 
     // 6. Generate add_tenant_to_users migration (1 second later to avoid collision)
-    let ts2 = crate::generator::migration::next_timestamp();
+    let ts2 = crate::generator::migration::next_timestamp(project);
     let content = engine.render(
         "addon/multitenancy/add_tenant_to_users_migration.rs.tera",
         &ctx,
     )?;
     let migration2_module = format!("m{}_add_tenant_id_to_users", ts2);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration2_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration2_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration2_module));
 
     // Register modules via markers
     let mods_marker = "// === ROMANCE:MODS ===";
 
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/entities/mod.rs"),
         mods_marker,
         "pub mod tenant;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod tenant;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         mods_marker,
         "pub mod tenant;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:ROUTES ===",
         "        .merge(tenant::router())",
@@ -128,47 +148,51 @@ fn install_multitenancy(project_root: &Path) -> Result<()> {
 
     // Register migrations
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration1_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration1_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration2_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration2_module),
     )?;
 
     // Add mod tenant to main.rs
-    super::add_mod_to_main(project_root, "tenant")?;
+    super::add_mod_to_main(fs, project_root, "tenant")?;
 
     // Patch existing auth files in-place
-    patch_auth_for_multitenancy(project_root)?;
+    patch_auth_for_multitenancy(project)?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "multitenancy", true)?;
+    super::update_feature_flag(fs, project_root, "multitenancy", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Multitenancy installed successfully!".green().bold()
     );
-    println!("  All future entities will include tenant_id column.");
-    println!("  Existing entities need manual migration to add tenant_id.");
-    println!("  Tenant admin API: POST/GET /api/tenants (admin-only)");
-    println!();
-    println!("Next steps:");
-    println!("  romance db migrate");
+    say!("  All future entities will include tenant_id column.");
+    say!("  Existing entities need manual migration to add tenant_id.");
+    say!("  Tenant admin API: POST/GET /api/tenants (admin-only)");
+    say!();
+    say!("Next steps:");
+    say!("  romance db migrate");
 
     Ok(())
 }
@@ -210,13 +234,14 @@ fn insert_before_first(content: &str, needle: &str, block: &str) -> String {
 /// Patch existing auth files to add tenant_id support.
 /// This is needed when multitenancy is installed AFTER `romance generate auth`.
 /// Uses line-based operations instead of multi-line string matching for robustness.
-fn patch_auth_for_multitenancy(project_root: &Path) -> Result<()> {
-    use colored::Colorize;
+fn patch_auth_for_multitenancy(project: &Project) -> Result<()> {
+    let project_root = project.root();
+    let fs = project.fs();
 
     // === 1. Patch backend/src/auth.rs ===
     let auth_path = project_root.join("backend/src/auth.rs");
-    if auth_path.exists() {
-        let content = std::fs::read_to_string(&auth_path)?;
+    if fs.exists(&auth_path) {
+        let content = fs.read_to_string(&auth_path)?;
         if !content.contains("tenant_id") {
             let mut patched = content;
 
@@ -240,18 +265,15 @@ fn patch_auth_for_multitenancy(project_root: &Path) -> Result<()> {
                 "        tenant_id: tenant_id.map(|t| t.to_string()),",
             );
 
-            std::fs::write(&auth_path, patched)?;
-            println!(
-                "  {} backend/src/auth.rs (added tenant_id)",
-                "patch".yellow()
-            );
+            fs.write(&auth_path, &patched)?;
+            utils::ui::updated_with("backend/src/auth.rs", "added tenant_id");
         }
     }
 
     // === 2. Patch backend/src/entities/user.rs ===
     let user_model_path = project_root.join("backend/src/entities/user.rs");
-    if user_model_path.exists() {
-        let content = std::fs::read_to_string(&user_model_path)?;
+    if fs.exists(&user_model_path) {
+        let content = fs.read_to_string(&user_model_path)?;
         if !content.contains("tenant_id") {
             let mut lines: Vec<String> = content.lines().map(String::from).collect();
             let mut insertions: Vec<(usize, String)> = Vec::new();
@@ -279,18 +301,15 @@ fn patch_auth_for_multitenancy(project_root: &Path) -> Result<()> {
             }
 
             let patched = lines.join("\n") + "\n";
-            std::fs::write(&user_model_path, patched)?;
-            println!(
-                "  {} backend/src/entities/user.rs (added tenant_id)",
-                "patch".yellow()
-            );
+            fs.write(&user_model_path, &patched)?;
+            utils::ui::updated_with("backend/src/entities/user.rs", "added tenant_id");
         }
     }
 
     // === 3. Patch backend/src/handlers/auth.rs ===
     let auth_handlers_path = project_root.join("backend/src/handlers/auth.rs");
-    if auth_handlers_path.exists() {
-        let content = std::fs::read_to_string(&auth_handlers_path)?;
+    if fs.exists(&auth_handlers_path) {
+        let content = fs.read_to_string(&auth_handlers_path)?;
         if !content.contains("tenant_id") {
             let mut patched = content;
 
@@ -366,21 +385,20 @@ fn patch_auth_for_multitenancy(project_root: &Path) -> Result<()> {
             }
 
             let patched = lines.join("\n") + "\n";
-            std::fs::write(&auth_handlers_path, patched)?;
-            println!(
-                "  {} backend/src/handlers/auth.rs (added tenant_id)",
-                "patch".yellow()
-            );
+            fs.write(&auth_handlers_path, &patched)?;
+            utils::ui::updated_with("backend/src/handlers/auth.rs", "added tenant_id");
         }
     }
 
     Ok(())
 }
 
-fn uninstall_multitenancy(project_root: &Path) -> Result<()> {
+fn uninstall_multitenancy(project: &Project) -> Result<()> {
     use colored::Colorize;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Uninstalling multitenancy...".bold());
+    say!("{}", "Uninstalling multitenancy...".bold());
 
     // Delete generated files
     let files_to_remove = [
@@ -391,35 +409,39 @@ fn uninstall_multitenancy(project_root: &Path) -> Result<()> {
     ];
 
     for file in &files_to_remove {
-        if super::remove_file_if_exists(&project_root.join(file))? {
-            println!("  {} {}", "delete".red(), file);
+        if super::remove_file_if_exists(fs, &project_root.join(file))? {
+            utils::ui::removed(file);
         }
     }
 
     // Remove mod declarations
-    super::remove_mod_from_main(project_root, "tenant")?;
+    super::remove_mod_from_main(fs, project_root, "tenant")?;
 
     super::remove_line_from_file(
+        fs,
         &project_root.join("backend/src/entities/mod.rs"),
         "pub mod tenant;",
     )?;
     super::remove_line_from_file(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         "pub mod tenant;",
     )?;
     super::remove_line_from_file(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "pub mod tenant;",
     )?;
     super::remove_line_from_file(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         ".merge(tenant::router())",
     )?;
 
     // Unpatch auth.rs — remove tenant_id from Claims
     let auth_path = project_root.join("backend/src/auth.rs");
-    if auth_path.exists() {
-        let content = std::fs::read_to_string(&auth_path)?;
+    if fs.exists(&auth_path) {
+        let content = fs.read_to_string(&auth_path)?;
         if content.contains("tenant_id") {
             let mut patched = content;
             // Remove tenant_id field from Claims
@@ -434,31 +456,28 @@ fn uninstall_multitenancy(project_root: &Path) -> Result<()> {
                 "        tenant_id: tenant_id.map(|t| t.to_string()),\n",
                 "",
             );
-            std::fs::write(&auth_path, patched)?;
-            println!("  {} backend/src/auth.rs (removed tenant_id)", "patch".yellow());
+            fs.write(&auth_path, &patched)?;
+            utils::ui::updated_with("backend/src/auth.rs", "removed tenant_id");
         }
     }
 
     // Unpatch user model
     let user_model_path = project_root.join("backend/src/entities/user.rs");
-    if user_model_path.exists() {
-        let content = std::fs::read_to_string(&user_model_path)?;
+    if fs.exists(&user_model_path) {
+        let content = fs.read_to_string(&user_model_path)?;
         if content.contains("    pub tenant_id: Uuid,") {
             let patched = content
                 .replace("    pub tenant_id: Uuid,\n", "")
                 .replace("    pub tenant_id: Option<Uuid>,\n", "");
-            std::fs::write(&user_model_path, patched)?;
-            println!(
-                "  {} backend/src/entities/user.rs (removed tenant_id)",
-                "patch".yellow()
-            );
+            fs.write(&user_model_path, &patched)?;
+            utils::ui::updated_with("backend/src/entities/user.rs", "removed tenant_id");
         }
     }
 
     // Unpatch auth handlers — revert create_token calls and remove tenant_id
     let auth_handlers_path = project_root.join("backend/src/handlers/auth.rs");
-    if auth_handlers_path.exists() {
-        let content = std::fs::read_to_string(&auth_handlers_path)?;
+    if fs.exists(&auth_handlers_path) {
+        let content = fs.read_to_string(&auth_handlers_path)?;
         if content.contains("tenant_id") {
             let mut patched = content;
             // Revert create_token calls (remove tenant_id argument)
@@ -504,31 +523,28 @@ fn uninstall_multitenancy(project_root: &Path) -> Result<()> {
                 patched.push('\n');
             }
 
-            std::fs::write(&auth_handlers_path, patched)?;
-            println!(
-                "  {} backend/src/handlers/auth.rs (removed tenant_id)",
-                "patch".yellow()
-            );
+            fs.write(&auth_handlers_path, &patched)?;
+            utils::ui::updated_with("backend/src/handlers/auth.rs", "removed tenant_id");
         }
     }
 
     // Clean up tenant references in all generated entity files
-    cleanup_entity_tenant_references(project_root)?;
+    cleanup_entity_tenant_references(project)?;
 
     // Remove feature flag
-    super::remove_feature_flag(project_root, "multitenancy")?;
+    super::remove_feature_flag(fs, project_root, "multitenancy")?;
 
     // Note: migration files are left in place (can't safely remove without risking DB state)
 
     // Regenerate AI context
-    crate::ai_context::regenerate(project_root).ok();
+    crate::ai_context::regenerate(project).ok();
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Multitenancy uninstalled successfully.".green().bold()
     );
-    println!(
+    say!(
         "  {}",
         "Note: Migration files were left in place. Create a new migration to remove tenant_id columns."
             .dimmed()
@@ -539,15 +555,14 @@ fn uninstall_multitenancy(project_root: &Path) -> Result<()> {
 
 /// Clean up tenant_id references from all generated entity files.
 /// This handles entity model and handler files that were generated while multitenancy was active.
-fn cleanup_entity_tenant_references(project_root: &Path) -> Result<()> {
-    use colored::Colorize;
+fn cleanup_entity_tenant_references(project: &Project) -> Result<()> {
+    let project_root = project.root();
+    let fs = project.fs();
 
     // Clean up entity handler files
     let handlers_dir = project_root.join("backend/src/handlers");
-    if handlers_dir.exists() {
-        for entry in std::fs::read_dir(&handlers_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+    if fs.exists(&handlers_dir) {
+        for path in fs.read_dir(&handlers_dir)? {
             if path.extension().map(|e| e == "rs").unwrap_or(false) {
                 let name = path.file_stem().unwrap().to_string_lossy().to_string();
                 // Skip files already handled by other uninstall steps
@@ -557,7 +572,7 @@ fn cleanup_entity_tenant_references(project_root: &Path) -> Result<()> {
                 ) {
                     continue;
                 }
-                let content = std::fs::read_to_string(&path)?;
+                let content = fs.read_to_string(&path)?;
                 if !content.contains("TenantGuard") {
                     continue;
                 }
@@ -589,28 +604,22 @@ fn cleanup_entity_tenant_references(project_root: &Path) -> Result<()> {
                 if !patched.ends_with('\n') {
                     patched.push('\n');
                 }
-                std::fs::write(&path, patched)?;
-                println!(
-                    "  {} backend/src/handlers/{}.rs (removed tenant_id)",
-                    "patch".yellow(),
-                    name
-                );
+                fs.write(&path, &patched)?;
+                utils::ui::updated_with(&format!("backend/src/handlers/{}.rs", name), "removed tenant_id");
             }
         }
     }
 
     // Clean up entity model files
     let entities_dir = project_root.join("backend/src/entities");
-    if entities_dir.exists() {
-        for entry in std::fs::read_dir(&entities_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+    if fs.exists(&entities_dir) {
+        for path in fs.read_dir(&entities_dir)? {
             if path.extension().map(|e| e == "rs").unwrap_or(false) {
                 let name = path.file_stem().unwrap().to_string_lossy().to_string();
                 if matches!(name.as_str(), "user" | "tenant" | "mod" | "audit_entry") {
                     continue;
                 }
-                let content = std::fs::read_to_string(&path)?;
+                let content = fs.read_to_string(&path)?;
                 if !content.contains("tenant_id") && !content.contains("super::tenant") {
                     continue;
                 }
@@ -675,12 +684,8 @@ fn cleanup_entity_tenant_references(project_root: &Path) -> Result<()> {
                 if !patched.ends_with('\n') {
                     patched.push('\n');
                 }
-                std::fs::write(&path, patched)?;
-                println!(
-                    "  {} backend/src/entities/{}.rs (removed tenant_id)",
-                    "patch".yellow(),
-                    name
-                );
+                fs.write(&path, &patched)?;
+                utils::ui::updated_with(&format!("backend/src/entities/{}.rs", name), "removed tenant_id");
             }
         }
     }
@@ -1,4 +1,10 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
@@ -6,72 +12,87 @@ pub struct OauthAddon {
     pub provider: String,
 }
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/oauth/oauth.rs.tera", "backend/src/oauth.rs"),
+    ("addon/oauth/oauth_handlers.rs.tera", "backend/src/handlers/oauth.rs"),
+    ("addon/oauth/oauth_routes.rs.tera", "backend/src/routes/oauth.rs"),
+    ("addon/oauth/OAuthButton.tsx.tera", "frontend/src/features/auth/OAuthButton.tsx"),
+];
+
 impl Addon for OauthAddon {
     fn name(&self) -> &str {
         "oauth"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)?;
-        super::check_auth_exists(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)?;
+        super::check_auth_exists(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/oauth.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/oauth.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_oauth(project, &self.provider)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_oauth(project_root, &self.provider)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &oauth_context(&self.installed_provider(project)))
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling OAuth...".bold());
+        say!("{}", "Uninstalling OAuth...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/oauth.rs"))? {
-            println!("  {} backend/src/oauth.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/oauth.rs"))? {
+            utils::ui::removed("backend/src/oauth.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/handlers/oauth.rs"))? {
-            println!("  {} backend/src/handlers/oauth.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/handlers/oauth.rs"))? {
+            utils::ui::removed("backend/src/handlers/oauth.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/routes/oauth.rs"))? {
-            println!("  {} backend/src/routes/oauth.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/routes/oauth.rs"))? {
+            utils::ui::removed("backend/src/routes/oauth.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("frontend/src/features/auth/OAuthButton.tsx"),
         )? {
-            println!(
-                "  {} frontend/src/features/auth/OAuthButton.tsx",
-                "delete".red()
-            );
+            utils::ui::removed("frontend/src/features/auth/OAuthButton.tsx");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "oauth")?;
+        super::remove_mod_from_main(fs, project_root, "oauth")?;
 
         // Remove from handlers/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/handlers/mod.rs"),
             "pub mod oauth;",
         )?;
 
         // Remove from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "pub mod oauth;",
         )?;
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             ".merge(oauth::router())",
         )?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!("{}", "OAuth uninstalled successfully.".green().bold());
+        say!();
+        say!("{}", "OAuth uninstalled successfully.".green().bold());
 
         Ok(())
     }
@@ -81,93 +102,116 @@ impl Addon for OauthAddon {
     }
 }
 
-fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
+const PROVIDERS: &[&str] = &["google", "github", "discord"];
+
+impl OauthAddon {
+    /// The provider the addon was installed for, found from the client ID it
+    /// added to `.env.example`, falling back to `self.provider`.
+    fn installed_provider(&self, project: &Project) -> String {
+        let env = project
+            .fs()
+            .read_to_string(&project.join("backend/.env.example"))
+            .unwrap_or_default();
+        PROVIDERS
+            .iter()
+            .find(|p| env.contains(&format!("{}_CLIENT_ID=", p.to_uppercase())))
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| self.provider.clone())
+    }
+}
+
+fn oauth_context(provider: &str) -> tera::Context {
+    use heck::ToPascalCase;
+
+    let mut ctx = tera::Context::new();
+    ctx.insert("provider", provider);
+    ctx.insert("provider_pascal", &provider.to_pascal_case());
+    ctx
+}
+
+fn install_oauth(project: &Project, provider: &str) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
-    use heck::ToPascalCase;
-    use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    let valid_providers = ["google", "github", "discord"];
-    if !valid_providers.contains(&provider) {
+    if !PROVIDERS.contains(&provider) {
         anyhow::bail!(
             "Unsupported OAuth provider '{}'. Supported: {}",
             provider,
-            valid_providers.join(", ")
+            PROVIDERS.join(", ")
         );
     }
 
-    println!(
+    say!(
         "{}",
         format!("Installing OAuth ({})...", provider).bold()
     );
 
-    let engine = TemplateEngine::new()?;
-    let timestamp = crate::generator::migration::next_timestamp();
+    let engine = TemplateEngine::for_project(project)?;
+    let timestamp = crate::generator::migration::next_timestamp(project);
 
-    let mut ctx = Context::new();
-    ctx.insert("provider", provider);
-    ctx.insert("provider_pascal", &provider.to_pascal_case());
+    let mut ctx = oauth_context(provider);
     ctx.insert("timestamp", &timestamp);
 
     // Generate OAuth module
     let content = engine.render("addon/oauth/oauth.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/oauth.rs"), &content)?;
-    println!("  {} backend/src/oauth.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/oauth.rs"), &content)?;
+    utils::ui::created("backend/src/oauth.rs");
 
     // Generate OAuth handlers
     let content = engine.render("addon/oauth/oauth_handlers.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/oauth.rs"),
         &content,
     )?;
-    println!("  {} backend/src/handlers/oauth.rs", "create".green());
+    utils::ui::created("backend/src/handlers/oauth.rs");
 
     // Generate OAuth routes
     let content = engine.render("addon/oauth/oauth_routes.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/routes/oauth.rs"),
         &content,
     )?;
-    println!("  {} backend/src/routes/oauth.rs", "create".green());
+    utils::ui::created("backend/src/routes/oauth.rs");
 
     // Generate migration to add oauth columns to users table
     let content = engine.render("addon/oauth/oauth_migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_add_oauth_to_users", timestamp);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration_module));
 
     // Generate frontend OAuth button
     let content = engine.render("addon/oauth/OAuthButton.tsx.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("frontend/src/features/auth/OAuthButton.tsx"),
         &content,
     )?;
-    println!(
-        "  {} frontend/src/features/auth/OAuthButton.tsx",
-        "create".green()
-    );
+    utils::ui::created("frontend/src/features/auth/OAuthButton.tsx");
 
     // Register modules
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod oauth;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         mods_marker,
         "pub mod oauth;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:ROUTES ===",
         "        .merge(oauth::router())",
@@ -175,12 +219,14 @@ fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
 
     // Register migration
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration_module),
@@ -188,8 +234,8 @@ fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
 
     // Inject oauth fields into user entity model (both Model and UserPublic)
     let user_model_path = project_root.join("backend/src/entities/user.rs");
-    if user_model_path.exists() {
-        let mut user_content = std::fs::read_to_string(&user_model_path)?;
+    if fs.exists(&user_model_path) {
+        let mut user_content = fs.read_to_string(&user_model_path)?;
         if !user_content.contains("oauth_provider") {
             // Add oauth fields to Model struct: insert before "pub created_at" in "pub struct Model"
             if let Some(model_pos) = user_content.find("pub struct Model") {
@@ -205,15 +251,15 @@ fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
                     user_content.insert_str(insert_pos, "    pub oauth_provider: Option<String>,\n    pub oauth_id: Option<String>,\n");
                 }
             }
-            std::fs::write(&user_model_path, user_content)?;
-            println!("  {} backend/src/entities/user.rs (added oauth fields)", "update".green());
+            fs.write(&user_model_path, &user_content)?;
+            utils::ui::updated_with("backend/src/entities/user.rs", "added oauth fields");
         }
     }
 
     // Patch auth handlers to include oauth fields in UserPublic and ActiveModel
     let auth_handlers_path = project_root.join("backend/src/handlers/auth.rs");
-    if auth_handlers_path.exists() {
-        let mut auth_content = std::fs::read_to_string(&auth_handlers_path)?;
+    if fs.exists(&auth_handlers_path) {
+        let mut auth_content = fs.read_to_string(&auth_handlers_path)?;
         // Add ..Default::default() to ActiveModel in register handler so new optional fields are handled
         if !auth_content.contains("..Default::default()") {
             // Find the ActiveModel block and add ..Default::default() before its closing brace
@@ -247,14 +293,15 @@ fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
                 "            oauth_provider: u.oauth_provider,\n            oauth_id: u.oauth_id,\n            created_at: u.created_at,",
             );
         }
-        std::fs::write(&auth_handlers_path, auth_content)?;
+        fs.write(&auth_handlers_path, &auth_content)?;
     }
 
     // Add mod oauth to main.rs
-    super::add_mod_to_main(project_root, "oauth")?;
+    super::add_mod_to_main(fs, project_root, "oauth")?;
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[
             ("oauth2", r#"{ version = "4", features = ["reqwest"] }"#),
@@ -265,30 +312,34 @@ fn install_oauth(project_root: &Path, provider: &str) -> Result<()> {
     // Add env vars
     let provider_upper = provider.to_uppercase();
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         &format!("{}_CLIENT_ID=your-client-id", provider_upper),
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         &format!("{}_CLIENT_SECRET=your-client-secret", provider_upper),
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         &format!("{}_CLIENT_ID=your-client-id", provider_upper),
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         &format!("{}_CLIENT_SECRET=your-client-secret", provider_upper),
     )?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         format!("OAuth ({}) installed successfully!", provider)
             .green()
             .bold()
     );
-    println!(
+    say!(
         "  Set {}_CLIENT_ID and {}_CLIENT_SECRET in backend/.env",
         provider_upper, provider_upper
     );
@@ -1,79 +1,95 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct ObservabilityAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/observability/tracing_setup.rs.tera", "backend/src/middleware/tracing_setup.rs"),
+    ("addon/observability/request_id.rs.tera", "backend/src/middleware/request_id.rs"),
+];
+
 impl Addon for ObservabilityAddon {
     fn name(&self) -> &str {
         "observability"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/middleware/request_id.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root
-            .join("backend/src/middleware/request_id.rs")
-            .exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_observability(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_observability(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling observability...".bold());
+        say!("{}", "Uninstalling observability...".bold());
 
         // Delete files
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/middleware/request_id.rs"),
         )? {
-            println!("  {} backend/src/middleware/request_id.rs", "delete".red());
+            utils::ui::removed("backend/src/middleware/request_id.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/middleware/tracing_setup.rs"),
         )? {
-            println!(
-                "  {} backend/src/middleware/tracing_setup.rs",
-                "delete".red()
-            );
+            utils::ui::removed("backend/src/middleware/tracing_setup.rs");
         }
 
         // Remove lines from middleware/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/middleware/mod.rs"),
             "request_id",
         )?;
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/middleware/mod.rs"),
             "tracing_setup",
         )?;
 
         // Remove request_id_layer from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "request_id_layer",
         )?;
 
         // Clean up middleware module if security doesn't use it
-        let security_installed = project_root
-            .join("backend/src/middleware/security_headers.rs")
-            .exists();
+        let security_installed = fs.exists(&project_root.join("backend/src/middleware/security_headers.rs"));
         if !security_installed {
-            super::remove_file_if_exists(&project_root.join("backend/src/middleware/mod.rs"))?;
-            let _ = std::fs::remove_dir(project_root.join("backend/src/middleware"));
-            super::remove_mod_from_main(project_root, "middleware")?;
+            super::remove_file_if_exists(fs, &project_root.join("backend/src/middleware/mod.rs"))?;
+            let _ = fs.remove_dir(&project_root.join("backend/src/middleware"));
+            super::remove_mod_from_main(fs, project_root, "middleware")?;
         }
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Observability uninstalled successfully.".green().bold()
         );
@@ -82,43 +98,40 @@ impl Addon for ObservabilityAddon {
     }
 }
 
-fn install_observability(project_root: &Path) -> Result<()> {
+fn install_observability(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing observability...".bold());
+    say!("{}", "Installing observability...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate tracing setup
     let content = engine.render("addon/observability/tracing_setup.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/middleware/tracing_setup.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/middleware/tracing_setup.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/middleware/tracing_setup.rs");
 
     // Generate request ID middleware
     let content = engine.render("addon/observability/request_id.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/middleware/request_id.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/middleware/request_id.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/middleware/request_id.rs");
 
     // Ensure middleware/mod.rs exists
     let middleware_mod_path = project_root.join("backend/src/middleware/mod.rs");
-    if middleware_mod_path.exists() {
-        let content = std::fs::read_to_string(&middleware_mod_path)?;
+    if fs.exists(&middleware_mod_path) {
+        let content = fs.read_to_string(&middleware_mod_path)?;
         let mut new_content = content.clone();
         if !new_content.contains("mod tracing_setup;") {
             new_content = format!("pub mod tracing_setup;\n{}", new_content);
@@ -126,20 +139,21 @@ fn install_observability(project_root: &Path) -> Result<()> {
         if !new_content.contains("mod request_id;") {
             new_content = format!("pub mod request_id;\n{}", new_content);
         }
-        std::fs::write(&middleware_mod_path, new_content)?;
+        fs.write(&middleware_mod_path, &new_content)?;
     } else {
         utils::write_file(
+            fs,
             &middleware_mod_path,
             "pub mod request_id;\npub mod tracing_setup;\n",
         )?;
     }
 
     // Add mod middleware to main.rs if not present
-    super::add_mod_to_main(project_root, "middleware")?;
+    super::add_mod_to_main(fs, project_root, "middleware")?;
 
     // Replace scaffold's tracing init with observability module's init_tracing()
     let main_path = project_root.join("backend/src/main.rs");
-    let main_content = std::fs::read_to_string(&main_path)?;
+    let main_content = fs.read_to_string(&main_path)?;
     if !main_content.contains("init_tracing()") {
         // Find and replace the existing tracing_subscriber block
         if main_content.contains("tracing_subscriber::registry()") {
@@ -163,13 +177,14 @@ fn install_observability(project_root: &Path) -> Result<()> {
                 "use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};\n",
                 "",
             );
-            std::fs::write(&main_path, new_main)?;
-            println!("  {} backend/src/main.rs (replaced tracing init)", "update".green());
+            fs.write(&main_path, &new_main)?;
+            utils::ui::updated_with("backend/src/main.rs", "replaced tracing init");
         }
     }
 
     // Inject trace layer into routes
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:MIDDLEWARE ===",
         "        .layer(crate::middleware::request_id::request_id_layer())",
@@ -177,6 +192,7 @@ fn install_observability(project_root: &Path) -> Result<()> {
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[
             ("tower-http", r#"{ version = "0.6", features = ["cors", "trace", "request-id", "propagate-header"] }"#),
@@ -185,21 +201,23 @@ fn install_observability(project_root: &Path) -> Result<()> {
 
     // Add RUST_LOG to .env
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "RUST_LOG=info",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "RUST_LOG=info",
     )?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Observability installed successfully!".green().bold()
     );
-    println!("  Structured logging with request ID propagation enabled.");
-    println!("  Set RUST_LOG=debug for verbose logging.");
+    say!("  Structured logging with request ID propagation enabled.");
+    say!("  Set RUST_LOG=debug for verbose logging.");
 
     Ok(())
 }
@@ -1,64 +1,82 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct SearchAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/search/search.rs.tera", "backend/src/search.rs"),
+    ("addon/search/search_handler.rs.tera", "backend/src/handlers/search.rs"),
+    ("addon/search/SearchBar.tsx.tera", "frontend/src/components/SearchBar.tsx"),
+];
+
 impl Addon for SearchAddon {
     fn name(&self) -> &str {
         "search"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/search.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/search.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_search(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_search(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling full-text search...".bold());
+        say!("{}", "Uninstalling full-text search...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/search.rs"))? {
-            println!("  {} backend/src/search.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/search.rs"))? {
+            utils::ui::removed("backend/src/search.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/handlers/search.rs"))? {
-            println!("  {} backend/src/handlers/search.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/handlers/search.rs"))? {
+            utils::ui::removed("backend/src/handlers/search.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("frontend/src/components/SearchBar.tsx"),
         )? {
-            println!(
-                "  {} frontend/src/components/SearchBar.tsx",
-                "delete".red()
-            );
+            utils::ui::removed("frontend/src/components/SearchBar.tsx");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "search")?;
+        super::remove_mod_from_main(fs, project_root, "search")?;
 
         // Remove from handlers/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/handlers/mod.rs"),
             "pub mod search;",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "search")?;
+        super::remove_feature_flag(fs, project_root, "search")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Full-text search uninstalled successfully.".green().bold()
         );
@@ -67,62 +85,63 @@ impl Addon for SearchAddon {
     }
 }
 
-fn install_search(project_root: &Path) -> Result<()> {
+fn install_search(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing full-text search...".bold());
+    say!("{}", "Installing full-text search...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate search module
     let content = engine.render("addon/search/search.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/search.rs"), &content)?;
-    println!("  {} backend/src/search.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/search.rs"), &content)?;
+    utils::ui::created("backend/src/search.rs");
 
     // Generate search handler template
     let content = engine.render("addon/search/search_handler.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/search.rs"),
         &content,
     )?;
-    println!("  {} backend/src/handlers/search.rs", "create".green());
+    utils::ui::created("backend/src/handlers/search.rs");
 
     // Generate frontend search component
     let content = engine.render("addon/search/SearchBar.tsx.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("frontend/src/components/SearchBar.tsx"),
         &content,
     )?;
-    println!(
-        "  {} frontend/src/components/SearchBar.tsx",
-        "create".green()
-    );
+    utils::ui::created("frontend/src/components/SearchBar.tsx");
 
     // Add mod search to main.rs
-    super::add_mod_to_main(project_root, "search")?;
+    super::add_mod_to_main(fs, project_root, "search")?;
 
     // Register search handler
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod search;",
     )?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "search", true)?;
+    super::update_feature_flag(fs, project_root, "search", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Full-text search installed successfully!".green().bold()
     );
-    println!("  Use [searchable] annotation on fields: title:string[searchable]");
-    println!("  Search endpoint: GET /api/{{entities}}/search?q=term");
+    say!("  Use [searchable] annotation on fields: title:string[searchable]");
+    say!("  Search endpoint: GET /api/{{entities}}/search?q=term");
 
     Ok(())
 }
@@ -1,86 +1,103 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct SecurityAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/security/security_headers.rs.tera", "backend/src/middleware/security_headers.rs"),
+    ("addon/security/rate_limit.rs.tera", "backend/src/middleware/rate_limit.rs"),
+];
+
 impl Addon for SecurityAddon {
     fn name(&self) -> &str {
         "security"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/middleware/security_headers.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root
-            .join("backend/src/middleware/security_headers.rs")
-            .exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_security(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_security(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling security middleware...".bold());
+        say!("{}", "Uninstalling security middleware...".bold());
 
         // Delete files
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/middleware/security_headers.rs"),
         )? {
-            println!(
-                "  {} backend/src/middleware/security_headers.rs",
-                "delete".red()
-            );
+            utils::ui::removed("backend/src/middleware/security_headers.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/middleware/rate_limit.rs"),
         )? {
-            println!("  {} backend/src/middleware/rate_limit.rs", "delete".red());
+            utils::ui::removed("backend/src/middleware/rate_limit.rs");
         }
 
         // Remove middleware lines from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "security_headers",
         )?;
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "rate_limit_middleware",
         )?;
 
         // Remove lines from middleware/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/middleware/mod.rs"),
             "security_headers",
         )?;
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/middleware/mod.rs"),
             "rate_limit",
         )?;
 
         // Remove [security] section from romance.toml
-        super::remove_toml_section(project_root, "security")?;
+        super::remove_toml_section(fs, project_root, "security")?;
 
         // Clean up middleware module if observability doesn't use it
-        let observability_installed = project_root
-            .join("backend/src/middleware/request_id.rs")
-            .exists();
+        let observability_installed = fs.exists(&project_root.join("backend/src/middleware/request_id.rs"));
         if !observability_installed {
-            super::remove_file_if_exists(&project_root.join("backend/src/middleware/mod.rs"))?;
-            let _ = std::fs::remove_dir(project_root.join("backend/src/middleware"));
-            super::remove_mod_from_main(project_root, "middleware")?;
+            super::remove_file_if_exists(fs, &project_root.join("backend/src/middleware/mod.rs"))?;
+            let _ = fs.remove_dir(&project_root.join("backend/src/middleware"));
+            super::remove_mod_from_main(fs, project_root, "middleware")?;
         }
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Security middleware uninstalled successfully.".green().bold()
         );
@@ -89,57 +106,57 @@ impl Addon for SecurityAddon {
     }
 }
 
-fn install_security(project_root: &Path) -> Result<()> {
+fn install_security(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing security middleware...".bold());
+    say!("{}", "Installing security middleware...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate security headers middleware
     let content = engine.render("addon/security/security_headers.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/middleware/security_headers.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/middleware/security_headers.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/middleware/security_headers.rs");
 
     // Generate rate limiter
     let content = engine.render("addon/security/rate_limit.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/middleware/rate_limit.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/middleware/rate_limit.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/middleware/rate_limit.rs");
 
     // Generate middleware mod.rs
     let content = engine.render("addon/security/middleware_mod.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/middleware/mod.rs"),
         &content,
     )?;
-    println!("  {} backend/src/middleware/mod.rs", "create".green());
+    utils::ui::created("backend/src/middleware/mod.rs");
 
     // Add mod middleware to main.rs
-    super::add_mod_to_main(project_root, "middleware")?;
+    super::add_mod_to_main(fs, project_root, "middleware")?;
 
     // Inject middleware into routes/mod.rs
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:MIDDLEWARE ===",
         "        .layer(axum::middleware::from_fn(crate::middleware::security_headers::security_headers))",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:MIDDLEWARE ===",
         "        .layer(axum::middleware::from_fn(crate::middleware::rate_limit::rate_limit_middleware))",
@@ -147,6 +164,7 @@ fn install_security(project_root: &Path) -> Result<()> {
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[
             ("tower", r#"{ version = "0.5", features = ["limit", "timeout"] }"#),
@@ -158,41 +176,45 @@ fn install_security(project_root: &Path) -> Result<()> {
 
     // Add per-user rate limit env vars (anonymous IP-based + authenticated user-based)
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "RATE_LIMIT_ANON_RPM=30",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "RATE_LIMIT_AUTH_RPM=120",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "RATE_LIMIT_ANON_RPM=30",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "RATE_LIMIT_AUTH_RPM=120",
     )?;
 
     // Update romance.toml
     let config_path = project_root.join("romance.toml");
-    let content = std::fs::read_to_string(&config_path)?;
+    let content = fs.read_to_string(&config_path)?;
     if !content.contains("[security]") {
         let new_content = format!(
             "{}\n[security]\nrate_limit_anon_rpm = 30\nrate_limit_auth_rpm = 120\ncors_origins = [\"http://localhost:5173\"]\n",
             content.trim_end()
         );
-        std::fs::write(&config_path, new_content)?;
+        fs.write(&config_path, &new_content)?;
     }
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Security middleware installed successfully!".green().bold()
     );
-    println!("  Security headers, per-user rate limiting, and CORS configured.");
-    println!("  Anonymous: {} RPM (IP-based), Authenticated: {} RPM (user-based).", 30, 120);
-    println!("  Configure in romance.toml under [security].");
+    say!("  Security headers, per-user rate limiting, and CORS configured.");
+    say!("  Anonymous: {} RPM (IP-based), Authenticated: {} RPM (user-based).", 30, 120);
+    say!("  Configure in romance.toml under [security].");
 
     Ok(())
 }
@@ -1,86 +1,102 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct SoftDeleteAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/soft_delete/soft_delete.rs.tera", "backend/src/soft_delete.rs"),
+];
+
 impl Addon for SoftDeleteAddon {
     fn name(&self) -> &str {
         "soft-delete"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/soft_delete.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root
-            .join("backend/src/soft_delete.rs")
-            .exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_soft_delete(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_soft_delete(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling soft delete...".bold());
+        say!("{}", "Uninstalling soft delete...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/soft_delete.rs"))? {
-            println!("  {} backend/src/soft_delete.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/soft_delete.rs"))? {
+            utils::ui::removed("backend/src/soft_delete.rs");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "soft_delete")?;
+        super::remove_mod_from_main(fs, project_root, "soft_delete")?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "soft_delete")?;
+        super::remove_feature_flag(fs, project_root, "soft_delete")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!("{}", "Soft delete uninstalled successfully.".green().bold());
+        say!();
+        say!("{}", "Soft delete uninstalled successfully.".green().bold());
 
         Ok(())
     }
 }
 
-fn install_soft_delete(project_root: &Path) -> Result<()> {
+fn install_soft_delete(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing soft delete...".bold());
+    say!("{}", "Installing soft delete...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate soft_delete helper module
     let content = engine.render("addon/soft_delete/soft_delete.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/soft_delete.rs"),
         &content,
     )?;
-    println!("  {} backend/src/soft_delete.rs", "create".green());
+    utils::ui::created("backend/src/soft_delete.rs");
 
     // Add mod to main.rs
-    super::add_mod_to_main(project_root, "soft_delete")?;
+    super::add_mod_to_main(fs, project_root, "soft_delete")?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "soft_delete", true)?;
+    super::update_feature_flag(fs, project_root, "soft_delete", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Soft delete installed successfully!".green().bold()
     );
-    println!("  Future entities will use soft-delete by default.");
-    println!("  Entities get: DELETE (soft), POST /:id/restore, DELETE /:id/permanent");
+    say!("  Future entities will use soft-delete by default.");
+    say!("  Entities get: DELETE (soft), POST /:id/restore, DELETE /:id/permanent");
 
     Ok(())
 }
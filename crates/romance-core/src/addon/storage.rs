@@ -1,77 +1,98 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct StorageAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/storage/storage.rs.tera", "backend/src/storage.rs"),
+    ("addon/storage/upload_handler.rs.tera", "backend/src/handlers/upload.rs"),
+    ("addon/storage/upload_routes.rs.tera", "backend/src/routes/upload.rs"),
+    ("addon/storage/FileUpload.tsx.tera", "frontend/src/components/FileUpload.tsx"),
+];
+
 impl Addon for StorageAddon {
     fn name(&self) -> &str {
         "storage"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/storage.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/storage.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_storage(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_storage(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling file storage...".bold());
+        say!("{}", "Uninstalling file storage...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/storage.rs"))? {
-            println!("  {} backend/src/storage.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/storage.rs"))? {
+            utils::ui::removed("backend/src/storage.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/handlers/upload.rs"))? {
-            println!("  {} backend/src/handlers/upload.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/handlers/upload.rs"))? {
+            utils::ui::removed("backend/src/handlers/upload.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/routes/upload.rs"))? {
-            println!("  {} backend/src/routes/upload.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/routes/upload.rs"))? {
+            utils::ui::removed("backend/src/routes/upload.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("frontend/src/components/FileUpload.tsx"),
         )? {
-            println!(
-                "  {} frontend/src/components/FileUpload.tsx",
-                "delete".red()
-            );
+            utils::ui::removed("frontend/src/components/FileUpload.tsx");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "storage")?;
+        super::remove_mod_from_main(fs, project_root, "storage")?;
 
         // Remove from handlers/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/handlers/mod.rs"),
             "pub mod upload;",
         )?;
 
         // Remove from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "pub mod upload;",
         )?;
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             ".merge(upload::router())",
         )?;
 
         // Remove [storage] section from romance.toml
-        super::remove_toml_section(project_root, "storage")?;
+        super::remove_toml_section(fs, project_root, "storage")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "File storage uninstalled successfully.".green().bold()
         );
@@ -80,65 +101,69 @@ impl Addon for StorageAddon {
     }
 }
 
-fn install_storage(project_root: &Path) -> Result<()> {
+fn install_storage(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing file storage...".bold());
+    say!("{}", "Installing file storage...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate storage backend trait + impls
     let content = engine.render("addon/storage/storage.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/storage.rs"), &content)?;
-    println!("  {} backend/src/storage.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/storage.rs"), &content)?;
+    utils::ui::created("backend/src/storage.rs");
 
     // Generate upload handler
     let content = engine.render("addon/storage/upload_handler.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/handlers/upload.rs"),
         &content,
     )?;
-    println!("  {} backend/src/handlers/upload.rs", "create".green());
+    utils::ui::created("backend/src/handlers/upload.rs");
 
     // Generate upload routes
     let content = engine.render("addon/storage/upload_routes.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/routes/upload.rs"),
         &content,
     )?;
-    println!("  {} backend/src/routes/upload.rs", "create".green());
+    utils::ui::created("backend/src/routes/upload.rs");
 
     // Generate frontend upload component
     let content = engine.render("addon/storage/FileUpload.tsx.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("frontend/src/components/FileUpload.tsx"),
         &content,
     )?;
-    println!(
-        "  {} frontend/src/components/FileUpload.tsx",
-        "create".green()
-    );
+    utils::ui::created("frontend/src/components/FileUpload.tsx");
 
     // Add mod storage to main.rs
-    super::add_mod_to_main(project_root, "storage")?;
+    super::add_mod_to_main(fs, project_root, "storage")?;
 
     // Register upload routes
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/handlers/mod.rs"),
         mods_marker,
         "pub mod upload;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         mods_marker,
         "pub mod upload;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:ROUTES ===",
         "        .merge(upload::router())",
@@ -146,6 +171,7 @@ fn install_storage(project_root: &Path) -> Result<()> {
 
     // Add dependencies
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[
             ("axum", r#"{ version = "0.8", features = ["json", "multipart"] }"#),
@@ -156,49 +182,55 @@ fn install_storage(project_root: &Path) -> Result<()> {
 
     // Add env vars for storage configuration
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "UPLOAD_DIR=./uploads",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "UPLOAD_URL=/uploads",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env"),
         "MAX_FILE_SIZE=10MB",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "UPLOAD_DIR=./uploads",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "UPLOAD_URL=/uploads",
     )?;
     super::append_env_var(
+        fs,
         &project_root.join("backend/.env.example"),
         "MAX_FILE_SIZE=10MB",
     )?;
 
     // Create uploads directory
-    std::fs::create_dir_all(project_root.join("backend/uploads"))?;
-    println!("  {} backend/uploads/", "create".green());
+    fs.create_dir_all(&project_root.join("backend/uploads"))?;
+    utils::ui::created("backend/uploads/");
 
     // Update romance.toml
     let config_path = project_root.join("romance.toml");
-    let content = std::fs::read_to_string(&config_path)?;
+    let content = fs.read_to_string(&config_path)?;
     if !content.contains("[storage]") {
         let new_content = format!(
             "{}\n[storage]\nbackend = \"local\"\nupload_dir = \"./uploads\"\nmax_file_size = \"10MB\"\n",
             content.trim_end()
         );
-        std::fs::write(&config_path, new_content)?;
+        fs.write(&config_path, &new_content)?;
     }
 
-    println!();
-    println!("{}", "File storage installed successfully!".green().bold());
-    println!("  Use `avatar:image` or `document:file` field types in entity generation.");
-    println!("  Configure storage in romance.toml under [storage].");
+    say!();
+    say!("{}", "File storage installed successfully!".green().bold());
+    say!("  Use `avatar:image` or `document:file` field types in entity generation.");
+    say!("  Configure storage in romance.toml under [storage].");
 
     Ok(())
 }
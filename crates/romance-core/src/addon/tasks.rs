@@ -1,65 +1,83 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct TasksAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/tasks/tasks.rs.tera", "backend/src/tasks.rs"),
+    ("addon/tasks/model.rs.tera", "backend/src/entities/background_task.rs"),
+    ("addon/tasks/scheduler.rs.tera", "backend/src/scheduler.rs"),
+];
+
 impl Addon for TasksAddon {
     fn name(&self) -> &str {
         "tasks"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/tasks.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/tasks.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_tasks(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_tasks(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling background tasks...".bold());
+        say!("{}", "Uninstalling background tasks...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/tasks.rs"))? {
-            println!("  {} backend/src/tasks.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/tasks.rs"))? {
+            utils::ui::removed("backend/src/tasks.rs");
         }
-        if super::remove_file_if_exists(&project_root.join("backend/src/scheduler.rs"))? {
-            println!("  {} backend/src/scheduler.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/scheduler.rs"))? {
+            utils::ui::removed("backend/src/scheduler.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("backend/src/entities/background_task.rs"),
         )? {
-            println!(
-                "  {} backend/src/entities/background_task.rs",
-                "delete".red()
-            );
+            utils::ui::removed("backend/src/entities/background_task.rs");
         }
 
         // Remove mod declarations from main.rs
-        super::remove_mod_from_main(project_root, "tasks")?;
-        super::remove_mod_from_main(project_root, "scheduler")?;
+        super::remove_mod_from_main(fs, project_root, "tasks")?;
+        super::remove_mod_from_main(fs, project_root, "scheduler")?;
 
         // Remove from entities/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/entities/mod.rs"),
             "pub mod background_task;",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "background_tasks")?;
+        super::remove_feature_flag(fs, project_root, "background_tasks")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "Background tasks uninstalled successfully.".green().bold()
         );
@@ -68,52 +86,49 @@ impl Addon for TasksAddon {
     }
 }
 
-fn install_tasks(project_root: &Path) -> Result<()> {
+fn install_tasks(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing background tasks...".bold());
+    say!("{}", "Installing background tasks...".bold());
 
-    let engine = TemplateEngine::new()?;
-    let timestamp = crate::generator::migration::next_timestamp();
+    let engine = TemplateEngine::for_project(project)?;
+    let timestamp = crate::generator::migration::next_timestamp(project);
 
     let mut ctx = Context::new();
     ctx.insert("timestamp", &timestamp);
 
     // Generate tasks module
     let content = engine.render("addon/tasks/tasks.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/tasks.rs"), &content)?;
-    println!("  {} backend/src/tasks.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/tasks.rs"), &content)?;
+    utils::ui::created("backend/src/tasks.rs");
 
     // Generate background_task entity model
     let content = engine.render("addon/tasks/model.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/entities/background_task.rs"),
         &content,
     )?;
-    println!(
-        "  {} backend/src/entities/background_task.rs",
-        "create".green()
-    );
+    utils::ui::created("backend/src/entities/background_task.rs");
 
     // Generate migration
     let content = engine.render("addon/tasks/migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_create_background_tasks_table", timestamp);
     utils::write_file(
+        fs,
         &project_root.join(format!("backend/migration/src/{}.rs", migration_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration_module));
 
     // Register entity module
     let mods_marker = "// === ROMANCE:MODS ===";
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/entities/mod.rs"),
         mods_marker,
         "pub mod background_task;",
@@ -121,12 +136,14 @@ fn install_tasks(project_root: &Path) -> Result<()> {
 
     // Register migration
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATION_MODS ===",
         &format!("mod {};", migration_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         "// === ROMANCE:MIGRATIONS ===",
         &format!("            Box::new({}::Migration),", migration_module),
@@ -134,31 +151,31 @@ fn install_tasks(project_root: &Path) -> Result<()> {
 
     // Generate scheduler module
     let content = engine.render("addon/tasks/scheduler.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/scheduler.rs"), &content)?;
-    println!("  {} backend/src/scheduler.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/scheduler.rs"), &content)?;
+    utils::ui::created("backend/src/scheduler.rs");
 
     // Add mod tasks and mod scheduler to main.rs
-    super::add_mod_to_main(project_root, "tasks")?;
-    super::add_mod_to_main(project_root, "scheduler")?;
+    super::add_mod_to_main(fs, project_root, "tasks")?;
+    super::add_mod_to_main(fs, project_root, "scheduler")?;
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "background_tasks", true)?;
+    super::update_feature_flag(fs, project_root, "background_tasks", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Background tasks installed successfully!".green().bold()
     );
-    println!("  Run migrations: romance db migrate");
-    println!("  Enqueue tasks: TaskQueue::new(db).enqueue(\"send_email\", payload).await?");
-    println!("  Start worker: TaskQueue::new(db).start_worker(4, handler).await");
-    println!();
-    println!("  Scheduler (recurring jobs):");
-    println!("    let mut scheduler = scheduler::Scheduler::new();");
-    println!("    scheduler.add_job(\"cleanup\", Duration::from_secs(3600), || {{");
-    println!("        tokio::spawn(async {{ /* ... */ }})");
-    println!("    }});");
-    println!("    scheduler.start();");
+    say!("  Run migrations: romance db migrate");
+    say!("  Enqueue tasks: TaskQueue::new(db).enqueue(\"send_email\", payload).await?");
+    say!("  Start worker: TaskQueue::new(db).start_worker(4, handler).await");
+    say!();
+    say!("  Scheduler (recurring jobs):");
+    say!("    let mut scheduler = scheduler::Scheduler::new();");
+    say!("    scheduler.add_job(\"cleanup\", Duration::from_secs(3600), || {{");
+    say!("        tokio::spawn(async {{ /* ... */ }})");
+    say!("    }});");
+    say!("    scheduler.start();");
 
     Ok(())
 }
@@ -1,78 +1,95 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct ValidationAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/validation/validate_middleware.rs.tera", "backend/src/validation.rs"),
+];
+
 impl Addon for ValidationAddon {
     fn name(&self) -> &str {
         "validation"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/validation.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root
-            .join("backend/src/validation.rs")
-            .exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_validation(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_validation(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling validation...".bold());
+        say!("{}", "Uninstalling validation...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/validation.rs"))? {
-            println!("  {} backend/src/validation.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/validation.rs"))? {
+            utils::ui::removed("backend/src/validation.rs");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "validation")?;
+        super::remove_mod_from_main(fs, project_root, "validation")?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "validation")?;
+        super::remove_feature_flag(fs, project_root, "validation")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!("{}", "Validation uninstalled successfully.".green().bold());
+        say!();
+        say!("{}", "Validation uninstalled successfully.".green().bold());
 
         Ok(())
     }
 }
 
-fn install_validation(project_root: &Path) -> Result<()> {
+fn install_validation(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing validation...".bold());
+    say!("{}", "Installing validation...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate validation middleware
     let content = engine.render("addon/validation/validate_middleware.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("backend/src/validation.rs"),
         &content,
     )?;
-    println!("  {} backend/src/validation.rs", "create".green());
+    utils::ui::created("backend/src/validation.rs");
 
     // Add mod declaration to main.rs
-    super::add_mod_to_main(project_root, "validation")?;
+    super::add_mod_to_main(fs, project_root, "validation")?;
 
     // Add validator dependencies to Cargo.toml
     crate::generator::auth::insert_cargo_dependency(
+        fs,
         &project_root.join("backend/Cargo.toml"),
         &[
             ("validator", r#"{ version = "0.19", features = ["derive"] }"#),
@@ -80,11 +97,11 @@ fn install_validation(project_root: &Path) -> Result<()> {
     )?;
 
     // Update romance.toml features
-    super::update_feature_flag(project_root, "validation", true)?;
+    super::update_feature_flag(fs, project_root, "validation", true)?;
 
-    println!();
-    println!("{}", "Validation installed successfully!".green().bold());
-    println!("  Entity fields now support validation rules: name:string[min=3,max=100]");
+    say!();
+    say!("{}", "Validation installed successfully!".green().bold());
+    say!("  Entity fields now support validation rules: name:string[min=3,max=100]");
 
     Ok(())
 }
@@ -1,58 +1,78 @@
 use crate::addon::Addon;
+use crate::fs::ProjectFs;
+use crate::manifest::RenderedFile;
+use crate::inject;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use std::path::Path;
 
 pub struct WebsocketAddon;
 
+/// Templates rendered into files the addon owns, as (template, output) pairs.
+const FILES: &[(&str, &str)] = &[
+    ("addon/websocket/ws.rs.tera", "backend/src/ws.rs"),
+    ("addon/websocket/useWebSocket.ts.tera", "frontend/src/lib/useWebSocket.ts"),
+];
+
 impl Addon for WebsocketAddon {
     fn name(&self) -> &str {
         "websocket"
     }
 
-    fn check_prerequisites(&self, project_root: &Path) -> Result<()> {
-        super::check_romance_project(project_root)
+    fn check_prerequisites(&self, fs: &dyn ProjectFs, project_root: &Path) -> Result<()> {
+        super::check_romance_project(fs, project_root)
+    }
+
+    fn is_already_installed(&self, fs: &dyn ProjectFs, project_root: &Path) -> bool {
+        fs.exists(&project_root.join("backend/src/ws.rs"))
     }
 
-    fn is_already_installed(&self, project_root: &Path) -> bool {
-        project_root.join("backend/src/ws.rs").exists()
+    fn install(&self, project: &Project) -> Result<()> {
+        install_websocket(project)
     }
 
-    fn install(&self, project_root: &Path) -> Result<()> {
-        install_websocket(project_root)
+    fn rendered_files(&self, project: &Project) -> Result<Vec<RenderedFile>> {
+        super::render_files(project, FILES, &tera::Context::new())
     }
 
-    fn uninstall(&self, project_root: &Path) -> Result<()> {
+    fn uninstall(&self, project: &Project) -> Result<()> {
         use colored::Colorize;
+        let project_root = project.root();
+        let fs = project.fs();
 
-        println!("{}", "Uninstalling WebSocket support...".bold());
+        say!("{}", "Uninstalling WebSocket support...".bold());
 
         // Delete files
-        if super::remove_file_if_exists(&project_root.join("backend/src/ws.rs"))? {
-            println!("  {} backend/src/ws.rs", "delete".red());
+        if super::remove_file_if_exists(fs, &project_root.join("backend/src/ws.rs"))? {
+            utils::ui::removed("backend/src/ws.rs");
         }
         if super::remove_file_if_exists(
+            fs,
             &project_root.join("frontend/src/lib/useWebSocket.ts"),
         )? {
-            println!("  {} frontend/src/lib/useWebSocket.ts", "delete".red());
+            utils::ui::removed("frontend/src/lib/useWebSocket.ts");
         }
 
         // Remove mod declaration from main.rs
-        super::remove_mod_from_main(project_root, "ws")?;
+        super::remove_mod_from_main(fs, project_root, "ws")?;
 
         // Remove ws_handler route from routes/mod.rs
         super::remove_line_from_file(
+            fs,
             &project_root.join("backend/src/routes/mod.rs"),
             "ws_handler",
         )?;
 
         // Remove feature flag
-        super::remove_feature_flag(project_root, "websocket")?;
+        super::remove_feature_flag(fs, project_root, "websocket")?;
 
         // Regenerate AI context
-        crate::ai_context::regenerate(project_root).ok();
+        crate::ai_context::regenerate(project).ok();
 
-        println!();
-        println!(
+        say!();
+        say!(
             "{}",
             "WebSocket support uninstalled successfully.".green().bold()
         );
@@ -61,47 +81,47 @@ impl Addon for WebsocketAddon {
     }
 }
 
-fn install_websocket(project_root: &Path) -> Result<()> {
+fn install_websocket(project: &Project) -> Result<()> {
     use crate::template::TemplateEngine;
-    use crate::utils;
     use colored::Colorize;
     use tera::Context;
+    let project_root = project.root();
+    let fs = project.fs();
 
-    println!("{}", "Installing WebSocket support...".bold());
+    say!("{}", "Installing WebSocket support...".bold());
 
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
     let ctx = Context::new();
 
     // Generate backend ws module
     let content = engine.render("addon/websocket/ws.rs.tera", &ctx)?;
-    utils::write_file(&project_root.join("backend/src/ws.rs"), &content)?;
-    println!("  {} backend/src/ws.rs", "create".green());
+    utils::write_file(fs, &project_root.join("backend/src/ws.rs"), &content)?;
+    utils::ui::created("backend/src/ws.rs");
 
     // Generate frontend useWebSocket hook
     let content = engine.render("addon/websocket/useWebSocket.ts.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_root.join("frontend/src/lib/useWebSocket.ts"),
         &content,
     )?;
-    println!("  {} frontend/src/lib/useWebSocket.ts", "create".green());
+    utils::ui::created("frontend/src/lib/useWebSocket.ts");
 
     // Add `mod ws;` to main.rs if not present
-    super::add_mod_to_main(project_root, "ws")?;
+    super::add_mod_to_main(fs, project_root, "ws")?;
 
     // Inject WS route into routes/mod.rs via MIDDLEWARE marker
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &project_root.join("backend/src/routes/mod.rs"),
         "// === ROMANCE:MIDDLEWARE ===",
         "        .route(\"/ws\", axum::routing::get(crate::ws::ws_handler))",
     )?;
-    println!(
-        "  {} backend/src/routes/mod.rs (added /ws route)",
-        "update".green()
-    );
+    utils::ui::updated_with("backend/src/routes/mod.rs", "added /ws route");
 
     // Add WebSocketState to AppState in routes/mod.rs
     let routes_path = project_root.join("backend/src/routes/mod.rs");
-    let routes_content = std::fs::read_to_string(&routes_path)?;
+    let routes_content = fs.read_to_string(&routes_path)?;
 
     if !routes_content.contains("pub ws:") {
         // Add use import for ws module
@@ -126,44 +146,38 @@ fn install_websocket(project_root: &Path) -> Result<()> {
             "    let event_bus = EventBus::new();\n    let ws = WebSocketState::new();\n\n    // Bridge entity events to WebSocket clients\n    tokio::spawn(crate::ws::bridge_events(event_bus.clone(), ws.clone()));\n\n    let state = AppState { db, event_bus, ws };",
         );
 
-        std::fs::write(&routes_path, routes_content)?;
-        println!(
-            "  {} backend/src/routes/mod.rs (added WebSocketState to AppState)",
-            "update".green()
-        );
+        fs.write(&routes_path, &routes_content)?;
+        utils::ui::updated_with("backend/src/routes/mod.rs", "added WebSocketState to AppState");
     }
 
     // Add axum ws feature to Cargo.toml
     // The scaffold already has axum = { version = "0.8", features = ["json"] }
     // We need to add the "ws" feature
     let cargo_path = project_root.join("backend/Cargo.toml");
-    let cargo_content = std::fs::read_to_string(&cargo_path)?;
+    let cargo_content = fs.read_to_string(&cargo_path)?;
     if cargo_content.contains("axum") && !cargo_content.contains("\"ws\"") {
         let new_content = cargo_content.replace(
             r#"features = ["json"]"#,
             r#"features = ["json", "ws"]"#,
         );
-        std::fs::write(&cargo_path, new_content)?;
-        println!(
-            "  {} backend/Cargo.toml (added ws feature to axum)",
-            "update".green()
-        );
+        fs.write(&cargo_path, &new_content)?;
+        utils::ui::updated_with("backend/Cargo.toml", "added ws feature to axum");
     }
 
     // Update romance.toml
-    super::update_feature_flag(project_root, "websocket", true)?;
+    super::update_feature_flag(fs, project_root, "websocket", true)?;
 
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "WebSocket support installed successfully!".green().bold()
     );
-    println!("  Backend: WebSocket endpoint at /ws");
-    println!("  Frontend: import {{ useWebSocket }} from '@/lib/useWebSocket'");
-    println!("  Entity events are automatically broadcast to connected clients.");
-    println!();
-    println!("  Usage example (frontend):");
-    println!("    const {{ messages, sendMessage, isConnected }} = useWebSocket('ws://localhost:3000/ws');");
+    say!("  Backend: WebSocket endpoint at /ws");
+    say!("  Frontend: import {{ useWebSocket }} from '@/lib/useWebSocket'");
+    say!("  Entity events are automatically broadcast to connected clients.");
+    say!();
+    say!("  Usage example (frontend):");
+    say!("    const {{ messages, sendMessage, isConnected }} = useWebSocket('ws://localhost:3000/ws');");
 
     Ok(())
 }
@@ -2,39 +2,39 @@
 //! for AI assistants. This file is regenerated every time entities, auth, or admin are modified,
 //! so AI agents always have up-to-date knowledge of the schema, relations, and API endpoints.
 
+use crate::project::Project;
+use crate::say;
 use crate::{relation, utils};
 use anyhow::Result;
 use heck::ToPascalCase;
-use std::fs;
-use std::path::Path;
 
 /// Regenerate the project-level CLAUDE.md based on current project state.
 /// Called after scaffold, entity generation, auth generation, and admin generation.
-pub fn regenerate(project_root: &Path) -> Result<()> {
+pub fn regenerate(project: &Project) -> Result<()> {
     let mut sections = Vec::new();
 
-    sections.push(generate_header(project_root));
+    sections.push(generate_header(project));
     sections.push(generate_stack_section());
     sections.push(generate_cli_reference_section());
 
-    let entities = scan_entities(project_root)?;
+    let entities = scan_entities(project)?;
     if !entities.is_empty() {
         sections.push(generate_entity_section(&entities));
-        sections.push(generate_relations_section(project_root, &entities)?);
+        sections.push(generate_relations_section(project, &entities)?);
         sections.push(generate_api_section(&entities));
     }
 
-    if has_auth(project_root) {
+    if has_auth(project) {
         sections.push(generate_auth_section());
     }
 
-    if has_admin(project_root) {
+    if has_admin(project) {
         sections.push(generate_admin_section(&entities));
     }
 
-    let addons = scan_addons(project_root);
+    let addons = scan_addons(project);
     if !addons.is_empty() {
-        sections.push(generate_addons_section(&addons));
+        sections.push(generate_addons_section(project, &addons));
     }
 
     sections.push(generate_conventions_section());
@@ -42,10 +42,10 @@ pub fn regenerate(project_root: &Path) -> Result<()> {
     let generated = sections.join("\n\n");
 
     // Preserve custom notes if file already exists
-    let claude_path = project_root.join("CLAUDE.md");
+    let claude_path = project.join("CLAUDE.md");
     let custom_marker = "\n## Custom Notes";
-    let content = if claude_path.exists() {
-        let existing = fs::read_to_string(&claude_path)?;
+    let content = if project.fs().exists(&claude_path) {
+        let existing = project.fs().read_to_string(&claude_path)?;
         if let Some(pos) = existing.rfind(custom_marker) {
             let custom_block = &existing[pos..];
             // Replace everything before Custom Notes with new generated content
@@ -62,14 +62,15 @@ pub fn regenerate(project_root: &Path) -> Result<()> {
         generated
     };
 
-    fs::write(&claude_path, &content)?;
-    println!("  Updated CLAUDE.md (AI context)");
+    project.fs().write(&claude_path, &content)?;
+    say!("  Updated CLAUDE.md (AI context)");
 
     Ok(())
 }
 
-fn generate_header(project_root: &Path) -> String {
-    let project_name = project_root
+fn generate_header(project: &Project) -> String {
+    let project_name = project
+        .root()
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| "project".to_string());
@@ -108,6 +109,7 @@ fn generate_cli_reference_section() -> String {
 | `romance generate entity <name> [fields...]` | Generate CRUD entity | `romance generate entity Product title:string price:decimal` |
 | `romance generate auth` | Add JWT authentication | `romance generate auth` |
 | `romance generate admin` | Add admin panel (requires auth) | `romance generate admin` |
+| `romance generate <recipe> [args...]` | Run a custom recipe from `.romance/generators/` | `romance generate service Billing` |
 | `romance add <addon>` | Install addon | `romance add validation` |
 | `romance dev` | Run backend + frontend dev servers | `romance dev` |
 | `romance db migrate` | Run pending migrations | `romance db migrate` |
@@ -116,6 +118,8 @@ fn generate_cli_reference_section() -> String {
 | `romance test` | Run tests with temp database | `romance test` |
 | `romance check` | Run cargo check + tsc | `romance check` |
 | `romance update` | Update scaffold to latest templates | `romance update` |
+| `romance history` | List journaled operations | `romance history` |
+| `romance undo [id]` | Revert one operation | `romance undo 3` |
 
 ### Field Syntax
 
@@ -166,23 +170,23 @@ Available rules: `min=N`, `max=N`, `email`, `url`, `searchable`
 }
 
 /// Scan entity model files to extract field information.
-fn scan_entities(project_root: &Path) -> Result<Vec<EntityInfo>> {
-    let entities_dir = project_root.join("backend/src/entities");
-    if !entities_dir.exists() {
+fn scan_entities(project: &Project) -> Result<Vec<EntityInfo>> {
+    let entities_dir = project.join("backend/src/entities");
+    if !project.fs().exists(&entities_dir) {
         return Ok(Vec::new());
     }
 
-    let entity_names = relation::discover_entities(project_root)?;
+    let entity_names = relation::discover_entities(project)?;
     let mut entities = Vec::new();
 
     for name in &entity_names {
         // Skip junction tables (contain underscore and both halves are entity names)
         let model_path = entities_dir.join(format!("{}.rs", name));
-        if !model_path.exists() {
+        if !project.fs().exists(&model_path) {
             continue;
         }
 
-        let content = fs::read_to_string(&model_path)?;
+        let content = project.fs().read_to_string(&model_path)?;
 
         // Skip if it looks like a junction table (no ROMANCE:CUSTOM marker)
         if !content.contains("ROMANCE:CUSTOM") && !content.contains("ROMANCE:RELATIONS") {
@@ -291,18 +295,18 @@ fn generate_entity_section(entities: &[EntityInfo]) -> String {
     s
 }
 
-fn generate_relations_section(project_root: &Path, entities: &[EntityInfo]) -> Result<String> {
+fn generate_relations_section(project: &Project, entities: &[EntityInfo]) -> Result<String> {
     let mut s = String::from("## Relations\n\n");
     let mut has_any = false;
 
-    let entities_dir = project_root.join("backend/src/entities");
+    let entities_dir = project.join("backend/src/entities");
 
     for entity in entities {
         let model_path = entities_dir.join(format!("{}.rs", entity.snake_name));
-        if !model_path.exists() {
+        if !project.fs().exists(&model_path) {
             continue;
         }
-        let content = fs::read_to_string(&model_path)?;
+        let content = project.fs().read_to_string(&model_path)?;
 
         // Parse belongs_to from Relation enum
         for line in content.lines() {
@@ -384,12 +388,12 @@ fn generate_relations_section(project_root: &Path, entities: &[EntityInfo]) -> R
     }
 
     // Scan for junction tables
-    let junction_entities = relation::discover_entities(project_root)?;
+    let junction_entities = relation::discover_entities(project)?;
     for name in &junction_entities {
         if name.contains('_') {
             let model_path = entities_dir.join(format!("{}.rs", name));
-            if model_path.exists() {
-                let content = fs::read_to_string(&model_path)?;
+            if project.fs().exists(&model_path) {
+                let content = project.fs().read_to_string(&model_path)?;
                 if !content.contains("ROMANCE:CUSTOM") {
                     s.push_str(&format!("- Junction table: `{}`\n", name));
                     has_any = true;
@@ -412,7 +416,7 @@ fn generate_api_section(entities: &[EntityInfo]) -> String {
     for entity in entities {
         let plural = utils::pluralize(&entity.snake_name);
         s.push_str(&format!("### {} CRUD\n\n", entity.name));
-        s.push_str(&format!("| Method | Path | Description |\n"));
+        s.push_str("| Method | Path | Description |\n");
         s.push_str("|--------|------|-------------|\n");
         s.push_str(&format!(
             "| GET | `/api/{}?page=1&per_page=20` | List (paginated) |\n",
@@ -450,12 +454,12 @@ fn generate_api_section(entities: &[EntityInfo]) -> String {
     s
 }
 
-fn has_auth(project_root: &Path) -> bool {
-    project_root.join("backend/src/auth.rs").exists()
+fn has_auth(project: &Project) -> bool {
+    project.fs().exists(&project.join("backend/src/auth.rs"))
 }
 
-fn has_admin(project_root: &Path) -> bool {
-    project_root.join("backend/src/handlers/admin.rs").exists()
+fn has_admin(project: &Project) -> bool {
+    project.fs().exists(&project.join("backend/src/handlers/admin.rs"))
 }
 
 fn generate_auth_section() -> String {
@@ -514,44 +518,46 @@ fn generate_conventions_section() -> String {
         .to_string()
 }
 
-fn scan_addons(project_root: &Path) -> Vec<String> {
+fn scan_addons(project: &Project) -> Vec<String> {
+    let exists = |path: &str| project.fs().exists(&project.join(path));
     let mut addons = Vec::new();
 
-    if project_root.join("backend/src/validation.rs").exists() {
+    if exists("backend/src/validation.rs") {
         addons.push("validation".to_string());
     }
-    if project_root.join("backend/src/middleware/security_headers.rs").exists() {
+    if exists("backend/src/middleware/security_headers.rs") {
         addons.push("security".to_string());
     }
-    if project_root.join("backend/src/middleware/request_id.rs").exists() {
+    if exists("backend/src/middleware/request_id.rs") {
         addons.push("observability".to_string());
     }
-    if project_root.join("backend/src/storage.rs").exists() {
+    if exists("backend/src/storage.rs") {
         addons.push("storage".to_string());
     }
-    if project_root.join("backend/src/soft_delete.rs").exists() {
+    if exists("backend/src/soft_delete.rs") {
         addons.push("soft-delete".to_string());
     }
-    if project_root.join("backend/src/audit.rs").exists() {
+    if exists("backend/src/audit.rs") {
         addons.push("audit-log".to_string());
     }
-    if project_root.join("backend/src/oauth.rs").exists() {
+    if exists("backend/src/oauth.rs") {
         addons.push("oauth".to_string());
     }
-    if project_root.join("backend/src/search.rs").exists() {
+    if exists("backend/src/search.rs") {
         addons.push("search".to_string());
     }
-    if project_root.join("frontend/src/features/dev/DevDashboard.tsx").exists() {
+    if exists("frontend/src/features/dev/DevDashboard.tsx") {
         addons.push("dashboard".to_string());
     }
-    if project_root.join("backend/src/tenant.rs").exists() {
+    if exists("backend/src/tenant.rs") {
         addons.push("multitenancy".to_string());
     }
+    addons.extend(crate::addon::declarative::list_installed(project).unwrap_or_default());
 
     addons
 }
 
-fn generate_addons_section(addons: &[String]) -> String {
+fn generate_addons_section(project: &Project, addons: &[String]) -> String {
     let mut s = String::from("## Installed Addons\n\n");
 
     for addon in addons {
@@ -618,7 +624,19 @@ fn generate_addons_section(addons: &[String]) -> String {
                 s.push_str("- Tenant admin API: `POST/GET /api/tenants` (admin-only)\n");
                 s.push_str("- Users belong to a tenant (`users.tenant_id` FK)\n\n");
             }
-            _ => {}
+            name => {
+                // Declarative addon installed from a local directory
+                if let Ok(addon) = crate::addon::declarative::DeclarativeAddon::load_installed(project, name) {
+                    s.push_str(&format!("### {} (local addon)\n", name));
+                    if let Some(description) = &addon.manifest.description {
+                        s.push_str(&format!("- {}\n", description));
+                    }
+                    for file in &addon.manifest.files {
+                        s.push_str(&format!("- `{}`\n", file.output));
+                    }
+                    s.push('\n');
+                }
+            }
         }
     }
 
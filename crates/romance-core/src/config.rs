@@ -1,5 +1,7 @@
+use crate::fs::ProjectFs;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +19,11 @@ pub struct RomanceConfig {
     pub storage: Option<StorageConfig>,
     #[serde(default)]
     pub environment: EnvironmentConfig,
+    #[serde(default)]
+    pub templates: TemplatesConfig,
+    /// Entity modules, keyed by name (`[modules.billing]`).
+    #[serde(default)]
+    pub modules: BTreeMap<String, ModuleConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -80,6 +87,29 @@ pub struct FeaturesConfig {
     pub multitenancy: bool,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TemplatesConfig {
+    /// Extra directory of template overrides, relative to the project root.
+    /// `.romance/templates/` is always layered on top of it.
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+/// A module grouping entities under `entities/<name>/`, `handlers/<name>/`,
+/// `routes/<name>/` and `features/<name>/`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleConfig {
+    /// Route prefix after the API prefix (default: `/<name>`).
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Prepended to the module's table names, e.g. `billing_` for `billing_invoices`.
+    #[serde(default)]
+    pub table_prefix: Option<String>,
+    /// Postgres schema holding the module's tables.
+    #[serde(default)]
+    pub schema: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SecurityConfig {
     #[serde(default = "default_rate_limit")]
@@ -161,9 +191,9 @@ fn deep_merge(base: toml::Value, override_val: toml::Value) -> toml::Value {
 }
 
 impl RomanceConfig {
-    pub fn load(dir: &Path) -> Result<Self> {
+    pub fn load(fs: &dyn ProjectFs, dir: &Path) -> Result<Self> {
         let path = dir.join("romance.toml");
-        let content = std::fs::read_to_string(&path)?;
+        let content = fs.read_to_string(&path)?;
         let config: RomanceConfig = toml::from_str(&content)?;
         Ok(config)
     }
@@ -176,6 +206,8 @@ impl RomanceConfig {
     /// 3. If `romance.{env}.toml` exists (e.g. `romance.production.toml`),
     ///    deep-merges those overrides on top of the base config
     ///
+    /// `environment.active` is set to the environment that was used.
+    ///
     /// This is fully backward-compatible: projects without env-specific files
     /// behave exactly as before.
     pub fn load_with_env(dir: &Path) -> Result<Self> {
@@ -204,7 +236,8 @@ impl RomanceConfig {
         };
 
         // Deserialize the merged TOML value into RomanceConfig
-        let config: RomanceConfig = merged_value.try_into()?;
+        let mut config: RomanceConfig = merged_value.try_into()?;
+        config.environment.active = env_name;
         Ok(config)
     }
 
@@ -224,6 +257,7 @@ impl RomanceConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
     use std::io::Write;
 
     /// Helper: write a romance.toml and return the tempdir.
@@ -254,7 +288,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert_eq!(config.project.name, "my-app");
         assert_eq!(config.backend.port, 3000);
         assert_eq!(config.frontend.port, 5173);
@@ -281,7 +315,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(!config.codegen.generate_openapi);
         assert!(!config.codegen.generate_ts_types);
     }
@@ -307,7 +341,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(config.codegen.generate_openapi);
         assert!(config.codegen.generate_ts_types);
     }
@@ -329,7 +363,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(!config.features.validation);
         assert!(!config.features.soft_delete);
         assert!(!config.features.audit_log);
@@ -359,7 +393,7 @@ search = true
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(config.has_feature("validation"));
         assert!(config.has_feature("search"));
         assert!(!config.has_feature("soft_delete"));
@@ -383,7 +417,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(!config.has_feature("nonexistent_feature"));
     }
 
@@ -406,7 +440,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert!(config.backend.api_prefix.is_none());
     }
 
@@ -428,7 +462,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert_eq!(config.backend.api_prefix.as_deref(), Some("/api/v1"));
     }
 
@@ -453,7 +487,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         let sec = config.security.unwrap();
         assert_eq!(sec.rate_limit_rpm, 60);
         assert!(!sec.csrf);
@@ -478,7 +512,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         let store = config.storage.unwrap();
         assert_eq!(store.backend, "local");
         assert_eq!(store.upload_dir, "./uploads");
@@ -490,7 +524,7 @@ api_base_url = "http://localhost:3000"
     #[test]
     fn load_missing_file_errors() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(RomanceConfig::load(dir.path()).is_err());
+        assert!(RomanceConfig::load(&DiskFs, dir.path()).is_err());
     }
 
     // ── Project description optional ──────────────────────────────────
@@ -513,7 +547,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert_eq!(config.project.description.as_deref(), Some("A test project"));
     }
 
@@ -536,7 +570,7 @@ api_base_url = "http://localhost:3000"
 "#,
         );
 
-        let config = RomanceConfig::load(dir.path()).unwrap();
+        let config = RomanceConfig::load(&DiskFs, dir.path()).unwrap();
         assert_eq!(config.environment.active, "development");
     }
 
@@ -601,6 +635,7 @@ port = 8080
         assert_eq!(config.backend.database_url, "postgres://localhost/test");
         // project name should remain from base
         assert_eq!(config.project.name, "test");
+        assert_eq!(config.environment.active, "production");
     }
 
     #[test]
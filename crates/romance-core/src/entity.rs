@@ -1,4 +1,6 @@
 use anyhow::{bail, Result};
+use crate::error::Failure;
+use crate::say;
 use dialoguer::{Confirm, Input, Select};
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +25,13 @@ pub struct EntityDefinition {
     pub name: String,
     pub fields: Vec<FieldDefinition>,
     pub relations: Vec<RelationDefinition>,
+    /// The module the entity is generated in (`--module`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// The entity's table, when it isn't the one its name implies (entities
+    /// imported from an existing database).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,20 +45,15 @@ pub enum ValidationRule {
     Unique,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum FieldVisibility {
+    #[default]
     Public,             // Everyone can see
     Authenticated,      // Only logged-in users
     AdminOnly,          // Only admin role
     Roles(Vec<String>), // Specific roles
 }
 
-impl Default for FieldVisibility {
-    fn default() -> Self {
-        FieldVisibility::Public
-    }
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct FieldDefinition {
     pub name: String,
@@ -335,6 +339,70 @@ fn parse_visibility(s: &str) -> FieldVisibility {
 /// Searchable: name:type[searchable]
 /// Relation formats: name:has_many->Entity, name:m2m->Entity
 pub fn parse_entity(name: &str, field_strs: &[String]) -> Result<EntityDefinition> {
+    parse_entity_args(name, field_strs)
+        .map_err(|e| Failure::InvalidInput.error(format!("{:#}", e)))
+}
+
+/// Format an entity as the CLI args `parse_entity` reads back, e.g.
+/// `author_id:uuid->User` or `posts:has_many->Post`.
+pub fn format_entity_args(entity: &EntityDefinition) -> Vec<String> {
+    let mut args: Vec<String> = entity
+        .fields
+        .iter()
+        .map(|field| {
+            let type_str = match &field.field_type {
+                FieldType::Enum(variants) => format!("enum({})", variants.join(",")),
+                other => format!("{:?}", other).to_lowercase(),
+            };
+            let mut annotations: Vec<String> = field
+                .validations
+                .iter()
+                .map(|rule| match rule {
+                    ValidationRule::Min(n) => format!("min={}", n),
+                    ValidationRule::Max(n) => format!("max={}", n),
+                    ValidationRule::Email => "email".to_string(),
+                    ValidationRule::Url => "url".to_string(),
+                    ValidationRule::Regex(pattern) => format!("regex={}", pattern),
+                    ValidationRule::Required => "required".to_string(),
+                    ValidationRule::Unique => "unique".to_string(),
+                })
+                .collect();
+            if field.searchable {
+                annotations.push("searchable".to_string());
+            }
+            match &field.visibility {
+                FieldVisibility::Public => {}
+                FieldVisibility::Authenticated => annotations.push("authenticated".to_string()),
+                FieldVisibility::AdminOnly => annotations.push("admin_only".to_string()),
+                FieldVisibility::Roles(roles) => annotations.push(format!("roles={}", roles.join(";"))),
+            }
+
+            let mut arg = format!("{}:{}", field.name, type_str);
+            if !annotations.is_empty() {
+                arg.push_str(&format!("[{}]", annotations.join(",")));
+            }
+            if let Some(target) = &field.relation {
+                arg.push_str(&format!("->{}", target));
+            }
+            if field.optional {
+                arg.push('?');
+            }
+            arg
+        })
+        .collect();
+
+    for rel in &entity.relations {
+        let kind = match rel.relation_type {
+            RelationType::BelongsTo => continue, // Formatted with its field
+            RelationType::HasMany => "has_many",
+            RelationType::ManyToMany => "m2m",
+        };
+        args.push(format!("{}:{}->{}", rel.name, kind, rel.target_entity));
+    }
+    args
+}
+
+fn parse_entity_args(name: &str, field_strs: &[String]) -> Result<EntityDefinition> {
     let mut fields = Vec::new();
     let mut relations = Vec::new();
 
@@ -374,8 +442,8 @@ pub fn parse_entity(name: &str, field_strs: &[String]) -> Result<EntityDefinitio
         let type_and_relation: Vec<&str> = type_and_relation_str.splitn(2, "->").collect();
         // Support `uuid?->Entity` syntax: strip `?` from end of type part
         let raw_type_str = type_and_relation[0];
-        let (type_str, type_optional) = if raw_type_str.ends_with('?') {
-            (raw_type_str[..raw_type_str.len() - 1].to_lowercase(), true)
+        let (type_str, type_optional) = if let Some(stripped) = raw_type_str.strip_suffix('?') {
+            (stripped.to_lowercase(), true)
         } else {
             (raw_type_str.to_lowercase(), false)
         };
@@ -447,6 +515,8 @@ pub fn parse_entity(name: &str, field_strs: &[String]) -> Result<EntityDefinitio
         name: name.to_string(),
         fields,
         relations,
+        module: None,
+        table_name: None,
     })
 }
 
@@ -475,7 +545,14 @@ const RELATION_TYPE_OPTIONS: &[(&str, &str)] = &[
 
 /// Interactively prompt the user to define entity fields and relations.
 pub fn prompt_entity_fields(entity_name: &str) -> Result<(Vec<FieldDefinition>, Vec<RelationDefinition>)> {
-    println!(
+    if !crate::utils::ui::is_interactive() {
+        return Err(Failure::InvalidInput.error(format!(
+            "No fields given for '{}'; pass them as arguments (e.g. title:string) when not running interactively",
+            entity_name
+        )));
+    }
+
+    say!(
         "Define fields for '{}' (press Enter with empty name to finish):",
         entity_name
     );
@@ -521,7 +598,7 @@ pub fn prompt_entity_fields(entity_name: &str) -> Result<(Vec<FieldDefinition>,
                 .filter(|v| !v.is_empty())
                 .collect();
             if variants.is_empty() {
-                println!("No variants provided, defaulting to String type.");
+                say!("No variants provided, defaulting to String type.");
                 FieldType::String
             } else {
                 FieldType::Enum(variants)
@@ -556,7 +633,7 @@ pub fn prompt_entity_fields(entity_name: &str) -> Result<(Vec<FieldDefinition>,
             visibility: FieldVisibility::default(),
         });
 
-        println!();
+        say!();
     }
 
     // Prompt for additional relations (has_many, m2m)
@@ -604,7 +681,7 @@ pub fn prompt_entity_fields(entity_name: &str) -> Result<(Vec<FieldDefinition>,
                 optional: false,
             });
 
-            println!();
+            say!();
         }
     }
 
@@ -1096,4 +1173,24 @@ mod tests {
         assert_eq!(entity.fields[0].relation.as_deref(), Some("User"));
         assert!(entity.fields[0].validations.contains(&ValidationRule::Required));
     }
+
+    // ── format_entity_args ────────────────────────────────────────────
+
+    #[test]
+    fn format_entity_args_round_trips() {
+        let args: Vec<String> = [
+            "title:string[min=3,max=100,searchable]",
+            "status:enum(draft,published)",
+            "salary:decimal[roles=hr;admin]",
+            "published_at:datetime?",
+            "author_id:uuid->User?",
+            "comments:has_many->Comment",
+            "tags:m2m->Tag",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let entity = parse_entity("Post", &args).unwrap();
+        assert_eq!(format_entity_args(&entity), args);
+    }
 }
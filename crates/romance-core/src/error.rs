@@ -0,0 +1,133 @@
+//! Failure categories, so the CLI can exit with a stable code for each kind
+//! of error.
+
+use serde::Serialize;
+use std::fmt;
+
+/// What kind of failure an error is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// Bad arguments: entity or field definitions, names, or a prompt that
+    /// can't be answered in non-interactive mode.
+    InvalidInput,
+    /// No `romance.toml` in the project directory.
+    NotAProject,
+    /// The project isn't in a state the command can work with: a missing
+    /// marker, prerequisite or generated file, or something already there.
+    Precondition,
+    /// Conflicts were left for the user to resolve.
+    Conflict,
+    /// An external command (cargo, npm, npx) failed.
+    Command,
+    /// Reading or writing a file failed.
+    Io,
+}
+
+impl Failure {
+    /// All categories, in exit code order.
+    pub const ALL: &'static [Failure] = &[
+        Failure::InvalidInput,
+        Failure::NotAProject,
+        Failure::Precondition,
+        Failure::Conflict,
+        Failure::Command,
+        Failure::Io,
+    ];
+
+    /// The process exit code for this kind of failure. Uncategorized errors
+    /// exit with 1.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Failure::InvalidInput => 2,
+            Failure::NotAProject => 3,
+            Failure::Precondition => 4,
+            Failure::Conflict => 5,
+            Failure::Command => 6,
+            Failure::Io => 7,
+        }
+    }
+
+    /// An error of this kind with `message`.
+    pub fn error(self, message: impl fmt::Display) -> anyhow::Error {
+        anyhow::Error::new(Categorized {
+            failure: self,
+            message: message.to_string(),
+        })
+    }
+
+    /// `err` as this kind of failure, unless it already has a kind.
+    pub fn tag(self, err: anyhow::Error) -> anyhow::Error {
+        if Failure::of(&err).is_some() {
+            err
+        } else {
+            self.error(format!("{:#}", err))
+        }
+    }
+
+    /// The kind of `err`: the outermost categorized error in its chain, or
+    /// [`Failure::Io`] if it was caused by an I/O error.
+    pub fn of(err: &anyhow::Error) -> Option<Failure> {
+        err.chain()
+            .find_map(|cause| cause.downcast_ref::<Categorized>().map(|c| c.failure))
+            .or_else(|| {
+                err.chain()
+                    .any(|cause| cause.is::<std::io::Error>())
+                    .then_some(Failure::Io)
+            })
+    }
+}
+
+#[derive(Debug)]
+struct Categorized {
+    failure: Failure,
+    message: String,
+}
+
+impl fmt::Display for Categorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Categorized {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn finds_category_under_context() {
+        let err = Err::<(), _>(Failure::Precondition.error("Marker missing"))
+            .context("Generation failed")
+            .unwrap_err();
+        assert_eq!(Failure::of(&err), Some(Failure::Precondition));
+        assert_eq!(format!("{:#}", err), "Generation failed: Marker missing");
+    }
+
+    #[test]
+    fn tag_keeps_an_existing_category() {
+        let err = Failure::Precondition.tag(Failure::NotAProject.error("No romance.toml"));
+        assert_eq!(Failure::of(&err), Some(Failure::NotAProject));
+        let err = Failure::Precondition.tag(anyhow::anyhow!("Auth must be generated first"));
+        assert_eq!(Failure::of(&err), Some(Failure::Precondition));
+        assert_eq!(err.to_string(), "Auth must be generated first");
+    }
+
+    #[test]
+    fn io_errors_are_categorized() {
+        let err = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Failed to read romance.toml");
+        assert_eq!(Failure::of(&err), Some(Failure::Io));
+        assert_eq!(Failure::of(&anyhow::anyhow!("something else")), None);
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let codes: BTreeSet<i32> = Failure::ALL.iter().map(|f| f.exit_code()).collect();
+        assert_eq!(codes.len(), Failure::ALL.len());
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+    }
+}
@@ -0,0 +1,394 @@
+//! The filesystem generators read and write project files through.
+//!
+//! [`DiskFs`] is the real filesystem. [`MemoryFs`] keeps every file in
+//! memory, so a whole generation (a new project, an entity, an addon) can run
+//! without touching the disk: for dry-runs, snapshot tests of generated output,
+//! or writing the result into an archive.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// The file operations generators perform, modelled on `std::fs`.
+pub trait ProjectFs: Send + Sync {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Write `contents` to the file at `path`. Like `std::fs::write`, the
+    /// parent directory must already exist.
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// The paths of the entries in the directory at `path`, sorted.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    /// Remove the directory at `path`, which must be empty.
+    fn remove_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+}
+
+/// What a file held before the first change [`DiskFs`] made to it while the
+/// write log was on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Before {
+    Missing,
+    Text(String),
+    /// Not UTF-8, so it can't be journaled.
+    Binary,
+}
+
+thread_local! {
+    static WRITE_LOG: RefCell<Option<BTreeMap<PathBuf, Before>>> = const { RefCell::new(None) };
+}
+
+/// Start logging the files [`DiskFs`] writes or removes on this thread, for
+/// the generation journal.
+pub fn start_write_log() {
+    WRITE_LOG.with(|log| *log.borrow_mut() = Some(BTreeMap::new()));
+}
+
+/// Stop logging, and return every file changed since [`start_write_log`]
+/// with what it held before.
+pub fn take_write_log() -> BTreeMap<PathBuf, Before> {
+    WRITE_LOG.with(|log| log.borrow_mut().take()).unwrap_or_default()
+}
+
+/// Note the content of `path` before it's changed, if the log is on and it
+/// wasn't changed before.
+fn log_change(path: &Path) {
+    WRITE_LOG.with(|log| {
+        if let Some(log) = log.borrow_mut().as_mut() {
+            log.entry(path.to_path_buf()).or_insert_with(|| match std::fs::read(path) {
+                Err(_) => Before::Missing,
+                Ok(bytes) => String::from_utf8(bytes).map_or(Before::Binary, Before::Text),
+            });
+        }
+    });
+}
+
+/// Log every file under `dir` before it's removed.
+fn log_tree(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            log_tree(&path);
+        } else {
+            log_change(&path);
+        }
+    }
+}
+
+/// The real filesystem.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DiskFs;
+
+impl ProjectFs for DiskFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        log_change(path);
+        std::fs::write(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        log_change(path);
+        std::fs::remove_file(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        log_tree(path);
+        std::fs::remove_dir_all(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+}
+
+/// A filesystem held in memory. It starts out empty, or with a copy of a
+/// directory on disk ([`MemoryFs::load`]); nothing written to it reaches
+/// the disk.
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    files: BTreeMap<PathBuf, String>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+/// Directories never copied by [`MemoryFs::load`]: build output and
+/// dependencies, which generators don't read.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A copy of the text files under `dir` on disk, at the same paths.
+    /// `.git`, `node_modules` and `target` directories are left out.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let fs = Self::new();
+        fs.create_dir_all(dir)?;
+        fs.copy_dir(dir)?;
+        Ok(fs)
+    }
+
+    fn copy_dir(&self, dir: &Path) -> io::Result<()> {
+        for path in DiskFs.read_dir(dir)? {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if path.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                    self.create_dir_all(&path)?;
+                    self.copy_dir(&path)?;
+                }
+            } else if let Ok(content) = std::fs::read_to_string(&path) {
+                self.write(&path, &content)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every file, by path.
+    pub fn files(&self) -> BTreeMap<PathBuf, String> {
+        self.state().files.clone()
+    }
+
+    /// The files under `dir`, by path relative to it.
+    pub fn files_under(&self, dir: &Path) -> BTreeMap<PathBuf, String> {
+        let dir = normalize(dir);
+        self.state()
+            .files
+            .iter()
+            .filter_map(|(path, content)| {
+                let relative = path.strip_prefix(&dir).ok()?;
+                Some((relative.to_path_buf(), content.clone()))
+            })
+            .collect()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl MemoryState {
+    fn is_dir(&self, path: &Path) -> bool {
+        path.parent().is_none() || path.as_os_str().is_empty() || self.dirs.contains(path)
+    }
+}
+
+impl ProjectFs for MemoryFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let path = normalize(path);
+        self.state()
+            .files
+            .get(&path)
+            .cloned()
+            .ok_or_else(|| not_found(&path))
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        let path = normalize(path);
+        let mut state = self.state();
+        if state.is_dir(&path) {
+            return Err(io::Error::other(format!("{} is a directory", path.display())));
+        }
+        let parent = path.parent().unwrap_or(Path::new(""));
+        if !state.is_dir(parent) {
+            return Err(not_found(parent));
+        }
+        state.files.insert(path, contents.to_string());
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let path = normalize(path);
+        let mut state = self.state();
+        for dir in path.ancestors() {
+            if state.files.contains_key(dir) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} is a file", dir.display()),
+                ));
+            }
+        }
+        for dir in path.ancestors().filter(|d| !state.is_dir(d)).collect::<Vec<_>>() {
+            state.dirs.insert(dir.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let path = normalize(path);
+        let state = self.state();
+        if !state.is_dir(&path) {
+            return Err(not_found(&path));
+        }
+        let children: BTreeSet<PathBuf> = state
+            .files
+            .keys()
+            .chain(state.dirs.iter())
+            .filter(|p| p.parent() == Some(path.as_path()))
+            .cloned()
+            .collect();
+        Ok(children.into_iter().collect())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let path = normalize(path);
+        match self.state().files.remove(&path) {
+            Some(_) => Ok(()),
+            None => Err(not_found(&path)),
+        }
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let path = normalize(path);
+        let mut state = self.state();
+        if !state.dirs.contains(&path) {
+            return Err(not_found(&path));
+        }
+        let has_children = state
+            .files
+            .keys()
+            .chain(state.dirs.iter())
+            .any(|p| p.parent() == Some(path.as_path()));
+        if has_children {
+            return Err(io::Error::other(format!("{} is not empty", path.display())));
+        }
+        state.dirs.remove(&path);
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let path = normalize(path);
+        let mut state = self.state();
+        if !state.dirs.contains(&path) {
+            return Err(not_found(&path));
+        }
+        state.files.retain(|p, _| !p.starts_with(&path));
+        state.dirs.retain(|p| !p.starts_with(&path));
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        let state = self.state();
+        state.files.contains_key(&path) || state.is_dir(&path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.state().is_dir(&normalize(path))
+    }
+}
+
+/// `path` without `.` components, so `./backend/src` and `backend/src`
+/// name the same file.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{}: no such file or directory", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_fs_writes_need_a_parent_directory() {
+        let fs = MemoryFs::new();
+        assert!(fs.write(Path::new("app/src/main.rs"), "fn main() {}").is_err());
+
+        fs.create_dir_all(Path::new("app/src")).unwrap();
+        fs.write(Path::new("app/src/main.rs"), "fn main() {}").unwrap();
+        assert_eq!(fs.read_to_string(Path::new("./app/src/main.rs")).unwrap(), "fn main() {}");
+        assert!(fs.is_dir(Path::new("app")));
+        assert!(fs.exists(Path::new("app/src/main.rs")));
+        assert!(!fs.is_dir(Path::new("app/src/main.rs")));
+    }
+
+    #[test]
+    fn memory_fs_lists_and_removes_entries() {
+        let fs = MemoryFs::new();
+        fs.create_dir_all(Path::new("app/src/handlers")).unwrap();
+        fs.write(Path::new("app/src/main.rs"), "").unwrap();
+        fs.write(Path::new("app/src/handlers/mod.rs"), "").unwrap();
+
+        assert_eq!(
+            fs.read_dir(Path::new("app/src")).unwrap(),
+            vec![PathBuf::from("app/src/handlers"), PathBuf::from("app/src/main.rs")]
+        );
+        assert!(fs.read_dir(Path::new("app/missing")).is_err());
+        assert!(fs.remove_dir(Path::new("app/src/handlers")).is_err());
+
+        fs.remove_file(Path::new("app/src/handlers/mod.rs")).unwrap();
+        fs.remove_dir(Path::new("app/src/handlers")).unwrap();
+        assert!(!fs.exists(Path::new("app/src/handlers")));
+
+        fs.remove_dir_all(Path::new("app")).unwrap();
+        assert!(fs.files().is_empty());
+        assert!(!fs.exists(Path::new("app/src")));
+    }
+
+    #[test]
+    fn memory_fs_loads_a_directory_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/react")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn f() {}").unwrap();
+        std::fs::write(dir.path().join("node_modules/react/index.js"), "").unwrap();
+
+        let fs = MemoryFs::load(dir.path()).unwrap();
+        fs.write(&dir.path().join("src/new.rs"), "").unwrap();
+
+        let files = fs.files_under(dir.path());
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec![Path::new("src/lib.rs"), Path::new("src/new.rs")]
+        );
+        assert!(!dir.path().join("src/new.rs").exists());
+    }
+}
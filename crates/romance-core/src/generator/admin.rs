@@ -1,40 +1,38 @@
 use crate::generator::context::markers;
+use crate::inject;
+use crate::project::Project;
 use crate::relation;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use colored::Colorize;
 use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
-use std::fs;
-use std::path::Path;
 use tera::Context;
 
-pub fn generate() -> Result<()> {
-    let project_dir = Path::new(".");
-
-    if !project_dir.join("romance.toml").exists() {
-        anyhow::bail!("Not a Romance project (romance.toml not found)");
-    }
+pub fn generate(project: &Project) -> Result<()> {
+    let project_dir = project.root();
+    let fs = project.fs();
 
     // Check auth is generated
-    if !project_dir.join("backend/src/auth.rs").exists() {
+    if !fs.exists(&project_dir.join("backend/src/auth.rs")) {
         anyhow::bail!(
             "Auth must be generated first. Run: romance generate auth"
         );
     }
 
     // Check idempotency
-    if project_dir.join("frontend/src/admin/AdminLayout.tsx").exists() {
+    if fs.exists(&project_dir.join("frontend/src/admin/AdminLayout.tsx")) {
         anyhow::bail!("Admin already generated (frontend/src/admin/AdminLayout.tsx exists)");
     }
 
-    println!("{}", "Generating admin panel...".bold());
+    say!("{}", "Generating admin panel...".bold());
 
-    let config = crate::config::RomanceConfig::load(project_dir)?;
-    let engine = TemplateEngine::new()?;
+    let config = project.config();
+    let engine = TemplateEngine::for_project(project)?;
 
     // Discover entities
-    let entity_names = relation::discover_entities(project_dir)?;
+    let entity_names = relation::discover_entities(project)?;
 
     let mut ctx = Context::new();
     ctx.insert("project_name", &config.project.name);
@@ -50,8 +48,8 @@ pub fn generate() -> Result<()> {
             }
             // Skip junction tables (no ROMANCE:CUSTOM marker)
             let model_path = entities_dir.join(format!("{}.rs", n));
-            if model_path.exists() {
-                if let Ok(content) = fs::read_to_string(&model_path) {
+            if fs.exists(&model_path) {
+                if let Ok(content) = fs.read_to_string(&model_path) {
                     return content.contains("ROMANCE:CUSTOM");
                 }
             }
@@ -71,52 +69,57 @@ pub fn generate() -> Result<()> {
     let admin_dir = project_dir.join("frontend/src/admin");
 
     let content = engine.render("admin/frontend/AdminLayout.tsx.tera", &ctx)?;
-    utils::write_file(&admin_dir.join("AdminLayout.tsx"), &content)?;
-    println!("  {} frontend/src/admin/AdminLayout.tsx", "create".green());
+    utils::write_file(fs, &admin_dir.join("AdminLayout.tsx"), &content)?;
+    utils::ui::created("frontend/src/admin/AdminLayout.tsx");
 
     let content = engine.render("admin/frontend/Dashboard.tsx.tera", &ctx)?;
-    utils::write_file(&admin_dir.join("Dashboard.tsx"), &content)?;
-    println!("  {} frontend/src/admin/Dashboard.tsx", "create".green());
+    utils::write_file(fs, &admin_dir.join("Dashboard.tsx"), &content)?;
+    utils::ui::created("frontend/src/admin/Dashboard.tsx");
 
     let content = engine.render("admin/frontend/adminRoutes.tsx.tera", &ctx)?;
-    utils::write_file(&admin_dir.join("routes.tsx"), &content)?;
-    println!("  {} frontend/src/admin/routes.tsx", "create".green());
+    utils::write_file(fs, &admin_dir.join("routes.tsx"), &content)?;
+    utils::ui::created("frontend/src/admin/routes.tsx");
 
     // Backend admin routes + handlers
     let content = engine.render("admin/backend/admin_routes.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_dir.join("backend/src/routes/admin.rs"),
         &content,
     )?;
-    println!("  {} backend/src/routes/admin.rs", "create".green());
+    utils::ui::created("backend/src/routes/admin.rs");
 
     let content = engine.render("admin/backend/admin_handlers.rs.tera", &ctx)?;
     utils::write_file(
+        fs,
         &project_dir.join("backend/src/handlers/admin.rs"),
         &content,
     )?;
-    println!("  {} backend/src/handlers/admin.rs", "create".green());
+    utils::ui::created("backend/src/handlers/admin.rs");
 
     // Register via markers
     let base = project_dir.join("backend/src");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("routes/mod.rs"),
         markers::MODS,
         "pub mod admin;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("handlers/mod.rs"),
         markers::MODS,
         "pub mod admin;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("routes/mod.rs"),
         markers::ROUTES,
         "        .merge(admin::router())",
     )?;
 
-    println!();
-    println!("{}", "Admin panel generated successfully!".green().bold());
+    say!();
+    say!("{}", "Admin panel generated successfully!".green().bold());
 
     Ok(())
 }
@@ -1,5 +1,9 @@
-use crate::config::RomanceConfig;
 use crate::generator::context::{self, markers};
+use crate::fs::ProjectFs;
+use crate::inject;
+use crate::manifest::{self, FileCategory, RenderedFile};
+use crate::project::Project;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
@@ -8,102 +12,77 @@ use heck::ToSnakeCase;
 use std::path::Path;
 use tera::Context;
 
-pub fn generate() -> Result<()> {
-    let project_dir = Path::new(".");
-
-    if !project_dir.join("romance.toml").exists() {
-        anyhow::bail!("Not a Romance project (romance.toml not found)");
-    }
+/// Generate authentication into `project`, reloading it afterwards so its
+/// features report auth.
+pub fn generate(project: &mut Project) -> Result<()> {
+    let project_dir = project.root();
+    let fs = project.fs();
 
     // Check idempotency
-    if project_dir.join("backend/src/auth.rs").exists() {
+    if fs.exists(&project_dir.join("backend/src/auth.rs")) {
         anyhow::bail!("Auth already generated (backend/src/auth.rs exists)");
     }
 
-    println!("{}", "Generating authentication...".bold());
+    say!("{}", "Generating authentication...".bold());
 
-    let config = RomanceConfig::load(project_dir)?;
-    let engine = TemplateEngine::new()?;
+    let engine = TemplateEngine::for_project(project)?;
 
-    let timestamp = super::migration::next_timestamp();
+    let timestamp = super::migration::next_timestamp(project);
+
+    let files = rendered_files(project)?;
+    for file in files.iter().filter(|f| f.output.starts_with("backend/")) {
+        utils::write_file(fs, &project_dir.join(&file.output), &file.content)?;
+        utils::ui::created(&file.output);
+    }
 
     let mut ctx = Context::new();
-    ctx.insert("project_name", &config.project.name);
-    ctx.insert("project_name_snake", &config.project.name.to_snake_case());
+    ctx.insert("has_multitenancy", &project.config().has_feature("multitenancy"));
     ctx.insert("timestamp", &timestamp);
-    ctx.insert("has_multitenancy", &config.has_feature("multitenancy"));
-
-    // Backend auth module
-    let content = engine.render("auth/backend/auth.rs.tera", &ctx)?;
-    utils::write_file(&project_dir.join("backend/src/auth.rs"), &content)?;
-    println!("  {} backend/src/auth.rs", "create".green());
-
-    // User entity model
-    let content = engine.render("auth/backend/user_model.rs.tera", &ctx)?;
-    utils::write_file(
-        &project_dir.join("backend/src/entities/user.rs"),
-        &content,
-    )?;
-    println!("  {} backend/src/entities/user.rs", "create".green());
-
-    // Auth handlers
-    let content = engine.render("auth/backend/auth_handlers.rs.tera", &ctx)?;
-    utils::write_file(
-        &project_dir.join("backend/src/handlers/auth.rs"),
-        &content,
-    )?;
-    println!("  {} backend/src/handlers/auth.rs", "create".green());
-
-    // Auth routes
-    let content = engine.render("auth/backend/auth_routes.rs.tera", &ctx)?;
-    utils::write_file(
-        &project_dir.join("backend/src/routes/auth.rs"),
-        &content,
-    )?;
-    println!("  {} backend/src/routes/auth.rs", "create".green());
 
     // User migration
     let content = engine.render("auth/backend/user_migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_create_users_table", timestamp);
     utils::write_file(
+        fs,
         &project_dir.join(format!("backend/migration/src/{}.rs", migration_module)),
         &content,
     )?;
-    println!(
-        "  {} backend/migration/src/{}.rs",
-        "create".green(),
-        migration_module
-    );
+    utils::ui::created(&format!("backend/migration/src/{}.rs", migration_module));
 
     // Register modules via markers
     let base = project_dir.join("backend/src");
 
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("entities/mod.rs"),
         markers::MODS,
         "pub mod user;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("handlers/mod.rs"),
         markers::MODS,
         "pub mod auth;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("routes/mod.rs"),
         markers::MODS,
         "pub mod auth;",
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &base.join("routes/mod.rs"),
         markers::ROUTES,
         "        .merge(auth::router())",
     )?;
 
     // Register migration
-    context::register_migration(project_dir, &migration_module)?;
+    context::register_migration(fs, project_dir, &migration_module)?;
 
     // Add dependencies to Cargo.toml
     insert_cargo_dependency(
+        fs,
         &project_dir.join("backend/Cargo.toml"),
         &[
             ("argon2", r#""0.5""#),
@@ -114,49 +93,35 @@ pub fn generate() -> Result<()> {
     // Add JWT_SECRET to .env and .env.example (random per project)
     let jwt_secret = generate_jwt_secret();
     append_env_var(
+        fs,
         &project_dir.join("backend/.env"),
         &format!("JWT_SECRET={}", jwt_secret),
     )?;
     append_env_var(
+        fs,
         &project_dir.join("backend/.env.example"),
         &format!("JWT_SECRET={}", jwt_secret),
     )?;
 
     // Add mod auth to main.rs
     let main_path = base.join("main.rs");
-    let main_content = std::fs::read_to_string(&main_path)?;
+    let main_content = fs.read_to_string(&main_path)?;
     if !main_content.contains("mod auth;") {
         let new_content = main_content.replace("mod errors;", "mod auth;\nmod errors;");
-        std::fs::write(&main_path, new_content)?;
+        fs.write(&main_path, &new_content)?;
     }
 
     // Frontend auth files
-    let auth_dir = project_dir.join("frontend/src/features/auth");
-
-    let frontend_files = vec![
-        ("auth/frontend/types.ts.tera", "types.ts"),
-        ("auth/frontend/api.ts.tera", "api.ts"),
-        ("auth/frontend/hooks.ts.tera", "hooks.ts"),
-        ("auth/frontend/AuthContext.tsx.tera", "AuthContext.tsx"),
-        ("auth/frontend/LoginPage.tsx.tera", "LoginPage.tsx"),
-        ("auth/frontend/RegisterPage.tsx.tera", "RegisterPage.tsx"),
-        ("auth/frontend/ProtectedRoute.tsx.tera", "ProtectedRoute.tsx"),
-    ];
-
-    for (template, output) in &frontend_files {
-        let content = engine.render(template, &ctx)?;
-        utils::write_file(&auth_dir.join(output), &content)?;
-        println!(
-            "  {} frontend/src/features/auth/{}",
-            "create".green(),
-            output
-        );
+    for file in files.iter().filter(|f| f.output.starts_with("frontend/")) {
+        utils::write_file(fs, &project_dir.join(&file.output), &file.content)?;
+        utils::ui::created(&file.output);
     }
+    manifest::track_generated(project, &files)?;
 
     // Create a minimal user API module for FK dropdown support
     // (entities with FK references to User need userApi.list())
     let user_api_path = project_dir.join("frontend/src/features/user/api.ts");
-    if !user_api_path.exists() {
+    if !fs.exists(&user_api_path) {
         let user_api_content = r#"import { apiFetch, apiFetchPaginated } from '@/lib/utils';
 
 export interface User {
@@ -184,25 +149,65 @@ export const userApi = {
     apiFetch<User>(`/auth/users/${id}`),
 };
 "#;
-        utils::write_file(&user_api_path, user_api_content)?;
-        println!(
-            "  {} frontend/src/features/user/api.ts",
-            "create".green()
-        );
+        utils::write_file(fs, &user_api_path, user_api_content)?;
+        utils::ui::created("frontend/src/features/user/api.ts");
     }
 
-    println!();
-    println!("{}", "Authentication generated successfully!".green().bold());
-    println!();
-    println!("Next steps:");
-    println!("  cd backend && cargo check");
-    println!("  romance db migrate");
+    say!();
+    say!("{}", "Authentication generated successfully!".green().bold());
+    say!();
+    say!("Next steps:");
+    say!("  cd backend && cargo check");
+    say!("  romance db migrate");
 
-    Ok(())
+    project.reload()
+}
+
+/// Render the auth module, user model, auth handlers and routes and the
+/// frontend auth feature with the project's current features, keyed by
+/// output path. The users migration is not included.
+pub fn rendered_files(project: &Project) -> Result<Vec<RenderedFile>> {
+    let config = project.config();
+    let engine = TemplateEngine::for_project(project)?;
+
+    let mut ctx = Context::new();
+    ctx.insert("project_name", &config.project.name);
+    ctx.insert("project_name_snake", &config.project.name.to_snake_case());
+    ctx.insert("has_multitenancy", &config.has_feature("multitenancy"));
+
+    let files = [
+        ("auth/backend/auth.rs.tera", "backend/src/auth.rs"),
+        ("auth/backend/user_model.rs.tera", "backend/src/entities/user.rs"),
+        ("auth/backend/auth_handlers.rs.tera", "backend/src/handlers/auth.rs"),
+        ("auth/backend/auth_routes.rs.tera", "backend/src/routes/auth.rs"),
+        ("auth/frontend/types.ts.tera", "frontend/src/features/auth/types.ts"),
+        ("auth/frontend/api.ts.tera", "frontend/src/features/auth/api.ts"),
+        ("auth/frontend/hooks.ts.tera", "frontend/src/features/auth/hooks.ts"),
+        ("auth/frontend/AuthContext.tsx.tera", "frontend/src/features/auth/AuthContext.tsx"),
+        ("auth/frontend/LoginPage.tsx.tera", "frontend/src/features/auth/LoginPage.tsx"),
+        ("auth/frontend/RegisterPage.tsx.tera", "frontend/src/features/auth/RegisterPage.tsx"),
+        ("auth/frontend/ProtectedRoute.tsx.tera", "frontend/src/features/auth/ProtectedRoute.tsx"),
+    ];
+    files
+        .into_iter()
+        .map(|(template, output)| {
+            Ok(RenderedFile {
+                output: output.to_string(),
+                template: Some(template.to_string()),
+                category: FileCategory::Auth,
+                entity_name: None,
+                content: engine.render(template, &ctx)?,
+            })
+        })
+        .collect()
 }
 
-pub fn insert_cargo_dependency(path: &Path, deps: &[(&str, &str)]) -> Result<()> {
-    let content = std::fs::read_to_string(path)?;
+pub fn insert_cargo_dependency(
+    fs: &dyn ProjectFs,
+    path: &Path,
+    deps: &[(&str, &str)],
+) -> Result<()> {
+    let content = fs.read_to_string(path)?;
     let mut new_content = content.clone();
 
     for (name, version) in deps {
@@ -221,7 +226,7 @@ pub fn insert_cargo_dependency(path: &Path, deps: &[(&str, &str)]) -> Result<()>
         }
     }
 
-    std::fs::write(path, new_content)?;
+    fs.write(path, &new_content)?;
     Ok(())
 }
 
@@ -299,15 +304,15 @@ fn merge_features_into_dep(content: &str, dep_name: &str, new_features: &[String
     }
 }
 
-pub fn append_env_var(path: &Path, line: &str) -> Result<()> {
-    if let Ok(content) = std::fs::read_to_string(path) {
+pub fn append_env_var(fs: &dyn ProjectFs, path: &Path, line: &str) -> Result<()> {
+    if let Ok(content) = fs.read_to_string(path) {
         // Check by key (everything before '=') to avoid duplicates
         let key = line.split('=').next().unwrap_or(line);
         if content.lines().any(|l| l.starts_with(&format!("{}=", key))) {
             return Ok(());
         }
         let new_content = format!("{}\n{}\n", content.trim_end(), line);
-        std::fs::write(path, new_content)?;
+        fs.write(path, &new_content)?;
     }
     Ok(())
 }
@@ -324,6 +329,7 @@ pub fn generate_jwt_secret() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
 
     #[test]
     fn extract_features_basic() {
@@ -398,6 +404,7 @@ axum = { version = "0.8", features = ["json"] }
         .unwrap();
 
         insert_cargo_dependency(
+            &DiskFs,
             &cargo_path,
             &[("axum", r#"{ version = "0.8", features = ["json", "multipart"] }"#)],
         )
@@ -1,33 +1,47 @@
 use crate::entity::{EntityDefinition, FieldType, FieldVisibility, RelationType, ValidationRule};
-use crate::generator::context::{self, markers, ProjectFeatures};
+use crate::generator::context::{self, markers};
 use crate::generator::junction;
 use crate::generator::plan::{self, GenerationTracker};
+use crate::inject;
+use crate::manifest::{self, FileCategory, RenderedFile};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
 use crate::relation;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use heck::{ToPascalCase, ToSnakeCase};
-use std::path::Path;
+use std::path::PathBuf;
 use tera::Context;
 
 /// Pre-validate that all required markers exist before generation.
-pub fn validate(_entity: &EntityDefinition) -> Result<()> {
-    let base = Path::new("backend/src");
-    let mut checks = vec![
-        plan::check(base.join("routes/mod.rs"), markers::ROUTES),
-        plan::check(base.join("routes/mod.rs"), markers::MODS),
-        plan::check(base.join("entities/mod.rs"), markers::MODS),
-        plan::check(base.join("handlers/mod.rs"), markers::MODS),
-    ];
-
-    let seed_path = Path::new("backend/src/seed.rs");
-    if seed_path.exists() {
+pub fn validate(project: &Project, entity: &EntityDefinition) -> Result<()> {
+    let fs = project.fs();
+    let base = project.backend_src();
+    let mut checks = match module::of_entity(project, entity)? {
+        Some(m) => vec![
+            plan::check(m.mod_file(&base, "routes"), markers::ROUTES),
+            plan::check(m.mod_file(&base, "routes"), markers::MODS),
+            plan::check(m.mod_file(&base, "entities"), markers::MODS),
+            plan::check(m.mod_file(&base, "handlers"), markers::MODS),
+        ],
+        None => vec![
+            plan::check(base.join("routes/mod.rs"), markers::ROUTES),
+            plan::check(base.join("routes/mod.rs"), markers::MODS),
+            plan::check(base.join("entities/mod.rs"), markers::MODS),
+            plan::check(base.join("handlers/mod.rs"), markers::MODS),
+        ],
+    };
+
+    let seed_path = base.join("seed.rs");
+    if fs.exists(&seed_path) {
         checks.push(plan::check(seed_path, markers::SEEDS));
     }
 
     let main_rs = base.join("main.rs");
-    if main_rs.exists() {
-        let content = std::fs::read_to_string(&main_rs).unwrap_or_default();
+    if fs.exists(&main_rs) {
+        let content = fs.read_to_string(&main_rs).unwrap_or_default();
         if content.contains(markers::OPENAPI_PATHS) {
             checks.push(plan::check(&main_rs, markers::OPENAPI_PATHS));
             checks.push(plan::check(&main_rs, markers::OPENAPI_SCHEMAS));
@@ -35,56 +49,53 @@ pub fn validate(_entity: &EntityDefinition) -> Result<()> {
         }
     }
 
-    plan::validate_markers(&checks)
+    plan::validate_markers(fs, &checks)
 }
 
-pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> Result<()> {
-    let engine = TemplateEngine::new()?;
-    let ctx = build_context(entity);
-    let snake_name = entity.name.to_snake_case();
-
-    let base = Path::new("backend/src");
-
-    // Generate model
-    let model_content = engine.render("entity/backend/model.rs.tera", &ctx)?;
-    let model_path = base.join(format!("entities/{}.rs", snake_name));
-    utils::write_generated(&model_path, &model_content)?;
-    tracker.track(model_path.to_path_buf());
+pub fn generate(
+    project: &Project,
+    entity: &EntityDefinition,
+    tracker: &mut GenerationTracker,
+) -> Result<()> {
 
-    // Generate handlers
-    let handlers_content = engine.render("entity/backend/handlers.rs.tera", &ctx)?;
-    let handlers_path = base.join(format!("handlers/{}.rs", snake_name));
-    utils::write_generated(&handlers_path, &handlers_content)?;
-    tracker.track(handlers_path.to_path_buf());
+    let fs = project.fs();
+    let files = rendered_files(project, entity)?;
+    for file in &files {
+        let path = project.join(&file.output);
+        utils::write_generated(fs, &path, &file.content)?;
+        utils::ui::created(&file.output);
+        tracker.track(path);
+    }
+    manifest::track_generated(project, &files)?;
+    let snake_name = entity.name.to_snake_case();
+    let entity_module = module::of_entity(project, entity)?;
+    let entity_path = module::rust_path(entity_module.as_ref(), &snake_name);
 
-    // Generate routes
-    let routes_content = engine.render("entity/backend/routes.rs.tera", &ctx)?;
-    let routes_path = base.join(format!("routes/{}.rs", snake_name));
-    utils::write_generated(&routes_path, &routes_content)?;
-    tracker.track(routes_path.to_path_buf());
+    let base = project.backend_src();
 
     // Register module in entities/handlers/routes mod.rs files
-    context::register_backend_module(base, &snake_name)?;
+    module::register_entity(fs, &base, entity_module.as_ref(), &snake_name)?;
 
     // Register entity in OpenAPI spec (only if OpenAPI markers are present)
     let main_rs = base.join("main.rs");
-    let main_has_openapi = main_rs.exists()
-        && std::fs::read_to_string(&main_rs)
+    let main_has_openapi = fs.exists(&main_rs)
+        && fs.read_to_string(&main_rs)
             .map(|c| c.contains(markers::OPENAPI_PATHS))
             .unwrap_or(false);
     if main_has_openapi {
         // Add handler paths
         let paths = vec![
-            format!("crate::handlers::{}::list", snake_name),
-            format!("crate::handlers::{}::get", snake_name),
-            format!("crate::handlers::{}::create", snake_name),
-            format!("crate::handlers::{}::update", snake_name),
-            format!("crate::handlers::{}::delete", snake_name),
-            format!("crate::handlers::{}::bulk_create", snake_name),
-            format!("crate::handlers::{}::bulk_delete", snake_name),
+            format!("crate::handlers::{}::list", entity_path),
+            format!("crate::handlers::{}::get", entity_path),
+            format!("crate::handlers::{}::create", entity_path),
+            format!("crate::handlers::{}::update", entity_path),
+            format!("crate::handlers::{}::delete", entity_path),
+            format!("crate::handlers::{}::bulk_create", entity_path),
+            format!("crate::handlers::{}::bulk_delete", entity_path),
         ];
         for path in &paths {
-            utils::insert_at_marker(
+            inject::insert(
+                fs,
                 &main_rs,
                 markers::OPENAPI_PATHS,
                 &format!("        {},", path),
@@ -93,38 +104,40 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
 
         // Add schema types
         let schemas = vec![
-            format!("crate::entities::{}::Model", snake_name),
-            format!("crate::entities::{}::Create{}", snake_name, entity.name),
-            format!("crate::entities::{}::Update{}", snake_name, entity.name),
+            format!("crate::entities::{}::Model", entity_path),
+            format!("crate::entities::{}::Create{}", entity_path, entity.name),
+            format!("crate::entities::{}::Update{}", entity_path, entity.name),
             format!(
                 "crate::entities::{}::{}Response",
-                snake_name, entity.name
+                entity_path, entity.name
             ),
         ];
         for schema in &schemas {
-            utils::insert_at_marker(
+            inject::insert(
+                fs,
                 &main_rs,
                 markers::OPENAPI_SCHEMAS,
                 &format!("            {},", schema),
             )?;
         }
 
-        // Add tag
-        utils::insert_at_marker(
-            &main_rs,
-            markers::OPENAPI_TAGS,
-            &format!(
-                "        (name = \"{}\", description = \"{} management\"),",
-                entity.name, entity.name
-            ),
-        )?;
+        // Add tag; module entities share the module's, added with the module
+        if entity_module.is_none() {
+            inject::insert(
+                fs,
+                &main_rs,
+                markers::OPENAPI_TAGS,
+                &format!(
+                    "        (name = \"{}\", description = \"{} management\"),",
+                    entity.name, entity.name
+                ),
+            )?;
+        }
     }
 
     // Handle reverse relations: inject has-many into target entities
     // Note: junction (M2M) generation is deferred to generate_relations()
     // to ensure the entity migration runs first.
-    let project_root = Path::new(".");
-    let features = ProjectFeatures::load(project_root);
     for rel in &entity.relations {
         if rel.relation_type == RelationType::BelongsTo {
             // Skip self-referential FKs — the Related impl is already generated
@@ -133,13 +146,13 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
             if rel.target_entity.to_snake_case() == entity.name.to_snake_case() {
                 continue;
             }
-            if relation::entity_exists(project_root, &rel.target_entity) {
+            if relation::entity_exists(project, &rel.target_entity) {
                 inject_has_many(
-                    base,
+                    project,
                     &rel.target_entity,
                     &entity.name,
+                    entity_module.as_ref(),
                     &rel.fk_column.clone().unwrap_or_else(|| format!("{}_id", rel.target_entity.to_snake_case())),
-                    &features.api_prefix,
                 )?;
             }
         }
@@ -148,32 +161,105 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
     utils::ui::success(&format!("Generated backend files for '{}'", entity.name));
 
     // Insert seed function if seed.rs exists
-    let seed_path = Path::new("backend/src/seed.rs");
-    if seed_path.exists() {
-        let seed_fn = build_seed_function(entity);
-        utils::insert_at_marker(seed_path, markers::SEEDS, &seed_fn)?;
+    let seed_path = base.join("seed.rs");
+    if fs.exists(&seed_path) {
+        let seed_fn = build_seed_function(entity, entity_module.as_ref());
+        inject::insert(fs, &seed_path, markers::SEEDS, &seed_fn)?;
     }
 
     Ok(())
 }
 
+/// Render the entity's model, handlers and routes, keyed by output path
+/// (relative to the project root).
+pub fn render_files(project: &Project, entity: &EntityDefinition) -> Result<Vec<(PathBuf, String)>> {
+    Ok(rendered_files(project, entity)?
+        .into_iter()
+        .map(|file| (PathBuf::from(file.output), file.content))
+        .collect())
+}
+
+/// Like [`render_files`], with the template and manifest category of each file.
+pub fn rendered_files(project: &Project, entity: &EntityDefinition) -> Result<Vec<RenderedFile>> {
+    let engine = TemplateEngine::for_project(project)?;
+    let entity_module = module::of_entity(project, entity)?;
+    let ctx = build_context(project, entity, entity_module.as_ref());
+    let snake_name = entity.name.to_snake_case();
+
+    [
+        ("entity/backend/model.rs.tera", "entities"),
+        ("entity/backend/handlers.rs.tera", "handlers"),
+        ("entity/backend/routes.rs.tera", "routes"),
+    ]
+    .into_iter()
+    .map(|(template, dir)| {
+        Ok(RenderedFile {
+            output: module::backend_file(entity_module.as_ref(), dir, &snake_name),
+            template: Some(template.to_string()),
+            category: FileCategory::Entity,
+            entity_name: Some(entity.name.clone()),
+            content: engine.render(template, &ctx)?,
+        })
+    })
+    .collect()
+}
+
+/// Re-render an existing entity's backend files from a changed definition.
+///
+/// `previous` is what [`render_files`] produced for the files as they were
+/// last generated; relation code injected into them since then is kept, as
+/// is everything below `ROMANCE:CUSTOM`. Returns the files written, under
+/// the project root.
+pub fn refresh(
+    project: &Project,
+    entity: &EntityDefinition,
+    previous: &[(PathBuf, String)],
+) -> Result<Vec<PathBuf>> {
+    let fs = project.fs();
+    let files = rendered_files(project, entity)?;
+    let mut written = Vec::new();
+    for file in &files {
+        let path = PathBuf::from(&file.output);
+        let target = project.join(&path);
+        match previous.iter().find(|(p, _)| *p == path) {
+            Some((_, before)) => utils::write_rerendered(fs, &target, before, &file.content)?,
+            None => utils::write_generated(fs, &target, &file.content)?,
+        }
+        written.push(target);
+    }
+    manifest::track_generated(project, &files)?;
+    Ok(written)
+}
+
+/// Replace the entity's seed function in `seed.rs` (if it has one) with one
+/// built from the current definition. Returns `true` if the file changed.
+pub fn refresh_seed(project: &Project, entity: &EntityDefinition) -> Result<bool> {
+    let fs = project.fs();
+    let seed_path = project.backend_src().join("seed.rs");
+    let seed_fn = format!("seed_{}s", entity.name.to_snake_case());
+    if !inject::remove_stmts(fs, &seed_path, |stmt| inject::mentions(stmt, &seed_fn))? {
+        return Ok(false);
+    }
+    let entity_module = module::of_entity(project, entity)?;
+    inject::insert(fs, &seed_path, markers::SEEDS, &build_seed_function(entity, entity_module.as_ref()))?;
+    Ok(true)
+}
+
 /// Generate M2M junction tables and apply pending relations.
 /// Must be called AFTER migration::generate() to ensure correct migration order.
-pub fn generate_relations(entity: &EntityDefinition) -> Result<()> {
-    let project_root = Path::new(".");
-
+pub fn generate_relations(project: &Project, entity: &EntityDefinition) -> Result<()> {
     for rel in &entity.relations {
         if rel.relation_type == RelationType::ManyToMany {
-            junction::generate(&entity.name, &rel.target_entity)?;
+            junction::generate(project, &entity.name, &rel.target_entity)?;
         }
     }
 
     // Apply any pending relations that target this newly generated entity
-    let pending = relation::take_pending_for(project_root, &entity.name)?;
+    let pending = relation::take_pending_for(project, &entity.name)?;
     for p in &pending {
         if p.relation_type == "ManyToMany" {
-            junction::generate(&p.source_entity, &p.target_entity)?;
-            println!("  Applied pending M2M: {} <-> {}", p.source_entity, p.target_entity);
+            junction::generate(project, &p.source_entity, &p.target_entity)?;
+            say!("  Applied pending M2M: {} <-> {}", p.source_entity, p.target_entity);
         }
     }
 
@@ -186,15 +272,19 @@ pub fn generate_relations(entity: &EntityDefinition) -> Result<()> {
 /// 2. list_posts handler in user handlers
 /// 3. /users/:id/posts route in user routes
 fn inject_has_many(
-    base: &Path,
+    project: &Project,
     parent_entity: &str,
     child_entity: &str,
+    child_module: Option<&EntityModule>,
     fk_column: &str,
-    api_prefix: &str,
 ) -> Result<()> {
+    let fs = project.fs();
     let parent_snake = parent_entity.to_snake_case();
     let child_snake = child_entity.to_snake_case();
     let fk_pascal = fk_column.to_pascal_case();
+    let parent_module = module::locate(project, parent_entity);
+    let child_model = module::model_path(parent_module.as_ref(), child_module, &child_snake);
+    let child_path = module::rust_path(child_module, &child_snake);
 
     // Derive the Relation variant name from the FK column (matches model template)
     let fk_base = fk_column.strip_suffix("_id").unwrap_or(fk_column);
@@ -204,19 +294,19 @@ fn inject_has_many(
     // Only inject if Related<child::Entity> doesn't already exist — when multiple FKs
     // from child to parent exist (e.g., creator_id + assignee_id → User), only the first
     // Related impl should be generated (Rust allows only one impl per trait+type pair).
-    let model_path = base.join(format!("entities/{}.rs", parent_snake));
-    let related_check = format!("impl Related<super::{}::Entity> for Entity", child_snake);
-    let model_content = std::fs::read_to_string(&model_path).unwrap_or_default();
+    let model_path = project.join(module::backend_file(parent_module.as_ref(), "entities", &parent_snake));
+    let related_check = format!("impl Related<{}::Entity> for Entity", child_model);
+    let model_content = fs.read_to_string(&model_path).unwrap_or_default();
     if !model_content.contains(&related_check) {
         let related_impl = format!(
-            r#"impl Related<super::{}::Entity> for Entity {{
+            r#"impl Related<{}::Entity> for Entity {{
     fn to() -> RelationDef {{
-        super::{}::Relation::{}.def().rev()
+        {}::Relation::{}.def().rev()
     }}
 }}"#,
-            child_snake, child_snake, relation_variant
+            child_model, child_model, relation_variant
         );
-        utils::insert_at_marker(&model_path, markers::RELATIONS, &related_impl)?;
+        inject::insert(fs, &model_path, markers::RELATIONS, &related_impl)?;
     }
 
     // 2. Inject list handler into parent handlers
@@ -232,11 +322,11 @@ fn inject_has_many(
 
     // Skip handler/route injection if parent doesn't have its own handlers/routes files.
     // This happens for auth-generated entities (User) where handlers live in auth.rs.
-    let handlers_path = base.join(format!("handlers/{}.rs", parent_snake));
-    let routes_path = base.join(format!("routes/{}.rs", parent_snake));
+    let handlers_path = project.join(module::backend_file(parent_module.as_ref(), "handlers", &parent_snake));
+    let routes_path = project.join(module::backend_file(parent_module.as_ref(), "routes", &parent_snake));
 
-    if !handlers_path.exists() || !routes_path.exists() {
-        println!(
+    if !fs.exists(&handlers_path) || !fs.exists(&routes_path) {
+        say!(
             "  Injected has-many: {} -> {} (via {}) [model only — no handlers/routes file]",
             parent_entity, utils::pluralize(child_entity), fk_column
         );
@@ -248,14 +338,14 @@ fn inject_has_many(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
     Query(params): Query<crate::pagination::PageRequest>,
-) -> crate::errors::AppResult<crate::api::ApiResponse<Vec<crate::entities::{child_snake}::Model>>> {{
+) -> crate::errors::AppResult<crate::api::ApiResponse<Vec<crate::entities::{child_path}::Model>>> {{
     use crate::api::ok_page;
     use crate::pagination::PageMeta;
 
     let page = params.page();
     let per_page = params.per_page();
-    let paginator = crate::entities::{child_snake}::Entity::find()
-        .filter(crate::entities::{child_snake}::Column::{fk_pascal}.eq(id))
+    let paginator = crate::entities::{child_path}::Entity::find()
+        .filter(crate::entities::{child_path}::Column::{fk_pascal}.eq(id))
         .paginate(&state.db, per_page);
     let total = paginator.num_items().await?;
     let data = paginator.fetch_page(page - 1).await?;
@@ -263,10 +353,11 @@ fn inject_has_many(
     Ok(ok_page(data, meta))
 }}"#,
         handler_name = handler_name,
-        child_snake = child_snake,
+        child_path = child_path,
         fk_pascal = fk_pascal,
     );
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &handlers_path,
         markers::RELATION_HANDLERS,
         &handler_code,
@@ -284,15 +375,20 @@ fn inject_has_many(
 
     let route_line = format!(
         "        .route(\"{}/{}/{{id}}/{}\", get({}::{}))",
-        api_prefix, parent_plural, url_suffix, parent_snake, handler_name
+        module::route_prefix(parent_module.as_ref(), &project.features().api_prefix),
+        parent_plural,
+        url_suffix,
+        parent_snake,
+        handler_name
     );
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &routes_path,
         markers::RELATION_ROUTES,
         &route_line,
     )?;
 
-    println!(
+    say!(
         "  Injected has-many: {} -> {} (via {})",
         parent_entity, utils::pluralize(child_entity), fk_column
     );
@@ -304,8 +400,9 @@ fn inject_has_many(
 /// Generates a `seed_{entity}s()` async function that uses the `fake` crate
 /// to insert randomised rows, plus a call line to invoke it from `run()`.
 /// Both are inserted before the `ROMANCE:SEEDS` marker in `seed.rs`.
-fn build_seed_function(entity: &EntityDefinition) -> String {
+fn build_seed_function(entity: &EntityDefinition, entity_module: Option<&EntityModule>) -> String {
     let snake = entity.name.to_snake_case();
+    let entity_path = module::rust_path(entity_module, &snake);
 
     // Build field assignment lines. We skip:
     //  - fields with a relation (FK fields) — handled by ..Default::default()
@@ -330,7 +427,7 @@ fn build_seed_function(entity: &EntityDefinition) -> String {
     use fake::faker::lorem::en::*;
     use fake::faker::name::en::*;
     use fake::faker::internet::en::*;
-    use crate::entities::{snake}::ActiveModel;
+    use crate::entities::{entity_path}::ActiveModel;
     use sea_orm::Set;
 
     for _ in 0..count {{
@@ -349,6 +446,7 @@ fn build_seed_function(entity: &EntityDefinition) -> String {
 
 seed_{snake}s(db, 10).await?;"#,
         snake = snake,
+        entity_path = entity_path,
         fields_block = fields_block,
     )
 }
@@ -372,13 +470,25 @@ fn field_type_to_faker(ft: &FieldType) -> String {
     }
 }
 
-fn build_context(entity: &EntityDefinition) -> Context {
+fn build_context(project: &Project, entity: &EntityDefinition, entity_module: Option<&EntityModule>) -> Context {
     let mut ctx = Context::new();
+    let snake = entity.name.to_snake_case();
     ctx.insert("entity_name", &entity.name);
-    ctx.insert("entity_name_snake", &entity.name.to_snake_case());
+    ctx.insert("entity_name_snake", &snake);
+    ctx.insert("entity_path", &module::rust_path(entity_module, &snake));
+    ctx.insert(
+        "table_name",
+        &entity.table_name.clone().unwrap_or_else(|| module::table_name(entity_module, &snake)),
+    );
+    ctx.insert("schema_name", &entity_module.and_then(EntityModule::schema));
+    ctx.insert("tenant_path", &module::model_path(entity_module, None, "tenant"));
+    ctx.insert(
+        "openapi_tag",
+        &entity_module.map(EntityModule::title).unwrap_or_else(|| entity.name.clone()),
+    );
 
-    let features = ProjectFeatures::load(Path::new("."));
-    ctx.insert("api_prefix", &features.api_prefix);
+    let features = project.features();
+    ctx.insert("api_prefix", &module::route_prefix(entity_module, &features.api_prefix));
     ctx.insert("soft_delete", &features.soft_delete);
     ctx.insert("has_validation", &features.has_validation);
     ctx.insert("has_search", &features.has_search);
@@ -425,6 +535,10 @@ fn build_context(entity: &EntityDefinition) -> Context {
             } else {
                 false
             };
+            let relation_path = f.relation.as_ref().map(|target| {
+                let target_module = module::of_target(project, entity_module, target);
+                module::model_path(entity_module, target_module.as_ref(), &target.to_snake_case())
+            });
 
             serde_json::json!({
                 "name": f.name,
@@ -435,6 +549,7 @@ fn build_context(entity: &EntityDefinition) -> Context {
                 "optional": f.optional,
                 "relation": f.relation,
                 "relation_variant": relation_variant,
+                "relation_path": relation_path,
                 "is_first_relation_to_target": is_first,
                 "validations": validations,
                 "has_validations": has_validations,
@@ -479,9 +594,11 @@ fn build_context(entity: &EntityDefinition) -> Context {
             // Use FK base name for the detail field (e.g., creator_id -> creator)
             // This avoids duplicate fields when multiple FKs point to the same entity
             let fk_base = f.name.strip_suffix("_id").unwrap_or(&f.name);
+            let target_module = module::of_target(project, entity_module, target);
             serde_json::json!({
                 "target": target,
                 "target_snake": target.to_snake_case(),
+                "target_path": module::rust_path(target_module.as_ref(), &target.to_snake_case()),
                 "detail_field": fk_base.to_snake_case(),
                 "fk_field": f.name,
                 "fk_rust_name": utils::rust_ident(&f.name),
@@ -1,12 +1,14 @@
 use crate::config::RomanceConfig;
 use crate::entity::{FieldType, ValidationRule};
-use crate::utils;
+use crate::fs::ProjectFs;
+use crate::inject;
 use anyhow::Result;
 use std::path::Path;
 
 /// All marker strings used across generators.
 pub mod markers {
     pub const MODS: &str = "// === ROMANCE:MODS ===";
+    pub const MAIN_MODS: &str = "// === ROMANCE:MAIN_MODS ===";
     pub const ROUTES: &str = "// === ROMANCE:ROUTES ===";
     pub const MIGRATION_MODS: &str = "// === ROMANCE:MIGRATION_MODS ===";
     pub const MIGRATIONS: &str = "// === ROMANCE:MIGRATIONS ===";
@@ -17,6 +19,7 @@ pub mod markers {
     pub const IMPORTS: &str = "// === ROMANCE:IMPORTS ===";
     pub const APP_ROUTES: &str = "{/* === ROMANCE:APP_ROUTES === */}";
     pub const NAV_LINKS: &str = "{/* === ROMANCE:NAV_LINKS === */}";
+    pub const NAV_SECTIONS: &str = "{/* === ROMANCE:NAV_SECTIONS === */}";
     pub const OPENAPI_PATHS: &str = "// === ROMANCE:OPENAPI_PATHS ===";
     pub const OPENAPI_SCHEMAS: &str = "// === ROMANCE:OPENAPI_SCHEMAS ===";
     pub const OPENAPI_TAGS: &str = "// === ROMANCE:OPENAPI_TAGS ===";
@@ -38,14 +41,14 @@ pub struct ProjectFeatures {
 impl ProjectFeatures {
     /// Load feature flags from `romance.toml` at `project_root`.
     /// Falls back to defaults if the config file is missing.
-    pub fn load(project_root: &Path) -> Self {
-        let config = RomanceConfig::load(project_root).ok();
+    pub fn load(fs: &dyn ProjectFs, project_root: &Path) -> Self {
+        let config = RomanceConfig::load(fs, project_root).ok();
         let soft_delete = config.as_ref().map(|c| c.has_feature("soft_delete")).unwrap_or(false);
         let has_validation = config.as_ref().map(|c| c.has_feature("validation")).unwrap_or(false);
         let has_search = config.as_ref().map(|c| c.has_feature("search")).unwrap_or(false);
         let has_audit = config.as_ref().map(|c| c.has_feature("audit_log")).unwrap_or(false);
         let has_multitenancy = config.as_ref().map(|c| c.has_feature("multitenancy")).unwrap_or(false);
-        let has_auth = project_root.join("backend/src/auth.rs").exists();
+        let has_auth = fs.exists(&project_root.join("backend/src/auth.rs"));
         let api_prefix = config.as_ref()
             .and_then(|c| c.backend.api_prefix.clone())
             .unwrap_or_else(|| "/api".to_string());
@@ -108,28 +111,32 @@ pub fn is_numeric(field_type: &FieldType) -> bool {
 
 /// Register a backend module: adds `pub mod` to entities/handlers/routes mod.rs
 /// and merges the router in routes/mod.rs.
-pub fn register_backend_module(backend_src: &Path, module_name: &str) -> Result<()> {
+pub fn register_backend_module(fs: &dyn ProjectFs, backend_src: &Path, module_name: &str) -> Result<()> {
     let routes_mod = backend_src.join("routes/mod.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &routes_mod,
         markers::ROUTES,
         &format!("        .merge({module_name}::router())"),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &routes_mod,
         markers::MODS,
         &format!("pub mod {};", module_name),
     )?;
 
     let entities_mod = backend_src.join("entities/mod.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &entities_mod,
         markers::MODS,
         &format!("pub mod {};", module_name),
     )?;
 
     let handlers_mod = backend_src.join("handlers/mod.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &handlers_mod,
         markers::MODS,
         &format!("pub mod {};", module_name),
@@ -139,14 +146,16 @@ pub fn register_backend_module(backend_src: &Path, module_name: &str) -> Result<
 }
 
 /// Register a migration module in `backend/migration/src/lib.rs`.
-pub fn register_migration(project_root: &Path, migration_module: &str) -> Result<()> {
+pub fn register_migration(fs: &dyn ProjectFs, project_root: &Path, migration_module: &str) -> Result<()> {
     let lib_path = project_root.join("backend/migration/src/lib.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         markers::MIGRATION_MODS,
         &format!("mod {};", migration_module),
     )?;
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &lib_path,
         markers::MIGRATIONS,
         &format!("            Box::new({}::Migration),", migration_module),
@@ -157,6 +166,7 @@ pub fn register_migration(project_root: &Path, migration_module: &str) -> Result
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
     use std::io::Write;
 
     #[test]
@@ -244,7 +254,7 @@ mod tests {
         let mut f = std::fs::File::create(base.join("handlers/mod.rs")).unwrap();
         writeln!(f, "// === ROMANCE:MODS ===").unwrap();
 
-        register_backend_module(base, "product").unwrap();
+        register_backend_module(&DiskFs, base, "product").unwrap();
 
         let routes = std::fs::read_to_string(base.join("routes/mod.rs")).unwrap();
         assert!(routes.contains("pub mod product;"));
@@ -271,7 +281,7 @@ mod tests {
         std::fs::write(base.join("entities/mod.rs"), "// === ROMANCE:MODS ===\n").unwrap();
         std::fs::write(base.join("handlers/mod.rs"), "// === ROMANCE:MODS ===\n").unwrap();
 
-        let result = register_backend_module(base, "product");
+        let result = register_backend_module(&DiskFs, base, "product");
         assert!(result.is_err());
     }
 
@@ -300,7 +310,7 @@ impl MigratorTrait for Migrator {
         )
         .unwrap();
 
-        register_migration(dir.path(), "m20260216_create_product_table").unwrap();
+        register_migration(&DiskFs, dir.path(), "m20260216_create_product_table").unwrap();
 
         let content =
             std::fs::read_to_string(dir.path().join("backend/migration/src/lib.rs")).unwrap();
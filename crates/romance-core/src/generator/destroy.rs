@@ -0,0 +1,1059 @@
+//! Removing generated code: the inverse of the generators.
+//!
+//! Besides deleting an entity's own files, destroy undoes what generation
+//! injected elsewhere — module declarations, the router merge, OpenAPI
+//! entries, has-many and many-to-many code in related entities, junction
+//! tables, seed functions and frontend routes — and emits a migration that
+//! drops the table. Auth, the admin panel, many-to-many relations and single
+//! fields are undone the same way. Anything that can't safely be removed is
+//! reported instead.
+
+use crate::entity::RelationType;
+use crate::fs::ProjectFs;
+use crate::generator::context::{self, markers};
+use crate::generator::{backend, frontend, migration, recover};
+use crate::inject;
+use crate::manifest::{self, Manifest};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::relation;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::Result;
+use heck::{ToPascalCase, ToSnakeCase};
+use quote::ToTokens;
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+/// What a destroy command changed, and what it left for the user.
+#[derive(Debug, Default)]
+pub struct DestroyReport {
+    /// Files and directories deleted.
+    pub removed: Vec<String>,
+    /// Files edited, with a description of what was taken out.
+    pub cleaned: Vec<(String, String)>,
+    /// Files created (the drop-table migration).
+    pub created: Vec<String>,
+    /// References that could not be removed automatically.
+    pub leftovers: Vec<String>,
+    /// The project root, which reported paths are relative to.
+    root: PathBuf,
+}
+
+impl DestroyReport {
+    fn new(project: &Project) -> Self {
+        Self {
+            root: project.root().to_path_buf(),
+            ..Self::default()
+        }
+    }
+
+    fn show(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root).unwrap_or(path).display().to_string()
+    }
+
+    fn remove_path(&mut self, fs: &dyn ProjectFs, path: &Path) -> Result<()> {
+        if fs.is_dir(path) {
+            fs.remove_dir_all(path)?;
+            self.removed.push(format!("{}/", self.show(path)));
+        } else if fs.exists(path) {
+            fs.remove_file(path)?;
+            self.removed.push(self.show(path));
+        }
+        Ok(())
+    }
+
+    fn cleaned(&mut self, changed: bool, path: &Path, what: impl Into<String>) {
+        if changed {
+            self.cleaned.push((self.show(path), what.into()));
+        }
+    }
+
+    /// Report the re-rendered files whose contents differ from `before`.
+    fn rewritten(
+        &mut self,
+        fs: &dyn ProjectFs,
+        before: &[(PathBuf, String)],
+        written: &[PathBuf],
+        what: &str,
+    ) {
+        for path in written {
+            let unchanged = before
+                .iter()
+                .any(|(p, content)| p == path && fs.read_to_string(path).ok().as_ref() == Some(content));
+            self.cleaned(!unchanged, path, what);
+        }
+    }
+}
+
+/// The current contents of `paths`.
+fn snapshot(fs: &dyn ProjectFs, paths: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    paths
+        .iter()
+        .map(|p| (p.clone(), fs.read_to_string(p).unwrap_or_default()))
+        .collect()
+}
+
+/// A table the drop migration removes.
+struct DroppedTable {
+    name: String,
+    table_ref: String,
+    create_migration: Option<String>,
+}
+
+/// Destroy an entity and everything generation added for it.
+///
+/// Unless `keep_table` is set, a migration dropping the entity's table (and
+/// its junction tables) is generated; rolling it back re-runs the original
+/// create migrations.
+pub fn entity(project: &Project, name: &str, keep_table: bool) -> Result<DestroyReport> {
+    let fs = project.fs();
+    let base = &project.backend_src();
+    let pascal = name.to_pascal_case();
+    let snake = name.to_snake_case();
+    let entity_module = module::locate(project, name);
+    let m = entity_module.as_ref();
+    let entity_path = module::rust_path(m, &snake);
+    let plural = module::url_path(m, &snake);
+    let feature = module::feature_path(m, name);
+
+    let model_path = project.join(module::backend_file(m, "entities", &snake));
+    let feature_dir = project.frontend_src().join("features").join(&feature);
+    if !fs.exists(&model_path) && !fs.exists(&feature_dir) {
+        anyhow::bail!(
+            "Entity '{}' not found (no {} or {})",
+            pascal,
+            project.relative(&model_path).display(),
+            project.relative(&feature_dir).display()
+        );
+    }
+
+    let others: Vec<(Option<EntityModule>, String)> = module::all_entities(project)?
+        .into_iter()
+        .filter(|(om, other)| !(*other == snake && module::same(om.as_ref(), m)))
+        .collect();
+    // Junctions live at the top level, whatever the modules of their entities
+    let junctions: Vec<String> = others
+        .iter()
+        .filter(|(jm, j)| {
+            jm.is_none()
+                && others
+                    .iter()
+                    .any(|(_, o)| o != j && relation::junction_name(&snake, o) == *j)
+        })
+        .map(|(_, j)| j.clone())
+        .collect();
+    let is_junction = |om: &Option<EntityModule>, o: &String| om.is_none() && junctions.contains(o);
+    let children: Vec<(Option<EntityModule>, String)> = others
+        .iter()
+        .filter(|(om, o)| !is_junction(om, o))
+        .filter(|(om, o)| {
+            let belongs_to = format!("belongs_to = \"{}::Entity\"", module::model_path(om.as_ref(), m, &snake));
+            fs.read_to_string(&project.join(module::backend_file(om.as_ref(), "entities", o)))
+                .map(|c| c.contains(&belongs_to))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+    let paths: Vec<&str> = std::iter::once(entity_path.as_str())
+        .chain(junctions.iter().map(String::as_str))
+        .collect();
+
+    // Table names come from the models, so read them before deleting anything
+    let mut tables: Vec<DroppedTable> = junctions
+        .iter()
+        .map(|j| dropped_table(project, None, j, j))
+        .collect();
+    tables.push(dropped_table(project, m, &snake, &module::table_name(m, &snake)));
+
+    let mut report = DestroyReport::new(project);
+
+    // ── Files ────────────────────────────────────────────────────────
+    for dir in ["entities", "handlers", "routes"] {
+        report.remove_path(fs, &project.join(module::backend_file(m, dir, &snake)))?;
+    }
+    for junction in &junctions {
+        report.remove_path(fs, &base.join(format!("entities/{}.rs", junction)))?;
+    }
+    report.remove_path(fs, &feature_dir)?;
+    for (om, other) in &others {
+        let hooks = project
+            .frontend_src()
+            .join("features")
+            .join(module::feature_path(om.as_ref(), other))
+            .join(format!("{}_hooks.ts", snake));
+        report.remove_path(fs, &hooks)?;
+    }
+
+    // ── Registrations ────────────────────────────────────────────────
+    for dir in ["entities", "handlers", "routes"] {
+        let mod_rs = module::mod_rs(m, base, dir);
+        let line = format!("pub mod {};", snake);
+        let changed = inject::remove(fs, &mod_rs, markers::MODS, &line)?;
+        report.cleaned(changed, &mod_rs, line);
+    }
+    let entities_mod = base.join("entities/mod.rs");
+    for junction in &junctions {
+        let line = format!("pub mod {};", junction);
+        let changed = inject::remove(fs, &entities_mod, markers::MODS, &line)?;
+        report.cleaned(changed, &entities_mod, line);
+    }
+    let routes_mod = module::mod_rs(m, base, "routes");
+    let merge = format!(".merge({}::router())", snake);
+    let changed = inject::remove(fs, &routes_mod, markers::ROUTES, &merge)?;
+    report.cleaned(changed, &routes_mod, merge);
+
+    let main_rs = base.join("main.rs");
+    let handlers_prefix = format!("crate::handlers::{}::", entity_path);
+    let changed = inject::remove_entries(fs, &main_rs, markers::OPENAPI_PATHS, |entry| {
+        entry.starts_with(&handlers_prefix)
+    })?;
+    report.cleaned(changed, &main_rs, "OpenAPI paths");
+    let changed = inject::remove_entries(fs, &main_rs, markers::OPENAPI_SCHEMAS, |entry| {
+        paths
+            .iter()
+            .any(|p| entry.starts_with(&format!("crate::entities::{}::", p)))
+    })?;
+    report.cleaned(changed, &main_rs, "OpenAPI schemas");
+    // Module entities share the module's tag
+    if m.is_none() {
+        let tag = format!("(name=\"{}\"", pascal);
+        let changed = inject::remove_entries(fs, &main_rs, markers::OPENAPI_TAGS, |entry| {
+            entry.starts_with(&format!("{},", tag)) || entry == format!("{})", tag)
+        })?;
+        report.cleaned(changed, &main_rs, "OpenAPI tag");
+    }
+
+    // ── Code injected into related entities ──────────────────────────
+    let is_child = |om: &Option<EntityModule>, o: &String| {
+        children.iter().any(|(cm, c)| c == o && module::same(cm.as_ref(), om.as_ref()))
+    };
+    for (om, other) in others.iter().filter(|(om, o)| !is_junction(om, o) && !is_child(om, o)) {
+        let models: Vec<String> = std::iter::once(module::model_path(om.as_ref(), m, &snake))
+            .chain(junctions.iter().map(|j| module::model_path(om.as_ref(), None, j)))
+            .collect();
+        clean_related_entity(
+            project,
+            om.as_ref(),
+            other,
+            |item| {
+                matches!(item, syn::Item::Impl(_))
+                    && models.iter().any(|model| inject::mentions(item, &format!("{}::", model)))
+            },
+            |f| mentions_any(f, &paths, |p| format!("entities::{}::", p)),
+            &mut report,
+        )?;
+    }
+    for (cm, child) in &children {
+        report.leftovers.push(format!(
+            "{} still has a foreign key to {} ({}); remove that field or relation",
+            child.to_pascal_case(),
+            pascal,
+            module::backend_file(cm.as_ref(), "entities", child)
+        ));
+    }
+
+    // ── Seeds ────────────────────────────────────────────────────────
+    let seed_rs = base.join("seed.rs");
+    let seed_fn = format!("seed_{}s", snake);
+    let in_body = inject::remove_stmts(fs, &seed_rs, |stmt| inject::mentions(stmt, &seed_fn))?;
+    let top_level = inject::remove_items(fs, &seed_rs, |item| {
+        matches!(item, syn::Item::Fn(f) if f.sig.ident == seed_fn)
+    })?;
+    report.cleaned(in_body || top_level, &seed_rs, seed_fn);
+
+    // ── Frontend registrations ───────────────────────────────────────
+    let app_tsx = &project.frontend_src().join("App.tsx");
+    let feature_import = format!("/features/{}/", feature);
+    let changed = remove_lines(fs, app_tsx, |line| {
+        line.trim_start().starts_with("import ") && line.contains(&feature_import)
+    })?;
+    report.cleaned(changed, app_tsx, "imports");
+    let route_path = format!("path=\"/{}", plural);
+    let changed = remove_lines(fs, app_tsx, |line| {
+        line.trim_start().starts_with("<Route")
+            && line
+                .match_indices(&route_path)
+                .any(|(i, m)| line[i + m.len()..].starts_with(['"', '/']))
+    })?;
+    report.cleaned(changed, app_tsx, "routes");
+    let sidebar = &project.frontend_src().join("components/AppSidebar.tsx");
+    let changed = remove_nav_link(fs, sidebar, &plural)?;
+    report.cleaned(changed, sidebar, "nav link");
+
+    // ── Drop migration ───────────────────────────────────────────────
+    if !keep_table {
+        report.created.push(write_drop_migration(project, &snake, &tables)?);
+        for table in tables.iter().filter(|t| t.create_migration.is_none()) {
+            report.leftovers.push(format!(
+                "No create migration found for table '{}'; rolling back the drop migration will not recreate it",
+                table.name
+            ));
+        }
+    }
+
+    // ── Bookkeeping ──────────────────────────────────────────────────
+    relation::take_pending_for(project, &pascal)?;
+    prune_manifest(project, &report, Some(&pascal))?;
+
+    let mut patterns = module_patterns(&paths);
+    if m.is_some() {
+        patterns.push(format!("super::{}::", snake));
+    }
+    report.leftovers.extend(remaining_references(
+        project,
+        &patterns,
+        &[feature_import],
+        &child_files(project, &children),
+    )?);
+    Ok(report)
+}
+
+/// Destroy a single field of a generated entity.
+///
+/// The entity's files are re-rendered without the field, keeping relation
+/// code injected into them and everything below `ROMANCE:CUSTOM`. A foreign
+/// key also takes the has-many code it added to the parent entity with it.
+/// Unless `keep_column` is set, a migration dropping the column is generated.
+pub fn field(project: &Project, entity_name: &str, field_name: &str, keep_column: bool) -> Result<DestroyReport> {
+    let fs = project.fs();
+    let base = &project.backend_src();
+    let previous = recover::entity(project, entity_name)?;
+    let pascal = previous.name.clone();
+    let snake = pascal.to_snake_case();
+    let entity_module = module::of_entity(project, &previous)?;
+    let m = entity_module.as_ref();
+    let field_name = field_name.to_snake_case();
+
+    let Some(field) = previous.fields.iter().find(|f| f.name == field_name).cloned() else {
+        let names: Vec<&str> = previous.fields.iter().map(|f| f.name.as_str()).collect();
+        anyhow::bail!("{} has no field '{}' (fields: {})", pascal, field_name, names.join(", "));
+    };
+    if previous.fields.len() == 1 {
+        anyhow::bail!(
+            "'{}' is the only field of {}; use `romance destroy entity {}` instead",
+            field_name,
+            pascal,
+            pascal
+        );
+    }
+
+    let mut updated = previous.clone();
+    updated.fields.retain(|f| f.name != field_name);
+    updated
+        .relations
+        .retain(|r| r.fk_column.as_deref() != Some(field_name.as_str()));
+
+    let model = project.join(module::backend_file(m, "entities", &snake));
+    let table = table_name(fs, &model, &module::table_name(m, &snake));
+    let parent = field.relation.as_ref().map(|target| target.to_snake_case());
+    let parent_module = parent.as_ref().and_then(|p| module::locate(project, p));
+    let references = parent.as_ref().map(|p| {
+        let parent_model = project.join(module::backend_file(parent_module.as_ref(), "entities", p));
+        table_name(fs, &parent_model, &module::table_name(parent_module.as_ref(), p))
+    });
+
+    let mut report = DestroyReport::new(project);
+
+    // ── The entity's own files ───────────────────────────────────────
+    let previous_files = backend::render_files(project, &previous)?;
+    let feature_dir = project.frontend_src().join("features").join(module::feature_path(m, &pascal));
+    let mut files: Vec<PathBuf> = previous_files.iter().map(|(p, _)| project.join(p)).collect();
+    collect_files(fs, &feature_dir, "ts", &mut files);
+    collect_files(fs, &feature_dir, "tsx", &mut files);
+    let before = snapshot(fs, &files);
+
+    let mut written = backend::refresh(project, &updated, &previous_files)?;
+    if fs.exists(&feature_dir) {
+        written.extend(frontend::write_files(project, &updated)?);
+    }
+    report.rewritten(fs, &before, &written, &field_name);
+
+    // ── Has-many code the foreign key added to its parent ────────────
+    if let Some(parent) = &parent {
+        let fk_base = field_name.strip_suffix("_id").unwrap_or(&field_name);
+        let child_plural = utils::pluralize(&snake);
+        let handler = if fk_base == parent {
+            format!("list_{}", child_plural)
+        } else {
+            format!("list_{}_by_{}", child_plural, fk_base)
+        };
+
+        let pm = parent_module.as_ref();
+        let model = project.join(module::backend_file(pm, "entities", parent));
+        let relation_def = format!(
+            "{}::Relation::{}",
+            module::model_path(pm, m, &snake),
+            fk_base.to_pascal_case()
+        );
+        let changed = inject::remove_items(fs, &model, |item| {
+            matches!(item, syn::Item::Impl(_)) && inject::mentions(item, &relation_def)
+        })?;
+        report.cleaned(changed, &model, format!("Related<{}>", pascal));
+        let other_fk = updated
+            .relations
+            .iter()
+            .find(|r| r.relation_type == RelationType::BelongsTo && r.target_entity.to_snake_case() == *parent);
+        if let (true, Some(other_fk)) = (changed, other_fk) {
+            report.leftovers.push(format!(
+                "{} no longer implements Related<{}>; add one through {}.{} by hand if you need it",
+                project.relative(&model).display(),
+                pascal,
+                pascal,
+                other_fk.name
+            ));
+        }
+
+        let handlers = project.join(module::backend_file(pm, "handlers", parent));
+        let changed = inject::remove_items(fs, &handlers, |item| {
+            matches!(item, syn::Item::Fn(f) if f.sig.ident == handler)
+        })?;
+        report.cleaned(changed, &handlers, &handler);
+        let routes = project.join(module::backend_file(pm, "routes", parent));
+        let route_handler = format!("{}::{}", parent, handler);
+        let changed = inject::remove_method_calls(fs, &routes, |call| {
+            call.method == "route" && inject::mentions(&call.args, &route_handler)
+        })?;
+        report.cleaned(changed, &routes, "relation route");
+    }
+
+    // ── Seeds ────────────────────────────────────────────────────────
+    let seed_rs = base.join("seed.rs");
+    let changed = backend::refresh_seed(project, &updated)?;
+    report.cleaned(changed, &seed_rs, format!("seed_{}s", snake));
+
+    // ── Drop-column migration ────────────────────────────────────────
+    if !keep_column {
+        let engine = TemplateEngine::for_project(project)?;
+        let mut ctx = Context::new();
+        ctx.insert("table", &table);
+        ctx.insert("table_ref", &table_ref(m, &table));
+        ctx.insert("column", &field_name);
+        ctx.insert("migration_method", field.field_type.to_sea_orm_migration());
+        ctx.insert("references", &references);
+        ctx.insert(
+            "references_ref",
+            &references.as_ref().map(|r| table_ref(parent_module.as_ref(), r)),
+        );
+        let content = engine.render("entity/backend/drop_column_migration.rs.tera", &ctx)?;
+        let module = format!(
+            "m{}_remove_{}_from_{}",
+            migration::next_timestamp(project),
+            field_name,
+            table.to_snake_case()
+        );
+        let path = project.migration_src().join(format!("{}.rs", module));
+        utils::write_file(fs, &path, &content)?;
+        context::register_migration(fs, project.root(), &module)?;
+        report.created.push(report.show(&path));
+    }
+
+    let column = format!("Column::{}", field_name.to_pascal_case());
+    let mut files = Vec::new();
+    collect_files(fs, base, "rs", &mut files);
+    for file in files.iter().filter(|f| !written.contains(f)) {
+        let text: String = fs.read_to_string(file)?.split_whitespace().collect();
+        if inject::text_mentions(&text, &format!("{}::{}", snake, column)) {
+            report
+                .leftovers
+                .push(format!("{} still references `{}::{}`", project.relative(file).display(), snake, column));
+        }
+    }
+    Ok(report)
+}
+
+/// Destroy a many-to-many relation between two entities: the junction entity,
+/// the handlers, routes and `Related` impls generated into both sides, and
+/// their frontend relation hooks. Unless `keep_table` is set, a migration
+/// dropping the junction table is generated.
+pub fn relation(project: &Project, entity_a: &str, entity_b: &str, keep_table: bool) -> Result<DestroyReport> {
+    let fs = project.fs();
+    let base = &project.backend_src();
+    let (a, b) = (entity_a.to_snake_case(), entity_b.to_snake_case());
+    let (module_a, module_b) = (module::locate(project, &a), module::locate(project, &b));
+    let junction = relation::junction_name(&a, &b).to_snake_case();
+    let junction_path = base.join(format!("entities/{}.rs", junction));
+
+    let mut report = DestroyReport::new(project);
+    let was_pending = relation::remove_pending_between(project, &a, &b)?;
+    if was_pending {
+        report.cleaned(true, Path::new(".romance/pending_relations.json"), "pending relation");
+    }
+    if !fs.exists(&junction_path) {
+        if was_pending {
+            return Ok(report);
+        }
+        anyhow::bail!(
+            "No many-to-many relation between {} and {} (no {})",
+            entity_a.to_pascal_case(),
+            entity_b.to_pascal_case(),
+            project.relative(&junction_path).display()
+        );
+    }
+
+    let table = dropped_table(project, None, &junction, &junction);
+
+    report.remove_path(fs, &junction_path)?;
+    let entities_mod = base.join("entities/mod.rs");
+    let line = format!("pub mod {};", junction);
+    let changed = inject::remove(fs, &entities_mod, markers::MODS, &line)?;
+    report.cleaned(changed, &entities_mod, line);
+    let main_rs = base.join("main.rs");
+    let schema_prefix = format!("crate::entities::{}::", junction);
+    let changed = inject::remove_entries(fs, &main_rs, markers::OPENAPI_SCHEMAS, |entry| {
+        entry.starts_with(&schema_prefix)
+    })?;
+    report.cleaned(changed, &main_rs, "OpenAPI schemas");
+
+    for ((side, side_module), (other, other_module)) in [((&a, &module_a), (&b, &module_b)), ((&b, &module_b), (&a, &module_a))] {
+        let other_path = module::rust_path(other_module.as_ref(), other);
+        let related = format!("entities::{}::", other_path);
+        let find_related = format!("find_related(crate::entities::{}::Entity)", other_path);
+        let junction_model = module::model_path(side_module.as_ref(), None, &junction);
+        let own_fns = [
+            format!("list_{}", utils::pluralize(other)),
+            format!("add_{}", other),
+            format!("remove_{}", other),
+        ];
+        clean_related_entity(
+            project,
+            side_module.as_ref(),
+            side,
+            |item| {
+                matches!(item, syn::Item::Impl(_))
+                    && inject::mentions(item, &format!("{}::", junction_model))
+            },
+            |f| {
+                inject::mentions(f, &format!("entities::{}::", junction))
+                    || (own_fns.iter().any(|n| f.sig.ident == n)
+                        && inject::mentions(f, &related)
+                        && inject::mentions(f, &find_related))
+            },
+            &mut report,
+        )?;
+        let hooks = project
+            .frontend_src()
+            .join("features")
+            .join(module::feature_path(side_module.as_ref(), side))
+            .join(format!("{}_hooks.ts", other));
+        report.remove_path(fs, &hooks)?;
+    }
+
+    if !keep_table {
+        report.created.push(write_drop_migration(project, &junction, &[table])?);
+    }
+    prune_manifest(project, &report, None)?;
+    report
+        .leftovers
+        .extend(remaining_references(project, &module_patterns(&[&junction]), &[], &[])?);
+    Ok(report)
+}
+
+/// Destroy generated authentication: the auth module, the `User` entity, the
+/// auth handlers, routes and frontend feature, the Cargo dependencies and the
+/// `JWT_SECRET` auth added. Entity handlers are re-rendered without the
+/// `AuthUser` extractor. Unless `keep_table` is set, a migration dropping the
+/// users table is generated. `project` is reloaded once auth is gone, so its
+/// features no longer report it.
+pub fn auth(project: &mut Project, keep_table: bool) -> Result<DestroyReport> {
+    let fs = project.fs();
+    let base = &project.backend_src();
+    if !fs.exists(&base.join("auth.rs")) {
+        anyhow::bail!("Auth is not generated (backend/src/auth.rs not found)");
+    }
+    if fs.exists(&base.join("routes/admin.rs")) || fs.exists(&project.frontend_src().join("admin")) {
+        anyhow::bail!("The admin panel depends on auth; run `romance destroy admin` first");
+    }
+    if project.features().has_multitenancy {
+        anyhow::bail!("Multitenancy depends on auth; disable it in romance.toml first");
+    }
+
+    let others: Vec<String> = relation::discover_entities(project)?
+        .into_iter()
+        .filter(|e| e != "user")
+        .collect();
+    let belongs_to = "belongs_to = \"super::user::Entity\"";
+    let mut refreshed = Vec::new();
+    let mut children: Vec<(Option<EntityModule>, String)> = Vec::new();
+    for other in &others {
+        let model = fs.read_to_string(&base.join(format!("entities/{}.rs", other))).unwrap_or_default();
+        if model.contains(belongs_to) {
+            children.push((None, other.clone()));
+        }
+        // Entities generated while auth existed take `AuthUser` in their handlers;
+        // render them as they are now, before auth.rs goes away
+        let handlers = fs.read_to_string(&base.join(format!("handlers/{}.rs", other))).unwrap_or_default();
+        if model.contains("ROMANCE:CUSTOM") && handlers.contains("AuthUser") {
+            let definition = recover::entity(project, other)?;
+            let previous = backend::render_files(project, &definition)?;
+            refreshed.push((definition, previous));
+        }
+    }
+    let table = dropped_table(project, None, "user", "users");
+
+    let mut report = DestroyReport::new(project);
+
+    // ── Files ────────────────────────────────────────────────────────
+    for path in ["auth.rs", "entities/user.rs", "handlers/auth.rs", "routes/auth.rs"] {
+        report.remove_path(fs, &base.join(path))?;
+    }
+    let features_dir = &project.frontend_src().join("features");
+    report.remove_path(fs, &features_dir.join("auth"))?;
+    let user_dir = features_dir.join("user");
+    report.remove_path(fs, &user_dir.join("api.ts"))?;
+    if fs.read_dir(&user_dir).map(|d| d.is_empty()).unwrap_or(false) {
+        fs.remove_dir(&user_dir)?;
+    }
+
+    // ── Registrations ────────────────────────────────────────────────
+    for (dir, module) in [("entities", "user"), ("handlers", "auth"), ("routes", "auth")] {
+        let mod_rs = base.join(format!("{}/mod.rs", dir));
+        let line = format!("pub mod {};", module);
+        let changed = inject::remove(fs, &mod_rs, markers::MODS, &line)?;
+        report.cleaned(changed, &mod_rs, line);
+    }
+    let routes_mod = base.join("routes/mod.rs");
+    let changed = inject::remove(fs, &routes_mod, markers::ROUTES, ".merge(auth::router())")?;
+    report.cleaned(changed, &routes_mod, ".merge(auth::router())");
+    let main_rs = base.join("main.rs");
+    let changed = remove_lines(fs, &main_rs, |line| line.trim() == "mod auth;")?;
+    report.cleaned(changed, &main_rs, "mod auth;");
+
+    // ── Entities ─────────────────────────────────────────────────────
+    project.reload()?;
+    for (definition, previous) in &refreshed {
+        let paths: Vec<PathBuf> = previous.iter().map(|(p, _)| project.join(p)).collect();
+        let before = snapshot(project.fs(), &paths);
+        let written = backend::refresh(project, definition, previous)?;
+        report.rewritten(project.fs(), &before, &written, "AuthUser");
+    }
+    for (_, child) in &children {
+        report.leftovers.push(format!(
+            "{} still has a foreign key to User (backend/src/entities/{}.rs); remove that field or relation",
+            child.to_pascal_case(),
+            child
+        ));
+    }
+
+    // ── Dependencies and environment ─────────────────────────────────
+    let fs = project.fs();
+    let mut sources = Vec::new();
+    collect_files(fs, base, "rs", &mut sources);
+    let still_used = |needle: &str| {
+        sources
+            .iter()
+            .any(|f| fs.read_to_string(f).map(|c| c.contains(needle)).unwrap_or(false))
+    };
+    let cargo_toml = &project.join("backend/Cargo.toml");
+    for dep in ["argon2", "jsonwebtoken"] {
+        if still_used(&format!("{}::", dep)) {
+            continue;
+        }
+        let prefix = format!("{} =", dep);
+        let changed = remove_lines(fs, cargo_toml, |line| line.trim_start().starts_with(&prefix))?;
+        report.cleaned(changed, cargo_toml, dep);
+    }
+    // Projects scaffolded with a JWT_SECRET keep it: auth didn't add it
+    if !still_used("JWT_SECRET") && !scaffolded_env_key(project, "JWT_SECRET") {
+        for env in ["backend/.env", "backend/.env.example"] {
+            let env = &project.join(env);
+            let changed = remove_lines(fs, env, |line| line.starts_with("JWT_SECRET="))?;
+            report.cleaned(changed, env, "JWT_SECRET");
+        }
+    }
+
+    // ── Drop migration ───────────────────────────────────────────────
+    if !keep_table {
+        let missing = table.create_migration.is_none().then(|| table.name.clone());
+        report.created.push(write_drop_migration(project, "user", &[table])?);
+        if let Some(name) = missing {
+            report.leftovers.push(format!(
+                "No create migration found for table '{}'; rolling back the drop migration will not recreate it",
+                name
+            ));
+        }
+    }
+
+    prune_manifest(project, &report, Some("User"))?;
+    let mut patterns = module_patterns(&["user", "auth"]);
+    patterns.push("crate::auth::".to_string());
+    report.leftovers.extend(remaining_references(
+        project,
+        &patterns,
+        &["/features/auth/".to_string(), "/features/user/".to_string()],
+        &child_files(project, &children),
+    )?);
+    Ok(report)
+}
+
+/// Destroy the generated admin panel: its frontend, backend handlers and
+/// routes, and their registrations. The admin panel has no tables.
+pub fn admin(project: &Project) -> Result<DestroyReport> {
+    let fs = project.fs();
+    let base = &project.backend_src();
+    let admin_dir = &project.frontend_src().join("admin");
+    if !fs.exists(admin_dir) && !fs.exists(&base.join("routes/admin.rs")) {
+        anyhow::bail!("Admin panel is not generated (frontend/src/admin not found)");
+    }
+
+    let mut report = DestroyReport::new(project);
+    report.remove_path(fs, admin_dir)?;
+    for dir in ["handlers", "routes"] {
+        report.remove_path(fs, &base.join(format!("{}/admin.rs", dir)))?;
+        let mod_rs = base.join(format!("{}/mod.rs", dir));
+        let changed = inject::remove(fs, &mod_rs, markers::MODS, "pub mod admin;")?;
+        report.cleaned(changed, &mod_rs, "pub mod admin;");
+    }
+    let routes_mod = base.join("routes/mod.rs");
+    let changed = inject::remove(fs, &routes_mod, markers::ROUTES, ".merge(admin::router())")?;
+    report.cleaned(changed, &routes_mod, ".merge(admin::router())");
+
+    prune_manifest(project, &report, None)?;
+    report.leftovers.extend(remaining_references(
+        project,
+        &module_patterns(&["admin"]),
+        &["./admin/".to_string(), "@/admin/".to_string()],
+        &[],
+    )?);
+    Ok(report)
+}
+
+/// Read the table name from an entity model, falling back to `default`.
+pub(super) fn table_name(fs: &dyn ProjectFs, model: &Path, default: &str) -> String {
+    let content = fs.read_to_string(model).unwrap_or_default();
+    regex::Regex::new(r#"table_name\s*=\s*"([^"]+)""#)
+        .ok()
+        .and_then(|re| re.captures(&content).map(|c| c[1].to_string()))
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// A sea-query reference to `table`, in the module's schema if it has one.
+pub(super) fn table_ref(entity_module: Option<&EntityModule>, table: &str) -> String {
+    module::table_ref(entity_module, &format!("Alias::new(\"{}\")", table))
+}
+
+/// The table of an entity model and the migration that created it.
+fn dropped_table(project: &Project, entity_module: Option<&EntityModule>, snake: &str, default: &str) -> DroppedTable {
+    let model = project.join(module::backend_file(entity_module, "entities", snake));
+    let name = table_name(project.fs(), &model, default);
+    // Auth's users table was created as `create_users_table`
+    let create_migration = migration::find_create_migration(project, snake)
+        .or_else(|| migration::find_create_migration(project, &name));
+    DroppedTable {
+        table_ref: table_ref(entity_module, &name),
+        name,
+        create_migration,
+    }
+}
+
+/// Write and register a migration dropping `tables`.
+fn write_drop_migration(project: &Project, snake: &str, tables: &[DroppedTable]) -> Result<String> {
+    let fs = project.fs();
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert(
+        "tables",
+        &tables
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "name": t.name,
+                    "table_ref": t.table_ref,
+                    "create_migration": t.create_migration,
+                })
+            })
+            .collect::<Vec<_>>(),
+    );
+    let content = engine.render("entity/backend/drop_migration.rs.tera", &ctx)?;
+
+    let module = format!("m{}_drop_{}_table", migration::next_timestamp(project), snake);
+    let path = project.migration_src().join(format!("{}.rs", module));
+    utils::write_file(fs, &path, &content)?;
+    context::register_migration(fs, project.root(), &module)?;
+    Ok(project.relative(&path).display().to_string())
+}
+
+/// Remove relation code that generation injected into another entity: the
+/// model items matching `impl_pred`, the handlers matching `fn_pred`, and the
+/// routes to those handlers.
+fn clean_related_entity(
+    project: &Project,
+    other_module: Option<&EntityModule>,
+    other: &str,
+    impl_pred: impl Fn(&syn::Item) -> bool,
+    fn_pred: impl Fn(&syn::ItemFn) -> bool,
+    report: &mut DestroyReport,
+) -> Result<()> {
+    let fs = project.fs();
+    let model = project.join(module::backend_file(other_module, "entities", other));
+    let changed = inject::remove_items(fs, &model, &impl_pred)?;
+    report.cleaned(changed, &model, "Related impls");
+
+    let handlers = project.join(module::backend_file(other_module, "handlers", other));
+    let source = fs.read_to_string(&handlers).unwrap_or_default();
+    let Ok(file) = syn::parse_file(&source) else {
+        return Ok(());
+    };
+    let removed_fns: Vec<String> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(f) if fn_pred(f) => Some(f.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    if removed_fns.is_empty() {
+        return Ok(());
+    }
+    let changed = inject::remove_items(fs, &handlers, |item| {
+        matches!(item, syn::Item::Fn(f) if removed_fns.iter().any(|n| f.sig.ident == n))
+    })?;
+    report.cleaned(changed, &handlers, removed_fns.join(", "));
+
+    let routes = project.join(module::backend_file(other_module, "routes", other));
+    let changed = inject::remove_method_calls(fs, &routes, |call| {
+        call.method == "route"
+            && removed_fns
+                .iter()
+                .any(|n| inject::mentions(&call.args, &format!("{}::{}", other, n)))
+    })?;
+    report.cleaned(changed, &routes, "relation routes");
+    Ok(())
+}
+
+fn mentions_any(tokens: &impl ToTokens, modules: &[&str], path: impl Fn(&str) -> String) -> bool {
+    modules.iter().any(|m| inject::mentions(tokens, &path(m)))
+}
+
+/// Whether `backend/.env.example` had `key` as scaffolded, per its
+/// generated base.
+fn scaffolded_env_key(project: &Project, key: &str) -> bool {
+    let fs = project.fs();
+    let Ok(tracked) = Manifest::load(fs, project.root()) else {
+        return false;
+    };
+    let path = "backend/.env.example";
+    tracked
+        .files
+        .get(path)
+        .and_then(|record| manifest::load_base(fs, project.root(), path, &record.generated_hash))
+        .is_some_and(|base| base.lines().any(|line| line.starts_with(&format!("{}=", key))))
+}
+
+/// Remove lines matching `pred`. Returns `true` if the file changed.
+fn remove_lines(fs: &dyn ProjectFs, path: &Path, pred: impl Fn(&str) -> bool) -> Result<bool> {
+    if !fs.exists(path) {
+        return Ok(false);
+    }
+    let content = fs.read_to_string(path)?;
+    let updated: String = content
+        .split_inclusive('\n')
+        .filter(|line| !pred(line))
+        .collect();
+    if updated == content {
+        return Ok(false);
+    }
+    fs.write(path, &updated)?;
+    Ok(true)
+}
+
+/// Remove the `<NavLink to="/plural">…</NavLink>` block from the sidebar.
+fn remove_nav_link(fs: &dyn ProjectFs, path: &Path, plural: &str) -> Result<bool> {
+    if !fs.exists(path) {
+        return Ok(false);
+    }
+    let content = fs.read_to_string(path)?;
+    let opening = format!("<NavLink to=\"/{}\"", plural);
+    let mut kept = String::with_capacity(content.len());
+    let mut skipping = false;
+    for line in content.split_inclusive('\n') {
+        if !skipping && line.contains(&opening) {
+            skipping = true;
+        }
+        if skipping {
+            if line.contains("</NavLink>") {
+                skipping = false;
+            }
+            continue;
+        }
+        kept.push_str(line);
+    }
+    if kept == content {
+        return Ok(false);
+    }
+    fs.write(path, &kept)?;
+    Ok(true)
+}
+
+/// Paths that refer to a backend module from elsewhere in the code.
+pub(super) fn module_patterns(modules: &[&str]) -> Vec<String> {
+    modules
+        .iter()
+        .flat_map(|m| {
+            [
+                format!("entities::{}::", m),
+                format!("handlers::{}::", m),
+                format!("super::{}::", m),
+                format!("{}::router()", m),
+            ]
+        })
+        .collect()
+}
+
+/// Backend files that still mention one of `patterns`, and frontend files
+/// that still contain one of the `imports`. Files under `skip` are left out.
+pub(super) fn remaining_references(
+    project: &Project,
+    patterns: &[String],
+    imports: &[String],
+    skip: &[PathBuf],
+) -> Result<Vec<String>> {
+    let fs = project.fs();
+    let mut found = Vec::new();
+    let mut files = Vec::new();
+    collect_files(fs, &project.backend_src(), "rs", &mut files);
+    let skipped = |file: &PathBuf| skip.iter().any(|s| file.starts_with(s));
+    for file in files.iter().filter(|f| !skipped(f)) {
+        let text: String = fs.read_to_string(file)?.split_whitespace().collect();
+        if let Some(pattern) = patterns.iter().find(|p| inject::text_mentions(&text, p)) {
+            found.push(format!("{} still references `{}`", project.relative(file).display(), pattern));
+        }
+    }
+
+    let mut files = Vec::new();
+    collect_files(fs, &project.frontend_src(), "ts", &mut files);
+    collect_files(fs, &project.frontend_src(), "tsx", &mut files);
+    for file in files.iter().filter(|f| !skipped(f)) {
+        let content = fs.read_to_string(file)?;
+        if let Some(import) = imports.iter().find(|i| content.contains(i.as_str())) {
+            found.push(format!("{} still imports from {}", project.relative(file).display(), import));
+        }
+    }
+    Ok(found)
+}
+
+/// The files of entities that keep a foreign key to a destroyed one; their
+/// references are reported once, as the foreign key itself.
+fn child_files(project: &Project, children: &[(Option<EntityModule>, String)]) -> Vec<PathBuf> {
+    children
+        .iter()
+        .flat_map(|(m, c)| {
+            [
+                project.join(module::backend_file(m.as_ref(), "entities", c)),
+                project.join(module::backend_file(m.as_ref(), "handlers", c)),
+                project.frontend_src().join("features").join(module::feature_path(m.as_ref(), c)),
+            ]
+        })
+        .collect()
+}
+
+/// Drop manifest records for removed files, and for `entity` if given.
+fn prune_manifest(project: &Project, report: &DestroyReport, entity: Option<&str>) -> Result<()> {
+    let fs = project.fs();
+    if !Manifest::exists(fs, project.root()) {
+        return Ok(());
+    }
+    let mut manifest = Manifest::load(fs, project.root())?;
+    let before = manifest.files.len();
+    manifest.files.retain(|path, record| {
+        (entity.is_none() || record.entity_name.as_deref() != entity)
+            && !report
+                .removed
+                .iter()
+                .any(|removed| path == removed.trim_end_matches('/') || path.starts_with(removed.as_str()))
+    });
+    if manifest.files.len() != before {
+        manifest.save(fs, project.root())?;
+    }
+    Ok(())
+}
+
+pub(super) fn collect_files(fs: &dyn ProjectFs, dir: &Path, ext: &str, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs.read_dir(dir) else { return };
+    for path in entries {
+        if fs.is_dir(&path) {
+            collect_files(fs, &path, ext, out);
+        } else if path.extension().and_then(|e| e.to_str()) == Some(ext) {
+            out.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::parse_entity;
+    use crate::fs::MemoryFs;
+    use crate::generator::plan::GenerationTracker;
+    use crate::generator::{admin, auth, junction};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    fn project() -> (Arc<MemoryFs>, Project) {
+        let memory = Arc::new(MemoryFs::new());
+        let project = crate::scaffold::render_project(memory.clone(), Path::new("/app"), "app").unwrap();
+        (memory, project)
+    }
+
+    fn generate_entity(project: &Project, name: &str) {
+        let entity = parse_entity(name, &["title:string".to_string()]).unwrap();
+        let mut tracker = GenerationTracker::new();
+        backend::generate(project, &entity, &mut tracker).unwrap();
+        migration::generate(project, &entity, &mut tracker).unwrap();
+        frontend::generate(project, &entity, &mut tracker).unwrap();
+    }
+
+    /// The project's files but migrations and the manifest, which destroy
+    /// adds to rather than restores.
+    fn sources(memory: &MemoryFs) -> BTreeMap<PathBuf, String> {
+        memory
+            .files_under(Path::new("/app"))
+            .into_iter()
+            .filter(|(path, _)| !path.starts_with("backend/migration") && !path.starts_with(".romance"))
+            .collect()
+    }
+
+    fn assert_same(before: &BTreeMap<PathBuf, String>, after: &BTreeMap<PathBuf, String>) {
+        let paths: Vec<&PathBuf> = before.keys().chain(after.keys()).collect();
+        for path in paths {
+            assert_eq!(before.get(path), after.get(path), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn relation_removes_only_its_own_insertions() {
+        let (memory, project) = project();
+        for name in ["Post", "Tag", "Category"] {
+            generate_entity(&project, name);
+        }
+        junction::generate(&project, "Post", "Category").unwrap();
+        let before = sources(&memory);
+
+        junction::generate(&project, "Post", "Tag").unwrap();
+        relation(&project, "Tag", "Post", true).unwrap();
+        assert_same(&before, &sources(&memory));
+    }
+
+    #[test]
+    fn auth_removes_only_its_own_insertions() {
+        let (memory, mut project) = project();
+        generate_entity(&project, "Post");
+        let before = sources(&memory);
+
+        auth::generate(&mut project).unwrap();
+        auth(&mut project, true).unwrap();
+        assert_same(&before, &sources(&memory));
+    }
+
+    #[test]
+    fn admin_removes_only_its_own_insertions() {
+        let (memory, mut project) = project();
+        auth::generate(&mut project).unwrap();
+        generate_entity(&project, "Post");
+        let before = sources(&memory);
+
+        admin::generate(&project).unwrap();
+        admin(&project).unwrap();
+        assert_same(&before, &sources(&memory));
+    }
+}
@@ -1,86 +1,67 @@
 use crate::entity::{EntityDefinition, RelationType};
-use crate::generator::context::{self, markers, ProjectFeatures};
+use crate::generator::context::{self, markers};
 use crate::generator::plan::{self, GenerationTracker};
+use crate::manifest::{self, FileCategory, RenderedFile};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
-use std::path::Path;
+use std::path::PathBuf;
 use tera::Context;
 
 /// Pre-validate that frontend markers exist in App.tsx and AppSidebar.tsx.
-pub fn validate(_entity: &EntityDefinition) -> Result<()> {
-    let app_path = Path::new("frontend/src/App.tsx");
-    let sidebar_path = Path::new("frontend/src/components/AppSidebar.tsx");
+pub fn validate(project: &Project, entity: &EntityDefinition) -> Result<()> {
+    let fs = project.fs();
+    let app_path = project.frontend_src().join("App.tsx");
+    let sidebar_path = project.frontend_src().join("components/AppSidebar.tsx");
+    let nav_marker = match module::of_entity(project, entity)? {
+        Some(m) => m.nav_marker(),
+        None => markers::NAV_LINKS.to_string(),
+    };
     let checks = vec![
-        plan::check(app_path, markers::IMPORTS),
-        plan::check(app_path, markers::APP_ROUTES),
-        plan::check(sidebar_path, markers::NAV_LINKS),
+        plan::check(&app_path, markers::IMPORTS),
+        plan::check(&app_path, markers::APP_ROUTES),
+        plan::check(sidebar_path, &nav_marker),
     ];
-    plan::validate_markers(&checks)
+    plan::validate_markers(fs, &checks)
 }
 
-pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> Result<()> {
-    let engine = TemplateEngine::new()?;
-    let ctx = build_context(entity);
-    let snake_name = entity.name.to_snake_case();
-    let camel_name = entity.name.to_lower_camel_case();
-
-    let base = Path::new("frontend/src");
-    let feature_dir = base.join(format!("features/{}", camel_name));
-
-    // Types
-    let content = engine.render("entity/frontend/types.ts.tera", &ctx)?;
-    let types_path = feature_dir.join("types.ts");
-    utils::write_generated(&types_path, &content)?;
-    tracker.track(types_path);
-
-    // API client
-    let content = engine.render("entity/frontend/api.ts.tera", &ctx)?;
-    let api_path = feature_dir.join("api.ts");
-    utils::write_generated(&api_path, &content)?;
-    tracker.track(api_path);
-
-    // Hooks
-    let content = engine.render("entity/frontend/hooks.ts.tera", &ctx)?;
-    let hooks_path = feature_dir.join("hooks.ts");
-    utils::write_generated(&hooks_path, &content)?;
-    tracker.track(hooks_path);
-
-    // List component
-    let content = engine.render("entity/frontend/List.tsx.tera", &ctx)?;
-    let list_path = feature_dir.join(format!("{}List.tsx", entity.name));
-    utils::write_generated(&list_path, &content)?;
-    tracker.track(list_path);
-
-    // Form component
-    let content = engine.render("entity/frontend/Form.tsx.tera", &ctx)?;
-    let form_path = feature_dir.join(format!("{}Form.tsx", entity.name));
-    utils::write_generated(&form_path, &content)?;
-    tracker.track(form_path);
+pub fn generate(
+    project: &Project,
+    entity: &EntityDefinition,
+    tracker: &mut GenerationTracker,
+) -> Result<()> {
 
-    // Detail component
-    let content = engine.render("entity/frontend/Detail.tsx.tera", &ctx)?;
-    let detail_path = feature_dir.join(format!("{}Detail.tsx", entity.name));
-    utils::write_generated(&detail_path, &content)?;
-    tracker.track(detail_path);
+    let fs = project.fs();
+    for path in write_files(project, entity)? {
+        utils::ui::created(&project.relative(&path).display().to_string());
+        tracker.track(path);
+    }
+    let snake_name = entity.name.to_snake_case();
+    let entity_module = module::of_entity(project, entity)?;
+    let feature = module::feature_path(entity_module.as_ref(), &entity.name);
+    let base = project.frontend_src();
 
     // Inject imports and routes into App.tsx
     let app_path = base.join("App.tsx");
     let entity_pascal = &entity.name;
-    let plural = utils::pluralize(&snake_name);
+    let plural = module::url_path(entity_module.as_ref(), &snake_name);
 
     // Imports
     utils::insert_at_marker(
+        fs,
         &app_path,
         markers::IMPORTS,
         &format!(
-            "import {entity_pascal}List from '@/features/{camel_name}/{entity_pascal}List'\nimport {entity_pascal}Form from '@/features/{camel_name}/{entity_pascal}Form'\nimport {entity_pascal}Detail from '@/features/{camel_name}/{entity_pascal}Detail'",
+            "import {entity_pascal}List from '@/features/{feature}/{entity_pascal}List'\nimport {entity_pascal}Form from '@/features/{feature}/{entity_pascal}Form'\nimport {entity_pascal}Detail from '@/features/{feature}/{entity_pascal}Detail'",
         ),
     )?;
 
     // Routes
     utils::insert_at_marker(
+        fs,
         &app_path,
         markers::APP_ROUTES,
         &format!(
@@ -88,11 +69,16 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
         ),
     )?;
 
-    // Nav link in sidebar
+    // Nav link in sidebar, in the module's section if it has one
     let sidebar_path = base.join("components/AppSidebar.tsx");
+    let nav_marker = match &entity_module {
+        Some(m) => m.nav_marker(),
+        None => markers::NAV_LINKS.to_string(),
+    };
     utils::insert_at_marker(
+        fs,
         &sidebar_path,
-        markers::NAV_LINKS,
+        &nav_marker,
         &format!(
             "        <NavLink to=\"/{plural}\" className={{navLinkClass}}>\n          <LayoutList className=\"h-4 w-4\" />\n          {entity_plural}\n        </NavLink>",
             plural = plural,
@@ -100,25 +86,72 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
         ),
     )?;
 
-    // Generate M2M relation hooks for each ManyToMany relation
-    for rel in &entity.relations {
-        if rel.relation_type == RelationType::ManyToMany {
-            let rel_ctx = build_relation_context(&entity.name, &rel.target_entity);
-            let content = engine.render("entity/frontend/relation_hooks.ts.tera", &rel_ctx)?;
-            let related_snake = rel.target_entity.to_snake_case();
-            let rel_hooks_path = feature_dir.join(format!("{}_hooks.ts", related_snake));
-            utils::write_file(&rel_hooks_path, &content)?;
-            tracker.track(rel_hooks_path);
-        }
-    }
-
     utils::ui::success(&format!(
         "Generated frontend files for '{}' in features/{}",
-        entity.name, snake_name
+        entity.name, feature
     ));
     Ok(())
 }
 
+/// Render the entity's feature files (types, API client, hooks, components and
+/// relation hooks) without touching `App.tsx` or the sidebar, returning the
+/// files written. Used on its own to refresh the files of an existing entity.
+pub fn write_files(project: &Project, entity: &EntityDefinition) -> Result<Vec<PathBuf>> {
+    let fs = project.fs();
+    let files = rendered_files(project, entity)?;
+    let mut written = Vec::new();
+    for file in &files {
+        let path = project.join(&file.output);
+        utils::write_generated(fs, &path, &file.content)?;
+        written.push(path);
+    }
+    manifest::track_generated(project, &files)?;
+    Ok(written)
+}
+
+/// Render the entity's feature files, keyed by output path.
+pub fn rendered_files(project: &Project, entity: &EntityDefinition) -> Result<Vec<RenderedFile>> {
+    let engine = TemplateEngine::for_project(project)?;
+    let entity_module = module::of_entity(project, entity)?;
+    let ctx = build_context(project, entity, entity_module.as_ref());
+    let feature_dir = format!(
+        "frontend/src/features/{}",
+        module::feature_path(entity_module.as_ref(), &entity.name)
+    );
+    let rendered = |template: &str, output: String, ctx: &Context| -> Result<RenderedFile> {
+        Ok(RenderedFile {
+            output: format!("{}/{}", feature_dir, output),
+            template: Some(template.to_string()),
+            category: FileCategory::Entity,
+            entity_name: Some(entity.name.clone()),
+            content: engine.render(template, ctx)?,
+        })
+    };
+
+    let mut files = vec![
+        rendered("entity/frontend/types.ts.tera", "types.ts".to_string(), &ctx)?,
+        rendered("entity/frontend/api.ts.tera", "api.ts".to_string(), &ctx)?,
+        rendered("entity/frontend/hooks.ts.tera", "hooks.ts".to_string(), &ctx)?,
+        rendered("entity/frontend/List.tsx.tera", format!("{}List.tsx", entity.name), &ctx)?,
+        rendered("entity/frontend/Form.tsx.tera", format!("{}Form.tsx", entity.name), &ctx)?,
+        rendered("entity/frontend/Detail.tsx.tera", format!("{}Detail.tsx", entity.name), &ctx)?,
+    ];
+
+    // M2M relation hooks for each ManyToMany relation
+    for rel in &entity.relations {
+        if rel.relation_type == RelationType::ManyToMany {
+            let mut rel_ctx = build_relation_context(&entity.name, &rel.target_entity);
+            rel_ctx.insert("entity_url_path", &module::url_path(entity_module.as_ref(), &entity.name.to_snake_case()));
+            files.push(rendered(
+                "entity/frontend/relation_hooks.ts.tera",
+                format!("{}_hooks.ts", rel.target_entity.to_snake_case()),
+                &rel_ctx,
+            )?);
+        }
+    }
+    Ok(files)
+}
+
 fn build_relation_context(entity_name: &str, related_name: &str) -> Context {
     let mut ctx = Context::new();
     ctx.insert("entity_name", &entity_name.to_pascal_case());
@@ -130,13 +163,14 @@ fn build_relation_context(entity_name: &str, related_name: &str) -> Context {
     ctx
 }
 
-fn build_context(entity: &EntityDefinition) -> Context {
+fn build_context(project: &Project, entity: &EntityDefinition, entity_module: Option<&EntityModule>) -> Context {
     let mut ctx = Context::new();
     ctx.insert("entity_name", &entity.name);
     ctx.insert("entity_name_snake", &entity.name.to_snake_case());
     ctx.insert("entity_name_camel", &entity.name.to_lower_camel_case());
+    ctx.insert("url_path", &module::url_path(entity_module, &entity.name.to_snake_case()));
 
-    let features = ProjectFeatures::load(Path::new("."));
+    let features = project.features();
     ctx.insert("has_validation", &features.has_validation);
     ctx.insert("api_prefix", &features.api_prefix);
 
@@ -230,8 +264,10 @@ fn build_context(entity: &EntityDefinition) -> Context {
         .filter(|f| seen_relations.insert(f.relation.as_ref().unwrap().clone()))
         .map(|f| {
             let rel = f.relation.as_ref().unwrap();
+            let rel_module = module::of_target(project, entity_module, rel);
             serde_json::json!({
                 "relation_camel": rel.to_lower_camel_case(),
+                "feature_path": module::feature_path(rel_module.as_ref(), rel),
             })
         })
         .collect();
@@ -1,10 +1,13 @@
 use crate::generator::context::{self, markers};
+use crate::inject;
+use crate::module;
+use crate::project::Project;
 use crate::relation;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use heck::{ToPascalCase, ToSnakeCase};
-use std::path::Path;
 use tera::Context;
 
 /// Generate a junction table for a many-to-many relation between two entities.
@@ -17,24 +20,47 @@ use tera::Context;
 /// 6. Injects M2M handlers + routes into the source entity
 /// 7. If target entity exists, injects M2M handlers + routes into target too
 pub fn generate(
+    project: &Project,
     source_entity: &str,
     target_entity: &str,
 ) -> Result<()> {
-    let engine = TemplateEngine::new()?;
+    generate_junction(project, source_entity, target_entity, None)
+}
+
+/// Like [`generate`], for a junction table that already exists in the
+/// database as `table` (imported entities): the junction model maps onto it
+/// and no migration is generated.
+pub fn generate_existing(
+    project: &Project,
+    source_entity: &str,
+    target_entity: &str,
+    table: &str,
+) -> Result<()> {
+    generate_junction(project, source_entity, target_entity, Some(table))
+}
+
+fn generate_junction(
+    project: &Project,
+    source_entity: &str,
+    target_entity: &str,
+    existing_table: Option<&str>,
+) -> Result<()> {
+    let engine = TemplateEngine::for_project(project)?;
     let junction = relation::junction_name(source_entity, target_entity);
     let junction_snake = junction.to_snake_case();
 
-    let base = Path::new("backend/src");
-    let project_root = Path::new(".");
+    let base = project.backend_src();
+    let project_root = project.root();
+    let fs = project.fs();
 
     // If target entity doesn't exist, store as pending and return
-    if !relation::entity_exists(project_root, target_entity) {
-        println!(
+    if !relation::entity_exists(project, target_entity) {
+        say!(
             "  Target entity '{}' not found — storing pending M2M relation",
             target_entity
         );
         relation::store_pending(
-            project_root,
+            project,
             relation::PendingRelation {
                 source_entity: source_entity.to_string(),
                 target_entity: target_entity.to_string(),
@@ -46,12 +72,12 @@ pub fn generate(
 
     // Check if junction already exists (idempotency / circular M2M protection)
     let junction_path = base.join(format!("entities/{}.rs", junction_snake));
-    if junction_path.exists() {
-        println!("  Junction entity '{}' already exists, skipping", junction);
+    if fs.exists(&junction_path) {
+        say!("  Junction entity '{}' already exists, skipping", junction);
         // Still inject relations if needed
-        inject_m2m_into_entity(&engine, source_entity, target_entity, &junction)?;
-        if relation::entity_exists(project_root, target_entity) {
-            inject_m2m_into_entity(&engine, target_entity, source_entity, &junction)?;
+        inject_m2m_into_entity(project, &engine, source_entity, target_entity, &junction)?;
+        if relation::entity_exists(project, target_entity) {
+            inject_m2m_into_entity(project, &engine, target_entity, source_entity, &junction)?;
         }
         return Ok(());
     }
@@ -65,51 +91,73 @@ pub fn generate(
 
     let entity_a_snake = entity_a.to_snake_case();
     let entity_b_snake = entity_b.to_snake_case();
+    // Junctions live at the top level, whatever the modules of their entities
+    let module_a = module::locate(project, entity_a);
+    let module_b = module::locate(project, entity_b);
+    let table_a = module::table_name(module_a.as_ref(), &entity_a_snake);
+    let table_b = module::table_name(module_b.as_ref(), &entity_b_snake);
 
     // Build context for junction templates
     let mut ctx = Context::new();
-    ctx.insert("junction_table", &format!("{}_{}", entity_a_snake, entity_b_snake));
+    ctx.insert(
+        "junction_table",
+        &existing_table.map_or_else(|| format!("{}_{}", entity_a_snake, entity_b_snake), str::to_string),
+    );
     ctx.insert("junction_snake", &junction_snake);
     ctx.insert("junction_iden", &junction.to_pascal_case());
     ctx.insert("entity_a", &entity_a.to_pascal_case());
     ctx.insert("entity_b", &entity_b.to_pascal_case());
     ctx.insert("entity_a_snake", &entity_a_snake);
     ctx.insert("entity_b_snake", &entity_b_snake);
-    ctx.insert("entity_a_table", &utils::pluralize(&entity_a_snake));
-    ctx.insert("entity_b_table", &utils::pluralize(&entity_b_snake));
+    ctx.insert("entity_a_table", &table_a);
+    ctx.insert("entity_b_table", &table_b);
+    ctx.insert("entity_a_path", &module::model_path(None, module_a.as_ref(), &entity_a_snake));
+    ctx.insert("entity_b_path", &module::model_path(None, module_b.as_ref(), &entity_b_snake));
+    ctx.insert(
+        "entity_a_table_ref",
+        &module::table_ref(module_a.as_ref(), &format!("Alias::new(\"{}\")", table_a)),
+    );
+    ctx.insert(
+        "entity_b_table_ref",
+        &module::table_ref(module_b.as_ref(), &format!("Alias::new(\"{}\")", table_b)),
+    );
 
     // 1. Generate junction model
     let model_content = engine.render("entity/backend/junction_model.rs.tera", &ctx)?;
-    utils::write_file(&junction_path, &model_content)?;
+    utils::write_file(fs, &junction_path, &model_content)?;
+    utils::ui::created(&project.relative(&junction_path).display().to_string());
 
     // 2. Register junction mod in entities/mod.rs
     let entities_mod = base.join("entities/mod.rs");
-    utils::insert_at_marker(
+    inject::insert(
+        fs,
         &entities_mod,
         markers::MODS,
         &format!("pub mod {};", junction_snake),
     )?;
 
-    // 3. Generate junction migration
-    let timestamp = super::migration::next_timestamp();
-    let migration_content = engine.render("entity/backend/junction_migration.rs.tera", &ctx)?;
-    let migration_module = format!("m{}_create_{}_table", timestamp, junction_snake);
-    let migration_path =
-        Path::new("backend/migration/src").join(format!("{}.rs", migration_module));
-    utils::write_file(&migration_path, &migration_content)?;
+    // 3. Generate junction migration, unless the table already exists
+    if existing_table.is_none() {
+        let timestamp = super::migration::next_timestamp(project);
+        let migration_content = engine.render("entity/backend/junction_migration.rs.tera", &ctx)?;
+        let migration_module = format!("m{}_create_{}_table", timestamp, junction_snake);
+        let migration_path = project.migration_src().join(format!("{}.rs", migration_module));
+        utils::write_file(fs, &migration_path, &migration_content)?;
+        utils::ui::created(&project.relative(&migration_path).display().to_string());
 
-    // 4. Register migration in lib.rs
-    context::register_migration(Path::new("."), &migration_module)?;
+        // 4. Register migration in lib.rs
+        context::register_migration(fs, project_root, &migration_module)?;
+    }
 
     // 5. Inject Related<T> via junction + M2M handlers/routes into source entity
-    inject_m2m_into_entity(&engine, source_entity, target_entity, &junction)?;
+    inject_m2m_into_entity(project, &engine, source_entity, target_entity, &junction)?;
 
     // 6. If target entity exists, inject reverse M2M into it too
-    if relation::entity_exists(project_root, target_entity) {
-        inject_m2m_into_entity(&engine, target_entity, source_entity, &junction)?;
+    if relation::entity_exists(project, target_entity) {
+        inject_m2m_into_entity(project, &engine, target_entity, source_entity, &junction)?;
     }
 
-    println!(
+    say!(
         "  Generated M2M junction: {} <-> {} (via {})",
         source_entity, target_entity, junction
     );
@@ -125,35 +173,40 @@ const TEMPLATE_SPLIT_DELIMITER: &str = "\n---ROMANCE_SPLIT---\n";
 /// - M2M handlers (list/add/remove) into handlers
 /// - M2M routes into routes
 fn inject_m2m_into_entity(
+    project: &Project,
     engine: &TemplateEngine,
     entity: &str,
     related: &str,
     junction: &str,
 ) -> Result<()> {
-    let base = Path::new("backend/src");
+    let fs = project.fs();
     let entity_snake = entity.to_snake_case();
     let related_snake = related.to_snake_case();
     let junction_snake = junction.to_snake_case();
+    let entity_module = module::locate(project, entity);
+    let related_module = module::locate(project, related);
 
     // 1. Inject Related<related::Entity> via junction into entity model
-    let model_path = base.join(format!("entities/{}.rs", entity_snake));
-    if model_path.exists() {
+    let model_path = project.join(module::backend_file(entity_module.as_ref(), "entities", &entity_snake));
+    if fs.exists(&model_path) {
+        let junction_model = module::model_path(entity_module.as_ref(), None, &junction_snake);
         let related_impl = format!(
-            r#"impl Related<super::{}::Entity> for Entity {{
+            r#"impl Related<{}::Entity> for Entity {{
     fn to() -> RelationDef {{
-        super::{}::Relation::{}.def()
+        {}::Relation::{}.def()
     }}
     fn via() -> Option<RelationDef> {{
-        Some(super::{}::Relation::{}.def().rev())
+        Some({}::Relation::{}.def().rev())
     }}
 }}"#,
-            related_snake,
-            junction_snake,
+            module::model_path(entity_module.as_ref(), related_module.as_ref(), &related_snake),
+            junction_model,
             related.to_pascal_case(),
-            junction_snake,
+            junction_model,
             entity.to_pascal_case(),
         );
-        utils::insert_at_marker(
+        inject::insert(
+            fs,
             &model_path,
             markers::RELATIONS,
             &related_impl,
@@ -161,15 +214,17 @@ fn inject_m2m_into_entity(
     }
 
     // 2. Inject M2M handlers into entity handlers via Tera template
-    let handlers_path = base.join(format!("handlers/{}.rs", entity_snake));
-    if handlers_path.exists() {
+    let handlers_path = project.join(module::backend_file(entity_module.as_ref(), "handlers", &entity_snake));
+    if fs.exists(&handlers_path) {
         let related_plural = utils::pluralize(&related_snake);
 
         let mut ctx = Context::new();
         ctx.insert("entity_name", &entity.to_pascal_case());
         ctx.insert("entity_snake", &entity_snake);
+        ctx.insert("entity_path", &module::rust_path(entity_module.as_ref(), &entity_snake));
         ctx.insert("related_name", &related.to_pascal_case());
         ctx.insert("related_snake", &related_snake);
+        ctx.insert("related_path", &module::rust_path(related_module.as_ref(), &related_snake));
         ctx.insert("related_plural", &related_plural);
         ctx.insert("junction_snake", &junction_snake);
 
@@ -178,7 +233,8 @@ fn inject_m2m_into_entity(
 
         for part in &parts {
             let trimmed = part.trim_end_matches('\n');
-            utils::insert_at_marker(
+            inject::insert(
+                fs,
                 &handlers_path,
                 markers::RELATION_HANDLERS,
                 trimmed,
@@ -187,13 +243,9 @@ fn inject_m2m_into_entity(
     }
 
     // 3. Inject M2M routes into entity routes via Tera template
-    let routes_path = base.join(format!("routes/{}.rs", entity_snake));
-    if routes_path.exists() {
-        let project_root = Path::new(".");
-        let config = crate::config::RomanceConfig::load(project_root).ok();
-        let api_prefix = config.as_ref()
-            .and_then(|c| c.backend.api_prefix.clone())
-            .unwrap_or_else(|| "/api".to_string());
+    let routes_path = project.join(module::backend_file(entity_module.as_ref(), "routes", &entity_snake));
+    if fs.exists(&routes_path) {
+        let api_prefix = module::route_prefix(entity_module.as_ref(), &project.features().api_prefix);
 
         let entity_plural = utils::pluralize(&entity_snake);
         let related_plural = utils::pluralize(&related_snake);
@@ -212,7 +264,8 @@ fn inject_m2m_into_entity(
 
         for part in &parts {
             let trimmed = part.trim_end_matches('\n');
-            utils::insert_at_marker(
+            inject::insert(
+                fs,
                 &routes_path,
                 markers::RELATION_ROUTES,
                 trimmed,
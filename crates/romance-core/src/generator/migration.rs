@@ -1,30 +1,37 @@
 use crate::entity::EntityDefinition;
-use crate::generator::context::{self, markers, ProjectFeatures};
+use crate::error::Failure;
+use crate::generator::context::{self, markers};
 use crate::generator::plan::{self, GenerationTracker};
+use crate::inject;
+use crate::manifest::{self, FileCategory, Manifest, RenderedFile};
+use crate::module;
+use crate::project::Project;
+use crate::relation;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use heck::ToSnakeCase;
-use std::path::Path;
 use tera::Context;
 
 /// Generate a unique migration timestamp by scanning existing migration files.
 /// If the current second already has migrations, increment until unique.
-pub fn next_timestamp() -> String {
+pub fn next_timestamp(project: &Project) -> String {
     let base = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
-    let migration_dir = Path::new("backend/migration/src");
+    let migration_dir = project.migration_src();
 
-    if !migration_dir.exists() {
+    if !project.fs().exists(&migration_dir) {
         return base;
     }
 
     // Collect all existing timestamps from migration filenames (m{timestamp}_...)
-    let existing: Vec<String> = std::fs::read_dir(migration_dir)
+    let existing: Vec<String> = project
+        .fs()
+        .read_dir(&migration_dir)
         .into_iter()
         .flatten()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| {
-            let name = e.file_name().to_string_lossy().to_string();
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
             if name.starts_with('m') && name.ends_with(".rs") {
                 // Extract timestamp: m20260214173404_create_...
                 name.get(1..15).map(|s| s.to_string())
@@ -54,28 +61,153 @@ pub fn next_timestamp() -> String {
     base
 }
 
+/// The project's migration modules, oldest first.
+pub fn modules(project: &Project) -> Vec<String> {
+    let mut modules: Vec<String> = project
+        .fs()
+        .read_dir(&project.migration_src())
+        .into_iter()
+        .flatten()
+        .filter_map(|path| {
+            let module = path.file_name()?.to_string_lossy().strip_suffix(".rs")?.to_string();
+            let (stamp, _) = module.strip_prefix('m')?.split_once('_')?;
+            (!stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_digit())).then_some(module)
+        })
+        .collect();
+    modules.sort();
+    modules
+}
+
+/// The modules `romance db squash` replaced with baseline migrations, read
+/// from the `//! Squashed:` lines of the baselines.
+pub fn squashed_modules(project: &Project) -> Vec<String> {
+    let mut squashed = Vec::new();
+    for module in modules(project).iter().filter(|m| m.ends_with("_squashed_baseline")) {
+        let path = project.migration_src().join(format!("{}.rs", module));
+        let content = project.fs().read_to_string(&path).unwrap_or_default();
+        squashed.extend(content.lines().filter_map(|l| l.strip_prefix("//! Squashed:")).map(|m| m.trim().to_string()));
+    }
+    squashed
+}
+
+/// Whether `module` is a `m<timestamp>_create_<name>_table` migration.
+pub fn creates_table(module: &str, name: &str) -> bool {
+    module
+        .strip_prefix('m')
+        .and_then(|rest| rest.strip_suffix(&format!("_create_{}_table", name)))
+        .is_some_and(|stamp| !stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The latest `m<timestamp>_create_<name>_table` migration module, if any.
+/// Entity migrations are named after the module, auth's and the addons'
+/// after the table.
+pub fn find_create_migration(project: &Project, name: &str) -> Option<String> {
+    modules(project).into_iter().rfind(|module| creates_table(module, name))
+}
+
+/// Tables recorded by `m<timestamp>_import_baseline` migrations (and carried
+/// over by squashed baselines): imported tables that existed before the
+/// project had migrations for them.
+pub fn baseline_tables(project: &Project) -> Vec<String> {
+    let mut tables = Vec::new();
+    for path in project.fs().read_dir(&project.migration_src()).into_iter().flatten() {
+        let is_baseline = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .is_some_and(|name| name.ends_with("_import_baseline.rs") || name.ends_with("_squashed_baseline.rs"));
+        if !is_baseline {
+            continue;
+        }
+        let content = project.fs().read_to_string(&path).unwrap_or_default();
+        for line in content.lines() {
+            if let Some(list) = line.strip_prefix("//! Tables:") {
+                tables.extend(list.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()));
+            }
+        }
+    }
+    tables
+}
+
+/// The names an entity module had before `romance rename entity`, latest
+/// first, read from the `m<timestamp>_rename_<from>_to_<to>` migrations of
+/// the entity or, for a junction, of either side.
+pub fn former_names(project: &Project, name: &str) -> Vec<String> {
+    let mut renames: Vec<String> = modules(project)
+        .into_iter()
+        .chain(squashed_modules(project))
+        .filter(|module| {
+            module
+                .strip_prefix('m')
+                .and_then(|rest| rest.split_once("_rename_"))
+                .is_some_and(|(stamp, _)| stamp.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect();
+    renames.sort();
+
+    let mut names = Vec::new();
+    let mut current = name.to_string();
+    while let Some(from) = renamed_from(&renames, &current) {
+        if from == name || names.contains(&from) {
+            break;
+        }
+        names.push(from.clone());
+        current = from;
+    }
+    names
+}
+
+/// The name `module` had before the latest of the `renames` that renamed it.
+fn renamed_from(renames: &[String], module: &str) -> Option<String> {
+    renames.iter().rev().find_map(|migration| {
+        let (_, rest) = migration.split_once("_rename_")?;
+        let (from, to) = rest.split_once("_to_")?;
+        if to == module {
+            return Some(from.to_string());
+        }
+        // Junction tables are renamed along with the entity
+        let partner = module
+            .strip_prefix(&format!("{}_", to))
+            .or_else(|| module.strip_suffix(&format!("_{}", to)))?;
+        (relation::junction_name(to, partner) == module).then(|| relation::junction_name(from, partner))
+    })
+}
+
 /// Pre-validate that migration markers exist.
-pub fn validate(_entity: &EntityDefinition) -> Result<()> {
-    let lib_path = Path::new("backend/migration/src/lib.rs");
+pub fn validate(project: &Project, _entity: &EntityDefinition) -> Result<()> {
+    let lib_path = project.migration_src().join("lib.rs");
     let checks = vec![
-        plan::check(lib_path, markers::MIGRATION_MODS),
+        plan::check(&lib_path, markers::MIGRATION_MODS),
         plan::check(lib_path, markers::MIGRATIONS),
     ];
-    plan::validate_markers(&checks)
+    plan::validate_markers(project.fs(), &checks)
 }
 
-pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> Result<()> {
-    let engine = TemplateEngine::new()?;
+pub fn generate(
+    project: &Project,
+    entity: &EntityDefinition,
+    tracker: &mut GenerationTracker,
+) -> Result<()> {
+    let engine = TemplateEngine::for_project(project)?;
 
-    let timestamp = next_timestamp();
+    let timestamp = next_timestamp(project);
     let snake_name = entity.name.to_snake_case();
+    let entity_module = module::of_entity(project, entity)?;
 
     let mut ctx = Context::new();
     ctx.insert("entity_name", &entity.name);
     ctx.insert("entity_name_snake", &snake_name);
     ctx.insert("timestamp", &timestamp);
+    let table_name = entity
+        .table_name
+        .clone()
+        .unwrap_or_else(|| module::table_name(entity_module.as_ref(), &snake_name));
+    ctx.insert("table_name", &table_name);
+    ctx.insert(
+        "table_ref",
+        &module::table_ref(entity_module.as_ref(), &format!("{}::Table", entity.name)),
+    );
 
-    let features = ProjectFeatures::load(Path::new("."));
+    let features = project.features();
     let has_searchable_fields = entity.fields.iter().any(|f| f.searchable);
 
     ctx.insert("soft_delete", &features.soft_delete);
@@ -86,6 +218,13 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
         .fields
         .iter()
         .map(|f| {
+            // The referenced table, in the target's module (or the entity's,
+            // if the target doesn't exist yet)
+            let relation_table = f.relation.as_ref().map(|target| {
+                let target_module = module::of_target(project, entity_module.as_ref(), target);
+                let table = module::table_name(target_module.as_ref(), &target.to_snake_case());
+                module::table_ref(target_module.as_ref(), &format!("Alias::new(\"{}\")", table))
+            });
             serde_json::json!({
                 "name": f.name,
                 "postgres_type": f.field_type.to_postgres(),
@@ -93,6 +232,7 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
                 "migration_method": f.field_type.to_sea_orm_migration(),
                 "optional": f.optional,
                 "relation": f.relation,
+                "relation_table": relation_table,
                 "searchable": f.searchable,
             })
         })
@@ -101,14 +241,295 @@ pub fn generate(entity: &EntityDefinition, tracker: &mut GenerationTracker) -> R
 
     let content = engine.render("entity/backend/migration.rs.tera", &ctx)?;
     let migration_module = format!("m{}_create_{}_table", timestamp, snake_name);
-    let migration_path =
-        Path::new("backend/migration/src").join(format!("{}.rs", migration_module));
-    utils::write_file(&migration_path, &content)?;
-    tracker.track(migration_path.to_path_buf());
+    let migration_path = project.migration_src().join(format!("{}.rs", migration_module));
+    utils::write_file(project.fs(), &migration_path, &content)?;
+    utils::ui::created(&project.relative(&migration_path).display().to_string());
+    tracker.track(migration_path);
 
     // Register migration in lib.rs
-    context::register_migration(Path::new("."), &migration_module)?;
+    context::register_migration(project.fs(), project.root(), &migration_module)?;
 
-    println!("  Generated migration for '{}'", entity.name);
+    say!("  Generated migration for '{}'", entity.name);
     Ok(())
 }
+
+/// Generate an empty migration `m<timestamp>_<name>` to fill in by hand,
+/// or with `sql` one running the statements of `sql/<module>.up.sql` and
+/// `.down.sql`. Returns the migration module.
+pub fn generate_blank(project: &Project, name: &str, sql: bool, tracker: &mut GenerationTracker) -> Result<String> {
+    let snake_name = name.to_snake_case();
+    let valid = snake_name.starts_with(|c: char| c.is_ascii_lowercase())
+        && snake_name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(Failure::InvalidInput.error(format!(
+            "Invalid migration name '{}': use a snake_case name such as 'add_price_index'",
+            name
+        )));
+    }
+
+    let lib_path = project.migration_src().join("lib.rs");
+    let checks = [
+        plan::check(&lib_path, markers::MIGRATION_MODS),
+        plan::check(lib_path, markers::MIGRATIONS),
+    ];
+    plan::validate_markers(project.fs(), &checks)?;
+
+    let migration_module = format!("m{}_{}", next_timestamp(project), snake_name);
+    let template = if sql {
+        "migration/sql_migration.rs.tera"
+    } else {
+        "migration/blank_migration.rs.tera"
+    };
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert("migration_module", &migration_module);
+
+    let mut files = vec![RenderedFile {
+        output: format!("backend/migration/src/{}.rs", migration_module),
+        template: Some(template.to_string()),
+        category: FileCategory::Migration,
+        entity_name: None,
+        content: engine.render(template, &ctx)?,
+    }];
+    if sql {
+        for (direction, template) in [("up", "migration/up.sql.tera"), ("down", "migration/down.sql.tera")] {
+            files.push(RenderedFile {
+                output: format!("backend/migration/sql/{}.{}.sql", migration_module, direction),
+                template: Some(template.to_string()),
+                category: FileCategory::Migration,
+                entity_name: None,
+                content: engine.render(template, &ctx)?,
+            });
+        }
+    }
+    for file in &files {
+        let path = project.join(&file.output);
+        utils::write_file(project.fs(), &path, &file.content)?;
+        utils::ui::created(&file.output);
+        tracker.track(path);
+    }
+
+    context::register_migration(project.fs(), project.root(), &migration_module)?;
+    manifest::track_generated(project, &files)?;
+    Ok(migration_module)
+}
+
+/// The baseline migration written by [`squash`].
+pub struct SquashedBaseline {
+    pub module: String,
+    /// The statements recording the baseline as applied, in a database that
+    /// had applied the squashed migrations.
+    pub record_sql: String,
+}
+
+/// Check that every migration in `squashed` is in `applied`, the versions a
+/// database has run: only migrations every database has applied can be
+/// replaced with a baseline.
+pub fn check_applied(squashed: &[String], applied: &[String]) -> Result<()> {
+    let pending: Vec<&str> = squashed.iter().filter(|m| !applied.contains(m)).map(String::as_str).collect();
+    if !pending.is_empty() {
+        return Err(Failure::Precondition.error(format!(
+            "Not applied yet, so they can't be squashed: {} (run romance db migrate first)",
+            pending.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Replace `squashed`, the oldest migrations, with one baseline migration
+/// `m<timestamp>_squashed_baseline` running `schema`, the SQL dump of the
+/// schema they create. The baseline takes the place of the first of them in
+/// `migrations()`, and its `down` drops the tables again.
+pub fn squash(
+    project: &Project,
+    squashed: &[String],
+    schema: &str,
+    tracker: &mut GenerationTracker,
+) -> Result<SquashedBaseline> {
+    let fs = project.fs();
+    let src = project.migration_src();
+    let lib_path = src.join("lib.rs");
+    let checks = [
+        plan::check(&lib_path, markers::MIGRATION_MODS),
+        plan::check(&lib_path, markers::MIGRATIONS),
+    ];
+    plan::validate_markers(fs, &checks)?;
+
+    let all = modules(project);
+    let (Some(first), Some(last)) = (squashed.first(), squashed.last()) else {
+        return Err(Failure::InvalidInput.error("No migrations to squash"));
+    };
+    if !all.starts_with(squashed) {
+        return Err(Failure::InvalidInput.error("Only the oldest migrations can be squashed"));
+    }
+    // Later migrations can't reach into squashed ones (e.g. a drop
+    // migration re-running the create migration in its `down`)
+    let mut references = Vec::new();
+    for module in &all[squashed.len()..] {
+        let content = fs.read_to_string(&src.join(format!("{}.rs", module)))?;
+        if let Some(used) = squashed
+            .iter()
+            .find(|m| content.contains(&format!("crate::{}::", m)) || content.contains(&format!("super::{}::", m)))
+        {
+            references.push(format!("{} uses {}", module, used));
+        }
+    }
+    if !references.is_empty() {
+        return Err(Failure::Precondition.error(format!(
+            "Later migrations refer to migrations being squashed: {} (squash up to a later timestamp)",
+            references.join(", ")
+        )));
+    }
+
+    let stamp = last[1..].split_once('_').map_or("", |(stamp, _)| stamp);
+    let module = format!("m{}_squashed_baseline", stamp);
+    // Earlier baselines pass on what they recorded
+    let mut names = Vec::new();
+    let mut tables = Vec::new();
+    for squashed_module in squashed {
+        let content = fs.read_to_string(&src.join(format!("{}.rs", squashed_module)))?;
+        for line in content.lines() {
+            if let Some(name) = line.strip_prefix("//! Squashed:") {
+                names.push(name.trim().to_string());
+            } else if let Some(list) = line.strip_prefix("//! Tables:") {
+                tables.extend(list.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()));
+            }
+        }
+        names.push(squashed_module.clone());
+    }
+    let record_sql = format!(
+        "DELETE FROM seaql_migrations WHERE version <= '{}';\n\
+         INSERT INTO seaql_migrations (version, applied_at) VALUES ('{}', EXTRACT(EPOCH FROM now())::bigint) ON CONFLICT DO NOTHING;\n",
+        last, module
+    );
+
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert("migration_module", &module);
+    ctx.insert("squashed", &names);
+    ctx.insert("tables", &tables);
+    ctx.insert("record_sql", &record_sql.lines().collect::<Vec<_>>());
+    let template = "migration/squashed_baseline.rs.tera";
+    let files = vec![
+        RenderedFile {
+            output: format!("backend/migration/src/{}.rs", module),
+            template: Some(template.to_string()),
+            category: FileCategory::Migration,
+            entity_name: None,
+            content: engine.render(template, &ctx)?,
+        },
+        RenderedFile {
+            output: format!("backend/migration/sql/{}.up.sql", module),
+            template: None,
+            category: FileCategory::Migration,
+            entity_name: None,
+            content: format!(
+                "-- {}: the schema of the {} squashed migration(s), dumped by `romance db squash`.\n\n{}",
+                module,
+                names.len(),
+                schema.trim_start()
+            ),
+        },
+        RenderedFile {
+            output: format!("backend/migration/sql/{}.down.sql", module),
+            template: None,
+            category: FileCategory::Migration,
+            entity_name: None,
+            content: drop_schema_sql(&module, schema),
+        },
+    ];
+
+    // The squashed migrations and the SQL files they embed
+    let mut removed = Vec::new();
+    for squashed_module in squashed {
+        removed.push(src.join(format!("{}.rs", squashed_module)));
+        for direction in ["up", "down"] {
+            let sql = project.join(format!("backend/migration/sql/{}.{}.sql", squashed_module, direction));
+            if fs.exists(&sql) {
+                removed.push(sql);
+            }
+        }
+    }
+
+    for file in &files {
+        let path = project.join(&file.output);
+        utils::write_file(fs, &path, &file.content)?;
+        utils::ui::created(&file.output);
+        tracker.track(path);
+    }
+    // The baseline takes the first migration's entries, the others go
+    let lib = fs.read_to_string(&lib_path)?;
+    let lib = lib
+        .replace(&format!("mod {};", first), &format!("mod {};", module))
+        .replace(&format!("{}::Migration", first), &format!("{}::Migration", module));
+    fs.write(&lib_path, &lib)?;
+    for squashed_module in &squashed[1..] {
+        inject::remove(fs, &lib_path, markers::MIGRATION_MODS, &format!("mod {};", squashed_module))?;
+        inject::remove(
+            fs,
+            &lib_path,
+            markers::MIGRATIONS,
+            &format!("            Box::new({}::Migration),", squashed_module),
+        )?;
+    }
+    utils::ui::updated(&project.relative(&lib_path).display().to_string());
+    for path in &removed {
+        fs.remove_file(path)?;
+        utils::ui::removed(&project.relative(path).display().to_string());
+    }
+
+    if Manifest::exists(fs, project.root()) {
+        let mut manifest = Manifest::load(fs, project.root())?;
+        manifest
+            .files
+            .retain(|output, _| !removed.iter().any(|path| project.relative(path) == std::path::Path::new(output)));
+        manifest.save(fs, project.root())?;
+    }
+    manifest::track_generated(project, &files)?;
+
+    Ok(SquashedBaseline { module, record_sql })
+}
+
+/// The `down` of a squashed baseline: drop the tables and types `schema`
+/// creates, latest first.
+fn drop_schema_sql(module: &str, schema: &str) -> String {
+    let create = regex::Regex::new(r"(?m)^CREATE (TABLE|TYPE) ([^\s(]+)").unwrap();
+    let created: Vec<(String, String)> = create
+        .captures_iter(schema)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .collect();
+    let mut sql = format!("-- {}: drops the schema of up.sql, run by `romance db rollback`.\n", module);
+    for kind in ["TABLE", "TYPE"] {
+        for (_, name) in created.iter().rev().filter(|(k, _)| k == kind) {
+            sql.push_str(&format!("DROP {} IF EXISTS {} CASCADE;\n", kind, name));
+        }
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(modules: &[&str]) -> Vec<String> {
+        modules.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn only_applied_migrations_can_be_squashed() {
+        let squashed = names(&[
+            "m20260101000000_create_post_table",
+            "m20260102000000_create_tag_table",
+            "m20260103000000_create_post_tag_table",
+        ]);
+        assert!(check_applied(&squashed, &squashed).is_ok());
+
+        let applied = names(&["m20260101000000_create_post_table", "m20260103000000_create_post_tag_table"]);
+        let err = check_applied(&squashed, &applied).unwrap_err();
+        assert_eq!(Failure::of(&err), Some(Failure::Precondition));
+        let message = err.to_string();
+        assert!(message.contains("m20260102000000_create_tag_table"), "{}", message);
+        assert!(!message.contains("create_post_table"), "{}", message);
+        assert!(!message.contains("create_post_tag_table"), "{}", message);
+    }
+}
@@ -2,12 +2,17 @@ pub mod admin;
 pub mod auth;
 pub mod backend;
 pub mod context;
+pub mod destroy;
 pub mod frontend;
 pub mod junction;
 pub mod migration;
+pub mod module;
 pub mod openapi;
 pub mod orchestrator;
 pub mod plan;
+pub mod recipe;
+pub mod recover;
+pub mod rename;
 pub mod types;
 
 pub use orchestrator::check_entity_prerequisites;
@@ -0,0 +1,174 @@
+//! `romance generate module`: declare an entity module and create the files
+//! its entities are registered in.
+
+use crate::config::ModuleConfig;
+use crate::error::Failure;
+use crate::generator::context::{self, markers};
+use crate::generator::migration;
+use crate::generator::plan::{self, GenerationTracker};
+use crate::inject;
+use crate::manifest::{self, FileCategory, RenderedFile};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::Result;
+use tera::Context;
+
+/// Declare module `name` in `romance.toml`, create its `mod.rs` files and
+/// register it with the router, OpenAPI tags and the sidebar. With a schema,
+/// also generates a migration creating it.
+pub fn generate(project: &mut Project, name: &str, config: ModuleConfig, tracker: &mut GenerationTracker) -> Result<()> {
+    module::validate_name(name)?;
+    if project.config().modules.contains_key(name) {
+        return Err(Failure::Precondition.error(format!("Module '{}' already exists", name)));
+    }
+    if let Some(schema) = &config.schema {
+        module::validate_name(schema)?;
+    }
+
+    let fs = project.fs();
+    let base = project.backend_src();
+    for dir in ["entities", "handlers", "routes"] {
+        let clash = base.join(dir).join(format!("{}.rs", name));
+        if fs.exists(&clash) {
+            return Err(Failure::Precondition.error(format!(
+                "Module '{}' would clash with {}",
+                name,
+                project.relative(&clash).display()
+            )));
+        }
+    }
+
+    let sidebar_path = project.frontend_src().join("components/AppSidebar.tsx");
+    let sidebar = fs.read_to_string(&sidebar_path).unwrap_or_default();
+    let sections_marker = if sidebar.contains(markers::NAV_SECTIONS) {
+        markers::NAV_SECTIONS
+    } else {
+        markers::NAV_LINKS
+    };
+    let routes_mod = base.join("routes/mod.rs");
+    let mut checks = vec![
+        plan::check(&routes_mod, markers::ROUTES),
+        plan::check(&routes_mod, markers::MODS),
+        plan::check(base.join("entities/mod.rs"), markers::MODS),
+        plan::check(base.join("handlers/mod.rs"), markers::MODS),
+        plan::check(&sidebar_path, sections_marker),
+    ];
+    if config.schema.is_some() {
+        let lib_path = project.migration_src().join("lib.rs");
+        checks.push(plan::check(&lib_path, markers::MIGRATION_MODS));
+        checks.push(plan::check(lib_path, markers::MIGRATIONS));
+    }
+    plan::validate_markers(fs, &checks)?;
+
+    let m = EntityModule {
+        name: name.to_string(),
+        config,
+    };
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert("module_name", &m.name);
+    ctx.insert("module_title", &m.title());
+    ctx.insert("route_prefix", &module::route_prefix(Some(&m), &project.features().api_prefix));
+
+    // 1. The module's mod.rs files, which its entities are registered in
+    let mut files = Vec::new();
+    for dir in ["entities", "handlers"] {
+        files.push(RenderedFile {
+            output: format!("backend/src/{}/{}/mod.rs", dir, m.name),
+            template: None,
+            category: FileCategory::Marker,
+            entity_name: None,
+            content: format!("{}\n", markers::MODS),
+        });
+    }
+    files.push(RenderedFile {
+        output: format!("backend/src/routes/{}/mod.rs", m.name),
+        template: Some("module/backend/routes.rs.tera".to_string()),
+        category: FileCategory::Marker,
+        entity_name: None,
+        content: engine.render("module/backend/routes.rs.tera", &ctx)?,
+    });
+    for file in &files {
+        let path = project.join(&file.output);
+        utils::write_file(fs, &path, &file.content)?;
+        utils::ui::created(&file.output);
+        tracker.track(path);
+    }
+    manifest::track_generated(project, &files)?;
+
+    // 2. Register the module like a top-level entity, router included
+    context::register_backend_module(fs, &base, &m.name)?;
+
+    // 3. One OpenAPI tag for all of the module's endpoints
+    let main_rs = base.join("main.rs");
+    let main_has_openapi = fs.exists(&main_rs)
+        && fs
+            .read_to_string(&main_rs)
+            .map(|c| c.contains(markers::OPENAPI_TAGS))
+            .unwrap_or(false);
+    if main_has_openapi {
+        inject::insert(
+            fs,
+            &main_rs,
+            markers::OPENAPI_TAGS,
+            &format!(
+                "        (name = \"{}\", description = \"{} module\"),",
+                m.title(),
+                m.title()
+            ),
+        )?;
+    }
+
+    // 4. A sidebar section the module's nav links go in
+    utils::insert_at_marker(
+        fs,
+        &sidebar_path,
+        sections_marker,
+        &format!(
+            "        <div className=\"pt-4 space-y-1\">\n          <p className=\"px-3 pb-1 text-xs font-semibold uppercase tracking-wider text-sidebar-foreground/50\">{}</p>\n          {}\n        </div>",
+            m.title(),
+            m.nav_marker()
+        ),
+    )?;
+
+    // 5. The Postgres schema holding the module's tables
+    if let Some(schema) = m.schema() {
+        let mut ctx = Context::new();
+        ctx.insert("schema", schema);
+        let content = engine.render("module/backend/schema_migration.rs.tera", &ctx)?;
+        let migration_module = format!("m{}_create_{}_schema", migration::next_timestamp(project), schema);
+        let migration_path = project.migration_src().join(format!("{}.rs", migration_module));
+        utils::write_file(fs, &migration_path, &content)?;
+        utils::ui::created(&project.relative(&migration_path).display().to_string());
+        tracker.track(migration_path);
+        context::register_migration(fs, project.root(), &migration_module)?;
+    }
+
+    // 6. Declare it in romance.toml
+    let config_path = project.join("romance.toml");
+    let content = fs.read_to_string(&config_path)?;
+    let mut section = format!("\n[modules.{}]\n", m.name);
+    for (key, value) in [
+        ("prefix", &m.config.prefix),
+        ("table_prefix", &m.config.table_prefix),
+        ("schema", &m.config.schema),
+    ] {
+        if let Some(value) = value {
+            section.push_str(&format!("{} = {}\n", key, toml::Value::from(value.as_str())));
+        }
+    }
+    let updated = format!("{}\n{}", content.trim_end(), section);
+    fs.write(&config_path, &updated)?;
+    crate::manifest::refresh(fs, project.root(), "romance.toml", &content, &updated)?;
+    utils::ui::updated("romance.toml");
+
+    project.reload()?;
+    utils::ui::success(&format!(
+        "Generated module '{}' (routes under {})",
+        m.name,
+        module::route_prefix(Some(&m), &project.features().api_prefix)
+    ));
+    Ok(())
+}
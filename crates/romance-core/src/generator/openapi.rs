@@ -1,15 +1,19 @@
+use crate::error::Failure;
+use crate::project::Project;
+use crate::say;
 use anyhow::Result;
 use std::process::Command;
 
-pub fn generate() -> Result<()> {
-    println!("Generating OpenAPI spec...");
+pub fn generate(project: &Project) -> Result<()> {
+    say!("Generating OpenAPI spec...");
     let status = Command::new("cargo")
         .args(["run", "--bin", "openapi-export"])
-        .current_dir("backend")
+        .current_dir(project.join("backend"))
+        .stdout(crate::utils::ui::child_stdout())
         .status()?;
     if !status.success() {
-        anyhow::bail!("OpenAPI generation failed");
+        return Err(Failure::Command.error("OpenAPI generation failed"));
     }
-    println!("OpenAPI spec generated at backend/openapi.json");
+    say!("OpenAPI spec generated at backend/openapi.json");
     Ok(())
 }
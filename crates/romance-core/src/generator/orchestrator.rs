@@ -1,6 +1,6 @@
 use crate::entity::{EntityDefinition, RelationType};
-use heck::ToSnakeCase;
-use std::path::Path;
+use crate::project::Project;
+use crate::relation;
 
 /// Check if prerequisites for entity generation are met.
 ///
@@ -10,20 +10,18 @@ use std::path::Path;
 ///
 /// M2M and HasMany relations are not checked here because the pending relations
 /// system handles deferred application automatically.
-pub fn check_entity_prerequisites(entity: &EntityDefinition, project_root: &Path) -> Vec<String> {
+pub fn check_entity_prerequisites(entity: &EntityDefinition, project: &Project) -> Vec<String> {
     let mut warnings = vec![];
 
     for rel in &entity.relations {
-        if rel.relation_type == RelationType::BelongsTo {
-            let target_snake = rel.target_entity.to_snake_case();
-            let target_path = project_root.join(format!("backend/src/entities/{}.rs", target_snake));
-            if !target_path.exists() {
-                warnings.push(format!(
-                    "Warning: Target entity '{}' does not exist yet. \
-                     Has-many reverse relation will not be injected.",
-                    rel.target_entity
-                ));
-            }
+        if rel.relation_type == RelationType::BelongsTo
+            && !relation::entity_exists(project, &rel.target_entity)
+        {
+            warnings.push(format!(
+                "Warning: Target entity '{}' does not exist yet. \
+                 Has-many reverse relation will not be injected.",
+                rel.target_entity
+            ));
         }
     }
 
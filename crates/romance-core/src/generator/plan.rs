@@ -1,3 +1,5 @@
+use crate::error::Failure;
+use crate::fs::ProjectFs;
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -18,11 +20,11 @@ pub fn check(path: impl Into<PathBuf>, marker: &str) -> MarkerCheck {
 /// Validate that all expected markers exist in their respective files.
 ///
 /// Returns `Err` listing all missing markers if any are absent.
-pub fn validate_markers(checks: &[MarkerCheck]) -> Result<()> {
+pub fn validate_markers(fs: &dyn ProjectFs, checks: &[MarkerCheck]) -> Result<()> {
     let mut missing = Vec::new();
 
     for c in checks {
-        if !c.path.exists() {
+        if !fs.exists(&c.path) {
             missing.push(format!(
                 "File '{}' does not exist (expected marker '{}')",
                 c.path.display(),
@@ -30,7 +32,7 @@ pub fn validate_markers(checks: &[MarkerCheck]) -> Result<()> {
             ));
             continue;
         }
-        let content = std::fs::read_to_string(&c.path)?;
+        let content = fs.read_to_string(&c.path)?;
         if !content.contains(&c.marker) {
             missing.push(format!(
                 "Marker '{}' not found in '{}'",
@@ -43,15 +45,16 @@ pub fn validate_markers(checks: &[MarkerCheck]) -> Result<()> {
     if missing.is_empty() {
         Ok(())
     } else {
-        anyhow::bail!(
+        Err(Failure::Precondition.error(format!(
             "Pre-validation failed — {} missing marker(s):\n  {}",
             missing.len(),
             missing.join("\n  ")
-        );
+        )))
     }
 }
 
 /// Tracks newly created files during generation for rollback on failure.
+#[derive(Default)]
 pub struct GenerationTracker {
     created_files: Vec<PathBuf>,
 }
@@ -69,10 +72,10 @@ impl GenerationTracker {
     }
 
     /// Delete all tracked files (best-effort rollback).
-    pub fn rollback(&self) {
+    pub fn rollback(&self, fs: &dyn ProjectFs) {
         for path in &self.created_files {
-            if path.exists() {
-                if let Err(e) = std::fs::remove_file(path) {
+            if fs.exists(path) {
+                if let Err(e) = fs.remove_file(path) {
                     eprintln!(
                         "  Warning: failed to clean up '{}': {}",
                         path.display(),
@@ -89,6 +92,7 @@ impl GenerationTracker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -103,7 +107,7 @@ mod tests {
             check(tmp.path(), "// === ROMANCE:MODS ==="),
             check(tmp.path(), "// === ROMANCE:ROUTES ==="),
         ];
-        assert!(validate_markers(&checks).is_ok());
+        assert!(validate_markers(&DiskFs, &checks).is_ok());
     }
 
     #[test]
@@ -113,7 +117,7 @@ mod tests {
         tmp.flush().unwrap();
 
         let checks = vec![check(tmp.path(), "// === ROMANCE:MODS ===")];
-        let result = validate_markers(&checks);
+        let result = validate_markers(&DiskFs, &checks);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("ROMANCE:MODS"));
     }
@@ -124,7 +128,7 @@ mod tests {
             std::path::Path::new("/tmp/romance_nonexistent_12345.rs"),
             "// === ROMANCE:MODS ===",
         )];
-        let result = validate_markers(&checks);
+        let result = validate_markers(&DiskFs, &checks);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
@@ -138,7 +142,7 @@ mod tests {
 
         let mut tracker = GenerationTracker::new();
         tracker.track(file_path.clone());
-        tracker.rollback();
+        tracker.rollback(&DiskFs);
 
         assert!(!file_path.exists());
     }
@@ -157,7 +161,7 @@ mod tests {
         tracker.track(file_a.clone());
         tracker.track(file_b.clone());
         tracker.track(file_c.clone());
-        tracker.rollback();
+        tracker.rollback(&DiskFs);
 
         assert!(!file_a.exists());
         assert!(!file_b.exists());
@@ -173,7 +177,7 @@ mod tests {
         let mut tracker = GenerationTracker::new();
         tracker.track(file_path.clone());
         // Should not panic
-        tracker.rollback();
+        tracker.rollback(&DiskFs);
     }
 
     #[test]
@@ -186,7 +190,7 @@ mod tests {
             check(tmp.path(), "// === ROMANCE:MODS ==="),
             check(tmp.path(), "// === ROMANCE:ROUTES ==="),
         ];
-        let result = validate_markers(&checks);
+        let result = validate_markers(&DiskFs, &checks);
         assert!(result.is_err());
         let msg = result.unwrap_err().to_string();
         // Both missing markers should be listed
@@ -205,7 +209,7 @@ mod tests {
             check(tmp.path(), "// === ROMANCE:MODS ==="),
             check(tmp.path(), "// === ROMANCE:ROUTES ==="),
         ];
-        let result = validate_markers(&checks);
+        let result = validate_markers(&DiskFs, &checks);
         assert!(result.is_err());
         let msg = result.unwrap_err().to_string();
         assert!(msg.contains("1 missing marker(s)"));
@@ -0,0 +1,483 @@
+//! User-defined generator recipes.
+//!
+//! A recipe lives in `.romance/generators/<name>/` and is described by a
+//! `recipe.toml` listing its CLI arguments, the templates it renders and the
+//! marker injections it performs. It runs as `romance generate <name> ...`.
+//!
+//! ```toml
+//! description = "Service module with route and test"
+//!
+//! [[args]]
+//! name = "name"
+//! help = "Service name"
+//!
+//! [[files]]
+//! template = "service.rs.tera"
+//! output = "backend/src/services/{{ name | snake_case }}.rs"
+//!
+//! [[injections]]
+//! file = "backend/src/main.rs"
+//! marker = "MAIN_MODS"
+//! line = "mod {{ name | snake_case }};"
+//! ```
+//!
+//! Output paths, injected lines and file names are Tera templates rendered with
+//! the recipe arguments, so the shared filters (`snake_case`, `plural`,
+//! `rust_ident`, ...) are available everywhere.
+
+use crate::generator::plan::{self, GenerationTracker};
+use crate::inject;
+use crate::manifest::FileCategory;
+use crate::project::Project;
+use crate::say;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::{Context as _, Result};
+use colored::Colorize;
+use heck::ToSnakeCase;
+use serde::Deserialize;
+use std::path::{Component, PathBuf};
+use tera::Context;
+
+/// Directory (relative to the project root) holding recipes.
+pub const RECIPES_DIR: &str = ".romance/generators";
+
+#[derive(Debug, Deserialize)]
+pub struct Recipe {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(skip)]
+    pub dir: PathBuf,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub args: Vec<RecipeArg>,
+    #[serde(default)]
+    pub files: Vec<RecipeFile>,
+    #[serde(default)]
+    pub injections: Vec<RecipeInjection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeArg {
+    pub name: String,
+    #[serde(default)]
+    pub help: Option<String>,
+    /// Value used when the argument is omitted. Arguments without a default are required.
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeFile {
+    /// Template path relative to the recipe directory.
+    pub template: String,
+    /// Output path relative to the project root (rendered with Tera).
+    pub output: String,
+    /// Replace the file if it already exists instead of skipping it.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeInjection {
+    /// File to inject into, relative to the project root (rendered with Tera).
+    pub file: String,
+    /// Marker name (`MAIN_MODS`) or full marker text (`// === ROMANCE:MAIN_MODS ===`).
+    pub marker: String,
+    /// Line to insert before the marker (rendered with Tera).
+    pub line: String,
+}
+
+impl Recipe {
+    /// Load a recipe from `.romance/generators/<name>/recipe.toml`.
+    pub fn load(project: &Project, name: &str) -> Result<Self> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            anyhow::bail!(
+                "Invalid generator name '{}' (use letters, digits, '-' and '_')",
+                name
+            );
+        }
+        let dir = project.join(RECIPES_DIR).join(name);
+        let path = dir.join("recipe.toml");
+        if !project.fs().exists(&path) {
+            let available = list(project)?;
+            if available.is_empty() {
+                anyhow::bail!(
+                    "Unknown generator '{}'. No recipes found in {}/",
+                    name,
+                    RECIPES_DIR
+                );
+            }
+            anyhow::bail!(
+                "Unknown generator '{}'. Available recipes: {}",
+                name,
+                available.join(", ")
+            );
+        }
+        let content = project.fs().read_to_string(&path)?;
+        let mut recipe: Recipe = toml::from_str(&content)
+            .with_context(|| format!("Invalid recipe {}", path.display()))?;
+        recipe.name = name.to_string();
+        recipe.dir = dir;
+        Ok(recipe)
+    }
+
+    /// One-line usage string, e.g. `romance generate service <name> [kind]`.
+    pub fn usage(&self) -> String {
+        let mut usage = format!("romance generate {}", self.name);
+        for arg in &self.args {
+            if arg.default.is_some() {
+                usage.push_str(&format!(" [{}]", arg.name));
+            } else {
+                usage.push_str(&format!(" <{}>", arg.name));
+            }
+        }
+        usage
+    }
+
+    /// Map command-line arguments onto the recipe's declared arguments.
+    ///
+    /// Values are taken positionally in declaration order; `--name value` and
+    /// `--name=value` set an argument explicitly.
+    pub fn parse_args(&self, raw: &[String]) -> Result<Context> {
+        let mut values: Vec<Option<String>> = vec![None; self.args.len()];
+        let mut positional = Vec::new();
+
+        let mut iter = raw.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(flag) = arg.strip_prefix("--") {
+                let (key, value) = match flag.split_once('=') {
+                    Some((k, v)) => (k.to_string(), v.to_string()),
+                    None => {
+                        let value = iter
+                            .next()
+                            .with_context(|| format!("Missing value for --{}", flag))?;
+                        (flag.to_string(), value.clone())
+                    }
+                };
+                let key = key.replace('-', "_");
+                let index = self
+                    .args
+                    .iter()
+                    .position(|a| a.name == key)
+                    .with_context(|| {
+                        format!("Unknown argument --{} for '{}'\nUsage: {}", key, self.name, self.usage())
+                    })?;
+                values[index] = Some(value);
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        for slot in values.iter_mut().filter(|v| v.is_none()) {
+            match positional.next() {
+                Some(value) => *slot = Some(value),
+                None => break,
+            }
+        }
+        if let Some(extra) = positional.next() {
+            anyhow::bail!(
+                "Unexpected argument '{}' for '{}'\nUsage: {}",
+                extra,
+                self.name,
+                self.usage()
+            );
+        }
+
+        let mut ctx = Context::new();
+        for (arg, value) in self.args.iter().zip(values) {
+            let value = value.or_else(|| arg.default.clone()).with_context(|| {
+                format!(
+                    "Missing required argument <{}> for '{}'\nUsage: {}",
+                    arg.name,
+                    self.name,
+                    self.usage()
+                )
+            })?;
+            ctx.insert(&arg.name, &value);
+        }
+        Ok(ctx)
+    }
+}
+
+/// Names of all recipes defined in the project.
+pub fn list(project: &Project) -> Result<Vec<String>> {
+    let fs = project.fs();
+    let dir = project.join(RECIPES_DIR);
+    let mut names = Vec::new();
+    if fs.is_dir(&dir) {
+        for path in fs.read_dir(&dir)? {
+            if fs.exists(&path.join("recipe.toml")) {
+                if let Some(name) = path.file_name() {
+                    names.push(name.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Expand a marker name like `MODS` to its full `// === ROMANCE:MODS ===` form.
+pub(crate) fn full_marker(marker: &str) -> String {
+    if marker.contains("ROMANCE:") {
+        marker.to_string()
+    } else {
+        format!("// === ROMANCE:{} ===", marker)
+    }
+}
+
+/// Reject output paths that escape the project root.
+pub(crate) fn project_relative(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if path.is_absolute() || path.components().any(|c| matches!(c, Component::ParentDir)) {
+        anyhow::bail!("Recipe path '{}' must stay inside the project", path.display());
+    }
+    Ok(path)
+}
+
+/// Run a recipe with the given command-line arguments.
+pub fn generate(
+    project: &Project,
+    recipe: &Recipe,
+    raw_args: &[String],
+    tracker: &mut GenerationTracker,
+) -> Result<()> {
+    let project_root = project.root();
+    let fs = project.fs();
+    let mut ctx = recipe.parse_args(raw_args)?;
+    let config = project.config();
+    ctx.insert("project_name", &config.project.name);
+    ctx.insert("project_name_snake", &config.project.name.to_snake_case());
+
+    let mut engine = TemplateEngine::for_project(project)?;
+
+    // Render every path and injection first so nothing is written on error
+    let mut outputs = Vec::new();
+    for file in &recipe.files {
+        let source = recipe.dir.join(project_relative(&file.template)?);
+        let content = fs.read_to_string(&source)
+            .with_context(|| format!("Recipe template {} not found", source.display()))?;
+        let template_name = format!("{}/{}", recipe.name, file.template);
+        engine.add_template(&template_name, &content)?;
+        let rendered = engine.render(&template_name, &ctx)?;
+        let output = project_relative(&engine.render_str(&file.output, &ctx)?)?;
+        outputs.push((file, template_name, output, rendered));
+    }
+
+    let mut injections = Vec::new();
+    let mut checks = Vec::new();
+    for injection in &recipe.injections {
+        let target = project_relative(&engine.render_str(&injection.file, &ctx)?)?;
+        let marker = full_marker(&injection.marker);
+        let line = engine.render_str(&injection.line, &ctx)?;
+        // Files created by this recipe may carry the marker themselves
+        if !outputs.iter().any(|(_, _, output, _)| *output == target) {
+            checks.push(plan::check(project_root.join(&target), &marker));
+        }
+        injections.push((target, marker, line));
+    }
+    plan::validate_markers(fs, &checks)?;
+
+    say!(
+        "{}",
+        format!("Running generator '{}'...", recipe.name).bold()
+    );
+
+    let mut manifest = project.manifest()?;
+
+    for (file, template_name, output, content) in &outputs {
+        let path = project_root.join(output);
+        let display = output.display().to_string();
+        if fs.exists(&path) && !file.overwrite {
+            utils::ui::skipped(&display, "already exists");
+            continue;
+        }
+        if !fs.exists(&path) {
+            tracker.track(path.clone());
+        }
+        utils::write_generated(fs, &path, content)?;
+        utils::ui::created(&display);
+        if let Some(manifest) = manifest.as_mut() {
+            manifest.record_file(
+                &display,
+                Some(&format!("{}/{}", RECIPES_DIR, template_name)),
+                FileCategory::Recipe,
+                content,
+                None,
+            );
+        }
+    }
+
+    for (target, marker, line) in &injections {
+        inject::insert(fs, &project_root.join(target), marker, line)?;
+        utils::ui::injected(&target.display().to_string(), line.trim());
+    }
+
+    if let Some(manifest) = manifest.as_mut() {
+        manifest.updated_at = chrono::Utc::now().to_rfc3339();
+        manifest.save(fs, project_root)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::DiskFs;
+    use tempfile::TempDir;
+
+    const RECIPE: &str = r#"
+description = "Service module"
+
+[[args]]
+name = "name"
+
+[[args]]
+name = "kind"
+default = "sync"
+
+[[files]]
+template = "service.rs.tera"
+output = "backend/src/services/{{ name | snake_case }}.rs"
+
+[[injections]]
+file = "backend/src/services/mod.rs"
+marker = "MODS"
+line = "pub mod {{ name | snake_case }};"
+"#;
+
+    fn setup() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let recipe_dir = root.join(RECIPES_DIR).join("service");
+        utils::write_file(&DiskFs, &recipe_dir.join("recipe.toml"), RECIPE).unwrap();
+        utils::write_file(
+            &DiskFs,
+            &recipe_dir.join("service.rs.tera"),
+            "pub struct {{ name | pascal_case }}Service; // {{ kind }} {{ name | plural }}\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("backend/src/services/mod.rs"),
+            "// === ROMANCE:MODS ===\n",
+        )
+        .unwrap();
+        utils::write_file(
+            &DiskFs,
+            &root.join("romance.toml"),
+            "[project]\nname = \"app\"\n\n[backend]\nport = 3000\ndatabase_url = \"postgres://localhost/app\"\n\n[frontend]\nport = 5173\napi_base_url = \"http://localhost:3000\"\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn list_finds_recipes() {
+        let dir = setup();
+        assert_eq!(list(&Project::open(dir.path()).unwrap()).unwrap(), vec!["service"]);
+    }
+
+    #[test]
+    fn load_unknown_recipe_lists_available() {
+        let dir = setup();
+        let err = Recipe::load(&Project::open(dir.path()).unwrap(), "report").unwrap_err().to_string();
+        assert!(err.contains("Available recipes: service"));
+    }
+
+    #[test]
+    fn load_rejects_names_outside_the_recipes_dir() {
+        let dir = setup();
+        let project = Project::open(dir.path()).unwrap();
+        for name in ["../../x", "a/b", ""] {
+            let err = Recipe::load(&project, name).unwrap_err().to_string();
+            assert!(err.contains("Invalid generator name"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn parse_args_positional_named_and_default() {
+        let dir = setup();
+        let recipe = Recipe::load(&Project::open(dir.path()).unwrap(), "service").unwrap();
+
+        let ctx = recipe.parse_args(&args(&["billing"])).unwrap();
+        assert_eq!(ctx.get("name").unwrap(), "billing");
+        assert_eq!(ctx.get("kind").unwrap(), "sync");
+
+        let ctx = recipe.parse_args(&args(&["--kind=async", "billing"])).unwrap();
+        assert_eq!(ctx.get("kind").unwrap(), "async");
+        assert_eq!(ctx.get("name").unwrap(), "billing");
+
+        assert!(recipe.parse_args(&[]).is_err());
+        assert!(recipe.parse_args(&args(&["a", "b", "c"])).is_err());
+        assert!(recipe.parse_args(&args(&["--nope", "x"])).is_err());
+        assert_eq!(recipe.usage(), "romance generate service <name> [kind]");
+    }
+
+    #[test]
+    fn generate_renders_files_and_injects() {
+        let dir = setup();
+        let root = dir.path();
+        let recipe = Recipe::load(&Project::open(root).unwrap(), "service").unwrap();
+        let mut tracker = GenerationTracker::new();
+
+        generate(&Project::open(root).unwrap(), &recipe, &args(&["PaymentGateway"]), &mut tracker).unwrap();
+
+        let content =
+            std::fs::read_to_string(root.join("backend/src/services/payment_gateway.rs")).unwrap();
+        assert_eq!(
+            content,
+            "pub struct PaymentGatewayService; // sync PaymentGateways\n"
+        );
+        let module = std::fs::read_to_string(root.join("backend/src/services/mod.rs")).unwrap();
+        assert!(module.contains("pub mod payment_gateway;\n// === ROMANCE:MODS ==="));
+    }
+
+    #[test]
+    fn generate_fails_before_writing_when_marker_missing() {
+        let dir = setup();
+        let root = dir.path();
+        std::fs::write(root.join("backend/src/services/mod.rs"), "// no marker\n").unwrap();
+        let recipe = Recipe::load(&Project::open(root).unwrap(), "service").unwrap();
+        let mut tracker = GenerationTracker::new();
+
+        assert!(generate(&Project::open(root).unwrap(), &recipe, &args(&["billing"]), &mut tracker).is_err());
+        assert!(!root.join("backend/src/services/billing.rs").exists());
+    }
+
+    #[test]
+    fn output_paths_must_stay_in_project() {
+        assert!(project_relative("../outside.rs").is_err());
+        assert!(project_relative("/etc/passwd").is_err());
+        assert!(project_relative("backend/src/x.rs").is_ok());
+    }
+
+    #[test]
+    fn templates_must_stay_in_project() {
+        let dir = setup();
+        let root = dir.path();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+        let recipe_dir = root.join(RECIPES_DIR).join("service");
+        let recipe = std::fs::read_to_string(recipe_dir.join("recipe.toml")).unwrap();
+        std::fs::write(
+            recipe_dir.join("recipe.toml"),
+            recipe.replace("service.rs.tera", "../../../secret.txt"),
+        )
+        .unwrap();
+        let recipe = Recipe::load(&Project::open(root).unwrap(), "service").unwrap();
+
+        let err = generate(&Project::open(root).unwrap(), &recipe, &args(&["billing"]), &mut GenerationTracker::new())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("must stay inside the project"), "{}", err);
+        assert!(!root.join("backend/src/services/billing.rs").exists());
+    }
+}
@@ -0,0 +1,429 @@
+//! Recover an entity's definition from the files generated for it.
+//!
+//! Nothing records the `name:type[...]` arguments an entity was generated
+//! with, but every part of the definition that affects generated code is
+//! visible in the output: the model gives names, Rust types, optionality,
+//! foreign keys, validations and field visibility; the create migration tells
+//! `text` and file columns apart from plain strings; the handlers show which
+//! fields are searchable; and the form's zod schema repeats the validations.
+//! Re-rendering the recovered definition reproduces the generated sections.
+
+use crate::entity::{
+    EntityDefinition, FieldDefinition, FieldType, FieldVisibility, RelationDefinition,
+    RelationType, ValidationRule,
+};
+use crate::fs::ProjectFs;
+use crate::generator::migration;
+use crate::module;
+use crate::project::Project;
+use crate::relation;
+use anyhow::{Context, Result};
+use heck::{ToPascalCase, ToSnakeCase};
+use quote::ToTokens;
+use regex::Regex;
+use std::path::Path;
+use syn::ext::IdentExt;
+
+/// Columns every generated model has that are not part of the definition.
+pub(crate) const IMPLICIT_COLUMNS: &[&str] = &["id", "tenant_id", "created_at", "updated_at", "deleted_at"];
+
+/// Recover the definition of a generated entity from its model, migrations,
+/// handlers and frontend form.
+pub fn entity(project: &Project, name: &str) -> Result<EntityDefinition> {
+    let fs = project.fs();
+    let pascal = name.to_pascal_case();
+    let snake = name.to_snake_case();
+    let entity_module = module::locate(project, &snake);
+    let model_file = module::backend_file(entity_module.as_ref(), "entities", &snake);
+    let model = fs
+        .read_to_string(&project.join(&model_file))
+        .with_context(|| format!("Entity '{}' not found ({})", pascal, model_file))?;
+    if !model.contains("ROMANCE:CUSTOM") {
+        anyhow::bail!("{} was not generated by `romance generate entity`", model_file);
+    }
+    let file = syn::parse_file(&model).with_context(|| format!("Failed to parse {}", model_file))?;
+
+    let handlers = fs
+        .read_to_string(&project.join(module::backend_file(entity_module.as_ref(), "handlers", &snake)))
+        .unwrap_or_default();
+    let handlers: String = handlers.split_whitespace().collect();
+    let feature_dir = project
+        .frontend_src()
+        .join("features")
+        .join(module::feature_path(entity_module.as_ref(), name));
+    let form = fs.read_to_string(&feature_dir.join(format!("{}Form.tsx", pascal))).ok();
+    // Columns are declared in the create migration, under the entity's name at
+    // the time; later renames only rename the table
+    let mut names = vec![pascal.clone()];
+    names.extend(migration::former_names(project, &snake).iter().map(|n| n.to_pascal_case()));
+    let columns = column_methods(fs, &project.migration_src(), &names);
+    let foreign_keys = foreign_keys(&file);
+    let visibility = visibilities(&model);
+    let create = find_struct(&file, &format!("Create{}", pascal));
+
+    let Some(model_struct) = find_struct(&file, "Model") else {
+        anyhow::bail!("No `Model` struct in {}", model_file);
+    };
+
+    let mut fields = Vec::new();
+    let mut relations = Vec::new();
+    for field in &model_struct.fields {
+        let Some(ident) = &field.ident else { continue };
+        let field_name = ident.unraw().to_string();
+        if IMPLICIT_COLUMNS.contains(&field_name.as_str()) {
+            continue;
+        }
+        let (rust_type, optional) = unwrap_option(&field.ty);
+        let column = field_name.to_pascal_case();
+        let field_type = match rust_type.as_str() {
+            "bool" => FieldType::Bool,
+            "i32" => FieldType::Int32,
+            "i64" => FieldType::Int64,
+            "f64" => FieldType::Float64,
+            "Decimal" => FieldType::Decimal,
+            "Uuid" => FieldType::Uuid,
+            "DateTimeWithTimeZone" => FieldType::DateTime,
+            "Date" => FieldType::Date,
+            "Json" => FieldType::Json,
+            _ => match columns.get(&column).map(String::as_str) {
+                Some("text()") => FieldType::Text,
+                Some("string_len(512)") => FieldType::File,
+                _ => FieldType::String,
+            },
+        };
+
+        let relation = foreign_keys
+            .iter()
+            .find(|(from, _)| *from == column)
+            .map(|(_, target)| target.to_pascal_case());
+        if let Some(target) = &relation {
+            relations.push(RelationDefinition {
+                name: field_name.clone(),
+                relation_type: RelationType::BelongsTo,
+                target_entity: target.clone(),
+                fk_column: Some(field_name.clone()),
+                optional,
+            });
+        }
+
+        let create_field = create.and_then(|s| {
+            s.fields
+                .iter()
+                .find(|f| f.ident.as_ref().map(|i| i.unraw() == field_name).unwrap_or(false))
+        });
+        let validations = match &form {
+            Some(form) => zod_validations(form, &field_name, create_field),
+            None => create_field.map(|f| model_validations(f, &model)).unwrap_or_default(),
+        };
+
+        fields.push(FieldDefinition {
+            searchable: handlers.contains(&format!("Column::{}.contains(query)", column)),
+            visibility: visibility
+                .iter()
+                .find(|(n, _)| *n == field_name)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default(),
+            name: field_name,
+            field_type,
+            optional,
+            default: None,
+            relation,
+            validations,
+        });
+    }
+
+    // Many-to-many relations declared on this entity left relation hooks in its feature
+    let mut others = relation::discover_entities(project)?;
+    for m in module::all(project) {
+        others.extend(module::entities(project, &m)?);
+    }
+    for other in others {
+        if other != snake && fs.exists(&feature_dir.join(format!("{}_hooks.ts", other))) {
+            relations.push(RelationDefinition {
+                name: crate::utils::pluralize(&other),
+                relation_type: RelationType::ManyToMany,
+                target_entity: other.to_pascal_case(),
+                fk_column: None,
+                optional: false,
+            });
+        }
+    }
+
+    // Imported entities keep the table they were imported from
+    let table_name = Regex::new(r#"table_name\s*=\s*"([^"]+)""#)
+        .unwrap()
+        .captures(&model)
+        .map(|c| c[1].to_string())
+        .filter(|table| *table != module::table_name(entity_module.as_ref(), &snake));
+
+    Ok(EntityDefinition {
+        name: pascal,
+        fields,
+        relations,
+        module: entity_module.map(|m| m.name),
+        table_name,
+    })
+}
+
+fn find_struct<'a>(file: &'a syn::File, name: &str) -> Option<&'a syn::ItemStruct> {
+    file.items.iter().find_map(|item| match item {
+        syn::Item::Struct(s) if s.ident == name => Some(s),
+        _ => None,
+    })
+}
+
+/// The type's last path segment, looking through `Option<...>`.
+fn unwrap_option(ty: &syn::Type) -> (String, bool) {
+    let syn::Type::Path(path) = ty else {
+        return (ty.to_token_stream().to_string(), false);
+    };
+    let Some(last) = path.path.segments.last() else {
+        return (String::new(), false);
+    };
+    if last.ident == "Option" {
+        if let syn::PathArguments::AngleBracketed(args) = &last.arguments {
+            if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                return (unwrap_option(inner).0, true);
+            }
+        }
+    }
+    (last.ident.to_string(), false)
+}
+
+/// `(from column, target module)` for each `belongs_to` variant of `Relation`.
+pub(crate) fn foreign_keys(file: &syn::File) -> Vec<(String, String)> {
+    let belongs_to = Regex::new(r#"belongs_to\s*=\s*"(?:\w+::)*(\w+)::Entity""#).unwrap();
+    let from = Regex::new(r#"from\s*=\s*"Column::(\w+)""#).unwrap();
+    let Some(relation) = file.items.iter().find_map(|item| match item {
+        syn::Item::Enum(e) if e.ident == "Relation" => Some(e),
+        _ => None,
+    }) else {
+        return Vec::new();
+    };
+    relation
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let attrs: String = variant.attrs.iter().map(|a| a.to_token_stream().to_string()).collect();
+            let target = belongs_to.captures(&attrs)?[1].to_string();
+            let column = from.captures(&attrs)?[1].to_string();
+            Some((column, target))
+        })
+        .collect()
+}
+
+/// The column builder method (`text()`, `string_len(512)`, ...) for each of
+/// the entity's columns, as last defined by any migration.
+pub(crate) fn column_methods(
+    fs: &dyn ProjectFs,
+    migration_dir: &Path,
+    names: &[String],
+) -> std::collections::HashMap<String, String> {
+    let re = Regex::new(&format!(
+        r"ColumnDef::new\((?:{})::(\w+)\)\s*\.(\w+\([^)]*\))",
+        names.join("|")
+    ))
+    .unwrap();
+    let mut methods = std::collections::HashMap::new();
+    let Ok(files) = fs.read_dir(migration_dir) else {
+        return methods;
+    };
+    for file in files {
+        let content = fs.read_to_string(&file).unwrap_or_default();
+        for caps in re.captures_iter(&content) {
+            methods.insert(caps[1].to_string(), caps[2].to_string());
+        }
+    }
+    methods
+}
+
+/// Field visibility from the `filter_for_role` method of the response DTO.
+fn visibilities(model: &str) -> Vec<(String, FieldVisibility)> {
+    let Some(start) = model.find("fn filter_for_role") else {
+        return Vec::new();
+    };
+    let body = &model[start..];
+    let re = Regex::new(
+        r#"(?s)if !is_authenticated \{\s*obj\.remove\("(\w+)"\)|if role != Some\("admin"\) \{\s*obj\.remove\("(\w+)"\)|let allowed_roles = vec!\[([^\]]*)\];.*?obj\.remove\("(\w+)"\)"#,
+    )
+    .unwrap();
+    re.captures_iter(body)
+        .filter_map(|caps| {
+            if let Some(name) = caps.get(1) {
+                Some((name.as_str().to_string(), FieldVisibility::Authenticated))
+            } else if let Some(name) = caps.get(2) {
+                Some((name.as_str().to_string(), FieldVisibility::AdminOnly))
+            } else {
+                let roles = caps[3]
+                    .split(',')
+                    .map(|r| r.trim().trim_matches('"').to_string())
+                    .filter(|r| !r.is_empty())
+                    .collect();
+                Some((caps.get(4)?.as_str().to_string(), FieldVisibility::Roles(roles)))
+            }
+        })
+        .collect()
+}
+
+/// Validation rules from the field's entry in the form's zod schema, in the
+/// order they were declared.
+fn zod_validations(form: &str, field: &str, create_field: Option<&syn::Field>) -> Vec<ValidationRule> {
+    let prefix = format!("{}: z.", field);
+    let Some(line) = form.lines().map(str::trim).find(|l| l.starts_with(&prefix)) else {
+        return Vec::new();
+    };
+    let re = Regex::new(
+        r"\.min\((\d+), 'Must be at least|\.max\((\d+), 'Must be at most|(\.email\()|(\.url\()|\.regex\(/(.*)/, 'Invalid format'\)",
+    )
+    .unwrap();
+    let mut rules: Vec<ValidationRule> = re
+        .captures_iter(line)
+        .filter_map(|caps| {
+            if let Some(n) = caps.get(1) {
+                n.as_str().parse().ok().map(ValidationRule::Min)
+            } else if let Some(n) = caps.get(2) {
+                n.as_str().parse().ok().map(ValidationRule::Max)
+            } else if caps.get(3).is_some() {
+                Some(ValidationRule::Email)
+            } else if caps.get(4).is_some() {
+                Some(ValidationRule::Url)
+            } else {
+                caps.get(5).map(|p| ValidationRule::Regex(p.as_str().to_string()))
+            }
+        })
+        .collect();
+    // `required` only shows up as a server-side `length(min = 1)` the form can't explain
+    let required = create_field
+        .map(|f| validate_attrs(f).iter().any(|a| a == "length(min = 1)"))
+        .unwrap_or(false);
+    if required && !rules.contains(&ValidationRule::Min(1)) {
+        rules.push(ValidationRule::Required);
+    }
+    rules
+}
+
+/// Validation rules from `#[validate(...)]` attributes, for entities without a form.
+fn model_validations(field: &syn::Field, model: &str) -> Vec<ValidationRule> {
+    let bound = Regex::new(r"^(?:length|range)\((min|max) = (\d+)\)$").unwrap();
+    validate_attrs(field)
+        .iter()
+        .filter_map(|attr| {
+            if let Some(caps) = bound.captures(attr) {
+                let n: u64 = caps[2].parse().ok()?;
+                return Some(if &caps[1] == "min" { ValidationRule::Min(n) } else { ValidationRule::Max(n) });
+            }
+            match attr.as_str() {
+                "email" => Some(ValidationRule::Email),
+                "url" => Some(ValidationRule::Url),
+                _ if attr.starts_with("regex(") => {
+                    let name = field.ident.as_ref()?.unraw().to_string().to_uppercase();
+                    let re = Regex::new(&format!(r#"static RE_{}: Lazy<Regex> = Lazy::new\(\|\| Regex::new\(r"(.*)"\)"#, name)).ok()?;
+                    re.captures(model).map(|c| ValidationRule::Regex(c[1].to_string()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The contents of each `#[validate(...)]` attribute on a field, normalized
+/// to the template's spacing.
+fn validate_attrs(field: &syn::Field) -> Vec<String> {
+    field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("validate"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::List(list) => Some(list.tokens.to_string().replace(" (", "(").replace("* ", "*")),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{format_entity_args, parse_entity};
+    use crate::fs::MemoryFs;
+    use crate::generator::plan::GenerationTracker;
+    use crate::generator::{backend, frontend};
+    use std::sync::Arc;
+
+    fn project() -> Project {
+        crate::scaffold::render_project(Arc::new(MemoryFs::new()), Path::new("/app"), "app").unwrap()
+    }
+
+    fn generate(project: &Project, name: &str, fields: &[&str]) -> EntityDefinition {
+        let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+        let entity = parse_entity(name, &fields).unwrap();
+        let mut tracker = GenerationTracker::new();
+        backend::generate(project, &entity, &mut tracker).unwrap();
+        migration::generate(project, &entity, &mut tracker).unwrap();
+        frontend::generate(project, &entity, &mut tracker).unwrap();
+        entity
+    }
+
+    #[test]
+    fn recovers_the_definition_an_entity_was_generated_with() {
+        let project = project();
+        generate(&project, "Author", &["name:string"]);
+        let post = generate(
+            &project,
+            "Post",
+            &[
+                "title:string[min=3,max=100,searchable]",
+                "body:text",
+                "cover:file?",
+                "views:i32?",
+                "price:decimal",
+                "published:bool",
+                "author_id:uuid->Author",
+                "notes:string[admin_only]",
+            ],
+        );
+        let recovered = entity(&project, "Post").unwrap();
+        assert_eq!(format_entity_args(&recovered), format_entity_args(&post));
+        assert_eq!(recovered.relations.len(), 1);
+        assert_eq!(recovered.relations[0].target_entity, "Author");
+    }
+
+    #[test]
+    fn implicit_columns_are_not_fields() {
+        let project = project();
+        generate(&project, "Post", &["title:string"]);
+        let path = project.join("backend/src/entities/post.rs");
+        let model = project.fs().read_to_string(&path).unwrap();
+        assert!(model.contains("pub created_at:"), "{}", model);
+        // As the multitenancy and soft-delete addons add them
+        let model = model.replacen(
+            "pub title: String,",
+            "pub title: String,\n    pub tenant_id: Uuid,\n    pub deleted_at: Option<DateTimeWithTimeZone>,",
+            1,
+        );
+        project.fs().write(&path, &model).unwrap();
+
+        let recovered = entity(&project, "Post").unwrap();
+        let names: Vec<&str> = recovered.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["title"]);
+    }
+
+    #[test]
+    fn only_generated_models_are_recovered() {
+        let project = project();
+        let err = entity(&project, "Post").unwrap_err();
+        assert!(err.to_string().contains("Entity 'Post' not found"), "{}", err);
+
+        let path = project.join("backend/src/entities/legacy.rs");
+        crate::utils::write_file(project.fs(), &path, "pub struct Model {\n    pub id: i32,\n}\n").unwrap();
+        let err = entity(&project, "Legacy").unwrap_err();
+        assert!(err.to_string().contains("was not generated by `romance generate entity`"), "{}", err);
+    }
+
+    #[test]
+    fn unwraps_optional_types() {
+        let ty: syn::Type = syn::parse_str("Option<DateTimeWithTimeZone>").unwrap();
+        assert_eq!(unwrap_option(&ty), ("DateTimeWithTimeZone".to_string(), true));
+        let ty: syn::Type = syn::parse_str("String").unwrap();
+        assert_eq!(unwrap_option(&ty), ("String".to_string(), false));
+    }
+}
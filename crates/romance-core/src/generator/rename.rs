@@ -0,0 +1,534 @@
+//! Renaming a generated entity across the whole stack.
+//!
+//! Generated code spells an entity's name in a handful of forms — `Post`,
+//! `post`, `posts`, `postId` — alone or inside longer identifiers such as
+//! `PostResponse`, `list_posts`, `usePosts` or the `note_post` junction.
+//! Rather than re-rendering, the rename rewrites those identifiers in every
+//! backend and frontend source file, so custom blocks, relation code and user
+//! edits come along. Files named after the entity are moved, the manifest and
+//! pending relations follow, and a migration renames the tables and foreign
+//! key columns so no data is lost.
+
+use crate::error::Failure;
+use crate::fs::ProjectFs;
+use crate::generator::context;
+use crate::generator::destroy::{collect_files, module_patterns, remaining_references, table_name, table_ref};
+use crate::generator::migration;
+use crate::manifest::{self, Manifest};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::relation;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::Result;
+use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+/// Directories whose files are named after the entities they belong to.
+const NAMED_DIRS: &[&str] = &[
+    "backend/src/entities",
+    "backend/src/handlers",
+    "backend/src/routes",
+    "frontend/src/features",
+];
+
+/// What a rename changed.
+#[derive(Debug, Default)]
+pub struct RenameReport {
+    /// Files moved, as `(from, to)`.
+    pub moved: Vec<(String, String)>,
+    /// Files rewritten in place.
+    pub rewritten: Vec<String>,
+    /// Files created (the rename migration).
+    pub created: Vec<String>,
+    /// References to the old name that were left alone.
+    pub leftovers: Vec<String>,
+}
+
+/// A foreign key column the migration renames.
+#[derive(serde::Serialize)]
+struct RenamedColumn {
+    /// Its table, as a sea-query table reference.
+    table: String,
+    from: String,
+    to: String,
+}
+
+/// A table the migration renames. Its schema, if any, stays the same.
+#[derive(serde::Serialize)]
+struct RenamedTable {
+    from: String,
+    to: String,
+    /// `from` and `to` as sea-query table references.
+    from_ref: String,
+    to_ref: String,
+}
+
+/// Rename an entity: its files, every reference to it, its junction entities,
+/// foreign keys named after it, the manifest and pending relations. A
+/// migration renames the table, its junction tables and the `<entity>_id`
+/// columns pointing at it.
+pub fn entity(project: &Project, from: &str, to: &str) -> Result<RenameReport> {
+    let project_root = project.root();
+    let fs = project.fs();
+    let base = &project.backend_src();
+    let (old, new) = (from.to_snake_case(), to.to_snake_case());
+    let (old_pascal, new_pascal) = (old.to_pascal_case(), new.to_pascal_case());
+
+    let valid = to.starts_with(|c: char| c.is_ascii_alphabetic())
+        && to.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid || utils::RUST_RESERVED_WORDS.contains(&new.as_str()) {
+        anyhow::bail!("'{}' is not a valid entity name", to);
+    }
+    if old == new {
+        anyhow::bail!("{} is already named {}", old_pascal, new_pascal);
+    }
+    let entity_module = module::locate(project, from);
+    let m = entity_module.as_ref();
+    let model_path = project.join(module::backend_file(m, "entities", &old));
+    if !fs.exists(&model_path) {
+        anyhow::bail!("Entity '{}' not found ({})", old_pascal, project.relative(&model_path).display());
+    }
+    if old == "user" && fs.exists(&base.join("auth.rs")) {
+        anyhow::bail!("User belongs to the generated auth and can't be renamed");
+    }
+    let feature_dir = project.frontend_src().join("features").join(module::feature_path(m, &new));
+    if relation::entity_exists(project, &new) || fs.exists(&feature_dir) {
+        return Err(Failure::Precondition.error(format!("Entity '{}' already exists", new_pascal)));
+    }
+
+    let entities = module::all_entities(project)?;
+    let is_old = |em: &Option<EntityModule>, e: &String| *e == old && module::same(em.as_ref(), m);
+    let others: Vec<&String> = entities
+        .iter()
+        .filter(|(em, e)| !is_old(em, e))
+        .map(|(_, e)| e)
+        .collect();
+    // Junctions live at the top level, whatever the modules of their entities
+    let junctions: Vec<(&String, String)> = entities
+        .iter()
+        .filter(|(jm, _)| jm.is_none())
+        .filter_map(|(_, j)| {
+            let partner = others
+                .iter()
+                .find(|o| **o != j && relation::junction_name(&old, o) == *j)?;
+            Some((j, relation::junction_name(&new, partner)))
+        })
+        .collect();
+    if let Some((_, taken)) = junctions.iter().find(|(_, j)| others.contains(&j)) {
+        return Err(Failure::Precondition.error(format!(
+            "Entity '{}' already exists",
+            taken.to_pascal_case()
+        )));
+    }
+
+    // ── Tables and columns, read before anything changes ─────────────
+    let old_fk = format!("{}_id", old);
+    let new_fk = format!("{}_id", new);
+    let mut columns = Vec::new();
+    let mut protected = Vec::new();
+    for (em, e) in &entities {
+        let model = project.join(module::backend_file(em.as_ref(), "entities", e));
+        let table = table_name(fs, &model, &module::table_name(em.as_ref(), e));
+        for column in model_columns(fs, &model) {
+            if !is_old(em, e) && column == old_fk {
+                columns.push(RenamedColumn {
+                    table: table_ref(em.as_ref(), &table),
+                    from: old_fk.clone(),
+                    to: new_fk.clone(),
+                });
+            } else if column.contains(&old) {
+                protected.push(column);
+            }
+        }
+    }
+    let mut tables = Vec::new();
+    let renamed_table = |m: Option<&EntityModule>, from: String, to: String| RenamedTable {
+        from_ref: table_ref(m, &from),
+        to_ref: table_ref(m, &to),
+        from,
+        to,
+    };
+    let old_table = table_name(fs, &model_path, &module::table_name(m, &old));
+    if old_table == module::table_name(m, &old) {
+        tables.push(renamed_table(m, old_table, module::table_name(m, &new)));
+    }
+    for (junction, renamed) in &junctions {
+        let junction_model = base.join(format!("entities/{}.rs", junction));
+        if table_name(fs, &junction_model, junction) == **junction {
+            tables.push(renamed_table(None, junction.to_string(), renamed.clone()));
+        }
+    }
+
+    let mut renamer = Renamer::new(&old, &new);
+    for (junction, renamed) in &junctions {
+        renamer.add_name(junction, renamed);
+    }
+    for other in others
+        .iter()
+        .filter(|o| o.contains(&old) && !junctions.iter().any(|(j, _)| j == *o))
+    {
+        renamer.add_name(other, other);
+    }
+    for column in &protected {
+        renamer.add_name(column, column);
+    }
+    renamer.finish();
+
+    let mut report = RenameReport::default();
+
+    // ── Sources ──────────────────────────────────────────────────────
+    let mut files = Vec::new();
+    collect_files(fs, base, "rs", &mut files);
+    collect_files(fs, &project.frontend_src(), "ts", &mut files);
+    collect_files(fs, &project.frontend_src(), "tsx", &mut files);
+    let show = |path: &Path| project.relative(path).display().to_string();
+    // Work out every change before writing, so a clash leaves nothing half-renamed
+    let mut changes = Vec::new();
+    for file in files {
+        let content = fs.read_to_string(&file)?;
+        let rewritten = renamer.rewrite(&content);
+        let target = project.join(renamer.rename_path(project.relative(&file)));
+        if target != file && fs.exists(&target) {
+            anyhow::bail!("Can't move {} to {}: it already exists", show(&file), show(&target));
+        }
+        if target != file || rewritten != content {
+            changes.push((file, target, rewritten));
+        }
+    }
+    for (file, target, content) in changes {
+        if target == file {
+            fs.write(&file, &content)?;
+            report.rewritten.push(show(&file));
+            continue;
+        }
+        utils::write_file(fs, &target, &content)?;
+        fs.remove_file(&file)?;
+        remove_empty_parents(fs, project_root, &file);
+        report.moved.push((show(&file), show(&target)));
+    }
+
+    // ── Migration ────────────────────────────────────────────────────
+    if !tables.is_empty() || !columns.is_empty() {
+        let engine = TemplateEngine::for_project(project)?;
+        let mut ctx = Context::new();
+        ctx.insert("tables", &tables);
+        ctx.insert("columns", &columns);
+        let content = engine.render("entity/backend/rename_migration.rs.tera", &ctx)?;
+        let module = format!("m{}_rename_{}_to_{}", migration::next_timestamp(project), old, new);
+        let path = project.migration_src().join(format!("{}.rs", module));
+        utils::write_file(fs, &path, &content)?;
+        context::register_migration(fs, project_root, &module)?;
+        report.created.push(show(&path));
+    }
+
+    // ── Bookkeeping ──────────────────────────────────────────────────
+    if relation::rename_pending(project, &old_pascal, &new_pascal)? {
+        report.rewritten.push(".romance/pending_relations.json".to_string());
+    }
+    rename_in_manifest(project, &renamer, &old_pascal, &new_pascal)?;
+
+    let old_path = module::rust_path(m, &old);
+    let mut paths: Vec<&str> = vec![&old_path];
+    paths.extend(junctions.iter().map(|(j, _)| j.as_str()));
+    let mut patterns = module_patterns(&paths);
+    if m.is_some() {
+        patterns.push(format!("super::{}::", old));
+    }
+    report.leftovers = remaining_references(
+        project,
+        &patterns,
+        &[format!("/features/{}/", module::feature_path(m, &old))],
+        &[],
+    )?;
+    Ok(report)
+}
+
+/// Move manifest records and their bases along with the files, rewriting the
+/// bases the same way so later updates merge against the renamed code.
+fn rename_in_manifest(project: &Project, renamer: &Renamer, old_pascal: &str, new_pascal: &str) -> Result<()> {
+    let fs = project.fs();
+    let project_root = project.root();
+    if !Manifest::exists(fs, project_root) {
+        return Ok(());
+    }
+    let mut manifest = Manifest::load(fs, project_root)?;
+    let mut changed = false;
+    for (path, mut record) in std::mem::take(&mut manifest.files) {
+        let target = renamer.rename_path(Path::new(&path)).display().to_string();
+        if record.entity_name.as_deref() == Some(old_pascal) {
+            record.entity_name = Some(new_pascal.to_string());
+            changed = true;
+        }
+        if let Some(base) = manifest::load_base(fs, project_root, &path, &record.generated_hash) {
+            let rewritten = renamer.rewrite(&base);
+            if target != path || rewritten != base {
+                if target != path {
+                    let _ = fs.remove_file(&project_root.join(manifest::BASE_DIR).join(&path));
+                }
+                manifest::save_base(fs, project_root, &target, &rewritten)?;
+                record.generated_hash = manifest::content_hash(&rewritten);
+                changed = true;
+            }
+        }
+        changed |= target != path;
+        manifest.files.insert(target, record);
+    }
+    if changed {
+        manifest.save(fs, project_root)?;
+    }
+    Ok(())
+}
+
+/// Column names of an entity's `Model`.
+fn model_columns(fs: &dyn ProjectFs, model: &Path) -> Vec<String> {
+    let source = fs.read_to_string(model).unwrap_or_default();
+    let Ok(file) = syn::parse_file(&source) else {
+        return Vec::new();
+    };
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Struct(s) if s.ident == "Model" => Some(s),
+            _ => None,
+        })
+        .map(|model| {
+            model
+                .fields
+                .iter()
+                .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn remove_empty_parents(fs: &dyn ProjectFs, root: &Path, file: &Path) {
+    let mut dir = file.parent();
+    while let Some(d) = dir.filter(|d| *d != root) {
+        if fs.remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+/// Rewrites identifiers that spell one name into another.
+struct Renamer {
+    /// `(from, to)` pairs, longest `from` first. Pairs mapping a name to
+    /// itself protect names that contain the renamed one, like a `post_tag`
+    /// entity or a `post_count` column.
+    pairs: Vec<(String, String)>,
+    /// The old name, when a single lowercase word (`post`). Alone it is also
+    /// a common word, an axum router and a utoipa method, so it is only
+    /// renamed where it is used as a module or a path segment.
+    bare: Option<(String, String)>,
+}
+
+impl Renamer {
+    fn new(old: &str, new: &str) -> Self {
+        let mut renamer = Self {
+            pairs: Vec::new(),
+            bare: None,
+        };
+        renamer.add_name(old, new);
+        if !old.contains('_') {
+            renamer.bare = Some((old.to_string(), new.to_string()));
+        }
+        renamer
+    }
+
+    /// Map the snake-case name `from` to `to` in each form generated code
+    /// uses: snake, Pascal and camel case, with proper and naive plurals.
+    fn add_name(&mut self, from: &str, to: &str) {
+        let forms = |name: &str| {
+            let (snake, pascal, camel) = (name.to_snake_case(), name.to_pascal_case(), name.to_lower_camel_case());
+            [
+                utils::pluralize(&snake),
+                format!("{}s", snake),
+                utils::pluralize(&pascal),
+                format!("{}s", pascal),
+                utils::pluralize(&camel),
+                format!("{}s", camel),
+                snake,
+                pascal,
+                camel,
+            ]
+        };
+        for (from, to) in forms(from).into_iter().zip(forms(to)) {
+            if !self.pairs.iter().any(|(f, _)| *f == from) {
+                self.pairs.push((from, to));
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.pairs.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    }
+
+    /// Rewrite every identifier in `text`.
+    fn rewrite(&self, text: &str) -> String {
+        let bytes = text.as_bytes();
+        let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if !is_ident(bytes[i]) {
+                let next = text[i..].find(|c: char| c.is_ascii_alphanumeric() || c == '_');
+                let end = next.map_or(text.len(), |n| i + n);
+                out.push_str(&text[i..end]);
+                i = end;
+                continue;
+            }
+            let end = bytes[i..].iter().position(|b| !is_ident(*b)).map_or(bytes.len(), |n| i + n);
+            let ident = &text[i..end];
+            // Hyphenated words are prose ("Post-mutation events"), not code
+            let hyphenated = text[end..].starts_with('-')
+                && text[end + 1..].starts_with(|c: char| c.is_ascii_alphabetic());
+            match &self.bare {
+                _ if hyphenated => out.push_str(ident),
+                Some((old, new)) if ident == old => {
+                    let used_as_path = used_as_path(&text[..i], &text[end..]);
+                    out.push_str(if used_as_path { new } else { old });
+                }
+                _ => out.push_str(&self.rename_ident(ident)),
+            }
+            i = end;
+        }
+        out
+    }
+
+    /// Rename the parts of `ident` that spell the old name.
+    fn rename_ident(&self, ident: &str) -> String {
+        let bytes = ident.as_bytes();
+        let mut out = String::with_capacity(ident.len());
+        let mut i = 0;
+        'outer: while i < ident.len() {
+            for (from, to) in &self.pairs {
+                if !ident[i..].starts_with(from.as_str()) {
+                    continue;
+                }
+                let end = i + from.len();
+                let upper = from.starts_with(|c: char| c.is_ascii_uppercase());
+                let starts = i == 0
+                    || bytes[i - 1] == b'_'
+                    || (upper && (bytes[i - 1].is_ascii_lowercase() || bytes[i - 1].is_ascii_digit()));
+                let ends = end == bytes.len() || bytes[end] == b'_' || bytes[end].is_ascii_uppercase();
+                if starts && ends {
+                    out.push_str(to);
+                    i = end;
+                    continue 'outer;
+                }
+            }
+            let c = ident[i..].chars().next().unwrap_or_default();
+            out.push(c);
+            i += c.len_utf8().max(1);
+        }
+        out
+    }
+
+    /// The path a file moves to: files under [`NAMED_DIRS`] are named after
+    /// their entity.
+    fn rename_path(&self, path: &Path) -> PathBuf {
+        let Some(dir) = NAMED_DIRS.iter().find(|d| path.starts_with(d)) else {
+            return path.to_path_buf();
+        };
+        let mut renamed = PathBuf::from(dir);
+        for component in path.strip_prefix(dir).unwrap_or(path).iter() {
+            let component = component.to_string_lossy();
+            let (stem, ext) = match component.split_once('.') {
+                Some((stem, ext)) => (stem, format!(".{}", ext)),
+                None => (component.as_ref(), String::new()),
+            };
+            let stem = match &self.bare {
+                Some((old, new)) if stem == old => new.clone(),
+                _ => self.rename_ident(stem),
+            };
+            renamed.push(format!("{}{}", stem, ext));
+        }
+        renamed
+    }
+}
+
+/// Whether a lone lowercase name between `before` and `after` is a module
+/// (`post::`, `mod post;`, `entities::post;`) or a path or key segment
+/// (`/features/post/`, `'post'`), rather than a variable, `post(...)` or
+/// `.post(...)`.
+fn used_as_path(before: &str, after: &str) -> bool {
+    let quoted = |q: char| before.ends_with(q) && after.starts_with(q);
+    after.starts_with("::")
+        || (before.ends_with("::") && !after.starts_with('('))
+        || before.ends_with('/')
+        || after.starts_with('/')
+        || quoted('"')
+        || quoted('\'')
+        || before
+            .strip_suffix(' ')
+            .and_then(|b| b.strip_suffix("mod"))
+            .is_some_and(|b| !b.ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renamer() -> Renamer {
+        let mut renamer = Renamer::new("post", "article");
+        renamer.add_name("note_post", "article_note");
+        renamer.add_name("post_tag", "post_tag");
+        renamer.add_name("post_count", "post_count");
+        renamer.finish();
+        renamer
+    }
+
+    #[test]
+    fn renames_every_form_of_the_name() {
+        let renamer = renamer();
+        assert_eq!(
+            renamer.rewrite("pub struct PostResponse; fn list_posts() {} const postId = usePosts();"),
+            "pub struct ArticleResponse; fn list_articles() {} const articleId = useArticles();"
+        );
+        assert_eq!(
+            renamer.rewrite("super::note_post::Relation::Post.def(); NotePost"),
+            "super::article_note::Relation::Article.def(); ArticleNote"
+        );
+        assert_eq!(renamer.rewrite("seed_posts(); #[sea_orm(table_name = \"posts\")]"), "seed_articles(); #[sea_orm(table_name = \"articles\")]");
+    }
+
+    #[test]
+    fn leaves_protected_and_unrelated_names_alone() {
+        let renamer = renamer();
+        let text = "post_tag::Entity; PostTag; post_count; Postgres; POST; poster; repost; // Post-mutation";
+        assert_eq!(renamer.rewrite(text), text);
+    }
+
+    #[test]
+    fn renames_a_lone_name_only_as_a_path() {
+        let renamer = renamer();
+        assert_eq!(
+            renamer.rewrite("use axum::routing::{get, post};\n.route(\"/\", post(post::create))\npub mod post;"),
+            "use axum::routing::{get, post};\n.route(\"/\", post(article::create))\npub mod article;"
+        );
+        assert_eq!(
+            renamer.rewrite("import PostList from '@/features/post/PostList'\nconst post = await api.post('/x')"),
+            "import ArticleList from '@/features/article/ArticleList'\nconst post = await api.post('/x')"
+        );
+    }
+
+    #[test]
+    fn moves_files_named_after_the_entity() {
+        let renamer = renamer();
+        let path = |p: &str| renamer.rename_path(Path::new(p));
+        assert_eq!(path("backend/src/handlers/post.rs"), Path::new("backend/src/handlers/article.rs"));
+        assert_eq!(path("backend/src/entities/note_post.rs"), Path::new("backend/src/entities/article_note.rs"));
+        assert_eq!(
+            path("frontend/src/features/post/PostList.tsx"),
+            Path::new("frontend/src/features/article/ArticleList.tsx")
+        );
+        assert_eq!(
+            path("frontend/src/features/note/post_hooks.ts"),
+            Path::new("frontend/src/features/note/article_hooks.ts")
+        );
+        assert_eq!(path("backend/src/post.rs"), Path::new("backend/src/post.rs"));
+    }
+}
@@ -1,15 +1,19 @@
+use crate::error::Failure;
+use crate::project::Project;
+use crate::say;
 use anyhow::Result;
 use std::process::Command;
 
-pub fn generate() -> Result<()> {
-    println!("Generating TypeScript types from Rust structs...");
+pub fn generate(project: &Project) -> Result<()> {
+    say!("Generating TypeScript types from Rust structs...");
     let status = Command::new("cargo")
         .args(["test", "--", "export_bindings"])
-        .current_dir("backend")
+        .current_dir(project.join("backend"))
+        .stdout(crate::utils::ui::child_stdout())
         .status()?;
     if !status.success() {
-        anyhow::bail!("TypeScript type generation failed");
+        return Err(Failure::Command.error("TypeScript type generation failed"));
     }
-    println!("TypeScript types exported to frontend/src/types/");
+    say!("TypeScript types exported to frontend/src/types/");
     Ok(())
 }
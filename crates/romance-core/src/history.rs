@@ -0,0 +1,581 @@
+//! Generation journal backing `romance history` and `romance undo`.
+//!
+//! While a mutating command runs, the files it writes or removes through
+//! [`DiskFs`](crate::fs::DiskFs) are logged with their previous content;
+//! afterwards the changes are recorded as an entry in `.romance/history/`. An
+//! entry can later be reverted on its own, as long as the files it touched
+//! have not been edited in a conflicting way since.
+
+use crate::fs::Before;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the project root) holding journal entries.
+pub const HISTORY_DIR: &str = ".romance/history";
+
+/// Directories never journaled.
+const SKIP_DIRS: &[&str] = &[".git", "target", "node_modules", "dist"];
+
+/// Files regenerated after every command; journaling them would only produce conflicts.
+const SKIP_FILES: &[&str] = &["CLAUDE.md"];
+
+/// Files larger than this are not journaled.
+const MAX_FILE_SIZE: usize = 1024 * 1024;
+
+/// The line keeping the journal, which holds file contents, out of git.
+const GITIGNORE_LINE: &str = "/.romance/history/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u32,
+    pub command: String,
+    pub timestamp: String,
+    pub romance_version: String,
+    /// False when the command returned an error after touching files.
+    pub succeeded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undone_at: Option<String>,
+    pub changes: Vec<FileChange>,
+    /// Migration modules added by this command.
+    #[serde(default)]
+    pub migrations: Vec<String>,
+}
+
+impl HistoryEntry {
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+}
+
+/// Result of reverting a journal entry.
+#[derive(Debug, Default)]
+pub struct UndoReport {
+    pub removed: Vec<String>,
+    pub restored: Vec<String>,
+}
+
+/// Start journaling a command: from here on, the files written or removed
+/// on this thread are logged until [`record`].
+pub fn begin() {
+    crate::fs::start_write_log();
+}
+
+/// Record the files changed since [`begin`] as a journal entry.
+///
+/// Returns `None` when the command did not change any file.
+pub fn record(project_root: &Path, command: &str, succeeded: bool) -> Result<Option<HistoryEntry>> {
+    let changes = changes(project_root, crate::fs::take_write_log())?;
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let migrations = changes
+        .iter()
+        .filter(|c| c.kind == ChangeKind::Created)
+        .filter_map(|c| migration_module(&c.path))
+        .collect();
+
+    let entry = HistoryEntry {
+        id: next_id(project_root)?,
+        command: command.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        romance_version: env!("CARGO_PKG_VERSION").to_string(),
+        succeeded,
+        undone_at: None,
+        changes,
+        migrations,
+    };
+    ignore_history(project_root)?;
+    save(project_root, &entry)?;
+    Ok(Some(entry))
+}
+
+/// The journaled changes among the logged files, with paths relative to the
+/// project root.
+fn changes(project_root: &Path, log: BTreeMap<PathBuf, Before>) -> Result<Vec<FileChange>> {
+    let root = std::path::absolute(project_root)?;
+    let mut changes = Vec::new();
+    for (path, before) in log {
+        let path = std::path::absolute(&path)?;
+        let Ok(rel) = path.strip_prefix(&root) else { continue };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if is_skipped(&rel) {
+            continue;
+        }
+        let before = match before {
+            Before::Missing => None,
+            Before::Text(content) => Some(content),
+            Before::Binary => continue,
+        };
+        let after = match fs::read(&path) {
+            Err(_) => None,
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(content) => Some(content),
+                // Binary files cannot be diffed or restored from JSON
+                Err(_) => continue,
+            },
+        };
+        if [&before, &after].iter().any(|c| c.as_ref().is_some_and(|c| c.len() > MAX_FILE_SIZE)) {
+            continue;
+        }
+        let kind = match (&before, &after) {
+            (None, None) => continue,
+            (None, Some(_)) => ChangeKind::Created,
+            (Some(_), None) => ChangeKind::Deleted,
+            (Some(old), Some(new)) if old == new => continue,
+            (Some(_), Some(_)) => ChangeKind::Modified,
+        };
+        changes.push(FileChange {
+            path: rel,
+            kind,
+            before,
+            after,
+        });
+    }
+    Ok(changes)
+}
+
+/// Whether `rel` is left out of the journal: build output, the journal
+/// itself, regenerated files and `.env` files, whose secrets would end up
+/// in it.
+fn is_skipped(rel: &str) -> bool {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    rel.starts_with(HISTORY_DIR)
+        || rel.split('/').any(|dir| SKIP_DIRS.contains(&dir))
+        || SKIP_FILES.contains(&name)
+        || name.starts_with(".env")
+}
+
+/// Add the journal to `.gitignore`: projects created before the journal
+/// existed don't ignore it.
+fn ignore_history(project_root: &Path) -> Result<()> {
+    let path = project_root.join(".gitignore");
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let ignored = content.lines().map(str::trim).any(|line| {
+        [GITIGNORE_LINE, ".romance/history/", "/.romance/", ".romance/", "/.romance", ".romance"].contains(&line)
+    });
+    if ignored {
+        return Ok(());
+    }
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    let updated = format!("{}{}{}\n", content, separator, GITIGNORE_LINE);
+    fs::write(&path, &updated)?;
+    crate::manifest::refresh(&crate::fs::DiskFs, project_root, ".gitignore", &content, &updated)
+}
+
+/// Extract the module name from a migration file path, e.g.
+/// `backend/migration/src/m20250101_000000_create_post_table.rs`.
+fn migration_module(path: &str) -> Option<String> {
+    let rest = path.strip_prefix("backend/migration/src/")?;
+    let module = rest.strip_suffix(".rs")?;
+    if module.starts_with('m') && module[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        Some(module.to_string())
+    } else {
+        None
+    }
+}
+
+fn entry_path(project_root: &Path, id: u32) -> std::path::PathBuf {
+    project_root.join(HISTORY_DIR).join(format!("{:04}.json", id))
+}
+
+fn next_id(project_root: &Path) -> Result<u32> {
+    Ok(list(project_root)?.iter().map(|e| e.id).max().unwrap_or(0) + 1)
+}
+
+fn save(project_root: &Path, entry: &HistoryEntry) -> Result<()> {
+    let dir = project_root.join(HISTORY_DIR);
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        entry_path(project_root, entry.id),
+        serde_json::to_string_pretty(entry)?,
+    )?;
+    Ok(())
+}
+
+/// Load all journal entries, oldest first.
+pub fn list(project_root: &Path) -> Result<Vec<HistoryEntry>> {
+    let dir = project_root.join(HISTORY_DIR);
+    let mut entries = Vec::new();
+    if !dir.exists() {
+        return Ok(entries);
+    }
+    for file in fs::read_dir(&dir)? {
+        let path = file?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            let content = fs::read_to_string(&path)?;
+            let entry: HistoryEntry = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid history entry {}", path.display()))?;
+            entries.push(entry);
+        }
+    }
+    entries.sort_by_key(|e| e.id);
+    Ok(entries)
+}
+
+/// Load a single journal entry by id.
+pub fn load(project_root: &Path, id: u32) -> Result<HistoryEntry> {
+    let path = entry_path(project_root, id);
+    if !path.exists() {
+        anyhow::bail!("No history entry #{}", id);
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Revert a single journal entry. Without an id, the most recent entry that has
+/// not been undone yet is reverted.
+///
+/// All files are checked before anything is written: if any file was edited
+/// after the operation in a way that cannot be reverted cleanly, nothing is
+/// changed and the conflicting paths are reported in the error.
+pub fn undo(project_root: &Path, id: Option<u32>) -> Result<(HistoryEntry, UndoReport)> {
+    let mut entry = match id {
+        Some(id) => load(project_root, id)?,
+        None => list(project_root)?
+            .into_iter()
+            .rev()
+            .find(|e| e.undone_at.is_none())
+            .ok_or_else(|| anyhow::anyhow!("Nothing to undo"))?,
+    };
+    if let Some(at) = &entry.undone_at {
+        anyhow::bail!("History entry #{} was already undone at {}", entry.id, at);
+    }
+
+    let mut actions = Vec::new();
+    let mut conflicts = Vec::new();
+    for change in &entry.changes {
+        let path = project_root.join(&change.path);
+        let current = fs::read_to_string(&path).ok();
+        match plan_revert(change, current.as_deref()) {
+            Some(action) => actions.push((change.path.clone(), action)),
+            None => conflicts.push(change.path.clone()),
+        }
+    }
+    if !conflicts.is_empty() {
+        anyhow::bail!(
+            "Cannot undo #{} ({}): these files were changed afterwards:\n  {}",
+            entry.id,
+            entry.command,
+            conflicts.join("\n  ")
+        );
+    }
+
+    let mut report = UndoReport::default();
+    for (rel, action) in actions {
+        let path = project_root.join(&rel);
+        match action {
+            Revert::Remove => {
+                if path.exists() {
+                    fs::remove_file(&path)?;
+                    remove_empty_parents(project_root, &path);
+                    report.removed.push(rel);
+                }
+            }
+            Revert::Write(content) => {
+                crate::utils::write_file(&crate::fs::DiskFs, &path, &content)?;
+                report.restored.push(rel);
+            }
+            Revert::Nothing => {}
+        }
+    }
+
+    entry.undone_at = Some(chrono::Utc::now().to_rfc3339());
+    save(project_root, &entry)?;
+    Ok((entry, report))
+}
+
+enum Revert {
+    Remove,
+    Write(String),
+    Nothing,
+}
+
+/// Decide how to revert one change given the file's current content.
+/// Returns `None` on conflict.
+fn plan_revert(change: &FileChange, current: Option<&str>) -> Option<Revert> {
+    match change.kind {
+        ChangeKind::Created => match current {
+            None => Some(Revert::Nothing),
+            Some(c) if Some(c) == change.after.as_deref() => Some(Revert::Remove),
+            Some(_) => None,
+        },
+        ChangeKind::Deleted => match current {
+            None => Some(Revert::Write(change.before.clone().unwrap_or_default())),
+            Some(c) if Some(c) == change.before.as_deref() => Some(Revert::Nothing),
+            Some(_) => None,
+        },
+        ChangeKind::Modified => {
+            let before = change.before.as_deref().unwrap_or_default();
+            let after = change.after.as_deref().unwrap_or_default();
+            let current = current?;
+            if current == after {
+                Some(Revert::Write(before.to_string()))
+            } else if current == before {
+                Some(Revert::Nothing)
+            } else {
+                remove_insertions(before, after, current).map(Revert::Write)
+            }
+        }
+    }
+}
+
+/// Revert an insert-only change against a file that has been edited since.
+///
+/// Marker injections only add lines, so a later command touching the same
+/// file does not prevent removing them again: each inserted block is removed
+/// from `current` as long as it is still present exactly once.
+fn remove_insertions(before: &str, after: &str, current: &str) -> Option<String> {
+    let diff = TextDiff::from_lines(before, after);
+    let mut blocks: Vec<String> = Vec::new();
+    let mut block = String::new();
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Delete => return None,
+            ChangeTag::Insert => block.push_str(change.value()),
+            ChangeTag::Equal => {
+                if !block.is_empty() {
+                    blocks.push(std::mem::take(&mut block));
+                }
+            }
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    let mut result = current.to_string();
+    for block in blocks {
+        if result.matches(block.as_str()).count() != 1 {
+            return None;
+        }
+        result = result.replacen(block.as_str(), "", 1);
+    }
+    Some(result)
+}
+
+/// Remove directories left empty by an undo, up to the project root.
+fn remove_empty_parents(project_root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        if d == project_root || fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{DiskFs, ProjectFs};
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        crate::utils::write_file(&DiskFs, &root.join(rel), content).unwrap();
+    }
+
+    fn remove(root: &Path, rel: &str) {
+        DiskFs.remove_file(&root.join(rel)).unwrap();
+    }
+
+    fn read(root: &Path, rel: &str) -> String {
+        fs::read_to_string(root.join(rel)).unwrap()
+    }
+
+    #[test]
+    fn record_captures_created_modified_and_deleted() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "a.rs", "a\n");
+        write(root, "b.rs", "b\n");
+        begin();
+
+        write(root, "a.rs", "a\nchanged\n");
+        remove(root, "b.rs");
+        write(root, "backend/migration/src/m20250101_000000_create_post_table.rs", "m");
+
+        let entry = record(root, "generate entity Post", true).unwrap().unwrap();
+        assert_eq!(entry.id, 1);
+        assert_eq!(entry.count(ChangeKind::Created), 1);
+        assert_eq!(entry.count(ChangeKind::Modified), 1);
+        assert_eq!(entry.count(ChangeKind::Deleted), 1);
+        assert_eq!(entry.migrations, vec!["m20250101_000000_create_post_table"]);
+        assert!(root.join(".romance/history/0001.json").exists());
+    }
+
+    #[test]
+    fn record_only_reads_the_files_written() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "untouched.rs", "u\n");
+        begin();
+        write(root, "a.rs", "a\n");
+        // Written with std::fs, not through the project filesystem
+        fs::write(root.join("b.rs"), "b\n").unwrap();
+        let entry = record(root, "generate entity A", true).unwrap().unwrap();
+        let paths: Vec<&str> = entry.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.rs"]);
+    }
+
+    #[test]
+    fn record_ignores_the_journal_in_git() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(root.join(".gitignore"), "/target/").unwrap();
+        for name in ["a.rs", "b.rs"] {
+            begin();
+            write(root, name, "x\n");
+            record(root, "generate", true).unwrap();
+        }
+        assert_eq!(read(root, ".gitignore"), "/target/\n/.romance/history/\n");
+    }
+
+    #[test]
+    fn ignoring_the_journal_keeps_a_tracked_gitignore_unmodified() {
+        use crate::manifest::{self, FileCategory, Manifest};
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(root.join(".gitignore"), "/target/\n").unwrap();
+        let mut tracked = Manifest::new("demo", "0.1.0");
+        tracked.record_file(".gitignore", None, FileCategory::Scaffold, "/target/\n", None);
+        tracked.save(&DiskFs, root).unwrap();
+        manifest::save_base(&DiskFs, root, ".gitignore", "/target/\n").unwrap();
+
+        begin();
+        write(root, "a.rs", "x\n");
+        record(root, "generate", true).unwrap();
+
+        let tracked = Manifest::load(&DiskFs, root).unwrap();
+        let record = &tracked.files[".gitignore"];
+        let base = manifest::load_base(&DiskFs, root, ".gitignore", &record.generated_hash);
+        assert!(!manifest::is_modified(&read(root, ".gitignore"), record, base.as_deref()));
+    }
+
+    #[test]
+    fn record_without_changes_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "a.rs", "a\n");
+        begin();
+        // Rewritten with the same content
+        write(dir.path(), "a.rs", "a\n");
+        assert!(record(dir.path(), "noop", true).unwrap().is_none());
+        assert!(list(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn record_skips_env_files_history_and_build_dirs() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        begin();
+        write(root, ".romance/history/notes.txt", "n");
+        write(root, "frontend/node_modules/x.js", "x");
+        write(root, "CLAUDE.md", "ctx");
+        write(root, "backend/.env", "JWT_SECRET=secret");
+        write(root, "backend/.env.production", "JWT_SECRET=secret");
+        write(root, "backend/src/main.rs", "fn main() {}");
+        let entry = record(root, "add auth", true).unwrap().unwrap();
+        let paths: Vec<&str> = entry.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["backend/src/main.rs"]);
+    }
+
+    #[test]
+    fn undo_reverts_latest_entry() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "mod.rs", "pub mod a;\n// === ROMANCE:MODS ===\n");
+        begin();
+        write(root, "mod.rs", "pub mod a;\npub mod b;\n// === ROMANCE:MODS ===\n");
+        write(root, "b.rs", "b\n");
+        record(root, "generate entity B", true).unwrap();
+
+        let (entry, report) = undo(root, None).unwrap();
+        assert_eq!(entry.id, 1);
+        assert_eq!(report.removed, vec!["b.rs"]);
+        assert_eq!(report.restored, vec!["mod.rs"]);
+        assert!(!root.join("b.rs").exists());
+        assert_eq!(read(root, "mod.rs"), "pub mod a;\n// === ROMANCE:MODS ===\n");
+
+        // Already undone
+        assert!(undo(root, Some(1)).is_err());
+        assert!(undo(root, None).is_err());
+    }
+
+    #[test]
+    fn undo_older_entry_removes_only_its_insertions() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "mod.rs", "// === ROMANCE:MODS ===\n");
+
+        begin();
+        write(root, "mod.rs", "pub mod a;\n// === ROMANCE:MODS ===\n");
+        record(root, "generate entity A", true).unwrap();
+
+        begin();
+        write(root, "mod.rs", "pub mod a;\npub mod b;\n// === ROMANCE:MODS ===\n");
+        record(root, "generate entity B", true).unwrap();
+
+        undo(root, Some(1)).unwrap();
+        assert_eq!(read(root, "mod.rs"), "pub mod b;\n// === ROMANCE:MODS ===\n");
+    }
+
+    #[test]
+    fn undo_refuses_on_conflicting_edit() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        begin();
+        write(root, "post.rs", "generated\n");
+        record(root, "generate entity Post", true).unwrap();
+
+        write(root, "post.rs", "generated\nuser edit\n");
+        let err = undo(root, None).unwrap_err().to_string();
+        assert!(err.contains("post.rs"));
+        // Nothing was touched and the entry is still undoable
+        assert!(root.join("post.rs").exists());
+        assert!(load(root, 1).unwrap().undone_at.is_none());
+    }
+
+    #[test]
+    fn undo_restores_deleted_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "backend/src/cache.rs", "cache\n");
+        begin();
+        remove(root, "backend/src/cache.rs");
+        record(root, "remove cache", true).unwrap();
+
+        undo(root, None).unwrap();
+        assert_eq!(read(root, "backend/src/cache.rs"), "cache\n");
+    }
+
+    #[test]
+    fn remove_insertions_rejects_deletions() {
+        assert!(remove_insertions("a\nb\n", "a\n", "a\nc\n").is_none());
+        assert_eq!(
+            remove_insertions("a\n", "a\nb\n", "x\na\nb\n").as_deref(),
+            Some("x\na\n")
+        );
+    }
+}
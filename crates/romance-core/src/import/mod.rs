@@ -0,0 +1,643 @@
+//! Generate entities for tables that already exist, e.g. when moving a
+//! legacy app onto Romance.
+//!
+//! A database source (SQL DDL or a live Postgres database) is read into a
+//! [`Database`]: tables, their columns and keys, and enum types. [`plan`]
+//! maps that onto entity definitions: column types onto [`FieldType`]s,
+//! foreign keys onto `BelongsTo` relations and tables holding nothing but two
+//! foreign keys onto many-to-many relations. [`generate`] then generates the
+//! entities in baseline mode: the tables exist, so instead of create-table
+//! migrations a single no-op baseline migration records which tables were
+//! imported.
+//!
+//! API descriptions ([`openapi`]) and sample payloads ([`sample`]) are mapped
+//! onto entities whose tables don't exist yet, and generated like
+//! `romance generate entity` would.
+
+pub mod openapi;
+pub mod postgres;
+pub mod sample;
+pub mod sql;
+
+use crate::entity::{
+    EntityDefinition, FieldDefinition, FieldType, FieldVisibility, RelationDefinition, RelationType,
+    ValidationRule,
+};
+use crate::error::Failure;
+use crate::generator::context::{self, markers};
+use crate::generator::plan::{self, GenerationTracker};
+use crate::generator::recover::IMPLICIT_COLUMNS;
+use crate::generator::{backend, frontend, junction, migration};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::relation;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::Result;
+use heck::{ToPascalCase, ToSnakeCase};
+use std::collections::BTreeMap;
+use tera::Context;
+
+/// Tables and enum types read from an import source.
+#[derive(Debug, Clone, Default)]
+pub struct Database {
+    pub tables: Vec<Table>,
+    /// Enum types by name, with their variants.
+    pub enums: BTreeMap<String, Vec<String>>,
+}
+
+impl Database {
+    /// Keep only the `tables` listed, by name or as `schema.name`. Fails on
+    /// names that aren't in the database.
+    pub fn retain_tables(&mut self, tables: &[String]) -> Result<()> {
+        if let Some(unknown) = tables.iter().find(|name| !self.tables.iter().any(|t| t.matches(name))) {
+            return Err(Failure::InvalidInput.error(format!("Table '{}' not found", unknown)));
+        }
+        self.tables.retain(|t| tables.iter().any(|name| t.matches(name)));
+        Ok(())
+    }
+
+    /// The table `name`, in `schema` if given.
+    pub fn find_table(&mut self, schema: Option<&str>, name: &str) -> Option<&mut Table> {
+        self.tables
+            .iter_mut()
+            .find(|t| t.name == name && (schema.is_none() || t.schema.as_deref() == schema))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+    /// Column sets with a unique constraint or index.
+    pub unique: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// The table's name, qualified with its schema unless that's `public`.
+    pub fn qualified_name(&self) -> String {
+        match self.schema.as_deref().filter(|s| *s != "public") {
+            Some(schema) => format!("{}.{}", schema, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.qualified_name() == name
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    /// The column's type as written in the source, lowercased, e.g.
+    /// `character varying(255)`.
+    pub data_type: String,
+    pub nullable: bool,
+    /// The default expression as written in the source.
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    /// The referenced table's schema, if qualified.
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+/// What an import will generate.
+#[derive(Debug, Clone, Default)]
+pub struct Import {
+    /// The tables already exist (imported from a database): no create-table
+    /// migrations, and a baseline migration instead.
+    pub baseline: bool,
+    /// New entities, parents before the entities referencing them.
+    pub entities: Vec<EntityDefinition>,
+    pub junctions: Vec<Junction>,
+    /// Tables or columns that were skipped or won't map cleanly onto the
+    /// generated models.
+    pub warnings: Vec<String>,
+}
+
+/// A many-to-many relation through an existing junction table.
+#[derive(Debug, Clone)]
+pub struct Junction {
+    pub table: String,
+    pub source: String,
+    pub target: String,
+}
+
+/// Qualified names of the tables in `db` whose entities don't exist yet: the
+/// tables imported unless others are asked for.
+pub fn new_tables(project: &Project, db: &Database) -> Vec<String> {
+    let modules = module::all(project);
+    db.tables
+        .iter()
+        .filter(|t| {
+            placement(t, &modules)
+                .map(|(name, _)| !relation::entity_exists(project, &name))
+                .unwrap_or(true)
+        })
+        .map(Table::qualified_name)
+        .collect()
+}
+
+/// Map the tables in `db` onto entities for `project`. Tables of entities
+/// that already exist are skipped, but foreign keys to them still become
+/// relations.
+pub fn plan(project: &Project, db: &Database) -> Import {
+    let modules = module::all(project);
+    let mut import = Import {
+        baseline: true,
+        ..Import::default()
+    };
+
+    // Which entity each importable table becomes
+    let mut names: BTreeMap<(Option<String>, String), (String, Option<EntityModule>)> = BTreeMap::new();
+    for table in &db.tables {
+        if table.name == "seaql_migrations" {
+            continue;
+        }
+        match placement(table, &modules) {
+            Ok((name, entity_module)) => {
+                names.insert(table_key(table.schema.as_deref(), &table.name), (name, entity_module));
+            }
+            Err(warning) => import.warnings.push(warning),
+        }
+    }
+    let entity_of = |schema: Option<&str>, table: &str| names.get(&table_key(schema, table)).map(|(n, _)| n.clone());
+
+    let mut entities: Vec<EntityDefinition> = Vec::new();
+    let mut junctions = Vec::new();
+    for table in &db.tables {
+        let Some((name, entity_module)) = names.get(&table_key(table.schema.as_deref(), &table.name)) else {
+            continue;
+        };
+        if let Some(junction) = as_junction(table, &entity_of) {
+            check_junction(table, &junction, &mut import.warnings);
+            junctions.push(junction);
+            continue;
+        }
+        if relation::entity_exists(project, name) {
+            import
+                .warnings
+                .push(format!("Skipped table '{}': entity '{}' already exists", table.name, name));
+            continue;
+        }
+        entities.push(entity(table, name, entity_module.as_ref(), db, &entity_of, &mut import.warnings));
+    }
+
+    // Put each many-to-many relation on whichever side is being imported
+    for junction in &junctions {
+        let (source, target) = if entities.iter().any(|e| e.name == junction.source) {
+            (&junction.source, &junction.target)
+        } else {
+            (&junction.target, &junction.source)
+        };
+        if let Some(entity) = entities.iter_mut().find(|e| e.name == *source) {
+            entity.relations.push(RelationDefinition {
+                name: utils::pluralize(&target.to_snake_case()),
+                relation_type: RelationType::ManyToMany,
+                target_entity: target.clone(),
+                fk_column: None,
+                optional: false,
+            });
+        }
+    }
+    junctions.retain(|j| {
+        let known = |name: &String| entities.iter().any(|e| e.name == *name) || relation::entity_exists(project, name);
+        known(&j.source) && known(&j.target)
+    });
+
+    import.entities = parents_first(entities);
+    import.junctions = junctions;
+    import
+}
+
+/// Generate the entities and junctions of `import`. In baseline mode no
+/// create-table migrations are generated, and a baseline migration lists the
+/// imported tables instead.
+pub fn generate(project: &Project, import: &Import, tracker: &mut GenerationTracker) -> Result<()> {
+    if import.entities.is_empty() && import.junctions.is_empty() {
+        return Err(Failure::InvalidInput.error("Nothing to import: no new entities found"));
+    }
+
+    // Pre-validate all markers before writing any files
+    for entity in &import.entities {
+        backend::validate(project, entity)?;
+        frontend::validate(project, entity)?;
+    }
+    let lib_path = project.migration_src().join("lib.rs");
+    let checks = [
+        plan::check(&lib_path, markers::MIGRATION_MODS),
+        plan::check(lib_path, markers::MIGRATIONS),
+    ];
+    plan::validate_markers(project.fs(), &checks)?;
+
+    for entity in &import.entities {
+        backend::generate(project, entity, tracker)?;
+        if import.baseline {
+            // Apply pending relations from earlier `generate entity` runs; the
+            // imported many-to-many relations use the existing junction tables
+            let mut without_m2m = entity.clone();
+            without_m2m.relations.retain(|r| r.relation_type != RelationType::ManyToMany);
+            backend::generate_relations(project, &without_m2m)?;
+        } else {
+            migration::generate(project, entity, tracker)?;
+            backend::generate_relations(project, entity)?;
+        }
+        frontend::generate(project, entity, tracker)?;
+    }
+    for j in &import.junctions {
+        junction::generate_existing(project, &j.source, &j.target, &j.table)?;
+    }
+    if !import.baseline {
+        return Ok(());
+    }
+
+    let mut tables: Vec<String> = import
+        .entities
+        .iter()
+        .map(|e| {
+            let entity_module = module::of_entity(project, e)?;
+            Ok(e.table_name
+                .clone()
+                .unwrap_or_else(|| module::table_name(entity_module.as_ref(), &e.name.to_snake_case())))
+        })
+        .collect::<Result<_>>()?;
+    tables.extend(import.junctions.iter().map(|j| j.table.clone()));
+
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert("tables", &tables.join(", "));
+    let content = engine.render("import/baseline_migration.rs.tera", &ctx)?;
+    let migration_module = format!("m{}_import_baseline", migration::next_timestamp(project));
+    let migration_path = project.migration_src().join(format!("{}.rs", migration_module));
+    utils::write_file(project.fs(), &migration_path, &content)?;
+    utils::ui::created(&project.relative(&migration_path).display().to_string());
+    tracker.track(migration_path);
+    context::register_migration(project.fs(), project.root(), &migration_module)?;
+    Ok(())
+}
+
+fn table_key(schema: Option<&str>, table: &str) -> (Option<String>, String) {
+    let schema = schema.filter(|s| *s != "public").map(str::to_string);
+    (schema, table.to_string())
+}
+
+/// The entity name and module for `table`: tables in a schema or with the
+/// table prefix of a declared module go in that module.
+fn placement(table: &Table, modules: &[EntityModule]) -> Result<(String, Option<EntityModule>), String> {
+    let name_for = |table_name: &str| utils::singularize(table_name).to_pascal_case();
+    match table.schema.as_deref().filter(|s| *s != "public") {
+        Some(schema) => match modules.iter().find(|m| m.schema() == Some(schema)) {
+            Some(m) => {
+                let prefix = m.config.table_prefix.as_deref().unwrap_or_default();
+                let bare = table.name.strip_prefix(prefix).unwrap_or(&table.name);
+                Ok((name_for(bare), Some(m.clone())))
+            }
+            None => Err(format!(
+                "Skipped table '{}.{}': no module has schema '{}' (romance generate module <name> --schema {})",
+                schema, table.name, schema, schema
+            )),
+        },
+        None => {
+            let prefixed = modules.iter().find_map(|m| {
+                let prefix = m.config.table_prefix.as_deref().filter(|p| !p.is_empty())?;
+                let bare = table.name.strip_prefix(prefix)?;
+                (m.schema().is_none() && !bare.is_empty()).then(|| (name_for(bare), Some(m.clone())))
+            });
+            Ok(prefixed.unwrap_or_else(|| (name_for(&table.name), None)))
+        }
+    }
+}
+
+/// `table` as a many-to-many junction: nothing but single-column foreign
+/// keys to two different importable tables, an `id` and timestamps.
+fn as_junction(
+    table: &Table,
+    entity_of: &dyn Fn(Option<&str>, &str) -> Option<String>,
+) -> Option<Junction> {
+    let [a, b] = table.foreign_keys.as_slice() else {
+        return None;
+    };
+    if a.columns.len() != 1 || b.columns.len() != 1 {
+        return None;
+    }
+    let fk_columns = [&a.columns[0], &b.columns[0]];
+    let only_keys = table
+        .columns
+        .iter()
+        .all(|c| fk_columns.contains(&&c.name) || ["id", "created_at", "updated_at"].contains(&c.name.as_str()));
+    let source = entity_of(a.schema.as_deref(), &a.table)?;
+    let target = entity_of(b.schema.as_deref(), &b.table)?;
+    (only_keys && source != target).then(|| Junction {
+        table: table.name.clone(),
+        source,
+        target,
+    })
+}
+
+/// Warn about junction tables whose columns differ from the generated
+/// junction model's.
+fn check_junction(table: &Table, junction: &Junction, warnings: &mut Vec<String>) {
+    let mut expected = vec![
+        format!("{}_id", junction.source.to_snake_case()),
+        format!("{}_id", junction.target.to_snake_case()),
+        "id".to_string(),
+        "created_at".to_string(),
+    ];
+    expected.retain(|c| !table.columns.iter().any(|col| col.name == *c));
+    if !expected.is_empty() {
+        warnings.push(format!(
+            "Junction table '{}' has no {} column(s); the generated junction model expects them",
+            table.name,
+            expected.join(", ")
+        ));
+    }
+}
+
+fn entity(
+    table: &Table,
+    name: &str,
+    entity_module: Option<&EntityModule>,
+    db: &Database,
+    entity_of: &dyn Fn(Option<&str>, &str) -> Option<String>,
+    warnings: &mut Vec<String>,
+) -> EntityDefinition {
+    let mut fields = Vec::new();
+    let mut relations = Vec::new();
+
+    let id = table.columns.iter().find(|c| c.name == "id");
+    if !id.is_some_and(|c| matches!(field_type(&c.data_type, db), Some(FieldType::Uuid))) || table.primary_key != ["id"] {
+        warnings.push(format!(
+            "Table '{}': primary key is not a uuid `id` column; the generated model expects one",
+            table.name
+        ));
+    }
+    for implicit in ["created_at", "updated_at"] {
+        if !table.columns.iter().any(|c| c.name == implicit) {
+            warnings.push(format!(
+                "Table '{}' has no {} column; the generated model expects one",
+                table.name, implicit
+            ));
+        }
+    }
+
+    for column in &table.columns {
+        if IMPLICIT_COLUMNS.contains(&column.name.as_str()) {
+            continue;
+        }
+        if column.name.to_snake_case() != column.name {
+            warnings.push(format!(
+                "Table '{}': column '{}' is not snake_case; the generated field '{}' won't map onto it",
+                table.name,
+                column.name,
+                column.name.to_snake_case()
+            ));
+        }
+        let mut field_type = field_type(&column.data_type, db).unwrap_or_else(|| {
+            warnings.push(format!(
+                "Table '{}': column '{}' has type '{}', imported as text",
+                table.name, column.name, column.data_type
+            ));
+            FieldType::Text
+        });
+
+        let fk = table
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.columns.len() == 1 && fk.columns[0] == column.name);
+        let mut relation = None;
+        if let Some(fk) = fk {
+            match entity_of(fk.schema.as_deref(), &fk.table) {
+                Some(target) if matches!(field_type, FieldType::Uuid) => relation = Some(target),
+                Some(_) => warnings.push(format!(
+                    "Table '{}': foreign key '{}' is not a uuid, imported as a plain field",
+                    table.name, column.name
+                )),
+                None => warnings.push(format!(
+                    "Table '{}': foreign key '{}' references '{}', which isn't imported; imported as a plain field",
+                    table.name, column.name, fk.table
+                )),
+            }
+        }
+        if relation.is_some() {
+            field_type = FieldType::Uuid;
+        }
+
+        let field_name = column.name.to_snake_case();
+        if let Some(target) = &relation {
+            relations.push(RelationDefinition {
+                name: field_name.clone(),
+                relation_type: RelationType::BelongsTo,
+                target_entity: target.clone(),
+                fk_column: Some(field_name.clone()),
+                optional: column.nullable,
+            });
+        }
+        fields.push(FieldDefinition {
+            name: field_name,
+            field_type,
+            optional: column.nullable,
+            default: column.default.as_deref().and_then(default_value),
+            relation,
+            validations: if table.unique.iter().any(|u| *u == [column.name.as_str()]) {
+                vec![ValidationRule::Unique]
+            } else {
+                Vec::new()
+            },
+            searchable: false,
+            visibility: FieldVisibility::default(),
+        });
+    }
+
+    let snake = name.to_snake_case();
+    EntityDefinition {
+        name: name.to_string(),
+        fields,
+        relations,
+        module: entity_module.map(|m| m.name.clone()),
+        table_name: (module::table_name(entity_module, &snake) != table.name).then(|| table.name.clone()),
+    }
+}
+
+/// The [`FieldType`] for a Postgres column type, `None` if there isn't one.
+pub fn field_type(data_type: &str, db: &Database) -> Option<FieldType> {
+    if data_type.ends_with(']') || data_type.starts_with('_') {
+        return None;
+    }
+    let (base, args) = match data_type.split_once('(') {
+        Some((base, rest)) => {
+            // `timestamp(3) with time zone`: the modifier sits mid-name
+            let (args, tail) = rest.split_once(')').unwrap_or((rest, ""));
+            (format!("{}{}", base.trim_end(), tail).trim().to_string(), Some(args))
+        }
+        None => (data_type.trim().to_string(), None),
+    };
+    let base = base.rsplit('.').next().unwrap_or(&base).trim_matches('"');
+    let length = args.and_then(|a| a.trim().parse::<u32>().ok());
+
+    Some(match base {
+        // Unbounded varchars don't fit in VARCHAR(255)
+        "character varying" | "varchar" => match length {
+            Some(n) if n <= 255 => FieldType::String,
+            _ => FieldType::Text,
+        },
+        "character" | "char" | "bpchar" => match length {
+            Some(n) if n > 255 => FieldType::Text,
+            _ => FieldType::String,
+        },
+        "text" | "citext" => FieldType::Text,
+        "boolean" | "bool" => FieldType::Bool,
+        "smallint" | "int2" | "integer" | "int" | "int4" | "smallserial" | "serial" | "serial4" => FieldType::Int32,
+        "bigint" | "int8" | "bigserial" | "serial8" => FieldType::Int64,
+        "real" | "float4" | "double precision" | "float8" | "float" => FieldType::Float64,
+        "numeric" | "decimal" | "money" => FieldType::Decimal,
+        "uuid" => FieldType::Uuid,
+        "timestamp" | "timestamptz" | "timestamp with time zone" | "timestamp without time zone" => FieldType::DateTime,
+        "date" => FieldType::Date,
+        "json" | "jsonb" => FieldType::Json,
+        other => FieldType::Enum(db.enums.get(other)?.clone()),
+    })
+}
+
+/// A column default as a plain value: string literals unquoted and casts
+/// dropped. Sequence defaults (serial columns) are left out.
+fn default_value(expr: &str) -> Option<String> {
+    let expr = expr.trim();
+    if expr.eq_ignore_ascii_case("null") || expr.starts_with("nextval(") {
+        return None;
+    }
+    if let Some(rest) = expr.strip_prefix('\'') {
+        let mut value = String::new();
+        let mut chars = rest.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            value.push(c);
+        }
+        return Some(value);
+    }
+    let value = expr.split("::").next().unwrap_or(expr);
+    let value = value
+        .strip_prefix('(')
+        .and_then(|v| v.strip_suffix(')'))
+        .unwrap_or(value);
+    Some(value.to_string()).filter(|v| !v.is_empty())
+}
+
+/// Order entities so that every entity comes after the ones it belongs to,
+/// keeping the source order otherwise.
+fn parents_first(entities: Vec<EntityDefinition>) -> Vec<EntityDefinition> {
+    fn visit(i: usize, entities: &[EntityDefinition], state: &mut [u8], order: &mut Vec<usize>) {
+        // 0 = unvisited, 1 = in progress (a cycle), 2 = done
+        if state[i] != 0 {
+            return;
+        }
+        state[i] = 1;
+        for rel in &entities[i].relations {
+            if rel.relation_type == RelationType::BelongsTo {
+                if let Some(parent) = entities.iter().position(|e| e.name == rel.target_entity) {
+                    visit(parent, entities, state, order);
+                }
+            }
+        }
+        state[i] = 2;
+        order.push(i);
+    }
+
+    let mut state = vec![0u8; entities.len()];
+    let mut order = Vec::new();
+    for i in 0..entities.len() {
+        visit(i, &entities, &mut state, &mut order);
+    }
+    let mut slots: Vec<Option<EntityDefinition>> = entities.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_postgres_types_onto_field_types() {
+        let mut db = Database::default();
+        db.enums.insert("post_status".to_string(), vec!["draft".to_string()]);
+        let cases = [
+            ("character varying(100)", "String"),
+            ("character varying(1000)", "Text"),
+            ("character varying", "Text"),
+            ("text", "Text"),
+            ("boolean", "Bool"),
+            ("integer", "Int32"),
+            ("bigint", "Int64"),
+            ("double precision", "Float64"),
+            ("numeric(10,2)", "Decimal"),
+            ("uuid", "Uuid"),
+            ("timestamp(3) with time zone", "DateTime"),
+            ("date", "Date"),
+            ("jsonb", "Json"),
+            ("public.post_status", "Enum([\"draft\"])"),
+        ];
+        for (data_type, expected) in cases {
+            let mapped = field_type(data_type, &db).map(|t| format!("{:?}", t));
+            assert_eq!(mapped.as_deref(), Some(expected), "{}", data_type);
+        }
+        assert!(field_type("text[]", &db).is_none());
+        assert!(field_type("tsvector", &db).is_none());
+    }
+
+    #[test]
+    fn cleans_up_default_expressions() {
+        assert_eq!(default_value("'draft'::character varying").as_deref(), Some("draft"));
+        assert_eq!(default_value("'it''s'::text").as_deref(), Some("it's"));
+        assert_eq!(default_value("0").as_deref(), Some("0"));
+        assert_eq!(default_value("now()").as_deref(), Some("now()"));
+        assert_eq!(default_value("(0)::numeric").as_deref(), Some("0"));
+        assert_eq!(default_value("nextval('posts_id_seq'::regclass)"), None);
+        assert_eq!(default_value("NULL"), None);
+    }
+
+    #[test]
+    fn new_tables_leave_out_existing_entities() {
+        let project = crate::scaffold::render_project(
+            std::sync::Arc::new(crate::fs::MemoryFs::new()),
+            std::path::Path::new("/app"),
+            "app",
+        )
+        .unwrap();
+        project
+            .fs()
+            .write(&project.backend_src().join("entities/customer.rs"), "")
+            .unwrap();
+        let table = |schema: Option<&str>, name: &str| Table {
+            schema: schema.map(str::to_string),
+            name: name.to_string(),
+            ..Table::default()
+        };
+        let db = Database {
+            tables: vec![table(None, "customers"), table(None, "posts"), table(Some("audit"), "events")],
+            ..Database::default()
+        };
+        assert_eq!(new_tables(&project, &db), vec!["posts", "audit.events"]);
+    }
+
+    #[test]
+    fn orders_parents_before_children() {
+        let comment = crate::entity::parse_entity("Comment", &["post_id:uuid->Post".to_string()]).unwrap();
+        let post = crate::entity::parse_entity("Post", &["author_id:uuid->User".to_string()]).unwrap();
+        let user = crate::entity::parse_entity("User", &["email:string".to_string()]).unwrap();
+        let names: Vec<String> = parents_first(vec![comment, post, user]).into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["User", "Post", "Comment"]);
+    }
+}
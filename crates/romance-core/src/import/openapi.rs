@@ -0,0 +1,377 @@
+//! Map OpenAPI component schemas, or JSON Schema definitions, onto entities.
+//!
+//! Each object schema becomes an entity named after the schema. Property
+//! types and formats become field types, `$ref`s to other object schemas
+//! become relations (`BelongsTo` for a single reference, `ManyToMany` for an
+//! array of them), `enum`s become enum fields and `minLength`, `maxLength`,
+//! `pattern`, `minimum`, `maximum` and the `email` and `uri` formats become
+//! validations. Anything else (`oneOf`, arrays of scalars, free-form objects)
+//! is reported, and stored as JSON where a column is still needed.
+
+use super::{parents_first, Import};
+use crate::entity::{
+    EntityDefinition, FieldDefinition, FieldType, FieldVisibility, RelationDefinition, RelationType,
+    ValidationRule,
+};
+use crate::error::Failure;
+use crate::generator::recover::IMPLICIT_COLUMNS;
+use crate::project::Project;
+use crate::relation;
+use anyhow::Result;
+use heck::{ToPascalCase, ToSnakeCase};
+use serde_json::{Map, Value};
+
+/// Parse an OpenAPI or JSON Schema document, in JSON or YAML.
+pub fn parse(source: &str) -> Result<Value> {
+    let trimmed = source.trim_start();
+    let doc: Value = if trimmed.starts_with('{') {
+        serde_json::from_str(source).map_err(|e| Failure::InvalidInput.error(format!("Invalid JSON: {}", e)))?
+    } else {
+        serde_yaml::from_str(source).map_err(|e| Failure::InvalidInput.error(format!("Invalid YAML: {}", e)))?
+    };
+    if !doc.is_object() {
+        return Err(Failure::InvalidInput.error("Expected an OpenAPI or JSON Schema document"));
+    }
+    Ok(doc)
+}
+
+/// The named schemas of `doc`: OpenAPI 3 `components.schemas`, Swagger 2
+/// `definitions`, JSON Schema `$defs`/`definitions`, or a root schema with a
+/// `title`.
+pub fn schemas(doc: &Value) -> Map<String, Value> {
+    for path in ["/components/schemas", "/definitions", "/$defs"] {
+        if let Some(Value::Object(schemas)) = doc.pointer(path) {
+            return schemas.clone();
+        }
+    }
+    let mut schemas = Map::new();
+    if let (Some(title), Some(_)) = (doc["title"].as_str(), doc.get("properties")) {
+        schemas.insert(title.to_string(), doc.clone());
+    }
+    schemas
+}
+
+/// Map the object schemas in `doc` onto new entities, only those named in
+/// `only` if it isn't empty. Schemas of entities that already exist are
+/// skipped.
+pub fn plan(project: &Project, doc: &Value, only: &[String]) -> Result<Import> {
+    let schemas = schemas(doc);
+    if let Some(unknown) = only.iter().find(|name| !schemas.contains_key(*name)) {
+        return Err(Failure::InvalidInput.error(format!("Schema '{}' not found", unknown)));
+    }
+
+    let mut import = Import::default();
+    let mut entities = Vec::new();
+    for (name, schema) in &schemas {
+        if !only.is_empty() && !only.contains(name) {
+            continue;
+        }
+        let Some(schema) = object_schema(doc, schema) else {
+            // Enums and scalar aliases are used through `$ref`s
+            if !is_scalar(doc, schema) {
+                import
+                    .warnings
+                    .push(format!("Skipped schema '{}': not an object with properties", name));
+            }
+            continue;
+        };
+        let entity_name = name.to_pascal_case();
+        if relation::entity_exists(project, &entity_name) {
+            import
+                .warnings
+                .push(format!("Skipped schema '{}': entity '{}' already exists", name, entity_name));
+            continue;
+        }
+        entities.push(entity(doc, &entity_name, &schema, &mut import.warnings));
+    }
+
+    // Relations need their target entity, imported now or earlier
+    let names: Vec<String> = entities.iter().map(|e| e.name.clone()).collect();
+    let known = |name: &str| names.iter().any(|n| n == name) || relation::entity_exists(project, name);
+    for entity in &mut entities {
+        let (kept, dropped): (Vec<_>, Vec<_>) = entity.relations.drain(..).partition(|r| known(&r.target_entity));
+        entity.relations = kept;
+        for rel in dropped {
+            import.warnings.push(format!(
+                "{}.{}: entity '{}' isn't imported, relation skipped",
+                entity.name, rel.name, rel.target_entity
+            ));
+            if let Some(field) = entity.fields.iter_mut().find(|f| Some(&f.name) == rel.fk_column.as_ref()) {
+                field.relation = None;
+            }
+        }
+    }
+    import.entities = parents_first(entities);
+    Ok(import)
+}
+
+/// Follow a `$ref` within the document.
+fn resolve<'a>(doc: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema["$ref"].as_str().and_then(|r| r.strip_prefix('#')) {
+        Some(pointer) => doc.pointer(pointer).unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// The name of the schema a `$ref` points to.
+fn ref_name(schema: &Value) -> Option<&str> {
+    schema["$ref"].as_str()?.rsplit('/').next()
+}
+
+/// `schema` as an object schema, merging `allOf` parts.
+fn object_schema(doc: &Value, schema: &Value) -> Option<Value> {
+    let schema = resolve(doc, schema);
+    let mut properties = Map::new();
+    let mut required: Vec<Value> = Vec::new();
+    for part in schema["allOf"].as_array().into_iter().flatten() {
+        if let Some(part) = object_schema(doc, part) {
+            properties.extend(part["properties"].as_object().cloned().unwrap_or_default());
+            required.extend(part["required"].as_array().cloned().unwrap_or_default());
+        }
+    }
+    properties.extend(schema["properties"].as_object().cloned().unwrap_or_default());
+    required.extend(schema["required"].as_array().cloned().unwrap_or_default());
+    (!properties.is_empty()).then(|| serde_json::json!({ "properties": properties, "required": required }))
+}
+
+fn is_scalar(doc: &Value, schema: &Value) -> bool {
+    let schema = resolve(doc, schema);
+    schema.get("enum").is_some() || matches!(type_of(schema).0, Some("string" | "integer" | "number" | "boolean"))
+}
+
+/// The schema's `type`, and whether it allows `null` (OpenAPI 3.0
+/// `nullable`, or a `["string", "null"]` type list).
+fn type_of(schema: &Value) -> (Option<&str>, bool) {
+    let nullable = schema["nullable"].as_bool().unwrap_or(false);
+    match &schema["type"] {
+        Value::String(t) => (Some(t.as_str()), nullable),
+        Value::Array(types) => {
+            let has_null = types.iter().any(|t| t == "null");
+            (types.iter().filter_map(Value::as_str).find(|t| *t != "null"), nullable || has_null)
+        }
+        _ => (None, nullable),
+    }
+}
+
+fn entity(doc: &Value, name: &str, schema: &Value, warnings: &mut Vec<String>) -> EntityDefinition {
+    let required: Vec<&str> = schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str).collect();
+    let mut fields = Vec::new();
+    let mut relations = Vec::new();
+
+    for (property, prop_schema) in schema["properties"].as_object().into_iter().flatten() {
+        let field_name = property.to_snake_case();
+        if IMPLICIT_COLUMNS.contains(&field_name.as_str()) {
+            continue;
+        }
+        let (_, nullable) = type_of(resolve(doc, prop_schema));
+        let optional = nullable || !required.contains(&property.as_str());
+        let mut report = |what: &str| warnings.push(format!("{}.{}: {}", name, property, what));
+
+        // References to other object schemas are relations
+        let items = &prop_schema["items"];
+        let target = |s: &Value| {
+            ref_name(s).filter(|_| object_schema(doc, s).is_some()).map(|t| t.to_pascal_case())
+        };
+        if let Some(target) = target(prop_schema) {
+            let fk = if field_name.ends_with("_id") { field_name.clone() } else { format!("{}_id", field_name) };
+            relations.push(RelationDefinition {
+                name: fk.clone(),
+                relation_type: RelationType::BelongsTo,
+                target_entity: target.clone(),
+                fk_column: Some(fk.clone()),
+                optional,
+            });
+            fields.push(field(fk, FieldType::Uuid, optional, Some(target), Vec::new()));
+            continue;
+        }
+        if let Some(target) = target(items) {
+            relations.push(RelationDefinition {
+                name: field_name,
+                relation_type: RelationType::ManyToMany,
+                target_entity: target,
+                fk_column: None,
+                optional: false,
+            });
+            continue;
+        }
+
+        let prop_schema = resolve(doc, prop_schema);
+        let mut validations = Vec::new();
+        let field_type = match field_type(prop_schema, &mut validations) {
+            Ok(field_type) => field_type,
+            Err(construct) => {
+                report(&format!("{}, stored as json", construct));
+                FieldType::Json
+            }
+        };
+        for unsupported in ["multipleOf", "exclusiveMinimum", "exclusiveMaximum", "minItems", "maxItems", "uniqueItems"] {
+            if prop_schema.get(unsupported).is_some() {
+                report(&format!("`{}` isn't supported, ignored", unsupported));
+            }
+        }
+        for (keyword, make) in [("minimum", ValidationRule::Min as fn(u64) -> _), ("maximum", ValidationRule::Max)] {
+            match prop_schema.get(keyword) {
+                Some(v) if v.as_u64().is_some() => validations.push(make(v.as_u64().unwrap_or_default())),
+                Some(v) => report(&format!("`{}: {}` isn't a non-negative integer, ignored", keyword, v)),
+                None => {}
+            }
+        }
+
+        let mut field = field(field_name, field_type, optional, None, validations);
+        field.default = prop_schema.get("default").map(|d| match d {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+        fields.push(field);
+    }
+
+    EntityDefinition {
+        name: name.to_string(),
+        fields,
+        relations,
+        module: None,
+        table_name: None,
+    }
+}
+
+fn field(
+    name: String,
+    field_type: FieldType,
+    optional: bool,
+    relation: Option<String>,
+    validations: Vec<ValidationRule>,
+) -> FieldDefinition {
+    FieldDefinition {
+        name,
+        field_type,
+        optional,
+        default: None,
+        relation,
+        validations,
+        searchable: false,
+        visibility: FieldVisibility::default(),
+    }
+}
+
+/// The field type for a scalar schema, adding the validations it implies.
+/// `Err` describes a construct that has no field type.
+fn field_type(schema: &Value, validations: &mut Vec<ValidationRule>) -> Result<FieldType, String> {
+    for composite in ["oneOf", "anyOf", "not"] {
+        if schema.get(composite).is_some() {
+            return Err(format!("`{}` can't be mapped", composite));
+        }
+    }
+    if let Some(variants) = schema["enum"].as_array() {
+        let variants: Vec<String> = variants.iter().filter_map(|v| v.as_str().map(str::to_string)).collect();
+        return Ok(FieldType::Enum(variants));
+    }
+
+    let format = schema["format"].as_str().unwrap_or_default();
+    match type_of(schema).0 {
+        Some("string") => {
+            if let Some(min) = schema["minLength"].as_u64() {
+                validations.push(ValidationRule::Min(min));
+            }
+            let max = schema["maxLength"].as_u64();
+            if let Some(max) = max {
+                validations.push(ValidationRule::Max(max));
+            }
+            if let Some(pattern) = schema["pattern"].as_str() {
+                validations.push(ValidationRule::Regex(pattern.to_string()));
+            }
+            Ok(match format {
+                "uuid" => FieldType::Uuid,
+                "date-time" => FieldType::DateTime,
+                "date" => FieldType::Date,
+                "binary" => FieldType::File,
+                "decimal" => FieldType::Decimal,
+                "email" | "uri" | "url" => {
+                    validations.push(if format == "email" { ValidationRule::Email } else { ValidationRule::Url });
+                    FieldType::String
+                }
+                _ if max.is_some_and(|m| m > 255) => FieldType::Text,
+                _ => FieldType::String,
+            })
+        }
+        Some("integer") if format == "int64" => Ok(FieldType::Int64),
+        Some("integer") => Ok(FieldType::Int32),
+        Some("number") if format == "decimal" => Ok(FieldType::Decimal),
+        Some("number") => Ok(FieldType::Float64),
+        Some("boolean") => Ok(FieldType::Bool),
+        Some("object") => Ok(FieldType::Json),
+        Some("array") => Err("an array of scalars".to_string()),
+        Some(other) => Err(format!("type `{}` can't be mapped", other)),
+        None if schema.get("allOf").is_some() => Err("`allOf` of scalars can't be mapped".to_string()),
+        None => Err("no type".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(schema: Value) -> (Result<FieldType, String>, Vec<ValidationRule>) {
+        let mut validations = Vec::new();
+        (field_type(&schema, &mut validations), validations)
+    }
+
+    #[test]
+    fn maps_formats_onto_field_types() {
+        let cases = [
+            (serde_json::json!({"type": "string", "format": "uuid"}), "Uuid"),
+            (serde_json::json!({"type": "string", "format": "date-time"}), "DateTime"),
+            (serde_json::json!({"type": "string", "format": "date"}), "Date"),
+            (serde_json::json!({"type": "string", "maxLength": 2000}), "Text"),
+            (serde_json::json!({"type": "integer", "format": "int64"}), "Int64"),
+            (serde_json::json!({"type": "integer"}), "Int32"),
+            (serde_json::json!({"type": "number"}), "Float64"),
+            (serde_json::json!({"type": ["boolean", "null"]}), "Bool"),
+            (serde_json::json!({"type": "object"}), "Json"),
+            (serde_json::json!({"type": "string", "enum": ["a", "b"]}), "Enum([\"a\", \"b\"])"),
+        ];
+        for (schema, expected) in cases {
+            let (field_type, _) = types(schema.clone());
+            assert_eq!(format!("{:?}", field_type.unwrap()), expected, "{}", schema);
+        }
+        assert!(types(serde_json::json!({"type": "array", "items": {"type": "string"}})).0.is_err());
+        assert!(types(serde_json::json!({"oneOf": [{"type": "string"}]})).0.is_err());
+    }
+
+    #[test]
+    fn maps_constraints_onto_validations() {
+        let (field_type, validations) = types(serde_json::json!({
+            "type": "string", "format": "email", "minLength": 3, "maxLength": 120, "pattern": "^[a-z]+@"
+        }));
+        assert!(matches!(field_type, Ok(FieldType::String)));
+        assert_eq!(
+            validations,
+            vec![
+                ValidationRule::Min(3),
+                ValidationRule::Max(120),
+                ValidationRule::Regex("^[a-z]+@".to_string()),
+                ValidationRule::Email,
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_schemas_in_each_document_kind() {
+        let openapi = serde_json::json!({"components": {"schemas": {"Pet": {"type": "object"}}}});
+        let swagger = serde_json::json!({"definitions": {"Pet": {"type": "object"}}});
+        let json_schema = serde_json::json!({"title": "Pet", "properties": {"name": {"type": "string"}}});
+        for doc in [openapi, swagger, json_schema] {
+            assert!(schemas(&doc).contains_key("Pet"), "{}", doc);
+        }
+    }
+
+    #[test]
+    fn merges_all_of_parts() {
+        let doc = serde_json::json!({"components": {"schemas": {
+            "Base": {"type": "object", "properties": {"name": {"type": "string"}}, "required": ["name"]},
+            "Pet": {"allOf": [{"$ref": "#/components/schemas/Base"}, {"properties": {"age": {"type": "integer"}}}]}
+        }}});
+        let pet = object_schema(&doc, &doc["components"]["schemas"]["Pet"]).unwrap();
+        assert_eq!(pet["properties"].as_object().unwrap().len(), 2);
+        assert_eq!(pet["required"], serde_json::json!(["name"]));
+    }
+}
@@ -0,0 +1,220 @@
+//! Read tables out of a live Postgres database.
+//!
+//! The catalog is queried through `psql`, like the other database commands
+//! shell out to the Postgres client tools: a single query collects columns,
+//! keys, unique constraints and indexes, and enum types from `pg_catalog` as
+//! one JSON document.
+
+use super::{Column, Database, ForeignKey, Table};
+use crate::error::Failure;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::process::Command;
+
+const CATALOG_QUERY: &str = r#"
+WITH tables AS (
+    SELECT c.oid, n.nspname AS schema, c.relname AS name
+    FROM pg_class c
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    WHERE c.relkind IN ('r', 'p')
+      AND NOT c.relispartition
+      AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+      AND n.nspname NOT LIKE 'pg_toast%'
+)
+SELECT json_build_object(
+    'tables', (
+        SELECT coalesce(json_agg(json_build_object(
+            'schema', t.schema,
+            'name', t.name,
+            'columns', (
+                SELECT json_agg(json_build_object(
+                    'name', a.attname,
+                    'type', format_type(a.atttypid, a.atttypmod),
+                    'nullable', NOT a.attnotnull,
+                    'default', pg_get_expr(d.adbin, d.adrelid)
+                ) ORDER BY a.attnum)
+                FROM pg_attribute a
+                LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+                WHERE a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+            ),
+            'constraints', (
+                SELECT json_agg(json_build_object(
+                    'kind', con.contype,
+                    'columns', (
+                        SELECT json_agg(a.attname ORDER BY k.ord)
+                        FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+                        JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    ),
+                    'ref_schema', rn.nspname,
+                    'ref_table', rc.relname
+                ))
+                FROM pg_constraint con
+                LEFT JOIN pg_class rc ON rc.oid = con.confrelid
+                LEFT JOIN pg_namespace rn ON rn.oid = rc.relnamespace
+                WHERE con.conrelid = t.oid AND con.contype IN ('p', 'f', 'u')
+            ),
+            'unique_indexes', (
+                SELECT json_agg((
+                    SELECT json_agg(a.attname ORDER BY k.ord)
+                    FROM unnest(i.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+                ))
+                FROM pg_index i
+                WHERE i.indrelid = t.oid AND i.indisunique AND NOT i.indisprimary
+                  AND i.indexprs IS NULL
+                  AND NOT EXISTS (SELECT 1 FROM pg_constraint con WHERE con.conindid = i.indexrelid)
+            )
+        ) ORDER BY t.schema, t.name), '[]'::json)
+        FROM tables t
+    ),
+    'enums', (
+        SELECT coalesce(json_object_agg(e.name, e.labels), '{}'::json)
+        FROM (
+            SELECT t.typname AS name, json_agg(e.enumlabel ORDER BY e.enumsortorder) AS labels
+            FROM pg_type t
+            JOIN pg_enum e ON e.enumtypid = t.oid
+            GROUP BY t.typname
+        ) e
+    )
+)
+"#;
+
+/// Read the tables and enum types of the database at `url`.
+pub fn introspect(url: &str) -> Result<Database> {
+    let output = Command::new("psql")
+        .args([url, "--no-psqlrc", "--tuples-only", "--no-align", "--quiet", "--command", CATALOG_QUERY])
+        .output()
+        .map_err(|e| Failure::Command.error(format!("Failed to run psql (is the Postgres client installed?): {}", e)))?;
+    if !output.status.success() {
+        return Err(Failure::Command.error(format!(
+            "Failed to read the database schema: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    from_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Deserialize)]
+struct Catalog {
+    tables: Vec<CatalogTable>,
+    enums: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct CatalogTable {
+    schema: String,
+    name: String,
+    columns: Option<Vec<CatalogColumn>>,
+    constraints: Option<Vec<CatalogConstraint>>,
+    unique_indexes: Option<Vec<Option<Vec<String>>>>,
+}
+
+#[derive(Deserialize)]
+struct CatalogColumn {
+    name: String,
+    #[serde(rename = "type")]
+    data_type: String,
+    nullable: bool,
+    default: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CatalogConstraint {
+    /// `p`rimary key, `f`oreign key or `u`nique.
+    kind: String,
+    columns: Option<Vec<String>>,
+    ref_schema: Option<String>,
+    ref_table: Option<String>,
+}
+
+/// Build a [`Database`] from the JSON document [`CATALOG_QUERY`] returns.
+fn from_json(json: &str) -> Result<Database> {
+    let catalog: Catalog = serde_json::from_str(json.trim()).context("Unexpected output from the catalog query")?;
+    let tables = catalog
+        .tables
+        .into_iter()
+        .map(|t| {
+            let mut table = Table {
+                schema: Some(t.schema),
+                name: t.name,
+                columns: t
+                    .columns
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|c| Column {
+                        name: c.name,
+                        data_type: c.data_type.to_lowercase(),
+                        nullable: c.nullable,
+                        default: c.default,
+                    })
+                    .collect(),
+                ..Table::default()
+            };
+            for con in t.constraints.unwrap_or_default() {
+                let columns = con.columns.unwrap_or_default();
+                match (con.kind.as_str(), con.ref_table) {
+                    ("p", _) => table.primary_key = columns,
+                    ("u", _) => table.unique.push(columns),
+                    ("f", Some(ref_table)) => table.foreign_keys.push(ForeignKey {
+                        columns,
+                        schema: con.ref_schema,
+                        table: ref_table,
+                    }),
+                    _ => {}
+                }
+            }
+            table.unique.extend(t.unique_indexes.unwrap_or_default().into_iter().flatten());
+            table
+        })
+        .collect();
+    Ok(Database {
+        tables,
+        enums: catalog.enums,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_tables_from_catalog_json() {
+        let json = r#"{
+            "tables": [
+                {
+                    "schema": "public",
+                    "name": "posts",
+                    "columns": [
+                        {"name": "id", "type": "uuid", "nullable": false, "default": "gen_random_uuid()"},
+                        {"name": "slug", "type": "character varying(100)", "nullable": false, "default": null},
+                        {"name": "status", "type": "post_status", "nullable": true, "default": "'draft'::post_status"},
+                        {"name": "author_id", "type": "uuid", "nullable": false, "default": null}
+                    ],
+                    "constraints": [
+                        {"kind": "p", "columns": ["id"], "ref_schema": null, "ref_table": null},
+                        {"kind": "f", "columns": ["author_id"], "ref_schema": "public", "ref_table": "users"},
+                        {"kind": "u", "columns": ["slug"], "ref_schema": null, "ref_table": null}
+                    ],
+                    "unique_indexes": [["author_id", "slug"]]
+                },
+                {"schema": "public", "name": "empty", "columns": null, "constraints": null, "unique_indexes": null}
+            ],
+            "enums": {"post_status": ["draft", "published"]}
+        }"#;
+        let db = from_json(json).unwrap();
+        assert_eq!(db.enums["post_status"], vec!["draft", "published"]);
+        let posts = &db.tables[0];
+        assert_eq!(posts.schema.as_deref(), Some("public"));
+        assert_eq!(posts.primary_key, vec!["id"]);
+        assert_eq!(posts.columns[1].data_type, "character varying(100)");
+        assert!(posts.columns[2].nullable);
+        assert_eq!(posts.foreign_keys[0].table, "users");
+        assert_eq!(posts.unique, vec![vec!["slug"], vec!["author_id", "slug"]]);
+        assert!(db.tables[1].columns.is_empty());
+    }
+
+    #[test]
+    fn rejects_unexpected_output() {
+        assert!(from_json("psql: error").is_err());
+    }
+}
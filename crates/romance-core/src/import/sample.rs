@@ -0,0 +1,297 @@
+//! Infer entities from sample JSON payloads, e.g. an example API response.
+//!
+//! The root object (or each object of a root array, for several samples)
+//! becomes the entity. Strings are checked for uuids, dates, timestamps and
+//! decimals; keys missing from some samples or `null` in any become optional
+//! fields. Arrays of objects become `has_many` child entities, named after
+//! the parent (`items` of `Order` → `OrderItem`) with a foreign key back to
+//! it. Nested objects become `json` fields, or with `nested_entities` entities
+//! of their own that the parent belongs to.
+
+use super::{parents_first, Import};
+use crate::entity::{EntityDefinition, FieldDefinition, FieldType, FieldVisibility, RelationDefinition, RelationType};
+use crate::error::Failure;
+use crate::generator::recover::IMPLICIT_COLUMNS;
+use crate::project::Project;
+use crate::relation;
+use crate::utils;
+use anyhow::Result;
+use heck::{ToPascalCase, ToSnakeCase};
+use serde_json::{Map, Value};
+
+/// Infer entity `name` from `sample`: an object, or an array of objects.
+pub fn infer(project: &Project, name: &str, sample: &Value, nested_entities: bool) -> Result<Import> {
+    let samples: Vec<&Map<String, Value>> = match sample {
+        Value::Object(object) => vec![object],
+        Value::Array(items) => items.iter().filter_map(Value::as_object).collect(),
+        _ => Vec::new(),
+    };
+    if samples.is_empty() {
+        return Err(Failure::InvalidInput.error("Expected a JSON object, or an array of objects, to infer fields from"));
+    }
+
+    let mut inference = Inference {
+        project,
+        nested_entities,
+        entities: Vec::new(),
+        warnings: Vec::new(),
+    };
+    inference.entity(name, &samples, None);
+    Ok(Import {
+        entities: parents_first(inference.entities),
+        warnings: inference.warnings,
+        ..Import::default()
+    })
+}
+
+struct Inference<'a> {
+    project: &'a Project,
+    nested_entities: bool,
+    entities: Vec<EntityDefinition>,
+    warnings: Vec<String>,
+}
+
+impl Inference<'_> {
+    /// Infer entity `name`, and the entities nested in it, from `samples`.
+    /// A child of `parent` gets a foreign key to it.
+    fn entity(&mut self, name: &str, samples: &[&Map<String, Value>], parent: Option<&str>) {
+        let mut fields = Vec::new();
+        let mut relations = Vec::new();
+        if let Some(parent) = parent {
+            let fk = format!("{}_id", parent.to_snake_case());
+            relations.push(belongs_to(&fk, parent, false));
+            fields.push(field(fk, FieldType::Uuid, false, Some(parent.to_string())));
+        }
+
+        let mut keys: Vec<&String> = Vec::new();
+        for sample in samples {
+            keys.extend(sample.keys().filter(|k| !keys.contains(k)).collect::<Vec<_>>());
+        }
+        for key in keys {
+            let field_name = key.to_snake_case();
+            if IMPLICIT_COLUMNS.contains(&field_name.as_str()) || fields.iter().any(|f| f.name == field_name) {
+                continue;
+            }
+            let values: Vec<&Value> = samples.iter().filter_map(|s| s.get(key)).filter(|v| !v.is_null()).collect();
+            let optional = values.len() < samples.len();
+            let note = |what: &str| format!("{}.{}: {}", name, key, what);
+
+            if values.is_empty() {
+                self.warnings.push(note("only null values, assumed string"));
+                fields.push(field(field_name, FieldType::String, true, None));
+                continue;
+            }
+
+            // Nested objects
+            if values.iter().all(|v| v.is_object()) {
+                if !self.nested_entities {
+                    fields.push(field(field_name, FieldType::Json, optional, None));
+                    continue;
+                }
+                let base = field_name.trim_end_matches("_id");
+                let target = base.to_pascal_case();
+                if !relation::entity_exists(self.project, &target) && !self.planned(&target) {
+                    let objects: Vec<&Map<String, Value>> = values.iter().filter_map(|v| v.as_object()).collect();
+                    self.entity(&target, &objects, None);
+                }
+                let fk = format!("{}_id", base);
+                relations.push(belongs_to(&fk, &target, optional));
+                fields.push(field(fk, FieldType::Uuid, optional, Some(target)));
+                continue;
+            }
+
+            // Arrays of objects are children; other arrays are stored as json
+            if values.iter().all(|v| v.is_array()) {
+                let items: Vec<&Value> = values.iter().flat_map(|v| v.as_array().into_iter().flatten()).collect();
+                if items.is_empty() {
+                    self.warnings.push(note("only empty arrays, stored as json"));
+                } else if items.iter().all(|v| v.is_object()) {
+                    let child = format!("{}{}", name, utils::singularize(&field_name).to_pascal_case());
+                    if relation::entity_exists(self.project, &child) || self.planned(&child) {
+                        self.warnings.push(note(&format!("entity '{}' already exists, stored as json", child)));
+                    } else {
+                        let objects: Vec<&Map<String, Value>> = items.iter().filter_map(|v| v.as_object()).collect();
+                        self.entity(&child, &objects, Some(name));
+                        relations.push(RelationDefinition {
+                            name: field_name,
+                            relation_type: RelationType::HasMany,
+                            target_entity: child,
+                            fk_column: None,
+                            optional: false,
+                        });
+                        continue;
+                    }
+                }
+                fields.push(field(field_name, FieldType::Json, optional, None));
+                continue;
+            }
+
+            let types: Option<Vec<FieldType>> = values.iter().map(|v| scalar_type(v)).collect();
+            let field_type = types.and_then(|types| types.into_iter().map(Some).reduce(|a, b| merge(a?, b?)).flatten());
+            let Some(field_type) = field_type else {
+                self.warnings.push(note("mixed value types, stored as json"));
+                fields.push(field(field_name, FieldType::Json, optional, None));
+                continue;
+            };
+
+            // `<entity>_id` uuids reference existing entities
+            let target = field_name.strip_suffix("_id").map(|t| t.to_pascal_case());
+            match target {
+                Some(target)
+                    if matches!(field_type, FieldType::Uuid)
+                        && (relation::entity_exists(self.project, &target) || self.planned(&target)) =>
+                {
+                    relations.push(belongs_to(&field_name, &target, optional));
+                    fields.push(field(field_name, field_type, optional, Some(target)));
+                }
+                _ => fields.push(field(field_name, field_type, optional, None)),
+            }
+        }
+
+        self.entities.push(EntityDefinition {
+            name: name.to_string(),
+            fields,
+            relations,
+            module: None,
+            table_name: None,
+        });
+    }
+
+    fn planned(&self, name: &str) -> bool {
+        self.entities.iter().any(|e| e.name == name)
+    }
+}
+
+fn belongs_to(fk: &str, target: &str, optional: bool) -> RelationDefinition {
+    RelationDefinition {
+        name: fk.to_string(),
+        relation_type: RelationType::BelongsTo,
+        target_entity: target.to_string(),
+        fk_column: Some(fk.to_string()),
+        optional,
+    }
+}
+
+fn field(name: String, field_type: FieldType, optional: bool, relation: Option<String>) -> FieldDefinition {
+    FieldDefinition {
+        name,
+        field_type,
+        optional,
+        default: None,
+        relation,
+        validations: Vec::new(),
+        searchable: false,
+        visibility: FieldVisibility::default(),
+    }
+}
+
+/// The field type a scalar value suggests; `None` for objects and arrays.
+fn scalar_type(value: &Value) -> Option<FieldType> {
+    Some(match value {
+        Value::Bool(_) => FieldType::Bool,
+        Value::Number(n) => match n.as_i64() {
+            Some(i) if i32::try_from(i).is_ok() => FieldType::Int32,
+            Some(_) => FieldType::Int64,
+            None if n.is_u64() => FieldType::Int64,
+            None => FieldType::Float64,
+        },
+        Value::String(s) => string_type(s),
+        _ => return None,
+    })
+}
+
+fn string_type(s: &str) -> FieldType {
+    if uuid::Uuid::try_parse(s).is_ok() && s.len() == 36 {
+        FieldType::Uuid
+    } else if chrono::DateTime::parse_from_rfc3339(s).is_ok() {
+        FieldType::DateTime
+    } else if chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() {
+        FieldType::Date
+    } else if is_decimal(s) {
+        FieldType::Decimal
+    } else if s.chars().count() > 255 {
+        FieldType::Text
+    } else {
+        FieldType::String
+    }
+}
+
+/// `12.50`, `-0.5`: a number kept as a string so it stays exact.
+fn is_decimal(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    match digits.split_once('.') {
+        Some((whole, fraction)) => {
+            !whole.is_empty()
+                && !fraction.is_empty()
+                && whole.bytes().all(|b| b.is_ascii_digit())
+                && fraction.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// The type that holds values of both `a` and `b`, if any.
+fn merge(a: FieldType, b: FieldType) -> Option<FieldType> {
+    use FieldType::*;
+    Some(match (a, b) {
+        (Int32, Int32) => Int32,
+        (Int32 | Int64, Int32 | Int64) => Int64,
+        (Int32 | Int64 | Float64, Int32 | Int64 | Float64) => Float64,
+        (Date, Date) => Date,
+        (Date | DateTime, Date | DateTime) => DateTime,
+        (Bool, Bool) => Bool,
+        (Uuid, Uuid) => Uuid,
+        (Decimal, Decimal) => Decimal,
+        // Strings that matched a pattern only sometimes are plain strings
+        (a @ (String | Text | Uuid | Date | DateTime | Decimal), b @ (String | Text | Uuid | Date | DateTime | Decimal)) => {
+            if matches!(a, Text) || matches!(b, Text) {
+                Text
+            } else {
+                String
+            }
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_string_patterns() {
+        let cases = [
+            ("7f1c2a3e-9d4b-4c1a-8e2f-0a1b2c3d4e5f", "Uuid"),
+            ("2024-05-01T10:30:00Z", "DateTime"),
+            ("2024-05-01T10:30:00.123+02:00", "DateTime"),
+            ("2024-05-01", "Date"),
+            ("19.99", "Decimal"),
+            ("-0.5", "Decimal"),
+            ("1.", "String"),
+            ("Alice", "String"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format!("{:?}", string_type(value)), expected, "{}", value);
+        }
+        assert!(matches!(string_type(&"x".repeat(300)), FieldType::Text));
+    }
+
+    #[test]
+    fn sizes_numbers() {
+        let ty = |v: Value| format!("{:?}", scalar_type(&v).unwrap());
+        assert_eq!(ty(serde_json::json!(42)), "Int32");
+        assert_eq!(ty(serde_json::json!(5_000_000_000_i64)), "Int64");
+        assert_eq!(ty(serde_json::json!(1.5)), "Float64");
+        assert_eq!(ty(serde_json::json!(true)), "Bool");
+    }
+
+    #[test]
+    fn merges_types_across_samples() {
+        let merged = |a, b| merge(a, b).map(|t| format!("{:?}", t));
+        assert_eq!(merged(FieldType::Int32, FieldType::Int64).as_deref(), Some("Int64"));
+        assert_eq!(merged(FieldType::Int32, FieldType::Float64).as_deref(), Some("Float64"));
+        assert_eq!(merged(FieldType::Date, FieldType::DateTime).as_deref(), Some("DateTime"));
+        assert_eq!(merged(FieldType::Uuid, FieldType::String).as_deref(), Some("String"));
+        assert_eq!(merged(FieldType::Bool, FieldType::String), None);
+    }
+}
@@ -0,0 +1,525 @@
+//! Read tables out of SQL DDL, e.g. a `pg_dump --schema-only` file.
+//!
+//! Only the statements that describe the shape of the data are understood:
+//! `CREATE TABLE`, `CREATE TYPE ... AS ENUM`, `CREATE UNIQUE INDEX` and the
+//! `ALTER TABLE ... ADD CONSTRAINT` / `ALTER COLUMN ... SET DEFAULT`
+//! statements pg_dump emits for keys and defaults. Everything else
+//! (functions, triggers, other indexes, grants, `SET` statements) is skipped.
+
+use super::{Column, Database, ForeignKey, Table};
+use crate::error::Failure;
+use anyhow::Result;
+
+/// Parse the tables and enum types declared in `sql`.
+pub fn parse(sql: &str) -> Result<Database> {
+    let tokens = tokenize(sql)?;
+    let mut db = Database::default();
+    for statement in tokens.split(|t| t.kind == Kind::Punct(';')) {
+        let mut p = Parser { sql, tokens: statement, pos: 0 };
+        if p.eat_keywords(&["create"]) {
+            p.eat_keywords(&["or", "replace"]);
+            while p.eat_any(&["unlogged", "temporary", "temp", "global", "local"]) {}
+            if p.eat_keywords(&["unique", "index"]) {
+                p.create_unique_index(&mut db);
+            } else if p.eat_keywords(&["table"]) {
+                if let Some(table) = p.create_table() {
+                    db.tables.push(table);
+                }
+            } else if p.eat_keywords(&["type"]) {
+                if let Some((name, variants)) = p.create_enum() {
+                    db.enums.insert(name, variants);
+                }
+            }
+        } else if p.eat_keywords(&["alter", "table"]) {
+            p.alter_table(&mut db);
+        }
+    }
+    Ok(db)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    /// A keyword or unquoted identifier, lowercased.
+    Word(String),
+    /// A `"quoted"` identifier.
+    Quoted(String),
+    /// A `'string'` literal.
+    Str(String),
+    /// A number, `$1`-style parameter or dollar-quoted body.
+    Other,
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if sql[i..].starts_with("/*") {
+            i = sql[i + 2..].find("*/").map_or(bytes.len(), |n| i + n + 4);
+        } else if c == b'\'' || c == b'"' {
+            // Quotes are escaped by doubling them
+            let mut value = String::new();
+            i += 1;
+            loop {
+                let Some(n) = sql[i..].find(c as char) else {
+                    return Err(Failure::InvalidInput.error(format!(
+                        "Unterminated {} starting at byte {}",
+                        if c == b'\'' { "string" } else { "quoted identifier" },
+                        start
+                    )));
+                };
+                value.push_str(&sql[i..i + n]);
+                i += n + 1;
+                if bytes.get(i) == Some(&c) {
+                    value.push(c as char);
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let kind = if c == b'\'' { Kind::Str(value) } else { Kind::Quoted(value) };
+            tokens.push(Token { kind, start, end: i });
+        } else if c == b'$' {
+            // Dollar-quoted function bodies (`$$ ... $$`, `$tag$ ... $tag$`)
+            // hold semicolons, so they have to be skipped as a whole
+            let tag_len = sql[i + 1..]
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                .unwrap_or(bytes.len() - i - 1);
+            if bytes.get(i + 1 + tag_len) == Some(&b'$') && !sql[i + 1..].starts_with(|ch: char| ch.is_ascii_digit()) {
+                let tag = &sql[i..i + tag_len + 2];
+                let body = i + tag.len();
+                i = sql[body..].find(tag).map_or(bytes.len(), |n| body + n + tag.len());
+            } else {
+                i += 1 + tag_len;
+            }
+            tokens.push(Token { kind: Kind::Other, start, end: i });
+        } else if c.is_ascii_alphabetic() || c == b'_' || !c.is_ascii() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$' || !bytes[i].is_ascii()) {
+                i += 1;
+            }
+            tokens.push(Token { kind: Kind::Word(sql[start..i].to_lowercase()), start, end: i });
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            tokens.push(Token { kind: Kind::Other, start, end: i });
+        } else {
+            i += 1;
+            tokens.push(Token { kind: Kind::Punct(c as char), start, end: i });
+        }
+    }
+    Ok(tokens)
+}
+
+/// Keywords that end a column's type and start its constraints.
+const COLUMN_CONSTRAINTS: &[&str] = &[
+    "not", "null", "default", "primary", "references", "unique", "check", "constraint", "collate",
+    "generated",
+];
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Kind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Kind::Word(w)) if w == word)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Kind::Punct(c))
+    }
+
+    /// Consume `words` if the next tokens are exactly those keywords.
+    fn eat_keywords(&mut self, words: &[&str]) -> bool {
+        let matches = words.iter().enumerate().all(|(i, word)| {
+            matches!(self.tokens.get(self.pos + i).map(|t| &t.kind), Some(Kind::Word(w)) if w == word)
+        });
+        if matches {
+            self.pos += words.len();
+        }
+        matches
+    }
+
+    fn eat_any(&mut self, words: &[&str]) -> bool {
+        let found = words.iter().any(|w| self.is_word(w));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let found = self.is_punct(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        let name = match self.peek()? {
+            Kind::Word(w) => w.clone(),
+            Kind::Quoted(q) => q.clone(),
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(name)
+    }
+
+    /// A possibly schema-qualified name, as `(schema, name)`.
+    fn qualified_name(&mut self) -> Option<(Option<String>, String)> {
+        let mut parts = vec![self.ident()?];
+        while self.eat_punct('.') {
+            parts.push(self.ident()?);
+        }
+        let name = parts.pop()?;
+        Some((parts.pop(), name))
+    }
+
+    /// `(a, b, c)`
+    fn ident_list(&mut self) -> Option<Vec<String>> {
+        if !self.eat_punct('(') {
+            return None;
+        }
+        let mut names = Vec::new();
+        loop {
+            names.push(self.ident()?);
+            if self.eat_punct(')') {
+                return Some(names);
+            }
+            if !self.eat_punct(',') {
+                return None;
+            }
+        }
+    }
+
+    /// Skip one token, or a whole parenthesized group.
+    fn skip(&mut self) {
+        let mut depth = 0usize;
+        while let Some(kind) = self.peek() {
+            self.pos += 1;
+            match kind {
+                Kind::Punct('(') | Kind::Punct('[') => depth += 1,
+                Kind::Punct(')') | Kind::Punct(']') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Skip to the next `,` or `)` at this nesting level.
+    fn skip_element(&mut self) {
+        while self.peek().is_some() && !self.is_punct(',') && !self.is_punct(')') {
+            self.skip();
+        }
+    }
+
+    /// Skip tokens until one of `words` (or `,` / `)`) and return the source
+    /// text skipped over.
+    fn text_until(&mut self, words: &[&str]) -> String {
+        let start = self.pos;
+        while let Some(kind) = self.peek() {
+            match kind {
+                Kind::Punct(',') | Kind::Punct(')') => break,
+                Kind::Word(w) if words.contains(&w.as_str()) => break,
+                _ => self.skip(),
+            }
+        }
+        if self.pos == start {
+            return String::new();
+        }
+        let text = &self.sql[self.tokens[start].start..self.tokens[self.pos - 1].end];
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn create_table(&mut self) -> Option<Table> {
+        self.eat_keywords(&["if", "not", "exists"]);
+        let (schema, name) = self.qualified_name()?;
+        // `CREATE TABLE ... PARTITION OF` and `AS SELECT` have no column list
+        if !self.eat_punct('(') {
+            return None;
+        }
+        let mut table = Table { schema, name, ..Table::default() };
+        while !self.eat_punct(')') {
+            self.table_element(&mut table);
+            if !self.eat_punct(',') && !self.is_punct(')') {
+                return None;
+            }
+        }
+        Some(table)
+    }
+
+    fn table_element(&mut self, table: &mut Table) {
+        if self.eat_keywords(&["constraint"]) {
+            self.ident();
+            self.table_constraint(table);
+        } else if self.is_word("primary") || self.is_word("foreign") || self.is_word("unique") {
+            self.table_constraint(table);
+        } else if self.eat_any(&["check", "exclude", "like"]) {
+            self.skip_element();
+        } else if let Some(name) = self.ident() {
+            self.column(table, name);
+        } else {
+            self.skip_element();
+        }
+    }
+
+    fn table_constraint(&mut self, table: &mut Table) {
+        if self.eat_keywords(&["primary", "key"]) {
+            if let Some(columns) = self.ident_list() {
+                table.primary_key = columns;
+            }
+        } else if self.eat_keywords(&["unique"]) {
+            self.eat_keywords(&["nulls", "not", "distinct"]);
+            if let Some(columns) = self.ident_list() {
+                table.unique.push(columns);
+            }
+        } else if self.eat_keywords(&["foreign", "key"]) {
+            if let Some(columns) = self.ident_list() {
+                if self.eat_keywords(&["references"]) {
+                    if let Some(fk) = self.references(columns) {
+                        table.foreign_keys.push(fk);
+                    }
+                }
+            }
+        }
+        self.skip_element();
+    }
+
+    /// `REFERENCES table [(columns)]`, after the keyword.
+    fn references(&mut self, columns: Vec<String>) -> Option<ForeignKey> {
+        let (schema, name) = self.qualified_name()?;
+        if self.is_punct('(') {
+            self.ident_list();
+        }
+        Some(ForeignKey { columns, schema, table: name })
+    }
+
+    fn column(&mut self, table: &mut Table, name: String) {
+        let data_type = self.text_until(COLUMN_CONSTRAINTS).to_lowercase();
+        let mut column = Column { name, data_type, nullable: true, default: None };
+        while self.peek().is_some() && !self.is_punct(',') && !self.is_punct(')') {
+            if self.eat_keywords(&["not", "null"]) {
+                column.nullable = false;
+            } else if self.eat_keywords(&["primary", "key"]) {
+                column.nullable = false;
+                table.primary_key = vec![column.name.clone()];
+            } else if self.eat_keywords(&["default"]) {
+                let default = self.text_until(COLUMN_CONSTRAINTS);
+                // `DEFAULT NULL` stops right at `null`
+                column.default = (!default.is_empty()).then_some(default);
+            } else if self.eat_keywords(&["references"]) {
+                if let Some(fk) = self.references(vec![column.name.clone()]) {
+                    table.foreign_keys.push(fk);
+                }
+            } else if self.eat_keywords(&["unique"]) {
+                table.unique.push(vec![column.name.clone()]);
+            } else if self.eat_keywords(&["constraint"]) {
+                self.ident();
+            } else {
+                // NULL, UNIQUE, CHECK (...), COLLATE x, GENERATED ..., ON DELETE ...
+                self.skip();
+            }
+        }
+        table.columns.push(column);
+    }
+
+    fn create_enum(&mut self) -> Option<(String, Vec<String>)> {
+        let (_, name) = self.qualified_name()?;
+        if !self.eat_keywords(&["as", "enum"]) || !self.eat_punct('(') {
+            return None;
+        }
+        let mut variants = Vec::new();
+        while let Some(Kind::Str(value)) = self.peek() {
+            variants.push(value.clone());
+            self.pos += 1;
+            self.eat_punct(',');
+        }
+        Some((name, variants))
+    }
+
+    /// `CREATE UNIQUE INDEX name ON table (columns)`, after `UNIQUE INDEX`.
+    /// Indexes on expressions are skipped.
+    fn create_unique_index(&mut self, db: &mut Database) {
+        self.eat_keywords(&["concurrently"]);
+        self.eat_keywords(&["if", "not", "exists"]);
+        if !self.is_word("on") {
+            self.ident();
+        }
+        if !self.eat_keywords(&["on"]) {
+            return;
+        }
+        self.eat_keywords(&["only"]);
+        let Some((schema, name)) = self.qualified_name() else { return };
+        if self.eat_keywords(&["using"]) {
+            self.ident();
+        }
+        let Some(columns) = self.ident_list() else { return };
+        if let Some(table) = db.find_table(schema.as_deref(), &name) {
+            table.unique.push(columns);
+        }
+    }
+
+    fn alter_table(&mut self, db: &mut Database) {
+        self.eat_keywords(&["if", "exists"]);
+        self.eat_keywords(&["only"]);
+        let Some((schema, name)) = self.qualified_name() else { return };
+        let Some(table) = db.find_table(schema.as_deref(), &name) else {
+            return;
+        };
+        while self.peek().is_some() {
+            if self.eat_keywords(&["add"]) {
+                if self.eat_keywords(&["constraint"]) {
+                    self.ident();
+                }
+                self.table_constraint(table);
+            } else if self.eat_keywords(&["alter"]) {
+                self.eat_keywords(&["column"]);
+                let column = self.ident();
+                if self.eat_keywords(&["set", "default"]) {
+                    let default = self.text_until(&[]);
+                    if let Some(column) = table.columns.iter_mut().find(|c| Some(&c.name) == column.as_ref()) {
+                        column.default = Some(default);
+                    }
+                } else if self.eat_keywords(&["set", "not", "null"]) {
+                    if let Some(column) = table.columns.iter_mut().find(|c| Some(&c.name) == column.as_ref()) {
+                        column.nullable = false;
+                    }
+                }
+                self.skip_element();
+            } else {
+                self.skip_element();
+            }
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"
+--
+-- PostgreSQL database dump
+--
+SET statement_timeout = 0;
+SELECT pg_catalog.set_config('search_path', '', false);
+
+CREATE TYPE public.post_status AS ENUM (
+    'draft',
+    'published'
+);
+
+CREATE FUNCTION public.touch() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    NEW.updated_at = now();
+    RETURN NEW;
+END;
+$$;
+
+CREATE TABLE public.posts (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    title character varying(200) NOT NULL,
+    "body" text,
+    status public.post_status DEFAULT 'draft'::public.post_status NOT NULL,
+    author_id uuid NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT title_not_empty CHECK ((char_length((title)::text) > 0))
+);
+
+CREATE TABLE public.users (
+    id uuid PRIMARY KEY,
+    email text NOT NULL UNIQUE,
+    handle text,
+    team_id uuid REFERENCES teams (id) ON DELETE SET NULL
+);
+
+ALTER TABLE ONLY public.posts
+    ADD CONSTRAINT posts_pkey PRIMARY KEY (id);
+
+CREATE UNIQUE INDEX users_handle_key ON public.users USING btree (handle);
+CREATE INDEX posts_title_idx ON public.posts USING btree (title);
+
+ALTER TABLE ONLY public.posts
+    ADD CONSTRAINT posts_author_id_fkey FOREIGN KEY (author_id) REFERENCES public.users(id) ON DELETE CASCADE;
+"#;
+
+    #[test]
+    fn parses_tables_columns_and_enums() {
+        let db = parse(DUMP).unwrap();
+        assert_eq!(db.enums["post_status"], vec!["draft", "published"]);
+        assert_eq!(db.tables.len(), 2);
+
+        let posts = &db.tables[0];
+        assert_eq!(posts.schema.as_deref(), Some("public"));
+        assert_eq!(posts.name, "posts");
+        let columns: Vec<(&str, &str, bool)> = posts
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.as_str(), c.nullable))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("id", "uuid", false),
+                ("title", "character varying(200)", false),
+                ("body", "text", true),
+                ("status", "public.post_status", false),
+                ("author_id", "uuid", false),
+                ("created_at", "timestamp with time zone", false),
+            ]
+        );
+        assert_eq!(posts.columns[3].default.as_deref(), Some("'draft'::public.post_status"));
+    }
+
+    #[test]
+    fn collects_keys_from_alter_table_and_inline_constraints() {
+        let db = parse(DUMP).unwrap();
+        let posts = &db.tables[0];
+        assert_eq!(posts.primary_key, vec!["id"]);
+        assert_eq!(posts.foreign_keys.len(), 1);
+        assert_eq!(posts.foreign_keys[0].columns, vec!["author_id"]);
+        assert_eq!(posts.foreign_keys[0].table, "users");
+
+        let users = &db.tables[1];
+        assert_eq!(users.primary_key, vec!["id"]);
+        assert!(!users.columns[1].nullable);
+        assert_eq!(users.foreign_keys[0].table, "teams");
+        assert_eq!(users.foreign_keys[0].schema, None);
+        assert_eq!(users.unique, vec![vec!["email"], vec!["handle"]]);
+        assert!(posts.unique.is_empty());
+    }
+
+    #[test]
+    fn rejects_unterminated_strings() {
+        assert!(parse("CREATE TABLE t (a text DEFAULT 'oops);").is_err());
+    }
+}
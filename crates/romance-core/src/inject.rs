@@ -0,0 +1,1061 @@
+//! Syntax-aware code injection for generated Rust sources.
+//!
+//! Generators register modules, routes, OpenAPI entries and migrations by
+//! adding lines to files the user also edits. For the constructs below the
+//! file is parsed with `syn` and the line is placed relative to the code
+//! itself, so a reformatted file, a moved marker or a similar-looking line
+//! elsewhere no longer matters:
+//!
+//! - `MODS`, `MAIN_MODS`, `MIGRATION_MODS`: top-level `mod x;` items, after
+//!   the last one.
+//! - `ROUTES`, `MIDDLEWARE`: the method-call chain in `create_router`, ahead
+//!   of the first `.layer(...)`/`.with_state(...)` (routes) or
+//!   `.with_state(...)` (middleware).
+//! - `OPENAPI_PATHS`, `OPENAPI_SCHEMAS`, `OPENAPI_TAGS`: the lists inside the
+//!   `#[openapi(...)]` attribute.
+//! - `MIGRATIONS`: the `vec![...]` returned by `migrations()`.
+//!
+//! Any other marker, a file that does not parse, or a file without the
+//! construct falls back to [`utils::insert_at_marker`].
+
+use crate::fs::ProjectFs;
+use crate::generator::context::markers;
+use crate::utils;
+use anyhow::Result;
+use proc_macro2::{LineColumn, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::path::Path;
+use syn::visit::Visit;
+
+/// Insert `line` into the Rust file at `path`.
+///
+/// Idempotent: a structurally identical entry that is already present is left
+/// alone, whatever its formatting.
+pub fn insert(fs: &dyn ProjectFs, path: &Path, marker: &str, line: &str) -> Result<()> {
+    if let Some(construct) = Construct::for_marker(path, marker) {
+        let source = fs.read_to_string(path)?;
+        if let Some(updated) = construct.insert(&source, line) {
+            if updated != source {
+                fs.write(path, &updated)?;
+            }
+            return Ok(());
+        }
+    }
+    utils::insert_at_marker(fs, path, marker, line)
+}
+
+/// Remove an entry previously added with [`insert`] using the same marker
+/// and line. Returns `true` if the file changed.
+///
+/// Entries are matched structurally (`pub mod post;` removes `mod post;` but
+/// never `mod post_tag;`). When the file cannot be parsed, only lines equal
+/// to `line` (ignoring surrounding whitespace) are removed.
+pub fn remove(fs: &dyn ProjectFs, path: &Path, marker: &str, line: &str) -> Result<bool> {
+    if !fs.exists(path) {
+        return Ok(false);
+    }
+    let source = fs.read_to_string(path)?;
+    let mut updated = Construct::for_marker(path, marker)
+        .and_then(|construct| construct.remove(&source, line))
+        .unwrap_or_else(|| source.clone());
+    if updated == source {
+        updated = remove_exact_line(&source, line);
+    }
+    if updated == source {
+        return Ok(false);
+    }
+    fs.write(path, &updated)?;
+    Ok(true)
+}
+
+/// The code construct a marker stands for.
+#[derive(Debug, Clone, Copy)]
+enum Construct {
+    /// Top-level `mod x;` declarations.
+    Mod,
+    /// The method-call chain in `create_router`; new calls go after the
+    /// last call preceding the first one named in `before`.
+    RouterChain { before: &'static [&'static str] },
+    /// A list nested inside the `#[openapi(...)]` attribute.
+    OpenApi(&'static [&'static str]),
+    /// The `vec![...]` inside the named function.
+    Vec(&'static str),
+}
+
+impl Construct {
+    fn for_marker(path: &Path, marker: &str) -> Option<Self> {
+        if path.extension().and_then(|e| e.to_str()) != Some("rs") {
+            return None;
+        }
+        match marker {
+            markers::MODS | markers::MAIN_MODS | markers::MIGRATION_MODS => Some(Self::Mod),
+            markers::ROUTES => Some(Self::RouterChain {
+                before: &["layer", "with_state"],
+            }),
+            markers::MIDDLEWARE => Some(Self::RouterChain {
+                before: &["with_state"],
+            }),
+            markers::OPENAPI_PATHS => Some(Self::OpenApi(&["paths"])),
+            markers::OPENAPI_SCHEMAS => Some(Self::OpenApi(&["components", "schemas"])),
+            markers::OPENAPI_TAGS => Some(Self::OpenApi(&["tags"])),
+            markers::MIGRATIONS => Some(Self::Vec("migrations")),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` when the construct can't be found, so the caller falls
+    /// back to the marker.
+    fn insert(self, source: &str, line: &str) -> Option<String> {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        match self {
+            Self::Mod => insert_mod(&src, &file, line),
+            Self::RouterChain { before } => insert_chain_call(&src, &file, before, line),
+            Self::OpenApi(path) => {
+                let (inner, close) = find_openapi_list(&file, path)?;
+                insert_list_entry(&src, inner, close, line)
+            }
+            Self::Vec(func) => {
+                let (inner, close) = find_vec_macro(&file, func)?;
+                insert_list_entry(&src, inner, close, line)
+            }
+        }
+    }
+
+    fn remove(self, source: &str, line: &str) -> Option<String> {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        match self {
+            Self::Mod => remove_mod(&src, &file, line),
+            Self::RouterChain { .. } => remove_chain_call(&src, &file, line),
+            Self::OpenApi(_) | Self::Vec(_) => {
+                let wanted = normalize(entry_text(line).parse().ok()?);
+                self.remove_entries(source, &|entry| entry == wanted)
+            }
+        }
+    }
+
+    /// Remove every list entry whose whitespace-free text satisfies `pred`.
+    fn remove_entries(self, source: &str, pred: &dyn Fn(&str) -> bool) -> Option<String> {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        let (inner, _) = match self {
+            Self::OpenApi(path) => find_openapi_list(&file, path)?,
+            Self::Vec(func) => find_vec_macro(&file, func)?,
+            Self::Mod | Self::RouterChain { .. } => return None,
+        };
+        Some(remove_list_entries(&src, inner, pred))
+    }
+}
+
+/// Remove every entry of the list a marker stands for (`OPENAPI_*`,
+/// `MIGRATIONS`) whose whitespace-free text satisfies `pred`. Returns `true`
+/// if the file changed; files that can't be parsed are left alone.
+pub fn remove_entries(fs: &dyn ProjectFs, path: &Path, marker: &str, pred: impl Fn(&str) -> bool) -> Result<bool> {
+    let Some(construct) = Construct::for_marker(path, marker) else {
+        return Ok(false);
+    };
+    edit(fs, path, |source| construct.remove_entries(source, &pred))
+}
+
+/// Remove top-level items (functions, impls, ...) matching `pred`.
+pub fn remove_items(fs: &dyn ProjectFs, path: &Path, pred: impl Fn(&syn::Item) -> bool) -> Result<bool> {
+    edit(fs, path, |source| {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        let ranges = file
+            .items
+            .iter()
+            .filter(|item| pred(item))
+            .filter_map(|item| token_range(item.to_token_stream()))
+            .map(|(start, end)| (src.offset(start), src.offset(end)))
+            .collect();
+        Some(src.remove_ranges(ranges))
+    })
+}
+
+/// Remove statements, in any function body, matching `pred`.
+pub fn remove_stmts(fs: &dyn ProjectFs, path: &Path, pred: impl Fn(&syn::Stmt) -> bool) -> Result<bool> {
+    edit(fs, path, |source| {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        let mut visitor = Matches { pred: &pred, found: Vec::new() };
+        visitor.visit_file(&file);
+        let ranges = visitor
+            .found
+            .into_iter()
+            .map(|(start, end)| (src.offset(start), src.offset(end)))
+            .collect();
+        Some(src.remove_ranges(ranges))
+    })
+}
+
+/// Remove `.method(args)` calls matching `pred` from whatever chain they
+/// are part of.
+pub fn remove_method_calls(
+    fs: &dyn ProjectFs,
+    path: &Path,
+    pred: impl Fn(&syn::ExprMethodCall) -> bool,
+) -> Result<bool> {
+    edit(fs, path, |source| {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        let mut visitor = CallMatches { pred: &pred, found: Vec::new() };
+        visitor.visit_file(&file);
+        let ranges = visitor
+            .found
+            .into_iter()
+            .map(|(start, end)| (src.offset(start), src.offset(end)))
+            .collect();
+        Some(src.remove_ranges(ranges))
+    })
+}
+
+/// Remove enum variants of `enum_name` matching `pred`.
+pub fn remove_variants(
+    fs: &dyn ProjectFs,
+    path: &Path,
+    enum_name: &str,
+    pred: impl Fn(&syn::Variant) -> bool,
+) -> Result<bool> {
+    edit(fs, path, |source| {
+        let file = syn::parse_file(source).ok()?;
+        let src = Source::new(source);
+        let mut ranges = Vec::new();
+        for item in &file.items {
+            let syn::Item::Enum(item) = item else { continue };
+            if item.ident != enum_name {
+                continue;
+            }
+            for pair in item.variants.pairs() {
+                if !pred(pair.value()) {
+                    continue;
+                }
+                let Some((start, mut end)) = token_range(pair.value().to_token_stream()) else {
+                    continue;
+                };
+                if let Some(comma) = pair.punct() {
+                    end = comma.span.end();
+                }
+                ranges.push((src.offset(start), src.offset(end)));
+            }
+        }
+        Some(src.remove_ranges(ranges))
+    })
+}
+
+/// Whether the tokens mention `path` (e.g. `super::post::`) as a whole path:
+/// `super::post::` is not mentioned by `super::post_tag::`.
+pub fn mentions(tokens: &impl ToTokens, path: &str) -> bool {
+    text_mentions(&normalize(tokens.to_token_stream()), path)
+}
+
+/// [`mentions`] for plain source text.
+pub fn text_mentions(text: &str, path: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(path).any(|(i, _)| {
+        let before_ok = !text[..i].chars().next_back().is_some_and(is_ident);
+        let after_ok = !path.ends_with(is_ident)
+            || !text[i + path.len()..].chars().next().is_some_and(is_ident);
+        before_ok && after_ok
+    })
+}
+
+/// Apply `f` to the file's contents; `None` (unparsable) leaves it alone.
+fn edit(fs: &dyn ProjectFs, path: &Path, f: impl FnOnce(&str) -> Option<String>) -> Result<bool> {
+    if !fs.exists(path) {
+        return Ok(false);
+    }
+    let source = fs.read_to_string(path)?;
+    match f(&source) {
+        Some(updated) if updated != source => {
+            fs.write(path, &updated)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Collects the spans of statements matching a predicate, without
+/// descending into a match.
+struct Matches<'p, T: ?Sized> {
+    pred: &'p T,
+    found: Vec<(LineColumn, LineColumn)>,
+}
+
+/// Collects the `.method(args)` spans of method calls matching a predicate.
+struct CallMatches<'p, T: ?Sized> {
+    pred: &'p T,
+    found: Vec<(LineColumn, LineColumn)>,
+}
+
+impl<'ast, T: Fn(&syn::ExprMethodCall) -> bool + ?Sized> Visit<'ast> for CallMatches<'_, T> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if (self.pred)(node) {
+            self.found
+                .push((node.dot_token.span.start(), node.paren_token.span.close().end()));
+            self.visit_expr(&node.receiver);
+        } else {
+            syn::visit::visit_expr_method_call(self, node);
+        }
+    }
+}
+
+impl<'ast, T: Fn(&syn::Stmt) -> bool + ?Sized> Visit<'ast> for Matches<'_, T> {
+    fn visit_stmt(&mut self, node: &'ast syn::Stmt) {
+        if (self.pred)(node) {
+            self.found.extend(token_range(node.to_token_stream()));
+        } else {
+            syn::visit::visit_stmt(self, node);
+        }
+    }
+}
+
+// ── mod declarations ──────────────────────────────────────────────────
+
+fn insert_mod(src: &Source, file: &syn::File, line: &str) -> Option<String> {
+    let new: syn::ItemMod = syn::parse_str(line.trim()).ok()?;
+    if new.content.is_some() {
+        return None;
+    }
+    let mods: Vec<&syn::ItemMod> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Mod(m) if m.content.is_none() => Some(m),
+            _ => None,
+        })
+        .collect();
+    if mods.iter().any(|m| m.ident == new.ident) {
+        return Some(src.text.to_string());
+    }
+    let (start, end) = token_range(mods.last()?.to_token_stream())?;
+    let start = src.offset(start);
+    let end = src.offset(end);
+    Some(src.insert_after(start, end, line.trim()))
+}
+
+fn remove_mod(src: &Source, file: &syn::File, line: &str) -> Option<String> {
+    let target: syn::ItemMod = syn::parse_str(line.trim()).ok()?;
+    let found = file.items.iter().find_map(|item| match item {
+        syn::Item::Mod(m) if m.content.is_none() && m.ident == target.ident => Some(m),
+        _ => None,
+    });
+    let Some(found) = found else {
+        return Some(src.text.to_string());
+    };
+    let (start, end) = token_range(found.to_token_stream())?;
+    Some(src.remove_range(src.offset(start), src.offset(end)))
+}
+
+// ── router chain ──────────────────────────────────────────────────────
+
+/// Parse a single `.method(args)` line into its method name and arguments.
+fn parse_chain_call(line: &str) -> Option<syn::ExprMethodCall> {
+    let expr: syn::Expr = syn::parse_str(&format!("__router{}", line.trim())).ok()?;
+    match expr {
+        syn::Expr::MethodCall(call) if matches!(*call.receiver, syn::Expr::Path(_)) => Some(call),
+        _ => None,
+    }
+}
+
+fn same_call(a: &syn::ExprMethodCall, b: &syn::ExprMethodCall) -> bool {
+    a.method == b.method && normalize(a.args.to_token_stream()) == normalize(b.args.to_token_stream())
+}
+
+/// Collects every method call inside the body of one function.
+struct MethodCalls<'ast> {
+    func: &'static str,
+    inside: bool,
+    calls: Vec<&'ast syn::ExprMethodCall>,
+}
+
+impl<'ast> Visit<'ast> for MethodCalls<'ast> {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        let was_inside = self.inside;
+        self.inside = node.sig.ident == self.func;
+        syn::visit::visit_item_fn(self, node);
+        self.inside = was_inside;
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if self.inside {
+            self.calls.push(node);
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// Flatten `a.b().c().d()` into `[b, c, d]`.
+fn flatten_chain(outer: &syn::ExprMethodCall) -> Vec<&syn::ExprMethodCall> {
+    let mut chain = vec![outer];
+    let mut current = outer;
+    while let syn::Expr::MethodCall(inner) = &*current.receiver {
+        chain.push(inner);
+        current = inner;
+    }
+    chain.reverse();
+    chain
+}
+
+/// The longest method-call chain in `create_router` that registers routes.
+fn router_chain(file: &syn::File) -> Option<Vec<&syn::ExprMethodCall>> {
+    let mut visitor = MethodCalls {
+        func: "create_router",
+        inside: false,
+        calls: Vec::new(),
+    };
+    visitor.visit_file(file);
+    visitor
+        .calls
+        .into_iter()
+        .map(flatten_chain)
+        .filter(|chain| chain.iter().any(|c| c.method == "route" || c.method == "merge"))
+        .max_by_key(|chain| chain.len())
+}
+
+fn insert_chain_call(
+    src: &Source,
+    file: &syn::File,
+    before: &[&str],
+    line: &str,
+) -> Option<String> {
+    let new = parse_chain_call(line)?;
+    let chain = router_chain(file)?;
+    if chain.iter().any(|call| same_call(call, &new)) {
+        return Some(src.text.to_string());
+    }
+    let text = line.trim();
+
+    // New calls join the group of calls that precedes the first `before` call.
+    let stop = chain
+        .iter()
+        .position(|call| before.iter().any(|name| call.method == name))
+        .unwrap_or(chain.len());
+    if stop == 0 {
+        let dot = src.offset(chain[0].dot_token.span.start());
+        return Some(if src.starts_line(dot) {
+            let indent = src.indent_at(dot);
+            src.splice(src.line_start(dot), src.line_start(dot), &format!("{indent}{text}\n"))
+        } else {
+            src.splice(dot, dot, text)
+        });
+    }
+    let anchor = chain[stop - 1];
+    let start = src.offset(anchor.dot_token.span.start());
+    let end = src.offset(anchor.paren_token.span.close().end());
+    Some(src.insert_after(start, end, text))
+}
+
+fn remove_chain_call(src: &Source, file: &syn::File, line: &str) -> Option<String> {
+    let target = parse_chain_call(line)?;
+    let chain = router_chain(file)?;
+    let Some(found) = chain.iter().find(|call| same_call(call, &target)) else {
+        return Some(src.text.to_string());
+    };
+    let start = src.offset(found.dot_token.span.start());
+    let end = src.offset(found.paren_token.span.close().end());
+    Some(src.remove_range(start, end))
+}
+
+// ── delimited lists ───────────────────────────────────────────────────
+
+/// Finds the `#[openapi(...)]` attribute anywhere in the file, including
+/// items declared inside function bodies.
+#[derive(Default)]
+struct OpenApiAttr {
+    tokens: Option<TokenStream>,
+}
+
+impl<'ast> Visit<'ast> for OpenApiAttr {
+    fn visit_attribute(&mut self, node: &'ast syn::Attribute) {
+        if self.tokens.is_none() && node.path().is_ident("openapi") {
+            if let syn::Meta::List(list) = &node.meta {
+                self.tokens = Some(list.tokens.clone());
+            }
+        }
+    }
+}
+
+/// Walk `name(...)` groups inside the `#[openapi]` attribute, e.g.
+/// `["components", "schemas"]`. Returns the list contents and its closing
+/// delimiter.
+fn find_openapi_list(file: &syn::File, path: &[&str]) -> Option<(TokenStream, Span)> {
+    let mut visitor = OpenApiAttr::default();
+    visitor.visit_file(file);
+    let mut tokens = visitor.tokens?;
+    let mut close = None;
+    for name in path {
+        let mut iter = tokens.clone().into_iter();
+        let group = loop {
+            match iter.next()? {
+                TokenTree::Ident(ident) if ident == name => {
+                    if let Some(TokenTree::Group(group)) = iter.next() {
+                        break group;
+                    }
+                }
+                _ => {}
+            }
+        };
+        close = Some(group.span_close());
+        tokens = group.stream();
+    }
+    Some((tokens, close?))
+}
+
+/// Finds the first `vec![...]` inside the named function or method.
+struct VecMacro {
+    func: &'static str,
+    inside: bool,
+    found: Option<(TokenStream, Span)>,
+}
+
+impl<'ast> Visit<'ast> for VecMacro {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        let was_inside = self.inside;
+        self.inside = node.sig.ident == self.func;
+        syn::visit::visit_item_fn(self, node);
+        self.inside = was_inside;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        let was_inside = self.inside;
+        self.inside = node.sig.ident == self.func;
+        syn::visit::visit_impl_item_fn(self, node);
+        self.inside = was_inside;
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if self.inside && self.found.is_none() && node.path.is_ident("vec") {
+            if let syn::MacroDelimiter::Bracket(bracket) = &node.delimiter {
+                self.found = Some((node.tokens.clone(), bracket.span.close()));
+            }
+        }
+    }
+}
+
+fn find_vec_macro(file: &syn::File, func: &'static str) -> Option<(TokenStream, Span)> {
+    let mut visitor = VecMacro {
+        func,
+        inside: false,
+        found: None,
+    };
+    visitor.visit_file(file);
+    visitor.found
+}
+
+/// One comma-separated entry of a delimited list.
+struct ListEntry {
+    start: LineColumn,
+    end: LineColumn,
+    /// End of the trailing comma, if there is one.
+    comma_end: Option<LineColumn>,
+    normalized: String,
+}
+
+fn split_entries(tokens: TokenStream) -> Vec<ListEntry> {
+    let mut entries = Vec::new();
+    let mut current: Vec<TokenTree> = Vec::new();
+    let mut flush = |current: &mut Vec<TokenTree>, comma_end: Option<LineColumn>| {
+        if let (Some(first), Some(last)) = (current.first(), current.last()) {
+            entries.push(ListEntry {
+                start: first.span().start(),
+                end: last.span().end(),
+                comma_end,
+                normalized: normalize(current.iter().cloned().collect()),
+            });
+        }
+        current.clear();
+    };
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == ',' => {
+                flush(&mut current, Some(p.span().end()));
+            }
+            _ => current.push(token),
+        }
+    }
+    flush(&mut current, None);
+    entries
+}
+
+fn entry_text(line: &str) -> &str {
+    line.trim().trim_end_matches(',').trim_end()
+}
+
+fn insert_list_entry(src: &Source, inner: TokenStream, close: Span, line: &str) -> Option<String> {
+    let entry = entry_text(line);
+    let wanted = normalize(entry.parse().ok()?);
+    let entries = split_entries(inner);
+    if entries.iter().any(|e| e.normalized == wanted) {
+        return Some(src.text.to_string());
+    }
+
+    let Some(last) = entries.last() else {
+        let close = src.offset(close.start());
+        return Some(if src.starts_line(close) {
+            let indent = src.indent_at(close);
+            src.splice(
+                src.line_start(close),
+                src.line_start(close),
+                &format!("{indent}    {entry},\n"),
+            )
+        } else {
+            src.splice(close, close, entry)
+        });
+    };
+
+    let start = src.offset(last.start);
+    let end = src.offset(last.end);
+    match last.comma_end.map(|c| src.offset(c)) {
+        Some(comma) if src.starts_line(start) && src.rest_is_trivia(comma) => {
+            let indent = src.indent_at(start);
+            let at = src.line_end(comma);
+            let prefix = if at == src.text.len() && !src.text.ends_with('\n') { "\n" } else { "" };
+            Some(src.splice(at, at, &format!("{prefix}{indent}{entry},\n")))
+        }
+        Some(comma) => Some(src.splice(comma, comma, &format!(" {entry},"))),
+        None if src.starts_line(start) && src.rest_is_trivia(end) => {
+            let indent = src.indent_at(start);
+            let at = src.line_end(end);
+            let with_comma = src.splice(end, end, ",");
+            let at = at + 1;
+            let prefix = if at == with_comma.len() && !with_comma.ends_with('\n') { "\n" } else { "" };
+            Some(Source::new(&with_comma).splice(at, at, &format!("{prefix}{indent}{entry},\n")))
+        }
+        None => Some(src.splice(end, end, &format!(", {entry}"))),
+    }
+}
+
+fn remove_list_entries(src: &Source, inner: TokenStream, pred: &dyn Fn(&str) -> bool) -> String {
+    let ranges = split_entries(inner)
+        .iter()
+        .filter(|e| pred(&e.normalized))
+        .map(|e| (src.offset(e.start), src.offset(e.comma_end.unwrap_or(e.end))))
+        .collect();
+    src.remove_ranges(ranges)
+}
+
+// ── helpers ───────────────────────────────────────────────────────────
+
+/// Token text with all whitespace removed, for formatting-insensitive
+/// comparisons.
+fn normalize(tokens: TokenStream) -> String {
+    tokens.to_string().split_whitespace().collect()
+}
+
+fn token_range(tokens: TokenStream) -> Option<(LineColumn, LineColumn)> {
+    let mut iter = tokens.into_iter();
+    let first = iter.next()?;
+    let last = iter.last().unwrap_or_else(|| first.clone());
+    Some((first.span().start(), last.span().end()))
+}
+
+fn remove_exact_line(source: &str, line: &str) -> String {
+    let wanted = line.trim();
+    if wanted.is_empty() {
+        return source.to_string();
+    }
+    source
+        .split_inclusive('\n')
+        .filter(|l| l.trim() != wanted)
+        .collect()
+}
+
+/// Source text with helpers for mapping span positions to byte offsets.
+struct Source<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { text, line_starts }
+    }
+
+    /// Byte offset of a span position (1-based line, 0-based char column).
+    fn offset(&self, pos: LineColumn) -> usize {
+        let start = self.line_starts[pos.line - 1];
+        self.text[start..]
+            .char_indices()
+            .nth(pos.column)
+            .map(|(i, _)| start + i)
+            .unwrap_or(self.text.len())
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0)
+    }
+
+    /// Offset just past the newline ending the line that contains `offset`.
+    fn line_end(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .find('\n')
+            .map(|i| offset + i + 1)
+            .unwrap_or(self.text.len())
+    }
+
+    fn indent_at(&self, offset: usize) -> &str {
+        let start = self.line_start(offset);
+        let line = &self.text[start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// Only whitespace precedes `offset` on its line.
+    fn starts_line(&self, offset: usize) -> bool {
+        self.text[self.line_start(offset)..offset].trim().is_empty()
+    }
+
+    /// Only whitespace or a line comment follows `offset` on its line.
+    fn rest_is_trivia(&self, offset: usize) -> bool {
+        let rest = self.text[offset..self.line_end(offset)].trim();
+        rest.is_empty() || rest.starts_with("//")
+    }
+
+    fn splice(&self, start: usize, end: usize, text: &str) -> String {
+        format!("{}{}{}", &self.text[..start], text, &self.text[end..])
+    }
+
+    /// Insert `text` after the code spanning `start..end`: on its own line
+    /// (matching indentation) when that code occupies whole lines, inline
+    /// otherwise.
+    fn insert_after(&self, start: usize, end: usize, text: &str) -> String {
+        if self.starts_line(start) && self.rest_is_trivia(end) {
+            let indent = self.indent_at(start);
+            let at = self.line_end(end);
+            let prefix = if at == self.text.len() && !self.text.ends_with('\n') { "\n" } else { "" };
+            self.splice(at, at, &format!("{prefix}{indent}{text}\n"))
+        } else {
+            self.splice(end, end, text)
+        }
+    }
+
+    /// Remove the code spanning `start..end`, taking its lines with it when
+    /// nothing else shares them.
+    fn remove_range(&self, start: usize, end: usize) -> String {
+        self.remove_ranges(vec![(start, end)])
+    }
+
+    /// Widen a whole-line extent over the blank line before it when a blank
+    /// line (or the end of the file) follows, so no double gap is left.
+    fn with_blank_before(&self, start: usize, end: usize) -> (usize, usize) {
+        if start == 0 {
+            return (start, end);
+        }
+        let prev = self.line_start(start - 1);
+        let blank_before = self.text[prev..start].trim().is_empty();
+        let blank_after = end == self.text.len() || self.text[end..self.line_end(end)].trim().is_empty();
+        if blank_before && blank_after {
+            (prev, end)
+        } else {
+            (start, end)
+        }
+    }
+
+    fn remove_ranges(&self, ranges: Vec<(usize, usize)>) -> String {
+        let mut extents: Vec<(usize, usize)> = ranges
+            .into_iter()
+            .map(|(start, end)| {
+                if self.starts_line(start) && self.text[end..self.line_end(end)].trim().is_empty() {
+                    self.with_blank_before(self.line_start(start), self.line_end(end))
+                } else if self.text[end..].starts_with(' ') {
+                    (start, end + 1)
+                } else {
+                    (start, end)
+                }
+            })
+            .collect();
+        extents.sort();
+        let mut out = String::with_capacity(self.text.len());
+        let mut cursor = 0;
+        for (start, end) in extents {
+            if start > cursor {
+                out.push_str(&self.text[cursor..start]);
+            }
+            cursor = cursor.max(end);
+        }
+        out.push_str(&self.text[cursor..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::DiskFs;
+    use std::fs;
+
+    const ROUTES: &str = "\
+use axum::Router;
+
+pub mod tag;
+// === ROMANCE:MODS ===
+
+pub fn create_router(db: DatabaseConnection) -> Router {
+    Router::new()
+        .route(\"/health\", get(health))
+        .merge(tag::router())
+// === ROMANCE:ROUTES ===
+// === ROMANCE:MIDDLEWARE ===
+        .with_state(state)
+}
+";
+
+    const MAIN: &str = "\
+mod errors;
+// === ROMANCE:MAIN_MODS ===
+mod routes;
+
+async fn main() {
+    #[derive(OpenApi)]
+    #[openapi(
+        paths(
+            crate::handlers::tag::list,
+            // === ROMANCE:OPENAPI_PATHS ===
+        ),
+        components(
+            schemas(
+                // === ROMANCE:OPENAPI_SCHEMAS ===
+            )
+        ),
+        tags(
+            (name = \"Tag\", description = \"Tag management\"),
+        )
+    )]
+    struct ApiDoc;
+}
+";
+
+    const MIGRATIONS: &str = "\
+mod m1_create_tag;
+
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m1_create_tag::Migration),
+            // === ROMANCE:MIGRATIONS ===
+        ]
+    }
+}
+";
+
+    fn with(construct: Construct, source: &str, line: &str) -> String {
+        construct.insert(source, line).expect("construct not found")
+    }
+
+    #[test]
+    fn mod_goes_after_last_mod() {
+        let out = with(Construct::Mod, MAIN, "mod billing;");
+        assert!(out.contains("mod routes;\nmod billing;\n"));
+    }
+
+    #[test]
+    fn mod_is_idempotent_across_visibility() {
+        let out = with(Construct::Mod, ROUTES, "mod tag;");
+        assert_eq!(out, ROUTES);
+    }
+
+    #[test]
+    fn mod_without_existing_mods_falls_back() {
+        assert!(Construct::Mod
+            .insert("// === ROMANCE:MODS ===\n", "pub mod post;")
+            .is_none());
+    }
+
+    #[test]
+    fn merge_goes_after_last_merge_even_without_marker() {
+        let source = ROUTES.replace("// === ROMANCE:ROUTES ===\n", "");
+        let out = with(
+            Construct::for_marker(Path::new("mod.rs"), markers::ROUTES).unwrap(),
+            &source,
+            "        .merge(post::router())",
+        );
+        assert!(out.contains(
+            "        .merge(tag::router())\n        .merge(post::router())\n"
+        ));
+        assert!(syn::parse_file(&out).is_ok());
+    }
+
+    #[test]
+    fn merge_goes_before_layers() {
+        let source = ROUTES.replace(
+            "        .with_state(state)",
+            "        .layer(cors())\n        .with_state(state)",
+        );
+        let out = with(
+            Construct::RouterChain { before: &["layer", "with_state"] },
+            &source,
+            ".merge(post::router())",
+        );
+        let merge = out.find(".merge(post::router())").unwrap();
+        assert!(merge < out.find(".layer(cors())").unwrap());
+        assert!(merge > out.find(".merge(tag::router())").unwrap());
+    }
+
+    #[test]
+    fn merge_in_single_line_chain() {
+        let source = "fn create_router() -> Router { Router::new().merge(a::router()).with_state(s) }\n";
+        let out = with(
+            Construct::RouterChain { before: &["layer", "with_state"] },
+            source,
+            "        .merge(b::router())",
+        );
+        assert!(out.contains(".merge(a::router()).merge(b::router()).with_state(s)"));
+    }
+
+    #[test]
+    fn merge_is_idempotent_ignoring_formatting() {
+        let construct = Construct::RouterChain { before: &["with_state"] };
+        let out = with(construct, ROUTES, ".merge( tag :: router ( ) )");
+        assert_eq!(out, ROUTES);
+    }
+
+    #[test]
+    fn openapi_path_appends_to_list() {
+        let out = with(
+            Construct::OpenApi(&["paths"]),
+            MAIN,
+            "        crate::handlers::post::list,",
+        );
+        assert!(out.contains(
+            "            crate::handlers::tag::list,\n            crate::handlers::post::list,\n"
+        ));
+        assert!(syn::parse_file(&out).is_ok());
+    }
+
+    #[test]
+    fn openapi_schema_into_empty_nested_list() {
+        let out = with(
+            Construct::OpenApi(&["components", "schemas"]),
+            MAIN,
+            "crate::entities::post::Model,",
+        );
+        assert!(out.contains(
+            "// === ROMANCE:OPENAPI_SCHEMAS ===\n                crate::entities::post::Model,\n            )"
+        ));
+    }
+
+    #[test]
+    fn openapi_tag_is_idempotent() {
+        let out = with(
+            Construct::OpenApi(&["tags"]),
+            MAIN,
+            "(name = \"Tag\", description = \"Tag management\"),",
+        );
+        assert_eq!(out, MAIN);
+    }
+
+    #[test]
+    fn openapi_list_without_trailing_comma() {
+        let source = "#[openapi(paths(a::list))]\nstruct ApiDoc;\n";
+        let out = with(Construct::OpenApi(&["paths"]), source, "b::list,");
+        assert!(out.contains("paths(a::list, b::list)"));
+    }
+
+    #[test]
+    fn migration_appends_to_vec() {
+        let out = with(
+            Construct::Vec("migrations"),
+            MIGRATIONS,
+            "            Box::new(m2_create_post::Migration),",
+        );
+        assert!(out.contains(
+            "            Box::new(m1_create_tag::Migration),\n            Box::new(m2_create_post::Migration),\n"
+        ));
+    }
+
+    #[test]
+    fn unparsable_file_falls_back() {
+        assert!(Construct::Mod.insert("mod a;\nfn broken( {\n", "mod b;").is_none());
+    }
+
+    #[test]
+    fn remove_mod_matches_whole_identifier() {
+        let source = "pub mod post;\npub mod post_tag;\n// === ROMANCE:MODS ===\n";
+        let out = Construct::Mod.remove(source, "pub mod post;").unwrap();
+        assert_eq!(out, "pub mod post_tag;\n// === ROMANCE:MODS ===\n");
+    }
+
+    #[test]
+    fn remove_merge_leaves_other_calls() {
+        let construct = Construct::RouterChain { before: &["with_state"] };
+        let out = construct.remove(ROUTES, ".merge(tag::router())").unwrap();
+        assert!(!out.contains("tag::router()"));
+        assert!(out.contains(".route(\"/health\", get(health))\n// === ROMANCE:ROUTES ==="));
+        assert!(syn::parse_file(&out).is_ok());
+    }
+
+    #[test]
+    fn remove_inline_list_entry() {
+        let source = "#[openapi(paths(a::list, b::list, c::list))]\nstruct ApiDoc;\n";
+        let out = Construct::OpenApi(&["paths"]).remove(source, "b::list,").unwrap();
+        assert!(out.contains("paths(a::list, c::list)"));
+    }
+
+    #[test]
+    fn insert_and_remove_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, MIGRATIONS).unwrap();
+
+        insert(&DiskFs, &path, markers::MIGRATION_MODS, "mod m2_create_post;").unwrap();
+        insert(&DiskFs, &path, markers::MIGRATIONS, "            Box::new(m2_create_post::Migration),").unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("mod m1_create_tag;\nmod m2_create_post;\n"));
+        assert!(content.contains("Box::new(m2_create_post::Migration),"));
+
+        assert!(remove(&DiskFs, &path, markers::MIGRATIONS, "Box::new(m2_create_post::Migration),").unwrap());
+        assert!(remove(&DiskFs, &path, markers::MIGRATION_MODS, "mod m2_create_post;").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), MIGRATIONS);
+        assert!(!remove(&DiskFs, &path, markers::MIGRATION_MODS, "mod m2_create_post;").unwrap());
+    }
+
+    #[test]
+    fn unknown_marker_uses_marker_injection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.rs");
+        fs::write(&path, "impl X {\n    // === ROMANCE:RELATIONS ===\n}\n").unwrap();
+        insert(&DiskFs, &path, markers::RELATIONS, "    fn a() {}").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "impl X {\n    fn a() {}\n    // === ROMANCE:RELATIONS ===\n}\n"
+        );
+    }
+
+    #[test]
+    fn remove_stmts_and_items_by_predicate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seed.rs");
+        fs::write(
+            &path,
+            "pub async fn run() {\n    seed_posts().await;\n    seed_tags().await;\n}\n\nasync fn seed_posts() {}\n\nasync fn seed_tags() {}\n",
+        )
+        .unwrap();
+
+        assert!(remove_stmts(&DiskFs, &path, |stmt| mentions(stmt, "seed_tags")).unwrap());
+        assert!(remove_items(&DiskFs, &path, |item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "seed_tags")).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "pub async fn run() {\n    seed_posts().await;\n}\n\nasync fn seed_posts() {}\n"
+        );
+        assert!(!remove_stmts(&DiskFs, &path, |stmt| mentions(stmt, "seed_tags")).unwrap());
+    }
+
+    #[test]
+    fn remove_method_calls_by_predicate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("routes.rs");
+        fs::write(
+            &path,
+            "fn router() -> Router {\n    Router::new()\n        .route(\"/a\", get(post::list))\n        .route(\"/b\", get(post::list_comments))\n}\n",
+        )
+        .unwrap();
+
+        assert!(remove_method_calls(&DiskFs, &path, |call| call.method == "route" && mentions(call, "post::list_comments")).unwrap());
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("get(post::list))\n}"));
+        assert!(!content.contains("list_comments"));
+    }
+}
@@ -1,12 +1,24 @@
 pub mod addon;
 pub mod ai_context;
 pub mod config;
+pub mod database;
 pub mod entity;
+pub mod error;
+pub mod fs;
 pub mod generator;
+pub mod history;
+pub mod import;
+pub mod inject;
+pub mod lint;
 pub mod manifest;
+pub mod merge;
+pub mod module;
+pub mod project;
 pub mod relation;
 pub mod scaffold;
+pub mod schema;
 pub mod seed;
+pub mod status;
 pub mod template;
 pub mod test_runner;
 pub mod updater;
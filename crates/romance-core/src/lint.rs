@@ -0,0 +1,703 @@
+//! Safety checks for migrations, for `romance db lint`: operations that lose
+//! data or lock busy tables while they run. SeaORM builder calls are checked,
+//! and so is raw SQL: `execute_unprepared` strings and the `.sql` files of
+//! `romance generate migration --sql`. Only `up` is checked; `down` undoes
+//! the migration and is expected to be destructive.
+//!
+//! Tables created by the same migration are new and empty, so indexes and
+//! constraints aren't reported in migrations creating tables. After review,
+//! a comment silences a rule for the statement that follows it:
+//! `// romance-lint: allow(drop_column)` (`--` in SQL).
+
+use crate::project::Project;
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// An operation `romance db lint` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    DropTable,
+    DropColumn,
+    Truncate,
+    RenameTable,
+    RenameColumn,
+    ChangeColumnType,
+    AddNotNullColumn,
+    SetNotNull,
+    CreateIndex,
+    AddConstraint,
+}
+
+impl Rule {
+    /// The name used in `romance-lint: allow(...)` comments.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::DropTable => "drop_table",
+            Rule::DropColumn => "drop_column",
+            Rule::Truncate => "truncate",
+            Rule::RenameTable => "rename_table",
+            Rule::RenameColumn => "rename_column",
+            Rule::ChangeColumnType => "change_column_type",
+            Rule::AddNotNullColumn => "add_not_null_column",
+            Rule::SetNotNull => "set_not_null",
+            Rule::CreateIndex => "create_index",
+            Rule::AddConstraint => "add_constraint",
+        }
+    }
+
+    /// Whether the operation loses data or breaks running code, rather than
+    /// only locking a table.
+    pub fn is_destructive(self) -> bool {
+        matches!(
+            self,
+            Rule::DropTable | Rule::DropColumn | Rule::Truncate | Rule::RenameTable | Rule::RenameColumn
+        )
+    }
+
+    pub fn problem(self) -> &'static str {
+        match self {
+            Rule::DropTable => "drops a table and its data",
+            Rule::DropColumn => "drops a column and its data; code still selecting it fails",
+            Rule::Truncate => "deletes every row of a table",
+            Rule::RenameTable => "renames a table; running code still uses the old name",
+            Rule::RenameColumn => "renames a column; running code still uses the old name",
+            Rule::ChangeColumnType => "changes a column's type, rewriting the table under an exclusive lock",
+            Rule::AddNotNullColumn => "adds a NOT NULL column without a default, which fails on tables with rows",
+            Rule::SetNotNull => "sets NOT NULL, scanning the whole table under an exclusive lock",
+            Rule::CreateIndex => "creates an index on an existing table, blocking writes while it builds",
+            Rule::AddConstraint => "adds a constraint validated against every row while writes are blocked",
+        }
+    }
+
+    /// The safe multi-step alternative.
+    pub fn suggestion(self) -> &'static str {
+        match self {
+            Rule::DropTable => {
+                "Remove the code using the table and deploy first, back the data up, then drop it in a later release"
+            }
+            Rule::DropColumn => {
+                "Remove the field from the model and deploy first, then drop the column in a later migration"
+            }
+            Rule::Truncate => "Make sure this is meant for every environment; scope it with DELETE ... WHERE otherwise",
+            Rule::RenameTable => {
+                "Create the new table, copy the data and switch the code to it, then drop the old table in a later release"
+            }
+            Rule::RenameColumn => {
+                "Add the new column, write both and backfill it, switch reads to it, then drop the old column in a later release"
+            }
+            Rule::ChangeColumnType => {
+                "Add a column with the new type, backfill it in batches, switch the code to it, then drop the old column"
+            }
+            Rule::AddNotNullColumn => {
+                "Add the column as nullable (or with a DEFAULT), backfill it, then SET NOT NULL in a later migration"
+            }
+            Rule::SetNotNull => {
+                "Add CHECK (column IS NOT NULL) NOT VALID, VALIDATE CONSTRAINT in a later migration, then SET NOT NULL, which reuses the check"
+            }
+            Rule::CreateIndex => {
+                "On large tables, build it with CREATE INDEX CONCURRENTLY outside the migration's transaction and keep CREATE INDEX IF NOT EXISTS in the migration"
+            }
+            Rule::AddConstraint => {
+                "Add it with NOT VALID, then VALIDATE CONSTRAINT in a later migration, which doesn't block writes"
+            }
+        }
+    }
+}
+
+/// An operation found in a migration.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub migration: String,
+    /// The file it was found in, relative to the project root.
+    pub file: String,
+    pub line: usize,
+    pub rule: Rule,
+    pub destructive: bool,
+    pub problem: &'static str,
+    pub suggestion: &'static str,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {} [{}]", self.file, self.line, self.problem, self.rule.name())
+    }
+}
+
+/// Check the `up` of migration `module`.
+pub fn lint(project: &Project, module: &str) -> Result<Vec<Finding>> {
+    let path = project.migration_src().join(format!("{}.rs", module));
+    let content = project.fs().read_to_string(&path)?;
+    let source = Source::scan(&content);
+    let Some((start, end)) = up_body(&source.code) else {
+        return Ok(Vec::new());
+    };
+    let body = &source.code[start..end];
+    let file = project.relative(&path).display().to_string();
+
+    // Raw SQL: strings in `up`, and the `.sql` files the migration embeds
+    let mut sql: Vec<(String, usize, String)> = source
+        .literals
+        .iter()
+        .filter(|(offset, _)| (start..end).contains(offset))
+        .map(|(offset, text)| (file.clone(), line_at(&content, *offset), text.clone()))
+        .collect();
+    let mut allowed = allows(&file, &content, &source.code, RUST_COMMENT, 1);
+    let include = Regex::new(r#"include_str!\s*\(\s*"([^"]+)"\s*\)"#).unwrap();
+    for captures in include.captures_iter(&content) {
+        let included = &captures[1];
+        if included.ends_with(".down.sql") {
+            continue;
+        }
+        // `../sql/<module>.up.sql`, relative to the migration
+        let mut included_path = project.migration_src();
+        for component in std::path::Path::new(included).components() {
+            match component {
+                std::path::Component::ParentDir => {
+                    included_path.pop();
+                }
+                other => included_path.push(other),
+            }
+        }
+        let text = project.fs().read_to_string(&included_path)?;
+        sql.push((project.relative(&included_path).display().to_string(), 1, text));
+    }
+    for (sql_file, first_line, text) in &sql {
+        allowed.extend(allows(sql_file, text, &blank_sql_comments(text), SQL_COMMENT, *first_line));
+    }
+
+    let creates_table = body.contains("create_table(")
+        || sql.iter().any(|(_, _, text)| text.to_uppercase().contains("CREATE TABLE"));
+    let mut hits: Vec<(String, usize, Rule)> = builder_calls(body, creates_table)
+        .into_iter()
+        .map(|(offset, rule)| (file.clone(), line_at(&content, start + offset), rule))
+        .collect();
+    for (sql_file, first_line, text) in &sql {
+        for (line, rule) in check_sql(text, creates_table) {
+            hits.push((sql_file.clone(), first_line + line, rule));
+        }
+    }
+
+    Ok(hits
+        .into_iter()
+        .filter(|(file, line, rule)| !allowed.iter().any(|allow| allow.silences(file, *line, *rule)))
+        .map(|(file, line, rule)| Finding {
+            migration: module.to_string(),
+            file,
+            line,
+            rule,
+            destructive: rule.is_destructive(),
+            problem: rule.problem(),
+            suggestion: rule.suggestion(),
+        })
+        .collect())
+}
+
+/// Rust source with comments and string contents blanked out (offsets are
+/// kept), and the string literals it contained.
+struct Source {
+    code: String,
+    literals: Vec<(usize, String)>,
+}
+
+impl Source {
+    fn scan(content: &str) -> Self {
+        let bytes = content.as_bytes();
+        let mut code = bytes.to_vec();
+        let mut literals = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &content[i..];
+            if rest.starts_with("//") {
+                let end = rest.find('\n').map_or(bytes.len(), |n| i + n);
+                blank(&mut code, i, end);
+                i = end;
+            } else if rest.starts_with("/*") {
+                let end = rest.find("*/").map_or(bytes.len(), |n| i + n + 2);
+                blank(&mut code, i, end);
+                i = end;
+            } else if let Some(hashes) = raw_string_start(rest) {
+                // r"..." or r#"..."#
+                let open = 2 + hashes;
+                let close = format!("\"{}", "#".repeat(hashes));
+                let end = rest[open..].find(&close).map_or(bytes.len(), |n| i + open + n);
+                literals.push((i, content[i + open..end].to_string()));
+                blank(&mut code, i + open, end);
+                i = (end + close.len()).min(bytes.len());
+            } else if bytes[i] == b'"' {
+                let mut text = String::new();
+                let mut j = i + 1;
+                let mut chars = content[j..].chars();
+                while let Some(c) = chars.next() {
+                    j += c.len_utf8();
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                j += escaped.len_utf8();
+                                text.push(match escaped {
+                                    'n' => '\n',
+                                    't' => '\t',
+                                    other => other,
+                                });
+                            }
+                        }
+                        c => text.push(c),
+                    }
+                }
+                literals.push((i, text));
+                blank(&mut code, i + 1, j.saturating_sub(1).max(i + 1));
+                i = j;
+            } else if bytes[i] == b'\'' {
+                // Char literals hold quotes and braces; lifetimes are left alone
+                let end = if rest[1..].starts_with('\\') {
+                    rest.get(3..).and_then(|r| r.find('\'')).map(|n| i + 3 + n + 1)
+                } else {
+                    rest[1..].chars().next().filter(|c| rest[1 + c.len_utf8()..].starts_with('\'')).map(|c| i + 2 + c.len_utf8())
+                };
+                match end {
+                    Some(end) => {
+                        blank(&mut code, i, end);
+                        i = end;
+                    }
+                    None => i += 1,
+                }
+            } else {
+                i += 1;
+            }
+        }
+        Source {
+            code: String::from_utf8_lossy(&code).into_owned(),
+            literals,
+        }
+    }
+}
+
+/// Replace `code[from..to]` with spaces, keeping line breaks.
+fn blank(code: &mut [u8], from: usize, to: usize) {
+    for b in &mut code[from..to] {
+        if *b != b'\n' {
+            *b = b' ';
+        }
+    }
+}
+
+/// The number of `#` of a raw string starting `s`.
+fn raw_string_start(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('r')?;
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    rest[hashes..].starts_with('"').then_some(hashes)
+}
+
+/// The byte range of the body of `fn up`, inside its braces.
+fn up_body(code: &str) -> Option<(usize, usize)> {
+    let signature = Regex::new(r"\bfn\s+up\s*\(").unwrap().find(code)?;
+    let open = signature.end() + code[signature.end()..].find('{')?;
+    let mut depth = 0;
+    for (offset, c) in code[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((open + 1, open + offset));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// SeaORM schema manager calls in `body`, by offset.
+fn builder_calls(body: &str, creates_table: bool) -> Vec<(usize, Rule)> {
+    let mut calls = vec![
+        (r"\bdrop_table\s*\(", Rule::DropTable),
+        (r"\.drop_column\s*\(", Rule::DropColumn),
+        (r"\btruncate_table\s*\(", Rule::Truncate),
+        (r"\brename_table\s*\(", Rule::RenameTable),
+        (r"\.rename_column\s*\(", Rule::RenameColumn),
+        (r"\.modify_column\s*\(", Rule::ChangeColumnType),
+    ];
+    if !creates_table {
+        calls.push((r"\bcreate_index\s*\(", Rule::CreateIndex));
+        calls.push((r"\bcreate_foreign_key\s*\(|\.add_foreign_key\s*\(", Rule::AddConstraint));
+    }
+
+    let mut hits: Vec<(usize, Rule)> = calls
+        .into_iter()
+        .flat_map(|(pattern, rule)| Regex::new(pattern).unwrap().find_iter(body).map(move |m| (m.start(), rule)).collect::<Vec<_>>())
+        .collect();
+    for m in Regex::new(r"\.add_column(_if_not_exists)?\s*\(").unwrap().find_iter(body) {
+        let column = balanced(&body[m.end()..]);
+        if column.contains(".not_null()") && !column.contains(".default(") && !column.contains(".primary_key()") {
+            hits.push((m.start(), Rule::AddNotNullColumn));
+        }
+    }
+    hits.sort_by_key(|(offset, _)| *offset);
+    hits
+}
+
+/// `s` up to the parenthesis closing the one just before it.
+fn balanced(s: &str) -> &str {
+    let mut depth = 1;
+    for (offset, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[..offset];
+                }
+            }
+            _ => {}
+        }
+    }
+    s
+}
+
+/// Reported statements in `sql`, by line (counted from 0).
+fn check_sql(sql: &str, creates_table: bool) -> Vec<(usize, Rule)> {
+    // Blank out `--` comments, keeping lines
+    let sql: String = sql
+        .lines()
+        .map(|line| line.split_once("--").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let alter_column_type = Regex::new(r"^ALTER (COLUMN )?\S+ (SET DATA )?TYPE ").unwrap();
+    let set_not_null = Regex::new(r"^ALTER (COLUMN )?\S+ SET NOT NULL").unwrap();
+    let add_constraint = Regex::new(r"^ADD (CONSTRAINT \S+ )?(FOREIGN KEY|CHECK)").unwrap();
+    let create_index = Regex::new(r"^CREATE (UNIQUE )?INDEX ").unwrap();
+
+    let mut hits = Vec::new();
+    let mut offset = 0;
+    for statement in sql.split(';') {
+        let line = sql[..offset + (statement.len() - statement.trim_start().len())].matches('\n').count();
+        offset += statement.len() + 1;
+        let normalized = statement.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase().replace('"', "");
+        if normalized.is_empty() {
+            continue;
+        }
+
+        let mut rules = Vec::new();
+        if normalized.starts_with("DROP TABLE ") {
+            rules.push(Rule::DropTable);
+        } else if normalized.starts_with("TRUNCATE ")
+            || (normalized.starts_with("DELETE FROM ") && !normalized.contains(" WHERE "))
+        {
+            rules.push(Rule::Truncate);
+        } else if create_index.is_match(&normalized) && !normalized.contains(" CONCURRENTLY ") {
+            if !creates_table {
+                rules.push(Rule::CreateIndex);
+            }
+        } else if let Some(table) = normalized.strip_prefix("ALTER TABLE ") {
+            // Skip the table name to the first action
+            let table = table.trim_start_matches("IF EXISTS ").trim_start_matches("ONLY ");
+            let actions = table.split_once(' ').map_or("", |(_, actions)| actions);
+            for action in split_top_level(actions) {
+                let action = action.trim();
+                if action.starts_with("DROP COLUMN ") {
+                    rules.push(Rule::DropColumn);
+                } else if action.starts_with("RENAME TO ") {
+                    rules.push(Rule::RenameTable);
+                } else if action.starts_with("RENAME ") && !action.starts_with("RENAME CONSTRAINT ") {
+                    rules.push(Rule::RenameColumn);
+                } else if alter_column_type.is_match(action) {
+                    rules.push(Rule::ChangeColumnType);
+                } else if set_not_null.is_match(action) {
+                    rules.push(Rule::SetNotNull);
+                } else if add_constraint.is_match(action) {
+                    if !creates_table && !action.contains(" NOT VALID") {
+                        rules.push(Rule::AddConstraint);
+                    }
+                } else if action.starts_with("ADD ")
+                    && !action.starts_with("ADD CONSTRAINT ")
+                    && !action.starts_with("ADD PRIMARY KEY")
+                    && !action.starts_with("ADD UNIQUE")
+                    && action.contains(" NOT NULL")
+                    && !action.contains(" DEFAULT ")
+                {
+                    rules.push(Rule::AddNotNullColumn);
+                }
+            }
+        }
+        rules.dedup();
+        hits.extend(rules.into_iter().map(|rule| (line, rule)));
+    }
+    hits
+}
+
+/// `s` split on the commas outside parentheses.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (offset, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&s[start..offset]);
+                start = offset + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Comments holding `romance-lint: allow(...)` in Rust and in SQL.
+const RUST_COMMENT: &str = "//[/!]?";
+const SQL_COMMENT: &str = "--";
+
+/// A `romance-lint: allow(...)` comment, silencing its rules in the
+/// statement that follows it.
+struct Allow {
+    file: String,
+    rules: Vec<String>,
+    lines: RangeInclusive<usize>,
+}
+
+impl Allow {
+    fn silences(&self, file: &str, line: usize, rule: Rule) -> bool {
+        self.file == file && self.lines.contains(&line) && self.rules.iter().any(|name| name == rule.name())
+    }
+}
+
+/// The allow comments starting with `comment` in `content`, the text of
+/// `file` from its line `first_line`. `code` is `content` with comments and
+/// strings blanked out, where a statement runs up to the next `;`.
+fn allows(file: &str, content: &str, code: &str, comment: &str, first_line: usize) -> Vec<Allow> {
+    Regex::new(&format!(r"{}\s*romance-lint:\s*allow\(([^)]*)\)", comment))
+        .unwrap()
+        .captures_iter(content)
+        .map(|captures| {
+            let comment_end = captures.get(0).unwrap().end();
+            let next_line = content[comment_end..].find('\n').map_or(content.len(), |n| comment_end + n);
+            let start = code[next_line..]
+                .find(|c: char| !c.is_whitespace())
+                .map_or(content.len(), |n| next_line + n);
+            let end = code[start..].find(';').map_or(content.len(), |n| start + n);
+            Allow {
+                file: file.to_string(),
+                rules: captures[1].split(',').map(|name| name.trim().to_string()).collect(),
+                lines: first_line + line_at(content, start) - 1..=first_line + line_at(content, end) - 1,
+            }
+        })
+        .collect()
+}
+
+/// `sql` with its `--` comments blanked out (offsets are kept).
+fn blank_sql_comments(sql: &str) -> String {
+    sql.split_inclusive('\n')
+        .map(|line| match line.find("--") {
+            Some(at) => {
+                let comment = &line[at..];
+                let newline = if comment.ends_with('\n') { "\n" } else { "" };
+                format!("{}{}{}", &line[..at], " ".repeat(comment.len() - newline.len()), newline)
+            }
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// The 1-based line of byte `offset` in `content`.
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::DiskFs;
+    use crate::utils;
+    use tempfile::TempDir;
+
+    /// Each rule with a SeaORM call and a SQL statement reporting it.
+    const RULES: &[(Rule, Option<&str>, &str)] = &[
+        (
+            Rule::DropTable,
+            Some(r#"manager.drop_table(Table::drop().table(Alias::new("old")).to_owned()).await?;"#),
+            "DROP TABLE old;",
+        ),
+        (
+            Rule::DropColumn,
+            Some(r#"manager.alter_table(Table::alter().table(Alias::new("posts")).drop_column(Alias::new("body")).to_owned()).await?;"#),
+            "ALTER TABLE posts DROP COLUMN body;",
+        ),
+        (
+            Rule::Truncate,
+            Some(r#"manager.truncate_table(Table::truncate().table(Alias::new("posts")).to_owned()).await?;"#),
+            "TRUNCATE posts;",
+        ),
+        (
+            Rule::RenameTable,
+            Some(r#"manager.rename_table(Table::rename().table(Alias::new("posts"), Alias::new("articles")).to_owned()).await?;"#),
+            "ALTER TABLE posts RENAME TO articles;",
+        ),
+        (
+            Rule::RenameColumn,
+            Some(r#"manager.alter_table(Table::alter().table(Alias::new("posts")).rename_column(Alias::new("body"), Alias::new("content")).to_owned()).await?;"#),
+            "ALTER TABLE posts RENAME COLUMN body TO content;",
+        ),
+        (
+            Rule::ChangeColumnType,
+            Some(r#"manager.alter_table(Table::alter().table(Alias::new("posts")).modify_column(ColumnDef::new(Alias::new("views")).big_integer()).to_owned()).await?;"#),
+            "ALTER TABLE posts ALTER COLUMN views TYPE bigint;",
+        ),
+        (
+            Rule::AddNotNullColumn,
+            Some(r#"manager.alter_table(Table::alter().table(Alias::new("posts")).add_column(ColumnDef::new(Alias::new("slug")).string().not_null()).to_owned()).await?;"#),
+            "ALTER TABLE posts ADD COLUMN slug text NOT NULL;",
+        ),
+        (Rule::SetNotNull, None, "ALTER TABLE posts ALTER COLUMN slug SET NOT NULL;"),
+        (
+            Rule::CreateIndex,
+            Some(r#"manager.create_index(Index::create().name("idx_slug").table(Alias::new("posts")).col(Alias::new("slug")).to_owned()).await?;"#),
+            "CREATE INDEX idx_slug ON posts (slug);",
+        ),
+        (
+            Rule::AddConstraint,
+            Some(r#"manager.create_foreign_key(ForeignKey::create().name("fk_author").from(Alias::new("posts"), Alias::new("author_id")).to(Alias::new("users"), Alias::new("id")).to_owned()).await?;"#),
+            "ALTER TABLE posts ADD CONSTRAINT fk_author FOREIGN KEY (author_id) REFERENCES users (id);",
+        ),
+    ];
+
+    fn migration(up: &str) -> String {
+        format!(
+            "use sea_orm_migration::prelude::*;\n\npub struct Migration;\n\n#[async_trait::async_trait]\nimpl MigrationTrait for Migration {{\n    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {{\n{}\n        Ok(())\n    }}\n\n    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {{\n        manager.drop_table(Table::drop().table(Alias::new(\"posts\")).to_owned()).await\n    }}\n}}\n",
+            up
+        )
+    }
+
+    fn project_with(files: &[(&str, &str)]) -> (TempDir, Project) {
+        let dir = TempDir::new().unwrap();
+        utils::write_file(
+            &DiskFs,
+            &dir.path().join("romance.toml"),
+            "[project]\nname = \"app\"\n\n[backend]\nport = 3000\ndatabase_url = \"postgres://localhost/app\"\n\n[frontend]\nport = 5173\napi_base_url = \"http://localhost:3000\"\n",
+        )
+        .unwrap();
+        for (path, content) in files {
+            utils::write_file(&DiskFs, &dir.path().join(path), content).unwrap();
+        }
+        let project = Project::open(dir.path()).unwrap();
+        (dir, project)
+    }
+
+    fn lint_up(up: &str) -> Vec<(usize, Rule)> {
+        let (_dir, project) = project_with(&[("backend/migration/src/m1_change.rs", &migration(up))]);
+        lint(&project, "m1_change").unwrap().into_iter().map(|f| (f.line, f.rule)).collect()
+    }
+
+    #[test]
+    fn every_rule_is_reported_in_sql() {
+        for (rule, _, sql) in RULES {
+            assert_eq!(check_sql(sql, false), vec![(0, *rule)], "{}", sql);
+        }
+        assert_eq!(check_sql("DELETE FROM posts;", false), vec![(0, Rule::Truncate)]);
+    }
+
+    #[test]
+    fn every_rule_is_reported_in_builder_calls() {
+        for (rule, call, _) in RULES {
+            if let Some(call) = call {
+                let rules: Vec<Rule> = builder_calls(call, false).into_iter().map(|(_, rule)| rule).collect();
+                assert_eq!(rules, vec![*rule], "{}", call);
+            }
+        }
+    }
+
+    #[test]
+    fn safe_forms_are_not_reported() {
+        for sql in [
+            "DELETE FROM posts WHERE id = 1;",
+            "CREATE INDEX CONCURRENTLY idx_slug ON posts (slug);",
+            "ALTER TABLE posts ADD CONSTRAINT fk FOREIGN KEY (author_id) REFERENCES users (id) NOT VALID;",
+            "ALTER TABLE posts ADD COLUMN views int NOT NULL DEFAULT 0;",
+            "ALTER TABLE posts RENAME CONSTRAINT a TO b;",
+        ] {
+            assert!(check_sql(sql, false).is_empty(), "{}", sql);
+        }
+        let call = r#".add_column(ColumnDef::new(Alias::new("views")).integer().not_null().default(0))"#;
+        assert!(builder_calls(call, false).is_empty());
+    }
+
+    #[test]
+    fn every_rule_can_be_allowed() {
+        for (rule, call, sql) in RULES {
+            let statement = match call {
+                Some(call) => call.to_string(),
+                None => format!("manager.get_connection().execute_unprepared(\"{}\").await?;", sql),
+            };
+            assert_eq!(lint_up(&statement).len(), 1, "{}", statement);
+            let allowed = format!("        // romance-lint: allow({})\n{}", rule.name(), statement);
+            assert!(lint_up(&allowed).is_empty(), "{}", allowed);
+        }
+    }
+
+    #[test]
+    fn allow_covers_only_the_next_statement() {
+        let up = r#"        // romance-lint: allow(drop_column, rename_column)
+        manager.alter_table(Table::alter().table(Alias::new("posts"))
+            .drop_column(Alias::new("body")).to_owned()).await?;
+        manager.alter_table(Table::alter().table(Alias::new("posts"))
+            .drop_column(Alias::new("summary")).to_owned()).await?;"#;
+        assert_eq!(lint_up(up), vec![(12, Rule::DropColumn)]);
+
+        // Rules not listed are still reported
+        let up = r#"        // romance-lint: allow(rename_column)
+        manager.alter_table(Table::alter().table(Alias::new("posts")).drop_column(Alias::new("body")).to_owned()).await?;"#;
+        assert_eq!(lint_up(up), vec![(9, Rule::DropColumn)]);
+    }
+
+    #[test]
+    fn sql_strings_use_sql_comments() {
+        let up = r#"        manager.get_connection().execute_unprepared("
+            -- romance-lint: allow(drop_table)
+            DROP TABLE old_posts;
+            DROP TABLE old_tags;
+        ").await?;"#;
+        assert_eq!(lint_up(up), vec![(11, Rule::DropTable)]);
+    }
+
+    #[test]
+    fn sql_files_are_checked_with_their_own_allows() {
+        let module = "m2_cleanup";
+        let rust = migration("        manager.get_connection().execute_unprepared(include_str!(\"../sql/m2_cleanup.up.sql\")).await?;")
+            .replace("        manager.drop_table(Table::drop().table(Alias::new(\"posts\")).to_owned()).await", "        manager.get_connection().execute_unprepared(include_str!(\"../sql/m2_cleanup.down.sql\")).await?;\n        Ok(())");
+        let (_dir, project) = project_with(&[
+            ("backend/migration/src/m2_cleanup.rs", &rust),
+            (
+                "backend/migration/sql/m2_cleanup.up.sql",
+                "-- romance-lint: allow(drop_table)\nDROP TABLE old_posts;\nDROP TABLE old_tags;\nALTER TABLE posts\n  DROP COLUMN body;\n",
+            ),
+            ("backend/migration/sql/m2_cleanup.down.sql", "DROP TABLE posts;\n"),
+        ]);
+
+        let findings = lint(&project, module).unwrap();
+        let found: Vec<(&str, usize, Rule)> = findings.iter().map(|f| (f.file.as_str(), f.line, f.rule)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("backend/migration/sql/m2_cleanup.up.sql", 3, Rule::DropTable),
+                ("backend/migration/sql/m2_cleanup.up.sql", 4, Rule::DropColumn),
+            ]
+        );
+    }
+
+    #[test]
+    fn constraints_on_tables_the_migration_creates_are_not_reported() {
+        let sql = "CREATE TABLE tags (id serial PRIMARY KEY, post_id int);\nALTER TABLE tags ADD CONSTRAINT fk FOREIGN KEY (post_id) REFERENCES posts (id);";
+        assert!(check_sql(sql, true).is_empty());
+        assert_eq!(check_sql(sql, false), vec![(1, Rule::AddConstraint)]);
+
+        let body = "manager.create_foreign_key(ForeignKey::create().name(\"fk\").to_owned()).await?;";
+        assert!(builder_calls(body, true).is_empty());
+        assert_eq!(builder_calls(body, false), vec![(8, Rule::AddConstraint)]);
+    }
+}
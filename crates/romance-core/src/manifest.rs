@@ -1,3 +1,5 @@
+use crate::fs::ProjectFs;
+use crate::project::Project;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -11,6 +13,17 @@ pub struct Manifest {
     pub updated_at: String,
     pub project_name: String,
     pub files: BTreeMap<String, FileRecord>,
+    /// Templates copied into `.romance/templates/` with `romance template eject`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ejected_templates: BTreeMap<String, EjectedTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EjectedTemplate {
+    /// Hash of the embedded template at the time it was ejected (or last reviewed).
+    pub upstream_hash: String,
+    pub ejected_at: String,
+    pub romance_version: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +45,21 @@ pub enum FileCategory {
     Entity,
     Marker,
     Static,
+    Recipe,
+    Auth,
+    Addon,
+    /// Migrations from `romance generate migration`, written to be filled in.
+    Migration,
+}
+
+/// A file as a generator rendered it, before anything else touched it.
+#[derive(Debug, Clone)]
+pub struct RenderedFile {
+    pub output: String,
+    pub template: Option<String>,
+    pub category: FileCategory,
+    pub entity_name: Option<String>,
+    pub content: String,
 }
 
 impl Manifest {
@@ -43,27 +71,28 @@ impl Manifest {
             updated_at: now,
             project_name: project_name.to_string(),
             files: BTreeMap::new(),
+            ejected_templates: BTreeMap::new(),
         }
     }
 
-    pub fn load(project_dir: &Path) -> Result<Self> {
+    pub fn load(fs: &dyn ProjectFs, project_dir: &Path) -> Result<Self> {
         let path = project_dir.join(".romance/manifest.json");
-        let content = std::fs::read_to_string(&path)?;
+        let content = fs.read_to_string(&path)?;
         let manifest: Manifest = serde_json::from_str(&content)?;
         Ok(manifest)
     }
 
-    pub fn save(&self, project_dir: &Path) -> Result<()> {
+    pub fn save(&self, fs: &dyn ProjectFs, project_dir: &Path) -> Result<()> {
         let dir = project_dir.join(".romance");
-        std::fs::create_dir_all(&dir)?;
+        fs.create_dir_all(&dir)?;
         let path = dir.join("manifest.json");
         let content = serde_json::to_string_pretty(self)?;
-        std::fs::write(&path, content)?;
+        fs.write(&path, &content)?;
         Ok(())
     }
 
-    pub fn exists(project_dir: &Path) -> bool {
-        project_dir.join(".romance/manifest.json").exists()
+    pub fn exists(fs: &dyn ProjectFs, project_dir: &Path) -> bool {
+        fs.exists(&project_dir.join(".romance/manifest.json"))
     }
 
     pub fn record_file(
@@ -88,6 +117,81 @@ impl Manifest {
     }
 }
 
+/// Where the last generated content of each tracked file is kept, as the
+/// common ancestor for three-way merges in `romance update`.
+pub const BASE_DIR: &str = ".romance/base";
+
+/// Keep `content` as the generated base of `output_path`.
+pub fn save_base(fs: &dyn ProjectFs, project_dir: &Path, output_path: &str, content: &str) -> Result<()> {
+    let path = project_dir.join(BASE_DIR).join(output_path);
+    if let Some(parent) = path.parent() {
+        fs.create_dir_all(parent)?;
+    }
+    fs.write(&path, content)?;
+    Ok(())
+}
+
+/// The generated base of `output_path`, if one was kept and it is the
+/// content the manifest says was generated.
+pub fn load_base(fs: &dyn ProjectFs, project_dir: &Path, output_path: &str, generated_hash: &str) -> Option<String> {
+    let content = fs.read_to_string(&project_dir.join(BASE_DIR).join(output_path)).ok()?;
+    (content_hash(&content) == generated_hash).then_some(content)
+}
+
+/// Record freshly generated files and keep their bases, so `romance update`
+/// can later merge template changes into them. Does nothing in projects
+/// without a manifest.
+pub fn track_generated(project: &Project, files: &[RenderedFile]) -> Result<()> {
+    let fs = project.fs();
+    let project_dir = project.root();
+    if !Manifest::exists(fs, project_dir) || files.is_empty() {
+        return Ok(());
+    }
+    let mut manifest = Manifest::load(fs, project_dir)?;
+    for file in files {
+        manifest.record_file(
+            &file.output,
+            file.template.as_deref(),
+            file.category.clone(),
+            &file.content,
+            file.entity_name.as_deref(),
+        );
+        save_base(fs, project_dir, &file.output, &file.content)?;
+    }
+    manifest.save(fs, project_dir)
+}
+
+/// Record `content`, which romance wrote over `previous` in a tracked file,
+/// as the file's generated content, so it isn't taken for a user edit. Files
+/// already edited by the user, untracked files and projects without a
+/// manifest are left alone.
+pub fn refresh(fs: &dyn ProjectFs, project_dir: &Path, output_path: &str, previous: &str, content: &str) -> Result<()> {
+    if !Manifest::exists(fs, project_dir) {
+        return Ok(());
+    }
+    let mut manifest = Manifest::load(fs, project_dir)?;
+    let Some(record) = manifest.files.get_mut(output_path) else {
+        return Ok(());
+    };
+    let base = load_base(fs, project_dir, output_path, &record.generated_hash);
+    if is_modified(previous, record, base.as_deref()) {
+        return Ok(());
+    }
+    record.generated_hash = content_hash(content);
+    manifest.save(fs, project_dir)?;
+    save_base(fs, project_dir, output_path, content)
+}
+
+/// Whether `current` was edited since it was generated as `record`. When the
+/// generated base was kept, only the part above `ROMANCE:CUSTOM` counts:
+/// the custom block is the user's to edit.
+pub fn is_modified(current: &str, record: &FileRecord, base: Option<&str>) -> bool {
+    match base {
+        Some(base) => crate::utils::generated_section(current) != crate::utils::generated_section(base),
+        None => content_hash(current) != record.generated_hash,
+    }
+}
+
 /// Compute SHA-256 hex digest of content.
 pub fn content_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
@@ -0,0 +1,242 @@
+//! Line-based three-way merge for `romance update`.
+//!
+//! The base is what romance generated last time (kept in `.romance/base/`),
+//! "yours" is the file as the user left it, and "template" is what the new
+//! templates render. Changes that don't overlap are combined; overlapping
+//! hunks that differ are written with git-style conflict markers.
+
+use similar::{DiffOp, TextDiff};
+
+pub const MARKER_YOURS: &str = "<<<<<<< yours";
+pub const MARKER_SEPARATOR: &str = "=======";
+pub const MARKER_TEMPLATE: &str = ">>>>>>> template";
+
+/// The result of merging one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    pub content: String,
+    /// Number of conflicting hunks written with markers.
+    pub conflicts: usize,
+}
+
+impl Merge {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts == 0
+    }
+}
+
+/// Which side of a conflict to keep when resolving it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Yours,
+    Template,
+}
+
+/// A change one side made to a range of base lines.
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+    yours: bool,
+}
+
+fn hunks<'a>(base: &str, side: &'a str, yours: bool) -> Vec<Hunk<'a>> {
+    let side_lines: Vec<&str> = side.split_inclusive('\n').collect();
+    TextDiff::from_lines(base, side)
+        .ops()
+        .iter()
+        .filter(|op| !matches!(op, DiffOp::Equal { .. }))
+        .map(|op| {
+            let (old, new) = (op.old_range(), op.new_range());
+            Hunk {
+                start: old.start,
+                end: old.end,
+                lines: side_lines[new].to_vec(),
+                yours,
+            }
+        })
+        .collect()
+}
+
+/// One side's version of base lines `start..end`, given its hunks in that range.
+fn region(base: &[&str], start: usize, end: usize, hunks: &[&Hunk]) -> String {
+    let mut out = String::new();
+    let mut pos = start;
+    for hunk in hunks {
+        out.extend(base[pos..hunk.start].iter().copied());
+        out.extend(hunk.lines.iter().copied());
+        pos = hunk.end;
+    }
+    out.extend(base[pos..end].iter().copied());
+    out
+}
+
+fn push_conflict_side(out: &mut String, text: &str) {
+    out.push_str(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Merge `yours` and `template`, both derived from `base`.
+pub fn merge3(base: &str, yours: &str, template: &str) -> Merge {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let mut all: Vec<Hunk> = hunks(base, yours, true);
+    all.extend(hunks(base, template, false));
+    all.sort_by_key(|h| (h.start, h.end));
+
+    let mut content = String::with_capacity(yours.len().max(template.len()));
+    let mut conflicts = 0;
+    let mut pos = 0;
+    let mut i = 0;
+    while i < all.len() {
+        // Group hunks whose base ranges overlap (or insert at the same spot)
+        let start = all[i].start;
+        let mut end = all[i].end;
+        let mut j = i + 1;
+        while j < all.len() && (all[j].start < end || all[j].start == start) {
+            end = end.max(all[j].end);
+            j += 1;
+        }
+        let group = &all[i..j];
+        content.extend(base_lines[pos..start].iter().copied());
+
+        let ours: Vec<&Hunk> = group.iter().filter(|h| h.yours).collect();
+        let theirs: Vec<&Hunk> = group.iter().filter(|h| !h.yours).collect();
+        let yours_text = region(&base_lines, start, end, &ours);
+        let template_text = region(&base_lines, start, end, &theirs);
+        if ours.is_empty() {
+            content.push_str(&template_text);
+        } else if theirs.is_empty() || yours_text == template_text {
+            content.push_str(&yours_text);
+        } else {
+            conflicts += 1;
+            content.push_str(MARKER_YOURS);
+            content.push('\n');
+            push_conflict_side(&mut content, &yours_text);
+            content.push_str(MARKER_SEPARATOR);
+            content.push('\n');
+            push_conflict_side(&mut content, &template_text);
+            content.push_str(MARKER_TEMPLATE);
+            content.push('\n');
+        }
+        pos = end;
+        i = j;
+    }
+    content.extend(base_lines[pos..].iter().copied());
+    Merge { content, conflicts }
+}
+
+/// Number of unresolved conflict blocks in `content`.
+pub fn count_conflicts(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|l| l.trim_end() == MARKER_YOURS)
+        .count()
+}
+
+/// Resolve every conflict block in `content` by keeping one side.
+pub fn resolve(content: &str, keep: Side) -> String {
+    enum State {
+        Outside,
+        Yours,
+        Template,
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut state = State::Outside;
+    for line in content.split_inclusive('\n') {
+        let marker = line.trim_end();
+        state = match (state, marker) {
+            (State::Outside, MARKER_YOURS) => State::Yours,
+            (State::Yours, MARKER_SEPARATOR) => State::Template,
+            (State::Template, MARKER_TEMPLATE) => State::Outside,
+            (state, _) => {
+                let kept = match state {
+                    State::Outside => true,
+                    State::Yours => keep == Side::Yours,
+                    State::Template => keep == Side::Template,
+                };
+                if kept {
+                    out.push_str(line);
+                }
+                state
+            }
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "a\nb\nc\nd\ne\n";
+
+    #[test]
+    fn unchanged_sides_merge_to_base() {
+        let merge = merge3(BASE, BASE, BASE);
+        assert!(merge.is_clean());
+        assert_eq!(merge.content, BASE);
+    }
+
+    #[test]
+    fn non_overlapping_changes_are_combined() {
+        let yours = "a\nB\nc\nd\ne\n";
+        let template = "a\nb\nc\nd\nE\nf\n";
+        let merge = merge3(BASE, yours, template);
+        assert!(merge.is_clean());
+        assert_eq!(merge.content, "a\nB\nc\nd\nE\nf\n");
+    }
+
+    #[test]
+    fn identical_changes_are_not_conflicts() {
+        let both = "a\nx\nc\nd\ne\n";
+        let merge = merge3(BASE, both, both);
+        assert!(merge.is_clean());
+        assert_eq!(merge.content, both);
+    }
+
+    #[test]
+    fn overlapping_changes_get_markers() {
+        let yours = "a\nmine\nc\nd\ne\n";
+        let template = "a\ntheirs\nc\nd\ne\n";
+        let merge = merge3(BASE, yours, template);
+        assert_eq!(merge.conflicts, 1);
+        assert_eq!(
+            merge.content,
+            "a\n<<<<<<< yours\nmine\n=======\ntheirs\n>>>>>>> template\nc\nd\ne\n"
+        );
+        assert_eq!(count_conflicts(&merge.content), 1);
+    }
+
+    #[test]
+    fn insertions_at_the_same_spot_conflict() {
+        let yours = "a\nb\nmine\nc\nd\ne\n";
+        let template = "a\nb\ntheirs\nc\nd\ne\n";
+        let merge = merge3(BASE, yours, template);
+        assert_eq!(merge.conflicts, 1);
+    }
+
+    #[test]
+    fn deletion_next_to_other_change_merges() {
+        let yours = "a\nc\nd\ne\n";
+        let template = "a\nb\nC\nd\ne\n";
+        let merge = merge3(BASE, yours, template);
+        assert!(merge.is_clean(), "{}", merge.content);
+        assert_eq!(merge.content, "a\nC\nd\ne\n");
+    }
+
+    #[test]
+    fn missing_trailing_newline_keeps_markers_on_own_lines() {
+        let merge = merge3("a\nb", "a\nmine", "a\ntheirs");
+        assert_eq!(merge.content, "a\n<<<<<<< yours\nmine\n=======\ntheirs\n>>>>>>> template\n");
+    }
+
+    #[test]
+    fn resolve_keeps_the_chosen_side() {
+        let merged = merge3(BASE, "a\nmine\nc\nd\ne\n", "a\ntheirs\nc\nd\ne\n").content;
+        assert_eq!(resolve(&merged, Side::Yours), "a\nmine\nc\nd\ne\n");
+        assert_eq!(resolve(&merged, Side::Template), "a\ntheirs\nc\nd\ne\n");
+        assert_eq!(count_conflicts(&resolve(&merged, Side::Yours)), 0);
+    }
+}
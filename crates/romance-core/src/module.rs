@@ -0,0 +1,344 @@
+//! Entity modules: namespaces grouping related entities.
+//!
+//! An entity generated with `--module billing` lives in
+//! `backend/src/{entities,handlers,routes}/billing/` and
+//! `frontend/src/features/billing/`, is routed under the module's prefix and
+//! gets the module's table prefix or Postgres schema. Modules are declared in
+//! `romance.toml` by `romance generate module`. Entities without a module stay
+//! at the top level, and every helper here renders them exactly as before.
+
+use crate::config::ModuleConfig;
+use crate::entity::EntityDefinition;
+use crate::error::Failure;
+use crate::fs::ProjectFs;
+use crate::generator::context::{self, markers};
+use crate::inject;
+use crate::project::Project;
+use crate::relation;
+use crate::utils;
+use anyhow::Result;
+use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
+use std::path::{Path, PathBuf};
+
+/// A module declared in `romance.toml`.
+#[derive(Debug, Clone)]
+pub struct EntityModule {
+    pub name: String,
+    pub config: ModuleConfig,
+}
+
+impl EntityModule {
+    /// The module `name` declared in the project's `romance.toml`.
+    pub fn load(project: &Project, name: &str) -> Result<Self> {
+        let config = project.config().modules.get(name).cloned().ok_or_else(|| {
+            Failure::Precondition.error(format!(
+                "Module '{}' doesn't exist. Run: romance generate module {}",
+                name, name
+            ))
+        })?;
+        Ok(Self {
+            name: name.to_string(),
+            config,
+        })
+    }
+
+    /// Route prefix after the API prefix, e.g. `/billing`.
+    pub fn prefix(&self) -> String {
+        let prefix = self.config.prefix.clone().unwrap_or_else(|| self.name.clone());
+        format!("/{}", prefix.trim_matches('/'))
+    }
+
+    /// Postgres schema holding the module's tables, if it has one.
+    pub fn schema(&self) -> Option<&str> {
+        self.config.schema.as_deref()
+    }
+
+    /// PascalCase name, used as its OpenAPI tag and sidebar section.
+    pub fn title(&self) -> String {
+        self.name.to_pascal_case()
+    }
+
+    /// The module's `mod.rs` in `backend/src/<dir>`.
+    pub fn mod_file(&self, backend_src: &Path, dir: &str) -> PathBuf {
+        backend_src.join(dir).join(&self.name).join("mod.rs")
+    }
+
+    /// Marker in `AppSidebar.tsx` closing the module's sidebar section.
+    pub fn nav_marker(&self) -> String {
+        format!("{{/* === ROMANCE:NAV_LINKS:{} === */}}", self.name)
+    }
+}
+
+/// Check that `name` can be used as a module name: a snake_case Rust
+/// identifier that isn't a keyword.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && name.to_snake_case() == name
+        && utils::rust_ident(name) == name;
+    if !valid {
+        return Err(Failure::InvalidInput.error(format!(
+            "Invalid module name '{}': use a snake_case name such as 'billing'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// The module `entity` is generated in, if it has one.
+pub fn of_entity(project: &Project, entity: &EntityDefinition) -> Result<Option<EntityModule>> {
+    entity
+        .module
+        .as_deref()
+        .map(|name| EntityModule::load(project, name))
+        .transpose()
+}
+
+/// The module an existing entity was generated in. `None` for top-level
+/// entities and for entities that don't exist.
+pub fn locate(project: &Project, entity_name: &str) -> Option<EntityModule> {
+    let snake = entity_name.to_snake_case();
+    let entities = project.backend_src().join("entities");
+    project
+        .config()
+        .modules
+        .iter()
+        .find(|(name, _)| project.fs().exists(&entities.join(name).join(format!("{}.rs", snake))))
+        .map(|(name, config)| EntityModule {
+            name: name.clone(),
+            config: config.clone(),
+        })
+}
+
+/// The module of `target`, an entity related to one in `source`: where it
+/// was generated, or `source` itself if it doesn't exist yet.
+pub fn of_target(project: &Project, source: Option<&EntityModule>, target: &str) -> Option<EntityModule> {
+    let top_level = backend_file(None, "entities", &target.to_snake_case());
+    if project.fs().exists(&project.join(top_level)) {
+        return None;
+    }
+    locate(project, target).or_else(|| source.cloned())
+}
+
+/// Snake_case names of the entities generated in `module`.
+pub fn entities(project: &Project, module: &EntityModule) -> Result<Vec<String>> {
+    let dir = project.backend_src().join("entities").join(&module.name);
+    if !project.fs().exists(&dir) {
+        return Ok(Vec::new());
+    }
+    Ok(project
+        .fs()
+        .read_dir(&dir)?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .filter(|name| name != "mod")
+        .collect())
+}
+
+/// Every declared module.
+pub fn all(project: &Project) -> Vec<EntityModule> {
+    project
+        .config()
+        .modules
+        .iter()
+        .map(|(name, config)| EntityModule {
+            name: name.clone(),
+            config: config.clone(),
+        })
+        .collect()
+}
+
+/// Every generated entity as `(module, snake_case name)`: the top-level
+/// entities, then each module's.
+pub fn all_entities(project: &Project) -> Result<Vec<(Option<EntityModule>, String)>> {
+    let mut found: Vec<(Option<EntityModule>, String)> = relation::discover_entities(project)?
+        .into_iter()
+        .map(|name| (None, name))
+        .collect();
+    for m in all(project) {
+        found.extend(entities(project, &m)?.into_iter().map(|name| (Some(m.clone()), name)));
+    }
+    Ok(found)
+}
+
+/// Register an entity's backend modules: in the top-level `mod.rs` files and
+/// `create_router` for top-level entities, in the module's otherwise.
+pub fn register_entity(
+    fs: &dyn ProjectFs,
+    backend_src: &Path,
+    module: Option<&EntityModule>,
+    snake: &str,
+) -> Result<()> {
+    let Some(m) = module else {
+        return context::register_backend_module(fs, backend_src, snake);
+    };
+    let routes_mod = m.mod_file(backend_src, "routes");
+    inject::insert(fs, &routes_mod, markers::ROUTES, &format!("        .merge({}::router())", snake))?;
+    for dir in ["routes", "entities", "handlers"] {
+        inject::insert(fs, &m.mod_file(backend_src, dir), markers::MODS, &format!("pub mod {};", snake))?;
+    }
+    Ok(())
+}
+
+/// The `mod.rs` in `backend/src/<dir>` an entity of `module` is declared in.
+pub fn mod_rs(module: Option<&EntityModule>, backend_src: &Path, dir: &str) -> PathBuf {
+    match module {
+        Some(m) => m.mod_file(backend_src, dir),
+        None => backend_src.join(dir).join("mod.rs"),
+    }
+}
+
+/// Path of an entity's module below `crate::entities` or `crate::handlers`,
+/// e.g. `billing::invoice`.
+pub fn rust_path(module: Option<&EntityModule>, snake: &str) -> String {
+    match module {
+        Some(m) => format!("{}::{}", m.name, snake),
+        None => snake.to_string(),
+    }
+}
+
+/// Whether `a` and `b` are the same module, or both the top level.
+pub fn same(a: Option<&EntityModule>, b: Option<&EntityModule>) -> bool {
+    a.map(|m| &m.name) == b.map(|m| &m.name)
+}
+
+/// Path from a model in `from` to the model `target_snake` in `to`, as in
+/// `super::invoice::Entity`.
+pub fn model_path(from: Option<&EntityModule>, to: Option<&EntityModule>, target_snake: &str) -> String {
+    if same(from, to) {
+        format!("super::{}", target_snake)
+    } else {
+        format!("crate::entities::{}", rust_path(to, target_snake))
+    }
+}
+
+/// An entity's file in `backend/src/<dir>`, relative to the project root.
+pub fn backend_file(module: Option<&EntityModule>, dir: &str, snake: &str) -> String {
+    match module {
+        Some(m) => format!("backend/src/{}/{}/{}.rs", dir, m.name, snake),
+        None => format!("backend/src/{}/{}.rs", dir, snake),
+    }
+}
+
+/// An entity's feature directory below `frontend/src/features`, e.g.
+/// `billing/invoice`, as used in `@/features/billing/invoice/api` imports.
+pub fn feature_path(module: Option<&EntityModule>, entity_name: &str) -> String {
+    let camel = entity_name.to_lower_camel_case();
+    match module {
+        Some(m) => format!("{}/{}", m.name, camel),
+        None => camel,
+    }
+}
+
+/// The entity's table name, with the module's table prefix.
+pub fn table_name(module: Option<&EntityModule>, snake: &str) -> String {
+    let prefix = module
+        .and_then(|m| m.config.table_prefix.as_deref())
+        .unwrap_or_default();
+    format!("{}{}", prefix, utils::pluralize(snake))
+}
+
+/// A sea-query table reference to `table` (a Rust expression), qualified with
+/// the module's schema if it has one.
+pub fn table_ref(module: Option<&EntityModule>, table: &str) -> String {
+    match module.and_then(EntityModule::schema) {
+        Some(schema) => format!("(Alias::new(\"{}\"), {})", schema, table),
+        None => table.to_string(),
+    }
+}
+
+/// The entity's API route prefix: the project's API prefix, then the module's.
+pub fn route_prefix(module: Option<&EntityModule>, api_prefix: &str) -> String {
+    match module {
+        Some(m) => format!("{}{}", api_prefix, m.prefix()),
+        None => api_prefix.to_string(),
+    }
+}
+
+/// The entity's path in API and frontend URLs, without a leading slash, e.g.
+/// `billing/invoices`.
+pub fn url_path(module: Option<&EntityModule>, snake: &str) -> String {
+    let plural = utils::pluralize(snake);
+    match module {
+        Some(m) => format!("{}/{}", m.prefix().trim_start_matches('/'), plural),
+        None => plural,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn billing(table_prefix: Option<&str>, schema: Option<&str>) -> EntityModule {
+        EntityModule {
+            name: "billing".to_string(),
+            config: ModuleConfig {
+                prefix: None,
+                table_prefix: table_prefix.map(str::to_string),
+                schema: schema.map(str::to_string),
+            },
+        }
+    }
+
+    #[test]
+    fn top_level_entities_keep_their_paths() {
+        assert_eq!(rust_path(None, "invoice"), "invoice");
+        assert_eq!(backend_file(None, "entities", "invoice"), "backend/src/entities/invoice.rs");
+        assert_eq!(feature_path(None, "InvoiceLine"), "invoiceLine");
+        assert_eq!(table_name(None, "invoice"), "invoices");
+        assert_eq!(table_ref(None, "Invoice::Table"), "Invoice::Table");
+        assert_eq!(route_prefix(None, "/api"), "/api");
+        assert_eq!(url_path(None, "invoice"), "invoices");
+        assert_eq!(model_path(None, None, "user"), "super::user");
+    }
+
+    #[test]
+    fn module_entities_are_namespaced() {
+        let m = billing(Some("billing_"), None);
+        assert_eq!(rust_path(Some(&m), "invoice"), "billing::invoice");
+        assert_eq!(
+            backend_file(Some(&m), "handlers", "invoice"),
+            "backend/src/handlers/billing/invoice.rs"
+        );
+        assert_eq!(feature_path(Some(&m), "InvoiceLine"), "billing/invoiceLine");
+        assert_eq!(table_name(Some(&m), "invoice"), "billing_invoices");
+        assert_eq!(route_prefix(Some(&m), "/api"), "/api/billing");
+        assert_eq!(url_path(Some(&m), "invoice"), "billing/invoices");
+    }
+
+    #[test]
+    fn schema_qualifies_table_refs() {
+        let m = billing(None, Some("billing"));
+        assert_eq!(table_name(Some(&m), "invoice"), "invoices");
+        assert_eq!(
+            table_ref(Some(&m), "Invoice::Table"),
+            "(Alias::new(\"billing\"), Invoice::Table)"
+        );
+    }
+
+    #[test]
+    fn model_paths_cross_modules_through_the_crate() {
+        let m = billing(None, None);
+        assert_eq!(model_path(Some(&m), Some(&m), "invoice"), "super::invoice");
+        assert_eq!(model_path(Some(&m), None, "user"), "crate::entities::user");
+        assert_eq!(model_path(None, Some(&m), "invoice"), "crate::entities::billing::invoice");
+    }
+
+    #[test]
+    fn custom_prefix_is_normalized() {
+        let mut m = billing(None, None);
+        m.config.prefix = Some("finance/billing/".to_string());
+        assert_eq!(m.prefix(), "/finance/billing");
+        assert_eq!(url_path(Some(&m), "invoice"), "finance/billing/invoices");
+    }
+
+    #[test]
+    fn validate_name_rejects_non_identifiers() {
+        assert!(validate_name("billing").is_ok());
+        assert!(validate_name("user_admin2").is_ok());
+        for bad in ["Billing", "2fa", "my-module", "type", ""] {
+            assert!(validate_name(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! A handle on a Romance project, passed to every generator and addon.
+//!
+//! Generators used to resolve `backend/src`, `romance.toml` and friends
+//! against the current directory. They now take a [`Project`], which knows its
+//! root and carries what was loaded from it, so romance-core can work on any
+//! number of projects in one process, whatever the current directory is.
+//! Every file is read and written through the project's [`ProjectFs`], which
+//! need not be the disk.
+
+use crate::config::RomanceConfig;
+use crate::error::Failure;
+use crate::fs::{DiskFs, ProjectFs};
+use crate::generator::context::ProjectFeatures;
+use crate::manifest::Manifest;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub struct Project {
+    root: PathBuf,
+    fs: Arc<dyn ProjectFs>,
+    config: RomanceConfig,
+    features: ProjectFeatures,
+}
+
+impl Project {
+    /// Open the project at `root` on disk, loading its `romance.toml`.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        Self::open_in(Arc::new(DiskFs), root)
+    }
+
+    /// Open the project at `root` in `fs`, loading its `romance.toml`.
+    pub fn open_in(fs: Arc<dyn ProjectFs>, root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        if !fs.exists(&root.join("romance.toml")) {
+            return Err(Failure::NotAProject.error(
+                "Not a Romance project (romance.toml not found)",
+            ));
+        }
+        let config = RomanceConfig::load(&*fs, &root)
+            .with_context(|| format!("Failed to load {}", root.join("romance.toml").display()))?;
+        let features = ProjectFeatures::load(&*fs, &root);
+        Ok(Self {
+            root,
+            fs,
+            config,
+            features,
+        })
+    }
+
+    /// Re-read `romance.toml` and the feature flags, after a step that may
+    /// have changed them (generating auth, installing an addon).
+    pub fn reload(&mut self) -> Result<()> {
+        *self = Self::open_in(self.fs.clone(), std::mem::take(&mut self.root))?;
+        Ok(())
+    }
+
+    /// The filesystem the project lives in.
+    pub fn fs(&self) -> &dyn ProjectFs {
+        &*self.fs
+    }
+
+    /// The project directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// `path`, relative to the project directory.
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// `path` relative to the project directory, for showing to the user.
+    /// Paths outside the project are returned unchanged.
+    pub fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    /// `backend/src`.
+    pub fn backend_src(&self) -> PathBuf {
+        self.root.join("backend/src")
+    }
+
+    /// `frontend/src`.
+    pub fn frontend_src(&self) -> PathBuf {
+        self.root.join("frontend/src")
+    }
+
+    /// `backend/migration/src`.
+    pub fn migration_src(&self) -> PathBuf {
+        self.root.join("backend/migration/src")
+    }
+
+    /// The configuration loaded from `romance.toml`.
+    pub fn config(&self) -> &RomanceConfig {
+        &self.config
+    }
+
+    /// Feature flags, as of opening or the last [`reload`](Self::reload).
+    pub fn features(&self) -> &ProjectFeatures {
+        &self.features
+    }
+
+    /// The generation manifest, if the project has one. Read from disk on
+    /// every call, since generators record files in it as they go.
+    pub fn manifest(&self) -> Result<Option<Manifest>> {
+        if !Manifest::exists(self.fs(), &self.root) {
+            return Ok(None);
+        }
+        Manifest::load(self.fs(), &self.root).map(Some)
+    }
+}
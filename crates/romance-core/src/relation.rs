@@ -1,21 +1,19 @@
+use crate::project::Project;
 use anyhow::Result;
 use heck::ToSnakeCase;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::Path;
 
 /// Discover existing entity module names by scanning backend/src/entities/ directory.
-pub fn discover_entities(project_root: &Path) -> Result<Vec<String>> {
-    let entities_dir = project_root.join("backend/src/entities");
+/// Entities generated in a module are listed by [`crate::module::entities`].
+pub fn discover_entities(project: &Project) -> Result<Vec<String>> {
+    let entities_dir = project.backend_src().join("entities");
     let mut entities = Vec::new();
 
-    if !entities_dir.exists() {
+    if !project.fs().exists(&entities_dir) {
         return Ok(entities);
     }
 
-    for entry in fs::read_dir(&entities_dir)? {
-        let entry = entry?;
-        let path = entry.path();
+    for path in project.fs().read_dir(&entities_dir)? {
         if let Some(ext) = path.extension() {
             if ext == "rs" {
                 if let Some(stem) = path.file_stem() {
@@ -31,12 +29,13 @@ pub fn discover_entities(project_root: &Path) -> Result<Vec<String>> {
     Ok(entities)
 }
 
-/// Check if an entity exists on disk.
-pub fn entity_exists(project_root: &Path, entity_name: &str) -> bool {
+/// Check if an entity exists in the project, at the top level or in a module.
+pub fn entity_exists(project: &Project, entity_name: &str) -> bool {
     let snake = entity_name.to_snake_case();
-    project_root
-        .join(format!("backend/src/entities/{}.rs", snake))
-        .exists()
+    project
+        .fs()
+        .exists(&project.backend_src().join(format!("entities/{}.rs", snake)))
+        || crate::module::locate(project, entity_name).is_some()
 }
 
 /// Get the junction table name for two entities (alphabetical order).
@@ -61,9 +60,9 @@ pub struct PendingRelation {
 const PENDING_FILE: &str = ".romance/pending_relations.json";
 
 /// Store a pending relation for later application.
-pub fn store_pending(project_root: &Path, pending: PendingRelation) -> Result<()> {
-    let path = project_root.join(PENDING_FILE);
-    let mut pendings = load_pending(project_root)?;
+pub fn store_pending(project: &Project, pending: PendingRelation) -> Result<()> {
+    let path = project.join(PENDING_FILE);
+    let mut pendings = load_pending(project)?;
 
     // Avoid duplicates
     let already = pendings.iter().any(|p| {
@@ -78,26 +77,26 @@ pub fn store_pending(project_root: &Path, pending: PendingRelation) -> Result<()
     pendings.push(pending);
 
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        project.fs().create_dir_all(parent)?;
     }
-    fs::write(&path, serde_json::to_string_pretty(&pendings)?)?;
+    project.fs().write(&path, &serde_json::to_string_pretty(&pendings)?)?;
     Ok(())
 }
 
 /// Load all pending relations.
-pub fn load_pending(project_root: &Path) -> Result<Vec<PendingRelation>> {
-    let path = project_root.join(PENDING_FILE);
-    if !path.exists() {
+pub fn load_pending(project: &Project) -> Result<Vec<PendingRelation>> {
+    let path = project.join(PENDING_FILE);
+    if !project.fs().exists(&path) {
         return Ok(Vec::new());
     }
-    let content = fs::read_to_string(&path)?;
+    let content = project.fs().read_to_string(&path)?;
     let pendings: Vec<PendingRelation> = serde_json::from_str(&content)?;
     Ok(pendings)
 }
 
 /// Remove resolved pending relations for a given target entity and return them.
-pub fn take_pending_for(project_root: &Path, target_entity: &str) -> Result<Vec<PendingRelation>> {
-    let mut all = load_pending(project_root)?;
+pub fn take_pending_for(project: &Project, target_entity: &str) -> Result<Vec<PendingRelation>> {
+    let mut all = load_pending(project)?;
     let target_snake = target_entity.to_snake_case();
 
     let (matched, remaining): (Vec<_>, Vec<_>) = all.drain(..).partition(|p| {
@@ -105,13 +104,57 @@ pub fn take_pending_for(project_root: &Path, target_entity: &str) -> Result<Vec<
     });
 
     if !matched.is_empty() {
-        let path = project_root.join(PENDING_FILE);
+        let path = project.join(PENDING_FILE);
         if remaining.is_empty() {
-            let _ = fs::remove_file(&path);
+            let _ = project.fs().remove_file(&path);
         } else {
-            fs::write(&path, serde_json::to_string_pretty(&remaining)?)?;
+            project.fs().write(&path, &serde_json::to_string_pretty(&remaining)?)?;
         }
     }
 
     Ok(matched)
 }
+
+/// Remove pending many-to-many relations between two entities, in either
+/// direction. Returns `true` if any were removed.
+pub fn remove_pending_between(project: &Project, entity_a: &str, entity_b: &str) -> Result<bool> {
+    let mut all = load_pending(project)?;
+    let (a, b) = (entity_a.to_snake_case(), entity_b.to_snake_case());
+
+    let (matched, remaining): (Vec<_>, Vec<_>) = all.drain(..).partition(|p| {
+        let (source, target) = (p.source_entity.to_snake_case(), p.target_entity.to_snake_case());
+        p.relation_type == "ManyToMany"
+            && ((source == a && target == b) || (source == b && target == a))
+    });
+
+    if !matched.is_empty() {
+        let path = project.join(PENDING_FILE);
+        if remaining.is_empty() {
+            let _ = project.fs().remove_file(&path);
+        } else {
+            project.fs().write(&path, &serde_json::to_string_pretty(&remaining)?)?;
+        }
+    }
+
+    Ok(!matched.is_empty())
+}
+
+/// Point pending relations from or to `from` at `to` instead. Returns `true`
+/// if any changed.
+pub fn rename_pending(project: &Project, from: &str, to: &str) -> Result<bool> {
+    let mut all = load_pending(project)?;
+    let from_snake = from.to_snake_case();
+    let mut changed = false;
+    for pending in &mut all {
+        for entity in [&mut pending.source_entity, &mut pending.target_entity] {
+            if entity.to_snake_case() == from_snake {
+                *entity = to.to_string();
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        project.fs().write(&project.join(PENDING_FILE), &serde_json::to_string_pretty(&all)?)?;
+    }
+    Ok(changed)
+}
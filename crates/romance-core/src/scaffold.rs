@@ -1,24 +1,22 @@
+use crate::error::Failure;
+use crate::fs::{DiskFs, ProjectFs};
 use crate::generator::auth::generate_jwt_secret;
-use crate::manifest::{FileCategory, Manifest};
+use crate::manifest::{save_base, FileCategory, Manifest, RenderedFile};
+use crate::project::Project;
+use crate::say;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
 use colored::Colorize;
 use heck::ToSnakeCase;
 use std::path::Path;
+use std::sync::Arc;
 use std::process::Command;
 use tera::Context;
 
-/// A rendered file entry: (output_path, template_name_or_none, category, content).
-struct RenderedFile {
-    output: String,
-    template: Option<String>,
-    category: FileCategory,
-    content: String,
-}
-
 /// Render backend source templates (Cargo.toml, main.rs, config.rs, etc.).
 fn render_backend_files(
+    fs: &dyn ProjectFs,
     engine: &TemplateEngine,
     ctx: &Context,
     project_dir: &Path,
@@ -52,7 +50,7 @@ fn render_backend_files(
     for (template, output) in &backend_files {
         let content = engine.render(template, ctx)?;
         let path = project_dir.join(output);
-        utils::write_file(&path, &content)?;
+        utils::write_file(fs, &path, &content)?;
         let category = if output.contains("mod.rs") {
             FileCategory::Marker
         } else {
@@ -62,29 +60,32 @@ fn render_backend_files(
             output: output.to_string(),
             template: Some(template.to_string()),
             category,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     // Backend .env files
     let env_content = engine.render("scaffold/backend/env.example.tera", ctx)?;
-    utils::write_file(&project_dir.join("backend/.env.example"), &env_content)?;
+    utils::write_file(fs, &project_dir.join("backend/.env.example"), &env_content)?;
     rendered.push(RenderedFile {
         output: "backend/.env.example".to_string(),
         template: Some("scaffold/backend/env.example.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content: env_content.clone(),
     });
-    println!("  {} backend/.env.example", "create".green());
-    utils::write_file(&project_dir.join("backend/.env"), &env_content)?;
-    println!("  {} backend/.env", "create".green());
+    utils::ui::created("backend/.env.example");
+    utils::write_file(fs, &project_dir.join("backend/.env"), &env_content)?;
+    utils::ui::created("backend/.env");
 
     Ok(rendered)
 }
 
 /// Render migration crate templates (Cargo.toml, lib.rs, main.rs).
 fn render_migration_files(
+    fs: &dyn ProjectFs,
     engine: &TemplateEngine,
     ctx: &Context,
     project_dir: &Path,
@@ -108,7 +109,7 @@ fn render_migration_files(
     for (template, output) in &migration_files {
         let content = engine.render(template, ctx)?;
         let path = project_dir.join(output);
-        utils::write_file(&path, &content)?;
+        utils::write_file(fs, &path, &content)?;
         let category = if output.contains("lib.rs") {
             FileCategory::Marker
         } else {
@@ -118,9 +119,10 @@ fn render_migration_files(
             output: output.to_string(),
             template: Some(template.to_string()),
             category,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     Ok(rendered)
@@ -128,6 +130,7 @@ fn render_migration_files(
 
 /// Render frontend templates (package.json, vite config, App.tsx, etc.).
 fn render_frontend_files(
+    fs: &dyn ProjectFs,
     engine: &TemplateEngine,
     ctx: &Context,
     project_dir: &Path,
@@ -162,19 +165,21 @@ fn render_frontend_files(
     for (template, output) in &frontend_files {
         let content = engine.render(template, ctx)?;
         let path = project_dir.join(output);
-        utils::write_file(&path, &content)?;
+        utils::write_file(fs, &path, &content)?;
         rendered.push(RenderedFile {
             output: output.to_string(),
             template: Some(template.to_string()),
             category: FileCategory::Scaffold,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     // Frontend vite-env.d.ts
     let vite_env_content = engine.render("scaffold/frontend/vite-env.d.ts.tera", ctx)?;
     utils::write_file(
+        fs,
         &project_dir.join("frontend/src/vite-env.d.ts"),
         &vite_env_content,
     )?;
@@ -182,24 +187,27 @@ fn render_frontend_files(
         output: "frontend/src/vite-env.d.ts".to_string(),
         template: Some("scaffold/frontend/vite-env.d.ts.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content: vite_env_content,
     });
-    println!("  {} frontend/src/vite-env.d.ts", "create".green());
+    utils::ui::created("frontend/src/vite-env.d.ts");
 
     // Frontend index.css (shadcn/ui theme with Tailwind v4)
     let index_css = engine.render("scaffold/frontend/index.css.tera", ctx)?;
-    utils::write_file(&project_dir.join("frontend/src/index.css"), &index_css)?;
+    utils::write_file(fs, &project_dir.join("frontend/src/index.css"), &index_css)?;
     rendered.push(RenderedFile {
         output: "frontend/src/index.css".to_string(),
         template: Some("scaffold/frontend/index.css.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content: index_css,
     });
-    println!("  {} frontend/src/index.css", "create".green());
+    utils::ui::created("frontend/src/index.css");
 
     // Frontend components.json (shadcn/ui config)
     let components_json = engine.render("scaffold/frontend/components.json.tera", ctx)?;
     utils::write_file(
+        fs,
         &project_dir.join("frontend/components.json"),
         &components_json,
     )?;
@@ -207,9 +215,10 @@ fn render_frontend_files(
         output: "frontend/components.json".to_string(),
         template: Some("scaffold/frontend/components.json.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content: components_json,
     });
-    println!("  {} frontend/components.json", "create".green());
+    utils::ui::created("frontend/components.json");
 
     // Frontend index.html
     let index_html = format!(
@@ -228,14 +237,15 @@ fn render_frontend_files(
 "#,
         name
     );
-    utils::write_file(&project_dir.join("frontend/index.html"), &index_html)?;
+    utils::write_file(fs, &project_dir.join("frontend/index.html"), &index_html)?;
     rendered.push(RenderedFile {
         output: "frontend/index.html".to_string(),
         template: None,
         category: FileCategory::Static,
+        entity_name: None,
         content: index_html,
     });
-    println!("  {} frontend/index.html", "create".green());
+    utils::ui::created("frontend/index.html");
 
     // Static component files (not templated)
     let static_components = vec![
@@ -251,14 +261,15 @@ fn render_frontend_files(
 
     for (embedded_path, output) in &static_components {
         let content = engine.get_raw(embedded_path)?;
-        utils::write_file(&project_dir.join(output), &content)?;
+        utils::write_file(fs, &project_dir.join(output), &content)?;
         rendered.push(RenderedFile {
             output: output.to_string(),
             template: None,
             category: FileCategory::Scaffold,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     Ok(rendered)
@@ -266,6 +277,7 @@ fn render_frontend_files(
 
 /// Render Docker, CI, and project root config templates.
 fn render_docker_files(
+    fs: &dyn ProjectFs,
     engine: &TemplateEngine,
     ctx: &Context,
     project_dir: &Path,
@@ -291,14 +303,15 @@ fn render_docker_files(
     for (template, output) in &docker_files {
         let content = engine.render(template, ctx)?;
         let path = project_dir.join(output);
-        utils::write_file(&path, &content)?;
+        utils::write_file(fs, &path, &content)?;
         rendered.push(RenderedFile {
             output: output.to_string(),
             template: Some(template.to_string()),
             category: FileCategory::Scaffold,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     // CI files
@@ -310,59 +323,64 @@ fn render_docker_files(
     for (template, output) in &ci_files {
         let content = engine.render(template, ctx)?;
         let path = project_dir.join(output);
-        utils::write_file(&path, &content)?;
+        utils::write_file(fs, &path, &content)?;
         rendered.push(RenderedFile {
             output: output.to_string(),
             template: Some(template.to_string()),
             category: FileCategory::Scaffold,
+            entity_name: None,
             content,
         });
-        println!("  {} {}", "create".green(), output);
+        utils::ui::created(output);
     }
 
     // romance.toml
     let content = engine.render("scaffold/romance.toml.tera", ctx)?;
-    utils::write_file(&project_dir.join("romance.toml"), &content)?;
+    utils::write_file(fs, &project_dir.join("romance.toml"), &content)?;
     rendered.push(RenderedFile {
         output: "romance.toml".to_string(),
         template: Some("scaffold/romance.toml.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content,
     });
-    println!("  {} romance.toml", "create".green());
+    utils::ui::created("romance.toml");
 
     // romance.production.toml (environment override example)
     let content = engine.render("scaffold/romance.production.toml.tera", ctx)?;
-    utils::write_file(&project_dir.join("romance.production.toml"), &content)?;
+    utils::write_file(fs, &project_dir.join("romance.production.toml"), &content)?;
     rendered.push(RenderedFile {
         output: "romance.production.toml".to_string(),
         template: Some("scaffold/romance.production.toml.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content,
     });
-    println!("  {} romance.production.toml", "create".green());
+    utils::ui::created("romance.production.toml");
 
     // README
     let content = engine.render("scaffold/README.md.tera", ctx)?;
-    utils::write_file(&project_dir.join("README.md"), &content)?;
+    utils::write_file(fs, &project_dir.join("README.md"), &content)?;
     rendered.push(RenderedFile {
         output: "README.md".to_string(),
         template: Some("scaffold/README.md.tera".to_string()),
         category: FileCategory::Scaffold,
+        entity_name: None,
         content,
     });
-    println!("  {} README.md", "create".green());
+    utils::ui::created("README.md");
 
     Ok(rendered)
 }
 
 /// Create non-template stub files (mod.rs markers, .gitignore).
-fn create_stub_files(project_dir: &Path) -> Result<Vec<RenderedFile>> {
+fn create_stub_files(fs: &dyn ProjectFs, project_dir: &Path) -> Result<Vec<RenderedFile>> {
     let mut rendered = Vec::new();
 
     // Backend stub files
     let entities_mod = "// === ROMANCE:MODS ===\n";
     utils::write_file(
+        fs,
         &project_dir.join("backend/src/entities/mod.rs"),
         entities_mod,
     )?;
@@ -370,12 +388,14 @@ fn create_stub_files(project_dir: &Path) -> Result<Vec<RenderedFile>> {
         output: "backend/src/entities/mod.rs".to_string(),
         template: None,
         category: FileCategory::Marker,
+        entity_name: None,
         content: entities_mod.to_string(),
     });
-    println!("  {} backend/src/entities/mod.rs", "create".green());
+    utils::ui::created("backend/src/entities/mod.rs");
 
     let handlers_mod = "// === ROMANCE:MODS ===\n";
     utils::write_file(
+        fs,
         &project_dir.join("backend/src/handlers/mod.rs"),
         handlers_mod,
     )?;
@@ -383,9 +403,10 @@ fn create_stub_files(project_dir: &Path) -> Result<Vec<RenderedFile>> {
         output: "backend/src/handlers/mod.rs".to_string(),
         template: None,
         category: FileCategory::Marker,
+        entity_name: None,
         content: handlers_mod.to_string(),
     });
-    println!("  {} backend/src/handlers/mod.rs", "create".green());
+    utils::ui::created("backend/src/handlers/mod.rs");
 
     // .gitignore
     let gitignore = "\
@@ -394,17 +415,19 @@ fn create_stub_files(project_dir: &Path) -> Result<Vec<RenderedFile>> {
 /backend/migration/target/
 /frontend/node_modules/
 /frontend/dist/
+/.romance/history/
 *.env
 !*.env.example
 ";
-    utils::write_file(&project_dir.join(".gitignore"), gitignore)?;
+    utils::write_file(fs, &project_dir.join(".gitignore"), gitignore)?;
     rendered.push(RenderedFile {
         output: ".gitignore".to_string(),
         template: None,
         category: FileCategory::Static,
+        entity_name: None,
         content: gitignore.to_string(),
     });
-    println!("  {} .gitignore", "create".green());
+    utils::ui::created(".gitignore");
 
     Ok(rendered)
 }
@@ -412,50 +435,46 @@ fn create_stub_files(project_dir: &Path) -> Result<Vec<RenderedFile>> {
 /// Install frontend npm dependencies and shadcn/ui components.
 fn install_frontend_deps(project_dir: &Path, name: &str) -> Result<()> {
     let frontend_dir = project_dir.join("frontend");
-    println!();
-    println!(
+    say!();
+    say!(
         "{}",
         "Installing frontend dependencies...".cyan().bold()
     );
     let npm_status = Command::new("npm")
         .args(["install"])
         .current_dir(&frontend_dir)
+        .stdout(utils::ui::child_stdout())
         .status();
 
     match npm_status {
         Ok(status) if status.success() => {
-            println!("  {} npm install", "done".green());
+            say!("  {} npm install", "done".green());
 
             // Install ALL shadcn/ui components
-            println!(
+            say!(
                 "{}",
                 "Installing shadcn/ui components...".cyan().bold()
             );
             let shadcn_status = Command::new("npx")
                 .args(["shadcn@latest", "add", "--all", "--yes", "--overwrite"])
                 .current_dir(&frontend_dir)
+                .stdout(utils::ui::child_stdout())
                 .status();
 
             match shadcn_status {
                 Ok(status) if status.success() => {
-                    println!("  {} shadcn/ui components", "done".green());
+                    say!("  {} shadcn/ui components", "done".green());
                 }
                 _ => {
-                    println!(
-                        "  {} Failed to install shadcn/ui components. Run manually:",
-                        "warn".yellow()
-                    );
-                    println!("    cd {}/frontend && npx shadcn@latest add --all --yes", name);
+                    utils::ui::warn("Failed to install shadcn/ui components. Run manually:");
+                    say!("    cd {}/frontend && npx shadcn@latest add --all --yes", name);
                 }
             }
         }
         _ => {
-            println!(
-                "  {} Failed to install npm dependencies. Run manually:",
-                "warn".yellow()
-            );
-            println!("    cd {}/frontend && npm install", name);
-            println!("    npx shadcn@latest add --all --yes");
+            utils::ui::warn("Failed to install npm dependencies. Run manually:");
+            say!("    cd {}/frontend && npm install", name);
+            say!("    npx shadcn@latest add --all --yes");
         }
     }
 
@@ -465,14 +484,35 @@ fn install_frontend_deps(project_dir: &Path, name: &str) -> Result<()> {
 pub fn create_project(name: &str) -> Result<()> {
     let project_dir = Path::new(name);
     if project_dir.exists() {
-        anyhow::bail!("Directory '{}' already exists", name);
+        return Err(Failure::Precondition.error(format!("Directory '{}' already exists", name)));
     }
 
-    println!(
+    say!(
         "{}",
         format!("Creating new Romance project: {}", name).bold()
     );
 
+    render_project(Arc::new(DiskFs), project_dir, name)?;
+
+    // Install frontend dependencies and shadcn/ui components
+    install_frontend_deps(project_dir, name)?;
+
+    say!();
+    say!("{}", "Project created successfully!".green().bold());
+    say!();
+    say!("Next steps:");
+    say!("  cd {}", name);
+    say!("  cd backend && cargo build");
+    say!("  romance dev");
+
+    Ok(())
+}
+
+/// Render a new project named `name` into `project_dir` on `fs`, including
+/// its manifest and AI context, and return a handle to it. Unlike
+/// [`create_project`], nothing is installed, so this works on a
+/// [`MemoryFs`](crate::fs::MemoryFs).
+pub fn render_project(fs: Arc<dyn ProjectFs>, project_dir: &Path, name: &str) -> Result<Project> {
     let engine = TemplateEngine::new()?;
     let mut ctx = Context::new();
     ctx.insert("project_name", name);
@@ -482,11 +522,11 @@ pub fn create_project(name: &str) -> Result<()> {
     let mut manifest = Manifest::new(name, env!("CARGO_PKG_VERSION"));
 
     // Render all template groups
-    let backend = render_backend_files(&engine, &ctx, project_dir)?;
-    let migration = render_migration_files(&engine, &ctx, project_dir)?;
-    let frontend = render_frontend_files(&engine, &ctx, project_dir, name)?;
-    let docker = render_docker_files(&engine, &ctx, project_dir)?;
-    let stubs = create_stub_files(project_dir)?;
+    let backend = render_backend_files(&*fs, &engine, &ctx, project_dir)?;
+    let migration = render_migration_files(&*fs, &engine, &ctx, project_dir)?;
+    let frontend = render_frontend_files(&*fs, &engine, &ctx, project_dir, name)?;
+    let docker = render_docker_files(&*fs, &engine, &ctx, project_dir)?;
+    let stubs = create_stub_files(&*fs, project_dir)?;
 
     // Record all rendered files in the manifest
     for file in backend
@@ -503,25 +543,16 @@ pub fn create_project(name: &str) -> Result<()> {
             &file.content,
             None,
         );
+        save_base(&*fs, project_dir, &file.output, &file.content)?;
     }
 
     // Save manifest
-    manifest.save(project_dir)?;
-    println!("  {} .romance/manifest.json", "create".green());
+    manifest.save(&*fs, project_dir)?;
+    utils::ui::created(".romance/manifest.json");
 
     // Generate project-level CLAUDE.md for AI assistants
-    crate::ai_context::regenerate(project_dir)?;
-
-    // Install frontend dependencies and shadcn/ui components
-    install_frontend_deps(project_dir, name)?;
+    let project = Project::open_in(fs, project_dir)?;
+    crate::ai_context::regenerate(&project)?;
 
-    println!();
-    println!("{}", "Project created successfully!".green().bold());
-    println!();
-    println!("Next steps:");
-    println!("  cd {}", name);
-    println!("  cd backend && cargo build");
-    println!("  romance dev");
-
-    Ok(())
+    Ok(project)
 }
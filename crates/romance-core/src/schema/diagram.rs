@@ -0,0 +1,313 @@
+//! Render the schema as an entity-relationship diagram: Mermaid (for
+//! Markdown docs and PR descriptions), Graphviz DOT, or a standalone HTML
+//! page that needs neither SVG nor JavaScript.
+//!
+//! Every foreign key is a one-to-many relation from the referenced table:
+//! exactly one parent for `NOT NULL` keys, zero or one for nullable ones.
+//! Many-to-many relations show as their junction table.
+
+use super::{Column, Table};
+use crate::error::Failure;
+use std::fmt::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Mermaid,
+    Dot,
+    Html,
+}
+
+impl Format {
+    /// The name `--format` takes for it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Mermaid => "mermaid",
+            Format::Dot => "dot",
+            Format::Html => "html",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mermaid" => Ok(Format::Mermaid),
+            "dot" => Ok(Format::Dot),
+            "html" | "svg-free-html" => Ok(Format::Html),
+            _ => Err(Failure::InvalidInput.error(format!(
+                "Unknown diagram format '{}' (expected mermaid, dot or html)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Render `tables` in `format`.
+pub fn render(tables: &[Table], format: Format) -> String {
+    match format {
+        Format::Mermaid => mermaid(tables),
+        Format::Dot => dot(tables),
+        Format::Html => html(tables),
+    }
+}
+
+fn mermaid(tables: &[Table]) -> String {
+    // Mermaid entity names can't contain dots, so `billing.invoices` is aliased
+    let id = |name: &str| name.replace('.', "_");
+    let mut out = String::from("erDiagram\n");
+    for table in tables {
+        if table.name.contains('.') {
+            let _ = writeln!(out, "    {}[\"{}\"] {{", id(&table.name), table.name);
+        } else {
+            let _ = writeln!(out, "    {} {{", table.name);
+        }
+        for column in &table.columns {
+            let keys: Vec<&str> = [(column.primary_key, "PK"), (column.references.is_some(), "FK")]
+                .into_iter()
+                .filter_map(|(is, key)| is.then_some(key))
+                .collect();
+            let _ = write!(out, "        {} {}", column.sql_type, column.name);
+            if !keys.is_empty() {
+                let _ = write!(out, " {}", keys.join(", "));
+            }
+            if column.nullable {
+                out.push_str(" \"nullable\"");
+            }
+            out.push('\n');
+        }
+        out.push_str("    }\n");
+    }
+    for table in tables {
+        for column in table.foreign_keys() {
+            let parent = column.references.as_deref().unwrap_or_default();
+            let one = if column.nullable { "|o" } else { "||" };
+            let _ = writeln!(out, "    {} {}--o{{ {} : \"{}\"", id(parent), one, id(&table.name), column.name);
+        }
+    }
+    out
+}
+
+fn dot(tables: &[Table]) -> String {
+    let mut out = String::from(
+        "digraph schema {\n    rankdir=LR;\n    node [shape=plaintext, fontname=\"Helvetica\"];\n    edge [dir=both, arrowtail=crowodot];\n\n",
+    );
+    for table in tables {
+        let header = if table.junction { "#eeeeee" } else { "#dde7f5" };
+        let _ = writeln!(
+            out,
+            "    \"{}\" [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\" cellpadding=\"4\">",
+            table.name
+        );
+        let _ = writeln!(out, "        <tr><td bgcolor=\"{}\"><b>{}</b></td></tr>", header, escape(&table.name));
+        for column in &table.columns {
+            let _ = writeln!(
+                out,
+                "        <tr><td port=\"{}\" align=\"left\">{}</td></tr>",
+                column.name,
+                escape(&describe(column))
+            );
+        }
+        out.push_str("    </table>>];\n");
+    }
+    out.push('\n');
+    for table in tables {
+        for column in table.foreign_keys() {
+            let parent = column.references.as_deref().unwrap_or_default();
+            let head = if column.nullable { "teeodot" } else { "teetee" };
+            let _ = writeln!(
+                out,
+                "    \"{}\":\"{}\" -> \"{}\":\"id\" [arrowhead={}];",
+                table.name, column.name, parent, head
+            );
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn html(tables: &[Table]) -> String {
+    let mut out = String::from(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Database schema</title>
+<style>
+  body { font-family: system-ui, sans-serif; margin: 2rem; color: #1f2937; }
+  main { display: flex; flex-wrap: wrap; gap: 1.5rem; align-items: flex-start; }
+  section { border: 1px solid #cbd5e1; border-radius: 6px; min-width: 18rem; }
+  section.junction { border-style: dashed; }
+  section:target { outline: 3px solid #3b82f6; }
+  h2 { margin: 0; padding: .5rem .75rem; font-size: 1rem; background: #dde7f5; }
+  section.junction h2 { background: #eeeeee; }
+  table { border-collapse: collapse; width: 100%; font-size: .875rem; }
+  td { padding: .25rem .75rem; border-top: 1px solid #e2e8f0; }
+  .type { color: #64748b; }
+  .key { font-weight: 600; }
+  ul { margin: 0; padding: .5rem .75rem .5rem 2rem; font-size: .8rem; border-top: 1px solid #e2e8f0; }
+</style>
+</head>
+<body>
+<h1>Database schema</h1>
+<main>
+"#,
+    );
+    for table in tables {
+        let class = if table.junction { " class=\"junction\"" } else { "" };
+        let _ = writeln!(out, "<section id=\"{}\"{}>", anchor(&table.name), class);
+        let title = if table.junction { format!("{} (junction)", table.name) } else { table.name.clone() };
+        let _ = writeln!(out, "<h2>{}</h2>\n<table>", escape(&title));
+        for column in &table.columns {
+            let name = if column.primary_key {
+                format!("<span class=\"key\">{}</span> PK", escape(&column.name))
+            } else {
+                escape(&column.name)
+            };
+            let reference = match &column.references {
+                Some(parent) => format!(
+                    "FK → <a href=\"#{}\">{}.id</a>",
+                    anchor(parent),
+                    escape(parent)
+                ),
+                None => String::new(),
+            };
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td class=\"type\">{}{}</td><td>{}</td></tr>",
+                name,
+                escape(&column.sql_type),
+                if column.nullable { "?" } else { "" },
+                reference
+            );
+        }
+        out.push_str("</table>\n");
+
+        // Relations, from both sides
+        let mut relations: Vec<String> = table
+            .foreign_keys()
+            .map(|column| {
+                let parent = column.references.as_deref().unwrap_or_default();
+                let one = if column.nullable { "0..1" } else { "1" };
+                format!(
+                    "many-to-{} <a href=\"#{}\">{}</a> via {}",
+                    one,
+                    anchor(parent),
+                    escape(parent),
+                    escape(&column.name)
+                )
+            })
+            .collect();
+        for child in tables {
+            for column in child.foreign_keys().filter(|c| c.references.as_deref() == Some(table.name.as_str())) {
+                relations.push(format!(
+                    "one-to-many <a href=\"#{}\">{}</a> via {}",
+                    anchor(&child.name),
+                    escape(&child.name),
+                    escape(&column.name)
+                ));
+            }
+        }
+        if !relations.is_empty() {
+            out.push_str("<ul>\n");
+            for relation in relations {
+                let _ = writeln!(out, "<li>{}</li>", relation);
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</main>\n</body>\n</html>\n");
+    out
+}
+
+/// `name: type?`, with `PK`/`FK` markers.
+fn describe(column: &Column) -> String {
+    let mut s = format!("{}: {}{}", column.name, column.sql_type, if column.nullable { "?" } else { "" });
+    if column.primary_key {
+        s.push_str(" PK");
+    }
+    if column.references.is_some() {
+        s.push_str(" FK");
+    }
+    s
+}
+
+fn anchor(table: &str) -> String {
+    format!("table-{}", table.replace('.', "-"))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, sql_type: &str, nullable: bool, references: Option<&str>) -> Column {
+        Column {
+            name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable,
+            primary_key: name == "id",
+            references: references.map(str::to_string),
+        }
+    }
+
+    fn tables() -> Vec<Table> {
+        vec![
+            Table {
+                entity: "Post".to_string(),
+                name: "posts".to_string(),
+                columns: vec![
+                    column("id", "uuid", false, None),
+                    column("title", "varchar", false, None),
+                    column("author_id", "uuid", true, Some("users")),
+                ],
+                junction: false,
+            },
+            Table {
+                entity: "User".to_string(),
+                name: "auth.users".to_string(),
+                columns: vec![column("id", "uuid", false, None)],
+                junction: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let out = render(&tables(), Format::Mermaid);
+        assert!(out.starts_with("erDiagram\n"));
+        assert!(out.contains("        uuid id PK\n"));
+        assert!(out.contains("        uuid author_id FK \"nullable\"\n"));
+        assert!(out.contains("    auth_users[\"auth.users\"] {\n"));
+        assert!(out.contains("    users |o--o{ posts : \"author_id\"\n"));
+    }
+
+    #[test]
+    fn renders_dot() {
+        let out = render(&tables(), Format::Dot);
+        assert!(out.starts_with("digraph schema {"));
+        assert!(out.contains("<td port=\"author_id\" align=\"left\">author_id: uuid? FK</td>"));
+        assert!(out.contains("\"posts\":\"author_id\" -> \"users\":\"id\" [arrowhead=teeodot];"));
+    }
+
+    #[test]
+    fn renders_html_without_svg_or_scripts() {
+        let out = render(&tables(), Format::Html);
+        assert!(out.contains("<section id=\"table-auth-users\">"));
+        assert!(out.contains("FK → <a href=\"#table-users\">users.id</a>"));
+        assert!(out.contains("many-to-0..1 <a href=\"#table-users\">users</a> via author_id"));
+        assert!(!out.contains("<svg") && !out.contains("<script"));
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("svg-free-html".parse::<Format>().unwrap(), Format::Html);
+        assert!("png".parse::<Format>().is_err());
+    }
+}
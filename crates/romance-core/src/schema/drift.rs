@@ -0,0 +1,490 @@
+//! Compare the models with the tables their migrations actually create, and
+//! write a migration that brings the database back in line.
+//!
+//! Only what the models declare is checked: tables and columns the models
+//! don't mention (`seaql_migrations`, columns left over from an old field)
+//! aren't drift. Column types are compared loosely, the way SeaORM maps them:
+//! `varchar`, `text` and enum types all hold a `String`, `json` and `jsonb`
+//! both hold `Json`.
+
+use super::{Column, Table};
+use crate::generator::context;
+use crate::generator::migration;
+use crate::generator::plan::GenerationTracker;
+use crate::import::{self, Database};
+use crate::project::Project;
+use crate::template::TemplateEngine;
+use crate::utils;
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+use tera::Context;
+
+/// Where the database disagrees with a model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Difference {
+    MissingTable {
+        table: String,
+    },
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    TypeMismatch {
+        table: String,
+        column: String,
+        model: String,
+        database: String,
+    },
+    NullabilityMismatch {
+        table: String,
+        column: String,
+        /// Whether the model's field is an `Option`.
+        model_nullable: bool,
+    },
+    MissingForeignKey {
+        table: String,
+        column: String,
+        references: String,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::MissingTable { table } => write!(f, "{}: table missing from the database", table),
+            Difference::MissingColumn { table, column } => {
+                write!(f, "{}.{}: column missing from the database", table, column)
+            }
+            Difference::TypeMismatch {
+                table,
+                column,
+                model,
+                database,
+            } => write!(f, "{}.{}: the model has {}, the database {}", table, column, model, database),
+            Difference::NullabilityMismatch {
+                table,
+                column,
+                model_nullable,
+            } => {
+                let (model, database) = if *model_nullable {
+                    ("optional", "NOT NULL")
+                } else {
+                    ("required", "nullable")
+                };
+                write!(f, "{}.{}: the model has it {}, the database {}", table, column, model, database)
+            }
+            Difference::MissingForeignKey {
+                table,
+                column,
+                references,
+            } => write!(f, "{}.{}: no foreign key to {}", table, column, references),
+        }
+    }
+}
+
+/// Every difference between `models` and the tables in `db`.
+pub fn compare(models: &[Table], db: &Database) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    for model in models {
+        let Some(table) = db.tables.iter().find(|t| t.qualified_name() == model.name) else {
+            differences.push(Difference::MissingTable {
+                table: model.name.clone(),
+            });
+            continue;
+        };
+
+        for column in &model.columns {
+            let Some(actual) = table.columns.iter().find(|c| c.name == column.name) else {
+                differences.push(Difference::MissingColumn {
+                    table: model.name.clone(),
+                    column: column.name.clone(),
+                });
+                continue;
+            };
+
+            // Custom model types and exotic column types aren't compared
+            if let (Some(_), Some(database)) = (postgres_type(column), sql_type(&actual.data_type, db)) {
+                if family(&column.sql_type) != family(database) {
+                    differences.push(Difference::TypeMismatch {
+                        table: model.name.clone(),
+                        column: column.name.clone(),
+                        model: column.sql_type.clone(),
+                        database: actual.data_type.clone(),
+                    });
+                }
+            }
+            if !column.primary_key && column.nullable != actual.nullable {
+                differences.push(Difference::NullabilityMismatch {
+                    table: model.name.clone(),
+                    column: column.name.clone(),
+                    model_nullable: column.nullable,
+                });
+            }
+            if let Some(references) = &column.references {
+                let exists = table.foreign_keys.iter().any(|fk| {
+                    let target = import::Table {
+                        schema: fk.schema.clone(),
+                        name: fk.table.clone(),
+                        ..import::Table::default()
+                    };
+                    fk.columns == [column.name.as_str()] && target.qualified_name() == *references
+                });
+                if !exists {
+                    differences.push(Difference::MissingForeignKey {
+                        table: model.name.clone(),
+                        column: column.name.clone(),
+                        references: references.clone(),
+                    });
+                }
+            }
+        }
+    }
+    differences
+}
+
+/// A Postgres column type in the vocabulary of [`Column::sql_type`], `None`
+/// for types the models can't declare (arrays, domains, ...).
+fn sql_type(data_type: &str, db: &Database) -> Option<&'static str> {
+    let base = match data_type.split_once('(') {
+        // `timestamp(3) with time zone`: the modifier sits mid-name
+        Some((base, rest)) => format!("{}{}", base.trim_end(), rest.split_once(')').map_or("", |(_, tail)| tail)),
+        None => data_type.to_string(),
+    };
+    let base = base.trim();
+    let base = base.rsplit('.').next().unwrap_or(base).trim_matches('"');
+    Some(match base {
+        "character varying" | "varchar" | "character" | "char" | "bpchar" => "varchar",
+        "text" | "citext" => "text",
+        "boolean" | "bool" => "boolean",
+        "smallint" | "int2" | "smallserial" => "smallint",
+        "integer" | "int" | "int4" | "serial" | "serial4" => "integer",
+        "bigint" | "int8" | "bigserial" | "serial8" => "bigint",
+        "real" | "float4" => "real",
+        "double precision" | "float8" | "float" => "double",
+        "numeric" | "decimal" => "decimal",
+        "uuid" => "uuid",
+        "timestamp with time zone" | "timestamptz" => "timestamptz",
+        "timestamp" | "timestamp without time zone" => "timestamp",
+        "date" => "date",
+        "json" | "jsonb" => "jsonb",
+        other if db.enums.contains_key(other) => "varchar",
+        _ => return None,
+    })
+}
+
+/// Types holding the same Rust value compare equal.
+fn family(sql_type: &str) -> &str {
+    match sql_type {
+        "varchar" | "text" => "string",
+        other => other,
+    }
+}
+
+/// The DDL type for a model column, `None` for custom types.
+fn postgres_type(column: &Column) -> Option<&'static str> {
+    Some(match column.sql_type.as_str() {
+        "varchar" => "VARCHAR(255)",
+        "text" => "TEXT",
+        "uuid" => "UUID",
+        "boolean" => "BOOLEAN",
+        "smallint" => "SMALLINT",
+        "integer" => "INTEGER",
+        "bigint" => "BIGINT",
+        "real" => "REAL",
+        "double" => "DOUBLE PRECISION",
+        "decimal" => "DECIMAL",
+        "timestamptz" => "TIMESTAMPTZ",
+        "timestamp" => "TIMESTAMP",
+        "date" => "DATE",
+        "jsonb" => "JSONB",
+        _ => return None,
+    })
+}
+
+/// The statements of a corrective migration: `(up, down, skipped)`, where
+/// `skipped` describes the differences SQL can't fix.
+pub fn statements(models: &[Table], differences: &[Difference]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    // Tables are created before any foreign key, so they can reference each other
+    let mut creates = Vec::new();
+    let mut up = Vec::new();
+    let mut down = Vec::new();
+    let mut skipped = Vec::new();
+    let column_of = |table: &str, column: &str| {
+        models
+            .iter()
+            .find(|t| t.name == table)
+            .and_then(|t| t.columns.iter().find(|c| c.name == column))
+    };
+
+    for difference in differences {
+        match difference {
+            Difference::MissingTable { table } => {
+                let Some(model) = models.iter().find(|t| t.name == *table) else { continue };
+                let mut definitions = Vec::new();
+                for column in &model.columns {
+                    let Some(ty) = postgres_type(column) else {
+                        skipped.push(format!("{}.{}: unknown type {}", table, column.name, column.sql_type));
+                        continue;
+                    };
+                    let not_null = if column.nullable { "" } else { " NOT NULL" };
+                    definitions.push(format!("{} {}{}", ident(&column.name), ty, not_null));
+                }
+                // Junctions have a composite key
+                let key: Vec<String> = model.columns.iter().filter(|c| c.primary_key).map(|c| ident(&c.name)).collect();
+                if !key.is_empty() {
+                    definitions.push(format!("PRIMARY KEY ({})", key.join(", ")));
+                }
+                creates.push((
+                    format!("CREATE TABLE {} ({})", ident(table), definitions.join(", ")),
+                    format!("DROP TABLE {}", ident(table)),
+                ));
+                for column in model.foreign_keys() {
+                    let parent = column.references.as_deref().unwrap_or_default();
+                    let (add, drop) = foreign_key(table, &column.name, parent);
+                    up.push(add);
+                    down.push(drop);
+                }
+            }
+            Difference::MissingColumn { table, column } => {
+                let Some(model) = column_of(table, column) else { continue };
+                let Some(ty) = postgres_type(model) else {
+                    skipped.push(format!("{}.{}: unknown type {}", table, column, model.sql_type));
+                    continue;
+                };
+                let not_null = if model.nullable { "" } else { " NOT NULL" };
+                up.push(format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}{}",
+                    ident(table),
+                    ident(column),
+                    ty,
+                    not_null
+                ));
+                down.push(format!("ALTER TABLE {} DROP COLUMN {}", ident(table), ident(column)));
+                if let Some(parent) = &model.references {
+                    let (add, drop) = foreign_key(table, column, parent);
+                    up.push(add);
+                    down.push(drop);
+                }
+            }
+            Difference::TypeMismatch {
+                table,
+                column,
+                database,
+                ..
+            } => {
+                let Some(ty) = column_of(table, column).and_then(postgres_type) else { continue };
+                up.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{}",
+                    ident(table),
+                    ident(column),
+                    ty,
+                    ident(column),
+                    ty
+                ));
+                down.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{}",
+                    ident(table),
+                    ident(column),
+                    database,
+                    ident(column),
+                    database
+                ));
+            }
+            Difference::NullabilityMismatch {
+                table,
+                column,
+                model_nullable,
+            } => {
+                let (fix, undo) = if *model_nullable {
+                    ("DROP", "SET")
+                } else {
+                    ("SET", "DROP")
+                };
+                up.push(format!("ALTER TABLE {} ALTER COLUMN {} {} NOT NULL", ident(table), ident(column), fix));
+                down.push(format!("ALTER TABLE {} ALTER COLUMN {} {} NOT NULL", ident(table), ident(column), undo));
+            }
+            Difference::MissingForeignKey {
+                table,
+                column,
+                references,
+            } => {
+                let (add, drop) = foreign_key(table, column, references);
+                up.push(add);
+                down.push(drop);
+            }
+        }
+    }
+
+    let (create, drop): (Vec<String>, Vec<String>) = creates.into_iter().unzip();
+    let up = create.into_iter().chain(up).collect();
+    let down = down.into_iter().rev().chain(drop.into_iter().rev()).collect();
+    (up, down, skipped)
+}
+
+/// Add and drop statements for a foreign key from `table.column`.
+fn foreign_key(table: &str, column: &str, references: &str) -> (String, String) {
+    let constraint = ident(&format!("fk_{}_{}", table.replace('.', "_"), column));
+    (
+        format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} (\"id\") ON DELETE CASCADE",
+            ident(table),
+            constraint,
+            ident(column),
+            ident(references)
+        ),
+        format!("ALTER TABLE {} DROP CONSTRAINT {}", ident(table), constraint),
+    )
+}
+
+/// Write a migration fixing `differences`, and describe the ones it can't
+/// fix. No migration when none of them can be fixed in SQL.
+pub fn write_migration(
+    project: &Project,
+    models: &[Table],
+    differences: &[Difference],
+    tracker: &mut GenerationTracker,
+) -> Result<(Option<PathBuf>, Vec<String>)> {
+    let (up, down, skipped) = statements(models, differences);
+    if up.is_empty() {
+        return Ok((None, skipped));
+    }
+
+    let engine = TemplateEngine::for_project(project)?;
+    let mut ctx = Context::new();
+    ctx.insert("up", &up);
+    ctx.insert("down", &down);
+    let content = engine.render("schema/drift_migration.rs.tera", &ctx)?;
+    let migration_module = format!("m{}_fix_schema_drift", migration::next_timestamp(project));
+    let migration_path = project.migration_src().join(format!("{}.rs", migration_module));
+    utils::write_file(project.fs(), &migration_path, &content)?;
+    utils::ui::created(&project.relative(&migration_path).display().to_string());
+    tracker.track(migration_path.clone());
+    context::register_migration(project.fs(), project.root(), &migration_module)?;
+    Ok((Some(migration_path), skipped))
+}
+
+/// `"name"`, or `"schema"."name"` for qualified names.
+fn ident(name: &str) -> String {
+    name.split('.').map(|part| format!("\"{}\"", part)).collect::<Vec<_>>().join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, sql_type: &str, nullable: bool, references: Option<&str>) -> Column {
+        Column {
+            name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable,
+            primary_key: name == "id",
+            references: references.map(str::to_string),
+        }
+    }
+
+    fn models() -> Vec<Table> {
+        vec![
+            Table {
+                entity: "Post".to_string(),
+                name: "posts".to_string(),
+                columns: vec![
+                    column("id", "uuid", false, None),
+                    column("title", "varchar", false, None),
+                    column("body", "text", false, None),
+                    column("views", "bigint", false, None),
+                    column("published_at", "timestamptz", true, None),
+                    column("author_id", "uuid", false, Some("users")),
+                ],
+                junction: false,
+            },
+            Table {
+                entity: "User".to_string(),
+                name: "users".to_string(),
+                columns: vec![column("id", "uuid", false, None)],
+                junction: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn finds_nothing_when_the_database_matches() {
+        let db = import::sql::parse(
+            r#"
+            CREATE TABLE users (id UUID PRIMARY KEY);
+            CREATE TABLE posts (
+                id UUID PRIMARY KEY,
+                title TEXT NOT NULL,
+                body CHARACTER VARYING(1000) NOT NULL,
+                views BIGINT NOT NULL,
+                published_at TIMESTAMP(3) WITH TIME ZONE,
+                author_id UUID NOT NULL REFERENCES users (id),
+                legacy_flag BOOLEAN
+            );
+            "#,
+        )
+        .unwrap();
+        assert_eq!(compare(&models(), &db), Vec::new());
+    }
+
+    #[test]
+    fn reports_every_kind_of_difference() {
+        let db = import::sql::parse(
+            r#"
+            CREATE TABLE posts (
+                id UUID PRIMARY KEY,
+                title VARCHAR(255),
+                views INTEGER NOT NULL,
+                published_at TIMESTAMPTZ NOT NULL,
+                author_id UUID NOT NULL
+            );
+            "#,
+        )
+        .unwrap();
+        let differences: Vec<String> = compare(&models(), &db).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            differences,
+            [
+                "posts.title: the model has it required, the database nullable",
+                "posts.body: column missing from the database",
+                "posts.views: the model has bigint, the database integer",
+                "posts.published_at: the model has it optional, the database NOT NULL",
+                "posts.author_id: no foreign key to users",
+                "users: table missing from the database",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_fixes_with_tables_before_foreign_keys() {
+        let db = import::sql::parse("CREATE TABLE posts (id UUID PRIMARY KEY, views INTEGER);").unwrap();
+        let differences = compare(&models(), &db);
+        let (up, down, skipped) = statements(&models(), &differences);
+        assert!(skipped.is_empty());
+        assert_eq!(up[0], r#"CREATE TABLE "users" ("id" UUID NOT NULL, PRIMARY KEY ("id"))"#);
+        assert!(up.contains(&r#"ALTER TABLE "posts" ADD COLUMN "body" TEXT NOT NULL"#.to_string()));
+        assert!(up.contains(
+            &r#"ALTER TABLE "posts" ALTER COLUMN "views" TYPE BIGINT USING "views"::BIGINT"#.to_string()
+        ));
+        assert!(up.contains(&r#"ALTER TABLE "posts" ALTER COLUMN "views" SET NOT NULL"#.to_string()));
+        assert_eq!(
+            up.last().unwrap(),
+            r#"ALTER TABLE "posts" ADD CONSTRAINT "fk_posts_author_id" FOREIGN KEY ("author_id") REFERENCES "users" ("id") ON DELETE CASCADE"#
+        );
+        assert_eq!(down[0], r#"ALTER TABLE "posts" DROP CONSTRAINT "fk_posts_author_id""#);
+        assert_eq!(down.last().unwrap(), r#"DROP TABLE "users""#);
+    }
+
+    #[test]
+    fn compares_types_by_the_rust_value_they_hold() {
+        let mut db = Database::default();
+        db.enums.insert("post_status".to_string(), vec!["draft".to_string()]);
+        assert_eq!(sql_type("character varying(100)", &db), Some("varchar"));
+        assert_eq!(sql_type("timestamp(6) without time zone", &db), Some("timestamp"));
+        assert_eq!(sql_type("post_status", &db), Some("varchar"));
+        assert_eq!(sql_type("integer[]", &db), None);
+        assert_eq!(family("text"), family("varchar"));
+    }
+}
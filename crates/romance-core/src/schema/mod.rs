@@ -0,0 +1,228 @@
+//! The project's database schema as its SeaORM models declare it: every
+//! table with its columns and foreign keys, including junction tables and
+//! the tenant and user tables of the multitenancy and auth features.
+//!
+//! [`diagram`] renders it as an entity-relationship diagram, and [`drift`]
+//! compares it with the tables the migrations create.
+
+pub mod diagram;
+pub mod drift;
+
+use crate::generator::recover;
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::relation;
+use crate::utils;
+use anyhow::{Context, Result};
+use heck::{ToPascalCase, ToSnakeCase};
+use quote::ToTokens;
+use regex::Regex;
+use serde::Serialize;
+use syn::ext::IdentExt;
+
+/// A table, read from the model of an entity or junction.
+#[derive(Debug, Clone, Serialize)]
+pub struct Table {
+    /// The model's entity, e.g. `Post`.
+    pub entity: String,
+    /// The table name, qualified with the module's schema if it has one.
+    pub name: String,
+    pub columns: Vec<Column>,
+    /// Whether this is a many-to-many junction table.
+    pub junction: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Column {
+    pub name: String,
+    /// The Postgres type, e.g. `varchar` or `timestamptz`.
+    pub sql_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// The table this column references, for foreign keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references: Option<String>,
+}
+
+impl Table {
+    /// The foreign key columns.
+    pub fn foreign_keys(&self) -> impl Iterator<Item = &Column> {
+        self.columns.iter().filter(|c| c.references.is_some())
+    }
+}
+
+/// Read every table from the project's models, sorted by name.
+pub fn scan(project: &Project) -> Result<Vec<Table>> {
+    let mut models: Vec<(Option<EntityModule>, String)> = relation::discover_entities(project)?
+        .into_iter()
+        .map(|snake| (None, snake))
+        .collect();
+    for m in module::all(project) {
+        for snake in module::entities(project, &m)? {
+            models.push((Some(m.clone()), snake));
+        }
+    }
+
+    // Foreign keys name the target's model; resolve them to tables afterwards
+    let mut read = Vec::new();
+    for (entity_module, snake) in &models {
+        let path = module::backend_file(entity_module.as_ref(), "entities", snake);
+        let content = project.fs().read_to_string(&project.join(&path))?;
+        if let Some((table, foreign_keys)) = read_model(project, entity_module.as_ref(), snake, &content)
+            .with_context(|| format!("Failed to read {}", path))?
+        {
+            read.push((snake.clone(), table, foreign_keys));
+        }
+    }
+
+    let table_of = |target: &str| {
+        read.iter()
+            .find(|(snake, _, _)| snake == target)
+            .map(|(_, table, _)| table.name.clone())
+            .unwrap_or_else(|| utils::pluralize(target))
+    };
+    let references: Vec<Vec<ForeignKey>> = read
+        .iter()
+        .map(|(_, _, fks)| fks.iter().map(|(column, target)| (column.clone(), table_of(target))).collect())
+        .collect();
+    let mut tables: Vec<Table> = read
+        .into_iter()
+        .zip(references)
+        .map(|((_, mut table, _), fks)| {
+            for column in &mut table.columns {
+                let pascal = column.name.to_pascal_case();
+                column.references = fks.iter().find(|(c, _)| *c == pascal).map(|(_, t)| t.clone());
+            }
+            table
+        })
+        .collect();
+
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tables)
+}
+
+/// A foreign key: `(column, target)`, e.g. `("AuthorId", "user")`, with
+/// the target model as declared and later its table.
+type ForeignKey = (String, String);
+
+/// The table a model declares, and its foreign keys. `None` for files
+/// without a `Model` struct.
+fn read_model(
+    project: &Project,
+    entity_module: Option<&EntityModule>,
+    snake: &str,
+    content: &str,
+) -> Result<Option<(Table, Vec<ForeignKey>)>> {
+    let file = syn::parse_file(content)?;
+    let Some(model) = file.items.iter().find_map(|item| match item {
+        syn::Item::Struct(s) if s.ident == "Model" => Some(s),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+
+    let attribute = |key: &str| {
+        Regex::new(&format!(r#"{}\s*=\s*"([^"]+)""#, key))
+            .unwrap()
+            .captures(content)
+            .map(|c| c[1].to_string())
+    };
+    let table_name = attribute("table_name").unwrap_or_else(|| module::table_name(entity_module, snake));
+    let name = match attribute("schema_name") {
+        Some(schema) => format!("{}.{}", schema, table_name),
+        None => table_name,
+    };
+
+    // Generated entities have a create migration telling text columns apart
+    let pascal = snake.to_pascal_case();
+    let methods = recover::column_methods(project.fs(), &project.migration_src(), std::slice::from_ref(&pascal));
+
+    let columns = model
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let column = field.ident.as_ref()?.unraw().to_string();
+            let (rust_type, nullable) = unwrap_option(&field.ty);
+            let method = methods.get(&column.to_pascal_case()).map(String::as_str);
+            Some(Column {
+                sql_type: sql_type(&rust_type, method),
+                nullable,
+                primary_key: field
+                    .attrs
+                    .iter()
+                    .any(|a| a.to_token_stream().to_string().contains("primary_key")),
+                references: None,
+                name: column,
+            })
+        })
+        .collect();
+
+    // Junctions are the models `romance generate entity` didn't write
+    let junction = !content.contains("ROMANCE:CUSTOM") && recover::foreign_keys(&file).len() == 2;
+    let table = Table {
+        entity: pascal,
+        name,
+        columns,
+        junction,
+    };
+    let foreign_keys = recover::foreign_keys(&file)
+        .into_iter()
+        .map(|(column, target)| (column, target.to_snake_case()))
+        .collect();
+    Ok(Some((table, foreign_keys)))
+}
+
+/// The type's last path segment, and whether it was wrapped in `Option`.
+fn unwrap_option(ty: &syn::Type) -> (String, bool) {
+    let text = ty.to_token_stream().to_string().replace(' ', "");
+    match text.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        Some(inner) => (inner.rsplit("::").next().unwrap_or(inner).to_string(), true),
+        None => (text.rsplit("::").next().unwrap_or(&text).to_string(), false),
+    }
+}
+
+/// The Postgres type of a model field, refined by its migration's column
+/// method where the Rust type is ambiguous.
+fn sql_type(rust_type: &str, method: Option<&str>) -> String {
+    match rust_type {
+        "Uuid" => "uuid",
+        "bool" => "boolean",
+        "i16" => "smallint",
+        "i32" => "integer",
+        "i64" => "bigint",
+        "f32" => "real",
+        "f64" => "double",
+        "Decimal" => "decimal",
+        "DateTimeWithTimeZone" => "timestamptz",
+        "DateTime" => "timestamp",
+        "Date" => "date",
+        "Json" => "jsonb",
+        "String" => match method {
+            Some("text()") => "text",
+            _ => "varchar",
+        },
+        other => return other.to_snake_case(),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_rust_types_onto_postgres_types() {
+        assert_eq!(sql_type("DateTimeWithTimeZone", None), "timestamptz");
+        assert_eq!(sql_type("String", Some("text()")), "text");
+        assert_eq!(sql_type("String", Some("string_len(512)")), "varchar");
+        assert_eq!(sql_type("Json", None), "jsonb");
+    }
+
+    #[test]
+    fn unwraps_optional_types() {
+        let ty: syn::Type = syn::parse_str("Option<sea_orm::prelude::Decimal>").unwrap();
+        assert_eq!(unwrap_option(&ty), ("Decimal".to_string(), true));
+        let ty: syn::Type = syn::parse_str("Uuid").unwrap();
+        assert_eq!(unwrap_option(&ty), ("Uuid".to_string(), false));
+    }
+}
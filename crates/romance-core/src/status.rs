@@ -0,0 +1,262 @@
+//! Drift between what romance generated and what the project contains now,
+//! for `romance status`.
+
+use crate::addon::{self, Addon};
+use crate::fs::ProjectFs;
+use crate::generator::migration;
+use crate::manifest::{self, FileCategory, Manifest};
+use crate::project::Project;
+use crate::relation::{self, PendingRelation};
+use crate::utils;
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// Directories scanned for generated-looking files the manifest doesn't track.
+const SCANNED_DIRS: &[&str] = &["backend/src", "frontend/src"];
+
+/// A tracked file, as reported by [`scan`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedFile {
+    pub path: String,
+    pub category: FileCategory,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+}
+
+/// Everything `romance status` reports.
+#[derive(Debug, Default, Serialize)]
+pub struct ProjectStatus {
+    /// Whether `.romance/manifest.json` exists. Without it, file drift can't
+    /// be reported.
+    pub has_manifest: bool,
+    /// Tracked files edited since they were generated. When the generated
+    /// base was kept, edits below `ROMANCE:CUSTOM` and lines added to files
+    /// with injection markers don't count, nor do edits to the migrations of
+    /// `romance generate migration`.
+    pub modified: Vec<TrackedFile>,
+    /// Tracked files that no longer exist.
+    pub deleted: Vec<TrackedFile>,
+    /// Files with `ROMANCE:` markers that the manifest doesn't track.
+    pub untracked: Vec<String>,
+    /// Entity modules without a create-table migration.
+    pub missing_migrations: Vec<String>,
+    /// Many-to-many relations waiting for their target entity.
+    pub pending_relations: Vec<PendingRelation>,
+    /// Installed addons none of whose files are recorded in the manifest.
+    pub unrecorded_addons: Vec<String>,
+}
+
+impl ProjectStatus {
+    /// Whether there is nothing to report.
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty()
+            && self.deleted.is_empty()
+            && self.untracked.is_empty()
+            && self.missing_migrations.is_empty()
+            && self.pending_relations.is_empty()
+            && self.unrecorded_addons.is_empty()
+    }
+}
+
+/// Compare the project against its manifest and generated state.
+pub fn scan(project: &Project) -> Result<ProjectStatus> {
+    let project_dir = project.root();
+    let fs = project.fs();
+    let mut status = ProjectStatus {
+        has_manifest: Manifest::exists(fs, project_dir),
+        missing_migrations: missing_migrations(project)?,
+        pending_relations: relation::load_pending(project)?,
+        ..Default::default()
+    };
+    if !status.has_manifest {
+        return Ok(status);
+    }
+
+    let manifest = Manifest::load(fs, project_dir)?;
+    for (path, record) in &manifest.files {
+        let file = TrackedFile {
+            path: path.clone(),
+            category: record.category.clone(),
+            entity: record.entity_name.clone(),
+        };
+        match fs.read_to_string(&project_dir.join(path)) {
+            Ok(current) => {
+                let base = manifest::load_base(fs, project_dir, path, &record.generated_hash);
+                let injected = base.as_deref().is_some_and(|b| utils::only_injected(&current, b));
+                // Blank migrations are generated to be filled in
+                let filled_in = record.category == FileCategory::Migration;
+                if !injected && !filled_in && manifest::is_modified(&current, record, base.as_deref()) {
+                    status.modified.push(file);
+                }
+            }
+            Err(_) => status.deleted.push(file),
+        }
+    }
+
+    for dir in SCANNED_DIRS {
+        collect_untracked(fs, project_dir, &project_dir.join(dir), &manifest, &mut status.untracked)?;
+    }
+    status.untracked.sort();
+
+    let mut installed: Vec<(String, Box<dyn Addon>)> = addon::BUILTIN_ADDONS
+        .iter()
+        .filter_map(|name| addon::builtin(name).map(|a| (name.to_string(), a)))
+        .collect();
+    for name in addon::declarative::list_installed(project)? {
+        let declarative = addon::declarative::DeclarativeAddon::load_installed(project, &name)?;
+        installed.push((name, Box::new(declarative)));
+    }
+    for (name, addon) in installed {
+        if !addon.is_already_installed(fs, project_dir) {
+            continue;
+        }
+        let files = addon.rendered_files(project)?;
+        if !files.is_empty() && !files.iter().any(|f| manifest.files.contains_key(&f.output)) {
+            status.unrecorded_addons.push(name);
+        }
+    }
+
+    Ok(status)
+}
+
+/// Entity modules with no `create_<module>_table` or `create_<table>_table`
+/// migration (kept or squashed), under their current name or one they were
+/// renamed from, whose table wasn't imported either.
+fn missing_migrations(project: &Project) -> Result<Vec<String>> {
+    let table_name = regex::Regex::new(r#"table_name\s*=\s*"([^"]+)""#)?;
+    let baseline = migration::baseline_tables(project);
+    let squashed = migration::squashed_modules(project);
+    let mut missing = Vec::new();
+    for module in relation::discover_entities(project)? {
+        let model = project
+            .fs()
+            .read_to_string(&project.backend_src().join(format!("entities/{}.rs", module)))
+            .unwrap_or_default();
+        let table = table_name.captures(&model).map(|c| c[1].to_string());
+        if table.as_ref().is_some_and(|t| baseline.contains(t)) {
+            continue;
+        }
+        let found = std::iter::once(module.clone())
+            .chain(table)
+            .chain(migration::former_names(project, &module))
+            .any(|name| {
+                migration::find_create_migration(project, &name).is_some()
+                    || squashed.iter().any(|m| migration::creates_table(m, &name))
+            });
+        if !found {
+            missing.push(module);
+        }
+    }
+    missing.sort();
+    Ok(missing)
+}
+
+fn collect_untracked(
+    fs: &dyn ProjectFs,
+    project_dir: &Path,
+    dir: &Path,
+    manifest: &Manifest,
+    untracked: &mut Vec<String>,
+) -> Result<()> {
+    let Ok(entries) = fs.read_dir(dir) else {
+        return Ok(());
+    };
+    for path in entries {
+        if fs.is_dir(&path) {
+            if path.file_name().is_some_and(|n| n != "node_modules") {
+                collect_untracked(fs, project_dir, &path, manifest, untracked)?;
+            }
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(project_dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().into_owned();
+        if manifest.files.contains_key(&relative) {
+            continue;
+        }
+        if fs.read_to_string(&path).is_ok_and(|c| c.contains("// === ROMANCE:")) {
+            untracked.push(relative);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::DiskFs;
+    use crate::utils::write_file;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write_file(
+            &DiskFs,
+            &dir.path().join("romance.toml"),
+            "[project]\nname = \"test\"\n\n[backend]\nport = 3000\ndatabase_url = \"postgres://localhost/test\"\n\n[frontend]\nport = 5173\napi_base_url = \"http://localhost:3000\"\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn reports_modified_deleted_and_untracked_files() {
+        let dir = project();
+        let root = dir.path();
+        let mut manifest = Manifest::new("test", "0.0.0");
+        for (path, content) in [
+            ("backend/src/kept.rs", "fn a() {}\n// === ROMANCE:CUSTOM ===\n"),
+            ("backend/src/edited.rs", "fn b() {}\n"),
+            ("backend/src/gone.rs", "fn c() {}\n"),
+            ("backend/src/lib.rs", "mod a;\n// === ROMANCE:MODS ===\n"),
+        ] {
+            manifest.record_file(path, None, FileCategory::Scaffold, content, None);
+            manifest::save_base(&DiskFs, root, path, content).unwrap();
+            write_file(&DiskFs, &root.join(path), content).unwrap();
+        }
+        manifest.save(&DiskFs, root).unwrap();
+        // Custom block edits don't count
+        write_file(&DiskFs, &root.join("backend/src/kept.rs"), "fn a() {}\n// === ROMANCE:CUSTOM ===\nfn mine() {}\n").unwrap();
+        write_file(&DiskFs, &root.join("backend/src/edited.rs"), "fn b() { todo!() }\n").unwrap();
+        // Neither do registrations added by later generators
+        write_file(&DiskFs, &root.join("backend/src/lib.rs"), "mod a;\nmod b;\n// === ROMANCE:MODS ===\n").unwrap();
+        std::fs::remove_file(root.join("backend/src/gone.rs")).unwrap();
+        write_file(&DiskFs, &root.join("backend/src/stray.rs"), "// === ROMANCE:CUSTOM ===\n").unwrap();
+        write_file(&DiskFs, &root.join("backend/src/plain.rs"), "fn d() {}\n").unwrap();
+
+        let status = scan(&Project::open(root).unwrap()).unwrap();
+        let paths = |files: &[TrackedFile]| files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&status.modified), vec!["backend/src/edited.rs"]);
+        assert_eq!(paths(&status.deleted), vec!["backend/src/gone.rs"]);
+        assert_eq!(status.untracked, vec!["backend/src/stray.rs"]);
+        assert!(!status.is_clean());
+    }
+
+    #[test]
+    fn reports_entities_without_migrations() {
+        let dir = project();
+        let root = dir.path();
+        let model = |table: &str| format!("#[sea_orm(table_name = \"{}\")]\npub struct Model;\n", table);
+        write_file(&DiskFs, &root.join("backend/src/entities/mod.rs"), "").unwrap();
+        write_file(&DiskFs, &root.join("backend/src/entities/post.rs"), &model("posts")).unwrap();
+        write_file(&DiskFs, &root.join("backend/src/entities/user.rs"), &model("users")).unwrap();
+        write_file(&DiskFs, &root.join("backend/src/entities/tag.rs"), &model("tags")).unwrap();
+        write_file(&DiskFs, &root.join("backend/migration/src/m20240101000000_create_post_table.rs"), "").unwrap();
+        write_file(&DiskFs, &root.join("backend/migration/src/m20240101000001_create_users_table.rs"), "").unwrap();
+
+        let status = scan(&Project::open(root).unwrap()).unwrap();
+        assert!(!status.has_manifest);
+        assert_eq!(status.missing_migrations, vec!["tag"]);
+
+        // Imported tables are covered by a baseline migration
+        write_file(
+            &DiskFs,
+            &root.join("backend/migration/src/m20240101000002_import_baseline.rs"),
+            "//! Tables: tags, legacy_things\n",
+        )
+        .unwrap();
+        let status = scan(&Project::open(root).unwrap()).unwrap();
+        assert!(status.missing_migrations.is_empty());
+    }
+}
@@ -1,11 +1,19 @@
+use crate::fs::ProjectFs;
+use crate::project::Project;
 use anyhow::{Context as _, Result};
 use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase, ToTitleCase};
 use romance_templates::Templates;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tera::{Context, Tera, Value};
 
+/// Project-local template overrides, layered on top of the embedded set.
+pub const OVERRIDE_DIR: &str = ".romance/templates";
+
 pub struct TemplateEngine {
     tera: Tera,
+    /// Raw (non-rendered) files overridden by the project, keyed by embedded path.
+    raw_overrides: HashMap<String, String>,
 }
 
 impl TemplateEngine {
@@ -32,7 +40,40 @@ impl TemplateEngine {
         tera.register_filter("title_case", title_case_filter);
         tera.register_filter("rust_ident", rust_ident_filter);
 
-        Ok(TemplateEngine { tera })
+        Ok(TemplateEngine {
+            tera,
+            raw_overrides: HashMap::new(),
+        })
+    }
+
+    /// Create an engine for a project, layering its template overrides on top
+    /// of the embedded templates.
+    ///
+    /// Overrides are looked up in the directory configured as `[templates] dir`
+    /// in romance.toml, then in `.romance/templates/`. A file overrides the
+    /// embedded template with the same relative path, e.g.
+    /// `.romance/templates/entity/frontend/List.tsx.tera`.
+    pub fn for_project(project: &Project) -> Result<Self> {
+        let mut engine = Self::new()?;
+        for dir in override_dirs(project) {
+            engine.load_overrides(project.fs(), &dir)?;
+        }
+        Ok(engine)
+    }
+
+    fn load_overrides(&mut self, fs: &dyn ProjectFs, dir: &Path) -> Result<()> {
+        for (name, path) in list_override_files(fs, dir)? {
+            let content = fs.read_to_string(&path)
+                .with_context(|| format!("Failed to read template override {}", path.display()))?;
+            if name.ends_with(".tera") {
+                self.tera
+                    .add_raw_template(&name, &content)
+                    .with_context(|| format!("Invalid template override {}", path.display()))?;
+            } else {
+                self.raw_overrides.insert(name, content);
+            }
+        }
+        Ok(())
     }
 
     pub fn render(&self, template_name: &str, context: &Context) -> Result<String> {
@@ -43,8 +84,26 @@ impl TemplateEngine {
         Ok(result)
     }
 
+    /// Register an additional template (e.g. from a user-defined recipe) under `name`.
+    pub fn add_template(&mut self, name: &str, content: &str) -> Result<()> {
+        self.tera
+            .add_raw_template(name, content)
+            .with_context(|| format!("Invalid template '{}'", name))?;
+        Ok(())
+    }
+
+    /// Render an inline template string with the same filters as file templates.
+    pub fn render_str(&mut self, source: &str, context: &Context) -> Result<String> {
+        self.tera
+            .render_str(source, context)
+            .with_context(|| format!("Failed to render '{}'", source))
+    }
+
     /// Read an embedded file as raw string without Tera rendering.
     pub fn get_raw(&self, path: &str) -> Result<String> {
+        if let Some(content) = self.raw_overrides.get(path) {
+            return Ok(content.clone());
+        }
         let content = Templates::get(path)
             .with_context(|| format!("Embedded file '{}' not found", path))?;
         let s = std::str::from_utf8(content.data.as_ref())
@@ -53,6 +112,129 @@ impl TemplateEngine {
     }
 }
 
+/// Directories holding template overrides for a project, lowest precedence first.
+pub fn override_dirs(project: &Project) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = &project.config().templates.dir {
+        dirs.push(project.join(dir));
+    }
+    dirs.push(project.join(OVERRIDE_DIR));
+    dirs
+}
+
+/// List override files in `dir` as (template name, path) pairs.
+pub fn list_override_files(fs: &dyn ProjectFs, dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    if fs.is_dir(dir) {
+        collect_files(fs, dir, dir, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(fs: &dyn ProjectFs, root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for path in fs.read_dir(dir)? {
+        if fs.is_dir(&path) {
+            collect_files(fs, root, &path, files)?;
+        } else {
+            let name = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Read the embedded (upstream) version of a template.
+pub fn embedded_template(name: &str) -> Option<String> {
+    Templates::get(name).and_then(|f| String::from_utf8(f.data.into_owned()).ok())
+}
+
+/// Names of all embedded templates and static files.
+pub fn embedded_template_names() -> Vec<String> {
+    let mut names: Vec<String> = Templates::iter().map(|f| f.to_string()).collect();
+    names.sort();
+    names
+}
+
+/// Resolve a template name given on the command line to an embedded template,
+/// accepting the name with or without its `.tera` extension.
+pub fn resolve_template_name(name: &str) -> Result<String> {
+    let name = name.trim_start_matches("./").trim_start_matches('/');
+    for candidate in [name.to_string(), format!("{}.tera", name)] {
+        if Templates::get(&candidate).is_some() {
+            return Ok(candidate);
+        }
+    }
+    let similar: Vec<String> = embedded_template_names()
+        .into_iter()
+        .filter(|n| n.contains(name))
+        .take(10)
+        .collect();
+    if similar.is_empty() {
+        anyhow::bail!(
+            "Unknown template '{}'. Run `romance template list` to see available templates.",
+            name
+        );
+    }
+    anyhow::bail!(
+        "Unknown template '{}'. Did you mean one of:\n  {}",
+        name,
+        similar.join("\n  ")
+    )
+}
+
+/// Copy an embedded template into `.romance/templates/` for editing and
+/// record its upstream hash in the manifest.
+///
+/// Returns the resolved template name and the path of the ejected copy.
+pub fn eject(project: &Project, name: &str, force: bool) -> Result<(String, PathBuf)> {
+    let name = resolve_template_name(name)?;
+    let content = embedded_template(&name)
+        .with_context(|| format!("Embedded template '{}' is not valid UTF-8", name))?;
+
+    let dest = project.join(OVERRIDE_DIR).join(&name);
+    if project.fs().exists(&dest) && !force {
+        anyhow::bail!(
+            "{} already exists. Use --force to overwrite it with the current default.",
+            dest.display()
+        );
+    }
+    crate::utils::write_file(project.fs(), &dest, &content)?;
+
+    if let Some(mut manifest) = project.manifest()? {
+        manifest.ejected_templates.insert(
+            name.clone(),
+            crate::manifest::EjectedTemplate {
+                upstream_hash: crate::manifest::content_hash(&content),
+                ejected_at: chrono::Utc::now().to_rfc3339(),
+                romance_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        );
+        manifest.save(project.fs(), project.root())?;
+    }
+
+    Ok((name, dest))
+}
+
+/// Ejected templates whose embedded (upstream) version changed since they were
+/// ejected or last reviewed.
+pub fn changed_upstream_templates(manifest: &crate::manifest::Manifest) -> Vec<String> {
+    manifest
+        .ejected_templates
+        .iter()
+        .filter(|(name, record)| {
+            embedded_template(name)
+                .map(|c| crate::manifest::content_hash(&c) != record.upstream_hash)
+                .unwrap_or(true)
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
 fn snake_case_filter(
     value: &Value,
     _args: &HashMap<String, Value>,
@@ -116,6 +298,7 @@ fn rust_ident_filter(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
 
     // ── Filter unit tests (direct function calls) ─────────────────────
 
@@ -282,4 +465,111 @@ mod tests {
         let result = tera.render("test", &ctx).unwrap();
         assert_eq!(result, "Hello Romance, port 3000");
     }
+
+    // ── Project overrides ─────────────────────────────────────────────
+
+    const LIST_TEMPLATE: &str = "entity/frontend/List.tsx.tera";
+
+    /// A project in `dir`, with `extra` appended to its `romance.toml`.
+    fn project(dir: &Path, extra: &str) -> Project {
+        std::fs::write(
+            dir.join("romance.toml"),
+            format!(
+                r#"
+[project]
+name = "app"
+
+[backend]
+port = 3000
+database_url = "postgres://localhost/app"
+
+[frontend]
+port = 5173
+api_base_url = "http://localhost:3000"
+{}"#,
+                extra
+            ),
+        )
+        .unwrap();
+        Project::open(dir).unwrap()
+    }
+
+    #[test]
+    fn for_project_without_overrides_matches_embedded() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = TemplateEngine::for_project(&project(dir.path(), "")).unwrap();
+        assert_eq!(
+            engine.get_raw(LIST_TEMPLATE).unwrap(),
+            TemplateEngine::new().unwrap().get_raw(LIST_TEMPLATE).unwrap()
+        );
+    }
+
+    #[test]
+    fn project_override_replaces_embedded_template() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::utils::write_file(
+            &DiskFs,
+            &dir.path().join(OVERRIDE_DIR).join(LIST_TEMPLATE),
+            "custom list for {{ entity_name }}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::for_project(&project(dir.path(), "")).unwrap();
+        let mut ctx = Context::new();
+        ctx.insert("entity_name", "Post");
+        assert_eq!(engine.render(LIST_TEMPLATE, &ctx).unwrap(), "custom list for Post");
+    }
+
+    #[test]
+    fn romance_override_dir_wins_over_configured_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path(), "\n[templates]\ndir = \"design-system\"\n");
+        crate::utils::write_file(&DiskFs, &dir.path().join("design-system/a.txt.tera"), "shared").unwrap();
+        crate::utils::write_file(&DiskFs, &dir.path().join("design-system/b.txt.tera"), "shared").unwrap();
+        crate::utils::write_file(&DiskFs, &dir.path().join(OVERRIDE_DIR).join("b.txt.tera"), "local").unwrap();
+
+        let engine = TemplateEngine::for_project(&project).unwrap();
+        let ctx = Context::new();
+        assert_eq!(engine.render("a.txt.tera", &ctx).unwrap(), "shared");
+        assert_eq!(engine.render("b.txt.tera", &ctx).unwrap(), "local");
+    }
+
+    #[test]
+    fn eject_copies_template_and_refuses_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path(), "");
+        let (name, path) = eject(&project, "entity/frontend/List.tsx", false).unwrap();
+        assert_eq!(name, LIST_TEMPLATE);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            embedded_template(LIST_TEMPLATE).unwrap()
+        );
+        assert!(eject(&project, LIST_TEMPLATE, false).is_err());
+        assert!(eject(&project, LIST_TEMPLATE, true).is_ok());
+    }
+
+    #[test]
+    fn eject_unknown_template_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = eject(&project(dir.path(), ""), "nope/missing.tera", false).unwrap_err();
+        assert!(err.to_string().contains("Unknown template"));
+    }
+
+    #[test]
+    fn changed_upstream_detects_hash_mismatch() {
+        let mut manifest = crate::manifest::Manifest::new("app", "0.0.0");
+        manifest.ejected_templates.insert(
+            LIST_TEMPLATE.to_string(),
+            crate::manifest::EjectedTemplate {
+                upstream_hash: "sha256:stale".to_string(),
+                ejected_at: String::new(),
+                romance_version: String::new(),
+            },
+        );
+        assert_eq!(changed_upstream_templates(&manifest), vec![LIST_TEMPLATE]);
+
+        let current = crate::manifest::content_hash(&embedded_template(LIST_TEMPLATE).unwrap());
+        manifest.ejected_templates.get_mut(LIST_TEMPLATE).unwrap().upstream_hash = current;
+        assert!(changed_upstream_templates(&manifest).is_empty());
+    }
 }
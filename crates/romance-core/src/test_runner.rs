@@ -1,131 +1,87 @@
+use crate::database;
+use crate::error::Failure;
+use crate::project::Project;
+use crate::say;
+use crate::utils;
 use anyhow::Result;
 use colored::Colorize;
-use std::path::Path;
 use std::process::Command;
 
 /// Run the test suite with a temporary database.
-pub fn run_tests(project_root: &Path) -> Result<()> {
-    println!("{}", "Running tests...".bold());
+pub fn run_tests(project: &Project) -> Result<()> {
+    say!("{}", "Running tests...".bold());
 
-    let backend_dir = project_root.join("backend");
-    if !backend_dir.exists() {
-        anyhow::bail!("Backend directory not found");
+    let fs = project.fs();
+    let backend_dir = project.join("backend");
+    if !fs.exists(&backend_dir) {
+        return Err(Failure::Precondition.error("Backend directory not found"));
     }
 
     // Generate test_helpers.rs if not present
     let test_helpers_path = backend_dir.join("src/test_helpers.rs");
-    if !test_helpers_path.exists() {
-        if let Ok(engine) = crate::template::TemplateEngine::new() {
+    if !fs.exists(&test_helpers_path) {
+        if let Ok(engine) = crate::template::TemplateEngine::for_project(project) {
             let ctx = tera::Context::new();
             if let Ok(content) = engine.render("addon/test/test_helpers.rs.tera", &ctx) {
-                let _ = crate::utils::write_file(&test_helpers_path, &content);
+                let _ = crate::utils::write_file(fs, &test_helpers_path, &content);
                 // Add mod test_helpers to main.rs
                 let main_path = backend_dir.join("src/main.rs");
-                if let Ok(main_content) = std::fs::read_to_string(&main_path) {
+                if let Ok(main_content) = fs.read_to_string(&main_path) {
                     if !main_content.contains("mod test_helpers;") {
                         let new_content = main_content.replace(
                             "mod errors;",
                             "mod errors;\n#[cfg(test)]\nmod test_helpers;",
                         );
-                        let _ = std::fs::write(&main_path, new_content);
+                        let _ = fs.write(&main_path, &new_content);
                     }
                 }
-                println!("  {} backend/src/test_helpers.rs", "create".green());
+                utils::ui::created("backend/src/test_helpers.rs");
             }
         }
     }
 
-    // Generate a unique test database name
-    let db_name = format!(
-        "romance_test_{}",
-        uuid::Uuid::new_v4().to_string().replace('-', "")[..8].to_string()
-    );
-
-    // Try to read the base DATABASE_URL to derive the test URL
-    let env_path = backend_dir.join(".env");
-    let base_url = if env_path.exists() {
-        let content = std::fs::read_to_string(&env_path)?;
-        content
-            .lines()
-            .find(|l| l.starts_with("DATABASE_URL="))
-            .and_then(|l| l.strip_prefix("DATABASE_URL="))
-            .map(|s| s.to_string())
-    } else {
-        None
-    };
-
-    let test_db_url = if let Some(ref url) = base_url {
-        // Replace the database name at the end of the URL
-        if let Some(pos) = url.rfind('/') {
-            format!("{}/{}", &url[..pos], db_name)
-        } else {
-            format!("postgres://localhost/{}", db_name)
-        }
-    } else {
-        format!("postgres://localhost/{}", db_name)
-    };
-
-    println!("  Creating test database: {}", db_name);
-
-    // Create test database
-    let create_status = Command::new("createdb")
-        .arg(&db_name)
-        .status();
-
-    let db_created = match create_status {
-        Ok(s) if s.success() => true,
-        _ => {
-            println!(
-                "{}",
-                "  Warning: Could not create test database (createdb not available)".yellow()
-            );
-            println!("  Running tests with existing DATABASE_URL...");
-            false
+    say!("  Creating test database...");
+    let test_db = match database::ScratchDatabase::create(project, "romance_test") {
+        Ok(db) => Some(db),
+        Err(e) => {
+            utils::ui::warn(&format!("Could not create test database: {}", e));
+            say!("  Running tests with existing DATABASE_URL...");
+            None
         }
     };
 
     // Run migrations on test database
-    if db_created {
-        println!("  Running migrations on test database...");
-        let migration_status = Command::new("cargo")
-            .args(["run", "--bin", "migration", "--", "up"])
-            .current_dir(&backend_dir)
-            .env("DATABASE_URL", &test_db_url)
-            .status()?;
-
-        if !migration_status.success() {
-            // Clean up test database
-            let _ = Command::new("dropdb").arg(&db_name).status();
-            anyhow::bail!("Test migrations failed");
+    if let Some(db) = &test_db {
+        say!("  Running migrations on test database {}...", db.name);
+        if database::migrate(&backend_dir, &db.url).is_err() {
+            return Err(Failure::Command.error("Test migrations failed"));
         }
     }
 
     // Run tests
-    let env_var = if db_created {
-        test_db_url.clone()
-    } else if let Some(url) = base_url {
-        url
-    } else {
-        "postgres://localhost/romance_test".to_string()
+    let env_var = match &test_db {
+        Some(db) => db.url.clone(),
+        None => database::url(project).unwrap_or_else(|| "postgres://localhost/romance_test".to_string()),
     };
 
-    println!("  Running cargo test...");
+    say!("  Running cargo test...");
     let test_status = Command::new("cargo")
         .args(["test"])
         .current_dir(&backend_dir)
         .env("DATABASE_URL", &env_var)
+        .stdout(utils::ui::child_stdout())
         .status()?;
 
     // Clean up test database
-    if db_created {
-        println!("  Dropping test database: {}", db_name);
-        let _ = Command::new("dropdb").arg(&db_name).status();
+    if let Some(db) = test_db {
+        say!("  Dropping test database: {}", db.name);
+        drop(db);
     }
 
     if !test_status.success() {
-        anyhow::bail!("Tests failed");
+        return Err(Failure::Command.error("Tests failed"));
     }
 
-    println!("{}", "All tests passed!".green().bold());
+    utils::ui::success("All tests passed!");
     Ok(())
 }
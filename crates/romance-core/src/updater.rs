@@ -1,31 +1,112 @@
-use crate::manifest::{content_hash, FileCategory, Manifest};
+use crate::addon::{self, Addon};
+use crate::generator;
+use crate::manifest::{self, content_hash, FileCategory, Manifest, RenderedFile};
+use crate::merge::{self, Merge, Side};
+use crate::module::{self, EntityModule};
+use crate::project::Project;
+use crate::relation;
 use crate::template::TemplateEngine;
 use crate::utils;
 use anyhow::Result;
-use heck::ToSnakeCase;
-use std::path::Path;
+use heck::{ToPascalCase, ToSnakeCase};
+use std::collections::{BTreeMap, HashSet};
 use tera::Context;
 
+/// The generator a file in an update plan belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UpdateGroup {
+    Scaffold,
+    Auth,
+    Addon(String),
+    Entity(String),
+}
+
+impl std::fmt::Display for UpdateGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateGroup::Scaffold => write!(f, "scaffold"),
+            UpdateGroup::Auth => write!(f, "auth"),
+            UpdateGroup::Addon(name) => write!(f, "addon {}", name),
+            UpdateGroup::Entity(name) => write!(f, "entity {}", name),
+        }
+    }
+}
+
 /// Describes one file's update status.
 #[derive(Debug)]
 pub struct UpdateItem {
     pub output_path: String,
-    pub template_path: String,
+    pub group: UpdateGroup,
+    /// What the current templates render for the file.
+    pub generated: RenderedFile,
+    /// What an update writes: the new template output, with the file's
+    /// `ROMANCE:CUSTOM` block and code injected above markers kept.
     pub new_content: String,
     pub old_generated_hash: String,
     pub user_modified: bool,
     pub template_changed: bool,
     pub current_content: Option<String>,
+    /// Three-way merge of the generated base, the current file and the new
+    /// template output, when the file was modified on both sides and its base
+    /// was kept.
+    pub merge: Option<Merge>,
 }
 
 /// Result of scanning the project for updatable files.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct UpdatePlan {
     pub auto_update: Vec<UpdateItem>,
+    /// Modified on both sides, but the changes don't overlap.
+    pub merged: Vec<UpdateItem>,
     pub conflicts: Vec<UpdateItem>,
     pub unchanged: Vec<UpdateItem>,
     pub new_files: Vec<UpdateItem>,
-    pub deleted: Vec<String>,
+    /// Generated files the manifest doesn't know yet (from before entity,
+    /// auth and addon files were tracked). Recording them makes the current
+    /// templates their base, so later template changes can be merged.
+    pub untracked: Vec<UpdateItem>,
+    pub deleted: Vec<UpdateItem>,
+    /// Groups that could not be re-rendered, with the reason.
+    pub skipped: Vec<(UpdateGroup, String)>,
+}
+
+/// Number of files in each state for one group of an [`UpdatePlan`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GroupPlan {
+    pub auto_update: usize,
+    pub merged: usize,
+    pub conflicts: usize,
+    pub unchanged: usize,
+    pub new_files: usize,
+    pub untracked: usize,
+    pub deleted: usize,
+}
+
+impl GroupPlan {
+    /// Whether the update changes any of the group's files.
+    pub fn has_changes(&self) -> bool {
+        self.auto_update + self.merged + self.conflicts + self.new_files > 0
+    }
+}
+
+impl UpdatePlan {
+    /// The plan summarised per scaffold, auth, addon and entity.
+    pub fn by_group(&self) -> BTreeMap<UpdateGroup, GroupPlan> {
+        let mut groups: BTreeMap<UpdateGroup, GroupPlan> = BTreeMap::new();
+        let mut count = |items: &[UpdateItem], field: fn(&mut GroupPlan) -> &mut usize| {
+            for item in items {
+                *field(groups.entry(item.group.clone()).or_default()) += 1;
+            }
+        };
+        count(&self.auto_update, |g| &mut g.auto_update);
+        count(&self.merged, |g| &mut g.merged);
+        count(&self.conflicts, |g| &mut g.conflicts);
+        count(&self.unchanged, |g| &mut g.unchanged);
+        count(&self.new_files, |g| &mut g.new_files);
+        count(&self.untracked, |g| &mut g.untracked);
+        count(&self.deleted, |g| &mut g.deleted);
+        groups
+    }
 }
 
 /// Files safe for automatic update (not user-edited config, not marker-managed).
@@ -182,94 +263,267 @@ pub fn get_scaffold_mappings() -> Vec<(&'static str, &'static str)> {
     ]
 }
 
-/// Scan the project and build an update plan for scaffold files.
-pub fn plan_update(project_dir: &Path) -> Result<UpdatePlan> {
-    let manifest = Manifest::load(project_dir)?;
-    let config = crate::config::RomanceConfig::load(project_dir)?;
-    let engine = TemplateEngine::new()?;
+/// Scan the project and build an update plan for its generated files:
+/// scaffold files, auth, installed addons and every generated entity.
+///
+/// Entities are recovered from their generated files and re-rendered through
+/// the generators.
+pub fn plan_update(project: &Project) -> Result<UpdatePlan> {
+    let project_dir = project.root();
+    let fs = project.fs();
+    let manifest = Manifest::load(fs, project_dir)?;
+    let config = project.config();
+    let engine = TemplateEngine::for_project(project)?;
 
     let mut ctx = Context::new();
     ctx.insert("project_name", &config.project.name);
     ctx.insert("project_name_snake", &config.project.name.to_snake_case());
+    // Reuse the project's secret so env.example renders reproducibly
+    let jwt_secret = fs
+        .read_to_string(&project_dir.join("backend/.env.example"))
+        .ok()
+        .and_then(|c| {
+            c.lines()
+                .find_map(|l| l.strip_prefix("JWT_SECRET=").map(|v| v.trim().to_string()))
+        })
+        .unwrap_or_else(crate::generator::auth::generate_jwt_secret);
+    ctx.insert("jwt_secret", &jwt_secret);
 
-    let mut plan = UpdatePlan {
-        auto_update: vec![],
-        conflicts: vec![],
-        unchanged: vec![],
-        new_files: vec![],
-        deleted: vec![],
-    };
+    let mut plan = UpdatePlan::default();
 
-    let mappings = get_updatable_scaffold_mappings();
+    for (template_path, output_path) in get_updatable_scaffold_mappings() {
+        let generated = RenderedFile {
+            output: output_path.to_string(),
+            template: Some(template_path.to_string()),
+            category: FileCategory::Scaffold,
+            entity_name: None,
+            content: engine.render(template_path, &ctx)?,
+        };
+        plan_file(project, &manifest, &mut plan, UpdateGroup::Scaffold, generated);
+    }
 
-    for (template_path, output_path) in &mappings {
-        let new_content = engine.render(template_path, &ctx)?;
-        let new_hash = content_hash(&new_content);
+    let mut groups: Vec<(UpdateGroup, Vec<RenderedFile>)> = Vec::new();
+    if fs.exists(&project_dir.join("backend/src/auth.rs")) {
+        groups.push((UpdateGroup::Auth, generator::auth::rendered_files(project)?));
+    }
+    for name in addon::BUILTIN_ADDONS {
+        if let Some(addon) = addon::builtin(name).filter(|a| a.is_already_installed(fs, project_dir)) {
+            groups.push((UpdateGroup::Addon(name.to_string()), addon.rendered_files(project)?));
+        }
+    }
+    for name in addon::declarative::list_installed(project)? {
+        let addon = addon::declarative::DeclarativeAddon::load_installed(project, &name)?;
+        groups.push((UpdateGroup::Addon(name), addon.rendered_files(project)?));
+    }
 
-        let full_path = project_dir.join(output_path);
-        let current_content = std::fs::read_to_string(&full_path).ok();
+    // Entity models that belong to auth or an addon are updated with it
+    let claimed: HashSet<String> = groups
+        .iter()
+        .flat_map(|(_, files)| files.iter().map(|f| f.output.clone()))
+        .collect();
+    let mut entities: Vec<(Option<EntityModule>, String)> = relation::discover_entities(project)?
+        .into_iter()
+        .map(|snake| (None, snake))
+        .collect();
+    for m in module::all(project) {
+        entities.extend(module::entities(project, &m)?.into_iter().map(|snake| (Some(m.clone()), snake)));
+    }
+    entities.sort_by(|a, b| a.1.cmp(&b.1));
+    for (entity_module, snake) in entities {
+        let model = module::backend_file(entity_module.as_ref(), "entities", &snake);
+        let generated = fs
+            .read_to_string(&project_dir.join(&model))
+            .is_ok_and(|content| content.contains("ROMANCE:CUSTOM"));
+        if claimed.contains(&model) || !generated {
+            continue;
+        }
+        let group = UpdateGroup::Entity(snake.to_pascal_case());
+        let files = generator::recover::entity(project, &snake).and_then(|entity| {
+            let mut files = generator::backend::rendered_files(project, &entity)?;
+            files.extend(generator::frontend::rendered_files(project, &entity)?);
+            Ok(files)
+        });
+        match files {
+            Ok(files) => groups.push((group, files)),
+            Err(e) => plan.skipped.push((group, format!("{:#}", e))),
+        }
+    }
 
-        if let Some(record) = manifest.files.get(*output_path) {
-            let template_changed = new_hash != record.generated_hash;
-            let user_modified = match &current_content {
-                Some(content) => content_hash(content) != record.generated_hash,
-                None => true,
-            };
+    for (group, files) in groups {
+        for generated in files {
+            plan_file(project, &manifest, &mut plan, group.clone(), generated);
+        }
+    }
 
-            if current_content.is_none() {
-                plan.deleted.push(output_path.to_string());
-                continue;
-            }
+    Ok(plan)
+}
 
-            let item = UpdateItem {
-                output_path: output_path.to_string(),
-                template_path: template_path.to_string(),
-                new_content,
-                old_generated_hash: record.generated_hash.clone(),
-                user_modified,
-                template_changed,
-                current_content,
-            };
-
-            if !template_changed {
-                plan.unchanged.push(item);
-            } else if !user_modified {
-                plan.auto_update.push(item);
-            } else {
-                plan.conflicts.push(item);
-            }
-        } else {
-            plan.new_files.push(UpdateItem {
-                output_path: output_path.to_string(),
-                template_path: template_path.to_string(),
-                new_content,
-                old_generated_hash: String::new(),
-                user_modified: false,
-                template_changed: true,
-                current_content,
-            });
+/// Classify one re-rendered file into the plan.
+fn plan_file(
+    project: &Project,
+    manifest: &Manifest,
+    plan: &mut UpdatePlan,
+    group: UpdateGroup,
+    generated: RenderedFile,
+) {
+    let fs = project.fs();
+    let project_dir = project.root();
+    let output_path = generated.output.clone();
+    let current_content = fs.read_to_string(&project_dir.join(&output_path)).ok();
+    let record = manifest.files.get(&output_path);
+    let scaffold = group == UpdateGroup::Scaffold;
+
+    let Some(record) = record else {
+        let item = UpdateItem {
+            output_path,
+            group,
+            new_content: generated.content.clone(),
+            generated,
+            old_generated_hash: String::new(),
+            user_modified: false,
+            template_changed: true,
+            current_content,
+            merge: None,
+        };
+        match (&item.current_content, scaffold) {
+            (_, true) => plan.new_files.push(item),
+            (Some(_), false) => plan.untracked.push(item),
+            // Only scaffold files are created; a missing entity or addon file
+            // was never generated here (or was removed on purpose)
+            (None, false) => {}
         }
+        return;
+    };
+
+    let Some(current) = current_content else {
+        plan.deleted.push(UpdateItem {
+            output_path,
+            group,
+            new_content: generated.content.clone(),
+            generated,
+            old_generated_hash: record.generated_hash.clone(),
+            user_modified: true,
+            template_changed: false,
+            current_content: None,
+            merge: None,
+        });
+        return;
+    };
+
+    let base = manifest::load_base(fs, project_dir, &output_path, &record.generated_hash);
+    let template_changed = content_hash(&generated.content) != record.generated_hash;
+    let user_modified = manifest::is_modified(&current, record, base.as_deref());
+    let merge = match &base {
+        Some(base) if template_changed && user_modified => {
+            Some(merge_generated(base, &current, &generated.content))
+        }
+        _ => None,
+    };
+    let previous = base.as_deref().unwrap_or(&generated.content);
+    let item = UpdateItem {
+        output_path,
+        group,
+        new_content: utils::rerender(&current, previous, &generated.content),
+        generated,
+        old_generated_hash: record.generated_hash.clone(),
+        user_modified,
+        template_changed,
+        current_content: Some(current),
+        merge,
+    };
+
+    if !template_changed {
+        plan.unchanged.push(item);
+    } else if !user_modified {
+        plan.auto_update.push(item);
+    } else if item.merge.as_ref().is_some_and(Merge::is_clean) {
+        plan.merged.push(item);
+    } else {
+        plan.conflicts.push(item);
     }
+}
 
-    Ok(plan)
+/// Merge the sections above `ROMANCE:CUSTOM` and keep the current custom block.
+fn merge_generated(base: &str, current: &str, generated: &str) -> Merge {
+    let custom = utils::split_custom_block(current)
+        .or_else(|| utils::split_custom_block(generated))
+        .map(|(_, custom)| custom)
+        .unwrap_or("");
+    let mut merge = merge::merge3(
+        utils::generated_section(base),
+        utils::generated_section(current),
+        utils::generated_section(generated),
+    );
+    merge.content.push_str(custom);
+    merge
 }
 
 /// Apply an update: write new content and update manifest record.
 pub fn apply_update(
-    project_dir: &Path,
+    project: &Project,
+    manifest: &mut Manifest,
+    item: &UpdateItem,
+) -> Result<()> {
+    write_and_record(project, manifest, item, &item.new_content)
+}
+
+/// Apply an item's three-way merge, conflict markers included. The new
+/// template output becomes the base for the next update.
+pub fn apply_merge(
+    project: &Project,
+    manifest: &mut Manifest,
+    item: &UpdateItem,
+) -> Result<()> {
+    let Some(merge) = &item.merge else {
+        anyhow::bail!("No merge available for {}", item.output_path);
+    };
+    write_and_record(project, manifest, item, &merge.content)
+}
+
+fn write_and_record(
+    project: &Project,
     manifest: &mut Manifest,
     item: &UpdateItem,
+    content: &str,
 ) -> Result<()> {
-    let full_path = project_dir.join(&item.output_path);
-    utils::write_file(&full_path, &item.new_content)?;
+    utils::write_file(project.fs(), &project.join(&item.output_path), content)?;
+    record_baseline(project, manifest, item)
+}
+
+/// Record the item's template output as its generated base without touching
+/// the file, so the next update can merge template changes into it.
+pub fn record_baseline(project: &Project, manifest: &mut Manifest, item: &UpdateItem) -> Result<()> {
+    let generated = &item.generated;
     manifest.record_file(
-        &item.output_path,
-        Some(&item.template_path),
-        FileCategory::Scaffold,
-        &item.new_content,
-        None,
+        &generated.output,
+        generated.template.as_deref(),
+        generated.category.clone(),
+        &generated.content,
+        generated.entity_name.as_deref(),
     );
-    Ok(())
+    manifest::save_base(project.fs(), project.root(), &generated.output, &generated.content)
+}
+
+/// Tracked files that still contain conflict markers from a merge, with the
+/// number of conflicts in each.
+pub fn unresolved_conflicts(project: &Project, manifest: &Manifest) -> Vec<(String, usize)> {
+    manifest
+        .files
+        .keys()
+        .filter_map(|path| {
+            let content = project.fs().read_to_string(&project.join(path)).ok()?;
+            let count = merge::count_conflicts(&content);
+            (count > 0).then(|| (path.clone(), count))
+        })
+        .collect()
+}
+
+/// Resolve every conflict in a file by keeping one side.
+pub fn resolve_conflicts(project: &Project, output_path: &str, keep: Side) -> Result<()> {
+    let fs = project.fs();
+    let full_path = project.join(output_path);
+    let content = fs.read_to_string(&full_path)?;
+    utils::write_file(fs, &full_path, &merge::resolve(&content, keep))
 }
 
 /// Generate a unified diff between two strings.
@@ -282,3 +536,139 @@ pub fn generate_diff(old: &str, new: &str, path: &str) -> String {
     }
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MemoryFs;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    const ERRORS: &str = "backend/src/errors.rs";
+
+    fn project() -> (Arc<MemoryFs>, Project) {
+        let memory = Arc::new(MemoryFs::new());
+        let project = crate::scaffold::render_project(memory.clone(), Path::new("/app"), "app").unwrap();
+        (memory, project)
+    }
+
+    fn read(project: &Project, rel: &str) -> String {
+        project.fs().read_to_string(&project.join(rel)).unwrap()
+    }
+
+    fn write(project: &Project, rel: &str, content: &str) {
+        utils::write_file(project.fs(), &project.join(rel), content).unwrap();
+    }
+
+    /// Pretend an older template generated `old` for `path`: it becomes the
+    /// recorded hash and base, and the file's content.
+    fn generated_before(project: &Project, path: &str, old: &str) {
+        let fs = project.fs();
+        let mut manifest = Manifest::load(fs, project.root()).unwrap();
+        let record = &manifest.files[path];
+        let (template, category) = (record.template.clone(), record.category.clone());
+        manifest.record_file(path, template.as_deref(), category, old, None);
+        manifest.save(fs, project.root()).unwrap();
+        manifest::save_base(fs, project.root(), path, old).unwrap();
+        write(project, path, old);
+    }
+
+    /// `content` with its first line replaced.
+    fn with_first_line(content: &str, line: &str) -> String {
+        let (_, rest) = content.split_once('\n').unwrap();
+        format!("{}\n{}", line, rest)
+    }
+
+    fn paths(items: &[UpdateItem]) -> Vec<&str> {
+        items.iter().map(|item| item.output_path.as_str()).collect()
+    }
+
+    #[test]
+    fn fresh_project_is_unchanged() {
+        let (_memory, project) = project();
+        let plan = plan_update(&project).unwrap();
+        assert!(paths(&plan.unchanged).contains(&ERRORS));
+        assert!(plan.auto_update.is_empty() && plan.merged.is_empty() && plan.conflicts.is_empty());
+        assert!(plan.new_files.is_empty() && plan.deleted.is_empty());
+    }
+
+    #[test]
+    fn unmodified_files_take_the_new_template() {
+        let (_memory, project) = project();
+        let generated = read(&project, ERRORS);
+        generated_before(&project, ERRORS, &with_first_line(&generated, "// old header"));
+
+        let plan = plan_update(&project).unwrap();
+        assert_eq!(paths(&plan.auto_update), vec![ERRORS]);
+        let item = &plan.auto_update[0];
+        assert!(item.template_changed && !item.user_modified);
+        assert_eq!(item.new_content, generated);
+
+        let mut manifest = Manifest::load(project.fs(), project.root()).unwrap();
+        apply_update(&project, &mut manifest, item).unwrap();
+        assert_eq!(read(&project, ERRORS), generated);
+        assert_eq!(manifest.files[ERRORS].generated_hash, content_hash(&generated));
+    }
+
+    #[test]
+    fn separate_changes_merge_cleanly() {
+        let (_memory, project) = project();
+        let generated = read(&project, ERRORS);
+        let old = with_first_line(&generated, "// old header");
+        generated_before(&project, ERRORS, &old);
+        write(&project, ERRORS, &format!("{}// kept by the user\n", old));
+
+        let plan = plan_update(&project).unwrap();
+        assert_eq!(paths(&plan.merged), vec![ERRORS]);
+        let item = &plan.merged[0];
+        let merge = item.merge.as_ref().unwrap();
+        assert_eq!(merge.content, format!("{}// kept by the user\n", generated));
+
+        let mut manifest = Manifest::load(project.fs(), project.root()).unwrap();
+        apply_merge(&project, &mut manifest, item).unwrap();
+        assert_eq!(read(&project, ERRORS), merge.content);
+        // The new template output is the base of the next update
+        let record = &manifest.files[ERRORS];
+        assert_eq!(record.generated_hash, content_hash(&generated));
+        assert_eq!(
+            manifest::load_base(project.fs(), project.root(), ERRORS, &record.generated_hash).as_deref(),
+            Some(generated.as_str())
+        );
+    }
+
+    #[test]
+    fn overlapping_changes_conflict() {
+        let (_memory, project) = project();
+        let generated = read(&project, ERRORS);
+        let old = with_first_line(&generated, "// old header");
+        generated_before(&project, ERRORS, &old);
+        write(&project, ERRORS, &with_first_line(&generated, "// user header"));
+
+        let plan = plan_update(&project).unwrap();
+        assert_eq!(paths(&plan.conflicts), vec![ERRORS]);
+        let item = &plan.conflicts[0];
+        assert!(item.template_changed && item.user_modified);
+        assert!(!item.merge.as_ref().unwrap().is_clean());
+
+        let mut manifest = Manifest::load(project.fs(), project.root()).unwrap();
+        apply_merge(&project, &mut manifest, item).unwrap();
+        assert_eq!(unresolved_conflicts(&project, &manifest), vec![(ERRORS.to_string(), 1)]);
+
+        resolve_conflicts(&project, ERRORS, Side::Yours).unwrap();
+        assert_eq!(read(&project, ERRORS), with_first_line(&generated, "// user header"));
+        assert!(unresolved_conflicts(&project, &manifest).is_empty());
+    }
+
+    #[test]
+    fn deleted_and_untracked_scaffold_files() {
+        let (_memory, project) = project();
+        project.fs().remove_file(&project.join(ERRORS)).unwrap();
+        let mut manifest = Manifest::load(project.fs(), project.root()).unwrap();
+        manifest.files.remove("backend/src/db.rs");
+        manifest.save(project.fs(), project.root()).unwrap();
+
+        let plan = plan_update(&project).unwrap();
+        assert_eq!(paths(&plan.deleted), vec![ERRORS]);
+        assert_eq!(paths(&plan.new_files), vec!["backend/src/db.rs"]);
+    }
+}
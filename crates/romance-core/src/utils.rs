@@ -1,43 +1,117 @@
+use crate::fs::ProjectFs;
 use anyhow::Result;
-use std::fs;
 use std::path::Path;
 
 /// Write content to a file, creating parent directories as needed.
-pub fn write_file(path: &Path, content: &str) -> Result<()> {
+pub fn write_file(fs: &dyn ProjectFs, path: &Path, content: &str) -> Result<()> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        fs.create_dir_all(parent)?;
     }
-    fs::write(path, content)?;
+    fs.write(path, content)?;
     Ok(())
 }
 
+const CUSTOM_MARKER: &str = "// === ROMANCE:CUSTOM ===";
+
 /// Read a file and split at the ROMANCE:CUSTOM marker.
 /// Returns (before_marker, custom_block) where custom_block includes the marker line.
-pub fn read_with_custom_block(path: &Path) -> Option<(String, String)> {
-    let content = fs::read_to_string(path).ok()?;
-    let marker = "// === ROMANCE:CUSTOM ===";
-    if let Some(pos) = content.find(marker) {
-        Some((content[..pos].to_string(), content[pos..].to_string()))
-    } else {
-        None
-    }
+pub fn read_with_custom_block(fs: &dyn ProjectFs, path: &Path) -> Option<(String, String)> {
+    let content = fs.read_to_string(path).ok()?;
+    split_custom_block(&content).map(|(before, custom)| (before.to_string(), custom.to_string()))
+}
+
+/// Split content at the ROMANCE:CUSTOM marker, like [`read_with_custom_block`].
+pub fn split_custom_block(content: &str) -> Option<(&str, &str)> {
+    content
+        .find(CUSTOM_MARKER)
+        .map(|pos| (&content[..pos], &content[pos..]))
+}
+
+/// The part of rendered template output above its ROMANCE:CUSTOM marker.
+pub fn generated_section(generated: &str) -> &str {
+    generated
+        .find(CUSTOM_MARKER)
+        .map(|pos| &generated[..pos])
+        .unwrap_or(generated)
 }
 
 /// Write generated content, preserving custom block if file already exists.
-pub fn write_generated(path: &Path, generated: &str) -> Result<()> {
-    let content = if let Some((_, custom_block)) = read_with_custom_block(path) {
-        format!("{}{}", generated, custom_block)
+pub fn write_generated(fs: &dyn ProjectFs, path: &Path, generated: &str) -> Result<()> {
+    let content = if let Some((_, custom_block)) = read_with_custom_block(fs, path) {
+        format!("{}{}", generated_section(generated), custom_block)
     } else {
         generated.to_string()
     };
-    write_file(path, &content)
+    write_file(fs, path, &content)
+}
+
+/// Write re-rendered content over an existing generated file.
+///
+/// Like [`write_generated`], but also keeps code that other generators have
+/// injected directly above a `ROMANCE:` marker since the file was rendered as
+/// `previous` (has-many handlers, many-to-many routes, `Related` impls...).
+pub fn write_rerendered(fs: &dyn ProjectFs, path: &Path, previous: &str, generated: &str) -> Result<()> {
+    match fs.read_to_string(path) {
+        Ok(current) if current.contains(CUSTOM_MARKER) => {
+            write_file(fs, path, &rerender(&current, previous, generated))
+        }
+        _ => write_generated(fs, path, generated),
+    }
+}
+
+/// The content [`write_rerendered`] would write over `current`.
+pub fn rerender(current: &str, previous: &str, generated: &str) -> String {
+    let Some((current, custom_block)) = split_custom_block(current) else {
+        return generated.to_string();
+    };
+    let previous = generated_section(previous);
+    let previous_lines: Vec<&str> = previous.split_inclusive('\n').collect();
+    let current_lines: Vec<&str> = current.split_inclusive('\n').collect();
+
+    let mut injected: Vec<(&str, String)> = Vec::new();
+    for op in similar::TextDiff::from_lines(previous, current).ops() {
+        if let similar::DiffOp::Insert { old_index, new_index, new_len } = *op {
+            let anchor = previous_lines.get(old_index).map(|l| l.trim()).unwrap_or("");
+            if anchor.starts_with("// === ROMANCE:") {
+                injected.push((anchor, current_lines[new_index..new_index + new_len].concat()));
+            }
+        }
+    }
+
+    let mut content = String::with_capacity(generated.len() + custom_block.len());
+    for line in generated_section(generated).split_inclusive('\n') {
+        for (marker, text) in &injected {
+            if line.trim() == *marker {
+                content.push_str(text);
+            }
+        }
+        content.push_str(line);
+    }
+    content.push_str(custom_block);
+    content
+}
+
+/// Whether the generated section of `current` only adds lines to `previous`,
+/// and `previous` has injection markers. Generators register modules, routes
+/// and relations by adding lines to such files, so additions there aren't
+/// counted as user edits.
+pub fn only_injected(current: &str, previous: &str) -> bool {
+    let previous = generated_section(previous);
+    let has_markers = previous
+        .lines()
+        .any(|l| l.contains("=== ROMANCE:") && !l.contains(CUSTOM_MARKER));
+    has_markers
+        && similar::TextDiff::from_lines(previous, generated_section(current))
+            .ops()
+            .iter()
+            .all(|op| matches!(op, similar::DiffOp::Equal { .. } | similar::DiffOp::Insert { .. }))
 }
 
 /// Insert a line before a named marker in a file.
 ///
 /// Returns an error if the marker is not found in the file.
-pub fn insert_at_marker(path: &Path, marker: &str, line: &str) -> Result<()> {
-    let content = fs::read_to_string(path)?;
+pub fn insert_at_marker(fs: &dyn ProjectFs, path: &Path, marker: &str, line: &str) -> Result<()> {
+    let content = fs.read_to_string(path)?;
     if content.contains(line) {
         return Ok(());
     }
@@ -48,8 +122,18 @@ pub fn insert_at_marker(path: &Path, marker: &str, line: &str) -> Result<()> {
             path.display()
         );
     }
-    let new_content = content.replace(marker, &format!("{}\n{}", line, marker));
-    fs::write(path, new_content)?;
+    let mut new_content = String::with_capacity(content.len() + line.len() + 1);
+    for existing in content.split_inclusive('\n') {
+        if existing.trim_start().starts_with(marker) {
+            // Insert above the marker line, keeping the marker's own indentation
+            new_content.push_str(line);
+            new_content.push('\n');
+            new_content.push_str(existing);
+        } else {
+            new_content.push_str(&existing.replace(marker, &format!("{}\n{}", line, marker)));
+        }
+    }
+    fs.write(path, &new_content)?;
     Ok(())
 }
 
@@ -69,6 +153,30 @@ pub fn pluralize(s: &str) -> String {
     }
 }
 
+/// Singularize a table name, undoing [`pluralize`] (`categories` ->
+/// `category`, `boxes` -> `box`). Words that don't look plural are returned
+/// unchanged.
+pub fn singularize(s: &str) -> String {
+    if let Some(stem) = s.strip_suffix("ies") {
+        if !stem.is_empty() {
+            return format!("{}y", stem);
+        }
+    }
+    if let Some(stem) = s.strip_suffix("es") {
+        // `statuses` -> `status`, but `houses` -> `house`
+        let latin_us = stem.len() > 2
+            && stem.ends_with("us")
+            && !matches!(stem.as_bytes()[stem.len() - 3], b'a' | b'e' | b'i' | b'o' | b'u' | b'r');
+        if stem.ends_with("ss") || stem.ends_with('x') || stem.ends_with("ch") || stem.ends_with("sh") || latin_us {
+            return stem.to_string();
+        }
+    }
+    match s.strip_suffix('s') {
+        Some(stem) if !stem.is_empty() && !stem.ends_with('s') && !stem.ends_with('u') => stem.to_string(),
+        _ => s.to_string(),
+    }
+}
+
 /// Rust reserved keywords that must be escaped with `r#` when used as identifiers.
 pub const RUST_RESERVED_WORDS: &[&str] = &[
     "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
@@ -91,68 +199,180 @@ pub fn rust_ident(name: &str) -> String {
 }
 
 /// Pretty CLI output helpers using the `colored` crate.
+///
+/// With `--json` ([`set_json`](ui::set_json)) the action helpers print one
+/// JSON event per line on stdout instead, e.g.
+/// `{"event":"created","path":"backend/src/entities/post.rs"}`, and
+/// everything else printed with [`say!`](crate::say) goes to stderr.
 pub mod ui {
     use colored::Colorize;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static JSON: AtomicBool = AtomicBool::new(false);
+    static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+
+    /// Switch between colored text and JSON events.
+    pub fn set_json(json: bool) {
+        JSON.store(json, Ordering::Relaxed);
+        if json {
+            colored::control::set_override(false);
+        }
+    }
+
+    pub fn is_json() -> bool {
+        JSON.load(Ordering::Relaxed)
+    }
+
+    /// Allow or forbid prompts. Without them, every prompt takes its
+    /// documented default.
+    pub fn set_interactive(interactive: bool) {
+        INTERACTIVE.store(interactive, Ordering::Relaxed);
+    }
+
+    pub fn is_interactive() -> bool {
+        INTERACTIVE.load(Ordering::Relaxed)
+    }
+
+    /// Print a JSON event: `{"event": kind, ...fields}`.
+    pub fn event(kind: &str, fields: Value) {
+        let mut event = json!({ "event": kind });
+        if let (Some(event), Value::Object(fields)) = (event.as_object_mut(), fields) {
+            event.extend(fields);
+        }
+        println!("{}", event);
+    }
 
     /// Print a "create" action (green)
     pub fn created(path: &str) {
+        if is_json() {
+            return event("created", json!({ "path": path }));
+        }
         println!("  {} {}", "create".green(), path);
     }
 
     /// Print an "update" action (cyan)
     pub fn updated(path: &str) {
+        if is_json() {
+            return event("updated", json!({ "path": path }));
+        }
         println!("  {} {}", "update".cyan(), path);
     }
 
+    /// Print an "update" action with what changed, e.g. `added tenant_id`
+    pub fn updated_with(path: &str, detail: &str) {
+        if is_json() {
+            return event("updated", json!({ "path": path, "detail": detail }));
+        }
+        println!("  {} {} ({})", "update".cyan(), path, detail);
+    }
+
     /// Print a "skip" action (yellow)
     pub fn skipped(path: &str, reason: &str) {
+        if is_json() {
+            return event("skipped", json!({ "path": path, "reason": reason }));
+        }
         println!("  {} {} ({})", "skip".yellow(), path, reason);
     }
 
     /// Print a "remove" action (red)
     pub fn removed(path: &str) {
+        if is_json() {
+            return event("removed", json!({ "path": path }));
+        }
         println!("  {} {}", "remove".red(), path);
     }
 
     /// Print an "inject" action (magenta)
     pub fn injected(target: &str, what: &str) {
+        if is_json() {
+            return event("injected", json!({ "path": target, "line": what }));
+        }
         println!("  {} {} → {}", "inject".magenta(), what, target);
     }
 
     /// Print a section header (bold)
     pub fn section(title: &str) {
-        println!("\n{}", title.bold());
+        if !is_json() {
+            println!("\n{}", title.bold());
+        }
     }
 
     /// Print a success message (green bold)
     pub fn success(msg: &str) {
+        if is_json() {
+            return event("success", json!({ "message": msg }));
+        }
         println!("\n{}", msg.green().bold());
     }
 
     /// Print a warning (yellow)
     pub fn warn(msg: &str) {
+        if is_json() {
+            return event("warning", json!({ "message": msg }));
+        }
         println!("  {} {}", "warn".yellow(), msg);
     }
 
     /// Print an error (red)
     pub fn error(msg: &str) {
+        if is_json() {
+            return event("error", json!({ "message": msg }));
+        }
         eprintln!("  {} {}", "error".red(), msg);
     }
 
     /// Print a check result (pass)
     pub fn check_pass(msg: &str) {
+        if is_json() {
+            return event("check", json!({ "ok": true, "message": msg }));
+        }
         println!("  {} {}", "✓".green(), msg);
     }
 
     /// Print a check result (fail)
     pub fn check_fail(msg: &str) {
+        if is_json() {
+            return event("check", json!({ "ok": false, "message": msg }));
+        }
         println!("  {} {}", "✗".red(), msg);
     }
+
+    /// Where child processes (cargo, npm...) should write their output:
+    /// the terminal, or stderr with `--json` so stdout stays parseable.
+    pub fn child_stdout() -> std::process::Stdio {
+        if is_json() {
+            std::io::stderr().into()
+        } else {
+            std::process::Stdio::inherit()
+        }
+    }
+}
+
+/// `println!` for human-readable output, which goes to stderr with `--json`
+/// so that stdout carries only [`ui`] events.
+#[macro_export]
+macro_rules! say {
+    () => {
+        if $crate::utils::ui::is_json() {
+            eprintln!();
+        } else {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        if $crate::utils::ui::is_json() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::DiskFs;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -196,6 +416,21 @@ mod tests {
         assert_eq!(pluralize("company"), "companies");
     }
 
+    #[test]
+    fn singularize_reverses_pluralize() {
+        for word in ["post", "category", "box", "match", "dish", "class", "bus", "day", "user_role"] {
+            assert_eq!(singularize(&pluralize(word)), word);
+        }
+    }
+
+    #[test]
+    fn singularize_keeps_singular_words() {
+        assert_eq!(singularize("status"), "status");
+        assert_eq!(singularize("address"), "address");
+        assert_eq!(singularize("person"), "person");
+        assert_eq!(singularize("houses"), "house");
+    }
+
     #[test]
     fn pluralize_vowel_y_preserved() {
         assert_eq!(pluralize("day"), "days");
@@ -240,7 +475,7 @@ mod tests {
     fn write_file_creates_parent_dirs() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("a/b/c/test.txt");
-        write_file(&path, "hello").unwrap();
+        write_file(&DiskFs, &path, "hello").unwrap();
         assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello");
     }
 
@@ -254,7 +489,7 @@ mod tests {
         writeln!(tmp, "// footer").unwrap();
         tmp.flush().unwrap();
 
-        insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
 
         let content = std::fs::read_to_string(tmp.path()).unwrap();
         assert!(content.contains("pub mod post;\n// === ROMANCE:MODS ==="));
@@ -267,8 +502,8 @@ mod tests {
         writeln!(tmp, "// === ROMANCE:MODS ===").unwrap();
         tmp.flush().unwrap();
 
-        insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
-        insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
 
         let content = std::fs::read_to_string(tmp.path()).unwrap();
         // Should appear exactly once
@@ -281,8 +516,8 @@ mod tests {
         writeln!(tmp, "// === ROMANCE:MODS ===").unwrap();
         tmp.flush().unwrap();
 
-        insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
-        insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod user;").unwrap();
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod user;").unwrap();
 
         let content = std::fs::read_to_string(tmp.path()).unwrap();
         assert!(content.contains("pub mod post;"));
@@ -295,6 +530,46 @@ mod tests {
         assert!(user_pos < marker_pos);
     }
 
+    #[test]
+    fn insert_at_marker_keeps_marker_indentation() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        writeln!(tmp, "        vec![").unwrap();
+        writeln!(tmp, "            // === ROMANCE:MIGRATIONS ===").unwrap();
+        tmp.flush().unwrap();
+
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MIGRATIONS ===", "            Box::new(a::Migration),").unwrap();
+
+        let content = std::fs::read_to_string(tmp.path()).unwrap();
+        assert_eq!(
+            content,
+            "        vec![\n            Box::new(a::Migration),\n            // === ROMANCE:MIGRATIONS ===\n"
+        );
+    }
+
+    #[test]
+    fn insert_at_marker_leaves_unindented_markers_as_before() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "// header\n// === ROMANCE:MODS ===\n// footer\n").unwrap();
+        tmp.flush().unwrap();
+
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;").unwrap();
+
+        let content = std::fs::read_to_string(tmp.path()).unwrap();
+        assert_eq!(content, "// header\npub mod post;\n// === ROMANCE:MODS ===\n// footer\n");
+    }
+
+    #[test]
+    fn insert_at_marker_keeps_inline_markers_in_place() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "const ROUTES = [ // === ROMANCE:ROUTES ===\n];\n").unwrap();
+        tmp.flush().unwrap();
+
+        insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:ROUTES ===", "post,").unwrap();
+
+        let content = std::fs::read_to_string(tmp.path()).unwrap();
+        assert_eq!(content, "const ROUTES = [ post,\n// === ROMANCE:ROUTES ===\n];\n");
+    }
+
     #[test]
     fn insert_at_marker_missing_marker_errors() {
         let mut tmp = NamedTempFile::new().unwrap();
@@ -302,7 +577,7 @@ mod tests {
         writeln!(tmp, "// no marker here").unwrap();
         tmp.flush().unwrap();
 
-        let result = insert_at_marker(tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;");
+        let result = insert_at_marker(&DiskFs, tmp.path(), "// === ROMANCE:MODS ===", "pub mod post;");
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("Marker"));
@@ -317,7 +592,7 @@ mod tests {
         write!(tmp, "generated code\n// === ROMANCE:CUSTOM ===\nuser code\n").unwrap();
         tmp.flush().unwrap();
 
-        let (generated, custom) = read_with_custom_block(tmp.path()).unwrap();
+        let (generated, custom) = read_with_custom_block(&DiskFs, tmp.path()).unwrap();
         assert_eq!(generated, "generated code\n");
         assert!(custom.starts_with("// === ROMANCE:CUSTOM ==="));
         assert!(custom.contains("user code"));
@@ -326,16 +601,16 @@ mod tests {
     #[test]
     fn read_with_custom_block_no_marker() {
         let mut tmp = NamedTempFile::new().unwrap();
-        write!(tmp, "just some code without marker\n").unwrap();
+        writeln!(tmp, "just some code without marker").unwrap();
         tmp.flush().unwrap();
 
-        assert!(read_with_custom_block(tmp.path()).is_none());
+        assert!(read_with_custom_block(&DiskFs, tmp.path()).is_none());
     }
 
     #[test]
     fn read_with_custom_block_nonexistent_file() {
         let path = Path::new("/tmp/romance_test_nonexistent_file_12345.rs");
-        assert!(read_with_custom_block(path).is_none());
+        assert!(read_with_custom_block(&DiskFs, path).is_none());
     }
 
     // ── write_generated ───────────────────────────────────────────────
@@ -345,7 +620,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("new.rs");
 
-        write_generated(&path, "generated content\n").unwrap();
+        write_generated(&DiskFs, &path, "generated content\n").unwrap();
         assert_eq!(std::fs::read_to_string(&path).unwrap(), "generated content\n");
     }
 
@@ -355,7 +630,7 @@ mod tests {
         write!(tmp, "old generated\n// === ROMANCE:CUSTOM ===\nmy custom code\n").unwrap();
         tmp.flush().unwrap();
 
-        write_generated(tmp.path(), "new generated\n").unwrap();
+        write_generated(&DiskFs, tmp.path(), "new generated\n").unwrap();
 
         let content = std::fs::read_to_string(tmp.path()).unwrap();
         assert!(content.starts_with("new generated\n"));
@@ -365,13 +640,53 @@ mod tests {
         assert!(!content.contains("old generated"));
     }
 
+    #[test]
+    fn write_generated_does_not_duplicate_custom_marker() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "old\n// === ROMANCE:CUSTOM ===\nmine\n").unwrap();
+        tmp.flush().unwrap();
+
+        write_generated(&DiskFs, tmp.path(), "new\n// === ROMANCE:CUSTOM ===\n// below\n").unwrap();
+
+        let content = std::fs::read_to_string(tmp.path()).unwrap();
+        assert_eq!(content, "new\n// === ROMANCE:CUSTOM ===\nmine\n");
+    }
+
+    #[test]
+    fn write_rerendered_keeps_code_injected_above_markers() {
+        let previous = "fn a(x: i32) {}\n\n// === ROMANCE:RELATIONS ===\n\n// === ROMANCE:CUSTOM ===\n";
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(
+            tmp,
+            "fn a(x: i32) {{}}\n\nfn injected() {{}}\n// === ROMANCE:RELATIONS ===\n\n// === ROMANCE:CUSTOM ===\nmine\n"
+        )
+        .unwrap();
+        tmp.flush().unwrap();
+
+        let generated = "fn a() {}\n\n// === ROMANCE:RELATIONS ===\n\n// === ROMANCE:CUSTOM ===\n";
+        write_rerendered(&DiskFs, tmp.path(), previous, generated).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(tmp.path()).unwrap(),
+            "fn a() {}\n\nfn injected() {}\n// === ROMANCE:RELATIONS ===\n\n// === ROMANCE:CUSTOM ===\nmine\n"
+        );
+    }
+
+    #[test]
+    fn only_injected_accepts_additions_to_marker_files() {
+        let previous = "mod a;\n// === ROMANCE:MODS ===\n";
+        assert!(only_injected("mod a;\nmod b;\n// === ROMANCE:MODS ===\n", previous));
+        assert!(!only_injected("mod b;\n// === ROMANCE:MODS ===\n", previous));
+        assert!(!only_injected("fn a() {}\nfn b() {}\n", "fn a() {}\n"));
+    }
+
     #[test]
     fn write_generated_no_custom_block_replaces_entirely() {
         let mut tmp = NamedTempFile::new().unwrap();
-        write!(tmp, "old content without custom marker\n").unwrap();
+        writeln!(tmp, "old content without custom marker").unwrap();
         tmp.flush().unwrap();
 
-        write_generated(tmp.path(), "new content\n").unwrap();
+        write_generated(&DiskFs, tmp.path(), "new content\n").unwrap();
 
         let content = std::fs::read_to_string(tmp.path()).unwrap();
         assert_eq!(content, "new content\n");
@@ -13,20 +13,23 @@
 //! structure (marker files) so that entity generators can work without
 //! needing a full scaffold (and thus without npm).
 //!
-//! IMPORTANT: The generators use relative paths from the current working
-//! directory (e.g., `Path::new("backend/src")`). Since tests share a single
-//! process and `set_current_dir` affects all threads, tests that change the
-//! working directory MUST be serialized via the CWD_LOCK mutex.
+//! Generators take a `Project` opened at the temp directory and no longer
+//! depend on the current working directory. Many tests still run inside
+//! `with_cwd`, as the CLI does; since `set_current_dir` affects all threads,
+//! tests that change the working directory MUST be serialized via the
+//! CWD_LOCK mutex.
 
 use std::fs;
 use std::path::Path;
 use std::sync::Mutex;
 
 use romance_core::addon::Addon;
+use romance_core::fs::DiskFs;
+use romance_core::project::Project;
 
 /// Global mutex to serialize tests that change the working directory.
-/// The generators use relative paths, so concurrent cwd changes would
-/// cause tests to interfere with each other.
+/// Some tests read files through relative paths, so concurrent cwd changes
+/// would cause tests to interfere with each other.
 static CWD_LOCK: Mutex<()> = Mutex::new(());
 
 /// Helper: create a minimal project structure that entity generators need.
@@ -144,7 +147,11 @@ export function SidebarContent() {
     )
     .unwrap();
 
-    // romance.toml (required by generators that load config)
+    write_romance_toml(project_dir);
+}
+
+/// Helper: write the `romance.toml` a [`Project`] needs to open.
+fn write_romance_toml(project_dir: &Path) {
     fs::write(
         project_dir.join("romance.toml"),
         r#"[project]
@@ -180,6 +187,11 @@ where
     }
 }
 
+/// Open the project at `project_dir`, for the generators that take one.
+fn open_project(project_dir: &Path) -> Project {
+    Project::open(project_dir).unwrap()
+}
+
 // ==========================================================================
 // Scaffold tests (require npm -- marked #[ignore])
 // ==========================================================================
@@ -227,6 +239,7 @@ fn test_scaffold_creates_expected_files() {
     assert!(project_dir.join("README.md").exists());
     assert!(project_dir.join(".gitignore").exists());
     assert!(project_dir.join(".romance/manifest.json").exists());
+    assert!(project_dir.join(".romance/base/backend/src/main.rs").exists(), "generated base kept for merges");
 
     // Docker and CI
     assert!(project_dir.join("Dockerfile").exists());
@@ -287,8 +300,8 @@ fn test_entity_generation_creates_backend_files() {
     .unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Backend entity files should exist
@@ -338,8 +351,8 @@ fn test_entity_generation_registers_modules() {
         romance_core::entity::parse_entity("Category", &["name:string".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Check that module was registered in entities/mod.rs
@@ -402,7 +415,7 @@ fn test_entity_model_contains_fields() {
     .unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let model_content =
@@ -445,7 +458,7 @@ fn test_entity_with_optional_field() {
     .unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let model_content =
@@ -480,12 +493,12 @@ fn test_entity_with_belongs_to_relation() {
 
     with_cwd(&project_dir, || {
         // First generate the target entity (Category)
-        romance_core::generator::backend::generate(&category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
         // Then generate the entity with FK relation
-        romance_core::generator::backend::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Product model should reference Category
@@ -543,7 +556,7 @@ fn test_entity_frontend_generation() {
     .unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let feature_dir = project_dir.join("frontend/src/features/task");
@@ -585,7 +598,7 @@ fn test_entity_frontend_types_contain_fields() {
     .unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let types_content = fs::read_to_string(
@@ -629,11 +642,11 @@ fn test_multiple_entity_generation() {
 
     with_cwd(&project_dir, || {
         // Generate two entities
-        romance_core::generator::backend::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Both entities should be registered
@@ -666,8 +679,8 @@ fn test_entity_generation_idempotent_registration() {
 
     with_cwd(&project_dir, || {
         // Generate twice
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Module should appear exactly once in mod.rs files (idempotent)
@@ -706,7 +719,7 @@ fn test_check_entity_prerequisites_no_warnings_when_no_relations() {
         romance_core::entity::parse_entity("Simple", &["name:string".to_string()]).unwrap();
 
     let warnings =
-        romance_core::generator::check_entity_prerequisites(&entity, &project_dir);
+        romance_core::generator::check_entity_prerequisites(&entity, &open_project(&project_dir));
     assert!(
         warnings.is_empty(),
         "No warnings expected for entity without relations"
@@ -726,7 +739,7 @@ fn test_check_entity_prerequisites_warns_missing_target() {
     .unwrap();
 
     let warnings =
-        romance_core::generator::check_entity_prerequisites(&entity, &project_dir);
+        romance_core::generator::check_entity_prerequisites(&entity, &open_project(&project_dir));
     assert!(
         !warnings.is_empty(),
         "Should warn when FK target entity does not exist"
@@ -750,7 +763,7 @@ fn test_check_entity_prerequisites_no_warning_when_target_exists() {
 
     with_cwd(&project_dir, || {
         // Generate the target entity first
-        romance_core::generator::backend::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Now check prerequisites for an entity that references User
@@ -761,7 +774,7 @@ fn test_check_entity_prerequisites_no_warning_when_target_exists() {
     .unwrap();
 
     let warnings =
-        romance_core::generator::check_entity_prerequisites(&post, &project_dir);
+        romance_core::generator::check_entity_prerequisites(&post, &open_project(&project_dir));
     assert!(
         warnings.is_empty(),
         "No warnings expected when FK target entity exists"
@@ -785,7 +798,7 @@ fn test_check_entity_prerequisites_multiple_missing_targets() {
     .unwrap();
 
     let warnings =
-        romance_core::generator::check_entity_prerequisites(&entity, &project_dir);
+        romance_core::generator::check_entity_prerequisites(&entity, &open_project(&project_dir));
     assert_eq!(
         warnings.len(),
         2,
@@ -812,7 +825,7 @@ fn test_check_entity_prerequisites_ignores_m2m_and_has_many() {
     .unwrap();
 
     let warnings =
-        romance_core::generator::check_entity_prerequisites(&entity, &project_dir);
+        romance_core::generator::check_entity_prerequisites(&entity, &open_project(&project_dir));
     assert!(
         warnings.is_empty(),
         "M2M and HasMany relations should not trigger warnings"
@@ -832,6 +845,7 @@ fn test_backend_validate_fails_on_missing_marker() {
     fs::create_dir_all(project_dir.join("backend/src/entities")).unwrap();
     fs::create_dir_all(project_dir.join("backend/src/handlers")).unwrap();
     fs::create_dir_all(project_dir.join("backend/src/routes")).unwrap();
+    write_romance_toml(&project_dir);
 
     // Write files without markers
     fs::write(
@@ -854,7 +868,7 @@ fn test_backend_validate_fails_on_missing_marker() {
         romance_core::entity::parse_entity("Product", &["title:string".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        let result = romance_core::generator::backend::validate(&entity);
+        let result = romance_core::generator::backend::validate(&open_project(&project_dir), &entity);
         assert!(result.is_err(), "Validation should fail when markers are missing");
         let msg = result.unwrap_err().to_string();
         assert!(msg.contains("Pre-validation failed"), "Error should mention pre-validation");
@@ -882,12 +896,12 @@ fn test_prevalidation_prevents_file_creation() {
     with_cwd(&project_dir, || {
         // Backend validation should pass
         assert!(
-            romance_core::generator::backend::validate(&entity).is_ok(),
+            romance_core::generator::backend::validate(&open_project(&project_dir), &entity).is_ok(),
             "Backend validation should pass"
         );
 
         // Migration validation should fail
-        let result = romance_core::generator::migration::validate(&entity);
+        let result = romance_core::generator::migration::validate(&open_project(&project_dir), &entity);
         assert!(
             result.is_err(),
             "Migration validation should fail without markers"
@@ -910,6 +924,7 @@ fn test_frontend_validate_fails_on_missing_nav_links() {
     let project_dir = dir.path().join("fe-validate-test");
 
     fs::create_dir_all(project_dir.join("frontend/src/components")).unwrap();
+    write_romance_toml(&project_dir);
 
     // App.tsx with IMPORTS and APP_ROUTES markers
     fs::write(
@@ -931,7 +946,7 @@ fn test_frontend_validate_fails_on_missing_nav_links() {
         romance_core::entity::parse_entity("Task", &["title:string".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        let result = romance_core::generator::frontend::validate(&entity);
+        let result = romance_core::generator::frontend::validate(&open_project(&project_dir), &entity);
         assert!(result.is_err(), "Should fail when NAV_LINKS marker is missing");
         assert!(result.unwrap_err().to_string().contains("NAV_LINKS"));
     });
@@ -971,7 +986,7 @@ fn test_auth_generation_creates_backend_files() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
     });
 
     // Backend auth files
@@ -997,7 +1012,7 @@ fn test_auth_generation_creates_frontend_files() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
     });
 
     let auth_dir = project_dir.join("frontend/src/features/auth");
@@ -1017,7 +1032,7 @@ fn test_auth_generation_registers_modules() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
     });
 
     // Modules registered in mod.rs files
@@ -1051,9 +1066,9 @@ fn test_auth_generation_idempotency() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
         // Second call should fail because auth.rs already exists
-        let result = romance_core::generator::auth::generate();
+        let result = romance_core::generator::auth::generate(&mut open_project(&project_dir));
         assert!(result.is_err(), "Auth generation should be idempotent");
         assert!(result.unwrap_err().to_string().contains("already generated"));
     });
@@ -1066,7 +1081,7 @@ fn test_auth_generation_adds_cargo_dependencies() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
     });
 
     let cargo = fs::read_to_string(project_dir.join("backend/Cargo.toml")).unwrap();
@@ -1085,7 +1100,7 @@ fn test_admin_generation_requires_auth() {
     setup_minimal_project(&project_dir);
 
     with_cwd(&project_dir, || {
-        let result = romance_core::generator::admin::generate();
+        let result = romance_core::generator::admin::generate(&open_project(&project_dir));
         assert!(result.is_err(), "Admin should fail without auth");
         assert!(result.unwrap_err().to_string().contains("Auth must be generated first"));
     });
@@ -1099,9 +1114,9 @@ fn test_admin_generation_creates_files() {
 
     with_cwd(&project_dir, || {
         // First generate auth (admin requires it)
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
         // Then generate admin
-        romance_core::generator::admin::generate().unwrap();
+        romance_core::generator::admin::generate(&open_project(&project_dir)).unwrap();
     });
 
     // Frontend admin files
@@ -1121,8 +1136,8 @@ fn test_admin_generation_registers_modules() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
-        romance_core::generator::admin::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
+        romance_core::generator::admin::generate(&open_project(&project_dir)).unwrap();
     });
 
     let routes_mod = fs::read_to_string(project_dir.join("backend/src/routes/mod.rs")).unwrap();
@@ -1140,17 +1155,17 @@ fn test_admin_generation_discovers_entities() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
 
         // Generate a Product entity before admin
         let product = romance_core::entity::parse_entity(
             "Product",
             &["title:string".to_string(), "price:decimal".to_string()],
         ).unwrap();
-        romance_core::generator::backend::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::admin::generate().unwrap();
+        romance_core::generator::admin::generate(&open_project(&project_dir)).unwrap();
     });
 
     // Admin dashboard should reference the Product entity
@@ -1166,9 +1181,9 @@ fn test_admin_generation_idempotency() {
     setup_project_for_auth(&project_dir);
 
     with_cwd(&project_dir, || {
-        romance_core::generator::auth::generate().unwrap();
-        romance_core::generator::admin::generate().unwrap();
-        let result = romance_core::generator::admin::generate();
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
+        romance_core::generator::admin::generate(&open_project(&project_dir)).unwrap();
+        let result = romance_core::generator::admin::generate(&open_project(&project_dir));
         assert!(result.is_err(), "Admin generation should be idempotent");
         assert!(result.unwrap_err().to_string().contains("already generated"));
     });
@@ -1189,14 +1204,14 @@ fn test_m2m_junction_creates_entity_and_migration() {
         let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
         let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
 
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::backend::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
         // Now generate the junction
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
     });
 
     // Junction entity should exist (alphabetical order: post_tag)
@@ -1229,12 +1244,12 @@ fn test_m2m_injects_related_impls() {
         let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
         let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
 
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
     });
 
     // Post model should have Related<tag::Entity> via junction
@@ -1258,12 +1273,12 @@ fn test_m2m_injects_handlers_and_routes() {
         let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
         let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
 
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
     });
 
     // Post handlers should have M2M handlers injected
@@ -1286,11 +1301,11 @@ fn test_m2m_pending_relations_stored_when_target_missing() {
     with_cwd(&project_dir, || {
         // Generate Post but NOT Tag
         let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
         // Try to create junction — Tag doesn't exist, should store pending
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
     });
 
     // Junction entity should NOT exist (target missing)
@@ -1300,7 +1315,7 @@ fn test_m2m_pending_relations_stored_when_target_missing() {
     );
 
     // Pending relation should be stored
-    let pending = romance_core::relation::load_pending(&project_dir).unwrap();
+    let pending = romance_core::relation::load_pending(&open_project(&project_dir)).unwrap();
     assert!(!pending.is_empty(), "Pending relation should be stored");
     assert_eq!(pending[0].source_entity, "Post");
     assert_eq!(pending[0].target_entity, "Tag");
@@ -1319,22 +1334,22 @@ fn test_m2m_pending_relations_applied_when_target_generated() {
             "Post",
             &["title:string".to_string(), "tags:m2m->Tag".to_string()],
         ).unwrap();
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate_relations(&post).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate_relations(&open_project(&project_dir), &post).unwrap();
 
         // Pending should exist
-        let pending = romance_core::relation::load_pending(Path::new(".")).unwrap();
+        let pending = romance_core::relation::load_pending(&open_project(Path::new("."))).unwrap();
         assert!(!pending.is_empty(), "Pending M2M should be stored since Tag doesn't exist");
 
         // Now generate Tag — pending relations should be applied
         let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
-        romance_core::generator::backend::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate_relations(&tag).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate_relations(&open_project(&project_dir), &tag).unwrap();
 
         // Pending should now be empty
-        let pending = romance_core::relation::load_pending(Path::new(".")).unwrap();
+        let pending = romance_core::relation::load_pending(&open_project(Path::new("."))).unwrap();
         assert!(pending.is_empty(), "Pending relations should be consumed after Tag is generated");
     });
 
@@ -1355,14 +1370,14 @@ fn test_m2m_junction_idempotent() {
         let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
         let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
 
-        romance_core::generator::backend::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
         // Generate junction twice — should not fail or duplicate
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
-        romance_core::generator::junction::generate("Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
     });
 
     // Junction entity should still exist and mod should be registered once
@@ -1384,14 +1399,14 @@ fn test_m2m_junction_alphabetical_ordering() {
         let article = romance_core::entity::parse_entity("Article", &["title:string".to_string()]).unwrap();
         let zebra = romance_core::entity::parse_entity("Zebra", &["name:string".to_string()]).unwrap();
 
-        romance_core::generator::backend::generate(&article, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&article, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::backend::generate(&zebra, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&zebra, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &article, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &article, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &zebra, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &zebra, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
         // Generate with Zebra as source, Article as target
         // Junction should still be article_zebra (alphabetical)
-        romance_core::generator::junction::generate("Zebra", "Article").unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Zebra", "Article").unwrap();
     });
 
     assert!(
@@ -1420,7 +1435,7 @@ fn test_frontend_injects_imports_into_app_tsx() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let app_tsx = fs::read_to_string(project_dir.join("frontend/src/App.tsx")).unwrap();
@@ -1440,7 +1455,7 @@ fn test_frontend_injects_routes_into_app_tsx() {
     let entity = romance_core::entity::parse_entity("Product", &["name:string".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let app_tsx = fs::read_to_string(project_dir.join("frontend/src/App.tsx")).unwrap();
@@ -1460,7 +1475,7 @@ fn test_frontend_injects_nav_links() {
     let entity = romance_core::entity::parse_entity("Invoice", &["amount:decimal".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let sidebar = fs::read_to_string(project_dir.join("frontend/src/components/AppSidebar.tsx")).unwrap();
@@ -1490,7 +1505,7 @@ fn test_frontend_types_ts_contains_correct_typescript_types() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let types = fs::read_to_string(project_dir.join("frontend/src/features/event/types.ts")).unwrap();
@@ -1518,7 +1533,7 @@ fn test_custom_block_preserved_on_regeneration() {
 
     with_cwd(&project_dir, || {
         // First generation
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Simulate user adding custom code below the CUSTOM marker
@@ -1533,7 +1548,7 @@ fn test_custom_block_preserved_on_regeneration() {
 
     // Regenerate
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Custom code should be preserved
@@ -1570,9 +1585,9 @@ fn test_entity_with_all_field_types() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // All backend files should exist
@@ -1626,8 +1641,8 @@ fn test_entity_with_file_and_image_types() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     assert!(project_dir.join("backend/src/entities/document.rs").exists());
@@ -1675,7 +1690,7 @@ fn test_addon_validation_install() {
     let project_dir = dir.path().join("addon-validation-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::validation::ValidationAddon.install(&project_dir).unwrap();
+    romance_core::addon::validation::ValidationAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/validation.rs").exists(), "validation.rs should be created");
 
@@ -1692,7 +1707,7 @@ fn test_addon_soft_delete_install() {
     let project_dir = dir.path().join("addon-soft-delete-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::soft_delete::SoftDeleteAddon.install(&project_dir).unwrap();
+    romance_core::addon::soft_delete::SoftDeleteAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/soft_delete.rs").exists(), "soft_delete.rs should be created");
 
@@ -1709,7 +1724,7 @@ fn test_addon_security_install() {
     let project_dir = dir.path().join("addon-security-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::security::SecurityAddon.install(&project_dir).unwrap();
+    romance_core::addon::security::SecurityAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/middleware/security_headers.rs").exists(), "security_headers.rs should exist");
     assert!(project_dir.join("backend/src/middleware/rate_limit.rs").exists(), "rate_limit.rs should exist");
@@ -1732,7 +1747,7 @@ fn test_addon_storage_install() {
     let project_dir = dir.path().join("addon-storage-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::storage::StorageAddon.install(&project_dir).unwrap();
+    romance_core::addon::storage::StorageAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/storage.rs").exists(), "storage.rs should exist");
     assert!(project_dir.join("backend/src/handlers/upload.rs").exists(), "upload handler should exist");
@@ -1754,7 +1769,7 @@ fn test_addon_observability_install() {
     let project_dir = dir.path().join("addon-obs-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::observability::ObservabilityAddon.install(&project_dir).unwrap();
+    romance_core::addon::observability::ObservabilityAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/middleware/request_id.rs").exists(),
         "request_id.rs should exist");
@@ -1769,7 +1784,7 @@ fn test_addon_search_install() {
     let project_dir = dir.path().join("addon-search-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::search::SearchAddon.install(&project_dir).unwrap();
+    romance_core::addon::search::SearchAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/search.rs").exists(), "search.rs should exist");
 
@@ -1787,7 +1802,7 @@ fn test_addon_audit_log_install() {
     setup_project_for_addon(&project_dir);
     add_auth_stub(&project_dir);
 
-    romance_core::addon::audit_log::AuditLogAddon.install(&project_dir).unwrap();
+    romance_core::addon::audit_log::AuditLogAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/audit.rs").exists(), "audit.rs should exist");
     assert!(project_dir.join("backend/src/entities/audit_entry.rs").exists(), "audit_entry entity should exist");
@@ -1816,7 +1831,7 @@ fn test_addon_email_install() {
     let project_dir = dir.path().join("addon-email-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::email::EmailAddon.install(&project_dir).unwrap();
+    romance_core::addon::email::EmailAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/email.rs").exists(), "email.rs should exist");
 
@@ -1830,7 +1845,7 @@ fn test_addon_cache_install() {
     let project_dir = dir.path().join("addon-cache-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::cache::CacheAddon.install(&project_dir).unwrap();
+    romance_core::addon::cache::CacheAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/cache.rs").exists(), "cache.rs should exist");
 
@@ -1844,7 +1859,7 @@ fn test_addon_tasks_install() {
     let project_dir = dir.path().join("addon-tasks-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::tasks::TasksAddon.install(&project_dir).unwrap();
+    romance_core::addon::tasks::TasksAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/tasks.rs").exists(), "tasks.rs should exist");
 
@@ -1858,7 +1873,7 @@ fn test_addon_websocket_install() {
     let project_dir = dir.path().join("addon-ws-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::websocket::WebsocketAddon.install(&project_dir).unwrap();
+    romance_core::addon::websocket::WebsocketAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/ws.rs").exists(), "ws.rs should exist");
 
@@ -1872,7 +1887,7 @@ fn test_addon_i18n_install() {
     let project_dir = dir.path().join("addon-i18n-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::i18n::I18nAddon.install(&project_dir).unwrap();
+    romance_core::addon::i18n::I18nAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/i18n.rs").exists(), "i18n.rs should exist");
 
@@ -1886,7 +1901,7 @@ fn test_addon_dashboard_install() {
     let project_dir = dir.path().join("addon-dashboard-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::dashboard::DashboardAddon.install(&project_dir).unwrap();
+    romance_core::addon::dashboard::DashboardAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/handlers/dev_dashboard.rs").exists(), "dev_dashboard handler should exist");
     assert!(project_dir.join("backend/src/routes/dev_dashboard.rs").exists(), "dev_dashboard routes should exist");
@@ -1900,7 +1915,7 @@ fn test_addon_api_keys_install() {
     setup_project_for_addon(&project_dir);
     add_auth_stub(&project_dir);
 
-    romance_core::addon::api_keys::ApiKeysAddon.install(&project_dir).unwrap();
+    romance_core::addon::api_keys::ApiKeysAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/api_keys.rs").exists(), "api_keys.rs should exist");
 
@@ -1915,7 +1930,7 @@ fn test_addon_multitenancy_install() {
     setup_project_for_addon(&project_dir);
     add_auth_stub(&project_dir);
 
-    romance_core::addon::multitenancy::MultitenancyAddon.install(&project_dir).unwrap();
+    romance_core::addon::multitenancy::MultitenancyAddon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/tenant.rs").exists(), "tenant.rs should exist");
 
@@ -1936,7 +1951,7 @@ fn test_addon_oauth_install() {
     let addon = romance_core::addon::oauth::OauthAddon {
         provider: "google".to_string(),
     };
-    addon.install(&project_dir).unwrap();
+    addon.install(&open_project(&project_dir)).unwrap();
 
     assert!(project_dir.join("backend/src/oauth.rs").exists(), "oauth.rs should exist");
     assert!(project_dir.join("backend/src/handlers/oauth.rs").exists(), "oauth handler should exist");
@@ -1954,11 +1969,11 @@ fn test_addon_validation_uninstall() {
     setup_project_for_addon(&project_dir);
 
     // Install first
-    romance_core::addon::validation::ValidationAddon.install(&project_dir).unwrap();
+    romance_core::addon::validation::ValidationAddon.install(&open_project(&project_dir)).unwrap();
     assert!(project_dir.join("backend/src/validation.rs").exists());
 
     // Then uninstall
-    romance_core::addon::validation::ValidationAddon.uninstall(&project_dir).unwrap();
+    romance_core::addon::validation::ValidationAddon.uninstall(&open_project(&project_dir)).unwrap();
     assert!(!project_dir.join("backend/src/validation.rs").exists(), "validation.rs should be deleted");
 
     let main_rs = fs::read_to_string(project_dir.join("backend/src/main.rs")).unwrap();
@@ -1972,11 +1987,11 @@ fn test_addon_security_uninstall() {
     setup_project_for_addon(&project_dir);
 
     // Install first
-    romance_core::addon::security::SecurityAddon.install(&project_dir).unwrap();
+    romance_core::addon::security::SecurityAddon.install(&open_project(&project_dir)).unwrap();
     assert!(project_dir.join("backend/src/middleware/security_headers.rs").exists());
 
     // Then uninstall
-    romance_core::addon::security::SecurityAddon.uninstall(&project_dir).unwrap();
+    romance_core::addon::security::SecurityAddon.uninstall(&open_project(&project_dir)).unwrap();
     assert!(!project_dir.join("backend/src/middleware/security_headers.rs").exists());
     assert!(!project_dir.join("backend/src/middleware/rate_limit.rs").exists());
 
@@ -1990,10 +2005,10 @@ fn test_addon_soft_delete_uninstall() {
     let project_dir = dir.path().join("addon-sd-uninstall-test");
     setup_project_for_addon(&project_dir);
 
-    romance_core::addon::soft_delete::SoftDeleteAddon.install(&project_dir).unwrap();
+    romance_core::addon::soft_delete::SoftDeleteAddon.install(&open_project(&project_dir)).unwrap();
     assert!(project_dir.join("backend/src/soft_delete.rs").exists());
 
-    romance_core::addon::soft_delete::SoftDeleteAddon.uninstall(&project_dir).unwrap();
+    romance_core::addon::soft_delete::SoftDeleteAddon.uninstall(&open_project(&project_dir)).unwrap();
     assert!(!project_dir.join("backend/src/soft_delete.rs").exists());
 
     let romance_toml = fs::read_to_string(project_dir.join("romance.toml")).unwrap();
@@ -2011,13 +2026,13 @@ fn test_run_addon_skips_if_already_installed() {
     setup_project_for_addon(&project_dir);
 
     // Install validation first
-    romance_core::addon::validation::ValidationAddon.install(&project_dir).unwrap();
+    romance_core::addon::validation::ValidationAddon.install(&open_project(&project_dir)).unwrap();
 
     // run_addon should skip since it's already installed
     // (it should not error, just print a skip message)
     romance_core::addon::run_addon(
         &romance_core::addon::validation::ValidationAddon,
-        &project_dir,
+        &mut open_project(&project_dir),
     ).unwrap();
 
     // Should still have exactly one validation.rs
@@ -2041,11 +2056,11 @@ fn test_entity_with_fk_generates_select_dropdown() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &category, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::backend::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &product, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Product form should fetch categories for select dropdown
@@ -2076,8 +2091,8 @@ fn test_entity_with_validation_rules() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Backend model should exist
@@ -2106,8 +2121,8 @@ fn test_entity_with_searchable_fields() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     assert!(project_dir.join("backend/src/entities/article.rs").exists());
@@ -2139,11 +2154,11 @@ fn test_multiple_fks_to_same_entity() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::backend::generate(&transfer, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&transfer, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &transfer, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &transfer, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let model = fs::read_to_string(project_dir.join("backend/src/entities/transfer.rs")).unwrap();
@@ -2181,8 +2196,8 @@ fn test_migration_timestamp_collision_avoidance() {
     let entity_b = romance_core::entity::parse_entity("EntityB", &["name:string".to_string()]).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::migration::generate(&entity_a, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity_b, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity_a, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity_b, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Both migrations should exist with different timestamps
@@ -2215,7 +2230,7 @@ fn test_relation_discovery() {
     fs::write(project_dir.join("backend/src/entities/post.rs"), "// post entity\n").unwrap();
     fs::write(project_dir.join("backend/src/entities/tag.rs"), "// tag entity\n").unwrap();
 
-    let entities = romance_core::relation::discover_entities(&project_dir).unwrap();
+    let entities = romance_core::relation::discover_entities(&open_project(&project_dir)).unwrap();
     assert!(entities.contains(&"user".to_string()));
     assert!(entities.contains(&"post".to_string()));
     assert!(entities.contains(&"tag".to_string()));
@@ -2234,11 +2249,11 @@ fn test_junction_name_alphabetical() {
 fn test_pending_relations_store_and_load() {
     let dir = tempfile::tempdir().unwrap();
     let project_dir = dir.path().join("pending-test");
-    fs::create_dir_all(project_dir.join(".romance")).unwrap();
+    setup_minimal_project(&project_dir);
 
     // Store pending
     romance_core::relation::store_pending(
-        &project_dir,
+        &open_project(&project_dir),
         romance_core::relation::PendingRelation {
             source_entity: "Post".to_string(),
             target_entity: "Tag".to_string(),
@@ -2247,7 +2262,7 @@ fn test_pending_relations_store_and_load() {
     ).unwrap();
 
     // Load and verify
-    let pending = romance_core::relation::load_pending(&project_dir).unwrap();
+    let pending = romance_core::relation::load_pending(&open_project(&project_dir)).unwrap();
     assert_eq!(pending.len(), 1);
     assert_eq!(pending[0].source_entity, "Post");
     assert_eq!(pending[0].target_entity, "Tag");
@@ -2257,7 +2272,7 @@ fn test_pending_relations_store_and_load() {
 fn test_pending_relations_no_duplicates() {
     let dir = tempfile::tempdir().unwrap();
     let project_dir = dir.path().join("pending-dup-test");
-    fs::create_dir_all(project_dir.join(".romance")).unwrap();
+    setup_minimal_project(&project_dir);
 
     let pending = romance_core::relation::PendingRelation {
         source_entity: "Post".to_string(),
@@ -2266,11 +2281,11 @@ fn test_pending_relations_no_duplicates() {
     };
 
     // Store twice
-    romance_core::relation::store_pending(&project_dir, pending.clone()).unwrap();
-    romance_core::relation::store_pending(&project_dir, pending).unwrap();
+    romance_core::relation::store_pending(&open_project(&project_dir), pending.clone()).unwrap();
+    romance_core::relation::store_pending(&open_project(&project_dir), pending).unwrap();
 
     // Should only have one
-    let all = romance_core::relation::load_pending(&project_dir).unwrap();
+    let all = romance_core::relation::load_pending(&open_project(&project_dir)).unwrap();
     assert_eq!(all.len(), 1, "Duplicate pending relations should be prevented");
 }
 
@@ -2278,11 +2293,11 @@ fn test_pending_relations_no_duplicates() {
 fn test_take_pending_for_removes_matched() {
     let dir = tempfile::tempdir().unwrap();
     let project_dir = dir.path().join("pending-take-test");
-    fs::create_dir_all(project_dir.join(".romance")).unwrap();
+    setup_minimal_project(&project_dir);
 
     // Store two pending relations for different targets
     romance_core::relation::store_pending(
-        &project_dir,
+        &open_project(&project_dir),
         romance_core::relation::PendingRelation {
             source_entity: "Post".to_string(),
             target_entity: "Tag".to_string(),
@@ -2290,7 +2305,7 @@ fn test_take_pending_for_removes_matched() {
         },
     ).unwrap();
     romance_core::relation::store_pending(
-        &project_dir,
+        &open_project(&project_dir),
         romance_core::relation::PendingRelation {
             source_entity: "Article".to_string(),
             target_entity: "Category".to_string(),
@@ -2299,12 +2314,12 @@ fn test_take_pending_for_removes_matched() {
     ).unwrap();
 
     // Take pending for "Tag"
-    let taken = romance_core::relation::take_pending_for(&project_dir, "Tag").unwrap();
+    let taken = romance_core::relation::take_pending_for(&open_project(&project_dir), "Tag").unwrap();
     assert_eq!(taken.len(), 1);
     assert_eq!(taken[0].target_entity, "Tag");
 
     // Remaining should only have Category relation
-    let remaining = romance_core::relation::load_pending(&project_dir).unwrap();
+    let remaining = romance_core::relation::load_pending(&open_project(&project_dir)).unwrap();
     assert_eq!(remaining.len(), 1);
     assert_eq!(remaining[0].target_entity, "Category");
 }
@@ -2316,14 +2331,14 @@ fn test_entity_exists_check() {
     setup_minimal_project(&project_dir);
 
     // No entity file exists yet
-    assert!(!romance_core::relation::entity_exists(&project_dir, "User"));
+    assert!(!romance_core::relation::entity_exists(&open_project(&project_dir), "User"));
 
     // Create user entity
     fs::write(project_dir.join("backend/src/entities/user.rs"), "// user\n").unwrap();
-    assert!(romance_core::relation::entity_exists(&project_dir, "User"));
+    assert!(romance_core::relation::entity_exists(&open_project(&project_dir), "User"));
 
     // PascalCase to snake_case conversion
-    assert!(romance_core::relation::entity_exists(&project_dir, "user"));
+    assert!(romance_core::relation::entity_exists(&open_project(&project_dir), "user"));
 }
 
 // ==========================================================================
@@ -2340,7 +2355,7 @@ fn test_tracker_rollback_cleans_up_generated_files() {
 
     with_cwd(&project_dir, || {
         let mut tracker = romance_core::generator::plan::GenerationTracker::new();
-        romance_core::generator::backend::generate(&entity, &mut tracker).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut tracker).unwrap();
 
         // Files should exist
         assert!(Path::new("backend/src/entities/widget.rs").exists());
@@ -2348,7 +2363,7 @@ fn test_tracker_rollback_cleans_up_generated_files() {
         assert!(Path::new("backend/src/routes/widget.rs").exists());
 
         // Rollback (must be done while cwd is still the project dir, since tracker stores relative paths)
-        tracker.rollback();
+        tracker.rollback(&DiskFs);
 
         // Files should be cleaned up
         assert!(!Path::new("backend/src/entities/widget.rs").exists(),
@@ -2382,7 +2397,7 @@ fn test_entity_generation_with_seed_file() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let seed = fs::read_to_string(project_dir.join("backend/src/seed.rs")).unwrap();
@@ -2412,15 +2427,15 @@ fn test_full_entity_pipeline_backend_migration_frontend() {
 
     with_cwd(&project_dir, || {
         // Simulate the full pipeline as done by the CLI
-        romance_core::generator::backend::validate(&entity).unwrap();
-        romance_core::generator::migration::validate(&entity).unwrap();
-        romance_core::generator::frontend::validate(&entity).unwrap();
+        romance_core::generator::backend::validate(&open_project(&project_dir), &entity).unwrap();
+        romance_core::generator::migration::validate(&open_project(&project_dir), &entity).unwrap();
+        romance_core::generator::frontend::validate(&open_project(&project_dir), &entity).unwrap();
 
         let mut tracker = romance_core::generator::plan::GenerationTracker::new();
-        romance_core::generator::backend::generate(&entity, &mut tracker).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut tracker).unwrap();
-        romance_core::generator::backend::generate_relations(&entity).unwrap();
-        romance_core::generator::frontend::generate(&entity, &mut tracker).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut tracker).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut tracker).unwrap();
+        romance_core::generator::backend::generate_relations(&open_project(&project_dir), &entity).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut tracker).unwrap();
     });
 
     // Backend files
@@ -2476,9 +2491,9 @@ fn test_reserved_word_type_field_escaped() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Backend model should use r#type for struct fields
@@ -2518,8 +2533,8 @@ fn test_self_referential_fk_no_duplicate_related() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let model = fs::read_to_string(project_dir.join("backend/src/entities/category.rs")).unwrap();
@@ -2557,7 +2572,7 @@ fn test_optional_fk_question_before_arrow() {
     assert_eq!(entity.fields[1].relation.as_deref(), Some("Category"));
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     let model = fs::read_to_string(project_dir.join("backend/src/entities/post.rs")).unwrap();
@@ -2583,12 +2598,12 @@ fn test_multiple_fks_frontend_unique_variables() {
     ).unwrap();
 
     with_cwd(&project_dir, || {
-        romance_core::generator::backend::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &user, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
 
-        romance_core::generator::backend::generate(&message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::migration::generate(&message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
-        romance_core::generator::frontend::generate(&message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &message, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
     });
 
     // Frontend Form should have unique variable names for FK selects
@@ -2600,3 +2615,1992 @@ fn test_multiple_fks_frontend_unique_variables() {
     let user_options_count = form.matches("userOptions").count();
     assert_eq!(user_options_count, 0, "Should not have generic userOptions, got {}", user_options_count);
 }
+
+// ── Destroy entity ──────────────────────────────────────────────────
+
+#[test]
+fn test_destroy_entity_reverses_has_many_and_drops_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-has-many-test");
+    setup_minimal_project(&project_dir);
+
+    let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+    let comment = romance_core::entity::parse_entity(
+        "Comment",
+        &["body:text".to_string(), "post_id:uuid->Post".to_string()],
+    ).unwrap();
+
+    let report = with_cwd(&project_dir, || {
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &comment, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &comment, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+
+        romance_core::generator::destroy::entity(&open_project(&project_dir), "Comment", false).unwrap()
+    });
+
+    assert!(!project_dir.join("backend/src/entities/comment.rs").exists());
+    assert!(!project_dir.join("backend/src/handlers/comment.rs").exists());
+    assert!(!project_dir.join("backend/src/routes/comment.rs").exists());
+
+    let entities_mod = fs::read_to_string(project_dir.join("backend/src/entities/mod.rs")).unwrap();
+    assert!(!entities_mod.contains("pub mod comment;"));
+    assert!(entities_mod.contains("pub mod post;"));
+    let routes_mod = fs::read_to_string(project_dir.join("backend/src/routes/mod.rs")).unwrap();
+    assert!(!routes_mod.contains("comment::router()"));
+
+    // Code injected into the parent by inject_has_many is gone
+    let post_model = fs::read_to_string(project_dir.join("backend/src/entities/post.rs")).unwrap();
+    assert!(!post_model.contains("super::comment::"), "Post model still references comment:\n{}", post_model);
+    let post_handlers = fs::read_to_string(project_dir.join("backend/src/handlers/post.rs")).unwrap();
+    assert!(!post_handlers.contains("list_comments"), "Post handlers still list comments");
+    let post_routes = fs::read_to_string(project_dir.join("backend/src/routes/post.rs")).unwrap();
+    assert!(!post_routes.contains("comment"), "Post routes still reference comments");
+
+    // A drop migration is generated and registered
+    let migration_dir = project_dir.join("backend/migration/src");
+    let drop = fs::read_dir(&migration_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().contains("drop_comment_table"))
+        .expect("drop migration should exist");
+    let drop_src = fs::read_to_string(drop.path()).unwrap();
+    assert!(drop_src.contains("Alias::new(\"comments\")"));
+    assert!(drop_src.contains("create_comment_table"), "down should recreate the table");
+    let lib = fs::read_to_string(migration_dir.join("lib.rs")).unwrap();
+    let drop_mod = drop.file_name().to_string_lossy().trim_end_matches(".rs").to_string();
+    assert!(lib.contains(&format!("mod {};", drop_mod)));
+    assert_eq!(report.created.len(), 1);
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+}
+
+#[test]
+fn test_destroy_entity_removes_junction_and_keeps_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-m2m-test");
+    setup_minimal_project(&project_dir);
+
+    let report = with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
+
+        romance_core::generator::destroy::entity(&open_project(&project_dir), "Tag", true).unwrap()
+    });
+
+    assert!(!project_dir.join("backend/src/entities/post_tag.rs").exists());
+    let entities_mod = fs::read_to_string(project_dir.join("backend/src/entities/mod.rs")).unwrap();
+    assert!(!entities_mod.contains("pub mod post_tag;"));
+    assert!(!entities_mod.contains("pub mod tag;"));
+
+    let post_model = fs::read_to_string(project_dir.join("backend/src/entities/post.rs")).unwrap();
+    assert!(!post_model.contains("super::tag::"), "Post model still references tag:\n{}", post_model);
+    let post_handlers = fs::read_to_string(project_dir.join("backend/src/handlers/post.rs")).unwrap();
+    assert!(!post_handlers.contains("post_tag"), "Post handlers still use the junction");
+
+    // --keep-table: no drop migration
+    assert!(report.created.is_empty());
+    let has_drop = fs::read_dir(project_dir.join("backend/migration/src"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_name().to_string_lossy().contains("_drop_"));
+    assert!(!has_drop);
+}
+
+#[test]
+fn test_destroy_entity_unknown_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-missing-test");
+    setup_minimal_project(&project_dir);
+
+    let result = with_cwd(&project_dir, || romance_core::generator::destroy::entity(&open_project(&project_dir), "Ghost", false));
+    assert!(result.is_err());
+}
+
+// ── Destroy field, relation, auth, admin ────────────────────────────
+
+#[test]
+fn test_destroy_field_rerenders_entity_and_drops_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-field-test");
+    setup_minimal_project(&project_dir);
+
+    let report = with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        let comment = romance_core::entity::parse_entity(
+            "Comment",
+            &["body:text".to_string(), "post_id:uuid->Post".to_string()],
+        ).unwrap();
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+        for entity in [&post, &comment, &tag] {
+            romance_core::generator::backend::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+            romance_core::generator::migration::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        }
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Comment", "Tag").unwrap();
+
+        // Hand-written code below the custom marker must survive
+        let model = std::path::Path::new("backend/src/entities/comment.rs");
+        let content = fs::read_to_string(model).unwrap();
+        fs::write(model, format!("{}\nfn keep_me() {{}}\n", content)).unwrap();
+
+        romance_core::generator::destroy::field(&open_project(&project_dir), "Comment", "post_id", false).unwrap()
+    });
+
+    let comment_model = fs::read_to_string(project_dir.join("backend/src/entities/comment.rs")).unwrap();
+    assert!(!comment_model.contains("post_id"), "field should be gone:\n{}", comment_model);
+    assert!(comment_model.contains("pub body: String"));
+    assert!(comment_model.contains("super::comment_tag::"), "M2M code injected into the model should be kept");
+    assert!(comment_model.contains("fn keep_me() {}"), "custom code should be kept");
+    assert_eq!(comment_model.matches("// === ROMANCE:CUSTOM ===").count(), 1);
+    let comment_handlers = fs::read_to_string(project_dir.join("backend/src/handlers/comment.rs")).unwrap();
+    assert!(comment_handlers.contains("pub async fn add_tag("), "M2M handlers should be kept");
+
+    // The has-many code the FK added to Post is gone
+    let post_model = fs::read_to_string(project_dir.join("backend/src/entities/post.rs")).unwrap();
+    assert!(!post_model.contains("super::comment::"), "Post model still references comment:\n{}", post_model);
+    let post_handlers = fs::read_to_string(project_dir.join("backend/src/handlers/post.rs")).unwrap();
+    assert!(!post_handlers.contains("list_comments"));
+    let post_routes = fs::read_to_string(project_dir.join("backend/src/routes/post.rs")).unwrap();
+    assert!(!post_routes.contains("list_comments"));
+
+    let migration_dir = project_dir.join("backend/migration/src");
+    let drop = fs::read_dir(&migration_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().contains("remove_post_id_from_comments"))
+        .expect("drop-column migration should exist");
+    let drop_src = fs::read_to_string(drop.path()).unwrap();
+    assert!(drop_src.contains(".drop_column(Alias::new(\"post_id\"))"));
+    assert!(drop_src.contains(".to(Alias::new(\"posts\"), Alias::new(\"id\"))"), "down should restore the FK");
+    let lib = fs::read_to_string(migration_dir.join("lib.rs")).unwrap();
+    assert!(lib.contains(drop.file_name().to_string_lossy().trim_end_matches(".rs")));
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+}
+
+#[test]
+fn test_destroy_field_unknown_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-field-missing-test");
+    setup_minimal_project(&project_dir);
+
+    let result = with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity(
+            "Post",
+            &["title:string".to_string(), "body:text".to_string()],
+        ).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::destroy::field(&open_project(&project_dir), "Post", "subtitle", false)
+    });
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("title, body"), "error should list the fields: {}", err);
+}
+
+#[test]
+fn test_destroy_relation_removes_junction_from_both_sides() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-relation-test");
+    setup_minimal_project(&project_dir);
+
+    let report = with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+        for entity in [&post, &tag] {
+            romance_core::generator::backend::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+            romance_core::generator::migration::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        }
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
+
+        romance_core::generator::destroy::relation(&open_project(&project_dir), "Tag", "Post", false).unwrap()
+    });
+
+    assert!(!project_dir.join("backend/src/entities/post_tag.rs").exists());
+    for (side, other) in [("post", "tag"), ("tag", "post")] {
+        let model = fs::read_to_string(project_dir.join(format!("backend/src/entities/{}.rs", side))).unwrap();
+        assert!(!model.contains("post_tag"), "{} model still uses the junction:\n{}", side, model);
+        let handlers = fs::read_to_string(project_dir.join(format!("backend/src/handlers/{}.rs", side))).unwrap();
+        assert!(!handlers.contains(&format!("fn add_{}(", other)));
+        assert!(!handlers.contains(&format!("fn list_{}s(", other)));
+        let routes = fs::read_to_string(project_dir.join(format!("backend/src/routes/{}.rs", side))).unwrap();
+        assert!(!routes.contains(&format!("add_{}", other)));
+    }
+    // The entities themselves stay
+    assert!(project_dir.join("backend/src/entities/post.rs").exists());
+    assert!(project_dir.join("backend/src/entities/tag.rs").exists());
+
+    assert_eq!(report.created.len(), 1);
+    assert!(report.created[0].contains("drop_post_tag_table"));
+    let drop_src = fs::read_to_string(project_dir.join(&report.created[0])).unwrap();
+    assert!(drop_src.contains("create_post_tag_table"), "down should recreate the junction table");
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+}
+
+#[test]
+fn test_destroy_relation_without_junction_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-relation-missing-test");
+    setup_minimal_project(&project_dir);
+
+    let result = with_cwd(&project_dir, || romance_core::generator::destroy::relation(&open_project(&project_dir), "Post", "Tag", false));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_destroy_auth_removes_auth_and_rerenders_handlers() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("destroy-auth-test");
+    setup_project_for_auth(&project_dir);
+
+    let report = with_cwd(&project_dir, || {
+        romance_core::generator::auth::generate(&mut open_project(&project_dir)).unwrap();
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        let handlers = fs::read_to_string("backend/src/handlers/post.rs").unwrap();
+        assert!(handlers.contains("AuthUser"));
+
+        romance_core::generator::admin::generate(&open_project(&project_dir)).unwrap();
+        let blocked = romance_core::generator::destroy::auth(&mut open_project(&project_dir), false);
+        assert!(blocked.unwrap_err().to_string().contains("destroy admin"));
+        romance_core::generator::destroy::admin(&open_project(&project_dir)).unwrap();
+
+        romance_core::generator::destroy::auth(&mut open_project(&project_dir), false).unwrap()
+    });
+
+    assert!(!project_dir.join("frontend/src/admin").exists());
+    assert!(!project_dir.join("backend/src/routes/admin.rs").exists());
+    for path in ["auth.rs", "entities/user.rs", "handlers/auth.rs", "routes/auth.rs"] {
+        assert!(!project_dir.join("backend/src").join(path).exists(), "{} should be removed", path);
+    }
+    assert!(!project_dir.join("frontend/src/features/auth").exists());
+    assert!(!project_dir.join("frontend/src/features/user").exists());
+
+    let routes_mod = fs::read_to_string(project_dir.join("backend/src/routes/mod.rs")).unwrap();
+    assert!(!routes_mod.contains("auth"), "routes/mod.rs still mentions auth:\n{}", routes_mod);
+    assert!(!routes_mod.contains("admin"));
+    let main_rs = fs::read_to_string(project_dir.join("backend/src/main.rs")).unwrap();
+    assert!(!main_rs.contains("mod auth;"));
+    let cargo = fs::read_to_string(project_dir.join("backend/Cargo.toml")).unwrap();
+    assert!(!cargo.contains("argon2") && !cargo.contains("jsonwebtoken"));
+    let env = fs::read_to_string(project_dir.join("backend/.env")).unwrap();
+    assert!(!env.contains("JWT_SECRET"));
+
+    let handlers = fs::read_to_string(project_dir.join("backend/src/handlers/post.rs")).unwrap();
+    assert!(!handlers.contains("AuthUser"), "handlers should be re-rendered without auth");
+
+    assert_eq!(report.created.len(), 1);
+    let drop_src = fs::read_to_string(project_dir.join(&report.created[0])).unwrap();
+    assert!(drop_src.contains("Alias::new(\"users\")"));
+    assert!(drop_src.contains("create_users_table"));
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+}
+
+// ── Definition recovery ─────────────────────────────────────────────
+
+#[test]
+fn test_recovered_definition_renders_identical_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("recover-test");
+    setup_minimal_project(&project_dir);
+    let config = fs::read_to_string(project_dir.join("romance.toml")).unwrap();
+    fs::write(project_dir.join("romance.toml"), format!("{}\n[features]\nvalidation = true\n", config)).unwrap();
+
+    let author = romance_core::entity::parse_entity("Author", &["name:string".to_string()]).unwrap();
+    let post = romance_core::entity::parse_entity(
+        "Post",
+        &[
+            "title:string[min=3,max=120,searchable]".to_string(),
+            "body:text".to_string(),
+            "slug:string[regex=^\\w+$]".to_string(),
+            "contact:string[email]?".to_string(),
+            "views:int[min=0]".to_string(),
+            "cover:image?".to_string(),
+            "published:bool".to_string(),
+            "price:decimal[admin_only]".to_string(),
+            "type:string".to_string(),
+            "author_id:uuid->Author".to_string(),
+        ],
+    )
+    .unwrap();
+
+    let recovered = with_cwd(&project_dir, || {
+        let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &author, &mut tracker).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &author, &mut tracker).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &post, &mut tracker).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &post, &mut tracker).unwrap();
+        romance_core::generator::frontend::generate(&open_project(&project_dir), &post, &mut tracker).unwrap();
+
+        let recovered = romance_core::generator::recover::entity(&open_project(&project_dir), "Post").unwrap();
+        // Rendering the recovered definition reproduces the generated sections
+        for (path, content) in romance_core::generator::backend::render_files(&open_project(&project_dir), &recovered).unwrap() {
+            let current = fs::read_to_string(&path).unwrap();
+            let section = content.split("// === ROMANCE:CUSTOM ===").next().unwrap();
+            assert!(current.starts_with(section), "{} differs after recovery", path.display());
+        }
+        recovered
+    });
+
+    let names: Vec<&str> = recovered.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["title", "body", "slug", "contact", "views", "cover", "published", "price", "type", "author_id"]
+    );
+    let field = |n: &str| recovered.fields.iter().find(|f| f.name == n).unwrap();
+    assert!(matches!(field("body").field_type, romance_core::entity::FieldType::Text));
+    assert!(matches!(field("cover").field_type, romance_core::entity::FieldType::File));
+    assert!(field("cover").optional);
+    assert!(field("title").searchable);
+    assert_eq!(
+        field("title").validations,
+        vec![
+            romance_core::entity::ValidationRule::Min(3),
+            romance_core::entity::ValidationRule::Max(120)
+        ]
+    );
+    assert_eq!(
+        field("slug").validations,
+        vec![romance_core::entity::ValidationRule::Regex("^\\w+$".to_string())]
+    );
+    assert_eq!(field("price").visibility, romance_core::entity::FieldVisibility::AdminOnly);
+    assert_eq!(field("author_id").relation.as_deref(), Some("Author"));
+}
+
+// ── Update: three-way merge ─────────────────────────────────────────
+
+#[test]
+fn test_update_renders_env_example_with_the_project_jwt_secret() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-jwt-test");
+    setup_minimal_project(&project_dir);
+    romance_core::manifest::Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+    fs::write(project_dir.join("backend/.env.example"), "JWT_SECRET=kept-secret\n").unwrap();
+
+    let plan = romance_core::updater::plan_update(&open_project(&project_dir)).unwrap();
+    let env_example = plan
+        .new_files
+        .iter()
+        .chain(&plan.untracked)
+        .find(|i| i.output_path == "backend/.env.example")
+        .unwrap();
+    assert!(env_example.new_content.contains("JWT_SECRET=kept-secret\n"), "{}", env_example.new_content);
+
+    // Without an .env.example a fresh secret is generated
+    fs::remove_file(project_dir.join("backend/.env.example")).unwrap();
+    let plan = romance_core::updater::plan_update(&open_project(&project_dir)).unwrap();
+    let env_example = plan.new_files.iter().find(|i| i.output_path == "backend/.env.example").unwrap();
+    let secret = env_example.new_content.lines().find_map(|l| l.strip_prefix("JWT_SECRET=")).unwrap();
+    assert!(secret.len() >= 32, "{}", secret);
+}
+
+/// Set up `backend/src/errors.rs` as if an older template generated `base`
+/// and the user then edited it into `yours`. Returns the current template output.
+fn setup_update_merge(project_dir: &Path, edit_base: impl Fn(&str) -> (String, String)) -> String {
+    setup_minimal_project(project_dir);
+    romance_core::manifest::Manifest::new("test-app", "0.0.0").save(&DiskFs, project_dir).unwrap();
+    let plan = romance_core::updater::plan_update(&open_project(project_dir)).unwrap();
+    let template = plan
+        .new_files
+        .iter()
+        .find(|i| i.output_path == "backend/src/errors.rs")
+        .unwrap()
+        .new_content
+        .clone();
+
+    let (base, yours) = edit_base(&template);
+    let mut manifest = romance_core::manifest::Manifest::load(&DiskFs, project_dir).unwrap();
+    manifest.record_file(
+        "backend/src/errors.rs",
+        Some("scaffold/backend/errors.rs.tera"),
+        romance_core::manifest::FileCategory::Scaffold,
+        &base,
+        None,
+    );
+    manifest.save(&DiskFs, project_dir).unwrap();
+    romance_core::manifest::save_base(&DiskFs, project_dir, "backend/src/errors.rs", &base).unwrap();
+    fs::write(project_dir.join("backend/src/errors.rs"), yours).unwrap();
+    template
+}
+
+/// Replace the first line of `content` that starts with `use ` with `line`.
+fn replace_first_use(content: &str, line: &str) -> String {
+    let first = content.lines().find(|l| l.starts_with("use ")).unwrap();
+    content.replacen(first, line, 1)
+}
+
+#[test]
+fn test_update_merges_non_overlapping_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-merge-test");
+    let template = setup_update_merge(&project_dir, |template| {
+        let base = replace_first_use(template, "use old_template::Thing;");
+        let yours = format!("{}\n// my own helper\nfn helper() {{}}\n", base);
+        (base, yours)
+    });
+
+    let plan = romance_core::updater::plan_update(&open_project(&project_dir)).unwrap();
+    assert!(plan.conflicts.iter().all(|i| i.output_path != "backend/src/errors.rs"));
+    let item = plan
+        .merged
+        .iter()
+        .find(|i| i.output_path == "backend/src/errors.rs")
+        .expect("errors.rs should merge cleanly");
+
+    let mut manifest = romance_core::manifest::Manifest::load(&DiskFs, &project_dir).unwrap();
+    romance_core::updater::apply_merge(&open_project(&project_dir), &mut manifest, item).unwrap();
+
+    let merged = fs::read_to_string(project_dir.join("backend/src/errors.rs")).unwrap();
+    assert_eq!(merged, format!("{}\n// my own helper\nfn helper() {{}}\n", template));
+    // The new template output is the base for the next update
+    let record = &manifest.files["backend/src/errors.rs"];
+    assert_eq!(
+        romance_core::manifest::load_base(&DiskFs, &project_dir, "backend/src/errors.rs", &record.generated_hash),
+        Some(template)
+    );
+}
+
+#[test]
+fn test_update_writes_conflict_markers_and_resolves() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-conflict-test");
+    let template = setup_update_merge(&project_dir, |template| {
+        let base = replace_first_use(template, "use old_template::Thing;");
+        let yours = replace_first_use(template, "use my_crate::Thing;");
+        (base, yours)
+    });
+
+    let plan = romance_core::updater::plan_update(&open_project(&project_dir)).unwrap();
+    let item = plan
+        .conflicts
+        .iter()
+        .find(|i| i.output_path == "backend/src/errors.rs")
+        .expect("errors.rs should conflict");
+    assert_eq!(item.merge.as_ref().map(|m| m.conflicts), Some(1));
+
+    let mut manifest = romance_core::manifest::Manifest::load(&DiskFs, &project_dir).unwrap();
+    romance_core::updater::apply_merge(&open_project(&project_dir), &mut manifest, item).unwrap();
+    let merged = fs::read_to_string(project_dir.join("backend/src/errors.rs")).unwrap();
+    assert!(merged.contains("<<<<<<< yours\nuse my_crate::Thing;\n=======\n"), "{}", merged);
+    assert!(merged.contains(">>>>>>> template\n"));
+
+    let unresolved = romance_core::updater::unresolved_conflicts(&open_project(&project_dir), &manifest);
+    assert_eq!(unresolved, vec![("backend/src/errors.rs".to_string(), 1)]);
+
+    romance_core::updater::resolve_conflicts(
+        &open_project(&project_dir),
+        "backend/src/errors.rs",
+        romance_core::merge::Side::Template,
+    )
+    .unwrap();
+    let resolved = fs::read_to_string(project_dir.join("backend/src/errors.rs")).unwrap();
+    assert_eq!(resolved, template);
+    assert!(romance_core::updater::unresolved_conflicts(&open_project(&project_dir), &manifest).is_empty());
+}
+
+#[test]
+fn test_update_without_base_falls_back_to_conflict() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-no-base-test");
+    setup_update_merge(&project_dir, |template| {
+        let base = replace_first_use(template, "use old_template::Thing;");
+        let yours = format!("{}// edited\n", base);
+        (base, yours)
+    });
+    fs::remove_dir_all(project_dir.join(".romance/base")).unwrap();
+
+    let plan = romance_core::updater::plan_update(&open_project(&project_dir)).unwrap();
+    let item = plan
+        .conflicts
+        .iter()
+        .find(|i| i.output_path == "backend/src/errors.rs")
+        .expect("errors.rs should conflict");
+    assert!(item.merge.is_none());
+}
+
+#[test]
+fn test_update_merges_template_changes_into_entity_files() {
+    use romance_core::manifest::{self, Manifest};
+    use romance_core::updater::{self, UpdateGroup};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-entity-test");
+    setup_minimal_project(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+
+    with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        let comment = romance_core::entity::parse_entity(
+            "Comment",
+            &["body:text".to_string(), "post_id:uuid->Post".to_string()],
+        ).unwrap();
+        for entity in [&post, &comment] {
+            romance_core::generator::backend::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+            romance_core::generator::migration::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        }
+    });
+
+    // Generated entity files are tracked with their base
+    let handlers = "backend/src/handlers/post.rs";
+    let mut manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    let record = &manifest.files[handlers];
+    assert_eq!(record.entity_name.as_deref(), Some("Post"));
+    let template = manifest::load_base(&DiskFs, &project_dir, handlers, &record.generated_hash).unwrap();
+
+    // Pretend an older template generated the handlers; the file also has the
+    // has-many handler the Comment FK injected and code in its custom block
+    let old = replace_first_use(&template, "use old_template::Thing;");
+    manifest.record_file(handlers, None, manifest::FileCategory::Entity, &old, Some("Post"));
+    manifest.save(&DiskFs, &project_dir).unwrap();
+    manifest::save_base(&DiskFs, &project_dir, handlers, &old).unwrap();
+    let current = fs::read_to_string(project_dir.join(handlers)).unwrap();
+    assert!(current.contains("list_comments"));
+    let current = format!("{}\nfn keep_me() {{}}\n", replace_first_use(&current, "use old_template::Thing;"));
+    fs::write(project_dir.join(handlers), &current).unwrap();
+
+    // Custom-block edits alone don't make a file modified
+    let model = project_dir.join("backend/src/entities/post.rs");
+    fs::write(&model, format!("{}\nfn mine() {{}}\n", fs::read_to_string(&model).unwrap())).unwrap();
+
+    let plan = with_cwd(&project_dir, || updater::plan_update(&open_project(&project_dir)).unwrap());
+    assert!(plan.skipped.is_empty(), "{:?}", plan.skipped);
+    assert!(plan.unchanged.iter().any(|i| i.output_path == "backend/src/entities/post.rs"));
+    let item = plan
+        .merged
+        .iter()
+        .find(|i| i.output_path == handlers)
+        .expect("post handlers should merge cleanly");
+    assert_eq!(item.group, UpdateGroup::Entity("Post".to_string()));
+    assert_eq!(plan.by_group()[&UpdateGroup::Entity("Post".to_string())].merged, 1);
+
+    updater::apply_merge(&open_project(&project_dir), &mut manifest, item).unwrap();
+    let merged = fs::read_to_string(project_dir.join(handlers)).unwrap();
+    assert!(!merged.contains("old_template"), "{}", merged);
+    assert!(merged.contains("list_comments"), "injected handler should be kept");
+    assert!(merged.ends_with("\nfn keep_me() {}\n"), "custom block should be kept");
+    assert_eq!(merged.matches("// === ROMANCE:CUSTOM ===").count(), 1);
+}
+
+#[test]
+fn test_update_tracks_files_generated_before_tracking() {
+    use romance_core::addon::Addon;
+    use romance_core::manifest::{FileCategory, Manifest};
+    use romance_core::updater::{self, UpdateGroup};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("update-untracked-test");
+    setup_project_for_addon(&project_dir);
+
+    with_cwd(&project_dir, || {
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        romance_core::generator::migration::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+    });
+    romance_core::addon::cache::CacheAddon.install(&open_project(&project_dir)).unwrap();
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+
+    let plan = with_cwd(&project_dir, || updater::plan_update(&open_project(&project_dir)).unwrap());
+    let untracked: Vec<_> = plan.untracked.iter().map(|i| i.output_path.as_str()).collect();
+    assert!(untracked.contains(&"backend/src/handlers/tag.rs"), "{:?}", untracked);
+    assert!(untracked.contains(&"backend/src/cache.rs"), "{:?}", untracked);
+    // Frontend files that were never generated are not created
+    assert!(plan.new_files.iter().all(|i| !matches!(i.group, UpdateGroup::Entity(_))));
+
+    let mut manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    for item in &plan.untracked {
+        updater::record_baseline(&open_project(&project_dir), &mut manifest, item).unwrap();
+    }
+    manifest.save(&DiskFs, &project_dir).unwrap();
+    assert_eq!(manifest.files["backend/src/cache.rs"].category, FileCategory::Addon);
+
+    let plan = with_cwd(&project_dir, || updater::plan_update(&open_project(&project_dir)).unwrap());
+    assert!(plan.untracked.is_empty());
+    for (group, counts) in plan.by_group() {
+        assert!(group == UpdateGroup::Scaffold || !counts.has_changes(), "{} has changes", group);
+    }
+
+    // Addons installed from now on are tracked when installed
+    romance_core::addon::run_addon(&romance_core::addon::search::SearchAddon, &mut open_project(&project_dir)).unwrap();
+    let manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    assert_eq!(manifest.files["backend/src/search.rs"].category, FileCategory::Addon);
+}
+
+#[test]
+fn test_status_reports_drift_from_manifest() {
+    use romance_core::addon::Addon;
+    use romance_core::manifest::Manifest;
+    use romance_core::relation::{self, PendingRelation};
+    use romance_core::status;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("status-test");
+    setup_project_for_addon(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+
+    with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+        let comment = romance_core::entity::parse_entity(
+            "Comment",
+            &["body:text".to_string(), "post_id:uuid->Post".to_string()],
+        ).unwrap();
+        for entity in [&post, &comment] {
+            romance_core::generator::backend::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+            romance_core::generator::migration::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        }
+        // No migration for Tag
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+        romance_core::generator::backend::generate(&open_project(&project_dir), &tag, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+    });
+    let status = status::scan(&open_project(&project_dir)).unwrap();
+    assert!(status.modified.is_empty(), "injected code isn't a modification: {:?}", status.modified);
+    assert_eq!(status.missing_migrations, vec!["tag"]);
+
+    let routes = project_dir.join("backend/src/routes/comment.rs");
+    fs::write(&routes, replace_first_use(&fs::read_to_string(&routes).unwrap(), "use my::Thing;")).unwrap();
+    fs::remove_file(project_dir.join("backend/src/handlers/tag.rs")).unwrap();
+    relation::store_pending(
+        &open_project(&project_dir),
+        PendingRelation {
+            source_entity: "Post".to_string(),
+            target_entity: "Label".to_string(),
+            relation_type: "ManyToMany".to_string(),
+        },
+    ).unwrap();
+    romance_core::addon::cache::CacheAddon.install(&open_project(&project_dir)).unwrap();
+
+    let status = status::scan(&open_project(&project_dir)).unwrap();
+    let paths = |files: &[status::TrackedFile]| files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+    assert_eq!(paths(&status.modified), vec!["backend/src/routes/comment.rs"]);
+    assert_eq!(status.modified[0].entity.as_deref(), Some("Comment"));
+    assert_eq!(paths(&status.deleted), vec!["backend/src/handlers/tag.rs"]);
+    assert_eq!(status.pending_relations.len(), 1);
+    assert_eq!(status.unrecorded_addons, vec!["cache"]);
+    assert!(!status.is_clean());
+
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json["deleted"][0]["category"], "entity");
+    assert_eq!(json["missing_migrations"][0], "tag");
+}
+
+#[test]
+fn test_rename_entity_rewrites_references_and_renames_tables() {
+    use romance_core::entity::FieldType;
+    use romance_core::manifest::Manifest;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("rename-test");
+    setup_minimal_project(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+
+    let report = with_cwd(&project_dir, || {
+        let post = romance_core::entity::parse_entity("Post", &["title:string".to_string(), "body:text".to_string()]).unwrap();
+        let comment = romance_core::entity::parse_entity(
+            "Comment",
+            &["body:text".to_string(), "post_id:uuid->Post".to_string()],
+        ).unwrap();
+        let tag = romance_core::entity::parse_entity("Tag", &["name:string".to_string()]).unwrap();
+        for entity in [&post, &comment, &tag] {
+            romance_core::generator::backend::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+            romance_core::generator::migration::generate(&open_project(&project_dir), entity, &mut romance_core::generator::plan::GenerationTracker::new()).unwrap();
+        }
+        romance_core::generator::junction::generate(&open_project(&project_dir), "Post", "Tag").unwrap();
+
+        romance_core::generator::rename::entity(&open_project(&project_dir), "Post", "Article").unwrap()
+    });
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+
+    for dir in ["entities", "handlers", "routes"] {
+        assert!(!project_dir.join(format!("backend/src/{}/post.rs", dir)).exists());
+        assert!(project_dir.join(format!("backend/src/{}/article.rs", dir)).exists());
+    }
+    assert!(project_dir.join("backend/src/entities/article_tag.rs").exists());
+    let entities_mod = fs::read_to_string(project_dir.join("backend/src/entities/mod.rs")).unwrap();
+    assert!(entities_mod.contains("pub mod article;") && entities_mod.contains("pub mod article_tag;"));
+    assert!(!entities_mod.contains("pub mod post"));
+    let routes_mod = fs::read_to_string(project_dir.join("backend/src/routes/mod.rs")).unwrap();
+    assert!(routes_mod.contains(".merge(article::router())"));
+    let routes = fs::read_to_string(project_dir.join("backend/src/routes/article.rs")).unwrap();
+    assert!(routes.contains("\"/api/articles\", post(article::create)"), "{}", routes);
+
+    // Foreign keys named after the entity follow it; other names are left alone
+    let comment = fs::read_to_string(project_dir.join("backend/src/entities/comment.rs")).unwrap();
+    assert!(comment.contains("pub article_id: Uuid") && comment.contains("super::article::Entity"));
+    assert!(comment.contains("pub body: String"));
+    let model = fs::read_to_string(project_dir.join("backend/src/entities/article.rs")).unwrap();
+    assert!(model.contains("table_name = \"articles\"") && model.contains("pub struct ArticleResponse"));
+
+    let migration_dir = project_dir.join("backend/migration/src");
+    let rename = fs::read_dir(&migration_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().contains("_rename_post_to_article"))
+        .expect("rename migration should exist");
+    let rename_src = fs::read_to_string(rename.path()).unwrap();
+    assert!(rename_src.contains(".table(Alias::new(\"posts\"), Alias::new(\"articles\"))"));
+    assert!(rename_src.contains(".table(Alias::new(\"post_tag\"), Alias::new(\"article_tag\"))"));
+    assert!(rename_src.contains(".table(Alias::new(\"comments\"))"));
+    assert!(rename_src.contains(".rename_column(Alias::new(\"post_id\"), Alias::new(\"article_id\"))"));
+    let lib = fs::read_to_string(migration_dir.join("lib.rs")).unwrap();
+    let rename_mod = rename.file_name().to_string_lossy().trim_end_matches(".rs").to_string();
+    assert!(lib.contains(&format!("mod {};", rename_mod)));
+
+    // The manifest follows, and the renamed entity is still recognised
+    let manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    assert!(!manifest.files.contains_key("backend/src/handlers/post.rs"));
+    assert_eq!(manifest.files["backend/src/handlers/article.rs"].entity_name.as_deref(), Some("Article"));
+    let status = romance_core::status::scan(&open_project(&project_dir)).unwrap();
+    assert!(status.modified.is_empty(), "{:?}", status.modified);
+    assert!(status.missing_migrations.is_empty(), "{:?}", status.missing_migrations);
+    let article = with_cwd(&project_dir, || romance_core::generator::recover::entity(&open_project(&project_dir), "Article").unwrap());
+    let body = article.fields.iter().find(|f| f.name == "body").unwrap();
+    assert!(matches!(body.field_type, FieldType::Text));
+}
+
+// ==========================================================================
+// Project handle tests
+// ==========================================================================
+
+#[test]
+fn test_generators_target_project_root_not_cwd() {
+    let dir = tempfile::tempdir().unwrap();
+    let shop = dir.path().join("shop");
+    let blog = dir.path().join("blog");
+    setup_minimal_project(&shop);
+    setup_minimal_project(&blog);
+    fs::write(
+        blog.join("romance.toml"),
+        fs::read_to_string(blog.join("romance.toml")).unwrap()
+            + "\n[features]\nsoft_delete = true\n",
+    )
+    .unwrap();
+
+    // No with_cwd: both projects are generated into from wherever the tests run
+    let shop = Project::open(&shop).unwrap();
+    let blog = Project::open(&blog).unwrap();
+    assert!(!shop.features().soft_delete);
+    assert!(blog.features().soft_delete);
+
+    let product = romance_core::entity::parse_entity("Product", &["title:string".to_string()]).unwrap();
+    let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::backend::generate(&shop, &product, &mut tracker).unwrap();
+    romance_core::generator::migration::generate(&shop, &product, &mut tracker).unwrap();
+    romance_core::generator::backend::generate(&blog, &post, &mut tracker).unwrap();
+    romance_core::generator::frontend::generate(&blog, &post, &mut tracker).unwrap();
+
+    assert!(shop.join("backend/src/entities/product.rs").exists());
+    assert!(!shop.join("backend/src/entities/post.rs").exists());
+    assert!(blog.join("backend/src/entities/post.rs").exists());
+    assert!(blog.join("frontend/src/features/post").exists());
+    assert!(!Path::new("backend/src/entities/product.rs").exists());
+
+    // Each project's own features shape its output
+    let product_model = fs::read_to_string(shop.join("backend/src/entities/product.rs")).unwrap();
+    let post_model = fs::read_to_string(blog.join("backend/src/entities/post.rs")).unwrap();
+    assert!(!product_model.contains("deleted_at"));
+    assert!(post_model.contains("deleted_at"));
+}
+
+#[test]
+fn test_project_open_requires_romance_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = Project::open(dir.path()).err().unwrap();
+    assert!(err.to_string().contains("romance.toml not found"));
+}
+
+#[test]
+fn test_generation_runs_entirely_in_memory() {
+    use romance_core::fs::{MemoryFs, ProjectFs};
+    use std::sync::Arc;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("memory-app");
+    let memory = Arc::new(MemoryFs::new());
+
+    let mut project =
+        romance_core::scaffold::render_project(memory.clone(), &project_dir, "memory-app").unwrap();
+    let post = romance_core::entity::parse_entity("Post", &["title:string".to_string()]).unwrap();
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::backend::generate(&project, &post, &mut tracker).unwrap();
+    romance_core::generator::migration::generate(&project, &post, &mut tracker).unwrap();
+    romance_core::generator::frontend::generate(&project, &post, &mut tracker).unwrap();
+    romance_core::addon::run_addon(&romance_core::addon::validation::ValidationAddon, &mut project).unwrap();
+
+    assert!(!project_dir.exists(), "nothing should be written to disk");
+
+    let files = memory.files_under(&project_dir);
+    for rel in [
+        "romance.toml",
+        "backend/src/main.rs",
+        "backend/src/entities/post.rs",
+        "backend/src/handlers/post.rs",
+        "frontend/src/features/post/PostList.tsx",
+        "backend/src/validation.rs",
+        "CLAUDE.md",
+    ] {
+        assert!(files.contains_key(Path::new(rel)), "{} should be generated", rel);
+    }
+    let entities = memory.read_to_string(&project_dir.join("backend/src/entities/mod.rs")).unwrap();
+    assert!(entities.contains("pub mod post;"), "{}", entities);
+    assert!(files.keys().any(|p| p.to_string_lossy().ends_with("_create_post_table.rs")));
+
+    let manifest = romance_core::manifest::Manifest::load(&*memory, &project_dir).unwrap();
+    assert!(manifest.files.contains_key("backend/src/handlers/post.rs"));
+    assert!(manifest.files.contains_key("backend/src/validation.rs"));
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.modified.is_empty(), "{:?}", status.modified);
+}
+
+// ==========================================================================
+// Failure category tests
+// ==========================================================================
+
+#[test]
+fn test_errors_carry_failure_categories() {
+    use romance_core::error::Failure;
+
+    let dir = tempfile::tempdir().unwrap();
+    let err = Project::open(dir.path()).err().unwrap();
+    assert_eq!(Failure::of(&err), Some(Failure::NotAProject));
+
+    let err = romance_core::entity::parse_entity("Post", &["title:strnig".to_string()]).unwrap_err();
+    assert_eq!(Failure::of(&err), Some(Failure::InvalidInput));
+
+    // Auth-dependent addons report the missing prerequisite, not a generic error
+    let project_dir = dir.path().join("app");
+    setup_minimal_project(&project_dir);
+    let mut project = Project::open(&project_dir).unwrap();
+    let err = romance_core::addon::run_addon(&romance_core::addon::api_keys::ApiKeysAddon, &mut project)
+        .unwrap_err();
+    assert_eq!(Failure::of(&err), Some(Failure::Precondition));
+
+    // Prompts refuse to run without a terminal instead of hanging
+    romance_core::utils::ui::set_interactive(false);
+    let err = romance_core::entity::prompt_entity_fields("Post").unwrap_err();
+    romance_core::utils::ui::set_interactive(true);
+    assert_eq!(Failure::of(&err), Some(Failure::InvalidInput));
+    assert!(err.to_string().contains("title:string"), "{}", err);
+}
+
+// ==========================================================================
+// Entity module tests
+// ==========================================================================
+
+/// Generate `entity` through the same steps as `romance generate entity`.
+fn generate_full_entity(project: &Project, entity: &romance_core::entity::EntityDefinition) {
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::backend::validate(project, entity).unwrap();
+    romance_core::generator::frontend::validate(project, entity).unwrap();
+    romance_core::generator::backend::generate(project, entity, &mut tracker).unwrap();
+    romance_core::generator::migration::generate(project, entity, &mut tracker).unwrap();
+    romance_core::generator::backend::generate_relations(project, entity).unwrap();
+    romance_core::generator::frontend::generate(project, entity, &mut tracker).unwrap();
+}
+
+fn module_entity(name: &str, module: &str, fields: &[&str]) -> romance_core::entity::EntityDefinition {
+    let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+    let mut entity = romance_core::entity::parse_entity(name, &fields).unwrap();
+    entity.module = Some(module.to_string());
+    entity
+}
+
+#[test]
+fn test_module_generation_declares_and_registers_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-app");
+    setup_minimal_project(&project_dir);
+
+    let mut project = open_project(&project_dir);
+    let config = romance_core::config::ModuleConfig {
+        prefix: None,
+        table_prefix: Some("billing_".to_string()),
+        schema: None,
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+
+    assert_eq!(
+        project.config().modules["billing"].table_prefix.as_deref(),
+        Some("billing_")
+    );
+    let toml = fs::read_to_string(project_dir.join("romance.toml")).unwrap();
+    assert!(toml.contains("[modules.billing]\ntable_prefix = \"billing_\""));
+
+    for dir in ["entities", "handlers", "routes"] {
+        let module_mod = project_dir.join(format!("backend/src/{}/billing/mod.rs", dir));
+        assert!(fs::read_to_string(module_mod).unwrap().contains("// === ROMANCE:MODS ==="));
+    }
+    let routes = fs::read_to_string(project_dir.join("backend/src/routes/mod.rs")).unwrap();
+    assert!(routes.contains("pub mod billing;"));
+    assert!(routes.contains(".merge(billing::router())"));
+    let sidebar = fs::read_to_string(project_dir.join("frontend/src/components/AppSidebar.tsx")).unwrap();
+    assert!(sidebar.contains(">Billing</p>"));
+    assert!(sidebar.contains("{/* === ROMANCE:NAV_LINKS:billing === */}"));
+
+    // Declared once, under a name that can be a Rust module
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    let again = romance_core::generator::module::generate(&mut project, "billing", Default::default(), &mut tracker);
+    assert!(again.unwrap_err().to_string().contains("already exists"));
+    let invalid = romance_core::generator::module::generate(&mut project, "Billing", Default::default(), &mut tracker);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_module_generation_keeps_status_clean() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-status-app");
+    let mut project =
+        romance_core::scaffold::render_project(std::sync::Arc::new(DiskFs), &project_dir, "module-status-app").unwrap();
+
+    let config = romance_core::config::ModuleConfig {
+        prefix: None,
+        table_prefix: None,
+        schema: Some("billing".to_string()),
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.is_clean(), "{:?}", status);
+
+    // Feature flags are written to romance.toml the same way
+    romance_core::addon::run_addon(&romance_core::addon::cache::CacheAddon, &mut project).unwrap();
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.modified.iter().all(|f| f.path != "romance.toml"), "{:?}", status.modified);
+}
+
+#[test]
+fn test_entity_generated_in_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-entity-app");
+    setup_minimal_project(&project_dir);
+
+    let mut project = open_project(&project_dir);
+    let config = romance_core::config::ModuleConfig {
+        prefix: None,
+        table_prefix: Some("billing_".to_string()),
+        schema: None,
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+
+    let customer = romance_core::entity::parse_entity("Customer", &["name:string".to_string()]).unwrap();
+    generate_full_entity(&project, &customer);
+    let invoice = module_entity("Invoice", "billing", &["number:string", "customer_id:uuid->Customer"]);
+    generate_full_entity(&project, &invoice);
+    let line = module_entity("InvoiceLine", "billing", &["amount:decimal", "invoice_id:uuid->Invoice"]);
+    generate_full_entity(&project, &line);
+
+    let read = |path: &str| fs::read_to_string(project_dir.join(path)).unwrap();
+
+    // Nested Rust modules, registered in the module's mod.rs files
+    assert!(!project_dir.join("backend/src/entities/invoice.rs").exists());
+    assert!(read("backend/src/entities/billing/mod.rs").contains("pub mod invoice;"));
+    assert!(read("backend/src/handlers/billing/mod.rs").contains("pub mod invoice_line;"));
+    let module_routes = read("backend/src/routes/billing/mod.rs");
+    assert!(module_routes.contains(".merge(invoice::router())"));
+    assert!(!read("backend/src/routes/mod.rs").contains("invoice"));
+
+    // Table prefix, and model paths across and within the module
+    let invoice_model = read("backend/src/entities/billing/invoice.rs");
+    assert!(invoice_model.contains("table_name = \"billing_invoices\""));
+    assert!(invoice_model.contains("belongs_to = \"crate::entities::customer::Entity\""));
+    assert!(invoice_model.contains("impl Related<super::invoice_line::Entity> for Entity"));
+    let line_model = read("backend/src/entities/billing/invoice_line.rs");
+    assert!(line_model.contains("belongs_to = \"super::invoice::Entity\""));
+    let customer_model = read("backend/src/entities/customer.rs");
+    assert!(customer_model.contains("impl Related<crate::entities::billing::invoice::Entity> for Entity"));
+
+    // Routes under the module's prefix, handlers through the nested path
+    let invoice_routes = read("backend/src/routes/billing/invoice.rs");
+    assert!(invoice_routes.contains("use crate::handlers::billing::invoice;"));
+    assert!(invoice_routes.contains("\"/api/billing/invoices\""));
+    assert!(invoice_routes.contains("/api/billing/invoices/{id}/invoice_lines"));
+    let invoice_handlers = read("backend/src/handlers/billing/invoice.rs");
+    assert!(invoice_handlers.contains("use crate::entities::billing::invoice::{"));
+    assert!(invoice_handlers.contains("tag = \"Billing\""));
+    assert!(read("backend/src/handlers/customer.rs").contains("crate::entities::billing::invoice::Entity::find()"));
+
+    // Migrations create and reference the prefixed tables
+    let migration_dir = project_dir.join("backend/migration/src");
+    let line_migration = fs::read_dir(&migration_dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().contains("create_invoice_line_table"))
+        .map(|e| fs::read_to_string(e.path()).unwrap())
+        .unwrap();
+    assert!(line_migration.contains("#[sea_orm(iden = \"billing_invoice_lines\")]"));
+    assert!(line_migration.contains(".to(Alias::new(\"billing_invoices\"), Alias::new(\"id\"))"));
+
+    // Frontend features, routes and sidebar section
+    assert!(project_dir.join("frontend/src/features/billing/invoice/InvoiceList.tsx").exists());
+    assert!(read("frontend/src/features/billing/invoice/api.ts").contains("'/billing/invoices'"));
+    let app = read("frontend/src/App.tsx");
+    assert!(app.contains("import InvoiceList from '@/features/billing/invoice/InvoiceList'"));
+    assert!(app.contains("<Route path=\"/billing/invoices\""));
+    let sidebar = read("frontend/src/components/AppSidebar.tsx");
+    let section = sidebar.find(">Billing</p>").unwrap();
+    let link = sidebar.find("<NavLink to=\"/billing/invoices\"").unwrap();
+    let marker = sidebar.find("{/* === ROMANCE:NAV_LINKS:billing === */}").unwrap();
+    assert!(section < link && link < marker);
+
+    // The definition, module included, is recovered from the nested files
+    let recovered = romance_core::generator::recover::entity(&project, "InvoiceLine").unwrap();
+    assert_eq!(recovered.module.as_deref(), Some("billing"));
+    assert_eq!(recovered.fields[1].relation.as_deref(), Some("Invoice"));
+}
+
+#[test]
+fn test_destroy_and_rename_entities_in_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-destroy-app");
+    setup_minimal_project(&project_dir);
+
+    let mut project = open_project(&project_dir);
+    let config = romance_core::config::ModuleConfig {
+        prefix: None,
+        table_prefix: Some("billing_".to_string()),
+        schema: None,
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+    let customer = romance_core::entity::parse_entity("Customer", &["name:string".to_string()]).unwrap();
+    generate_full_entity(&project, &customer);
+    generate_full_entity(&project, &module_entity("Invoice", "billing", &["number:string", "customer_id:uuid->Customer"]));
+    generate_full_entity(&project, &module_entity("InvoiceLine", "billing", &["amount:decimal", "invoice_id:uuid->Invoice"]));
+
+    let read = |path: &str| fs::read_to_string(project_dir.join(path)).unwrap();
+    let migration = |name: &str| {
+        fs::read_dir(project_dir.join("backend/migration/src"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .find(|e| e.file_name().to_string_lossy().contains(name))
+            .map(|e| fs::read_to_string(e.path()).unwrap())
+            .unwrap()
+    };
+
+    // Renaming keeps the entity in its module and the table prefix
+    let report = romance_core::generator::rename::entity(&open_project(&project_dir), "Invoice", "Bill").unwrap();
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+    assert!(!project_dir.join("backend/src/entities/billing/invoice.rs").exists());
+    assert!(read("backend/src/entities/billing/bill.rs").contains("table_name = \"billing_bills\""));
+    assert!(read("backend/src/entities/billing/mod.rs").contains("pub mod bill;"));
+    assert!(read("backend/src/entities/billing/invoice_line.rs").contains("belongs_to = \"super::bill::Entity\""));
+    assert!(project_dir.join("frontend/src/features/billing/bill/BillList.tsx").exists());
+    let rename = migration("_rename_invoice_to_bill");
+    assert!(rename.contains(".table(Alias::new(\"billing_invoices\"), Alias::new(\"billing_bills\"))"));
+    assert!(rename.contains(".rename_column(Alias::new(\"invoice_id\"), Alias::new(\"bill_id\"))"));
+
+    // Fields are re-rendered in place
+    let report = romance_core::generator::destroy::field(&open_project(&project_dir), "InvoiceLine", "amount", false).unwrap();
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+    assert!(!read("backend/src/entities/billing/invoice_line.rs").contains("amount"));
+    assert!(!read("frontend/src/features/billing/invoiceLine/types.ts").contains("amount"));
+    assert!(migration("_remove_amount_from_").contains(".table(Alias::new(\"billing_invoice_lines\"))"));
+
+    // Destroying a child cleans the module's registrations and its parent
+    let report = romance_core::generator::destroy::entity(&open_project(&project_dir), "InvoiceLine", false).unwrap();
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+    for dir in ["entities", "handlers", "routes"] {
+        assert!(!project_dir.join(format!("backend/src/{}/billing/invoice_line.rs", dir)).exists());
+        assert!(!read(&format!("backend/src/{}/billing/mod.rs", dir)).contains("pub mod invoice_line;"));
+    }
+    assert!(!project_dir.join("frontend/src/features/billing/invoiceLine").exists());
+    assert!(!read("backend/src/entities/billing/bill.rs").contains("invoice_line"));
+    assert!(!read("backend/src/handlers/billing/bill.rs").contains("invoice_line"));
+    assert!(!read("frontend/src/App.tsx").contains("InvoiceLine"));
+    assert!(!read("frontend/src/components/AppSidebar.tsx").contains("/billing/invoice_lines"));
+    assert!(migration("_drop_invoice_line_table").contains(".table(Alias::new(\"billing_invoice_lines\"))"));
+
+    // Destroying the last one leaves the top-level entity it pointed at clean
+    let report = romance_core::generator::destroy::entity(&open_project(&project_dir), "Bill", true).unwrap();
+    assert!(report.leftovers.is_empty(), "unexpected leftovers: {:?}", report.leftovers);
+    assert!(!read("backend/src/routes/billing/mod.rs").contains("bill::router()"));
+    assert!(!read("backend/src/entities/customer.rs").contains("billing::bill"));
+    assert!(!read("backend/src/handlers/customer.rs").contains("billing::bill"));
+    assert!(read("backend/src/entities/mod.rs").contains("pub mod customer;"));
+    assert!(read("frontend/src/components/AppSidebar.tsx").contains("{/* === ROMANCE:NAV_LINKS:billing === */}"));
+}
+
+#[test]
+fn test_module_schema_qualifies_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-schema-app");
+    setup_minimal_project(&project_dir);
+
+    let mut project = open_project(&project_dir);
+    let config = romance_core::config::ModuleConfig {
+        prefix: Some("finance".to_string()),
+        table_prefix: None,
+        schema: Some("billing".to_string()),
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+    generate_full_entity(&project, &module_entity("Invoice", "billing", &["number:string"]));
+
+    let migrations: Vec<String> = fs::read_dir(project_dir.join("backend/migration/src"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    let schema_migration = migrations.iter().find(|m| m.ends_with("_create_billing_schema.rs")).unwrap();
+    let schema_sql = fs::read_to_string(project_dir.join("backend/migration/src").join(schema_migration)).unwrap();
+    assert!(schema_sql.contains("CREATE SCHEMA IF NOT EXISTS \\\"billing\\\""));
+
+    let model = fs::read_to_string(project_dir.join("backend/src/entities/billing/invoice.rs")).unwrap();
+    assert!(model.contains("schema_name = \"billing\", table_name = \"invoices\""));
+    let routes = fs::read_to_string(project_dir.join("backend/src/routes/billing/invoice.rs")).unwrap();
+    assert!(routes.contains("\"/api/finance/invoices\""));
+    let invoice_migration = migrations.iter().find(|m| m.contains("create_invoice_table")).unwrap();
+    let invoice_sql = fs::read_to_string(project_dir.join("backend/migration/src").join(invoice_migration)).unwrap();
+    assert!(invoice_sql.contains(".table((Alias::new(\"billing\"), Invoice::Table))"));
+
+    // Dropping the table names the schema too
+    romance_core::generator::destroy::entity(&open_project(&project_dir), "Invoice", false).unwrap();
+    let drop = fs::read_dir(project_dir.join("backend/migration/src"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().contains("_drop_invoice_table"))
+        .map(|e| fs::read_to_string(e.path()).unwrap())
+        .unwrap();
+    assert!(drop.contains(".table((Alias::new(\"billing\"), Alias::new(\"invoices\")))"));
+}
+
+#[test]
+fn test_entity_in_undeclared_module_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("module-missing-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let invoice = module_entity("Invoice", "billing", &["number:string"]);
+    let err = romance_core::generator::backend::validate(&project, &invoice).unwrap_err();
+    assert_eq!(romance_core::error::Failure::of(&err), Some(romance_core::error::Failure::Precondition));
+    assert!(err.to_string().contains("romance generate module billing"));
+}
+
+// ==========================================================================
+// Import tests
+// ==========================================================================
+
+const LEGACY_SCHEMA: &str = r#"
+CREATE TYPE public.post_status AS ENUM ('draft', 'published');
+
+CREATE TABLE public.people (
+    id uuid NOT NULL,
+    name character varying(100) NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE TABLE public.posts (
+    id uuid NOT NULL,
+    title character varying(200) NOT NULL,
+    body text,
+    status public.post_status DEFAULT 'draft'::public.post_status NOT NULL,
+    author_id uuid NOT NULL,
+    views integer DEFAULT 0,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE TABLE public.tags (
+    id uuid PRIMARY KEY,
+    label text NOT NULL,
+    created_at timestamp with time zone NOT NULL,
+    updated_at timestamp with time zone NOT NULL
+);
+
+CREATE TABLE public.post_tag (
+    id uuid PRIMARY KEY,
+    post_id uuid NOT NULL REFERENCES public.posts(id),
+    tag_id uuid NOT NULL REFERENCES public.tags(id),
+    created_at timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY public.people ADD CONSTRAINT people_pkey PRIMARY KEY (id);
+ALTER TABLE ONLY public.posts ADD CONSTRAINT posts_pkey PRIMARY KEY (id);
+ALTER TABLE ONLY public.posts
+    ADD CONSTRAINT posts_author_id_fkey FOREIGN KEY (author_id) REFERENCES public.people(id);
+"#;
+
+#[test]
+fn test_import_sql_plans_entities_relations_and_junctions() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("import-plan-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let db = romance_core::import::sql::parse(LEGACY_SCHEMA).unwrap();
+    let import = romance_core::import::plan(&project, &db);
+
+    let names: Vec<&str> = import.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["People", "Post", "Tag"]);
+    assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+    let post = &import.entities[1];
+    assert_eq!(post.table_name, None);
+    let field = |name: &str| post.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(matches!(field("title").field_type, romance_core::entity::FieldType::String));
+    assert!(matches!(field("body").field_type, romance_core::entity::FieldType::Text));
+    assert!(field("body").optional);
+    assert!(matches!(
+        &field("status").field_type,
+        romance_core::entity::FieldType::Enum(v) if v == &["draft", "published"]
+    ));
+    assert_eq!(field("status").default.as_deref(), Some("draft"));
+    assert_eq!(field("author_id").relation.as_deref(), Some("People"));
+    assert!(post.relations.iter().any(|r| r.target_entity == "Tag"
+        && r.relation_type == romance_core::entity::RelationType::ManyToMany));
+
+    // `people` isn't the plural romance would pick, so the table is kept
+    assert_eq!(import.entities[0].table_name.as_deref(), Some("people"));
+    assert_eq!(import.junctions.len(), 1);
+    assert_eq!(import.junctions[0].table, "post_tag");
+}
+
+#[test]
+fn test_import_sql_generates_entities_without_create_migrations() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("import-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let db = romance_core::import::sql::parse(LEGACY_SCHEMA).unwrap();
+    let import = romance_core::import::plan(&project, &db);
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::import::generate(&project, &import, &mut tracker).unwrap();
+
+    let read = |path: &str| fs::read_to_string(project_dir.join(path)).unwrap();
+    assert!(read("backend/src/entities/people.rs").contains("table_name = \"people\""));
+    assert!(read("backend/src/entities/post.rs").contains("belongs_to = \"super::people::Entity\""));
+    assert!(read("backend/src/entities/post_tag.rs").contains("table_name = \"post_tag\""));
+    assert!(read("backend/src/handlers/tag.rs").contains("list_posts"));
+
+    // Only the baseline migration, listing every imported table
+    let migrations: Vec<String> = fs::read_dir(project_dir.join("backend/migration/src"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with('m'))
+        .collect();
+    assert_eq!(migrations.len(), 1, "{:?}", migrations);
+    assert!(migrations[0].ends_with("_import_baseline.rs"));
+    let baseline = read(&format!("backend/migration/src/{}", migrations[0]));
+    assert!(baseline.contains("//! Tables: people, posts, tags, post_tag"));
+    assert!(read("backend/migration/src/lib.rs").contains(&format!("mod {};", migrations[0].trim_end_matches(".rs"))));
+
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.missing_migrations.is_empty(), "{:?}", status.missing_migrations);
+
+    // The recovered definition keeps the imported table name
+    let people = romance_core::generator::recover::entity(&project, "People").unwrap();
+    assert_eq!(people.table_name.as_deref(), Some("people"));
+
+    // Importing again finds nothing new
+    let again = romance_core::import::plan(&project, &db);
+    assert!(again.entities.is_empty());
+}
+
+const PETSTORE_SPEC: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths: {}
+components:
+  schemas:
+    Status:
+      type: string
+      enum: [available, pending, sold]
+    Owner:
+      type: object
+      required: [name, email]
+      properties:
+        id:
+          type: string
+          format: uuid
+        name:
+          type: string
+          minLength: 2
+          maxLength: 80
+        email:
+          type: string
+          format: email
+    Tag:
+      type: object
+      required: [label]
+      properties:
+        label:
+          type: string
+          pattern: "^[a-z-]+$"
+    Pet:
+      type: object
+      required: [name, owner]
+      properties:
+        name:
+          type: string
+        birthDate:
+          type: string
+          format: date
+        weight:
+          type: number
+          nullable: true
+        status:
+          $ref: '#/components/schemas/Status'
+        owner:
+          $ref: '#/components/schemas/Owner'
+        tags:
+          type: array
+          items:
+            $ref: '#/components/schemas/Tag'
+        nicknames:
+          type: array
+          items:
+            type: string
+        extra:
+          oneOf:
+            - type: string
+            - type: integer
+"#;
+
+#[test]
+fn test_import_openapi_plans_entities_from_component_schemas() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("openapi-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let doc = romance_core::import::openapi::parse(PETSTORE_SPEC).unwrap();
+    let import = romance_core::import::openapi::plan(&project, &doc, &[]).unwrap();
+    assert!(!import.baseline);
+
+    let names: Vec<&str> = import.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names.len(), 3);
+    let position = |name: &str| names.iter().position(|n| *n == name).unwrap();
+    assert!(position("Owner") < position("Pet"));
+
+    use romance_core::entity::{FieldType, RelationType, ValidationRule};
+    let owner = &import.entities[position("Owner")];
+    assert!(!owner.fields.iter().any(|f| f.name == "id"));
+    let email = owner.fields.iter().find(|f| f.name == "email").unwrap();
+    assert_eq!(email.validations, vec![ValidationRule::Email]);
+    let name = owner.fields.iter().find(|f| f.name == "name").unwrap();
+    assert_eq!(name.validations, vec![ValidationRule::Min(2), ValidationRule::Max(80)]);
+
+    let pet = &import.entities[position("Pet")];
+    let field = |name: &str| pet.fields.iter().find(|f| f.name == name).unwrap();
+    assert!(matches!(field("birth_date").field_type, FieldType::Date));
+    assert!(field("weight").optional);
+    assert!(matches!(&field("status").field_type, FieldType::Enum(v) if v.len() == 3));
+    assert_eq!(field("owner_id").relation.as_deref(), Some("Owner"));
+    assert!(!field("owner_id").optional);
+    assert!(matches!(field("nicknames").field_type, FieldType::Json));
+    assert!(pet
+        .relations
+        .iter()
+        .any(|r| r.name == "tags" && r.relation_type == RelationType::ManyToMany && r.target_entity == "Tag"));
+
+    assert!(import.warnings.iter().any(|w| w.contains("Pet.nicknames")), "{:?}", import.warnings);
+    assert!(import.warnings.iter().any(|w| w.contains("Pet.extra") && w.contains("oneOf")));
+
+    // A relation to a schema that isn't imported is reported, not generated
+    let only_pet = romance_core::import::openapi::plan(&project, &doc, &["Pet".to_string()]).unwrap();
+    assert!(only_pet.entities[0].relations.is_empty());
+    assert!(only_pet.warnings.iter().any(|w| w.contains("'Owner' isn't imported")));
+    assert!(romance_core::import::openapi::plan(&project, &doc, &["Missing".to_string()]).is_err());
+}
+
+#[test]
+fn test_import_openapi_generates_crud_stack_with_migrations() {
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("openapi-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let doc = romance_core::import::openapi::parse(PETSTORE_SPEC).unwrap();
+    let import = romance_core::import::openapi::plan(&project, &doc, &[]).unwrap();
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::import::generate(&project, &import, &mut tracker).unwrap();
+
+    let read = |path: &str| fs::read_to_string(project_dir.join(path)).unwrap();
+    assert!(read("backend/src/entities/pet.rs").contains("belongs_to = \"super::owner::Entity\""));
+    assert!(read("backend/src/entities/owner.rs").contains("email"));
+    assert!(project_dir.join("frontend/src/features/pet").exists());
+
+    let migrations: Vec<String> = fs::read_dir(project_dir.join("backend/migration/src"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with('m'))
+        .collect();
+    assert!(migrations.iter().any(|m| m.ends_with("_create_pet_table.rs")), "{:?}", migrations);
+    assert!(migrations.iter().any(|m| m.contains("pet_tag")), "{:?}", migrations);
+    assert!(!migrations.iter().any(|m| m.ends_with("_import_baseline.rs")));
+
+    // Generated like `romance generate entity`, so nothing is missing
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.missing_migrations.is_empty(), "{:?}", status.missing_migrations);
+}
+
+const ORDER_SAMPLE: &str = r#"[
+    {
+        "id": "5b0e6f3c-1c1e-4f7a-9a52-3d2f1f0c9e11",
+        "reference": "ORD-1001",
+        "total": "149.90",
+        "placedAt": "2024-05-01T10:30:00Z",
+        "deliveryDate": "2024-05-04",
+        "paid": true,
+        "customer": {"name": "Ada", "email": "ada@example.com"},
+        "items": [
+            {"sku": "A-1", "quantity": 2, "unitPrice": "49.95"},
+            {"sku": "B-7", "quantity": 1, "unitPrice": "50.00", "note": "gift wrap"}
+        ]
+    },
+    {
+        "id": "0d7c7a7e-6b8f-4a61-b0a4-0b6f1f3e2d10",
+        "reference": "ORD-1002",
+        "total": "20.00",
+        "placedAt": "2024-05-02T08:00:00Z",
+        "deliveryDate": null,
+        "paid": false,
+        "customer": {"name": "Grace", "email": "grace@example.com"},
+        "items": []
+    }
+]"#;
+
+#[test]
+fn test_infer_entity_from_sample_json() {
+    use romance_core::entity::{format_entity_args, RelationType};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("sample-app");
+    setup_minimal_project(&project_dir);
+
+    let project = open_project(&project_dir);
+    let sample: serde_json::Value = serde_json::from_str(ORDER_SAMPLE).unwrap();
+    let import = romance_core::import::sample::infer(&project, "Order", &sample, false).unwrap();
+
+    let names: Vec<&str> = import.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Order", "OrderItem"]);
+
+    let order = format_entity_args(&import.entities[0]);
+    for arg in [
+        "reference:string",
+        "total:decimal",
+        "placed_at:datetime",
+        "delivery_date:date?",
+        "paid:bool",
+        "customer:json",
+        "items:has_many->OrderItem",
+    ] {
+        assert!(order.contains(&arg.to_string()), "{} not in {:?}", arg, order);
+    }
+    assert!(!order.iter().any(|a| a.starts_with("id:")));
+
+    let item = format_entity_args(&import.entities[1]);
+    for arg in ["order_id:uuid->Order", "sku:string", "quantity:int32", "unit_price:decimal", "note:string?"] {
+        assert!(item.contains(&arg.to_string()), "{} not in {:?}", arg, item);
+    }
+
+    // Nested objects as entities the parent belongs to
+    let nested = romance_core::import::sample::infer(&project, "Order", &sample, true).unwrap();
+    let names: Vec<&str> = nested.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names[0], "Customer");
+    let order = &nested.entities[names.iter().position(|n| *n == "Order").unwrap()];
+    assert!(order
+        .relations
+        .iter()
+        .any(|r| r.relation_type == RelationType::BelongsTo && r.target_entity == "Customer"));
+
+    // The inferred entities generate like `romance generate entity`
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::import::generate(&project, &import, &mut tracker).unwrap();
+    let item_model = fs::read_to_string(project_dir.join("backend/src/entities/order_item.rs")).unwrap();
+    assert!(item_model.contains("belongs_to = \"super::order::Entity\""));
+    assert!(fs::read_to_string(project_dir.join("backend/src/entities/order.rs"))
+        .unwrap()
+        .contains("impl Related<super::order_item::Entity> for Entity"));
+
+    assert!(romance_core::import::sample::infer(&project, "Order", &serde_json::json!(42), false).is_err());
+}
+
+// ==========================================================================
+// Schema diagram tests
+// ==========================================================================
+
+#[test]
+fn test_schema_scan_reads_tables_relations_and_junctions() {
+    use romance_core::schema::diagram::{self, Format};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("diagram-app");
+    setup_minimal_project(&project_dir);
+
+    let mut project = open_project(&project_dir);
+    let config = romance_core::config::ModuleConfig {
+        prefix: None,
+        table_prefix: None,
+        schema: Some("billing".to_string()),
+    };
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    romance_core::generator::module::generate(&mut project, "billing", config, &mut tracker).unwrap();
+
+    let parse = |name: &str, fields: &[&str]| {
+        let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+        romance_core::entity::parse_entity(name, &fields).unwrap()
+    };
+    generate_full_entity(&project, &parse("Category", &["name:string"]));
+    generate_full_entity(&project, &parse("Tag", &["label:string"]));
+    generate_full_entity(
+        &project,
+        &parse("Post", &["body:text", "category_id:uuid->Category?", "tags:m2m->Tag"]),
+    );
+    generate_full_entity(&project, &module_entity("Invoice", "billing", &["post_id:uuid->Post"]));
+
+    let tables = romance_core::schema::scan(&project).unwrap();
+    let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["billing.invoices", "categories", "post_tag", "posts", "tags"]);
+
+    let table = |name: &str| tables.iter().find(|t| t.name == name).unwrap();
+    let column = |table_name: &str, column: &str| {
+        table(table_name).columns.iter().find(|c| c.name == column).unwrap().clone()
+    };
+    assert_eq!(column("posts", "body").sql_type, "text");
+    assert!(column("posts", "id").primary_key);
+    let category = column("posts", "category_id");
+    assert!(category.nullable);
+    assert_eq!(category.references.as_deref(), Some("categories"));
+    assert_eq!(column("billing.invoices", "post_id").references.as_deref(), Some("posts"));
+    assert!(table("post_tag").junction);
+    assert_eq!(table("post_tag").foreign_keys().count(), 2);
+    assert!(!table("posts").junction);
+
+    let mermaid = diagram::render(&tables, Format::Mermaid);
+    assert!(mermaid.contains("    categories |o--o{ posts : \"category_id\""));
+    assert!(mermaid.contains("    posts ||--o{ billing_invoices : \"post_id\""));
+    assert!(mermaid.contains("    tags ||--o{ post_tag : \"tag_id\""));
+}
+
+// ==========================================================================
+// Schema drift tests
+// ==========================================================================
+
+#[test]
+fn test_schema_drift_compares_hand_edited_models_and_writes_a_fix() {
+    use romance_core::schema::drift::{self, Difference};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("drift-app");
+    setup_minimal_project(&project_dir);
+    let project = open_project(&project_dir);
+
+    let parse = |name: &str, fields: &[&str]| {
+        let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+        romance_core::entity::parse_entity(name, &fields).unwrap()
+    };
+    generate_full_entity(&project, &parse("Category", &["name:string"]));
+    generate_full_entity(
+        &project,
+        &parse("Post", &["title:string", "views:i32", "category_id:uuid->Category?"]),
+    );
+
+    // What the migrations create, with the foreign key lost along the way
+    let db = romance_core::import::sql::parse(
+        r#"
+        CREATE TABLE categories (
+            id UUID PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL
+        );
+        CREATE TABLE posts (
+            id UUID PRIMARY KEY,
+            title VARCHAR(255) NOT NULL,
+            views INTEGER NOT NULL,
+            category_id UUID,
+            created_at TIMESTAMPTZ NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL
+        );
+        "#,
+    )
+    .unwrap();
+
+    // Hand-edit the model
+    let model_path = project_dir.join("backend/src/entities/post.rs");
+    let model = fs::read_to_string(&model_path).unwrap();
+    let edited = model
+        .replacen("pub views: i32,", "pub views: i64,\n    pub slug: String,", 1)
+        .replacen("pub title: String,", "pub title: Option<String>,", 1);
+    fs::write(&model_path, edited).unwrap();
+
+    let models = romance_core::schema::scan(&project).unwrap();
+    let differences = drift::compare(&models, &db);
+    assert_eq!(
+        differences,
+        vec![
+            Difference::NullabilityMismatch {
+                table: "posts".to_string(),
+                column: "title".to_string(),
+                model_nullable: true,
+            },
+            Difference::TypeMismatch {
+                table: "posts".to_string(),
+                column: "views".to_string(),
+                model: "bigint".to_string(),
+                database: "integer".to_string(),
+            },
+            Difference::MissingColumn {
+                table: "posts".to_string(),
+                column: "slug".to_string(),
+            },
+            Difference::MissingForeignKey {
+                table: "posts".to_string(),
+                column: "category_id".to_string(),
+                references: "categories".to_string(),
+            },
+        ]
+    );
+
+    let mut tracker = romance_core::generator::plan::GenerationTracker::new();
+    let (migration, skipped) = drift::write_migration(&project, &models, &differences, &mut tracker).unwrap();
+    assert!(skipped.is_empty());
+    let migration = migration.unwrap();
+    let content = fs::read_to_string(&migration).unwrap();
+    assert!(content.contains(r#"ALTER TABLE "posts" ALTER COLUMN "title" DROP NOT NULL"#));
+    assert!(content.contains(r#"ALTER TABLE "posts" ADD COLUMN "slug" VARCHAR(255) NOT NULL"#));
+    assert!(content.contains(r#"FOREIGN KEY ("category_id") REFERENCES "categories" ("id")"#));
+    let module = migration.file_stem().unwrap().to_str().unwrap().to_string();
+    assert!(module.ends_with("_fix_schema_drift"));
+    let lib = fs::read_to_string(project_dir.join("backend/migration/src/lib.rs")).unwrap();
+    assert!(lib.contains(&format!("mod {};", module)));
+}
+
+// ==========================================================================
+// Migration generator tests
+// ==========================================================================
+
+#[test]
+fn test_generate_blank_and_sql_migrations() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+    use romance_core::manifest::{FileCategory, Manifest};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("migration-app");
+    setup_minimal_project(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+    let project = open_project(&project_dir);
+
+    let blank = migration::generate_blank(&project, "AddPriceIndex", false, &mut GenerationTracker::new()).unwrap();
+    assert!(blank.starts_with('m') && blank.ends_with("_add_price_index"));
+    let content = fs::read_to_string(project_dir.join(format!("backend/migration/src/{}.rs", blank))).unwrap();
+    assert!(content.contains("impl MigrationTrait for Migration"));
+
+    let sql = migration::generate_blank(&project, "backfill_slugs", true, &mut GenerationTracker::new()).unwrap();
+    assert!(sql > blank, "timestamps must stay ordered");
+    let content = fs::read_to_string(project_dir.join(format!("backend/migration/src/{}.rs", sql))).unwrap();
+    assert!(content.contains(&format!("include_str!(\"../sql/{}.up.sql\")", sql)));
+    assert!(project_dir.join(format!("backend/migration/sql/{}.up.sql", sql)).exists());
+    assert!(project_dir.join(format!("backend/migration/sql/{}.down.sql", sql)).exists());
+
+    let lib = fs::read_to_string(project_dir.join("backend/migration/src/lib.rs")).unwrap();
+    for module in [&blank, &sql] {
+        assert!(lib.contains(&format!("mod {};", module)));
+        assert!(lib.contains(&format!("Box::new({}::Migration)", module)));
+    }
+
+    let manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    let record = &manifest.files[&format!("backend/migration/sql/{}.up.sql", sql)];
+    assert_eq!(record.category, FileCategory::Migration);
+
+    // Filling a migration in isn't drift
+    fs::write(
+        project_dir.join(format!("backend/migration/sql/{}.up.sql", sql)),
+        "UPDATE posts SET slug = id::text;\n",
+    )
+    .unwrap();
+    let status = romance_core::status::scan(&project).unwrap();
+    assert!(status.modified.is_empty());
+
+    let err = migration::generate_blank(&project, "1st-fix", false, &mut GenerationTracker::new()).unwrap_err();
+    assert!(err.to_string().contains("Invalid migration name"));
+}
+
+#[test]
+fn test_generate_sql_migration_runs_its_up_and_down_files() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("sql-migration-app");
+    let project =
+        romance_core::scaffold::render_project(std::sync::Arc::new(DiskFs), &project_dir, "sql-migration-app").unwrap();
+
+    let module = migration::generate_blank(&project, "add_price_index", true, &mut GenerationTracker::new()).unwrap();
+    let rust = fs::read_to_string(project_dir.join(format!("backend/migration/src/{}.rs", module))).unwrap();
+    assert!(rust.contains(&format!("const UP: &str = include_str!(\"../sql/{}.up.sql\");", module)));
+    assert!(rust.contains(&format!("const DOWN: &str = include_str!(\"../sql/{}.down.sql\");", module)));
+    assert!(rust.contains("execute(manager, UP).await"));
+    assert!(rust.contains("execute(manager, DOWN).await"));
+    let up = fs::read_to_string(project_dir.join(format!("backend/migration/sql/{}.up.sql", module))).unwrap();
+    assert!(up.starts_with(&format!("-- {}: applied by `romance db migrate`.", module)));
+    let down = fs::read_to_string(project_dir.join(format!("backend/migration/sql/{}.down.sql", module))).unwrap();
+    assert!(down.starts_with(&format!("-- {}: undoes up.sql", module)));
+    assert!(romance_core::status::scan(&project).unwrap().is_clean());
+
+    // Nothing is written when the migration can't be registered
+    fs::write(project_dir.join("backend/migration/src/lib.rs"), "pub struct Migrator;\n").unwrap();
+    let err = migration::generate_blank(&project, "backfill_slugs", true, &mut GenerationTracker::new()).unwrap_err();
+    assert!(err.to_string().contains("ROMANCE:MIGRATION"), "{}", err);
+    let sql_files = fs::read_dir(project_dir.join("backend/migration/sql")).unwrap().count();
+    assert_eq!(sql_files, 2);
+}
+
+#[test]
+fn test_generated_migrations_register_in_order() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("migration-order-app");
+    let project =
+        romance_core::scaffold::render_project(std::sync::Arc::new(DiskFs), &project_dir, "migration-order-app").unwrap();
+
+    let modules: Vec<String> = [("add_price_index", false), ("backfill_slugs", true), ("add_slug_constraint", false)]
+        .into_iter()
+        .map(|(name, sql)| migration::generate_blank(&project, name, sql, &mut GenerationTracker::new()).unwrap())
+        .collect();
+    assert!(modules.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", modules);
+
+    let lib = fs::read_to_string(project_dir.join("backend/migration/src/lib.rs")).unwrap();
+    let mods: String = modules.iter().map(|m| format!("mod {};\n", m)).collect();
+    assert!(lib.contains(&format!("{}// === ROMANCE:MIGRATION_MODS ===", mods)), "{}", lib);
+    let entries: String = modules
+        .iter()
+        .map(|m| format!("            Box::new({}::Migration),\n", m))
+        .collect();
+    assert!(lib.contains(&format!("// === ROMANCE:MIGRATIONS ===\n{}        ]", entries)), "{}", lib);
+}
+
+// ==========================================================================
+// Migration lint tests
+// ==========================================================================
+
+#[test]
+fn test_lint_reports_destructive_and_locking_migrations() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+    use romance_core::lint::{self, Rule};
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("lint-app");
+    setup_minimal_project(&project_dir);
+    let project = open_project(&project_dir);
+    let src = project_dir.join("backend/migration/src");
+
+    // A new table with its index and a NOT NULL primary key is safe
+    fs::write(
+        src.join("m20250101000000_create_posts.rs"),
+        r#"use sea_orm_migration::prelude::*;
+pub struct Migration;
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(Table::create().table(Alias::new("posts"))
+            .col(ColumnDef::new(Alias::new("id")).uuid().not_null().primary_key())
+            .to_owned()).await?;
+        manager.create_index(Index::create().name("idx_posts_id").table(Alias::new("posts")).col(Alias::new("id")).to_owned()).await
+    }
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Alias::new("posts")).to_owned()).await
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        src.join("m20250102000000_reshape_posts.rs"),
+        r#"use sea_orm_migration::prelude::*;
+pub struct Migration;
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // manager.drop_table(...) in a comment isn't reported
+        manager.alter_table(Table::alter().table(Alias::new("posts"))
+            .drop_column(Alias::new("legacy"))
+            .add_column(ColumnDef::new(Alias::new("slug")).string().not_null())
+            .add_column(ColumnDef::new(Alias::new("views")).integer().not_null().default(0))
+            .to_owned()).await?;
+        // romance-lint: allow(rename_column)
+        manager.alter_table(Table::alter().table(Alias::new("posts"))
+            .rename_column(Alias::new("body"), Alias::new("content")).to_owned()).await?;
+        manager.get_connection().execute_unprepared("ALTER TABLE posts ALTER COLUMN views TYPE bigint").await?;
+        Ok(())
+    }
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Alias::new("posts")).to_owned()).await
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let sql = migration::generate_blank(&project, "tighten_posts", true, &mut GenerationTracker::new()).unwrap();
+    fs::write(
+        project_dir.join(format!("backend/migration/sql/{}.up.sql", sql)),
+        "-- DROP TABLE posts; is only a comment\n\
+         CREATE INDEX idx_posts_slug ON posts (slug);\n\
+         CREATE INDEX CONCURRENTLY idx_posts_views ON posts (views);\n\
+         ALTER TABLE posts ALTER COLUMN slug SET NOT NULL,\n  ADD CONSTRAINT fk_author FOREIGN KEY (author_id) REFERENCES users (id);\n\
+         ALTER TABLE posts ADD CONSTRAINT fk_editor FOREIGN KEY (editor_id) REFERENCES users (id) NOT VALID;\n\
+         DELETE FROM posts WHERE views = 0;\n",
+    )
+    .unwrap();
+    fs::write(project_dir.join(format!("backend/migration/sql/{}.down.sql", sql)), "DROP INDEX idx_posts_slug;\n").unwrap();
+
+    assert_eq!(
+        migration::modules(&project),
+        vec!["m20250101000000_create_posts".to_string(), "m20250102000000_reshape_posts".to_string(), sql.clone()]
+    );
+    assert!(lint::lint(&project, "m20250101000000_create_posts").unwrap().is_empty());
+
+    let findings = lint::lint(&project, "m20250102000000_reshape_posts").unwrap();
+    let rules: Vec<(Rule, usize)> = findings.iter().map(|f| (f.rule, f.line)).collect();
+    assert_eq!(
+        rules,
+        vec![(Rule::DropColumn, 7), (Rule::AddNotNullColumn, 8), (Rule::ChangeColumnType, 14)]
+    );
+    assert!(findings[0].destructive);
+    assert!(!findings[2].destructive);
+    assert_eq!(findings[0].file, "backend/migration/src/m20250102000000_reshape_posts.rs");
+    assert!(findings[1].suggestion.contains("nullable"));
+
+    let findings = lint::lint(&project, &sql).unwrap();
+    let rules: Vec<(Rule, usize)> = findings.iter().map(|f| (f.rule, f.line)).collect();
+    assert_eq!(rules, vec![(Rule::CreateIndex, 2), (Rule::SetNotNull, 4), (Rule::AddConstraint, 4)]);
+    assert_eq!(findings[0].file, format!("backend/migration/sql/{}.up.sql", sql));
+}
+
+// ==========================================================================
+// Migration squash tests
+// ==========================================================================
+
+#[test]
+fn test_squash_replaces_the_oldest_migrations_with_a_baseline() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+    use romance_core::manifest::Manifest;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("squash-app");
+    setup_minimal_project(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+    let project = open_project(&project_dir);
+
+    let create = migration::generate_blank(&project, "create_post_table", false, &mut GenerationTracker::new()).unwrap();
+    let rename = migration::generate_blank(&project, "rename_post_to_article", true, &mut GenerationTracker::new()).unwrap();
+    let later = migration::generate_blank(&project, "add_price_index", false, &mut GenerationTracker::new()).unwrap();
+    let squashed = vec![create.clone(), rename.clone()];
+
+    // A later migration re-running a squashed one keeps it from going
+    let later_path = project_dir.join(format!("backend/migration/src/{}.rs", later));
+    let later_content = fs::read_to_string(&later_path).unwrap();
+    fs::write(&later_path, format!("{}\n// crate::{}::Migration.up(manager)\n", later_content, create)).unwrap();
+    let err = migration::squash(&project, &squashed, "", &mut GenerationTracker::new()).err().unwrap();
+    assert!(err.to_string().contains(&format!("{} uses {}", later, create)));
+    fs::write(&later_path, &later_content).unwrap();
+
+    let err = migration::squash(&project, std::slice::from_ref(&rename), "", &mut GenerationTracker::new()).err().unwrap();
+    assert!(err.to_string().contains("oldest"));
+
+    let schema = "CREATE TYPE public.status AS ENUM ('draft', 'published');\n\n\
+                  CREATE TABLE public.articles (\n    id uuid NOT NULL\n);\n";
+    let baseline = migration::squash(&project, &squashed, schema, &mut GenerationTracker::new()).unwrap();
+    let stamp = &rename[1..15];
+    assert_eq!(baseline.module, format!("m{}_squashed_baseline", stamp));
+    assert!(baseline.record_sql.contains(&format!("WHERE version <= '{}'", rename)));
+
+    assert_eq!(migration::modules(&project), vec![baseline.module.clone(), later.clone()]);
+    assert!(!project_dir.join(format!("backend/migration/sql/{}.up.sql", rename)).exists());
+    let up = fs::read_to_string(project_dir.join(format!("backend/migration/sql/{}.up.sql", baseline.module))).unwrap();
+    assert!(up.contains("CREATE TABLE public.articles"));
+    let down = fs::read_to_string(project_dir.join(format!("backend/migration/sql/{}.down.sql", baseline.module))).unwrap();
+    assert!(down.contains(
+        "DROP TABLE IF EXISTS public.articles CASCADE;\nDROP TYPE IF EXISTS public.status CASCADE;"
+    ));
+
+    // The baseline runs where the first squashed migration did
+    let lib = fs::read_to_string(project_dir.join("backend/migration/src/lib.rs")).unwrap();
+    let baseline_entry = lib.find(&format!("Box::new({}::Migration)", baseline.module)).unwrap();
+    assert!(baseline_entry < lib.find(&format!("Box::new({}::Migration)", later)).unwrap());
+    assert!(lib.contains(&format!("mod {};", baseline.module)));
+    for module in &squashed {
+        assert!(!lib.contains(module.as_str()));
+    }
+
+    // What the squashed migrations told about the entities is kept
+    assert_eq!(migration::squashed_modules(&project), squashed);
+    assert_eq!(migration::former_names(&project, "article"), vec!["post".to_string()]);
+
+    let manifest = Manifest::load(&DiskFs, &project_dir).unwrap();
+    assert!(manifest.files.contains_key(&format!("backend/migration/src/{}.rs", baseline.module)));
+    assert!(!manifest.files.keys().any(|path| path.contains(&rename)));
+}
+
+#[test]
+fn test_squash_refuses_a_partially_applied_set() {
+    use romance_core::generator::migration;
+    use romance_core::generator::plan::GenerationTracker;
+    use romance_core::manifest::Manifest;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("squash-app");
+    setup_minimal_project(&project_dir);
+    Manifest::new("test-app", "0.0.0").save(&DiskFs, &project_dir).unwrap();
+    let project = open_project(&project_dir);
+
+    let create = migration::generate_blank(&project, "create_post_table", false, &mut GenerationTracker::new()).unwrap();
+    let index = migration::generate_blank(&project, "add_post_index", false, &mut GenerationTracker::new()).unwrap();
+    let seed = migration::generate_blank(&project, "seed_posts", true, &mut GenerationTracker::new()).unwrap();
+    let squashed = vec![create.clone(), index.clone(), seed.clone()];
+
+    // Only the first one ran: the others are named, and nothing is written
+    let err = migration::check_applied(&squashed, std::slice::from_ref(&create)).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains(&format!("{}, {}", index, seed)), "{}", message);
+    assert!(!message.contains(&create));
+    assert_eq!(migration::modules(&project), squashed);
+
+    // Versions applied beyond the squashed ones don't matter
+    let applied = vec![create, index, seed, "m20990101000000_later".to_string()];
+    migration::check_applied(&squashed, &applied).unwrap();
+}
+
+// ==========================================================================
+// Database lifecycle tests
+// ==========================================================================
+
+#[test]
+fn test_database_target_applies_the_active_environment() {
+    use romance_core::database::Target;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_dir = dir.path().join("db-app");
+    setup_minimal_project(&project_dir);
+    let target = Target::resolve(&open_project(&project_dir)).unwrap();
+    assert_eq!(target.url, "postgres://localhost/test");
+    assert_eq!(target.name, "test");
+
+    let config = fs::read_to_string(project_dir.join("romance.toml")).unwrap();
+    fs::write(project_dir.join("romance.toml"), format!("{}\n[environment]\nactive = \"production\"\n", config)).unwrap();
+    fs::write(
+        project_dir.join("romance.production.toml"),
+        "[backend]\ndatabase_url = \"postgres://db.internal:5432/app_prod?sslmode=require\"\n",
+    )
+    .unwrap();
+    let target = Target::resolve(&open_project(&project_dir)).unwrap();
+    assert_eq!(target.name, "app_prod");
+    assert_eq!(target.environment, "production");
+    assert!(target.is_production());
+
+    fs::write(project_dir.join("romance.production.toml"), "[backend]\ndatabase_url = \"postgres://db.internal\"\n").unwrap();
+    let err = Target::resolve(&open_project(&project_dir)).err().unwrap();
+    assert!(err.to_string().contains("names no database"));
+}